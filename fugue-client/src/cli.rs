@@ -2,7 +2,8 @@
 //!
 //! Uses clap for argument parsing with derive macros.
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use fugue_utils::tls::TlsClientOptions;
 use std::path::PathBuf;
 
 /// fugue - Claude Code terminal multiplexer client
@@ -30,14 +31,30 @@ pub struct Args {
     #[arg(long, short = 'S')]
     pub socket: Option<PathBuf>,
 
-    /// Connection address (tcp://host:port or unix://path)
+    /// Connection address (tcp://host:port, tls://host:port or unix://path)
     ///
-    /// Specifies the server address to connect to. Supports both TCP and Unix
+    /// Specifies the server address to connect to. Supports TCP, TLS and Unix
     /// sockets via URL format. Overrides --socket if provided.
-    /// Example: tcp://127.0.0.1:3000 or unix:///tmp/fugue.sock
+    /// Example: tls://build-box:3000 or unix:///tmp/fugue.sock
     #[arg(long, env = "FUGUE_ADDR")]
     pub addr: Option<String>,
 
+    /// CA certificate (PEM) used to verify the server for tls:// addresses
+    #[arg(long, env = "FUGUE_TLS_CA")]
+    pub tls_ca: Option<PathBuf>,
+
+    /// Client certificate (PEM) for servers that require mutual TLS
+    #[arg(long, env = "FUGUE_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Client private key (PEM) for servers that require mutual TLS
+    #[arg(long, env = "FUGUE_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Pre-shared token for servers with tls.auth_token configured
+    #[arg(long, env = "FUGUE_AUTH_TOKEN", hide_env_values = true)]
+    pub auth_token: Option<String>,
+
     /// Connection target alias (from [remotes] config)
     ///
     /// Specifies a named remote target to connect to. Looks up the address in the
@@ -52,6 +69,74 @@ pub struct Args {
     /// Example: fugue bash
     #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
     pub command: Vec<String>,
+
+    /// Run a one-shot command against the server instead of the TUI
+    #[command(subcommand)]
+    pub subcommand: Option<ClientCommand>,
+}
+
+/// One-shot commands that talk to the server and exit
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum ClientCommand {
+    /// Save, list, restore and delete named snapshots of sessions
+    #[command(subcommand)]
+    Snapshot(SnapshotCommand),
+
+    /// Create the sessions in a workspace file that aren't running yet
+    Up {
+        /// Workspace file
+        #[arg(default_value = ".fugue.toml")]
+        file: PathBuf,
+    },
+
+    /// Destroy the sessions in a workspace file
+    Down {
+        /// Workspace file
+        #[arg(default_value = ".fugue.toml")]
+        file: PathBuf,
+    },
+}
+
+/// `fugue snapshot` subcommands
+#[derive(Subcommand, Debug, PartialEq)]
+pub enum SnapshotCommand {
+    /// Save the current sessions under a name (replaces an existing snapshot)
+    Save {
+        /// Snapshot name
+        name: String,
+        /// Only save this session (name or ID)
+        #[arg(long, short)]
+        session: Option<String>,
+    },
+
+    /// List saved snapshots
+    List,
+
+    /// Restore sessions from a snapshot or from an earlier point in the WAL
+    ///
+    /// Restored sessions replace live sessions with the same name, unless
+    /// --as is given to restore a copy under a new name instead.
+    Restore {
+        /// Snapshot name
+        #[arg(required_unless_present = "seq", conflicts_with = "seq")]
+        name: Option<String>,
+        /// Restore the persisted state as of this WAL sequence
+        /// (see 'fugue-server state dump-wal')
+        #[arg(long)]
+        seq: Option<u64>,
+        /// Only restore this session (name or ID)
+        #[arg(long, short)]
+        session: Option<String>,
+        /// Restore a copy of the session under this name
+        #[arg(long = "as", value_name = "NAME")]
+        clone_as: Option<String>,
+    },
+
+    /// Delete a saved snapshot
+    Delete {
+        /// Snapshot name
+        name: String,
+    },
 }
 
 impl Args {
@@ -65,6 +150,16 @@ impl Args {
         !self.no_auto_start
     }
 
+    /// TLS and auth options for remote connections
+    pub fn tls_options(&self) -> TlsClientOptions {
+        TlsClientOptions {
+            ca_file: self.tls_ca.clone(),
+            cert_file: self.tls_cert.clone(),
+            key_file: self.tls_key.clone(),
+            auth_token: self.auth_token.clone(),
+        }
+    }
+
     /// Get the command string if provided
     pub fn command_string(&self) -> Option<String> {
         if self.command.is_empty() {
@@ -118,6 +213,34 @@ mod tests {
         assert_eq!(args.addr, Some("tcp://localhost:3000".to_string()));
     }
 
+    #[test]
+    fn test_tls_flags() {
+        let args = Args::parse_from([
+            "fugue",
+            "--addr",
+            "tls://remote:3000",
+            "--tls-ca",
+            "/etc/fugue/ca.pem",
+            "--tls-cert",
+            "/etc/fugue/client.pem",
+            "--tls-key",
+            "/etc/fugue/client.key",
+            "--auth-token",
+            "s3cret",
+        ]);
+        let opts = args.tls_options();
+        assert_eq!(opts.ca_file, Some(PathBuf::from("/etc/fugue/ca.pem")));
+        assert_eq!(opts.cert_file, Some(PathBuf::from("/etc/fugue/client.pem")));
+        assert_eq!(opts.key_file, Some(PathBuf::from("/etc/fugue/client.key")));
+        assert_eq!(opts.auth_token.as_deref(), Some("s3cret"));
+    }
+
+    #[test]
+    fn test_tls_cert_requires_key() {
+        let result = Args::try_parse_from(["fugue", "--tls-cert", "/etc/fugue/client.pem"]);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_combined_flags() {
         let args = Args::parse_from([
@@ -171,4 +294,55 @@ mod tests {
         assert_eq!(args.socket, Some(PathBuf::from("/tmp/sock")));
         assert_eq!(args.command_string(), Some("claude --resume".to_string()));
     }
+
+    #[test]
+    fn test_snapshot_subcommand() {
+        let args = Args::parse_from(["fugue", "-S", "/tmp/sock", "snapshot", "save", "good", "-s", "work"]);
+        assert_eq!(args.socket, Some(PathBuf::from("/tmp/sock")));
+        assert!(args.command.is_empty());
+        assert_eq!(
+            args.subcommand,
+            Some(ClientCommand::Snapshot(SnapshotCommand::Save {
+                name: "good".to_string(),
+                session: Some("work".to_string()),
+            }))
+        );
+
+        let args = Args::parse_from(["fugue", "snapshot", "restore", "--seq", "42", "--as", "retry"]);
+        assert_eq!(
+            args.subcommand,
+            Some(ClientCommand::Snapshot(SnapshotCommand::Restore {
+                name: None,
+                seq: Some(42),
+                session: None,
+                clone_as: Some("retry".to_string()),
+            }))
+        );
+
+        assert!(Args::try_parse_from(["fugue", "snapshot", "restore"]).is_err());
+        assert!(Args::try_parse_from(["fugue", "snapshot", "restore", "good", "--seq", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_workspace_subcommands() {
+        let args = Args::parse_from(["fugue", "up"]);
+        assert_eq!(
+            args.subcommand,
+            Some(ClientCommand::Up {
+                file: PathBuf::from(".fugue.toml"),
+            })
+        );
+
+        let args = Args::parse_from(["fugue", "down", "dev/.fugue.toml"]);
+        assert_eq!(
+            args.subcommand,
+            Some(ClientCommand::Down {
+                file: PathBuf::from("dev/.fugue.toml"),
+            })
+        );
+
+        // Other commands are still run in the new session
+        let args = Args::parse_from(["fugue", "claude", "--resume"]);
+        assert!(args.subcommand.is_none());
+    }
 }
@@ -4,19 +4,25 @@
 #![allow(dead_code)]
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use futures::{SinkExt, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpStream, UnixStream};
 use tokio::sync::mpsc;
+use tokio_rustls::TlsConnector;
 use tokio_util::codec::Framed;
 use url::Url;
 
 use fugue_protocol::{ClientCodec, ClientMessage, ServerMessage};
+use fugue_utils::tls::{self, TlsClientOptions, TLS_SCHEME};
 use fugue_utils::{socket_path, CcmuxError, Result};
 
 use super::handler::MessageSender;
 
+/// How long to wait for the server to acknowledge an auth token
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Trait alias for streams that can be used with Framed
 pub trait StreamTrait: AsyncRead + AsyncWrite + Unpin + Send {}
 impl<T: AsyncRead + AsyncWrite + Unpin + Send> StreamTrait for T {}
@@ -32,8 +38,10 @@ pub enum ConnectionState {
 
 /// Client connection to fugue server
 pub struct Connection {
-    /// Connection address URL (unix://path or tcp://host:port)
+    /// Connection address URL (unix://path, tcp://host:port or tls://host:port)
     connect_addr: String,
+    /// TLS material and auth token for remote connections
+    tls_options: TlsClientOptions,
     /// Current state
     state: ConnectionState,
     /// Channel for outgoing messages
@@ -54,6 +62,7 @@ impl Connection {
 
         Self {
             connect_addr: addr,
+            tls_options: TlsClientOptions::default(),
             state: ConnectionState::Disconnected,
             tx,
             rx,
@@ -75,6 +84,11 @@ impl Connection {
         conn
     }
 
+    /// Set TLS material and auth token used for `tls://` and `tcp://` addresses
+    pub fn set_tls_options(&mut self, options: TlsClientOptions) {
+        self.tls_options = options;
+    }
+
     /// Get current connection state
     pub fn state(&self) -> ConnectionState {
         self.state
@@ -89,7 +103,8 @@ impl Connection {
         self.state = ConnectionState::Connecting;
 
         // Parse address and connect
-        let stream: Box<dyn StreamTrait> = if self.connect_addr.starts_with("tcp://") {
+        let is_tls = self.connect_addr.starts_with(TLS_SCHEME);
+        let stream: Box<dyn StreamTrait> = if self.connect_addr.starts_with("tcp://") || is_tls {
             let url = Url::parse(&self.connect_addr).map_err(|e| {
                 self.state = ConnectionState::Disconnected;
                 CcmuxError::Connection(format!("Invalid TCP URL '{}': {}", self.connect_addr, e))
@@ -109,8 +124,15 @@ impl Connection {
                 self.state = ConnectionState::Disconnected;
                 CcmuxError::Connection(format!("Failed to connect to {}: {}", addr, e))
             })?;
-            
-            Box::new(tcp_stream)
+
+            if is_tls {
+                let tls_stream = Self::tls_handshake(&self.tls_options, host, tcp_stream)
+                    .await
+                    .inspect_err(|_| self.state = ConnectionState::Disconnected)?;
+                Box::new(tls_stream)
+            } else {
+                Box::new(tcp_stream)
+            }
         } else {
             // Assume Unix socket (either unix:// prefix or raw path)
             let path_str = if self.connect_addr.starts_with("unix://") {
@@ -141,7 +163,15 @@ impl Connection {
         };
 
         // Create framed transport with codec
-        let framed = Framed::new(stream, ClientCodec::new());
+        let mut framed = Framed::new(stream, ClientCodec::new());
+
+        // Present the pre-shared token before the app sends Connect
+        if let Some(ref token) = self.tls_options.auth_token {
+            if let Err(e) = Self::authenticate(&mut framed, token).await {
+                self.state = ConnectionState::Disconnected;
+                return Err(e);
+            }
+        }
 
         // Set up channels
         // BUG-072 FIX: Use unbounded channel for incoming to prevent deadlock.
@@ -162,6 +192,48 @@ impl Connection {
         Ok(())
     }
 
+    /// Wrap a TCP stream in TLS, verifying the server against the configured CA
+    async fn tls_handshake(
+        options: &TlsClientOptions,
+        host: &str,
+        tcp_stream: TcpStream,
+    ) -> Result<tokio_rustls::client::TlsStream<TcpStream>> {
+        let connector = TlsConnector::from(options.client_config()?);
+        connector
+            .connect(tls::server_name(host)?, tcp_stream)
+            .await
+            .map_err(|e| CcmuxError::Connection(format!("TLS handshake with {} failed: {}", host, e)))
+    }
+
+    /// Send `Authenticate` and wait for the server to accept the token
+    async fn authenticate(
+        framed: &mut Framed<Box<dyn StreamTrait>, ClientCodec>,
+        token: &str,
+    ) -> Result<()> {
+        framed
+            .send(ClientMessage::Authenticate {
+                token: token.to_string(),
+            })
+            .await
+            .map_err(|e| CcmuxError::Connection(format!("Failed to send auth token: {}", e)))?;
+
+        match tokio::time::timeout(AUTH_TIMEOUT, framed.next()).await {
+            Ok(Some(Ok(ServerMessage::Authenticated))) => Ok(()),
+            Ok(Some(Ok(ServerMessage::Error { message, .. }))) => Err(CcmuxError::Connection(
+                format!("Authentication failed: {}", message),
+            )),
+            Ok(Some(Ok(other))) => Err(CcmuxError::Protocol(format!(
+                "Unexpected response to Authenticate: {}",
+                other.type_name()
+            ))),
+            Ok(Some(Err(e))) => Err(CcmuxError::Connection(format!("Failed to receive: {}", e))),
+            Ok(None) => Err(CcmuxError::ConnectionClosed),
+            Err(_) => Err(CcmuxError::ConnectionTimeout {
+                seconds: AUTH_TIMEOUT.as_secs(),
+            }),
+        }
+    }
+
     /// Disconnect from server
     pub async fn disconnect(&mut self) {
         if let Some(handle) = self.task_handle.take() {
@@ -399,6 +471,75 @@ mod tests {
         assert_eq!(conn.connect_addr, format!("unix://{}", path.to_string_lossy()));
     }
 
+    #[tokio::test]
+    async fn test_auth_token_sent_before_connect() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut framed = Framed::new(stream, fugue_protocol::ServerCodec::new());
+            let first = framed.next().await.unwrap().unwrap();
+            framed.send(ServerMessage::Authenticated).await.unwrap();
+            first
+        });
+
+        let mut conn = Connection::with_socket_path(socket_path);
+        conn.set_tls_options(TlsClientOptions {
+            auth_token: Some("s3cret".to_string()),
+            ..Default::default()
+        });
+        conn.connect().await.unwrap();
+        assert_eq!(conn.state(), ConnectionState::Connected);
+
+        assert_eq!(
+            server.await.unwrap(),
+            ClientMessage::Authenticate {
+                token: "s3cret".to_string()
+            }
+        );
+        conn.disconnect().await;
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_rejected() {
+        let dir = tempdir().unwrap();
+        let socket_path = dir.path().join("test.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut framed = Framed::new(stream, fugue_protocol::ServerCodec::new());
+            let _ = framed.next().await;
+            framed
+                .send(ServerMessage::Error {
+                    code: fugue_protocol::ErrorCode::Unauthorized,
+                    message: "invalid auth token".to_string(),
+                    details: None,
+                })
+                .await
+                .unwrap();
+        });
+
+        let mut conn = Connection::with_socket_path(socket_path);
+        conn.set_tls_options(TlsClientOptions {
+            auth_token: Some("wrong".to_string()),
+            ..Default::default()
+        });
+        let err = conn.connect().await.unwrap_err();
+        assert!(err.to_string().contains("invalid auth token"));
+        assert_eq!(conn.state(), ConnectionState::Disconnected);
+    }
+
+    #[tokio::test]
+    async fn test_tls_connect_refused() {
+        // Nothing listens on port 1; the TCP connect fails before TLS starts
+        let mut conn = Connection::with_addr("tls://127.0.0.1:1".to_string());
+        assert!(conn.connect().await.is_err());
+        assert_eq!(conn.state(), ConnectionState::Disconnected);
+    }
+
     #[tokio::test]
     async fn test_disconnect_when_not_connected() {
         let mut conn = Connection::new();
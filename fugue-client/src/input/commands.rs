@@ -61,6 +61,10 @@ pub enum ClientCommand {
     CreateSession(Option<String>),
     /// Rename current session
     RenameSession(String),
+    /// Export current session to a file, optionally with scrollback
+    ExportSession { path: String, scrollback: bool },
+    /// Import a session from an exported file, optionally renaming it
+    ImportSession { path: String, name: Option<String> },
 
     // Copy/scroll mode
     /// Enter copy/scroll mode
@@ -89,6 +93,42 @@ pub enum ClientCommand {
     SelectWord { x: u16, y: u16 },
     /// Select line at position (triple-click)
     SelectLine { x: u16, y: u16 },
+    /// Search scrollback of every pane in the session
+    SearchScrollback(String),
+    /// Jump to the next (older) search match (n)
+    NextSearchMatch,
+    /// Jump to the previous (newer) search match (N)
+    PreviousSearchMatch,
+    /// Open the copy mode search prompt (/ forward, ? backward)
+    StartCopySearch { backward: bool },
+    /// Copy mode search prompt edited; search as you type
+    UpdateCopySearch(String),
+    /// Copy mode search prompt confirmed with Enter
+    ConfirmCopySearch(String),
+    /// Copy mode search prompt cancelled with Escape
+    CancelCopySearch,
+
+    // Paste buffers
+    /// List the server's paste buffers
+    ListBuffers,
+    /// Show a paste buffer (the most recent without a name)
+    ShowBuffer(Option<String>),
+    /// Store text in a paste buffer
+    SetBuffer {
+        name: Option<String>,
+        data: String,
+        append: bool,
+    },
+    /// Paste a buffer into the active pane
+    PasteBuffer { name: Option<String>, delete: bool },
+    /// Write a paste buffer to a file
+    SaveBuffer {
+        name: Option<String>,
+        path: String,
+        append: bool,
+    },
+    /// Delete a paste buffer
+    DeleteBuffer(Option<String>),
 
     // Layout
     /// Toggle pane zoom (fullscreen)
@@ -105,6 +145,12 @@ pub enum ClientCommand {
         amount: u16,
     },
 
+    // Agents
+    /// Approve the pending tool-permission prompt (once or always)
+    ApprovePermission { always: bool },
+    /// Deny the pending tool-permission prompt
+    DenyPermission,
+
     // Misc
     /// Show help
     ShowHelp,
@@ -142,6 +188,10 @@ impl CommandHandler {
     /// - `rename-session <name>`
     /// - `list-sessions`
     /// - `list-windows`
+    /// - `export-session [-S] <file>`
+    /// - `import-session <file> [name]`
+    /// - `search <query>`
+    /// - `set-buffer [-a] [-b name] <data>` and the other buffer commands
     pub fn parse_command(input: &str) -> Option<ClientCommand> {
         let input = input.trim();
         if input.is_empty() {
@@ -237,10 +287,73 @@ impl CommandHandler {
                 }
             }
             "list-sessions" | "ls" => Some(ClientCommand::ListSessions),
+            "export-session" | "exports" => {
+                let mut scrollback = false;
+                let mut path = None;
+                for part in parts {
+                    match part {
+                        "-S" => scrollback = true,
+                        _ => path = Some(part.to_string()),
+                    }
+                }
+                path.map(|path| ClientCommand::ExportSession { path, scrollback })
+            }
+            "import-session" | "imports" => {
+                let path = parts.next()?.to_string();
+                let name: String = parts.collect::<Vec<_>>().join(" ");
+                Some(ClientCommand::ImportSession {
+                    path,
+                    name: (!name.is_empty()).then_some(name),
+                })
+            }
 
             // Copy mode
             "copy-mode" | "copy" => Some(ClientCommand::EnterCopyMode),
             "clear-history" | "clearhist" => Some(ClientCommand::ClearHistory),
+            "search" | "find" => {
+                let query = input[command.len()..].trim();
+                if query.is_empty() {
+                    None
+                } else {
+                    Some(ClientCommand::SearchScrollback(query.to_string()))
+                }
+            }
+
+            // Paste buffers
+            "list-buffers" | "lsb" => Some(ClientCommand::ListBuffers),
+            "show-buffer" | "showb" => Some(ClientCommand::ShowBuffer(buffer_args(&input[command.len()..]).0)),
+            "set-buffer" | "setb" => {
+                let (name, flags, data) = buffer_args(&input[command.len()..]);
+                if data.is_empty() {
+                    None
+                } else {
+                    Some(ClientCommand::SetBuffer {
+                        name,
+                        data: data.to_string(),
+                        append: flags.contains('a'),
+                    })
+                }
+            }
+            "paste-buffer" | "pasteb" => {
+                let (name, flags, _) = buffer_args(&input[command.len()..]);
+                Some(ClientCommand::PasteBuffer {
+                    name,
+                    delete: flags.contains('d'),
+                })
+            }
+            "save-buffer" | "saveb" => {
+                let (name, flags, path) = buffer_args(&input[command.len()..]);
+                if path.is_empty() {
+                    None
+                } else {
+                    Some(ClientCommand::SaveBuffer {
+                        name,
+                        path: path.to_string(),
+                        append: flags.contains('a'),
+                    })
+                }
+            }
+            "delete-buffer" | "deleteb" => Some(ClientCommand::DeleteBuffer(buffer_args(&input[command.len()..]).0)),
 
             // Layout
             "zoom" | "resize-pane -Z" => Some(ClientCommand::ToggleZoom),
@@ -286,10 +399,21 @@ Session Commands:
   new-session [name]   Create new session
   rename-session <name> Rename current session
   list-sessions        List all sessions
+  export-session [-S] <file>  Export session (.json/.toml, -S with scrollback)
+  import-session <file> [name]  Recreate an exported session
 
 Other:
   copy-mode            Enter copy/scroll mode
   clear-history        Clear scrollback buffer
+  search <query>       Search scrollback of all panes (n/N: next/prev)
+
+Paste Buffers:
+  list-buffers         List buffers, most recent first
+  show-buffer [-b name]  Show a buffer
+  set-buffer [-a] [-b name] <data>  Set (or -a append to) a buffer
+  paste-buffer [-d] [-b name]  Paste into current pane (-d: then delete)
+  save-buffer [-a] [-b name] <file>  Write a buffer to a file
+  delete-buffer [-b name]  Delete a buffer
   zoom                 Toggle pane zoom
   redraw               Force screen redraw
   help                 Show this help
@@ -297,6 +421,35 @@ Other:
     }
 }
 
+/// Split the flags off the arguments of a buffer command
+///
+/// Returns the `-b` buffer name, any other single-letter flags and the rest
+/// of the arguments with their spacing kept. `--` ends the flags.
+fn buffer_args(mut rest: &str) -> (Option<String>, String, &str) {
+    let mut name = None;
+    let mut flags = String::new();
+    loop {
+        rest = rest.trim_start();
+        let word = rest.split_whitespace().next().unwrap_or("");
+        if word == "--" {
+            return (name, flags, rest[2..].trim_start());
+        } else if word == "-b" {
+            rest = rest[2..].trim_start();
+            let value = rest.split_whitespace().next().unwrap_or("");
+            name = (!value.is_empty()).then(|| value.to_string());
+            rest = &rest[value.len()..];
+        } else if word.len() > 1
+            && word.starts_with('-')
+            && word[1..].chars().all(|c| c.is_ascii_alphabetic())
+        {
+            flags.push_str(&word[1..]);
+            rest = &rest[word.len()..];
+        } else {
+            return (name, flags, rest);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -497,6 +650,41 @@ mod tests {
         assert_eq!(CommandHandler::parse_command("rename-session"), None);
     }
 
+    #[test]
+    fn test_parse_export_import_session() {
+        assert_eq!(
+            CommandHandler::parse_command("export-session -S dev.toml"),
+            Some(ClientCommand::ExportSession {
+                path: "dev.toml".to_string(),
+                scrollback: true,
+            })
+        );
+        assert_eq!(
+            CommandHandler::parse_command("exports dev.json"),
+            Some(ClientCommand::ExportSession {
+                path: "dev.json".to_string(),
+                scrollback: false,
+            })
+        );
+        assert_eq!(CommandHandler::parse_command("export-session -S"), None);
+
+        assert_eq!(
+            CommandHandler::parse_command("import-session dev.json dev copy"),
+            Some(ClientCommand::ImportSession {
+                path: "dev.json".to_string(),
+                name: Some("dev copy".to_string()),
+            })
+        );
+        assert_eq!(
+            CommandHandler::parse_command("import-session dev.json"),
+            Some(ClientCommand::ImportSession {
+                path: "dev.json".to_string(),
+                name: None,
+            })
+        );
+        assert_eq!(CommandHandler::parse_command("import-session"), None);
+    }
+
     #[test]
     fn test_parse_copy_mode() {
         assert_eq!(
@@ -509,6 +697,62 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_search() {
+        assert_eq!(
+            CommandHandler::parse_command("search \"connection  refused\" redis"),
+            Some(ClientCommand::SearchScrollback(
+                "\"connection  refused\" redis".to_string()
+            ))
+        );
+        assert_eq!(
+            CommandHandler::parse_command("find error"),
+            Some(ClientCommand::SearchScrollback("error".to_string()))
+        );
+        assert_eq!(CommandHandler::parse_command("search"), None);
+    }
+
+    #[test]
+    fn test_parse_buffer_commands() {
+        assert_eq!(
+            CommandHandler::parse_command("set-buffer -a -b plan  step 2:  test"),
+            Some(ClientCommand::SetBuffer {
+                name: Some("plan".to_string()),
+                data: "step 2:  test".to_string(),
+                append: true,
+            })
+        );
+        assert_eq!(
+            CommandHandler::parse_command("setb -- -d is data"),
+            Some(ClientCommand::SetBuffer {
+                name: None,
+                data: "-d is data".to_string(),
+                append: false,
+            })
+        );
+        assert_eq!(CommandHandler::parse_command("set-buffer -b plan"), None);
+        assert_eq!(
+            CommandHandler::parse_command("paste-buffer -d"),
+            Some(ClientCommand::PasteBuffer { name: None, delete: true })
+        );
+        assert_eq!(
+            CommandHandler::parse_command("save-buffer -b plan /tmp/plan.txt"),
+            Some(ClientCommand::SaveBuffer {
+                name: Some("plan".to_string()),
+                path: "/tmp/plan.txt".to_string(),
+                append: false,
+            })
+        );
+        assert_eq!(
+            CommandHandler::parse_command("deleteb -b buffer3"),
+            Some(ClientCommand::DeleteBuffer(Some("buffer3".to_string())))
+        );
+        assert_eq!(
+            CommandHandler::parse_command("lsb"),
+            Some(ClientCommand::ListBuffers)
+        );
+    }
+
     #[test]
     fn test_parse_misc_commands() {
         assert_eq!(
@@ -23,6 +23,9 @@ use uuid::Uuid;
 /// Default prefix key timeout in milliseconds
 const DEFAULT_PREFIX_TIMEOUT_MS: u64 = 500;
 
+/// Copy mode search patterns remembered for Up/Down in the prompt
+const MAX_SEARCH_HISTORY: usize = 50;
+
 /// Input handling mode
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InputMode {
@@ -136,6 +139,15 @@ pub enum InputAction {
     EnterUserCommandMode { timeout_ms: u32 },
 }
 
+/// Copy mode search prompt opened with `/` or `?`
+#[derive(Debug, Clone)]
+struct CopySearchPrompt {
+    backward: bool,
+    pattern: String,
+    /// Entry of the search history being shown, while browsing with Up/Down
+    history_index: Option<usize>,
+}
+
 /// Main input handler with prefix key state machine
 pub struct InputHandler {
     /// Current input mode
@@ -156,6 +168,10 @@ pub struct InputHandler {
     mouse_enabled: bool,
     /// Quick navigation bindings (no prefix required)
     quick_bindings: QuickBindings,
+    /// Copy mode search prompt being edited
+    copy_search: Option<CopySearchPrompt>,
+    /// Previous copy mode search patterns, oldest first
+    search_history: Vec<String>,
 }
 
 impl Default for InputHandler {
@@ -177,6 +193,8 @@ impl InputHandler {
             scroll_offset: 0,
             mouse_enabled: true,
             quick_bindings: QuickBindings::default(),
+            copy_search: None,
+            search_history: Vec::new(),
         }
     }
 
@@ -222,6 +240,20 @@ impl InputHandler {
         &self.command_buffer
     }
 
+    /// Switch to copy mode without a key press (e.g. to show a search match)
+    pub fn enter_copy_mode(&mut self) {
+        self.mode = InputMode::Copy;
+        self.scroll_offset = 0;
+        self.copy_search = None;
+    }
+
+    /// Copy mode search prompt as shown in the status bar, e.g. "/error"
+    pub fn copy_search_prompt(&self) -> Option<String> {
+        self.copy_search.as_ref().map(|prompt| {
+            format!("{}{}", if prompt.backward { '?' } else { '/' }, prompt.pattern)
+        })
+    }
+
     /// Get scroll offset (for copy mode)
     pub fn scroll_offset(&self) -> usize {
         self.scroll_offset
@@ -419,9 +451,22 @@ impl InputHandler {
             KeyCode::Char('[') => {
                 self.mode = InputMode::Copy;
                 self.scroll_offset = 0;
+                self.copy_search = None;
                 InputAction::Command(ClientCommand::EnterCopyMode)
             }
 
+            // Paste buffers (tmux defaults)
+            KeyCode::Char(']') => InputAction::Command(ClientCommand::PasteBuffer {
+                name: None,
+                delete: false,
+            }),
+            KeyCode::Char('#') => InputAction::Command(ClientCommand::ListBuffers),
+
+            // Agent permission prompts
+            KeyCode::Char('Y') => InputAction::Command(ClientCommand::ApprovePermission { always: false }),
+            KeyCode::Char('A') => InputAction::Command(ClientCommand::ApprovePermission { always: true }),
+            KeyCode::Char('N') => InputAction::Command(ClientCommand::DenyPermission),
+
             // Help
             KeyCode::Char('?') => InputAction::Command(ClientCommand::ShowHelp),
 
@@ -460,6 +505,10 @@ impl InputHandler {
 
     /// Handle key in copy mode
     fn handle_copy_key(&mut self, key: KeyEvent) -> InputAction {
+        if self.copy_search.is_some() {
+            return self.handle_copy_search_key(key);
+        }
+
         match key.code {
             // Exit copy mode
             KeyCode::Esc => {
@@ -558,6 +607,21 @@ impl InputHandler {
                 InputAction::ScrollDown { lines: usize::MAX }
             }
 
+            // Search (vi: / forward, ? backward)
+            KeyCode::Char(c @ ('/' | '?')) => {
+                let backward = c == '?';
+                self.copy_search = Some(CopySearchPrompt {
+                    backward,
+                    pattern: String::new(),
+                    history_index: None,
+                });
+                InputAction::Command(ClientCommand::StartCopySearch { backward })
+            }
+
+            // Search matches
+            KeyCode::Char('n') => InputAction::Command(ClientCommand::NextSearchMatch),
+            KeyCode::Char('N') => InputAction::Command(ClientCommand::PreviousSearchMatch),
+
             // Word movement (simplified - move by 5 chars)
             KeyCode::Char('w') => {
                 InputAction::Command(ClientCommand::MoveCopyCursor {
@@ -576,6 +640,53 @@ impl InputHandler {
         }
     }
 
+    /// Handle key while editing the copy mode search prompt
+    fn handle_copy_search_key(&mut self, key: KeyEvent) -> InputAction {
+        let Some(prompt) = self.copy_search.as_mut() else {
+            return InputAction::None;
+        };
+        match key.code {
+            KeyCode::Esc => {
+                self.copy_search = None;
+                return InputAction::Command(ClientCommand::CancelCopySearch);
+            }
+            KeyCode::Enter => {
+                let pattern = std::mem::take(&mut prompt.pattern);
+                self.copy_search = None;
+                if pattern.is_empty() {
+                    return InputAction::Command(ClientCommand::CancelCopySearch);
+                }
+                self.search_history.retain(|previous| *previous != pattern);
+                self.search_history.push(pattern.clone());
+                if self.search_history.len() > MAX_SEARCH_HISTORY {
+                    self.search_history.remove(0);
+                }
+                return InputAction::Command(ClientCommand::ConfirmCopySearch(pattern));
+            }
+            KeyCode::Backspace => {
+                prompt.pattern.pop();
+                prompt.history_index = None;
+            }
+            KeyCode::Up | KeyCode::Down => {
+                let count = self.search_history.len();
+                let index = match (key.code, prompt.history_index) {
+                    (KeyCode::Up, None) => count.checked_sub(1),
+                    (KeyCode::Up, Some(index)) => Some(index.saturating_sub(1)),
+                    (_, Some(index)) if index + 1 < count => Some(index + 1),
+                    _ => None,
+                };
+                prompt.history_index = index;
+                prompt.pattern = index.map(|i| self.search_history[i].clone()).unwrap_or_default();
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                prompt.pattern.push(c);
+                prompt.history_index = None;
+            }
+            _ => return InputAction::None,
+        }
+        InputAction::Command(ClientCommand::UpdateCopySearch(prompt.pattern.clone()))
+    }
+
     /// Handle mouse event
     fn handle_mouse(&mut self, mouse: MouseEvent) -> InputAction {
         if !self.mouse_enabled {
@@ -632,6 +743,22 @@ mod tests {
         assert_eq!(handler.mode(), InputMode::Normal);
     }
 
+    #[test]
+    fn test_prefix_permission_keys() {
+        let mut handler = InputHandler::new();
+        let prefix = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::CONTROL);
+
+        for (key, expected) in [
+            ('Y', ClientCommand::ApprovePermission { always: false }),
+            ('A', ClientCommand::ApprovePermission { always: true }),
+            ('N', ClientCommand::DenyPermission),
+        ] {
+            handler.handle_key(prefix);
+            let result = handler.handle_key(KeyEvent::new(KeyCode::Char(key), KeyModifiers::SHIFT));
+            assert_eq!(result, InputAction::Command(expected));
+        }
+    }
+
     #[test]
     fn test_double_prefix_sends_literal() {
         let mut handler = InputHandler::new();
@@ -835,6 +962,75 @@ mod tests {
         assert_eq!(result, InputAction::Command(ClientCommand::ExitCopyMode));
     }
 
+    #[test]
+    fn test_search_match_keys_in_copy_mode() {
+        let mut handler = InputHandler::new();
+        handler.enter_copy_mode();
+        assert_eq!(handler.mode(), InputMode::Copy);
+
+        let n_key = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::empty());
+        assert_eq!(
+            handler.handle_key(n_key),
+            InputAction::Command(ClientCommand::NextSearchMatch)
+        );
+
+        let shift_n_key = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        assert_eq!(
+            handler.handle_key(shift_n_key),
+            InputAction::Command(ClientCommand::PreviousSearchMatch)
+        );
+        assert_eq!(handler.mode(), InputMode::Copy);
+    }
+
+    #[test]
+    fn test_copy_search_prompt_and_history() {
+        let mut handler = InputHandler::new();
+        handler.enter_copy_mode();
+        let key = |code| KeyEvent::new(code, KeyModifiers::empty());
+
+        assert_eq!(
+            handler.handle_key(key(KeyCode::Char('?'))),
+            InputAction::Command(ClientCommand::StartCopySearch { backward: true })
+        );
+        assert_eq!(
+            handler.handle_key(key(KeyCode::Char('q'))),
+            InputAction::Command(ClientCommand::UpdateCopySearch("q".to_string()))
+        );
+        handler.handle_key(key(KeyCode::Char('x')));
+        handler.handle_key(key(KeyCode::Backspace));
+        assert_eq!(handler.copy_search_prompt().as_deref(), Some("?q"));
+        assert_eq!(
+            handler.handle_key(key(KeyCode::Enter)),
+            InputAction::Command(ClientCommand::ConfirmCopySearch("q".to_string()))
+        );
+        assert_eq!(handler.copy_search_prompt(), None);
+        assert_eq!(handler.mode(), InputMode::Copy);
+
+        handler.handle_key(key(KeyCode::Char('/')));
+        for c in "err.*".chars() {
+            handler.handle_key(key(KeyCode::Char(c)));
+        }
+        handler.handle_key(key(KeyCode::Enter));
+
+        // Up recalls the newest pattern first
+        handler.handle_key(key(KeyCode::Char('/')));
+        assert_eq!(
+            handler.handle_key(key(KeyCode::Up)),
+            InputAction::Command(ClientCommand::UpdateCopySearch("err.*".to_string()))
+        );
+        handler.handle_key(key(KeyCode::Up));
+        assert_eq!(handler.copy_search_prompt().as_deref(), Some("/q"));
+        handler.handle_key(key(KeyCode::Down));
+        handler.handle_key(key(KeyCode::Down));
+        assert_eq!(handler.copy_search_prompt().as_deref(), Some("/"));
+
+        assert_eq!(
+            handler.handle_key(key(KeyCode::Esc)),
+            InputAction::Command(ClientCommand::CancelCopySearch)
+        );
+        assert_eq!(handler.mode(), InputMode::Copy);
+    }
+
     #[test]
     fn test_mouse_disabled() {
         let mut handler = InputHandler::new();
@@ -38,11 +38,14 @@ pub fn handle_mouse_event(event: MouseEvent, mode: InputMode) -> InputAction {
             InputAction::None
         }
 
-        // Middle click - paste from clipboard (future)
-        MouseEventKind::Down(MouseButton::Middle) => {
-            // Currently no action for middle click
-            InputAction::None
-        }
+        // Middle click - paste the most recent buffer into the active pane
+        MouseEventKind::Down(MouseButton::Middle) => match mode {
+            InputMode::Copy => InputAction::None,
+            _ => InputAction::Command(ClientCommand::PasteBuffer {
+                name: None,
+                delete: false,
+            }),
+        },
 
         // Scroll wheel
         MouseEventKind::ScrollUp => {
@@ -277,6 +280,20 @@ mod tests {
         assert_eq!(result, InputAction::None);
     }
 
+    #[test]
+    fn test_middle_click_pastes_buffer() {
+        let event = make_mouse_event(MouseEventKind::Down(MouseButton::Middle), 10, 5);
+        let result = handle_mouse_event(event, InputMode::Normal);
+        assert_eq!(
+            result,
+            InputAction::Command(ClientCommand::PasteBuffer {
+                name: None,
+                delete: false
+            })
+        );
+        assert_eq!(handle_mouse_event(event, InputMode::Copy), InputAction::None);
+    }
+
     #[test]
     fn test_scroll_up_normal() {
         let event = make_mouse_event(MouseEventKind::ScrollUp, 10, 5);
@@ -11,25 +11,41 @@ mod commands;
 mod config;
 mod connection;
 mod input;
+mod oneshot;
+mod snapshot;
 mod ui;
+mod workspace;
 
 pub use commands::{is_command, parse_command, Command, ParseError};
 
 use auto_start::{ensure_server_running, AutoStartConfig, ServerStartResult};
-use cli::Args;
+use cli::{Args, ClientCommand};
+use connection::Connection;
+use oneshot::OneShot;
 use config::load_quick_bindings;
 use ui::App;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command-line arguments first (before terminal setup)
-    let args = Args::parse_args();
+    let mut args = Args::parse_args();
 
     // Initialize logging to file (not stderr, since we're using the terminal)
     init_logging_with_config(LogConfig::client())?;
     tracing::info!("fugue client starting");
     tracing::debug!("CLI args: {:?}", args);
 
+    // One-shot subcommands print their own output and exit
+    if let Some(command) = args.subcommand.take() {
+        return match run_command(args, command).await {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        };
+    }
+
     // Run the application
     match run_app(args).await {
         Ok(()) => {
@@ -46,6 +62,37 @@ async fn main() -> Result<()> {
 }
 
 async fn run_app(args: Args) -> Result<()> {
+    let resolved_addr = prepare_server(&args).await?;
+
+    // Load keybindings from config
+    let quick_bindings = load_quick_bindings();
+
+    // Create and run the app with optional custom connection
+    let mut app = if let Some(ref addr) = resolved_addr {
+        App::with_addr(addr.clone())?
+    } else if let Some(ref socket) = args.socket {
+        App::with_socket_path(socket.clone())?
+    } else {
+        App::new()?
+    };
+
+    // Apply TLS material and auth token for remote connections
+    app.set_tls_options(args.tls_options());
+
+    // Apply loaded keybindings
+    app.set_quick_bindings(quick_bindings);
+
+    // Set session command from CLI args (overrides default_command in config)
+    app.set_session_command(args.command_string());
+
+    app.run().await
+}
+
+/// Resolve the server address and auto-start the local server if needed
+///
+/// Returns the address to connect to, or `None` for the default socket
+/// (or `--socket`).
+async fn prepare_server(args: &Args) -> Result<Option<String>> {
     // Resolve target alias if provided
     let resolved_addr = if let Some(ref target) = args.target {
         match config::resolve_remote(target) {
@@ -99,23 +146,28 @@ async fn run_app(args: Args) -> Result<()> {
         tracing::info!("Connecting to custom address/socket, skipping default server auto-start");
     }
 
-    // Load keybindings from config
-    let quick_bindings = load_quick_bindings();
+    Ok(resolved_addr)
+}
 
-    // Create and run the app with optional custom connection
-    let mut app = if let Some(ref addr) = resolved_addr {
-        App::with_addr(addr.clone())?
+/// Run a one-shot subcommand against the server
+async fn run_command(args: Args, command: ClientCommand) -> Result<()> {
+    let resolved_addr = prepare_server(&args).await?;
+
+    let mut connection = if let Some(addr) = resolved_addr {
+        Connection::with_addr(addr)
     } else if let Some(ref socket) = args.socket {
-        App::with_socket_path(socket.clone())?
+        Connection::with_socket_path(socket.clone())
     } else {
-        App::new()?
+        Connection::new()
     };
+    connection.set_tls_options(args.tls_options());
 
-    // Apply loaded keybindings
-    app.set_quick_bindings(quick_bindings);
-
-    // Set session command from CLI args (overrides default_command in config)
-    app.set_session_command(args.command_string());
-
-    app.run().await
+    let mut client = OneShot::connect(connection).await?;
+    let result = match command {
+        ClientCommand::Snapshot(command) => snapshot::run(&mut client, command).await,
+        ClientCommand::Up { file } => workspace::up(&mut client, &file).await,
+        ClientCommand::Down { file } => workspace::down(&mut client, &file).await,
+    };
+    client.close().await;
+    result
 }
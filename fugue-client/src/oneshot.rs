@@ -0,0 +1,81 @@
+//! One-shot requests for command-line subcommands
+//!
+//! Connects as a `Compat` client, sends a single request and waits for its
+//! reply, skipping any broadcasts that arrive in between.
+
+use std::time::Duration;
+
+use fugue_protocol::{ClientMessage, ClientType, ServerMessage};
+use fugue_utils::{CcmuxError, Result};
+use uuid::Uuid;
+
+use crate::connection::Connection;
+
+/// How long to wait for the server to answer a request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A connection used for a handful of request/response exchanges
+pub struct OneShot {
+    connection: Connection,
+}
+
+impl OneShot {
+    /// Connect and complete the protocol handshake
+    pub async fn connect(mut connection: Connection) -> Result<Self> {
+        connection.connect().await?;
+        let mut client = Self { connection };
+
+        let reply = client
+            .request(
+                ClientMessage::Connect {
+                    client_id: Uuid::new_v4(),
+                    protocol_version: fugue_protocol::PROTOCOL_VERSION,
+                    client_type: ClientType::Compat,
+                },
+                |msg| matches!(msg, ServerMessage::Connected { .. }),
+            )
+            .await?;
+        debug_assert!(matches!(reply, ServerMessage::Connected { .. }));
+
+        Ok(client)
+    }
+
+    /// Send a request and wait for the reply accepted by `is_reply`
+    ///
+    /// Server errors are returned as `Err`.
+    pub async fn request(
+        &mut self,
+        msg: ClientMessage,
+        is_reply: impl Fn(&ServerMessage) -> bool,
+    ) -> Result<ServerMessage> {
+        self.connection.send(msg).await?;
+
+        let wait = async {
+            while let Some(msg) = self.connection.recv().await {
+                match msg {
+                    ServerMessage::Error { code, message, .. } => {
+                        return Err(CcmuxError::Protocol(format!(
+                            "server returned {:?}: {}",
+                            code, message
+                        )));
+                    }
+                    ServerMessage::Sequenced { inner, .. } if is_reply(&inner) => return Ok(*inner),
+                    msg if is_reply(&msg) => return Ok(msg),
+                    _ => continue,
+                }
+            }
+            Err(CcmuxError::ConnectionClosed)
+        };
+
+        tokio::time::timeout(REQUEST_TIMEOUT, wait)
+            .await
+            .map_err(|_| CcmuxError::ConnectionTimeout {
+                seconds: REQUEST_TIMEOUT.as_secs(),
+            })?
+    }
+
+    /// Close the connection
+    pub async fn close(mut self) {
+        self.connection.disconnect().await;
+    }
+}
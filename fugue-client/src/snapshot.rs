@@ -0,0 +1,107 @@
+//! `fugue snapshot` - named snapshots and point-in-time restore
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use fugue_protocol::{ClientMessage, ServerMessage, SnapshotSource};
+use fugue_utils::Result;
+
+use crate::cli::SnapshotCommand;
+use crate::oneshot::OneShot;
+
+/// Run a snapshot subcommand against the server
+pub async fn run(client: &mut OneShot, command: SnapshotCommand) -> Result<()> {
+    match command {
+        SnapshotCommand::Save { name, session } => {
+            let reply = client
+                .request(
+                    ClientMessage::SaveSnapshot {
+                        name,
+                        session_filter: session,
+                    },
+                    |msg| matches!(msg, ServerMessage::SnapshotSaved { .. }),
+                )
+                .await?;
+            if let ServerMessage::SnapshotSaved { snapshot } = reply {
+                println!(
+                    "Saved snapshot '{}' ({})",
+                    snapshot.name,
+                    snapshot.sessions.join(", ")
+                );
+            }
+        }
+        SnapshotCommand::List => {
+            let reply = client
+                .request(ClientMessage::ListSnapshots, |msg| {
+                    matches!(msg, ServerMessage::SnapshotList { .. })
+                })
+                .await?;
+            if let ServerMessage::SnapshotList { snapshots } = reply {
+                if snapshots.is_empty() {
+                    println!("No snapshots");
+                }
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0);
+                for snapshot in snapshots {
+                    println!(
+                        "{:<24} {:>10}  {}",
+                        snapshot.name,
+                        format_age(now.saturating_sub(snapshot.created_at)),
+                        snapshot.sessions.join(", ")
+                    );
+                }
+            }
+        }
+        SnapshotCommand::Restore {
+            name,
+            seq,
+            session,
+            clone_as,
+        } => {
+            let source = match (name, seq) {
+                (_, Some(seq)) => SnapshotSource::Sequence(seq),
+                (Some(name), None) => SnapshotSource::Named(name),
+                // clap requires one of the two
+                (None, None) => unreachable!("snapshot restore without a name or --seq"),
+            };
+            let reply = client
+                .request(
+                    ClientMessage::RestoreSnapshot {
+                        source,
+                        session_filter: session,
+                        clone_as,
+                    },
+                    |msg| matches!(msg, ServerMessage::SnapshotRestored { .. }),
+                )
+                .await?;
+            if let ServerMessage::SnapshotRestored { sessions } = reply {
+                for session in sessions {
+                    println!(
+                        "Restored session '{}' ({} window(s))",
+                        session.name, session.window_count
+                    );
+                }
+            }
+        }
+        SnapshotCommand::Delete { name } => {
+            client
+                .request(ClientMessage::DeleteSnapshot { name: name.clone() }, |msg| {
+                    matches!(msg, ServerMessage::SnapshotDeleted { .. })
+                })
+                .await?;
+            println!("Deleted snapshot '{}'", name);
+        }
+    }
+    Ok(())
+}
+
+/// Format an age in seconds as e.g. "5m ago"
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
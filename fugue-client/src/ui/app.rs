@@ -5,6 +5,8 @@
 // Allow unused code that's part of the public API for future features
 #![allow(dead_code)]
 
+use std::io::Write;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
 /// Maximum size for a single input chunk sent to the server.
@@ -27,25 +29,47 @@ const MAX_MESSAGES_PER_TICK: usize = 50;
 /// Default config is 30 seconds, tick rate is 100ms, so 300 ticks.
 const BEADS_REFRESH_INTERVAL_TICKS: u64 = 300;
 
+/// Most matches requested by the `search` command.
+const SEARCH_MATCH_LIMIT: usize = 200;
+
 use crossterm::event::{Event as CrosstermEvent, KeyCode, KeyModifiers};
 use ratatui::layout::Rect;
 use uuid::Uuid;
 
 use fugue_protocol::{
-    ClientMessage, ClientType, PaneState,
-    ServerMessage, SplitDirection,
+    AgentActivity, BudgetScope, ClientMessage, ClientType, ExportFormat, PaneState,
+    PermissionDecision, ServerMessage, SplitDirection, TerminalAlert,
 };
+use fugue_utils::tls::TlsClientOptions;
 use fugue_utils::Result;
 
 use crate::connection::Connection;
 use crate::input::{ClientCommand, InputAction, InputHandler, InputMode};
 
+use super::copy_search::{CopySearch, SEARCH_LINES};
 use super::event::{AppEvent, EventHandler, InputEvent};
 use super::layout::{LayoutManager, LayoutPolicy, SplitDirection as LayoutSplitDirection};
 use super::pane::PaneManager;
-use super::state::{AppState, ClientState, MailboxMessage, ViewMode};
+use super::state::{AppState, ClientState, MailboxMessage, ScrollbackSearch, ViewMode};
 use super::terminal::Terminal;
 
+/// Escape sequence that raises a notification in the user's terminal
+///
+/// Control characters are dropped so the text can't end the sequence early.
+fn terminal_alert_sequence(alert: TerminalAlert, title: &str, message: &str) -> String {
+    let clean = |text: &str| text.chars().filter(|c| !c.is_control()).collect::<String>();
+    match alert {
+        TerminalAlert::Bell => "\x07".to_string(),
+        TerminalAlert::Osc9 => format!("\x1b]9;{}: {}\x07", clean(title), clean(message)),
+        // Fields are separated by ';', so the title can't contain one
+        TerminalAlert::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            clean(title).replace(';', ","),
+            clean(message)
+        ),
+    }
+}
+
 /// Main application
 pub struct App {
     /// Client state
@@ -103,6 +127,11 @@ impl App {
         self.state.session_command = command;
     }
 
+    /// Set TLS material and auth token used when connecting to a remote server
+    pub fn set_tls_options(&mut self, options: TlsClientOptions) {
+        self.connection.set_tls_options(options);
+    }
+
     /// Get current application state
     pub fn state(&self) -> AppState {
         self.state.state
@@ -558,8 +587,18 @@ impl App {
             InputAction::ScrollUp { lines } => {
                 if let Some(pane_id) = self.state.active_pane_id {
                     // Update LOCAL UI pane for immediate visual feedback
-                    if let Some(pane) = self.state.pane_manager.get_mut(pane_id) {
-                        pane.scroll_up(lines);
+                    let history = self.state.pane_manager
+                        .get_mut(pane_id)
+                        .and_then(|pane| pane.scroll_up(lines));
+                    if let Some((start, end)) = history {
+                        // Past the local scrollback: page in history from the server
+                        self.connection
+                            .send(ClientMessage::CapturePane {
+                                pane_id,
+                                start: Some(start),
+                                end: Some(end),
+                            })
+                            .await?;
                     }
 
                     // Get updated scroll offset and sync with server
@@ -579,8 +618,18 @@ impl App {
             InputAction::ScrollDown { lines } => {
                 if let Some(pane_id) = self.state.active_pane_id {
                     // Update LOCAL UI pane for immediate visual feedback
-                    if let Some(pane) = self.state.pane_manager.get_mut(pane_id) {
-                        pane.scroll_down(lines);
+                    let history = self.state.pane_manager
+                        .get_mut(pane_id)
+                        .and_then(|pane| pane.scroll_down(lines));
+                    if let Some((start, end)) = history {
+                        self.connection
+                            .send(ClientMessage::CapturePane {
+                                pane_id,
+                                start: Some(start),
+                                end: Some(end),
+                            })
+                            .await?;
+                        return Ok(());
                     }
 
                     // Get updated scroll offset and sync with server
@@ -656,6 +705,7 @@ impl App {
                 self.state.state = AppState::SessionSelect;
                 self.state.session = None;
                 self.state.windows.clear();
+                self.state.window_layouts.clear();
                 self.state.panes.clear();
                 self.state.pane_manager = PaneManager::new();
                 self.state.active_pane_id = None;
@@ -846,10 +896,11 @@ impl App {
                         pane.enter_copy_mode();
                     }
                 }
-                self.state.status_message = Some("Copy mode - v: visual, V: line, hjkl: move, y: yank, q: exit".to_string());
+                self.state.status_message = Some("Copy mode - v: visual, V: line, hjkl: move, /?: search, y: yank, q: exit".to_string());
             }
 
             ClientCommand::ExitCopyMode => {
+                self.state.copy_search = None;
                 if let Some(pane_id) = self.state.active_pane_id {
                     if let Some(pane) = self.state.pane_manager.get_mut(pane_id) {
                         pane.exit_copy_mode();
@@ -886,6 +937,13 @@ impl App {
                             let len = text.len();
                             pane.exit_copy_mode();
                             self.state.status_message = Some(format!("Yanked {} bytes to clipboard", len));
+                            self.connection
+                                .send(ClientMessage::SetBuffer {
+                                    name: None,
+                                    data: text,
+                                    append: false,
+                                })
+                                .await?;
                         } else {
                             // BUG-039 FIX: Always exit copy mode when yank is triggered,
                             // even if there's no selection. The input handler has already
@@ -1039,6 +1097,19 @@ impl App {
                 self.state.status_message = Some(format!("View mode: {:?}", self.state.view_mode));
             }
 
+            ClientCommand::ApprovePermission { always } => {
+                let decision = if always {
+                    PermissionDecision::ApproveAlways
+                } else {
+                    PermissionDecision::Approve
+                };
+                self.resolve_permission(decision).await?;
+            }
+
+            ClientCommand::DenyPermission => {
+                self.resolve_permission(PermissionDecision::Deny).await?;
+            }
+
             ClientCommand::ShowHelp => {
                 self.state.status_message =
                     Some("Ctrl+B: prefix | c: new pane | x: close | n/p: next/prev".to_string());
@@ -1147,6 +1218,156 @@ impl App {
                 }
             }
 
+            ClientCommand::ExportSession { path, scrollback } => {
+                if let Some(session) = &self.state.session {
+                    let path = PathBuf::from(path);
+                    let format = match path.extension().and_then(|ext| ext.to_str()) {
+                        Some("toml") => ExportFormat::Toml,
+                        _ => ExportFormat::Json,
+                    };
+                    self.connection
+                        .send(ClientMessage::ExportSession {
+                            session_filter: session.id.to_string(),
+                            format,
+                            include_scrollback: scrollback,
+                        })
+                        .await?;
+                    self.state.pending_export = Some(path);
+                }
+            }
+
+            ClientCommand::ImportSession { path, name } => match std::fs::read_to_string(&path) {
+                Ok(document) => {
+                    self.connection
+                        .send(ClientMessage::ImportSession { document, name })
+                        .await?;
+                }
+                Err(e) => {
+                    self.state.status_message = Some(format!("Cannot read {}: {}", path, e));
+                }
+            },
+
+            ClientCommand::SearchScrollback(query) => {
+                if let Some(session) = &self.state.session {
+                    self.connection
+                        .send(ClientMessage::SearchScrollback {
+                            query,
+                            session_filter: Some(session.id.to_string()),
+                            context: 0,
+                            limit: SEARCH_MATCH_LIMIT,
+                        })
+                        .await?;
+                }
+            }
+
+            ClientCommand::StartCopySearch { backward } => {
+                let Some(pane_id) = self.state.active_pane_id else {
+                    return Ok(());
+                };
+                let Some(pane) = self.state.pane_manager.get(pane_id) else {
+                    return Ok(());
+                };
+                let origin = pane.copy_cursor_line().unwrap_or((0, 0));
+                let rows = pane.size().0 as i64;
+                self.state.copy_search = Some(CopySearch::new(pane_id, backward, origin));
+
+                // Search everything the server has, up to the bottom of the screen
+                self.connection
+                    .send(ClientMessage::CapturePane {
+                        pane_id,
+                        start: Some(rows - SEARCH_LINES),
+                        end: None,
+                    })
+                    .await?;
+            }
+
+            ClientCommand::UpdateCopySearch(ref pattern) | ClientCommand::ConfirmCopySearch(ref pattern) => {
+                let confirmed = matches!(cmd, ClientCommand::ConfirmCopySearch(_));
+                let Some(search) = &mut self.state.copy_search else {
+                    return Ok(());
+                };
+                let result = search.set_pattern(pattern);
+                let regex = search.regex().cloned();
+                if let Some(pane) = self.state.pane_manager.get_mut(search.pane_id) {
+                    pane.set_search_highlight(regex);
+                }
+                match result {
+                    Err(e) if confirmed => {
+                        self.state.status_message = Some(format!("Invalid search pattern: {}", e));
+                    }
+                    _ => self.show_copy_search_match().await?,
+                }
+            }
+
+            ClientCommand::CancelCopySearch => {
+                if let Some(search) = self.state.copy_search.take() {
+                    if let Some(pane) = self.state.pane_manager.get_mut(search.pane_id) {
+                        pane.set_search_highlight(None);
+                    }
+                }
+                self.state.status_message = None;
+            }
+
+            ClientCommand::NextSearchMatch | ClientCommand::PreviousSearchMatch
+                if self.copy_search_active() =>
+            {
+                if let Some(search) = &mut self.state.copy_search {
+                    search.step(cmd == ClientCommand::PreviousSearchMatch);
+                }
+                self.show_copy_search_match().await?;
+            }
+
+            ClientCommand::NextSearchMatch | ClientCommand::PreviousSearchMatch => {
+                if let Some(search) = &mut self.state.search {
+                    let count = search.hits.len();
+                    search.current = if cmd == ClientCommand::NextSearchMatch {
+                        (search.current + 1) % count
+                    } else {
+                        (search.current + count - 1) % count
+                    };
+                    self.show_search_match().await?;
+                } else {
+                    self.state.status_message = Some("No search - use :search <query>".to_string());
+                }
+            }
+
+            ClientCommand::ListBuffers => {
+                self.connection.send(ClientMessage::ListBuffers).await?;
+            }
+
+            ClientCommand::ShowBuffer(name) => {
+                self.state.pending_buffer_save = None;
+                self.connection.send(ClientMessage::GetBuffer { name }).await?;
+            }
+
+            ClientCommand::SetBuffer { name, data, append } => {
+                self.connection
+                    .send(ClientMessage::SetBuffer { name, data, append })
+                    .await?;
+            }
+
+            ClientCommand::PasteBuffer { name, delete } => {
+                if let Some(pane_id) = self.state.active_pane_id {
+                    self.connection
+                        .send(ClientMessage::PasteBuffer {
+                            name,
+                            pane_id,
+                            delete,
+                            bracketed: true,
+                        })
+                        .await?;
+                }
+            }
+
+            ClientCommand::SaveBuffer { name, path, append } => {
+                self.state.pending_buffer_save = Some((PathBuf::from(path), append));
+                self.connection.send(ClientMessage::GetBuffer { name }).await?;
+            }
+
+            ClientCommand::DeleteBuffer(name) => {
+                self.connection.send(ClientMessage::DeleteBuffer { name }).await?;
+            }
+
             // Commands not yet implemented
             ClientCommand::CloseWindow
             | ClientCommand::RenameWindow(_)
@@ -1195,6 +1416,40 @@ impl App {
         }
     }
 
+    /// Answer a tool-permission prompt
+    ///
+    /// Targets the active pane if it is waiting on a permission prompt,
+    /// otherwise the first pane that is.
+    async fn resolve_permission(&mut self, decision: PermissionDecision) -> Result<()> {
+        let awaiting = |pane_id: &Uuid| {
+            self.state.panes.get(pane_id).is_some_and(|pane| {
+                matches!(&pane.state, PaneState::Agent(state)
+                    if state.activity == AgentActivity::AwaitingPermission)
+            })
+        };
+        let target = self
+            .state
+            .active_pane_id
+            .filter(|id| awaiting(id))
+            .or_else(|| {
+                let mut ids: Vec<Uuid> = self.state.panes.keys().copied().filter(|id| awaiting(id)).collect();
+                ids.sort_by_key(|id| self.state.panes[id].index);
+                ids.into_iter().next()
+            });
+
+        match target {
+            Some(pane_id) => {
+                self.connection
+                    .send(ClientMessage::ResolvePermission { pane_id, decision })
+                    .await?;
+            }
+            None => {
+                self.state.status_message = Some("No pane is waiting for permission".to_string());
+            }
+        }
+        Ok(())
+    }
+
     /// Cycle through windows by offset (positive = forward, negative = backward)
     fn cycle_window(&mut self, offset: i32) {
         if self.state.windows.is_empty() {
@@ -1249,6 +1504,123 @@ impl App {
             .map(|p| p.id)
     }
 
+    /// Focus the pane of the current search match and show it in copy mode
+    /// Whether `n`/`N` step through a copy mode search rather than `:search` results
+    fn copy_search_active(&self) -> bool {
+        self.state.copy_search.as_ref().is_some_and(|search| {
+            Some(search.pane_id) == self.state.active_pane_id
+                && !search.pattern().is_empty()
+                && self.state.pane_manager
+                    .get(search.pane_id)
+                    .is_some_and(|pane| pane.copy_mode_cursor().is_some())
+        })
+    }
+
+    /// Move the active copy mode search's pane to its current match
+    async fn show_copy_search_match(&mut self) -> Result<()> {
+        let Some(search) = &self.state.copy_search else {
+            return Ok(());
+        };
+        let pane_id = search.pane_id;
+        let prompt = if search.backward { '?' } else { '/' };
+        self.state.status_message = Some(format!("{}{} {}", prompt, search.pattern(), search.counter()));
+        let Some((line, col)) = search.current_match() else {
+            return Ok(());
+        };
+        let Some(pane) = self.state.pane_manager.get_mut(pane_id) else {
+            return Ok(());
+        };
+
+        match pane.show_search_match(line, col) {
+            Some((start, end)) => {
+                self.connection
+                    .send(ClientMessage::CapturePane {
+                        pane_id,
+                        start: Some(start),
+                        end: Some(end),
+                    })
+                    .await?;
+            }
+            None => {
+                let offset = pane.scroll_offset();
+                self.connection
+                    .send(ClientMessage::SetViewportOffset { pane_id, offset })
+                    .await?;
+            }
+        }
+        self.state.needs_redraw = true;
+        Ok(())
+    }
+
+    async fn show_search_match(&mut self) -> Result<()> {
+        let Some(search) = &self.state.search else {
+            return Ok(());
+        };
+        let hit = search.hits[search.current].clone();
+        let position = format!(
+            "'{}' {}/{}{}",
+            search.query,
+            search.current + 1,
+            search.hits.len(),
+            if search.truncated { "+" } else { "" }
+        );
+
+        if !self.state.panes.contains_key(&hit.pane_id) {
+            self.state.status_message = Some(format!("Match {}: pane has closed", position));
+            return Ok(());
+        }
+
+        if self.state.active_pane_id != Some(hit.pane_id) {
+            let current_window_id = self.active_window_id();
+            self.state.last_pane_id = self.state.active_pane_id;
+            self.state.active_pane_id = Some(hit.pane_id);
+            self.state.pane_manager.set_active(hit.pane_id);
+            if current_window_id != Some(hit.window_id) {
+                self.state.last_window_id = current_window_id;
+                self.rebuild_layout_for_active_window();
+            } else if let Some(ref mut layout) = self.state.layout {
+                layout.set_active_pane(hit.pane_id);
+            }
+            self.connection
+                .send(ClientMessage::SelectPane { pane_id: hit.pane_id })
+                .await?;
+        }
+
+        let offset = self.state.pane_manager.get_mut(hit.pane_id).and_then(|pane| {
+            if pane.copy_mode_cursor().is_none() {
+                pane.enter_copy_mode();
+            }
+            pane.jump_to_line(&hit.text, hit.lines_from_end)
+                .then(|| pane.scroll_offset())
+        });
+        self.input_handler.enter_copy_mode();
+
+        let location = format!("{}.{}", hit.window_index, hit.pane_index);
+        self.state.status_message = Some(match offset {
+            Some(offset) => {
+                self.connection
+                    .send(ClientMessage::SetViewportOffset {
+                        pane_id: hit.pane_id,
+                        offset,
+                    })
+                    .await?;
+                format!(
+                    "Match {} in {}: {} (n/N: next/prev, q: exit)",
+                    position,
+                    location,
+                    hit.text.trim()
+                )
+            }
+            None => format!(
+                "Match {} in {} is too far back to show: {}",
+                position,
+                location,
+                hit.text.trim()
+            ),
+        });
+        Ok(())
+    }
+
     /// Get the active window ID (from the active pane)
     fn active_window_id(&self) -> Option<Uuid> {
         self.state.active_pane_id
@@ -1256,6 +1628,33 @@ impl App {
             .map(|p| p.window_id)
     }
 
+    /// Resize every pane in the current layout to its rect and tell the server
+    async fn resize_panes_to_layout(&mut self) -> Result<()> {
+        let (cols, rows) = self.state.terminal_size;
+        let pane_area = Rect::new(0, 0, cols, rows.saturating_sub(1));
+
+        if let Some(ref layout) = self.state.layout {
+            let weights = self.state.calculate_pane_weights();
+            let pane_rects = layout.calculate_rects(pane_area, &weights);
+
+            for (pane_id, rect) in &pane_rects {
+                let inner_width = rect.width.saturating_sub(2);
+                let inner_height = rect.height.saturating_sub(2);
+
+                self.state.pane_manager.resize_pane(*pane_id, inner_height, inner_width);
+
+                self.connection
+                    .send(ClientMessage::Resize {
+                        pane_id: *pane_id,
+                        cols: inner_width,
+                        rows: inner_height,
+                    })
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
     /// Rebuild the layout manager to only include panes from the active window.
     /// This ensures windows act like tabs - only one window's panes are visible at a time.
     fn rebuild_layout_for_active_window(&mut self) {
@@ -1268,6 +1667,26 @@ impl App {
             }
         };
 
+        // Prefer the server-owned split tree for this window
+        if let Some(server_layout) = self.state.window_layouts.get(&active_window_id) {
+            let mut layout_manager = LayoutManager::from_root(server_layout.into());
+
+            // Drop panes the server announced before their PaneCreated arrived
+            for pane_id in layout_manager.pane_ids() {
+                if !self.state.panes.contains_key(&pane_id) {
+                    layout_manager.remove_pane(pane_id);
+                }
+            }
+
+            if layout_manager.pane_ids().iter().any(|id| self.state.panes.contains_key(id)) {
+                if let Some(active_id) = self.state.active_pane_id {
+                    layout_manager.set_active_pane(active_id);
+                }
+                self.state.layout = Some(layout_manager);
+                return;
+            }
+        }
+
         // Filter panes to only those in the active window
         let mut pane_ids: Vec<Uuid> = self.state.panes
             .values()
@@ -1287,8 +1706,7 @@ impl App {
         let first_pane_id = pane_ids[0];
         let mut layout_manager = LayoutManager::new(first_pane_id);
 
-        // Add remaining panes as vertical splits (simple layout)
-        // TODO: Persist per-window layouts in the server for better reconstruction
+        // No layout from the server yet: add remaining panes as vertical splits
         for &pane_id in pane_ids.iter().skip(1) {
             layout_manager.root_mut().add_pane(
                 first_pane_id,
@@ -1451,6 +1869,7 @@ impl App {
                 self.state.last_seen_commit_seq = commit_seq;
                 self.state.session = Some(session);
                 self.state.windows = windows.into_iter().map(|w| (w.id, w)).collect();
+                self.state.window_layouts.clear();
                 self.state.panes = panes.into_iter().map(|p| (p.id, p)).collect();
                 self.state.active_pane_id = self.state.panes.keys().next().copied();
                 self.state.state = AppState::Attached;
@@ -1519,6 +1938,7 @@ impl App {
                 self.state.last_seen_commit_seq = commit_seq;
                 self.state.session = Some(session);
                 self.state.windows = windows.into_iter().map(|w| (w.id, w)).collect();
+                self.state.window_layouts.clear();
                 self.state.panes = panes.into_iter().map(|p| (p.id, p)).collect();
                 self.state.active_pane_id = self.state.panes.keys().next().copied();
                 self.state.state = AppState::Attached;
@@ -1611,7 +2031,10 @@ impl App {
                 let active_window_id = self.active_window_id();
                 let pane_in_active_window = active_window_id == Some(pane_window_id);
 
-                if pane_in_active_window {
+                if pane_in_active_window && self.state.window_layouts.contains_key(&pane_window_id) {
+                    // The server owns this window's layout; pick up the new pane from it
+                    self.rebuild_layout_for_active_window();
+                } else if pane_in_active_window {
                     // Add new pane to layout (only if in active window)
                     if let Some(ref mut layout) = self.state.layout {
                         // Split the active pane to add the new one
@@ -1762,6 +2185,7 @@ impl App {
                 if self.state.panes.is_empty() {
                     self.state.session = None;
                     self.state.windows.clear();
+                    self.state.window_layouts.clear();
                     self.state.active_pane_id = None;
                     self.state.layout = None;
                     self.state.state = AppState::SessionSelect;
@@ -1772,10 +2196,12 @@ impl App {
             }
             ServerMessage::WindowClosed { window_id } => {
                 self.state.windows.remove(&window_id);
+                self.state.window_layouts.remove(&window_id);
             }
             ServerMessage::SessionEnded { .. } => {
                 self.state.session = None;
                 self.state.windows.clear();
+                self.state.window_layouts.clear();
                 self.state.panes.clear();
                 self.state.pane_manager = PaneManager::new();
                 self.state.active_pane_id = None;
@@ -1792,6 +2218,8 @@ impl App {
             }
             ServerMessage::Error { code, message, details } => {
                 self.state.status_message = Some(format!("Error ({:?}): {}", code, message));
+                self.state.pending_export = None;
+                self.state.pending_buffer_save = None;
                 
                 if let Some(fugue_protocol::messages::ErrorDetails::HumanControl { remaining_ms }) = details {
                     self.state.human_control_lock_expiry = Some(Instant::now() + Duration::from_millis(remaining_ms));
@@ -2101,6 +2529,163 @@ impl App {
             ServerMessage::WatchdogStarted { .. } => {}
             ServerMessage::WatchdogStopped { .. } => {}
             ServerMessage::WatchdogStatusResponse { .. } => {}
+
+            // Auth handshake is completed by Connection before the app sees any messages
+            ServerMessage::Authenticated => {}
+
+            // Event subscriptions are only requested by MCP transports
+            ServerMessage::EventSubscription { .. } => {}
+
+            // Expect is only requested by the MCP bridge
+            ServerMessage::ExpectResult { .. } => {}
+
+            // Snapshot responses only go to the `fugue snapshot` command; restored
+            // sessions reach the TUI through SessionsChanged
+            ServerMessage::SnapshotSaved { .. } => {}
+            ServerMessage::SnapshotList { .. } => {}
+            ServerMessage::SnapshotDeleted { .. } => {}
+            ServerMessage::SnapshotRestored { .. } => {}
+
+            ServerMessage::SessionExported { document } => {
+                if let Some(path) = self.state.pending_export.take() {
+                    self.state.status_message = Some(match std::fs::write(&path, document) {
+                        Ok(()) => format!("Exported session to {}", path.display()),
+                        Err(e) => format!("Cannot write {}: {}", path.display(), e),
+                    });
+                }
+            }
+            ServerMessage::SessionImported { session } => {
+                self.state.status_message = Some(format!(
+                    "Imported session '{}' ({} window(s))",
+                    session.name, session.window_count
+                ));
+            }
+
+            ServerMessage::ScrollbackSearchResults { query, hits, truncated } => {
+                if hits.is_empty() {
+                    self.state.search = None;
+                    self.state.status_message = Some(format!("No matches for '{}'", query));
+                } else {
+                    self.state.search = Some(ScrollbackSearch {
+                        query,
+                        hits,
+                        truncated,
+                        current: 0,
+                    });
+                    self.show_search_match().await?;
+                }
+            }
+
+            ServerMessage::PaneCaptured { pane_id, start, lines, .. }
+                if self.state.copy_search.as_ref()
+                    .is_some_and(|search| search.pane_id == pane_id && !search.is_loaded()) =>
+            {
+                // Lines for a copy mode search that is waiting for them
+                if let Some(search) = &mut self.state.copy_search {
+                    search.set_lines(start, &lines);
+                }
+                self.show_copy_search_match().await?;
+            }
+            ServerMessage::PaneCaptured { pane_id, start, lines, history_start } => {
+                let retry = self.state.pane_manager
+                    .get_mut(pane_id)
+                    .and_then(|pane| pane.show_history(start, &lines, history_start));
+                if let Some((start, end)) = retry {
+                    self.connection
+                        .send(ClientMessage::CapturePane {
+                            pane_id,
+                            start: Some(start),
+                            end: Some(end),
+                        })
+                        .await?;
+                } else if lines.is_empty() {
+                    self.state.status_message = Some("Start of history".to_string());
+                }
+                self.state.needs_redraw = true;
+            }
+
+            ServerMessage::BufferSet { .. } => {}
+            ServerMessage::BufferPasted { .. } => {}
+            ServerMessage::BufferList { buffers } => {
+                self.state.status_message = Some(if buffers.is_empty() {
+                    "No buffers".to_string()
+                } else {
+                    let listed: Vec<String> = buffers
+                        .iter()
+                        .map(|buffer| format!("{} ({} bytes)", buffer.name, buffer.size))
+                        .collect();
+                    format!("Buffers: {}", listed.join(", "))
+                });
+            }
+            ServerMessage::BufferContent { name, data } => {
+                self.state.status_message = Some(match self.state.pending_buffer_save.take() {
+                    Some((path, append)) => {
+                        let result = std::fs::OpenOptions::new()
+                            .create(true)
+                            .write(true)
+                            .append(append)
+                            .truncate(!append)
+                            .open(&path)
+                            .and_then(|mut file| file.write_all(data.as_bytes()));
+                        match result {
+                            Ok(()) => format!("Saved buffer {} to {}", name, path.display()),
+                            Err(e) => format!("Cannot write {}: {}", path.display(), e),
+                        }
+                    }
+                    None => format!("{}: {}", name, data.escape_debug()),
+                });
+            }
+            ServerMessage::BufferDeleted { name } => {
+                self.state.status_message = Some(format!("Deleted buffer {}", name));
+            }
+
+            // Workspace responses only go to `fugue up` / `fugue down`
+            ServerMessage::WorkspaceStarted { .. } => {}
+            ServerMessage::WorkspaceStopped { .. } => {}
+
+            ServerMessage::PermissionRequested { request, .. } => {
+                self.state.status_message = Some(format!(
+                    "Permission requested: {} {} (prefix+Y approve, prefix+N deny)",
+                    request.tool, request.arguments
+                ));
+            }
+            ServerMessage::PermissionResolved { option, .. } => {
+                self.state.status_message = Some(format!("Permission answered: {}", option));
+            }
+            ServerMessage::BudgetExceeded { scope, usage, paused, .. } => {
+                let scope = match scope {
+                    BudgetScope::Pane(_) => "pane".to_string(),
+                    BudgetScope::Session(_) => "session".to_string(),
+                    BudgetScope::Tag(tag) => format!("tag '{}'", tag),
+                };
+                let mut message = format!(
+                    "Budget exceeded for {}: {} tokens, ${:.2}",
+                    scope, usage.tokens, usage.cost_usd
+                );
+                if !paused.is_empty() {
+                    message.push_str(&format!(", paused {} pane(s)", paused.len()));
+                }
+                self.state.status_message = Some(message);
+            }
+            ServerMessage::PaneResumed { .. } => {
+                self.state.status_message = Some("Pane resumed".to_string());
+            }
+            ServerMessage::LayoutChanged { layout } => {
+                let window_id = layout.window_id;
+                self.state.window_layouts.insert(window_id, layout.root);
+
+                if self.active_window_id() == Some(window_id) {
+                    self.rebuild_layout_for_active_window();
+                    self.resize_panes_to_layout().await?;
+                }
+            }
+            ServerMessage::TerminalNotification { title, message, alert } => {
+                // Written straight to the terminal, like OSC 52 on yank
+                let mut stdout = std::io::stdout();
+                let _ = stdout.write_all(terminal_alert_sequence(alert, &title, &message).as_bytes());
+                let _ = stdout.flush();
+                self.state.status_message = Some(format!("{}: {}", title, message));
+            }
         }
         break;
     }
@@ -2117,7 +2702,10 @@ impl App {
             InputMode::Normal => "".to_string(),
             InputMode::PrefixPending => " [PREFIX]".to_string(),
             InputMode::Command => format!(" :{}", self.input_handler.command_buffer()),
-            InputMode::Copy => format!(" [COPY +{}]", self.input_handler.scroll_offset()),
+            InputMode::Copy => match self.input_handler.copy_search_prompt() {
+                Some(prompt) => format!(" [COPY] {}", prompt),
+                None => format!(" [COPY +{}]", self.input_handler.scroll_offset()),
+            },
         };
 
         terminal.terminal_mut().draw(|frame| {
@@ -2145,6 +2733,19 @@ mod tests {
 
     // ==================== BUG-011 Tests: Large Paste Handling ====================
 
+    #[test]
+    fn test_terminal_alert_sequence() {
+        assert_eq!(terminal_alert_sequence(TerminalAlert::Bell, "api", "done"), "\x07");
+        assert_eq!(
+            terminal_alert_sequence(TerminalAlert::Osc9, "api", "done\x07\x1b"),
+            "\x1b]9;api: done\x07"
+        );
+        assert_eq!(
+            terminal_alert_sequence(TerminalAlert::Osc777, "a;b", "done"),
+            "\x1b]777;notify;a,b;done\x07"
+        );
+    }
+
     #[test]
     fn test_max_input_chunk_size_is_reasonable() {
         // Chunk size should be much smaller than protocol max (16MB)
@@ -0,0 +1,209 @@
+//! Copy mode search
+//!
+//! `/` and `?` search the pane's history with a regex, like tmux's
+//! copy-mode-vi. The history is fetched from the server once per search
+//! with `CapturePane`, so lines spilled to disk are found too; the pane
+//! highlights the matches that are on screen.
+
+use regex::{Regex, RegexBuilder};
+use uuid::Uuid;
+
+/// Lines of history searched, counting back from the bottom of the screen
+pub const SEARCH_LINES: i64 = 50_000;
+
+/// Compile a search pattern, ignoring case unless it has capitals
+pub fn compile(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(!pattern.chars().any(char::is_uppercase))
+        .build()
+}
+
+/// A copy mode search in one pane
+///
+/// Lines are numbered as in `CapturePane`: 0 is the top of the screen and
+/// history is negative.
+#[derive(Debug)]
+pub struct CopySearch {
+    pub pane_id: Uuid,
+    /// Direction of `/` (false) or `?` (true); `n` repeats it, `N` reverses it
+    pub backward: bool,
+    pattern: String,
+    regex: Option<Regex>,
+    /// Plain text of the searched lines, once fetched
+    lines: Option<Vec<String>>,
+    /// Line number of the first of `lines`
+    first_line: i64,
+    /// (line, column) of every match, in order
+    matches: Vec<(i64, usize)>,
+    /// Index into `matches` of the match shown
+    current: Option<usize>,
+    /// Copy cursor (line, column) when the search started
+    origin: (i64, usize),
+}
+
+impl CopySearch {
+    /// Start a search from the copy cursor at `origin`
+    pub fn new(pane_id: Uuid, backward: bool, origin: (i64, usize)) -> Self {
+        Self {
+            pane_id,
+            backward,
+            pattern: String::new(),
+            regex: None,
+            lines: None,
+            first_line: 0,
+            matches: Vec::new(),
+            current: None,
+            origin,
+        }
+    }
+
+    /// Whether the pane's lines have arrived
+    pub fn is_loaded(&self) -> bool {
+        self.lines.is_some()
+    }
+
+    /// Set the lines to search, as returned by `CapturePane`
+    pub fn set_lines(&mut self, first_line: i64, lines: &[String]) {
+        self.first_line = first_line;
+        self.lines = Some(
+            lines
+                .iter()
+                .map(|line| String::from_utf8_lossy(&strip_ansi_escapes::strip(line)).into_owned())
+                .collect(),
+        );
+        self.find_matches();
+    }
+
+    /// Pattern as typed
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Compiled pattern, if it is valid and not empty
+    pub fn regex(&self) -> Option<&Regex> {
+        self.regex.as_ref()
+    }
+
+    /// Change the pattern and move to the first match from the origin
+    ///
+    /// An invalid pattern clears the matches and returns the error.
+    pub fn set_pattern(&mut self, pattern: &str) -> Result<(), regex::Error> {
+        self.pattern = pattern.to_string();
+        self.regex = None;
+        let result = if pattern.is_empty() {
+            Ok(())
+        } else {
+            compile(pattern).map(|regex| self.regex = Some(regex))
+        };
+        self.find_matches();
+        result
+    }
+
+    fn find_matches(&mut self) {
+        self.matches.clear();
+        self.current = None;
+        let (Some(regex), Some(lines)) = (&self.regex, &self.lines) else {
+            return;
+        };
+        for (line, text) in (self.first_line..).zip(lines) {
+            for found in regex.find_iter(text).filter(|found| !found.is_empty()) {
+                self.matches.push((line, text[..found.start()].chars().count()));
+            }
+        }
+
+        // The first match after (or before) the origin, wrapping around
+        let after = self.matches.partition_point(|&pos| pos <= self.origin);
+        let before = self.matches.partition_point(|&pos| pos < self.origin);
+        self.current = match (self.backward, self.matches.len()) {
+            (_, 0) => None,
+            (false, count) => Some(after % count),
+            (true, count) => Some((before + count - 1) % count),
+        };
+    }
+
+    /// (line, column) of the match being shown
+    pub fn current_match(&self) -> Option<(i64, usize)> {
+        self.current.map(|index| self.matches[index])
+    }
+
+    /// Move to the next match in the search direction (`n`), or against it (`N`)
+    pub fn step(&mut self, reverse: bool) -> Option<(i64, usize)> {
+        let count = self.matches.len();
+        let index = self.current?;
+        self.current = Some(if self.backward != reverse {
+            (index + count - 1) % count
+        } else {
+            (index + 1) % count
+        });
+        self.current_match()
+    }
+
+    /// Match counter for the status bar, e.g. "[3/17]"
+    pub fn counter(&self) -> String {
+        match self.current {
+            Some(index) => format!("[{}/{}]", index + 1, self.matches.len()),
+            None if !self.is_loaded() => "[searching]".to_string(),
+            None => "[no matches]".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(backward: bool, origin: (i64, usize)) -> CopySearch {
+        let mut search = CopySearch::new(Uuid::new_v4(), backward, origin);
+        let lines: Vec<String> = [
+            "\x1b[31merror\x1b[0m: disk full",
+            "retrying",
+            "Error: disk full again",
+            "ok",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        search.set_lines(-2, &lines);
+        search
+    }
+
+    #[test]
+    fn test_forward_search_wraps_around() {
+        let mut search = search(false, (-1, 0));
+        search.set_pattern("disk").unwrap();
+        assert_eq!(search.current_match(), Some((0, 7)));
+        assert_eq!(search.counter(), "[2/2]");
+
+        assert_eq!(search.step(false), Some((-2, 7)));
+        assert_eq!(search.step(true), Some((0, 7)));
+    }
+
+    #[test]
+    fn test_backward_search_and_smart_case() {
+        let mut search = search(true, (0, 0));
+        search.set_pattern("err").unwrap();
+        assert_eq!(search.current_match(), Some((-2, 0)));
+        // `n` keeps going backward
+        assert_eq!(search.step(false), Some((0, 0)));
+
+        search.set_pattern("Err").unwrap();
+        assert_eq!(search.counter(), "[1/1]");
+        assert_eq!(search.current_match(), Some((0, 0)));
+    }
+
+    #[test]
+    fn test_invalid_pattern_clears_matches() {
+        let mut search = search(false, (0, 0));
+        search.set_pattern("full").unwrap();
+        assert!(search.current_match().is_some());
+
+        assert!(search.set_pattern("full(").is_err());
+        assert!(search.regex().is_none());
+        assert_eq!(search.current_match(), None);
+        assert_eq!(search.counter(), "[no matches]");
+
+        let mut pending = CopySearch::new(Uuid::new_v4(), false, (0, 0));
+        pending.set_pattern("x").unwrap();
+        assert_eq!(pending.counter(), "[searching]");
+    }
+}
@@ -27,6 +27,21 @@ impl From<fugue_protocol::SplitDirection> for SplitDirection {
     }
 }
 
+impl From<&fugue_protocol::LayoutNode> for LayoutNode {
+    fn from(node: &fugue_protocol::LayoutNode) -> Self {
+        match node {
+            fugue_protocol::LayoutNode::Pane { id } => LayoutNode::Pane { id: *id },
+            fugue_protocol::LayoutNode::Split { direction, children } => LayoutNode::Split {
+                direction: (*direction).into(),
+                children: children
+                    .iter()
+                    .map(|(child, ratio)| (LayoutNode::from(child), *ratio))
+                    .collect(),
+            },
+        }
+    }
+}
+
 /// Layout policy for dynamic resizing
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LayoutPolicy {
@@ -439,6 +454,16 @@ impl LayoutManager {
         }
     }
 
+    /// Create a layout manager around an existing layout tree
+    pub fn from_root(root: LayoutNode) -> Self {
+        let active_pane_id = root.pane_ids().first().copied();
+        Self {
+            root,
+            active_pane_id,
+            policy: LayoutPolicy::default(),
+        }
+    }
+
     /// Create from a preset
     pub fn from_preset(preset: LayoutPreset, pane_ids: Vec<Uuid>) -> Self {
         let root = preset.create_layout(pane_ids.clone());
@@ -1078,4 +1103,28 @@ mod tests {
         ]);
         assert!(multi_child.unwrap_single_child().is_none());
     }
+
+    #[test]
+    fn test_from_protocol_layout() {
+        let id1 = Uuid::new_v4();
+        let id2 = Uuid::new_v4();
+        let server = fugue_protocol::LayoutNode::Split {
+            direction: fugue_protocol::SplitDirection::Horizontal,
+            children: vec![
+                (fugue_protocol::LayoutNode::pane(id1), 0.7),
+                (fugue_protocol::LayoutNode::pane(id2), 0.3),
+            ],
+        };
+
+        let manager = LayoutManager::from_root(LayoutNode::from(&server));
+        assert_eq!(manager.pane_ids(), vec![id1, id2]);
+        assert_eq!(manager.active_pane_id(), Some(id1));
+        match manager.root() {
+            LayoutNode::Split { direction, children } => {
+                assert_eq!(*direction, SplitDirection::Horizontal);
+                assert!((children[0].1 - 0.7).abs() < f32::EPSILON);
+            }
+            _ => panic!("expected split"),
+        }
+    }
 }
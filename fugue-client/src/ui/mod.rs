@@ -5,6 +5,7 @@
 
 mod app;
 mod borders;
+mod copy_search;
 mod event;
 mod layout;
 mod pane;
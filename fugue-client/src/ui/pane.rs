@@ -9,6 +9,7 @@
 use std::io::Write;
 
 use base64::{engine::general_purpose, Engine as _};
+use regex::Regex;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Modifier, Style};
@@ -163,12 +164,26 @@ pub struct Pane {
     copy_mode_cursor: Option<SelectionPos>,
     /// Current text selection (when in visual mode)
     selection: Option<Selection>,
-    /// Internal paste buffer (fallback when OSC 52 not available)
-    paste_buffer: Option<String>,
     /// Whether bracketed paste mode is enabled by the application
     bracketed_paste_enabled: bool,
     /// Whether this pane is a mirror of another pane (FEAT-062)
     is_mirror: bool,
+    /// Server history shown in copy mode past the local scrollback
+    history: Option<HistoryView>,
+    /// Copy mode search pattern whose matches are highlighted
+    search_highlight: Option<Regex>,
+    /// Search match (line, col) to put the copy cursor on once requested history arrives
+    pending_match: Option<(i64, usize)>,
+}
+
+/// A screenful of server-side history, fetched with `CapturePane`
+struct HistoryView {
+    /// Line number of the top row (0 = top of the live screen, negative = history)
+    top: i64,
+    /// Oldest line the server still has, same numbering
+    start: i64,
+    /// Terminal holding just the fetched rows
+    parser: Parser,
 }
 
 impl Pane {
@@ -185,9 +200,11 @@ impl Pane {
             show_scrollbar: true,
             copy_mode_cursor: None,
             selection: None,
-            paste_buffer: None,
             bracketed_paste_enabled: false,
             is_mirror: false,
+            history: None,
+            search_highlight: None,
+            pending_match: None,
         }
     }
 
@@ -313,6 +330,7 @@ impl Pane {
         let (current_rows, current_cols) = self.size();
         if current_rows != rows || current_cols != cols {
             let was_at_bottom = self.scroll_offset == 0;
+            self.history = None;
             self.parser.set_size(rows, cols);
             if was_at_bottom {
                 self.parser.set_scrollback(0);
@@ -329,35 +347,187 @@ impl Pane {
     }
 
     /// Scroll up by given number of lines
-    pub fn scroll_up(&mut self, lines: usize) {
+    ///
+    /// In copy mode, returns the lines to request with `CapturePane` when
+    /// this scrolls past the local scrollback (see [`Pane::show_history`]).
+    pub fn scroll_up(&mut self, lines: usize) -> Option<(i64, i64)> {
+        let lines = i64::try_from(lines).unwrap_or(i64::MAX);
+        if let Some(history) = &self.history {
+            return self.history_range(history.top.saturating_sub(lines).max(history.start));
+        }
+
         // Calculate desired scroll position
-        let desired_offset = self.scroll_offset.saturating_add(lines);
+        let desired_offset = self.scroll_offset.saturating_add(lines as usize).min(self.max_local_offset());
         // Set scrollback - vt100 will clamp to actual scrollback buffer size
         self.parser.set_scrollback(desired_offset);
         // Read back the clamped value to stay in sync with vt100's state
-        self.scroll_offset = self.parser.screen().scrollback();
+        let previous = std::mem::replace(&mut self.scroll_offset, self.parser.screen().scrollback());
+
+        let remaining = lines - (self.scroll_offset - previous) as i64;
+        if remaining > 0 && self.copy_mode_cursor.is_some() {
+            self.history_range((-(self.scroll_offset as i64)).saturating_sub(remaining))
+        } else {
+            None
+        }
     }
 
     /// Scroll down by given number of lines
-    pub fn scroll_down(&mut self, lines: usize) {
+    ///
+    /// Like [`Pane::scroll_up`], returns the lines to request while the view
+    /// is still in server history.
+    pub fn scroll_down(&mut self, lines: usize) -> Option<(i64, i64)> {
+        let mut lines = lines;
+        if let Some(history) = &self.history {
+            let top = history.top.saturating_add(i64::try_from(lines).unwrap_or(i64::MAX));
+            let local_top = -(self.scroll_offset as i64);
+            if top < local_top {
+                return self.history_range(top);
+            }
+            // Back within the local scrollback
+            self.history = None;
+            lines = (top - local_top) as usize;
+        }
+
         // Calculate desired scroll position
         let desired_offset = self.scroll_offset.saturating_sub(lines);
         // Set scrollback - vt100 will clamp to valid range
         self.parser.set_scrollback(desired_offset);
         // Read back the clamped value to stay in sync with vt100's state
         self.scroll_offset = self.parser.screen().scrollback();
+        None
     }
 
     /// Scroll to top of scrollback
     pub fn scroll_to_top(&mut self) {
-        // Set to maximum possible value - vt100 will clamp to actual scrollback size
-        self.parser.set_scrollback(usize::MAX);
+        self.parser.set_scrollback(self.max_local_offset());
         // Read back the clamped value
         self.scroll_offset = self.parser.screen().scrollback();
     }
 
+    /// Furthest the local view can scroll up
+    ///
+    /// vt100 0.15 can't show more than a screenful of scrollback (larger
+    /// offsets underflow in `visible_rows`); older lines come from the server.
+    fn max_local_offset(&self) -> usize {
+        self.size().0 as usize
+    }
+
+    /// Lines to request for a screenful of history starting at `top`
+    fn history_range(&self, top: i64) -> Option<(i64, i64)> {
+        if self.history.as_ref().is_some_and(|history| history.top == top) {
+            return None;
+        }
+        let rows = self.size().0 as i64;
+        Some((top, top.saturating_add(rows - 1)))
+    }
+
+    /// Show lines fetched with `CapturePane` in copy mode
+    ///
+    /// `start` is the line number of the first of `lines` and `history_start`
+    /// the oldest line the server has. When the requested range was entirely
+    /// older than that, returns the range to request instead, if any.
+    pub fn show_history(&mut self, start: i64, lines: &[String], history_start: i64) -> Option<(i64, i64)> {
+        self.copy_mode_cursor?;
+        if lines.is_empty() {
+            let top = self.history.as_ref().map_or(-(self.scroll_offset as i64), |history| history.top);
+            return (history_start < top).then(|| self.history_range(history_start)).flatten();
+        }
+
+        let (rows, cols) = self.size();
+        let mut parser = Parser::new(rows, cols, 0);
+        for (i, line) in lines.iter().take(rows as usize).enumerate() {
+            if i > 0 {
+                parser.process(b"\r\n");
+            }
+            parser.process(line.as_bytes());
+        }
+        self.history = Some(HistoryView {
+            top: start,
+            start: history_start,
+            parser,
+        });
+        self.selection = None;
+        if let Some((line, col)) = self.pending_match.take() {
+            self.place_search_cursor(start, line, col);
+        }
+        None
+    }
+
+    /// Line number of the copy cursor, numbered like `CapturePane` lines
+    pub fn copy_cursor_line(&self) -> Option<(i64, usize)> {
+        let cursor = self.copy_mode_cursor?;
+        let top = self.history.as_ref().map_or(-(self.scroll_offset as i64), |history| history.top);
+        Some((top + cursor.row as i64, cursor.col))
+    }
+
+    /// Show a search match found at `line` (numbered like `CapturePane`)
+    ///
+    /// Scrolls locally when the line is within reach, otherwise returns the
+    /// lines to request; either way the copy cursor ends up on the match.
+    pub fn show_search_match(&mut self, line: i64, col: usize) -> Option<(i64, i64)> {
+        let rows = self.size().0 as i64;
+        // Center the line if the local scrollback reaches it
+        let offset = (rows / 2 - line).clamp(0, self.max_local_offset() as i64);
+        self.parser.set_scrollback(offset as usize);
+        self.scroll_offset = self.parser.screen().scrollback();
+        self.selection = None;
+
+        let local_top = -(self.scroll_offset as i64);
+        if line >= local_top {
+            self.history = None;
+            self.place_search_cursor(local_top, line, col);
+            return None;
+        }
+
+        let top = line - rows / 2;
+        let request = self.history_range(top);
+        match request {
+            Some(_) => self.pending_match = Some((line, col)),
+            None => self.place_search_cursor(top, line, col),
+        }
+        request
+    }
+
+    /// Put the copy cursor on the search match nearest (line, col), given the top line
+    fn place_search_cursor(&mut self, top: i64, line: i64, col: usize) {
+        let rows = self.size().0 as i64;
+        self.copy_mode_cursor = Some(SelectionPos::new((line - top).clamp(0, rows - 1) as usize, col));
+        let Some(regex) = &self.search_highlight else {
+            return;
+        };
+
+        // Screen rows can differ from lines (wrapping), so look around
+        let cols = self.size().1;
+        let target = (line - top) as usize;
+        let nearest = self
+            .screen()
+            .rows(0, cols)
+            .enumerate()
+            .flat_map(|(row, text)| {
+                regex
+                    .find_iter(&text)
+                    .map(|found| (row, text[..found.start()].chars().count()))
+                    .collect::<Vec<_>>()
+            })
+            .min_by_key(|&(row, found_col)| (row.abs_diff(target), found_col.abs_diff(col)));
+        if let Some((row, col)) = nearest {
+            self.copy_mode_cursor = Some(SelectionPos::new(row, col));
+        }
+    }
+
+    /// Highlight matches of a copy mode search, or stop highlighting
+    pub fn set_search_highlight(&mut self, regex: Option<Regex>) {
+        self.search_highlight = regex;
+    }
+
+    /// Copy mode search pattern being highlighted
+    pub fn search_highlight(&self) -> Option<&Regex> {
+        self.search_highlight.as_ref()
+    }
+
     /// Scroll to bottom (live view)
     pub fn scroll_to_bottom(&mut self) {
+        self.history = None;
         self.scroll_offset = 0;
         self.parser.set_scrollback(0);
     }
@@ -389,7 +559,10 @@ impl Pane {
 
     /// Get the underlying screen for rendering
     pub fn screen(&self) -> &Screen {
-        self.parser.screen()
+        match &self.history {
+            Some(history) => history.parser.screen(),
+            None => self.parser.screen(),
+        }
     }
 
     /// Build the title string for display
@@ -410,6 +583,7 @@ impl Pane {
                     AgentActivity::Generating => "Generating",
                     AgentActivity::ToolUse => "Tool Use",
                     AgentActivity::AwaitingConfirmation => "Confirm?",
+                    AgentActivity::AwaitingPermission => "Permission?",
                     AgentActivity::Custom(name) => name.as_str(),
                 };
                 // Include agent type prefix for non-Claude agents
@@ -440,8 +614,65 @@ impl Pane {
         self.selection = None;
     }
 
+    /// Scroll to a line of output and put the copy mode cursor on it
+    ///
+    /// `lines_from_end` is where the server saw the line; if the text occurs
+    /// more than once, the occurrence closest to that is used. Returns false,
+    /// leaving the view alone, if the line is no longer in the local scrollback.
+    pub fn jump_to_line(&mut self, text: &str, lines_from_end: usize) -> bool {
+        let (rows, cols) = self.size();
+        let rows = rows as usize;
+        // Match on the first screen row of the line; tabs are expanded on screen
+        let head: String = text.split('\t').next().unwrap_or_default().chars().take(cols as usize).collect();
+        let head = head.trim();
+        if head.is_empty() || rows == 0 {
+            return false;
+        }
+
+        // Older lines are out of reach (see `max_local_offset`)
+        self.history = None;
+        let previous = self.parser.screen().scrollback();
+        self.parser.set_scrollback(rows);
+        let max = self.parser.screen().scrollback();
+
+        // (distance from the bottom, column) of the closest occurrence
+        let mut best: Option<(usize, usize)> = None;
+        let mut offset = 0;
+        loop {
+            self.parser.set_scrollback(offset);
+            for (row, line) in self.parser.screen().rows(0, cols).enumerate() {
+                if let Some(index) = line.find(head) {
+                    let distance = offset + (rows - 1 - row);
+                    if best.is_none_or(|(d, _)| d.abs_diff(lines_from_end) > distance.abs_diff(lines_from_end)) {
+                        best = Some((distance, line[..index].chars().count()));
+                    }
+                }
+            }
+            if offset == max {
+                break;
+            }
+            offset = (offset + rows).min(max);
+        }
+
+        let Some((distance, col)) = best else {
+            self.parser.set_scrollback(previous);
+            return false;
+        };
+
+        // Center the line where the scrollback allows
+        let offset = distance.saturating_sub(rows - 1 - rows / 2).min(max);
+        self.parser.set_scrollback(offset);
+        self.scroll_offset = offset;
+        self.copy_mode_cursor = Some(SelectionPos::new(rows - 1 - (distance - offset), col));
+        self.selection = None;
+        true
+    }
+
     /// Exit copy mode and clear selection
     pub fn exit_copy_mode(&mut self) {
+        self.history = None;
+        self.search_highlight = None;
+        self.pending_match = None;
         self.copy_mode_cursor = None;
         self.selection = None;
         self.focus_state = FocusState::Focused;
@@ -524,7 +755,7 @@ impl Pane {
     pub fn extract_selection(&self) -> Option<String> {
         let selection = self.selection.as_ref()?;
         let (start, end) = selection.normalized();
-        let screen = self.parser.screen();
+        let screen = self.screen();
         let (_rows, cols) = self.size();
 
         let mut result = String::new();
@@ -583,13 +814,12 @@ impl Pane {
         }
     }
 
-    /// Copy selected text to system clipboard via OSC 52 and internal buffer
+    /// Copy selected text to system clipboard via OSC 52
+    ///
+    /// The caller also stores the text in a server paste buffer.
     pub fn yank_selection(&mut self) -> Option<String> {
         let text = self.extract_selection()?;
 
-        // Store in internal paste buffer (fallback)
-        self.paste_buffer = Some(text.clone());
-
         // Send OSC 52 to system clipboard
         // Format: ESC ] 52 ; c ; BASE64_TEXT BEL
         let encoded = general_purpose::STANDARD.encode(&text);
@@ -603,11 +833,6 @@ impl Pane {
         Some(text)
     }
 
-    /// Get text from internal paste buffer
-    pub fn paste_buffer(&self) -> Option<&str> {
-        self.paste_buffer.as_deref()
-    }
-
     /// Get visual mode indicator for status bar
     pub fn visual_mode_indicator(&self) -> Option<&'static str> {
         self.selection.as_ref().map(|s| match s.mode {
@@ -650,7 +875,7 @@ impl Pane {
 
     /// Select word at position (for double-click)
     pub fn select_word_at(&mut self, row: usize, col: usize) {
-        let screen = self.parser.screen();
+        let screen = self.screen();
         let (_, cols) = self.size();
 
         // Find word boundaries
@@ -770,7 +995,9 @@ impl PaneWidget {
             .border_style(border_style);
 
         // Add scroll indicator if scrolled
-        if pane.is_scrolled() {
+        if let Some(history) = &pane.history {
+            block = block.title_bottom(format!(" [{}↑ history] ", -history.top));
+        } else if pane.is_scrolled() {
             let scroll_indicator = format!(" [{}↑] ", pane.scroll_offset);
             block = block.title_bottom(scroll_indicator);
         }
@@ -831,6 +1058,9 @@ pub fn render_pane(pane: &Pane, area: Rect, buf: &mut Buffer, tick_count: u64) {
 
         pseudo_term.render(inner, buf);
 
+        // Render copy mode search matches
+        render_search_matches(pane, inner, buf);
+
         // Render selection highlighting
         render_selection(pane, inner, buf);
 
@@ -846,6 +1076,36 @@ pub fn render_pane(pane: &Pane, area: Rect, buf: &mut Buffer, tick_count: u64) {
     }
 }
 
+/// Render copy mode search matches over the terminal content
+fn render_search_matches(pane: &Pane, area: Rect, buf: &mut Buffer) {
+    let Some(regex) = pane.search_highlight() else {
+        return;
+    };
+
+    // Same colors as tmux's copy-mode-match-style and copy-mode-current-match-style
+    let match_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+    let current_style = Style::default().fg(Color::Black).bg(Color::Magenta);
+    let cursor = pane.copy_mode_cursor();
+
+    for (row, text) in pane.screen().rows(0, area.width).enumerate().take(area.height as usize) {
+        let y = area.y + row as u16;
+        for found in regex.find_iter(&text) {
+            let start = text[..found.start()].chars().count();
+            let len = found.as_str().chars().count();
+            let style = if cursor == Some(SelectionPos::new(row, start)) {
+                current_style
+            } else {
+                match_style
+            };
+            for col in start..(start + len).min(area.width as usize) {
+                if let Some(cell) = buf.cell_mut((area.x + col as u16, y)) {
+                    cell.set_style(style);
+                }
+            }
+        }
+    }
+}
+
 /// Render selection highlighting
 fn render_selection(pane: &Pane, area: Rect, buf: &mut Buffer) {
     let selection = match pane.selection() {
@@ -955,6 +1215,7 @@ fn render_agent_indicator(state: &AgentState, area: Rect, buf: &mut Buffer, tick
         AgentActivity::Generating => "[>]",
         AgentActivity::ToolUse => "[*]",
         AgentActivity::AwaitingConfirmation => "[?]",
+        AgentActivity::AwaitingPermission => "[!]",
         AgentActivity::Custom(_) => "[~]",
     };
 
@@ -964,6 +1225,7 @@ fn render_agent_indicator(state: &AgentState, area: Rect, buf: &mut Buffer, tick
         AgentActivity::Generating => Style::default().fg(Color::Green),
         AgentActivity::ToolUse => Style::default().fg(Color::Blue),
         AgentActivity::AwaitingConfirmation => Style::default().fg(Color::Magenta),
+        AgentActivity::AwaitingPermission => Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
         AgentActivity::Custom(_) => Style::default().fg(Color::Cyan),
     };
 
@@ -1444,6 +1706,119 @@ mod tests {
         assert_eq!(pane.focus_state(), FocusState::Focused);
     }
 
+    #[test]
+    fn test_pane_jump_to_line() {
+        let id = Uuid::new_v4();
+        let mut pane = Pane::new(id, 10, 40);
+        for i in 0..30 {
+            if i == 12 || i == 25 {
+                pane.process_output(format!("\x1b[31mERROR\x1b[0m build failed ({})\r\n", i).as_bytes());
+            } else {
+                pane.process_output(format!("line {}\r\n", i).as_bytes());
+            }
+        }
+
+        // Both occurrences match; the one nearer the server's position wins
+        assert!(pane.jump_to_line("build failed", 17));
+        let cursor = pane.copy_mode_cursor().unwrap();
+        assert_eq!(cursor.col, 6);
+        let rows: Vec<String> = pane.screen().rows(0, 40).collect();
+        assert_eq!(rows[cursor.row], "ERROR build failed (12)");
+        assert!(pane.is_scrolled());
+
+        assert!(pane.jump_to_line("build failed", 3));
+        let cursor = pane.copy_mode_cursor().unwrap();
+        let rows: Vec<String> = pane.screen().rows(0, 40).collect();
+        assert_eq!(rows[cursor.row], "ERROR build failed (25)");
+
+        // More than a screenful up is out of reach
+        let offset = pane.scroll_offset();
+        assert!(!pane.jump_to_line("line 0", 30));
+        assert_eq!(pane.scroll_offset(), offset);
+    }
+
+    #[test]
+    fn test_pane_shows_search_matches() {
+        let id = Uuid::new_v4();
+        let mut pane = Pane::new(id, 5, 20);
+        for i in 0..20 {
+            pane.process_output(format!("line {}\r\n", i).as_bytes());
+        }
+        pane.enter_copy_mode();
+        pane.set_search_highlight(Some(crate::ui::copy_search::compile("line (13|6)").unwrap()));
+
+        // Within the local scrollback ("line 16" is line 0)
+        assert_eq!(pane.show_search_match(-3, 0), None);
+        let cursor = pane.copy_mode_cursor().unwrap();
+        let rows: Vec<String> = pane.screen().rows(0, 20).collect();
+        assert_eq!(rows[cursor.row], "line 13");
+        assert_eq!(pane.copy_cursor_line(), Some((-3, 0)));
+
+        // Further back the lines come from the server; a wrapped line shifts
+        // the match, so the cursor is put on the nearest one on screen
+        assert_eq!(pane.show_search_match(-10, 0), Some((-12, -8)));
+        let lines: Vec<String> = ["line 4", "line 5", "line 6", &"z".repeat(30), "line 8"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(pane.show_history(-12, &lines, -16), None);
+        let cursor = pane.copy_mode_cursor().unwrap();
+        let rows: Vec<String> = pane.screen().rows(0, 20).collect();
+        assert_eq!(rows[cursor.row], "line 6");
+        assert_eq!(cursor.row, 1);
+
+        let area = Rect::new(0, 0, 22, 7);
+        let mut buf = Buffer::empty(area);
+        render_pane(&pane, area, &mut buf, 0);
+        let cell = buf.cell((2, 1 + cursor.row as u16)).unwrap();
+        assert_eq!(cell.bg, Color::Magenta);
+
+        pane.exit_copy_mode();
+        assert!(pane.search_highlight().is_none());
+    }
+
+    #[test]
+    fn test_pane_pages_server_history_in_copy_mode() {
+        let id = Uuid::new_v4();
+        let mut pane = Pane::new(id, 5, 20);
+        for i in 0..20 {
+            pane.process_output(format!("line {}\r\n", i).as_bytes());
+        }
+
+        // Outside copy mode scrolling stops at the local limit
+        assert_eq!(pane.scroll_up(100), None);
+        assert_eq!(pane.scroll_offset(), 5);
+        pane.scroll_to_bottom();
+
+        pane.enter_copy_mode();
+        assert_eq!(pane.scroll_up(5), None);
+        assert_eq!(pane.scroll_up(2), Some((-7, -3)));
+
+        let lines: Vec<String> = (0..5).map(|i| format!("old {}", i)).collect();
+        assert_eq!(pane.show_history(-7, &lines, -40), None);
+        let rows: Vec<String> = pane.screen().rows(0, 20).collect();
+        assert_eq!(rows[0], "old 0");
+        assert_eq!(rows[4], "old 4");
+
+        // Already at the oldest line
+        assert_eq!(pane.scroll_up(100), Some((-40, -36)));
+        assert_eq!(pane.show_history(-40, &lines, -40), None);
+        assert_eq!(pane.scroll_up(1), None);
+
+        // Scrolling back down returns to the local scrollback
+        assert_eq!(pane.scroll_down(10), Some((-30, -26)));
+        assert_eq!(pane.show_history(-30, &lines, -40), None);
+        assert_eq!(pane.scroll_down(27), None);
+        assert_eq!(pane.scroll_offset(), 3);
+        assert_ne!(pane.screen().rows(0, 20).next().unwrap(), "old 0");
+
+        // A range entirely before the start of history is retried from there
+        assert_eq!(pane.show_history(-500, &[], -40), Some((-40, -36)));
+        pane.exit_copy_mode();
+        assert_eq!(pane.show_history(-40, &lines, -40), None);
+        assert_eq!(pane.scroll_offset(), 3);
+    }
+
     #[test]
     fn test_pane_start_visual_selection() {
         let id = Uuid::new_v4();
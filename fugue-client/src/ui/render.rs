@@ -400,6 +400,7 @@ fn format_agent_indicator(agent_type: &str, activity: &fugue_protocol::AgentActi
         fugue_protocol::AgentActivity::Generating => format!(">[{}]", prefix),
         fugue_protocol::AgentActivity::ToolUse => format!("[{}*]", prefix),
         fugue_protocol::AgentActivity::AwaitingConfirmation => format!("[{}?]", prefix),
+        fugue_protocol::AgentActivity::AwaitingPermission => format!("[{}!]", prefix),
         fugue_protocol::AgentActivity::Custom(name) => format!("[{}:{}]", prefix, &name[..name.len().min(3)]),
     }
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Instant;
 
 use ratatui::layout::Rect;
@@ -6,10 +7,12 @@ use ratatui::widgets::ListState;
 use uuid::Uuid;
 
 use fugue_protocol::{
-    ClaudeActivity, MailPriority, PaneInfo, PaneState, SessionInfo, SplitDirection, WindowInfo,
+    ClaudeActivity, MailPriority, PaneInfo, PaneState, ScrollbackHit, SessionInfo,
+    SplitDirection, WindowInfo,
 };
 
 use crate::input::InputMode;
+use super::copy_search::CopySearch;
 use super::pane::{FocusState, PaneManager};
 use super::layout::LayoutManager;
 
@@ -47,6 +50,17 @@ pub struct MailboxMessage {
     pub summary: String,
 }
 
+/// Results of the last scrollback search, stepped through with n/N
+#[derive(Debug, Clone)]
+pub struct ScrollbackSearch {
+    pub query: String,
+    pub hits: Vec<ScrollbackHit>,
+    /// Whether the server had more matches than it returned
+    pub truncated: bool,
+    /// Index of the match being shown
+    pub current: usize,
+}
+
 /// Main state for the client
 pub struct ClientState {
     /// Current application state
@@ -85,10 +99,20 @@ pub struct ClientState {
     pub pane_manager: PaneManager,
     /// Layout manager for pane arrangement
     pub layout: Option<LayoutManager>,
+    /// Server-owned split tree for each window in the session
+    pub window_layouts: HashMap<Uuid, fugue_protocol::LayoutNode>,
     /// Pending split direction for next pane creation
     pub pending_split_direction: Option<SplitDirection>,
     /// Custom command to run in new sessions (from CLI args)
     pub session_command: Option<String>,
+    /// Destination file for an in-flight `export-session`
+    pub pending_export: Option<PathBuf>,
+    /// Destination file for an in-flight `save-buffer`, and whether to append
+    pub pending_buffer_save: Option<(PathBuf, bool)>,
+    /// Last scrollback search
+    pub search: Option<ScrollbackSearch>,
+    /// Copy mode search (/ or ?) in the active pane
+    pub copy_search: Option<CopySearch>,
     /// Previous input mode for tracking mode transitions (FEAT-056)
     /// Used to detect when user exits command mode
     pub previous_input_mode: InputMode,
@@ -129,8 +153,13 @@ impl ClientState {
             status_message: None,
             pane_manager: PaneManager::new(),
             layout: None,
+            window_layouts: HashMap::new(),
             pending_split_direction: None,
             session_command: None,
+            pending_export: None,
+            pending_buffer_save: None,
+            search: None,
+            copy_search: None,
             previous_input_mode: InputMode::Normal,
             last_beads_request_tick: 0,
             is_beads_tracked: false,
@@ -308,6 +308,10 @@ impl StatusBar {
                 format!("[?] {} Confirm", prefix),
                 Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
             ),
+            AgentActivity::AwaitingPermission => (
+                format!("[!] {} Permission", prefix),
+                Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            ),
             AgentActivity::Custom(name) => (
                 format!("[~] {} {}", prefix, name),
                 Style::default().fg(Color::Cyan),
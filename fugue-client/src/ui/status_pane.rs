@@ -8,7 +8,7 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Widget};
 
-use fugue_protocol::{AgentActivity, PaneState};
+use fugue_protocol::{AgentActivity, AgentUsage, PaneState};
 
 use super::pane::Pane;
 use super::state::ClientState;
@@ -73,6 +73,7 @@ fn render_agent_list(state: &ClientState, area: Rect, buf: &mut Buffer) {
                 AgentActivity::Generating => ("Generating", Style::default().fg(Color::Green)),
                 AgentActivity::ToolUse => ("Tool Use", Style::default().fg(Color::Yellow)),
                 AgentActivity::AwaitingConfirmation => ("Waiting Input", Style::default().fg(Color::Magenta)),
+                AgentActivity::AwaitingPermission => ("Permission", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
                 AgentActivity::Custom(ref s) => (s.as_str(), Style::default().fg(Color::White)),
             },
             PaneState::Exited { code } => {
@@ -85,11 +86,20 @@ fn render_agent_list(state: &ClientState, area: Rect, buf: &mut Buffer) {
             PaneState::Status => continue, 
         };
 
-        let line = Line::from(vec![
+        let mut spans = vec![
             Span::styled(format!("{:<10}", name), Style::default().fg(Color::White)),
             Span::raw(" "),
             Span::styled(status_str, style),
-        ]);
+        ];
+        if let PaneState::Agent(agent_state) = &pane_info.state {
+            if let Some(usage) = agent_state.usage() {
+                spans.push(Span::styled(
+                    format!("  {}", format_usage(&usage)),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+        }
+        let line = Line::from(spans);
 
         items.push(ListItem::new(line));
     }
@@ -104,6 +114,17 @@ fn render_agent_list(state: &ClientState, area: Rect, buf: &mut Buffer) {
     }
 }
 
+/// Compact usage summary, e.g. "12.3k tok ~$0.42" ("~" marks an estimated cost)
+fn format_usage(usage: &AgentUsage) -> String {
+    let tokens = match usage.tokens {
+        t if t >= 1_000_000 => format!("{:.1}M", t as f64 / 1_000_000.0),
+        t if t >= 1_000 => format!("{:.1}k", t as f64 / 1_000.0),
+        t => t.to_string(),
+    };
+    let approx = if usage.estimated { "~" } else { "" };
+    format!("{} tok {}${:.2}", tokens, approx, usage.cost_usd)
+}
+
 fn render_activity_feed(state: &ClientState, area: Rect, buf: &mut Buffer) {
     let block = Block::default()
         .title("Activity Feed");
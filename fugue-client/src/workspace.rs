@@ -0,0 +1,62 @@
+//! `fugue up` / `fugue down` - declarative workspace files
+
+use std::path::Path;
+
+use fugue_protocol::{ClientMessage, ServerMessage};
+use fugue_utils::{CcmuxError, Result};
+
+use crate::oneshot::OneShot;
+
+fn read_workspace(file: &Path) -> Result<String> {
+    std::fs::read_to_string(file).map_err(|source| CcmuxError::FileRead {
+        path: file.to_path_buf(),
+        source,
+    })
+}
+
+/// Create the workspace's sessions that aren't running yet
+pub async fn up(client: &mut OneShot, file: &Path) -> Result<()> {
+    let document = read_workspace(file)?;
+    // Relative paths in the file are relative to the file, not to the server
+    let base_dir = std::fs::canonicalize(file)?
+        .parent()
+        .map(|dir| dir.to_string_lossy().into_owned());
+
+    let reply = client
+        .request(ClientMessage::WorkspaceUp { document, base_dir }, |msg| {
+            matches!(msg, ServerMessage::WorkspaceStarted { .. })
+        })
+        .await?;
+    if let ServerMessage::WorkspaceStarted { created, existing } = reply {
+        for session in created {
+            println!(
+                "Created session '{}' ({} window(s))",
+                session.name, session.window_count
+            );
+        }
+        for name in existing {
+            println!("Session '{}' is already running", name);
+        }
+    }
+    Ok(())
+}
+
+/// Destroy the workspace's sessions
+pub async fn down(client: &mut OneShot, file: &Path) -> Result<()> {
+    let document = read_workspace(file)?;
+
+    let reply = client
+        .request(ClientMessage::WorkspaceDown { document }, |msg| {
+            matches!(msg, ServerMessage::WorkspaceStopped { .. })
+        })
+        .await?;
+    if let ServerMessage::WorkspaceStopped { destroyed, missing } = reply {
+        for name in destroyed {
+            println!("Destroyed session '{}'", name);
+        }
+        for name in missing {
+            println!("Session '{}' is not running", name);
+        }
+    }
+    Ok(())
+}
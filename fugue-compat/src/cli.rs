@@ -1,6 +1,9 @@
 //! CLI argument parsing for tmux-compatible commands
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
+use fugue_utils::tls::TlsClientOptions;
 
 /// tmux-compatible CLI for fugue
 #[derive(Parser, Debug)]
@@ -8,18 +11,46 @@ use clap::{Parser, Subcommand};
 #[command(about = "tmux-compatible CLI wrapper for fugue")]
 #[command(version)]
 pub struct Cli {
-    /// Connection address (tcp://host:port or unix://path)
+    /// Connection address (tcp://host:port, tls://host:port or unix://path)
     ///
-    /// Specifies the server address to connect to. Supports both TCP and Unix
+    /// Specifies the server address to connect to. Supports TCP, TLS and Unix
     /// sockets via URL format. Overrides default Unix socket if provided.
-    /// Example: tcp://127.0.0.1:3000 or unix:///tmp/fugue.sock
+    /// Example: tls://build-box:3000 or unix:///tmp/fugue.sock
     #[arg(long, env = "FUGUE_ADDR")]
     pub addr: Option<String>,
 
+    /// CA certificate (PEM) used to verify the server for tls:// addresses
+    #[arg(long, env = "FUGUE_TLS_CA")]
+    pub tls_ca: Option<PathBuf>,
+
+    /// Client certificate (PEM) for servers that require mutual TLS
+    #[arg(long, env = "FUGUE_TLS_CERT", requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// Client private key (PEM) for servers that require mutual TLS
+    #[arg(long, env = "FUGUE_TLS_KEY", requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Pre-shared token for servers with tls.auth_token configured
+    #[arg(long, env = "FUGUE_AUTH_TOKEN", hide_env_values = true)]
+    pub auth_token: Option<String>,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+impl Cli {
+    /// TLS and auth options for remote connections
+    pub fn tls_options(&self) -> TlsClientOptions {
+        TlsClientOptions {
+            ca_file: self.tls_ca.clone(),
+            cert_file: self.tls_cert.clone(),
+            key_file: self.tls_key.clone(),
+            auth_token: self.auth_token.clone(),
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Create a new session
@@ -57,7 +88,7 @@ pub enum Command {
     /// List all sessions
     #[command(name = "list-sessions")]
     ListSessions {
-        /// Format string (limited support)
+        /// Format string (e.g., "#{session_name}: #{?session_attached,attached,detached}")
         #[arg(short = 'F', long)]
         format: Option<String>,
     },
@@ -105,9 +136,13 @@ pub enum Command {
         #[arg(short = 'p', long)]
         print: bool,
 
-        /// Start line (negative = from start of scrollback)
-        #[arg(short = 'S', long)]
-        start_line: Option<i32>,
+        /// Start line (0 = top of the pane, negative = history, "-" = start of history)
+        #[arg(short = 'S', long, allow_hyphen_values = true)]
+        start_line: Option<String>,
+
+        /// End line (same numbering, "-" = bottom of the pane)
+        #[arg(short = 'E', long, allow_hyphen_values = true)]
+        end_line: Option<String>,
 
         /// Number of lines to capture
         #[arg(short = 'N', long)]
@@ -144,6 +179,10 @@ pub enum Command {
         /// Target session
         #[arg(short = 't', long = "target")]
         target: Option<String>,
+
+        /// Format string (e.g., "#{window_index}: #{window_name}#{?window_active, (active),}")
+        #[arg(short = 'F', long)]
+        format: Option<String>,
     },
 
     /// Split a window into panes
@@ -213,5 +252,131 @@ pub enum Command {
         /// Show all panes in all sessions
         #[arg(short = 'a', long)]
         all: bool,
+
+        /// Format string (e.g., "#{pane_id} #{pane_current_path}")
+        #[arg(short = 'F', long)]
+        format: Option<String>,
+    },
+
+    /// Print a message, expanding format variables for the target pane
+    #[command(name = "display-message", alias = "display")]
+    DisplayMessage {
+        /// Target pane
+        #[arg(short = 't', long = "target")]
+        target: Option<String>,
+
+        /// Print to stdout (always the case; there is no status line to show it in)
+        #[arg(short = 'p', long)]
+        print: bool,
+
+        /// Message format (e.g., "#{session_name}:#{window_index}.#{pane_index}")
+        #[arg(allow_hyphen_values = true)]
+        message: Option<String>,
+    },
+
+    /// Export a session to a portable file
+    #[command(name = "export-session")]
+    ExportSession {
+        /// Target session
+        #[arg(short = 't', long = "target")]
+        target: String,
+
+        /// Output file (stdout if omitted)
+        #[arg(short = 'o', long)]
+        output: Option<String>,
+
+        /// Document format (default: from the output extension, else json)
+        #[arg(short = 'f', long, value_parser = ["json", "toml"])]
+        format: Option<String>,
+
+        /// Include pane scrollback
+        #[arg(short = 'S', long)]
+        scrollback: bool,
+    },
+
+    /// Recreate a session from an exported file
+    #[command(name = "import-session")]
+    ImportSession {
+        /// Exported file ("-" for stdin)
+        file: String,
+
+        /// Name for the new session (default: the exported name)
+        #[arg(short = 's', long)]
+        name: Option<String>,
+    },
+
+    /// List paste buffers, most recent first
+    #[command(name = "list-buffers", alias = "lsb")]
+    ListBuffers {
+        /// Format string (e.g., "#{buffer_name}: #{buffer_size}")
+        #[arg(short = 'F', long)]
+        format: Option<String>,
+    },
+
+    /// Print a paste buffer
+    #[command(name = "show-buffer", alias = "showb")]
+    ShowBuffer {
+        /// Buffer name (default: the most recent buffer)
+        #[arg(short = 'b', long = "buffer")]
+        buffer: Option<String>,
+    },
+
+    /// Set the contents of a paste buffer
+    #[command(name = "set-buffer", alias = "setb")]
+    SetBuffer {
+        /// Buffer name (default: a new automatically named buffer)
+        #[arg(short = 'b', long = "buffer")]
+        buffer: Option<String>,
+
+        /// Append to the buffer
+        #[arg(short = 'a', long)]
+        append: bool,
+
+        /// Buffer contents
+        #[arg(allow_hyphen_values = true)]
+        data: String,
+    },
+
+    /// Paste a buffer into a pane
+    #[command(name = "paste-buffer", alias = "pasteb")]
+    PasteBuffer {
+        /// Buffer name (default: the most recent buffer)
+        #[arg(short = 'b', long = "buffer")]
+        buffer: Option<String>,
+
+        /// Target pane
+        #[arg(short = 't', long = "target")]
+        target: Option<String>,
+
+        /// Delete the buffer after pasting
+        #[arg(short = 'd', long)]
+        delete: bool,
+
+        /// Use bracketed paste if the application asked for it
+        #[arg(short = 'p', long)]
+        bracketed: bool,
+    },
+
+    /// Write a paste buffer to a file
+    #[command(name = "save-buffer", alias = "saveb")]
+    SaveBuffer {
+        /// Buffer name (default: the most recent buffer)
+        #[arg(short = 'b', long = "buffer")]
+        buffer: Option<String>,
+
+        /// Append to the file
+        #[arg(short = 'a', long)]
+        append: bool,
+
+        /// Output file ("-" for stdout)
+        path: String,
+    },
+
+    /// Delete a paste buffer
+    #[command(name = "delete-buffer", alias = "deleteb")]
+    DeleteBuffer {
+        /// Buffer name (default: the most recent buffer)
+        #[arg(short = 'b', long = "buffer")]
+        buffer: Option<String>,
     },
 }
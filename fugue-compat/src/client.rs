@@ -10,11 +10,13 @@ use futures::{SinkExt, StreamExt};
 use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::{TcpStream, UnixStream};
 use tokio::time::timeout;
+use tokio_rustls::TlsConnector;
 use tokio_util::codec::Framed;
 use url::Url;
 use uuid::Uuid;
 
 use fugue_protocol::{ClientCodec, ClientMessage, ClientType, ServerMessage, PROTOCOL_VERSION};
+use fugue_utils::tls::{self, TlsClientOptions, TLS_SCHEME};
 use fugue_utils::{socket_path, CcmuxError, Result};
 
 /// Timeout for server responses
@@ -32,16 +34,17 @@ pub struct Client {
 
 impl Client {
     /// Connect to the fugue server using default or provided address
-    pub async fn connect(addr: Option<String>) -> Result<Self> {
+    pub async fn connect(addr: Option<String>, tls_options: &TlsClientOptions) -> Result<Self> {
         let addr = addr.unwrap_or_else(|| {
             format!("unix://{}", socket_path().to_string_lossy())
         });
-        Self::connect_to_addr(&addr).await
+        Self::connect_to_addr(&addr, tls_options).await
     }
 
     /// Connect to a specific address URL
-    pub async fn connect_to_addr(addr: &str) -> Result<Self> {
-        let stream: Box<dyn StreamTrait> = if addr.starts_with("tcp://") {
+    pub async fn connect_to_addr(addr: &str, tls_options: &TlsClientOptions) -> Result<Self> {
+        let is_tls = addr.starts_with(TLS_SCHEME);
+        let stream: Box<dyn StreamTrait> = if addr.starts_with("tcp://") || is_tls {
             let url = Url::parse(addr).map_err(|e| {
                 CcmuxError::Connection(format!("Invalid TCP URL '{}': {}", addr, e))
             })?;
@@ -57,8 +60,19 @@ impl Client {
             let tcp_stream = TcpStream::connect(&tcp_addr).await.map_err(|e| {
                 CcmuxError::Connection(format!("Failed to connect to {}: {}", tcp_addr, e))
             })?;
-            
-            Box::new(tcp_stream)
+
+            if is_tls {
+                let connector = TlsConnector::from(tls_options.client_config()?);
+                let tls_stream = connector
+                    .connect(tls::server_name(host)?, tcp_stream)
+                    .await
+                    .map_err(|e| {
+                        CcmuxError::Connection(format!("TLS handshake with {} failed: {}", host, e))
+                    })?;
+                Box::new(tls_stream)
+            } else {
+                Box::new(tcp_stream)
+            }
         } else {
             // Assume Unix socket
             let path_str = if addr.starts_with("unix://") {
@@ -88,12 +102,37 @@ impl Client {
 
         let mut client = Self { framed, client_id };
 
+        // Present the pre-shared token before Connect
+        if let Some(ref token) = tls_options.auth_token {
+            client.authenticate(token).await?;
+        }
+
         // Send initial connect message
         client.handshake().await?;
 
         Ok(client)
     }
 
+    /// Send the auth token and wait for the server to accept it
+    async fn authenticate(&mut self, token: &str) -> Result<()> {
+        self.send(ClientMessage::Authenticate {
+            token: token.to_string(),
+        })
+        .await?;
+
+        match self.recv().await? {
+            ServerMessage::Authenticated => Ok(()),
+            ServerMessage::Error { message, .. } => Err(CcmuxError::Connection(format!(
+                "Authentication failed: {}",
+                message
+            ))),
+            other => Err(CcmuxError::Protocol(format!(
+                "Unexpected response to Authenticate: {}",
+                other.type_name()
+            ))),
+        }
+    }
+
     /// Perform initial handshake
     async fn handshake(&mut self) -> Result<()> {
         let connect_msg = ClientMessage::Connect {
@@ -0,0 +1,166 @@
+//! Paste buffer commands
+
+use std::io::Write;
+
+use fugue_protocol::{ClientMessage, ServerMessage};
+use fugue_utils::{CcmuxError, Result};
+
+use super::connect;
+use crate::format::FormatContext;
+use super::pane::{find_pane, get_first_pane};
+
+/// List paste buffers
+pub async fn list_buffers(format: Option<&str>) -> Result<i32> {
+    let mut client = connect().await?;
+
+    match client.request(ClientMessage::ListBuffers).await? {
+        ServerMessage::BufferList { buffers } => {
+            for buffer in buffers {
+                if let Some(fmt) = format {
+                    println!("{}", FormatContext::new().with_buffer(&buffer).expand(fmt));
+                } else {
+                    // Same as tmux: name: size bytes: "sample"
+                    println!("{}: {} bytes: \"{}\"", buffer.name, buffer.size, buffer.sample);
+                }
+            }
+            Ok(0)
+        }
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Print a paste buffer to stdout
+pub async fn show_buffer(buffer: Option<String>) -> Result<i32> {
+    save_buffer(buffer, false, "-").await
+}
+
+/// Set or append to a paste buffer
+pub async fn set_buffer(buffer: Option<String>, append: bool, data: String) -> Result<i32> {
+    let mut client = connect().await?;
+
+    let msg = ClientMessage::SetBuffer {
+        name: buffer,
+        data,
+        append,
+    };
+
+    match client.request(msg).await? {
+        ServerMessage::BufferSet { name } => {
+            tracing::debug!("Set buffer: {}", name);
+            Ok(0)
+        }
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Paste a buffer into a pane
+pub async fn paste_buffer(
+    buffer: Option<String>,
+    target: Option<&str>,
+    delete: bool,
+    bracketed: bool,
+) -> Result<i32> {
+    let mut client = connect().await?;
+
+    let pane_id = match target {
+        Some(t) => match find_pane(&mut client, t).await? {
+            Some(id) => id,
+            None => {
+                eprintln!("pane not found: {}", t);
+                return Ok(1);
+            }
+        },
+        None => match get_first_pane(&mut client).await? {
+            Some(id) => id,
+            None => {
+                eprintln!("no panes available");
+                return Ok(1);
+            }
+        },
+    };
+
+    let msg = ClientMessage::PasteBuffer {
+        name: buffer,
+        pane_id,
+        delete,
+        bracketed,
+    };
+
+    match client.request(msg).await? {
+        ServerMessage::BufferPasted { .. } => Ok(0),
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Write a paste buffer to a file, or stdout for "-"
+pub async fn save_buffer(buffer: Option<String>, append: bool, path: &str) -> Result<i32> {
+    let mut client = connect().await?;
+
+    match client.request(ClientMessage::GetBuffer { name: buffer }).await? {
+        ServerMessage::BufferContent { data, .. } => {
+            if path == "-" {
+                print!("{}", data);
+                std::io::stdout().flush()?;
+            } else {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .write(true)
+                    .append(append)
+                    .truncate(!append)
+                    .open(path)
+                    .and_then(|mut file| file.write_all(data.as_bytes()))
+                    .map_err(|e| CcmuxError::FileWrite {
+                        path: path.into(),
+                        source: e,
+                    })?;
+            }
+            Ok(0)
+        }
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Delete a paste buffer
+pub async fn delete_buffer(buffer: Option<String>) -> Result<i32> {
+    let mut client = connect().await?;
+
+    match client.request(ClientMessage::DeleteBuffer { name: buffer }).await? {
+        ServerMessage::BufferDeleted { .. } => Ok(0),
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
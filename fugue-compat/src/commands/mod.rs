@@ -1,21 +1,31 @@
 //! Command implementations
 
+mod buffer;
 mod pane;
 mod session;
 mod window;
 
 use crate::cli::Command;
 use crate::client::Client;
+use fugue_protocol::{ClientMessage, ServerMessage, SessionInfo};
+use fugue_utils::tls::TlsClientOptions;
 use fugue_utils::Result;
+use std::collections::HashMap;
 use std::sync::OnceLock;
 
-/// Global storage for server address
+/// Global storage for server address and TLS options
 static SERVER_ADDR: OnceLock<Option<String>> = OnceLock::new();
+static TLS_OPTIONS: OnceLock<TlsClientOptions> = OnceLock::new();
 
 /// Execute a CLI command
-pub async fn execute(command: Command, addr: Option<String>) -> Result<i32> {
-    // Store address for use by connect()
+pub async fn execute(
+    command: Command,
+    addr: Option<String>,
+    tls_options: TlsClientOptions,
+) -> Result<i32> {
+    // Store address and TLS options for use by connect()
     SERVER_ADDR.set(addr).ok();
+    TLS_OPTIONS.set(tls_options).ok();
 
     match command {
         // Session commands
@@ -46,8 +56,18 @@ pub async fn execute(command: Command, addr: Option<String>) -> Result<i32> {
             target,
             print,
             start_line,
+            end_line,
             line_count,
-        } => pane::capture_pane(target.as_deref(), print, start_line, line_count).await,
+        } => {
+            pane::capture_pane(
+                target.as_deref(),
+                print,
+                start_line.as_deref(),
+                end_line.as_deref(),
+                line_count,
+            )
+            .await
+        }
 
         Command::SplitWindow {
             target,
@@ -59,7 +79,17 @@ pub async fn execute(command: Command, addr: Option<String>) -> Result<i32> {
 
         Command::KillPane { target } => pane::kill_pane(target.as_deref()).await,
 
-        Command::ListPanes { target, all } => pane::list_panes(target.as_deref(), all).await,
+        Command::ListPanes {
+            target,
+            all,
+            format,
+        } => pane::list_panes(target.as_deref(), all, format.as_deref()).await,
+
+        Command::DisplayMessage {
+            target,
+            print: _,
+            message,
+        } => pane::display_message(target.as_deref(), message.as_deref()).await,
 
         // Window commands
         Command::NewWindow {
@@ -70,7 +100,9 @@ pub async fn execute(command: Command, addr: Option<String>) -> Result<i32> {
 
         Command::KillWindow { target } => window::kill_window(&target).await,
 
-        Command::ListWindows { target } => window::list_windows(target.as_deref()).await,
+        Command::ListWindows { target, format } => {
+            window::list_windows(target.as_deref(), format.as_deref()).await
+        }
 
         // Environment commands
         Command::SetEnvironment {
@@ -82,13 +114,61 @@ pub async fn execute(command: Command, addr: Option<String>) -> Result<i32> {
         Command::ShowEnvironment { target, name } => {
             session::show_environment(target.as_deref(), name).await
         }
+
+        // Export/import commands
+        Command::ExportSession {
+            target,
+            output,
+            format,
+            scrollback,
+        } => session::export_session(&target, output, format.as_deref(), scrollback).await,
+
+        Command::ImportSession { file, name } => session::import_session(&file, name).await,
+
+        // Paste buffer commands
+        Command::ListBuffers { format } => buffer::list_buffers(format.as_deref()).await,
+
+        Command::ShowBuffer { buffer } => buffer::show_buffer(buffer).await,
+
+        Command::SetBuffer {
+            buffer,
+            append,
+            data,
+        } => buffer::set_buffer(buffer, append, data).await,
+
+        Command::PasteBuffer {
+            buffer,
+            target,
+            delete,
+            bracketed,
+        } => buffer::paste_buffer(buffer, target.as_deref(), delete, bracketed).await,
+
+        Command::SaveBuffer {
+            buffer,
+            append,
+            path,
+        } => buffer::save_buffer(buffer, append, &path).await,
+
+        Command::DeleteBuffer { buffer } => buffer::delete_buffer(buffer).await,
     }
 }
 
 /// Helper to connect to the server
 async fn connect() -> Result<Client> {
     let addr = SERVER_ADDR.get().cloned().flatten();
-    Client::connect(addr).await
+    let tls_options = TLS_OPTIONS.get().cloned().unwrap_or_default();
+    Client::connect(addr, &tls_options).await
+}
+
+/// Fetch the sessions keyed by name, for their format variables
+async fn sessions_by_name(client: &mut Client) -> Result<HashMap<String, SessionInfo>> {
+    match client.request(ClientMessage::ListSessions).await? {
+        ServerMessage::SessionList { sessions } => Ok(sessions
+            .into_iter()
+            .map(|session| (session.name.clone(), session))
+            .collect()),
+        _ => Ok(HashMap::new()),
+    }
 }
 
 /// Parse a target string which may be a session name, UUID, or session:window:pane
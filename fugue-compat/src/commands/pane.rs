@@ -1,10 +1,16 @@
 //! Pane management commands
 
-use fugue_protocol::{ClientMessage, ServerMessage, SplitDirection};
+use std::collections::HashMap;
+
+use fugue_protocol::{ClientMessage, PaneListEntry, ServerMessage, SessionInfo, SplitDirection};
 use fugue_utils::Result;
 use uuid::Uuid;
 
-use super::{connect, parse_target, Target};
+use super::{connect, parse_target, sessions_by_name, Target};
+use crate::format::FormatContext;
+
+/// Message shown by display-message without a format, like tmux's minus the clock
+const DEFAULT_MESSAGE: &str = "[#{session_name}] #{window_index}:#{window_name}, current pane #{pane_index}";
 
 /// Send keys to a pane
 pub async fn send_keys(target: Option<&str>, _literal: bool, keys: &[String]) -> Result<i32> {
@@ -44,7 +50,8 @@ pub async fn send_keys(target: Option<&str>, _literal: bool, keys: &[String]) ->
 pub async fn capture_pane(
     target: Option<&str>,
     print: bool,
-    _start_line: Option<i32>,
+    start_line: Option<&str>,
+    end_line: Option<&str>,
     line_count: Option<usize>,
 ) -> Result<i32> {
     let mut client = connect().await?;
@@ -70,6 +77,34 @@ pub async fn capture_pane(
         }
     };
 
+    if start_line.is_some() || end_line.is_some() {
+        let (start, end) = match (parse_line(start_line), parse_line(end_line)) {
+            (Ok(start), Ok(end)) => (start, end),
+            (Err(value), _) | (_, Err(value)) => {
+                eprintln!("invalid line number: {}", value);
+                return Ok(1);
+            }
+        };
+        return match client.request(ClientMessage::CapturePane { pane_id, start, end }).await? {
+            ServerMessage::PaneCaptured { lines, .. } => {
+                if print {
+                    for line in lines {
+                        println!("{}", line);
+                    }
+                }
+                Ok(0)
+            }
+            ServerMessage::Error { message, .. } => {
+                eprintln!("error: {}", message);
+                Ok(1)
+            }
+            other => {
+                eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+                Ok(1)
+            }
+        };
+    }
+
     let lines = line_count.unwrap_or(100).min(1000);
 
     let msg = ClientMessage::ReadPane { pane_id, lines };
@@ -92,6 +127,14 @@ pub async fn capture_pane(
     }
 }
 
+/// Parse a `-S`/`-E` line number; "-" means the start or end of the pane
+fn parse_line(value: Option<&str>) -> std::result::Result<Option<i64>, &str> {
+    match value {
+        None | Some("-") => Ok(None),
+        Some(value) => value.parse().map(Some).map_err(|_| value),
+    }
+}
+
 /// Split a window/pane
 pub async fn split_window(
     target: Option<&str>,
@@ -199,16 +242,25 @@ pub async fn kill_pane(target: Option<&str>) -> Result<i32> {
 }
 
 /// List all panes
-pub async fn list_panes(target: Option<&str>, all: bool) -> Result<i32> {
+pub async fn list_panes(target: Option<&str>, all: bool, format: Option<&str>) -> Result<i32> {
     let mut client = connect().await?;
 
     let session_filter = if all { None } else { target.map(|s| s.to_string()) };
+    let sessions = match format {
+        Some(_) => sessions_by_name(&mut client).await?,
+        None => HashMap::new(),
+    };
 
     let msg = ClientMessage::ListAllPanes { session_filter };
 
     match client.request(msg).await? {
         ServerMessage::AllPanesList { panes } => {
-            for pane in panes {
+            for pane in &panes {
+                if let Some(fmt) = format {
+                    println!("{}", pane_context(pane, &panes, &sessions).expand(fmt));
+                    continue;
+                }
+
                 // Format: session:window.pane: [WxH] [title] [cwd]
                 println!(
                     "{}:{}.{}: [{}x{}] {}",
@@ -217,7 +269,7 @@ pub async fn list_panes(target: Option<&str>, all: bool) -> Result<i32> {
                     pane.pane_index,
                     pane.cols,
                     pane.rows,
-                    pane.cwd.unwrap_or_default()
+                    pane.cwd.as_deref().unwrap_or_default()
                 );
             }
             Ok(0)
@@ -233,8 +285,70 @@ pub async fn list_panes(target: Option<&str>, all: bool) -> Result<i32> {
     }
 }
 
+/// Print a message with format variables for the target pane
+pub async fn display_message(target: Option<&str>, message: Option<&str>) -> Result<i32> {
+    let mut client = connect().await?;
+
+    let pane_id = match target {
+        Some(t) => match find_pane(&mut client, t).await? {
+            Some(id) => id,
+            None => {
+                eprintln!("pane not found: {}", t);
+                return Ok(1);
+            }
+        },
+        None => match get_first_pane(&mut client).await? {
+            Some(id) => id,
+            None => {
+                eprintln!("no panes available");
+                return Ok(1);
+            }
+        },
+    };
+
+    let sessions = sessions_by_name(&mut client).await?;
+    let msg = ClientMessage::ListAllPanes {
+        session_filter: None,
+    };
+
+    match client.request(msg).await? {
+        ServerMessage::AllPanesList { panes } => match panes.iter().find(|p| p.id == pane_id) {
+            Some(pane) => {
+                let fmt = message.unwrap_or(DEFAULT_MESSAGE);
+                println!("{}", pane_context(pane, &panes, &sessions).expand(fmt));
+                Ok(0)
+            }
+            None => {
+                eprintln!("pane not found: {}", pane_id);
+                Ok(1)
+            }
+        },
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Format variables for a pane, its window and its session
+fn pane_context(
+    pane: &PaneListEntry,
+    listing: &[PaneListEntry],
+    sessions: &HashMap<String, SessionInfo>,
+) -> FormatContext {
+    sessions
+        .get(&pane.session_name)
+        .map(|session| FormatContext::new().with_session(session))
+        .unwrap_or_default()
+        .with_pane(pane, listing)
+}
+
 /// Helper to find a pane by target string
-async fn find_pane(
+pub(super) async fn find_pane(
     client: &mut super::super::client::Client,
     target: &str,
 ) -> Result<Option<Uuid>> {
@@ -301,7 +415,7 @@ async fn find_pane(
 }
 
 /// Get the first available pane
-async fn get_first_pane(client: &mut super::super::client::Client) -> Result<Option<Uuid>> {
+pub(super) async fn get_first_pane(client: &mut super::super::client::Client) -> Result<Option<Uuid>> {
     let msg = ClientMessage::ListAllPanes {
         session_filter: None,
     };
@@ -1,9 +1,12 @@
 //! Session management commands
 
-use fugue_protocol::{ClientMessage, ErrorCode, ServerMessage};
-use fugue_utils::Result;
+use std::io::Read;
+
+use fugue_protocol::{ClientMessage, ErrorCode, ExportFormat, ServerMessage};
+use fugue_utils::{CcmuxError, Result};
 
 use super::{connect, parse_target, Target};
+use crate::format::FormatContext;
 
 /// Create a new session
 pub async fn new_session(
@@ -101,6 +104,11 @@ pub async fn list_sessions(format: Option<&str>) -> Result<i32> {
             }
 
             for session in sessions {
+                if let Some(fmt) = format {
+                    println!("{}", FormatContext::new().with_session(&session).expand(fmt));
+                    continue;
+                }
+
                 // Default format similar to tmux: name: windows (created date) (attached)
                 let attached = if session.attached_clients > 0 {
                     "(attached)"
@@ -115,29 +123,15 @@ pub async fn list_sessions(format: Option<&str>) -> Result<i32> {
                     tags.into_iter().cloned().collect::<Vec<_>>().join(",")
                 };
 
-                if let Some(fmt) = format {
-                    // Support basic format strings
-                    let output = fmt
-                        .replace("#{session_name}", &session.name)
-                        .replace("#{session_id}", &session.id.to_string())
-                        .replace("#{session_windows}", &session.window_count.to_string())
-                        .replace(
-                            "#{session_attached}",
-                            &session.attached_clients.to_string(),
-                        )
-                        .replace("#{session_tags}", &tags_str);
-                    println!("{}", output);
+                let tags_display = if tags_str.is_empty() {
+                    String::new()
                 } else {
-                    let tags_display = if tags_str.is_empty() {
-                        String::new()
-                    } else {
-                        format!(" [{}]", tags_str)
-                    };
-                    println!(
-                        "{}: {} windows {}{} {}",
-                        session.name, session.window_count, attached, tags_display, session.id
-                    );
-                }
+                    format!(" [{}]", tags_str)
+                };
+                println!(
+                    "{}: {} windows {}{} {}",
+                    session.name, session.window_count, attached, tags_display, session.id
+                );
             }
             Ok(0)
         }
@@ -230,6 +224,85 @@ pub async fn show_environment(target: Option<&str>, name: Option<String>) -> Res
     }
 }
 
+/// Export a session as a portable document
+pub async fn export_session(
+    target: &str,
+    output: Option<String>,
+    format: Option<&str>,
+    scrollback: bool,
+) -> Result<i32> {
+    let mut client = connect().await?;
+
+    let format = match format {
+        Some("toml") => ExportFormat::Toml,
+        Some(_) => ExportFormat::Json,
+        None if output.as_deref().is_some_and(|path| path.ends_with(".toml")) => {
+            ExportFormat::Toml
+        }
+        None => ExportFormat::Json,
+    };
+
+    let msg = ClientMessage::ExportSession {
+        session_filter: target.strip_prefix('=').unwrap_or(target).to_string(),
+        format,
+        include_scrollback: scrollback,
+    };
+
+    match client.request(msg).await? {
+        ServerMessage::SessionExported { document } => {
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, document).map_err(|e| CcmuxError::FileWrite {
+                        path: path.into(),
+                        source: e,
+                    })?
+                }
+                None => print!("{}", document),
+            }
+            Ok(0)
+        }
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
+/// Recreate a session from an exported document
+pub async fn import_session(file: &str, name: Option<String>) -> Result<i32> {
+    let document = if file == "-" {
+        let mut document = String::new();
+        std::io::stdin().read_to_string(&mut document)?;
+        document
+    } else {
+        std::fs::read_to_string(file).map_err(|e| CcmuxError::FileRead {
+            path: file.into(),
+            source: e,
+        })?
+    };
+
+    let mut client = connect().await?;
+
+    match client.request(ClientMessage::ImportSession { document, name }).await? {
+        ServerMessage::SessionImported { session } => {
+            println!("{}: {} (imported)", session.name, session.id);
+            Ok(0)
+        }
+        ServerMessage::Error { message, .. } => {
+            eprintln!("error: {}", message);
+            Ok(1)
+        }
+        other => {
+            eprintln!("unexpected response: {:?}", std::mem::discriminant(&other));
+            Ok(1)
+        }
+    }
+}
+
 /// Helper to find a session by name or UUID
 async fn find_session(
     client: &mut super::super::client::Client,
@@ -3,7 +3,9 @@
 use fugue_protocol::{ClientMessage, ServerMessage};
 use fugue_utils::Result;
 
-use super::{connect, parse_target, Target};
+use super::{connect, parse_target, sessions_by_name, Target};
+use crate::client::Client;
+use crate::format::FormatContext;
 
 /// Create a new window
 pub async fn new_window(
@@ -66,7 +68,7 @@ pub async fn kill_window(target: &str) -> Result<i32> {
 }
 
 /// List windows in a session
-pub async fn list_windows(target: Option<&str>) -> Result<i32> {
+pub async fn list_windows(target: Option<&str>, format: Option<&str>) -> Result<i32> {
     let mut client = connect().await?;
 
     let session_filter = target.map(|t| {
@@ -87,6 +89,18 @@ pub async fn list_windows(target: Option<&str>) -> Result<i32> {
         } => {
             if windows.is_empty() {
                 eprintln!("no windows in session {}", session_name);
+            } else if let Some(fmt) = format {
+                let session = sessions_by_name(&mut client)
+                    .await?
+                    .remove(&session_name)
+                    .map(|session| FormatContext::new().with_session(&session))
+                    .unwrap_or_else(|| FormatContext::new().set("session_name", &session_name));
+                let active_index = focused_window(&mut client, &session_name).await?;
+
+                for window in windows {
+                    let active = active_index == Some(window.index);
+                    println!("{}", session.clone().with_window(&window, active).expand(fmt));
+                }
             } else {
                 for window in windows {
                     // Format: index: name (pane_count panes)
@@ -108,3 +122,17 @@ pub async fn list_windows(target: Option<&str>) -> Result<i32> {
         }
     }
 }
+
+/// Index of the window holding the session's focused pane
+async fn focused_window(client: &mut Client, session_name: &str) -> Result<Option<usize>> {
+    let msg = ClientMessage::ListAllPanes {
+        session_filter: Some(session_name.to_string()),
+    };
+
+    match client.request(msg).await? {
+        ServerMessage::AllPanesList { panes } => {
+            Ok(panes.iter().find(|p| p.is_focused).map(|p| p.window_index))
+        }
+        _ => Ok(None),
+    }
+}
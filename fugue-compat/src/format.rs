@@ -0,0 +1,319 @@
+//! tmux format strings
+//!
+//! Expands `#{variable}`, conditionals (`#{?cond,then,else}`), comparisons
+//! (`#{==:a,b}`, `#{!=:a,b}`, `#{||:a,b}`, `#{&&:a,b}`), truncation
+//! (`#{=10:pane_title}`, `#{=-10:...}` keeps the end) and the `#S`/`#I`/`#W`/
+//! `#P`/`#T`/`#D` shorthands. `##`, `#,` and `#}` escape the special
+//! characters. As in tmux, unknown variables expand to nothing.
+//!
+//! Besides tmux's `session_*`, `window_*`, `pane_*` and `buffer_*` variables,
+//! fugue adds `session_tags`, `session_worktree`, `pane_name`, `pane_agent`
+//! (the detected agent type) and `pane_agent_activity` (`idle`, `processing`,
+//! `tool_use`, ...).
+
+use std::collections::HashMap;
+
+use fugue_protocol::{PaneListEntry, PaneState, PasteBufferInfo, SessionInfo, WindowInfo};
+
+/// Variables available to a format string
+#[derive(Debug, Clone, Default)]
+pub struct FormatContext {
+    vars: HashMap<&'static str, String>,
+}
+
+impl FormatContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a variable (builder pattern)
+    pub fn set(mut self, name: &'static str, value: impl ToString) -> Self {
+        self.vars.insert(name, value.to_string());
+        self
+    }
+
+    /// Add the `session_*` variables
+    pub fn with_session(self, session: &SessionInfo) -> Self {
+        let mut tags: Vec<_> = session.tags.iter().map(String::as_str).collect();
+        tags.sort_unstable();
+
+        self.set("session_name", &session.name)
+            .set("session_id", session.id)
+            .set("session_windows", session.window_count)
+            .set("session_attached", session.attached_clients)
+            .set("session_created", session.created_at)
+            .set("session_tags", tags.join(","))
+            .set(
+                "session_worktree",
+                session.worktree.as_ref().map(|w| w.path.as_str()).unwrap_or_default(),
+            )
+    }
+
+    /// Add the `window_*` variables
+    pub fn with_window(self, window: &WindowInfo, active: bool) -> Self {
+        self.set("window_id", window.id)
+            .set("window_index", window.index)
+            .set("window_name", &window.name)
+            .set("window_panes", window.pane_count)
+            .set("window_active", flag(active))
+    }
+
+    /// Add the `pane_*` variables and what can be derived about its window
+    /// from the other entries in the same pane listing
+    pub fn with_pane(self, pane: &PaneListEntry, listing: &[PaneListEntry]) -> Self {
+        let siblings = listing
+            .iter()
+            .filter(|p| p.session_name == pane.session_name && p.window_index == pane.window_index);
+        let (window_panes, window_active) = siblings
+            .fold((0, false), |(count, active), p| (count + 1, active || p.is_focused));
+
+        let (dead, dead_status) = match pane.state {
+            PaneState::Exited { code } => (true, code.map(|c| c.to_string()).unwrap_or_default()),
+            _ => (false, String::new()),
+        };
+        let (agent, activity) = match &pane.state {
+            PaneState::Agent(agent) => (agent.agent_type.clone(), agent.activity.name().to_string()),
+            _ => (String::new(), String::new()),
+        };
+
+        self.set("session_name", &pane.session_name)
+            .set("window_index", pane.window_index)
+            .set("window_name", &pane.window_name)
+            .set("window_panes", window_panes)
+            .set("window_active", flag(window_active))
+            .set("pane_id", pane.id)
+            .set("pane_index", pane.pane_index)
+            .set("pane_name", pane.name.as_deref().unwrap_or_default())
+            .set("pane_title", pane.title.as_deref().unwrap_or_default())
+            .set("pane_current_path", pane.cwd.as_deref().unwrap_or_default())
+            .set("pane_width", pane.cols)
+            .set("pane_height", pane.rows)
+            .set("pane_active", flag(pane.is_focused))
+            .set("pane_dead", flag(dead))
+            .set("pane_dead_status", dead_status)
+            .set("pane_agent", agent)
+            .set("pane_agent_activity", activity)
+    }
+
+    /// Add the `buffer_*` variables
+    pub fn with_buffer(self, buffer: &PasteBufferInfo) -> Self {
+        self.set("buffer_name", &buffer.name)
+            .set("buffer_size", buffer.size)
+            .set("buffer_sample", &buffer.sample)
+            .set("buffer_created", buffer.created_at)
+    }
+
+    /// Expand a format string
+    pub fn expand(&self, format: &str) -> String {
+        let mut out = String::with_capacity(format.len());
+        let mut rest = format;
+
+        while let Some(pos) = rest.find('#') {
+            out.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+
+            match rest.chars().next() {
+                Some('{') => match closing_brace(&rest[1..]) {
+                    Some(end) => {
+                        out.push_str(&self.evaluate(&rest[1..end + 1]));
+                        rest = &rest[end + 2..];
+                    }
+                    // Unterminated: keep it as typed
+                    None => out.push('#'),
+                },
+                Some(c @ ('#' | ',' | '}')) => {
+                    out.push(c);
+                    rest = &rest[1..];
+                }
+                Some(c) => match alias(c) {
+                    Some(name) => {
+                        out.push_str(&self.lookup(name));
+                        rest = &rest[1..];
+                    }
+                    None => out.push('#'),
+                },
+                None => out.push('#'),
+            }
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    /// Evaluate the inside of a `#{...}`
+    fn evaluate(&self, expr: &str) -> String {
+        if let Some(rest) = expr.strip_prefix('?') {
+            let args = split_args(rest);
+            let cond = args.first().map(|c| self.value(c)).unwrap_or_default();
+            let branch = if truthy(&cond) { args.get(1) } else { args.get(2) };
+            return branch.map(|b| self.expand(b)).unwrap_or_default();
+        }
+
+        if let Some((op, rest)) = expr.split_once(':') {
+            if let Some(len) = op.strip_prefix('=').and_then(|n| n.parse::<isize>().ok()) {
+                return truncate(&self.value(rest), len);
+            }
+            if matches!(op, "==" | "!=" | "||" | "&&") {
+                let args = split_args(rest);
+                let a = args.first().map(|a| self.expand(a)).unwrap_or_default();
+                let b = args.get(1).map(|b| self.expand(b)).unwrap_or_default();
+                let result = match op {
+                    "==" => a == b,
+                    "!=" => a != b,
+                    "||" => truthy(&a) || truthy(&b),
+                    _ => truthy(&a) && truthy(&b),
+                };
+                return flag(result).to_string();
+            }
+        }
+
+        self.lookup(expr)
+    }
+
+    /// A variable name, or a nested format if it contains `#`
+    fn value(&self, s: &str) -> String {
+        if s.contains('#') {
+            self.expand(s)
+        } else {
+            self.lookup(s)
+        }
+    }
+
+    fn lookup(&self, name: &str) -> String {
+        self.vars.get(name).cloned().unwrap_or_default()
+    }
+}
+
+/// Variable for a single-character shorthand like `#S`
+fn alias(c: char) -> Option<&'static str> {
+    match c {
+        'S' => Some("session_name"),
+        'I' => Some("window_index"),
+        'W' => Some("window_name"),
+        'P' => Some("pane_index"),
+        'T' => Some("pane_title"),
+        'D' => Some("pane_id"),
+        _ => None,
+    }
+}
+
+fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+/// tmux treats empty values and "0" as false
+fn truthy(value: &str) -> bool {
+    !value.is_empty() && value != "0"
+}
+
+/// Keep the first `len` characters, or the last `-len`
+fn truncate(value: &str, len: isize) -> String {
+    if len >= 0 {
+        value.chars().take(len.unsigned_abs()).collect()
+    } else {
+        let skip = value.chars().count().saturating_sub(len.unsigned_abs());
+        value.chars().skip(skip).collect()
+    }
+}
+
+/// Offset of the `}` closing a `#{`, given the text after it
+fn closing_brace(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+    let mut depth = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                if bytes.get(i + 1) == Some(&b'{') {
+                    depth += 1;
+                }
+                // Skip the escaped character too
+                i += 1;
+            }
+            b'}' if depth == 0 => return Some(i),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Split arguments on commas that aren't escaped or inside a nested `#{...}`
+fn split_args(s: &str) -> Vec<&str> {
+    let bytes = s.as_bytes();
+    let mut args = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'#' => {
+                if bytes.get(i + 1) == Some(&b'{') {
+                    depth += 1;
+                }
+                i += 1;
+            }
+            b'}' if depth > 0 => depth -= 1,
+            b',' if depth == 0 => {
+                args.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    args.push(&s[start..]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> FormatContext {
+        FormatContext::new()
+            .set("session_name", "api")
+            .set("window_index", 1)
+            .set("pane_index", 0)
+            .set("pane_active", "1")
+            .set("pane_title", "cargo test --workspace")
+            .set("pane_agent", "")
+    }
+
+    #[test]
+    fn test_expand_variables_and_aliases() {
+        let ctx = context();
+        assert_eq!(ctx.expand("#{session_name}:#{window_index}.#{pane_index}"), "api:1.0");
+        assert_eq!(ctx.expand("#S:#I.#P"), "api:1.0");
+        assert_eq!(ctx.expand("#{nope}|##|#X|#{unterminated"), "|#|#X|#{unterminated");
+    }
+
+    #[test]
+    fn test_expand_conditionals() {
+        let ctx = context();
+        assert_eq!(ctx.expand("#{?pane_active,*,-}"), "*");
+        assert_eq!(ctx.expand("#{?pane_agent,agent,shell}"), "shell");
+        assert_eq!(ctx.expand("#{?pane_agent,agent}"), "");
+        assert_eq!(ctx.expand("#{?pane_active,#{session_name}#,x,no}"), "api,x");
+        assert_eq!(
+            ctx.expand("#{?#{==:#{session_name},api},#{?pane_agent,a,b},c}"),
+            "b"
+        );
+        assert_eq!(ctx.expand("#{!=:#{window_index},1}"), "0");
+        assert_eq!(ctx.expand("#{||:#{pane_agent},#{pane_active}}"), "1");
+        assert_eq!(ctx.expand("#{&&:#{pane_agent},#{pane_active}}"), "0");
+    }
+
+    #[test]
+    fn test_expand_truncation() {
+        let ctx = context();
+        assert_eq!(ctx.expand("#{=5:pane_title}"), "cargo");
+        assert_eq!(ctx.expand("#{=-9:pane_title}"), "workspace");
+        assert_eq!(ctx.expand("#{=99:#{session_name}}"), "api");
+    }
+}
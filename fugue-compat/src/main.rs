@@ -7,6 +7,7 @@
 mod cli;
 mod client;
 mod commands;
+mod format;
 
 use clap::Parser;
 use cli::Cli;
@@ -25,7 +26,8 @@ async fn main() {
 
     let cli = Cli::parse();
 
-    let exit_code = match commands::execute(cli.command, cli.addr).await {
+    let tls_options = cli.tls_options();
+    let exit_code = match commands::execute(cli.command, cli.addr, tls_options).await {
         Ok(code) => code,
         Err(e) => {
             eprintln!("{}", e);
@@ -10,14 +10,15 @@ pub mod types;
 // Re-export main types at crate root
 pub use codec::{ClientCodec, CodecError, ServerCodec};
 pub use messages::{
-    ClientMessage, ErrorCode, OrchestrationMessage, OrchestrationTarget, PaneListEntry,
-    ServerMessage,
+    ClientMessage, ErrorCode, ExpectCondition, ExpectOutcome, OrchestrationMessage,
+    OrchestrationTarget, PaneListEntry, ScrollbackHit, ServerMessage,
 };
 pub use types::{
-    AgentActivity, AgentState, ClaudeActivity, ClaudeState, ClientType, Dimensions, JsonValue,
-    MailPriority, PaneInfo, PaneState, PaneStuckStatus, PaneTarget, ReplyMessage, ReplyResult,
-    SessionInfo, SplitDirection, ViewportState, Widget, WidgetConversionError, WidgetUpdate,
-    WindowInfo, WorktreeInfo,
+    AgentActivity, AgentState, AgentUsage, BudgetScope, ClaudeActivity, ClaudeState, ClientType, Dimensions, ExportFormat, JsonValue,
+    LayoutNode, MailPriority, PaneInfo, PaneState, PaneStuckStatus, PaneTarget, PasteBufferInfo, PermissionDecision,
+    PermissionOption, PermissionRequest, ReplyMessage, ReplyResult,
+    SessionInfo, SnapshotInfo, SnapshotSource, SplitDirection, TerminalAlert, ViewportState, Widget, WidgetConversionError, WidgetUpdate,
+    WindowInfo, WindowLayout, WorktreeInfo,
 };
 
 /// Current protocol version
@@ -483,6 +483,188 @@ pub enum ClientMessage {
         /// Name of specific watchdog to query (None = all)
         name: Option<String>,
     },
+
+    // ==================== Remote Peering Auth ====================
+
+    /// Present a pre-shared token before the `Connect` handshake
+    ///
+    /// Required as the first message on TCP listeners configured with an
+    /// auth token. The server closes the connection if the token is wrong.
+    Authenticate {
+        /// Pre-shared token from the server's `[tls]` config
+        token: String,
+    },
+
+    // ==================== MCP Event Subscriptions ====================
+
+    /// Receive session broadcasts from every session, not just the attached one
+    ///
+    /// Used by MCP transports that push notifications to agents. `Output`
+    /// broadcasts are only forwarded when `include_output` is set.
+    SubscribeEvents { include_output: bool },
+
+    /// Stop receiving broadcasts from sessions this client is not attached to
+    UnsubscribeEvents,
+
+    // ==================== Server-side Expect ====================
+
+    /// Wait until a pane's output or agent state satisfies a condition
+    ///
+    /// The server replies exactly once with `ExpectResult`, which may arrive
+    /// long after other responses. Output conditions are matched against the
+    /// raw output stream, so text that scrolls past is never missed.
+    Expect {
+        pane_id: Uuid,
+        condition: ExpectCondition,
+        /// Give up after this long and reply with `ExpectOutcome::Timeout`
+        timeout_ms: u64,
+        /// Also match against this many existing scrollback lines (0 = new output only)
+        lines: usize,
+    },
+
+    // ==================== Permission Prompts ====================
+
+    /// Answer the tool-permission prompt an agent pane is blocked on
+    ///
+    /// The server sends the keystrokes for the matching option and replies
+    /// with `PermissionResolved`, or an error if the pane isn't awaiting
+    /// permission.
+    ResolvePermission {
+        pane_id: Uuid,
+        decision: PermissionDecision,
+    },
+
+    /// Resume a pane whose process was paused by a usage budget
+    ResumePane { pane_id: Uuid },
+
+    // ==================== Named Snapshots ====================
+
+    /// Save sessions under a name (all sessions when `session_filter` is None)
+    ///
+    /// Saving over an existing name replaces it.
+    SaveSnapshot {
+        name: String,
+        /// Session name or ID to save
+        session_filter: Option<String>,
+    },
+
+    /// List saved snapshots
+    ListSnapshots,
+
+    /// Delete a saved snapshot
+    DeleteSnapshot { name: String },
+
+    /// Restore sessions from a snapshot or an earlier point in the WAL
+    ///
+    /// Without `clone_as`, live sessions with the same IDs are torn down and
+    /// replaced (rolled back). With it, the single selected session is
+    /// restored alongside the live ones as a new session with fresh IDs.
+    RestoreSnapshot {
+        source: SnapshotSource,
+        /// Session name or ID within the source (None = every session in it)
+        session_filter: Option<String>,
+        /// Restore as a new session with this name instead of rolling back
+        clone_as: Option<String>,
+    },
+
+    // ==================== Session Export/Import ====================
+
+    /// Export a session as a portable document for `ImportSession`
+    ExportSession {
+        /// Session name or ID
+        session_filter: String,
+        format: ExportFormat,
+        /// Include each pane's scrollback as plain text
+        include_scrollback: bool,
+    },
+
+    /// Recreate a session from a document produced by `ExportSession`
+    ///
+    /// The imported session always gets fresh IDs.
+    ImportSession {
+        /// Export document (JSON or TOML)
+        document: String,
+        /// Name for the new session (defaults to the exported name)
+        name: Option<String>,
+    },
+
+    // ==================== Workspaces ====================
+
+    /// Create the sessions described by a workspace file (`.fugue.toml`)
+    ///
+    /// Sessions that already exist are left alone, so this is idempotent.
+    WorkspaceUp {
+        /// Workspace file contents
+        document: String,
+        /// Directory of the workspace file, for relative working directories
+        base_dir: Option<String>,
+    },
+
+    /// Destroy the sessions described by a workspace file
+    WorkspaceDown {
+        /// Workspace file contents
+        document: String,
+    },
+
+    // ==================== Search ====================
+
+    /// Search the scrollback of every pane
+    ///
+    /// Every whitespace-separated term (or double-quoted phrase) must occur
+    /// in a line, case-insensitively and starting at a word boundary.
+    SearchScrollback {
+        query: String,
+        /// Limit to one session (name or ID)
+        session_filter: Option<String>,
+        /// Lines of context before and after each hit
+        context: usize,
+        /// Maximum number of hits to return
+        limit: usize,
+    },
+
+    /// Read a range of a pane's history, including lines spilled to disk
+    ///
+    /// Lines are numbered like tmux `capture-pane`: 0 is the first line of
+    /// the visible screen and negative numbers count back into history.
+    CapturePane {
+        pane_id: Uuid,
+        /// First line, or `None` for the start of history
+        start: Option<i64>,
+        /// Last line (inclusive), or `None` for the end of output
+        end: Option<i64>,
+    },
+
+    // ==================== Paste Buffers ====================
+
+    /// Store text in a paste buffer
+    ///
+    /// Without a name, a new automatically named buffer is put on top of
+    /// the stack. With `append`, the text is added to the end of the buffer.
+    SetBuffer {
+        name: Option<String>,
+        data: String,
+        append: bool,
+    },
+
+    /// List paste buffers, most recent first
+    ListBuffers,
+
+    /// Read a paste buffer, or the most recent one if `name` is `None`
+    GetBuffer { name: Option<String> },
+
+    /// Delete a paste buffer, or the most recent one if `name` is `None`
+    DeleteBuffer { name: Option<String> },
+
+    /// Type a paste buffer into a pane, newlines becoming carriage returns
+    PasteBuffer {
+        /// Buffer to paste, or the most recent one
+        name: Option<String>,
+        pane_id: Uuid,
+        /// Delete the buffer afterwards
+        delete: bool,
+        /// Wrap in bracketed paste sequences if the pane's program enabled them
+        bracketed: bool,
+    },
 }
 
 impl ClientMessage {
@@ -544,6 +726,27 @@ impl ClientMessage {
             ClientMessage::WatchdogStart { .. } => "WatchdogStart",
             ClientMessage::WatchdogStop { .. } => "WatchdogStop",
             ClientMessage::WatchdogStatus { .. } => "WatchdogStatus",
+            ClientMessage::Authenticate { .. } => "Authenticate",
+            ClientMessage::SubscribeEvents { .. } => "SubscribeEvents",
+            ClientMessage::UnsubscribeEvents => "UnsubscribeEvents",
+            ClientMessage::Expect { .. } => "Expect",
+            ClientMessage::ResolvePermission { .. } => "ResolvePermission",
+            ClientMessage::ResumePane { .. } => "ResumePane",
+            ClientMessage::SaveSnapshot { .. } => "SaveSnapshot",
+            ClientMessage::ListSnapshots => "ListSnapshots",
+            ClientMessage::DeleteSnapshot { .. } => "DeleteSnapshot",
+            ClientMessage::RestoreSnapshot { .. } => "RestoreSnapshot",
+            ClientMessage::ExportSession { .. } => "ExportSession",
+            ClientMessage::ImportSession { .. } => "ImportSession",
+            ClientMessage::WorkspaceUp { .. } => "WorkspaceUp",
+            ClientMessage::WorkspaceDown { .. } => "WorkspaceDown",
+            ClientMessage::SearchScrollback { .. } => "SearchScrollback",
+            ClientMessage::CapturePane { .. } => "CapturePane",
+            ClientMessage::SetBuffer { .. } => "SetBuffer",
+            ClientMessage::ListBuffers => "ListBuffers",
+            ClientMessage::GetBuffer { .. } => "GetBuffer",
+            ClientMessage::DeleteBuffer { .. } => "DeleteBuffer",
+            ClientMessage::PasteBuffer { .. } => "PasteBuffer",
         }
     }
 }
@@ -993,6 +1196,176 @@ pub enum ServerMessage {
         /// List of running watchdogs
         watchdogs: Vec<WatchdogInfo>,
     },
+
+    // ==================== Remote Peering Auth ====================
+
+    /// Pre-shared token accepted; the client may now send `Connect`
+    Authenticated,
+
+    // ==================== MCP Event Subscriptions ====================
+
+    /// Current event subscription state after SubscribeEvents/UnsubscribeEvents
+    EventSubscription {
+        subscribed: bool,
+        include_output: bool,
+    },
+
+    // ==================== Server-side Expect ====================
+
+    /// Result of an `Expect` request
+    ExpectResult {
+        pane_id: Uuid,
+        outcome: ExpectOutcome,
+        /// Time from the request to the outcome
+        elapsed_ms: u64,
+    },
+
+    // ==================== Permission Prompts ====================
+
+    /// An agent pane stopped at a tool-permission prompt (broadcast)
+    PermissionRequested {
+        pane_id: Uuid,
+        agent_type: String,
+        request: PermissionRequest,
+    },
+
+    /// A permission prompt was answered via `ResolvePermission`
+    ///
+    /// Sent as the reply and broadcast to the pane's session.
+    PermissionResolved {
+        pane_id: Uuid,
+        decision: PermissionDecision,
+        /// Label of the option that was selected
+        option: String,
+    },
+
+    /// A usage budget was exceeded (broadcast to the sessions in scope)
+    BudgetExceeded {
+        scope: BudgetScope,
+        /// Pane whose usage crossed the limit
+        pane_id: Uuid,
+        /// Usage of the whole scope
+        usage: AgentUsage,
+        max_tokens: Option<u64>,
+        max_cost_usd: Option<f64>,
+        /// Panes paused because of the budget (empty for notify-only budgets)
+        paused: Vec<Uuid>,
+    },
+
+    /// A paused pane was resumed via `ResumePane`
+    PaneResumed { pane_id: Uuid },
+
+    /// A window's split tree changed (also sent for every window on attach)
+    LayoutChanged { layout: WindowLayout },
+
+    /// Response to `SaveSnapshot`
+    SnapshotSaved { snapshot: SnapshotInfo },
+
+    /// Response to `ListSnapshots`, oldest first
+    SnapshotList { snapshots: Vec<SnapshotInfo> },
+
+    /// Response to `DeleteSnapshot`
+    SnapshotDeleted { name: String },
+
+    /// Response to `RestoreSnapshot` with the sessions that were restored
+    SnapshotRestored { sessions: Vec<SessionInfo> },
+
+    /// Response to `ExportSession`
+    SessionExported { document: String },
+
+    /// Response to `ImportSession` with the new session
+    SessionImported { session: SessionInfo },
+
+    /// Response to `WorkspaceUp`
+    WorkspaceStarted {
+        /// Sessions that were created
+        created: Vec<SessionInfo>,
+        /// Names of sessions that already existed
+        existing: Vec<String>,
+    },
+
+    /// Response to `WorkspaceDown`
+    WorkspaceStopped {
+        /// Names of sessions that were destroyed
+        destroyed: Vec<String>,
+        /// Names of sessions that were not running
+        missing: Vec<String>,
+    },
+
+    /// Response to `SearchScrollback`, grouped by pane with newest hits first
+    ScrollbackSearchResults {
+        query: String,
+        hits: Vec<ScrollbackHit>,
+        /// Whether more lines matched than `limit`
+        truncated: bool,
+    },
+
+    /// Response to `CapturePane`
+    PaneCaptured {
+        pane_id: Uuid,
+        /// Line number of the first line in `lines`
+        start: i64,
+        lines: Vec<String>,
+        /// Line number of the oldest line still available
+        history_start: i64,
+    },
+
+    /// Response to `SetBuffer`
+    BufferSet { name: String },
+
+    /// Response to `ListBuffers`
+    BufferList { buffers: Vec<PasteBufferInfo> },
+
+    /// Response to `GetBuffer`
+    BufferContent { name: String, data: String },
+
+    /// Response to `DeleteBuffer`
+    BufferDeleted { name: String },
+
+    /// Response to `PasteBuffer`
+    BufferPasted { name: String, pane_id: Uuid },
+
+    /// Alert the user's terminal, sent to clients attached to the session
+    /// a notification was routed from
+    TerminalNotification {
+        title: String,
+        message: String,
+        alert: TerminalAlert,
+    },
+}
+
+/// Condition for a server-side `Expect`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ExpectCondition {
+    /// Any of these regexes matches pane output (first listed wins on ties)
+    Output { patterns: Vec<String> },
+    /// The pane's agent reaches any of these activities
+    AgentActivity { activities: Vec<AgentActivity> },
+}
+
+/// How a server-side `Expect` finished
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ExpectOutcome {
+    /// An output pattern matched
+    Matched {
+        /// Index into `ExpectCondition::Output::patterns`
+        pattern_index: usize,
+        /// Text of the whole match
+        text: String,
+        /// Full line containing the start of the match
+        line: String,
+        /// Positional capture groups, index 0 being the whole match (`None` for
+        /// groups that did not participate)
+        captures: Vec<Option<String>>,
+        /// Named capture groups that participated in the match
+        named_captures: std::collections::HashMap<String, String>,
+    },
+    /// The agent reached one of the requested activities
+    AgentActivity { activity: AgentActivity },
+    /// The timeout elapsed first
+    Timeout,
+    /// The pane closed first
+    PaneClosed,
 }
 
 /// Information about a single watchdog timer
@@ -1068,6 +1441,30 @@ impl ServerMessage {
             ServerMessage::WatchdogStarted { .. } => "WatchdogStarted",
             ServerMessage::WatchdogStopped { .. } => "WatchdogStopped",
             ServerMessage::WatchdogStatusResponse { .. } => "WatchdogStatusResponse",
+            ServerMessage::Authenticated => "Authenticated",
+            ServerMessage::EventSubscription { .. } => "EventSubscription",
+            ServerMessage::ExpectResult { .. } => "ExpectResult",
+            ServerMessage::PermissionRequested { .. } => "PermissionRequested",
+            ServerMessage::PermissionResolved { .. } => "PermissionResolved",
+            ServerMessage::BudgetExceeded { .. } => "BudgetExceeded",
+            ServerMessage::PaneResumed { .. } => "PaneResumed",
+            ServerMessage::LayoutChanged { .. } => "LayoutChanged",
+            ServerMessage::SnapshotSaved { .. } => "SnapshotSaved",
+            ServerMessage::SnapshotList { .. } => "SnapshotList",
+            ServerMessage::SnapshotDeleted { .. } => "SnapshotDeleted",
+            ServerMessage::SnapshotRestored { .. } => "SnapshotRestored",
+            ServerMessage::SessionExported { .. } => "SessionExported",
+            ServerMessage::SessionImported { .. } => "SessionImported",
+            ServerMessage::WorkspaceStarted { .. } => "WorkspaceStarted",
+            ServerMessage::WorkspaceStopped { .. } => "WorkspaceStopped",
+            ServerMessage::ScrollbackSearchResults { .. } => "ScrollbackSearchResults",
+            ServerMessage::PaneCaptured { .. } => "PaneCaptured",
+            ServerMessage::BufferSet { .. } => "BufferSet",
+            ServerMessage::BufferList { .. } => "BufferList",
+            ServerMessage::BufferContent { .. } => "BufferContent",
+            ServerMessage::BufferDeleted { .. } => "BufferDeleted",
+            ServerMessage::BufferPasted { .. } => "BufferPasted",
+            ServerMessage::TerminalNotification { .. } => "TerminalNotification",
         }
     }
 }
@@ -1094,6 +1491,25 @@ pub struct PaneListEntry {
     pub is_focused: bool,
 }
 
+/// Line of pane scrollback matching a `SearchScrollback` query
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScrollbackHit {
+    pub session_id: Uuid,
+    pub session_name: String,
+    pub window_id: Uuid,
+    pub window_index: usize,
+    pub pane_id: Uuid,
+    pub pane_index: usize,
+    /// Number of scrollback lines after the hit (0 = the newest line)
+    pub lines_from_end: usize,
+    /// Matching line, without escape sequences
+    pub text: String,
+    /// Context lines before the hit, oldest first
+    pub before: Vec<String>,
+    /// Context lines after the hit
+    pub after: Vec<String>,
+}
+
 /// Error codes for protocol errors
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ErrorCode {
@@ -1113,6 +1529,8 @@ pub enum ErrorCode {
     SessionNameExists,
     /// User priority lock is active - MCP focus operations blocked (FEAT-056)
     UserPriorityActive,
+    /// Missing or invalid credentials on an authenticated listener
+    Unauthorized,
 }
 
 /// Detailed error information
@@ -1699,9 +2117,10 @@ tags: HashSet::new(),
             ErrorCode::NoRecipients,
             ErrorCode::SessionNameExists,
             ErrorCode::UserPriorityActive,
+            ErrorCode::Unauthorized,
         ];
 
-        assert_eq!(codes.len(), 11);
+        assert_eq!(codes.len(), 12);
         for (i, code) in codes.iter().enumerate() {
             // Each code should be unique
             for (j, other) in codes.iter().enumerate() {
@@ -2253,4 +2672,346 @@ tags: HashSet::new(),
         let deserialized: ClientMessage = serde_json::from_str(&json).unwrap();
         assert_eq!(exited, deserialized);
     }
+
+    // ==================== Remote Peering Auth Tests ====================
+
+    #[test]
+    fn test_authenticate_roundtrip() {
+        let msg = ClientMessage::Authenticate {
+            token: "s3cret".to_string(),
+        };
+        assert_eq!(msg.type_name(), "Authenticate");
+
+        let bytes = bincode::serialize(&msg).unwrap();
+        let decoded: ClientMessage = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(msg, decoded);
+
+        let reply = ServerMessage::Authenticated;
+        assert_eq!(reply.type_name(), "Authenticated");
+        let bytes = bincode::serialize(&reply).unwrap();
+        let decoded: ServerMessage = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    // ==================== MCP Event Subscription Tests ====================
+
+    #[test]
+    fn test_subscribe_events_roundtrip() {
+        for msg in [
+            ClientMessage::SubscribeEvents { include_output: true },
+            ClientMessage::UnsubscribeEvents,
+        ] {
+            let bytes = bincode::serialize(&msg).unwrap();
+            let decoded: ClientMessage = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(msg, decoded);
+        }
+
+        let reply = ServerMessage::EventSubscription {
+            subscribed: true,
+            include_output: false,
+        };
+        assert_eq!(reply.type_name(), "EventSubscription");
+        let bytes = bincode::serialize(&reply).unwrap();
+        let decoded: ServerMessage = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    // ==================== Server-side Expect Tests ====================
+
+    #[test]
+    fn test_expect_roundtrip() {
+        let pane_id = Uuid::new_v4();
+        for condition in [
+            ExpectCondition::Output {
+                patterns: vec![r"(?P<code>\d+) passed".into(), "error".into()],
+            },
+            ExpectCondition::AgentActivity {
+                activities: vec![AgentActivity::Idle, AgentActivity::Custom("done".into())],
+            },
+        ] {
+            let msg = ClientMessage::Expect {
+                pane_id,
+                condition,
+                timeout_ms: 1000,
+                lines: 0,
+            };
+            assert_eq!(msg.type_name(), "Expect");
+            let bytes = bincode::serialize(&msg).unwrap();
+            let decoded: ClientMessage = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(msg, decoded);
+        }
+
+        let mut named_captures = HashMap::new();
+        named_captures.insert("code".to_string(), "12".to_string());
+        for outcome in [
+            ExpectOutcome::Matched {
+                pattern_index: 0,
+                text: "12 passed".into(),
+                line: "test result: 12 passed".into(),
+                captures: vec![Some("12 passed".into()), Some("12".into()), None],
+                named_captures,
+            },
+            ExpectOutcome::AgentActivity {
+                activity: AgentActivity::Idle,
+            },
+            ExpectOutcome::Timeout,
+            ExpectOutcome::PaneClosed,
+        ] {
+            let reply = ServerMessage::ExpectResult {
+                pane_id,
+                outcome,
+                elapsed_ms: 5,
+            };
+            assert_eq!(reply.type_name(), "ExpectResult");
+            let bytes = bincode::serialize(&reply).unwrap();
+            let decoded: ServerMessage = bincode::deserialize(&bytes).unwrap();
+            assert_eq!(reply, decoded);
+        }
+    }
+
+    #[test]
+    fn test_permission_messages_roundtrip() {
+        let pane_id = Uuid::new_v4();
+        let msg = ClientMessage::ResolvePermission {
+            pane_id,
+            decision: PermissionDecision::ApproveAlways,
+        };
+        assert_eq!(msg.type_name(), "ResolvePermission");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        for reply in [
+            ServerMessage::PermissionRequested {
+                pane_id,
+                agent_type: "codex".into(),
+                request: PermissionRequest {
+                    tool: "Shell".into(),
+                    arguments: "rm -rf target".into(),
+                    description: None,
+                    options: vec![PermissionOption {
+                        key: "y".into(),
+                        label: "Yes, proceed".into(),
+                    }],
+                },
+            },
+            ServerMessage::PermissionResolved {
+                pane_id,
+                decision: PermissionDecision::Deny,
+                option: "No".into(),
+            },
+        ] {
+            let decoded: ServerMessage =
+                bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+            assert_eq!(reply, decoded);
+        }
+    }
+
+    #[test]
+    fn test_budget_messages_roundtrip() {
+        let pane_id = Uuid::new_v4();
+        let msg = ClientMessage::ResumePane { pane_id };
+        assert_eq!(msg.type_name(), "ResumePane");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        for reply in [
+            ServerMessage::BudgetExceeded {
+                scope: BudgetScope::Tag("frontend".into()),
+                pane_id,
+                usage: AgentUsage {
+                    tokens: 120_000,
+                    cost_usd: 1.25,
+                    estimated: true,
+                },
+                max_tokens: None,
+                max_cost_usd: Some(1.0),
+                paused: vec![pane_id],
+            },
+            ServerMessage::PaneResumed { pane_id },
+        ] {
+            let decoded: ServerMessage =
+                bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+            assert_eq!(reply, decoded);
+        }
+    }
+
+    #[test]
+    fn test_layout_changed_roundtrip() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut root = LayoutNode::pane(a);
+        root.split(a, LayoutNode::pane(b), SplitDirection::Horizontal, 0.6);
+        let msg = ServerMessage::LayoutChanged {
+            layout: WindowLayout { window_id: Uuid::new_v4(), root },
+        };
+
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+        assert_eq!(msg.type_name(), "LayoutChanged");
+    }
+
+    #[test]
+    fn test_snapshot_messages_roundtrip() {
+        let msg = ClientMessage::RestoreSnapshot {
+            source: SnapshotSource::Sequence(42),
+            session_filter: Some("workers".into()),
+            clone_as: Some("workers-retry".into()),
+        };
+        assert_eq!(msg.type_name(), "RestoreSnapshot");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        let reply = ServerMessage::SnapshotList {
+            snapshots: vec![SnapshotInfo {
+                name: "known-good".into(),
+                created_at: 1_700_000_000,
+                sessions: vec!["workers".into(), "orchestrator".into()],
+            }],
+        };
+        assert_eq!(reply.type_name(), "SnapshotList");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    #[test]
+    fn test_export_import_messages_roundtrip() {
+        let msg = ClientMessage::ExportSession {
+            session_filter: "workers".into(),
+            format: ExportFormat::Toml,
+            include_scrollback: true,
+        };
+        assert_eq!(msg.type_name(), "ExportSession");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        let msg = ClientMessage::ImportSession {
+            document: "{}".into(),
+            name: Some("workers-copy".into()),
+        };
+        assert_eq!(msg.type_name(), "ImportSession");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+    }
+
+    #[test]
+    fn test_workspace_messages_roundtrip() {
+        let msg = ClientMessage::WorkspaceUp {
+            document: "[[session]]\nname = \"dev\"\n".into(),
+            base_dir: Some("/srv/app".into()),
+        };
+        assert_eq!(msg.type_name(), "WorkspaceUp");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        let reply = ServerMessage::WorkspaceStopped {
+            destroyed: vec!["dev".into()],
+            missing: vec!["docs".into()],
+        };
+        assert_eq!(reply.type_name(), "WorkspaceStopped");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    #[test]
+    fn test_search_messages_roundtrip() {
+        let msg = ClientMessage::SearchScrollback {
+            query: "\"connection refused\" redis".into(),
+            session_filter: None,
+            context: 2,
+            limit: 50,
+        };
+        assert_eq!(msg.type_name(), "SearchScrollback");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        let reply = ServerMessage::ScrollbackSearchResults {
+            query: "redis".into(),
+            hits: vec![ScrollbackHit {
+                session_id: Uuid::new_v4(),
+                session_name: "dev".into(),
+                window_id: Uuid::new_v4(),
+                window_index: 1,
+                pane_id: Uuid::new_v4(),
+                pane_index: 0,
+                lines_from_end: 12,
+                text: "redis: connection refused".into(),
+                before: vec!["starting worker".into()],
+                after: Vec::new(),
+            }],
+            truncated: false,
+        };
+        assert_eq!(reply.type_name(), "ScrollbackSearchResults");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    #[test]
+    fn test_capture_pane_roundtrip() {
+        let msg = ClientMessage::CapturePane {
+            pane_id: Uuid::new_v4(),
+            start: Some(-5000),
+            end: None,
+        };
+        assert_eq!(msg.type_name(), "CapturePane");
+        let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+
+        let reply = ServerMessage::PaneCaptured {
+            pane_id: Uuid::new_v4(),
+            start: -5000,
+            lines: vec!["$ cargo test".into(), "running 12 tests".into()],
+            history_start: -120_000,
+        };
+        assert_eq!(reply.type_name(), "PaneCaptured");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    #[test]
+    fn test_paste_buffer_roundtrip() {
+        let messages = vec![
+            ClientMessage::SetBuffer {
+                name: Some("plan".into()),
+                data: "step 1\nstep 2".into(),
+                append: false,
+            },
+            ClientMessage::ListBuffers,
+            ClientMessage::GetBuffer { name: None },
+            ClientMessage::DeleteBuffer { name: Some("plan".into()) },
+            ClientMessage::PasteBuffer {
+                name: None,
+                pane_id: Uuid::new_v4(),
+                delete: true,
+                bracketed: true,
+            },
+        ];
+        for msg in messages {
+            let decoded: ClientMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+            assert_eq!(msg, decoded);
+        }
+
+        let reply = ServerMessage::BufferList {
+            buffers: vec![PasteBufferInfo {
+                name: "buffer0".into(),
+                size: 13,
+                sample: "step 1\\nstep 2".into(),
+                created_at: 1_700_000_000,
+                automatic: true,
+            }],
+        };
+        assert_eq!(reply.type_name(), "BufferList");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&reply).unwrap()).unwrap();
+        assert_eq!(reply, decoded);
+    }
+
+    #[test]
+    fn test_terminal_notification_roundtrip() {
+        let msg = ServerMessage::TerminalNotification {
+            title: "api".into(),
+            message: "Agent finished".into(),
+            alert: TerminalAlert::Osc777,
+        };
+        assert_eq!(msg.type_name(), "TerminalNotification");
+        let decoded: ServerMessage = bincode::deserialize(&bincode::serialize(&msg).unwrap()).unwrap();
+        assert_eq!(msg, decoded);
+    }
 }
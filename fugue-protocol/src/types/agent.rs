@@ -1,6 +1,7 @@
 use super::common::JsonValue;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use uuid::Uuid;
 
 // ==================== Generic Agent System (FEAT-084) ====================
 
@@ -79,6 +80,135 @@ impl AgentState {
     pub fn is_claude(&self) -> bool {
         self.agent_type == "claude"
     }
+
+    /// Get the permission prompt the agent is blocked on, if any
+    pub fn permission_request(&self) -> Option<PermissionRequest> {
+        self.get_metadata(PERMISSION_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Set (or clear) the pending permission prompt
+    ///
+    /// Setting a request also sets the activity to `AwaitingPermission`.
+    pub fn set_permission_request(&mut self, request: Option<&PermissionRequest>) {
+        match request.and_then(|r| serde_json::to_value(r).ok()) {
+            Some(value) => {
+                self.activity = AgentActivity::AwaitingPermission;
+                self.set_metadata(PERMISSION_METADATA_KEY, value);
+            }
+            None => {
+                self.metadata.remove(PERMISSION_METADATA_KEY);
+            }
+        }
+    }
+
+    /// Get the token usage accounted to the agent's pane, if any
+    pub fn usage(&self) -> Option<AgentUsage> {
+        self.get_metadata(USAGE_METADATA_KEY)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Set the token usage accounted to the agent's pane
+    pub fn set_usage(&mut self, usage: &AgentUsage) {
+        if let Ok(value) = serde_json::to_value(usage) {
+            self.set_metadata(USAGE_METADATA_KEY, value);
+        }
+    }
+}
+
+/// Metadata key holding the pane's accumulated `AgentUsage`
+pub const USAGE_METADATA_KEY: &str = "usage";
+
+/// Tokens and cost accumulated by one or more agent panes
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct AgentUsage {
+    /// Tokens used, summed over agent restarts
+    pub tokens: u64,
+    /// Cost in USD, reported by the agent or estimated from `tokens`
+    pub cost_usd: f64,
+    /// Whether any part of `cost_usd` is an estimate
+    pub estimated: bool,
+}
+
+impl AgentUsage {
+    /// Add another usage total into this one
+    pub fn add(&mut self, other: &AgentUsage) {
+        self.tokens += other.tokens;
+        self.cost_usd += other.cost_usd;
+        self.estimated |= other.estimated;
+    }
+}
+
+/// What a usage budget applies to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum BudgetScope {
+    /// A single pane
+    Pane(Uuid),
+    /// All panes of a session
+    Session(Uuid),
+    /// All panes of sessions carrying a tag
+    Tag(String),
+}
+
+/// Metadata key holding a serialized `PermissionRequest`
+pub const PERMISSION_METADATA_KEY: &str = "permission";
+
+/// A tool-permission prompt an agent is blocked on
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PermissionRequest {
+    /// Tool the agent wants to use (e.g. "Bash", "Edit", "Shell")
+    pub tool: String,
+    /// Tool arguments as shown in the prompt (command, file path, ...)
+    pub arguments: String,
+    /// Explanation shown with the prompt, if any
+    pub description: Option<String>,
+    /// Choices offered by the prompt, in order
+    pub options: Vec<PermissionOption>,
+}
+
+/// One choice in a permission prompt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PermissionOption {
+    /// Keys that select this option
+    pub key: String,
+    /// Option text (e.g. "Yes, and don't ask again for rm commands")
+    pub label: String,
+}
+
+/// Answer to a permission prompt
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PermissionDecision {
+    /// Allow this tool call once
+    Approve,
+    /// Allow and stop asking for similar calls
+    ApproveAlways,
+    /// Reject the tool call
+    Deny,
+}
+
+impl PermissionRequest {
+    /// Option matching a decision, if the prompt offers one
+    pub fn option_for(&self, decision: PermissionDecision) -> Option<&PermissionOption> {
+        let is_yes = |o: &&PermissionOption| o.label.to_lowercase().starts_with("yes");
+        let is_always = |o: &&PermissionOption| {
+            let label = o.label.to_lowercase();
+            label.contains("always") || label.contains("don't ask") || label.contains("do not ask")
+        };
+        match decision {
+            PermissionDecision::Approve => self
+                .options
+                .iter()
+                .find(|o| is_yes(o) && !is_always(o))
+                .or_else(|| self.options.iter().find(is_yes)),
+            PermissionDecision::ApproveAlways => {
+                self.options.iter().find(|o| is_yes(o) && is_always(o))
+            }
+            PermissionDecision::Deny => self
+                .options
+                .iter()
+                .find(|o| o.label.to_lowercase().starts_with("no")),
+        }
+    }
 }
 
 impl Default for AgentState {
@@ -105,6 +235,11 @@ pub enum AgentActivity {
     AwaitingConfirmation,
     /// Agent-specific custom state
     Custom(String),
+    /// Blocked on a tool-permission prompt; the parsed prompt is available
+    /// from `AgentState::permission_request`
+    ///
+    /// Kept after `Custom` so existing variants keep their encoded index.
+    AwaitingPermission,
 }
 
 impl AgentActivity {
@@ -112,6 +247,21 @@ impl AgentActivity {
     pub fn is_active(&self) -> bool {
         !matches!(self, AgentActivity::Idle)
     }
+
+    /// Snake-case name, as used in hook filters and format variables
+    ///
+    /// Custom activities use their own name.
+    pub fn name(&self) -> &str {
+        match self {
+            AgentActivity::Idle => "idle",
+            AgentActivity::Processing => "processing",
+            AgentActivity::Generating => "generating",
+            AgentActivity::ToolUse => "tool_use",
+            AgentActivity::AwaitingConfirmation => "awaiting_confirmation",
+            AgentActivity::AwaitingPermission => "awaiting_permission",
+            AgentActivity::Custom(name) => name,
+        }
+    }
 }
 
 /// Claude Code specific state
@@ -195,7 +345,9 @@ impl From<AgentActivity> for ClaudeActivity {
             AgentActivity::Processing => ClaudeActivity::Thinking,
             AgentActivity::Generating => ClaudeActivity::Coding,
             AgentActivity::ToolUse => ClaudeActivity::ToolUse,
-            AgentActivity::AwaitingConfirmation => ClaudeActivity::AwaitingConfirmation,
+            AgentActivity::AwaitingConfirmation | AgentActivity::AwaitingPermission => {
+                ClaudeActivity::AwaitingConfirmation
+            }
             AgentActivity::Custom(_) => ClaudeActivity::Idle, // Fallback for unknown states
         }
     }
@@ -339,4 +491,93 @@ mod tests {
         let deserialized: ClaudeState = bincode::deserialize(&serialized).unwrap();
         assert_eq!(state, deserialized);
     }
+
+    // ==================== Permission Request Tests ====================
+
+    fn bash_request() -> PermissionRequest {
+        PermissionRequest {
+            tool: "Bash".to_string(),
+            arguments: "rm -rf target".to_string(),
+            description: Some("Remove build artifacts".to_string()),
+            options: vec![
+                PermissionOption { key: "1".to_string(), label: "Yes".to_string() },
+                PermissionOption {
+                    key: "2".to_string(),
+                    label: "Yes, and don't ask again for rm commands".to_string(),
+                },
+                PermissionOption {
+                    key: "3".to_string(),
+                    label: "No, and tell Claude what to do differently".to_string(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_permission_option_for_decision() {
+        let request = bash_request();
+        assert_eq!(request.option_for(PermissionDecision::Approve).unwrap().key, "1");
+        assert_eq!(request.option_for(PermissionDecision::ApproveAlways).unwrap().key, "2");
+        assert_eq!(request.option_for(PermissionDecision::Deny).unwrap().key, "3");
+
+        let yes_only = PermissionRequest {
+            options: vec![PermissionOption { key: "y".to_string(), label: "Yes, proceed".to_string() }],
+            ..bash_request()
+        };
+        assert!(yes_only.option_for(PermissionDecision::ApproveAlways).is_none());
+        assert!(yes_only.option_for(PermissionDecision::Deny).is_none());
+    }
+
+    #[test]
+    fn test_agent_state_permission_request() {
+        let mut state = AgentState::new("claude");
+        assert!(state.permission_request().is_none());
+
+        state.set_permission_request(Some(&bash_request()));
+        assert_eq!(state.activity, AgentActivity::AwaitingPermission);
+        assert_eq!(state.permission_request(), Some(bash_request()));
+
+        let serialized = bincode::serialize(&state).unwrap();
+        let deserialized: AgentState = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(deserialized.permission_request(), Some(bash_request()));
+
+        // Custom keeps the index it had before AwaitingPermission was added
+        let custom = bincode::serialize(&AgentActivity::Custom("x".into())).unwrap();
+        assert_eq!(&custom[..4], &5u32.to_le_bytes());
+
+        state.set_permission_request(None);
+        assert!(state.permission_request().is_none());
+        assert_eq!(
+            ClaudeActivity::from(AgentActivity::AwaitingPermission),
+            ClaudeActivity::AwaitingConfirmation
+        );
+    }
+
+    #[test]
+    fn test_agent_state_usage() {
+        let mut state = AgentState::new("gemini");
+        assert!(state.usage().is_none());
+
+        let mut usage = AgentUsage {
+            tokens: 1000,
+            cost_usd: 0.5,
+            estimated: false,
+        };
+        usage.add(&AgentUsage {
+            tokens: 500,
+            cost_usd: 0.25,
+            estimated: true,
+        });
+        state.set_usage(&usage);
+
+        let deserialized: AgentState = bincode::deserialize(&bincode::serialize(&state).unwrap()).unwrap();
+        assert_eq!(
+            deserialized.usage(),
+            Some(AgentUsage {
+                tokens: 1500,
+                cost_usd: 0.75,
+                estimated: true,
+            })
+        );
+    }
 }
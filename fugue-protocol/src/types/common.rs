@@ -122,6 +122,30 @@ pub enum MailPriority {
     Error,
 }
 
+/// A paste buffer, as listed by `ListBuffers`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PasteBufferInfo {
+    pub name: String,
+    /// Size in bytes
+    pub size: usize,
+    /// Start of the text, with control characters escaped
+    pub sample: String,
+    pub created_at: u64, // Unix timestamp
+    /// Named automatically (`buffer0`, ...) and dropped when over the limit
+    pub automatic: bool,
+}
+
+/// How a client alerts the user's terminal for a notification
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TerminalAlert {
+    /// Ring the bell (BEL)
+    Bell,
+    /// iTerm2/Windows Terminal desktop notification (OSC 9)
+    Osc9,
+    /// rxvt/foot/Ghostty notification with a title (OSC 777)
+    Osc777,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,6 +48,32 @@ impl SessionInfo {
     }
 }
 
+/// A saved, named snapshot of one or more sessions
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub created_at: u64, // Unix timestamp
+    /// Names of the sessions in the snapshot
+    pub sessions: Vec<String>,
+}
+
+/// Where a restore takes session state from
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SnapshotSource {
+    /// A snapshot saved with `SaveSnapshot`
+    Named(String),
+    /// The persisted state as of this WAL sequence (checkpoint plus replay)
+    Sequence(u64),
+}
+
+/// Document format for `ExportSession`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
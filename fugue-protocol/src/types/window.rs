@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::SplitDirection;
+
+/// Smallest share of a split a pane can be resized down to
+const MIN_SHARE: f32 = 0.1;
+
 /// Window information
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct WindowInfo {
@@ -12,6 +17,141 @@ pub struct WindowInfo {
     pub active_pane_id: Option<Uuid>,
 }
 
+/// A node in a window's split tree
+///
+/// The server owns one tree per window; clients only render it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LayoutNode {
+    /// A single pane
+    Pane { id: Uuid },
+    /// Children laid out along `direction`, each with its share of the space
+    /// (shares sum to 1.0)
+    Split {
+        direction: SplitDirection,
+        children: Vec<(LayoutNode, f32)>,
+    },
+}
+
+impl LayoutNode {
+    /// Create a leaf for a single pane
+    pub fn pane(id: Uuid) -> Self {
+        Self::Pane { id }
+    }
+
+    /// All pane IDs in the tree, in layout order
+    pub fn pane_ids(&self) -> Vec<Uuid> {
+        match self {
+            LayoutNode::Pane { id } => vec![*id],
+            LayoutNode::Split { children, .. } => {
+                children.iter().flat_map(|(child, _)| child.pane_ids()).collect()
+            }
+        }
+    }
+
+    /// Whether the tree contains a pane
+    pub fn contains(&self, pane_id: Uuid) -> bool {
+        match self {
+            LayoutNode::Pane { id } => *id == pane_id,
+            LayoutNode::Split { children, .. } => {
+                children.iter().any(|(child, _)| child.contains(pane_id))
+            }
+        }
+    }
+
+    /// Split `target`, placing `node` after it along `direction`
+    ///
+    /// `ratio` is the share `target` keeps (clamped to 0.1..=0.9). Returns
+    /// false if `target` is not in the tree.
+    pub fn split(&mut self, target: Uuid, node: LayoutNode, direction: SplitDirection, ratio: f32) -> bool {
+        match self {
+            LayoutNode::Pane { id } if *id == target => {
+                let ratio = ratio.clamp(MIN_SHARE, 1.0 - MIN_SHARE);
+                *self = LayoutNode::Split {
+                    direction,
+                    children: vec![(LayoutNode::pane(target), ratio), (node, 1.0 - ratio)],
+                };
+                true
+            }
+            LayoutNode::Pane { .. } => false,
+            LayoutNode::Split { children, .. } => children
+                .iter_mut()
+                .find(|(child, _)| child.contains(target))
+                .is_some_and(|(child, _)| child.split(target, node, direction, ratio)),
+        }
+    }
+
+    /// Remove a pane, giving its space to its siblings
+    ///
+    /// Splits left with a single child are collapsed into that child. Returns
+    /// false if the pane is not in the tree or is the root itself, which the
+    /// owner has to drop instead.
+    pub fn remove(&mut self, pane_id: Uuid) -> bool {
+        let LayoutNode::Split { children, .. } = self else {
+            return false;
+        };
+
+        let leaf = children
+            .iter()
+            .position(|(child, _)| matches!(child, LayoutNode::Pane { id } if *id == pane_id));
+        if let Some(index) = leaf {
+            let (_, share) = children.remove(index);
+            let remaining = 1.0 - share;
+            let count = children.len() as f32;
+            for (_, ratio) in children.iter_mut() {
+                *ratio = if remaining > 0.0 { *ratio / remaining } else { 1.0 / count };
+            }
+        } else if !children.iter_mut().any(|(child, _)| child.remove(pane_id)) {
+            return false;
+        }
+
+        if children.len() == 1 {
+            let (only, _) = children.remove(0);
+            *self = only;
+        }
+        true
+    }
+
+    /// Grow (positive `delta`) or shrink a pane within its enclosing split
+    ///
+    /// The space is taken from or given to the next sibling (the previous one
+    /// for the last child). Returns the split direction and the factor the
+    /// pane's share changed by, or `None` if the pane is not in a split.
+    pub fn resize(&mut self, pane_id: Uuid, delta: f32) -> Option<(SplitDirection, f32)> {
+        let LayoutNode::Split { direction, children } = self else {
+            return None;
+        };
+
+        let leaf = children
+            .iter()
+            .position(|(child, _)| matches!(child, LayoutNode::Pane { id } if *id == pane_id));
+        let Some(index) = leaf else {
+            return children.iter_mut().find_map(|(child, _)| child.resize(pane_id, delta));
+        };
+
+        if children.len() < 2 {
+            return None;
+        }
+        let neighbour = if index + 1 < children.len() { index + 1 } else { index - 1 };
+        let current = children[index].1;
+        let pair = current + children[neighbour].1;
+        let max_share = pair - MIN_SHARE;
+        if max_share < MIN_SHARE || current <= 0.0 {
+            return Some((*direction, 1.0));
+        }
+        let new_share = (current + delta).clamp(MIN_SHARE, max_share);
+        children[index].1 = new_share;
+        children[neighbour].1 = pair - new_share;
+        Some((*direction, new_share / current))
+    }
+}
+
+/// The split tree of one window
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WindowLayout {
+    pub window_id: Uuid,
+    pub root: LayoutNode,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,4 +258,81 @@ mod tests {
         let deserialized: WindowInfo = bincode::deserialize(&serialized).unwrap();
         assert_eq!(window, deserialized);
     }
+
+    // ==================== LayoutNode Tests ====================
+
+    fn shares(node: &LayoutNode) -> Vec<f32> {
+        match node {
+            LayoutNode::Split { children, .. } => children.iter().map(|(_, r)| *r).collect(),
+            LayoutNode::Pane { .. } => vec![1.0],
+        }
+    }
+
+    #[test]
+    fn test_layout_split_and_remove() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut root = LayoutNode::pane(a);
+
+        assert!(root.split(a, LayoutNode::pane(b), SplitDirection::Horizontal, 0.7));
+        assert!(root.split(b, LayoutNode::pane(c), SplitDirection::Vertical, 0.5));
+        assert!(!root.split(Uuid::new_v4(), LayoutNode::pane(c), SplitDirection::Vertical, 0.5));
+        assert_eq!(root.pane_ids(), vec![a, b, c]);
+        assert_eq!(shares(&root), vec![0.7, 0.3]);
+
+        // Removing c collapses the nested split back into b, keeping b's share
+        assert!(root.remove(c));
+        assert_eq!(root.pane_ids(), vec![a, b]);
+        assert_eq!(shares(&root), vec![0.7, 0.3]);
+
+        // Removing b leaves a as the root
+        assert!(root.remove(b));
+        assert_eq!(root, LayoutNode::pane(a));
+        assert!(!root.remove(a));
+    }
+
+    #[test]
+    fn test_layout_remove_redistributes_share() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let mut root = LayoutNode::Split {
+            direction: SplitDirection::Vertical,
+            children: vec![
+                (LayoutNode::pane(a), 0.5),
+                (LayoutNode::pane(b), 0.25),
+                (LayoutNode::pane(c), 0.25),
+            ],
+        };
+
+        assert!(root.remove(a));
+        assert_eq!(shares(&root), vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn test_layout_resize() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut root = LayoutNode::pane(a);
+        assert!(root.resize(a, 0.1).is_none());
+
+        root.split(a, LayoutNode::pane(b), SplitDirection::Horizontal, 0.5);
+        let (direction, factor) = root.resize(a, 0.25).unwrap();
+        assert_eq!(direction, SplitDirection::Horizontal);
+        assert!((factor - 1.5).abs() < 1e-6);
+        assert_eq!(shares(&root), vec![0.75, 0.25]);
+
+        // The last child takes space from the previous one, within limits
+        root.resize(b, 0.8).unwrap();
+        let s = shares(&root);
+        assert!((s[0] - 0.1).abs() < 1e-6 && (s[1] - 0.9).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_window_layout_serde() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut root = LayoutNode::pane(a);
+        root.split(a, LayoutNode::pane(b), SplitDirection::Vertical, 0.4);
+        let layout = WindowLayout { window_id: Uuid::new_v4(), root };
+
+        let serialized = bincode::serialize(&layout).unwrap();
+        let deserialized: WindowLayout = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(layout, deserialized);
+    }
 }
@@ -6,11 +6,11 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use fugue_protocol::{AgentActivity, AgentState, JsonValue};
+use fugue_protocol::{AgentActivity, AgentState, JsonValue, PermissionRequest};
 
 use crate::claude::ClaudeDetector;
 
-use super::AgentDetector;
+use super::{AgentDetector, ScreenSnapshot};
 
 /// Debounce duration for state change broadcasts (BUG-048)
 ///
@@ -74,7 +74,7 @@ impl Default for ClaudeAgentDetector {
 }
 
 impl AgentDetector for ClaudeAgentDetector {
-    fn agent_type(&self) -> &'static str {
+    fn agent_type(&self) -> &str {
         "claude"
     }
 
@@ -182,6 +182,27 @@ impl AgentDetector for ClaudeAgentDetector {
             None
         }
     }
+
+    fn classify_screen(&self, screen: &ScreenSnapshot) -> Option<AgentActivity> {
+        ClaudeDetector::classify_screen(screen).map(Into::into)
+    }
+
+    fn parse_permission(&self, screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        ClaudeDetector::parse_permission(screen)
+    }
+
+    fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<AgentState> {
+        let was_active = self.inner.is_claude();
+        let state_changed = self.inner.analyze_screen(screen).is_some();
+
+        // The screen is already settled, so no spinner debounce is needed
+        if state_changed || (!was_active && self.inner.is_claude()) {
+            self.last_state_broadcast = Some(Instant::now());
+            self.state()
+        } else {
+            None
+        }
+    }
 }
 
 impl std::fmt::Debug for ClaudeAgentDetector {
@@ -6,11 +6,11 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use fugue_protocol::{AgentActivity, AgentState, JsonValue};
+use fugue_protocol::{AgentActivity, AgentState, JsonValue, PermissionRequest};
 use regex::Regex;
 use lazy_static::lazy_static;
 
-use super::AgentDetector;
+use super::{permission, AgentDetector, ScreenSnapshot};
 
 /// Debounce duration for state change broadcasts
 const STATE_BROADCAST_DEBOUNCE_MS: u64 = 100;
@@ -69,7 +69,7 @@ impl Default for CodexAgentDetector {
 }
 
 impl AgentDetector for CodexAgentDetector {
-    fn agent_type(&self) -> &'static str {
+    fn agent_type(&self) -> &str {
         "codex"
     }
 
@@ -295,6 +295,99 @@ impl AgentDetector for CodexAgentDetector {
              None
         }
     }
+
+    /// Codex CLI shows approval requests inline with a `›` option list, a
+    /// `• Working (… • esc to interrupt)` row while busy, and a `›` input row.
+    fn classify_screen(&self, screen: &ScreenSnapshot) -> Option<AgentActivity> {
+        let tail: Vec<&str> = screen.last_rows(16).into_iter().map(str::trim).collect();
+        if tail
+            .iter()
+            .any(|row| row.starts_with("Would you like to") || row.starts_with("Allow command?"))
+            && tail.iter().any(|row| row.starts_with("› 1."))
+        {
+            return Some(AgentActivity::AwaitingConfirmation);
+        }
+
+        let is_bullet = |row: &str| row.starts_with('•') || row.starts_with('◦');
+        if let Some((working_row, _)) =
+            screen.find_free_row_rev(|row| is_bullet(row) && row.contains("esc to interrupt"))
+        {
+            // The transcript entry above the status row says what it's doing
+            let entry = screen
+                .free_rows()
+                .rev()
+                .filter(|(idx, _)| *idx < working_row)
+                .find(|(_, row)| is_bullet(row))
+                .map(|(_, row)| row.trim_start_matches(['•', '◦']).trim());
+            return Some(if entry.is_some_and(|e| e.starts_with("Running")) {
+                AgentActivity::ToolUse
+            } else {
+                AgentActivity::Processing
+            });
+        }
+
+        let prompt = tail.iter().rev().take(3).any(|row| row.starts_with('›'));
+        prompt.then_some(AgentActivity::Idle)
+    }
+
+    /// Codex asks inline: `Would you like to run the following command?`,
+    /// an optional `Reason:`, the `$ command` and the options.
+    fn parse_permission(&self, screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        let rows: Vec<&str> = screen.last_rows(24).into_iter().map(str::trim).collect();
+        let question = rows
+            .iter()
+            .rposition(|row| row.starts_with("Would you like to") || row.starts_with("Allow command?"))?;
+        let rest = &rows[question + 1..];
+        let options = permission::parse_options(rest.iter().copied());
+        if options.is_empty() {
+            return None;
+        }
+
+        let tool = if rows[question].contains("edit") { "Edit" } else { "Shell" };
+        let arguments = rest
+            .iter()
+            .find_map(|row| row.strip_prefix("$ "))
+            .or_else(|| {
+                rest.iter()
+                    .copied()
+                    .find(|row| !row.starts_with("Reason:") && permission::parse_options([*row]).is_empty())
+            })
+            .unwrap_or_default();
+        let description = rest
+            .iter()
+            .find_map(|row| row.strip_prefix("Reason:"))
+            .map(|reason| reason.trim().to_string());
+
+        Some(PermissionRequest {
+            tool: tool.to_string(),
+            arguments: arguments.to_string(),
+            description,
+            options,
+        })
+    }
+
+    fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<AgentState> {
+        let was_active = self.is_active;
+        let text = screen.text();
+        self.detect_presence(&text);
+        self.extract_metadata(&text);
+        if !self.is_active {
+            return None;
+        }
+
+        if let Some(activity) = self.classify_screen(screen) {
+            self.current_activity = activity;
+        }
+
+        // The screen is already settled, so no spinner debounce is needed
+        if !was_active || self.current_activity != self.last_broadcast_activity {
+            self.last_state_broadcast = Some(Instant::now());
+            self.last_broadcast_activity = self.current_activity.clone();
+            self.state()
+        } else {
+            None
+        }
+    }
 }
 
 impl CodexAgentDetector {
@@ -0,0 +1,438 @@
+//! Config-defined Agent Detectors
+//!
+//! Lets users describe agents such as Aider or in-house tools in
+//! `config.toml` instead of Rust. Each `[agent_detectors.<name>]` table is
+//! compiled into a `DetectorSpec`, and every pane gets a
+//! `ConfiguredAgentDetector` per spec alongside the built-in detectors.
+//!
+//! Each pane's `DetectorRegistry` compiles the specs from the config it is
+//! handed with its output, and compiles them again when that config changes
+//! on reload.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use tracing::debug;
+
+use fugue_protocol::{AgentActivity, AgentState, JsonValue};
+
+use super::AgentDetector;
+use crate::config::AgentDetectorConfig;
+
+/// Agent types reserved by the built-in detectors
+pub const BUILTIN_AGENT_TYPES: [&str; 3] = ["claude", "gemini", "codex"];
+
+/// Confidence reported when a config doesn't set one
+const DEFAULT_CONFIDENCE: u8 = 90;
+
+/// Debounce duration for state change broadcasts (matches the built-in detectors)
+const STATE_BROADCAST_DEBOUNCE_MS: u64 = 100;
+
+/// A compiled config-defined detector
+#[derive(Debug)]
+pub struct DetectorSpec {
+    agent_type: String,
+    confidence: u8,
+    presence: Vec<Regex>,
+    /// Activities in priority order
+    activity: Vec<(AgentActivity, Vec<Regex>)>,
+    session_id: Option<Regex>,
+    metadata: Vec<(String, Regex)>,
+}
+
+impl DetectorSpec {
+    /// Compile a detector definition
+    pub fn compile(agent_type: &str, config: &AgentDetectorConfig) -> Result<Self, String> {
+        if BUILTIN_AGENT_TYPES.contains(&agent_type) {
+            return Err(format!(
+                "agent detector '{}' conflicts with a built-in detector",
+                agent_type
+            ));
+        }
+        if config.presence.is_empty() {
+            return Err(format!(
+                "agent detector '{}' needs at least one presence pattern",
+                agent_type
+            ));
+        }
+
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|e| {
+                format!("agent detector '{}': invalid regex '{}': {}", agent_type, pattern, e)
+            })
+        };
+        let compile_all = |patterns: &[String]| {
+            patterns.iter().map(|p| compile(p)).collect::<Result<Vec<_>, _>>()
+        };
+
+        let patterns = &config.activity;
+        let mut activity = vec![
+            (AgentActivity::AwaitingConfirmation, compile_all(&patterns.awaiting_confirmation)?),
+            (AgentActivity::ToolUse, compile_all(&patterns.tool_use)?),
+            (AgentActivity::Generating, compile_all(&patterns.generating)?),
+            (AgentActivity::Processing, compile_all(&patterns.processing)?),
+            (AgentActivity::Idle, compile_all(&patterns.idle)?),
+        ];
+        for (name, custom) in &patterns.custom {
+            activity.push((AgentActivity::Custom(name.clone()), compile_all(custom)?));
+        }
+        activity.retain(|(_, regexes)| !regexes.is_empty());
+
+        Ok(Self {
+            agent_type: agent_type.to_string(),
+            confidence: config.confidence.unwrap_or(DEFAULT_CONFIDENCE).min(100),
+            presence: compile_all(&config.presence)?,
+            activity,
+            session_id: config.session_id.as_deref().map(compile).transpose()?,
+            metadata: config
+                .metadata
+                .iter()
+                .map(|(key, pattern)| Ok((key.clone(), compile(pattern)?)))
+                .collect::<Result<_, String>>()?,
+        })
+    }
+
+    /// Agent type this spec detects
+    pub fn agent_type(&self) -> &str {
+        &self.agent_type
+    }
+
+    fn matches_presence(&self, text: &str) -> bool {
+        self.presence.iter().any(|r| r.is_match(text))
+    }
+
+    fn match_activity(&self, text: &str) -> Option<AgentActivity> {
+        self.activity
+            .iter()
+            .find(|(_, regexes)| regexes.iter().any(|r| r.is_match(text)))
+            .map(|(activity, _)| activity.clone())
+    }
+}
+
+/// First capture group of `regex` in `text`, or the whole match if it has none
+fn capture(regex: &Regex, text: &str) -> Option<String> {
+    let caps = regex.captures(text)?;
+    caps.get(1)
+        .or_else(|| caps.get(0))
+        .map(|m| m.as_str().to_string())
+}
+
+/// Compile every detector in a config, failing on the first invalid one
+pub fn compile_all(
+    configs: &BTreeMap<String, AgentDetectorConfig>,
+) -> Result<Vec<Arc<DetectorSpec>>, String> {
+    configs
+        .iter()
+        .map(|(name, config)| DetectorSpec::compile(name, config).map(Arc::new))
+        .collect()
+}
+
+/// Compile every valid detector in a config, skipping invalid ones
+///
+/// Used for panes, where a bad detector shouldn't stop the others. Config
+/// reloads are validated with [`compile_all`] first.
+pub fn compile_valid(configs: &BTreeMap<String, AgentDetectorConfig>) -> Vec<Arc<DetectorSpec>> {
+    configs
+        .iter()
+        .filter_map(|(name, config)| match DetectorSpec::compile(name, config) {
+            Ok(spec) => Some(Arc::new(spec)),
+            Err(e) => {
+                debug!("Skipping agent detector: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Agent detector driven by a config-defined `DetectorSpec`
+pub struct ConfiguredAgentDetector {
+    spec: Arc<DetectorSpec>,
+    /// Whether the agent has been detected
+    is_active: bool,
+    /// Detection confidence (0-100)
+    confidence: u8,
+    /// Current activity state
+    current_activity: AgentActivity,
+    /// Last activity that was broadcast (for debounce comparison)
+    last_broadcast_activity: AgentActivity,
+    /// Last time a state change was broadcast (for debouncing)
+    last_state_broadcast: Option<Instant>,
+    /// Debounce duration for state broadcasts
+    broadcast_debounce: Duration,
+    /// Extracted session ID
+    session_id: Option<String>,
+    /// Extracted metadata captures
+    metadata: HashMap<String, String>,
+}
+
+impl ConfiguredAgentDetector {
+    /// Create a detector for a compiled spec
+    pub fn new(spec: Arc<DetectorSpec>) -> Self {
+        Self {
+            spec,
+            is_active: false,
+            confidence: 0,
+            current_activity: AgentActivity::Idle,
+            last_broadcast_activity: AgentActivity::Idle,
+            last_state_broadcast: None,
+            broadcast_debounce: Duration::from_millis(STATE_BROADCAST_DEBOUNCE_MS),
+            session_id: None,
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Output with escape sequences removed, which is what patterns match against
+    fn plain_text(text: &str) -> String {
+        String::from_utf8_lossy(&strip_ansi_escapes::strip(text.as_bytes())).into_owned()
+    }
+
+    fn check_presence(&mut self, text: &str) -> bool {
+        if self.spec.matches_presence(text) {
+            self.is_active = true;
+            self.confidence = self.spec.confidence;
+            return true;
+        }
+        false
+    }
+
+    fn update_captures(&mut self, text: &str) {
+        if let Some(regex) = &self.spec.session_id {
+            if let Some(id) = capture(regex, text) {
+                self.session_id = Some(id);
+            }
+        }
+        for (key, regex) in &self.spec.metadata {
+            if let Some(value) = capture(regex, text) {
+                self.metadata.insert(key.clone(), value);
+            }
+        }
+    }
+}
+
+impl AgentDetector for ConfiguredAgentDetector {
+    fn agent_type(&self) -> &str {
+        self.spec.agent_type()
+    }
+
+    fn detect_presence(&mut self, text: &str) -> bool {
+        let text = Self::plain_text(text);
+        self.check_presence(&text) || self.is_active
+    }
+
+    fn detect_activity(&self, text: &str) -> Option<AgentActivity> {
+        if !self.is_active {
+            return None;
+        }
+        self.spec.match_activity(&Self::plain_text(text))
+    }
+
+    fn extract_session_id(&mut self, text: &str) -> Option<String> {
+        let regex = self.spec.session_id.as_ref()?;
+        let id = capture(regex, &Self::plain_text(text))?;
+        self.session_id = Some(id.clone());
+        Some(id)
+    }
+
+    fn extract_metadata(&mut self, text: &str) -> HashMap<String, JsonValue> {
+        self.update_captures(&Self::plain_text(text));
+        self.metadata
+            .iter()
+            .map(|(k, v)| (k.clone(), JsonValue::new(serde_json::Value::String(v.clone()))))
+            .collect()
+    }
+
+    fn confidence(&self) -> u8 {
+        self.confidence
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    fn state(&self) -> Option<AgentState> {
+        if !self.is_active {
+            return None;
+        }
+
+        let mut state = AgentState::new(self.spec.agent_type());
+        state.activity = self.current_activity.clone();
+        state.session_id = self.session_id.clone();
+        for (key, value) in &self.metadata {
+            state.metadata.insert(
+                key.clone(),
+                JsonValue::new(serde_json::Value::String(value.clone())),
+            );
+        }
+        Some(state)
+    }
+
+    fn reset(&mut self) {
+        self.is_active = false;
+        self.confidence = 0;
+        self.current_activity = AgentActivity::Idle;
+        self.last_broadcast_activity = AgentActivity::Idle;
+        self.last_state_broadcast = None;
+        self.session_id = None;
+        self.metadata.clear();
+    }
+
+    fn mark_as_active(&mut self) {
+        self.is_active = true;
+        self.confidence = 100;
+    }
+
+    fn analyze(&mut self, text: &str) -> Option<AgentState> {
+        let was_active = self.is_active;
+        let text = Self::plain_text(text);
+
+        self.check_presence(&text);
+        if !self.is_active {
+            return None;
+        }
+
+        let session_before = self.session_id.clone();
+        let metadata_before = self.metadata.clone();
+        self.update_captures(&text);
+        let captures_changed =
+            self.session_id != session_before || self.metadata != metadata_before;
+
+        if let Some(activity) = self.spec.match_activity(&text) {
+            self.current_activity = activity;
+        }
+        let new_activity = self.current_activity.clone();
+
+        let just_detected = !was_active;
+        if just_detected || captures_changed {
+            self.last_state_broadcast = Some(Instant::now());
+            self.last_broadcast_activity = new_activity;
+            return self.state();
+        }
+
+        if new_activity != self.last_broadcast_activity {
+            let should_broadcast = match self.last_state_broadcast {
+                None => true,
+                Some(last) => last.elapsed() > self.broadcast_debounce,
+            };
+            if should_broadcast {
+                self.last_state_broadcast = Some(Instant::now());
+                self.last_broadcast_activity = new_activity;
+                return self.state();
+            }
+        }
+
+        None
+    }
+}
+
+impl std::fmt::Debug for ConfiguredAgentDetector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfiguredAgentDetector")
+            .field("agent_type", &self.spec.agent_type())
+            .field("is_active", &self.is_active)
+            .field("confidence", &self.confidence)
+            .field("current_activity", &self.current_activity)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AgentActivityPatterns;
+
+    fn aider_config() -> AgentDetectorConfig {
+        let mut metadata = BTreeMap::new();
+        metadata.insert("model".to_string(), r"Model: ([\w.-]+)".to_string());
+        let mut custom = BTreeMap::new();
+        custom.insert("committing".to_string(), vec!["Commit [0-9a-f]{7}".to_string()]);
+
+        AgentDetectorConfig {
+            presence: vec![r"^Aider v\d".to_string()],
+            confidence: None,
+            activity: AgentActivityPatterns {
+                awaiting_confirmation: vec![r"\(Y\)es/\(N\)o".to_string()],
+                processing: vec!["Thinking".to_string()],
+                idle: vec![r"(?m)^> $".to_string()],
+                custom,
+                ..Default::default()
+            },
+            session_id: Some(r"session ([\w-]+)".to_string()),
+            metadata,
+        }
+    }
+
+    fn aider() -> ConfiguredAgentDetector {
+        ConfiguredAgentDetector::new(Arc::new(DetectorSpec::compile("aider", &aider_config()).unwrap()))
+    }
+
+    #[test]
+    fn test_compile_rejects_bad_configs() {
+        let mut config = aider_config();
+        assert!(DetectorSpec::compile("claude", &config).is_err());
+
+        config.presence.clear();
+        assert!(DetectorSpec::compile("aider", &config).is_err());
+
+        let mut config = aider_config();
+        config.activity.tool_use = vec!["(".to_string()];
+        let err = DetectorSpec::compile("aider", &config).unwrap_err();
+        assert!(err.contains("invalid regex"));
+    }
+
+    #[test]
+    fn test_presence_and_captures() {
+        let mut detector = aider();
+        assert!(detector.analyze("just a shell\n").is_none());
+        assert!(!detector.is_active());
+
+        let state = detector
+            .analyze("\x1b[1mAider v0.60.0\x1b[0m\nModel: gpt-4o, session abc-123\n")
+            .unwrap();
+        assert_eq!(state.agent_type, "aider");
+        assert_eq!(state.session_id.as_deref(), Some("abc-123"));
+        assert_eq!(
+            state.metadata.get("model").map(|v| v.inner().clone()),
+            Some(serde_json::json!("gpt-4o"))
+        );
+        assert_eq!(detector.confidence(), DEFAULT_CONFIDENCE);
+    }
+
+    #[test]
+    fn test_activity_priority_and_custom() {
+        let mut detector = aider();
+        detector.mark_as_active();
+
+        assert_eq!(
+            detector.detect_activity("Thinking... Apply edits? (Y)es/(N)o"),
+            Some(AgentActivity::AwaitingConfirmation)
+        );
+        assert_eq!(detector.detect_activity("Thinking..."), Some(AgentActivity::Processing));
+        assert_eq!(
+            detector.detect_activity("Commit 1a2b3c4 add feature"),
+            Some(AgentActivity::Custom("committing".to_string()))
+        );
+        assert_eq!(detector.detect_activity("\n> "), Some(AgentActivity::Idle));
+        assert_eq!(detector.detect_activity("random output"), None);
+    }
+
+    #[test]
+    fn test_analyze_reports_activity_changes() {
+        let mut detector = ConfiguredAgentDetector {
+            broadcast_debounce: Duration::ZERO,
+            ..aider()
+        };
+
+        assert!(detector.analyze("Aider v0.60.0\n").is_some());
+        let state = detector.analyze("Thinking...").unwrap();
+        assert_eq!(state.activity, AgentActivity::Processing);
+        // No new information, no new state
+        assert!(detector.analyze("still going").is_none());
+        assert_eq!(detector.state().unwrap().activity, AgentActivity::Processing);
+
+        detector.reset();
+        assert!(!detector.is_active());
+        assert!(detector.state().is_none());
+    }
+}
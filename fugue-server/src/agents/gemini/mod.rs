@@ -6,9 +6,11 @@
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 
-use fugue_protocol::{AgentActivity, AgentState, JsonValue};
+use fugue_protocol::{AgentActivity, AgentState, JsonValue, PermissionRequest};
 
-use super::AgentDetector;
+use super::permission;
+use super::screen::BRAILLE_SPINNER;
+use super::{AgentDetector, ScreenSnapshot};
 
 /// Debounce duration for state change broadcasts
 ///
@@ -171,7 +173,7 @@ impl Default for GeminiAgentDetector {
 }
 
 impl AgentDetector for GeminiAgentDetector {
-    fn agent_type(&self) -> &'static str {
+    fn agent_type(&self) -> &str {
         "gemini"
     }
 
@@ -317,6 +319,106 @@ impl AgentDetector for GeminiAgentDetector {
             None
         }
     }
+
+    /// Gemini CLI draws tool calls and confirmations in boxes, a braille
+    /// spinner row while working, and a boxed `>` input at the bottom.
+    fn classify_screen(&self, screen: &ScreenSnapshot) -> Option<AgentActivity> {
+        let confirmation = screen.boxes().iter().any(|b| {
+            let asks = b.lines.first().is_some_and(|line| line.starts_with('?'))
+                || b.contains("Allow execution")
+                || b.contains("Apply this change?")
+                || b.contains("Do you want to proceed?");
+            asks && b.lines.iter().any(|line| line.contains("1. Yes"))
+        });
+        if confirmation {
+            return Some(AgentActivity::AwaitingConfirmation);
+        }
+
+        let working = screen
+            .find_free_row_rev(|row| {
+                row.starts_with(|c| BRAILLE_SPINNER.contains(&c)) && row.contains("esc to cancel")
+            })
+            .is_some();
+        if working {
+            // ⊶ marks a tool call that is still executing
+            let executing = screen
+                .boxes()
+                .iter()
+                .any(|b| b.lines.first().is_some_and(|line| line.starts_with('⊶')));
+            return Some(if executing {
+                AgentActivity::ToolUse
+            } else {
+                AgentActivity::Processing
+            });
+        }
+
+        let prompt = screen
+            .boxes()
+            .iter()
+            .any(|b| b.lines.first().is_some_and(|line| line.starts_with('>')));
+        prompt.then_some(AgentActivity::Idle)
+    }
+
+    /// Gemini boxes the prompt: `?  Shell rm -rf target [...]`, the command,
+    /// an `Allow …?` question and the options.
+    fn parse_permission(&self, screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        let dialog = screen.boxes().iter().rev().find(|b| {
+            b.lines.first().is_some_and(|line| line.starts_with('?'))
+                && b.lines.iter().any(|line| line.contains("1. "))
+        })?;
+        let options = permission::parse_options(dialog.lines.iter().map(String::as_str));
+        if options.is_empty() {
+            return None;
+        }
+
+        let header = dialog.lines[0].trim_start_matches('?').trim();
+        let (tool, rest) = header.split_once(' ').unwrap_or((header, ""));
+        let rest = rest.split(" [").next().unwrap_or(rest).trim();
+
+        let body: Vec<&str> = dialog.lines[1..]
+            .iter()
+            .map(String::as_str)
+            .filter(|line| !line.is_empty())
+            .take_while(|line| permission::parse_options([*line]).is_empty())
+            .collect();
+        let question = body.iter().rev().find(|line| line.ends_with('?')).copied();
+        let arguments = body
+            .iter()
+            .find(|line| Some(**line) != question)
+            .copied()
+            .unwrap_or(rest);
+
+        Some(PermissionRequest {
+            tool: tool.to_string(),
+            arguments: arguments.to_string(),
+            description: question.map(str::to_string),
+            options,
+        })
+    }
+
+    fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<AgentState> {
+        let was_active = self.is_active;
+        let text = screen.text();
+        self.check_presence(&text);
+        if !self.is_active {
+            return None;
+        }
+        self.extract_metadata(&text);
+
+        if let Some(activity) = self.classify_screen(screen) {
+            self.current_activity = activity;
+        }
+
+        // The screen is already settled, so no spinner debounce is needed
+        let just_detected = !was_active;
+        if just_detected || self.current_activity != self.last_broadcast_activity {
+            self.last_state_broadcast = Some(Instant::now());
+            self.last_broadcast_activity = self.current_activity.clone();
+            self.state()
+        } else {
+            None
+        }
+    }
 }
 
 impl std::fmt::Debug for GeminiAgentDetector {
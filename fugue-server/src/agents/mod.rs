@@ -8,6 +8,8 @@
 //! - `AgentDetector` trait: Interface for agent-specific detection logic
 //! - `DetectorRegistry`: Manages multiple detectors and routes analysis
 //! - Individual detector implementations (e.g., `ClaudeAgentDetector`)
+//! - `ConfiguredAgentDetector`: detectors declared in `config.toml`
+//! - `ScreenSnapshot`: rendered screen used by `Visual` detection
 //!
 //! # Example
 //!
@@ -23,11 +25,18 @@
 //! ```
 
 pub mod claude;
+pub mod configured;
 pub mod gemini;
 pub mod codex;
+pub mod permission;
+pub mod screen;
 
-use std::collections::HashMap;
-use fugue_protocol::{AgentActivity, AgentState, JsonValue};
+use std::collections::{BTreeMap, HashMap};
+use fugue_protocol::{AgentActivity, AgentState, JsonValue, PermissionRequest};
+
+use crate::config::AgentDetectorConfig;
+
+pub use screen::ScreenSnapshot;
 
 /// Trait for agent-specific detection logic (FEAT-084)
 ///
@@ -35,7 +44,7 @@ use fugue_protocol::{AgentActivity, AgentState, JsonValue};
 /// (e.g., Claude, Copilot, Aider) in terminal output and track its activity state.
 pub trait AgentDetector: Send + Sync {
     /// Return the agent type identifier (e.g., "claude", "copilot", "aider")
-    fn agent_type(&self) -> &'static str;
+    fn agent_type(&self) -> &str;
 
     /// Detect if this agent is present in the terminal output
     ///
@@ -74,6 +83,27 @@ pub trait AgentDetector: Send + Sync {
     /// This is a convenience method that combines presence detection,
     /// activity detection, and metadata extraction.
     fn analyze(&mut self, text: &str) -> Option<AgentState>;
+
+    /// Classify the current activity from the rendered screen
+    ///
+    /// Returns `None` if the screen shows no recognisable state (the
+    /// previous activity is kept).
+    fn classify_screen(&self, _screen: &ScreenSnapshot) -> Option<AgentActivity> {
+        None
+    }
+
+    /// Analyze the rendered screen and return state change if any occurred
+    ///
+    /// Used by `Visual` detection. Detectors without a screen classifier
+    /// fall back to text analysis of the whole screen.
+    fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<AgentState> {
+        self.analyze(&screen.text())
+    }
+
+    /// Parse the tool-permission prompt shown on screen, if any
+    fn parse_permission(&self, _screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        None
+    }
 }
 
 /// Registry for managing multiple agent detectors (FEAT-084)
@@ -87,6 +117,11 @@ pub struct DetectorRegistry {
     detectors: Vec<Box<dyn AgentDetector>>,
     /// Currently active detector index (if any)
     active_detector: Option<usize>,
+    /// Config the config-defined detectors were compiled from, and the index
+    /// where they start (`None` if this registry doesn't track them)
+    configured: Option<(BTreeMap<String, AgentDetectorConfig>, usize)>,
+    /// Config-defined agent marked active before its detector was compiled
+    pending_active: Option<String>,
 }
 
 impl DetectorRegistry {
@@ -95,18 +130,57 @@ impl DetectorRegistry {
         Self {
             detectors: Vec::new(),
             active_detector: None,
+            configured: None,
+            pending_active: None,
         }
     }
 
-    /// Create a registry with default detectors (Claude, Gemini)
+    /// Create a registry with default detectors (Claude, Gemini, Codex)
+    ///
+    /// Config-defined detectors follow them once
+    /// [`sync_configured`](Self::sync_configured) is called.
     pub fn with_defaults() -> Self {
         let mut registry = Self::new();
         registry.register(Box::new(claude::ClaudeAgentDetector::new()));
         registry.register(Box::new(gemini::GeminiAgentDetector::new()));
         registry.register(Box::new(crate::agents::codex::CodexAgentDetector::new()));
+        registry.configured = Some((BTreeMap::new(), registry.detectors.len()));
         registry
     }
 
+    /// Bring the config-defined detectors in line with `configs`
+    ///
+    /// Detectors are only recompiled when `configs` differs from the last
+    /// call. An active config-defined agent stays active if its detector
+    /// still exists.
+    pub fn sync_configured(&mut self, configs: &BTreeMap<String, AgentDetectorConfig>) {
+        let start = match &self.configured {
+            Some((current, _)) if current == configs => return,
+            Some((_, start)) => *start,
+            None => return,
+        };
+
+        let active_type = self
+            .active_detector
+            .filter(|&idx| idx >= start)
+            .and_then(|idx| self.detectors.get(idx))
+            .map(|d| d.agent_type().to_string())
+            .or_else(|| self.pending_active.take());
+
+        self.detectors.truncate(start);
+        if self.active_detector.is_some_and(|idx| idx >= start) {
+            self.active_detector = None;
+        }
+        for spec in configured::compile_valid(configs) {
+            self.register(Box::new(configured::ConfiguredAgentDetector::new(spec)));
+        }
+        self.configured = Some((configs.clone(), start));
+
+        if let Some(agent_type) = active_type {
+            self.mark_as_active(&agent_type);
+        }
+    }
+
     /// Register a new detector
     pub fn register(&mut self, detector: Box<dyn AgentDetector>) {
         self.detectors.push(detector);
@@ -151,6 +225,33 @@ impl DetectorRegistry {
         None
     }
 
+    /// Analyze the rendered screen and return agent state if it changed
+    ///
+    /// Screen counterpart of [`analyze`](Self::analyze), with the same
+    /// exclusive use of the active detector (BUG-057).
+    pub fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<AgentState> {
+        if let Some(idx) = self.active_detector {
+            if let Some(detector) = self.detectors.get_mut(idx) {
+                if let Some(state) = detector.analyze_screen(screen) {
+                    return Some(state);
+                }
+                if detector.is_active() {
+                    return None;
+                }
+                self.active_detector = None;
+            }
+        }
+
+        for (idx, detector) in self.detectors.iter_mut().enumerate() {
+            if let Some(state) = detector.analyze_screen(screen) {
+                self.active_detector = Some(idx);
+                return Some(state);
+            }
+        }
+
+        None
+    }
+
     /// Check if any agent is currently active
     pub fn is_agent_active(&self) -> bool {
         self.active_detector.is_some()
@@ -164,7 +265,7 @@ impl DetectorRegistry {
     }
 
     /// Get the active agent type if any
-    pub fn active_agent_type(&self) -> Option<&'static str> {
+    pub fn active_agent_type(&self) -> Option<&str> {
         self.active_detector
             .and_then(|idx| self.detectors.get(idx))
             .map(|d| d.agent_type())
@@ -176,11 +277,14 @@ impl DetectorRegistry {
             detector.reset();
         }
         self.active_detector = None;
+        self.pending_active = None;
     }
 
     /// Mark a specific agent type as active (for known command launches)
     ///
-    /// Returns `true` if the agent type was found and marked active.
+    /// Returns `true` if the agent type was found and marked active. An
+    /// unknown type is applied once config-defined detectors are synced, in
+    /// case it names one of them.
     pub fn mark_as_active(&mut self, agent_type: &str) -> bool {
         for (idx, detector) in self.detectors.iter_mut().enumerate() {
             if detector.agent_type() == agent_type {
@@ -189,9 +293,19 @@ impl DetectorRegistry {
                 return true;
             }
         }
+        if self.configured.is_some() {
+            self.pending_active = Some(agent_type.to_string());
+        }
         false
     }
 
+    /// Parse the active agent's tool-permission prompt from the screen
+    pub fn parse_permission(&self, screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        self.active_detector
+            .and_then(|idx| self.detectors.get(idx))
+            .and_then(|d| d.parse_permission(screen))
+    }
+
     /// Get mutable access to the active detector (if any)
     pub fn active_detector_mut(&mut self) -> Option<&mut Box<dyn AgentDetector>> {
         let idx = self.active_detector?;
@@ -396,4 +510,52 @@ mod tests {
         assert!(registry.is_agent_active());
         assert!(!registry.is_claude());
     }
+
+    #[test]
+    fn test_registry_picks_up_configured_detectors() {
+        let mut configs = BTreeMap::new();
+        configs.insert(
+            "fugue-test-agent".to_string(),
+            AgentDetectorConfig {
+                presence: vec![r"^fugue-test-agent v\d".to_string()],
+                ..Default::default()
+            },
+        );
+
+        // Registry built before the detector existed picks it up once synced
+        let mut registry = DetectorRegistry::with_defaults();
+        assert!(registry.analyze("fugue-test-agent v1.2\n").is_none());
+        registry.sync_configured(&configs);
+
+        let state = registry.analyze("fugue-test-agent v1.2\n").unwrap();
+        assert_eq!(state.agent_type, "fugue-test-agent");
+        assert_eq!(registry.active_agent_type(), Some("fugue-test-agent"));
+
+        // A changed config keeps the active agent if it is still defined
+        configs.get_mut("fugue-test-agent").unwrap().confidence = Some(50);
+        registry.sync_configured(&configs);
+        assert!(registry.analyze("more output").is_none());
+        assert_eq!(registry.active_agent_type(), Some("fugue-test-agent"));
+
+        registry.sync_configured(&BTreeMap::new());
+        assert!(registry.analyze("more output").is_none());
+        assert!(!registry.is_agent_active());
+    }
+
+    #[test]
+    fn test_registry_marks_configured_agent_before_sync() {
+        let mut configs = BTreeMap::new();
+        configs.insert(
+            "fugue-test-agent".to_string(),
+            AgentDetectorConfig {
+                presence: vec!["fugue-test-agent".to_string()],
+                ..Default::default()
+            },
+        );
+
+        let mut registry = DetectorRegistry::with_defaults();
+        assert!(!registry.mark_as_active("fugue-test-agent"));
+        registry.sync_configured(&configs);
+        assert_eq!(registry.active_agent_type(), Some("fugue-test-agent"));
+    }
 }
@@ -0,0 +1,77 @@
+//! Tool-permission prompt parsing
+//!
+//! Helpers shared by the per-agent `AgentDetector::parse_permission`
+//! implementations. Each agent lays its prompt out differently, but all of
+//! them end with a numbered option list.
+
+use fugue_protocol::PermissionOption;
+
+/// Markers agents draw in front of the highlighted option
+const SELECTION_MARKERS: &[char] = &['❯', '›', '●', '>'];
+
+/// Key hints agents append to option labels
+const KEY_HINTS: &[&str] = &["(esc)", "(shift+tab)", "(tab)", " esc"];
+
+/// Strip box borders and surrounding whitespace from a screen row
+pub fn unframe(row: &str) -> &str {
+    row.trim().trim_start_matches('│').trim_end_matches('│').trim()
+}
+
+/// Parse `1. Yes`-style option rows, skipping any other rows
+pub fn parse_options<'a>(rows: impl IntoIterator<Item = &'a str>) -> Vec<PermissionOption> {
+    rows.into_iter()
+        .filter_map(|row| {
+            let row = unframe(row).trim_start_matches(SELECTION_MARKERS).trim_start();
+            let (number, label) = row.split_once(". ")?;
+            if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+            Some(PermissionOption {
+                key: number.to_string(),
+                label: strip_key_hint(label.trim()).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Remove a trailing key hint such as `(esc)` from an option label
+fn strip_key_hint(label: &str) -> &str {
+    KEY_HINTS
+        .iter()
+        .find_map(|hint| label.strip_suffix(hint))
+        .map(str::trim_end)
+        .unwrap_or(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_options() {
+        let options = parse_options([
+            "│ Do you want to proceed?          │",
+            "│ ❯ 1. Yes                         │",
+            "│   2. Yes, and don't ask again    │",
+            "│   3. No, and tell Claude (esc)   │",
+            "  Press enter to confirm",
+        ]);
+        let labels: Vec<_> = options.iter().map(|o| (o.key.as_str(), o.label.as_str())).collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("1", "Yes"),
+                ("2", "Yes, and don't ask again"),
+                ("3", "No, and tell Claude"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_options_markers_and_hints() {
+        let options = parse_options(["› 1. Yes, proceed", "● 2. No esc", "v1. not an option"]);
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].label, "Yes, proceed");
+        assert_eq!(options[1].label, "No");
+    }
+}
@@ -0,0 +1,291 @@
+//! Named paste buffers
+//!
+//! A server-wide stack of paste buffers shared by every client and pane,
+//! like tmux's. Buffers created without a name are called `buffer0`,
+//! `buffer1`, ... and only the most recent `MAX_AUTOMATIC_BUFFERS` of them
+//! are kept; named buffers stay until deleted, up to `MAX_NAMED_BYTES` in
+//! total. Buffers are not persisted.
+
+use std::time::SystemTime;
+
+use parking_lot::Mutex;
+
+use fugue_protocol::PasteBufferInfo;
+
+/// Automatically named buffers kept before the oldest is dropped (tmux's `buffer-limit`)
+pub const MAX_AUTOMATIC_BUFFERS: usize = 50;
+
+/// Largest buffer, leaving room in a protocol frame for `BufferContent`
+pub const MAX_BUFFER_BYTES: usize = 8 * 1024 * 1024;
+
+/// Total size of the named buffers, which are otherwise never dropped
+pub const MAX_NAMED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Characters of a buffer shown in listings
+const SAMPLE_CHARS: usize = 50;
+
+#[derive(Debug)]
+struct PasteBuffer {
+    name: String,
+    data: String,
+    created_at: u64,
+    automatic: bool,
+}
+
+#[derive(Debug, Default)]
+struct Stack {
+    /// Most recent first
+    buffers: Vec<PasteBuffer>,
+    /// Number for the next automatically named buffer
+    next_automatic: u64,
+}
+
+impl Stack {
+    fn position(&self, name: Option<&str>) -> Option<usize> {
+        match name {
+            Some(name) => self.buffers.iter().position(|buffer| buffer.name == name),
+            None => (!self.buffers.is_empty()).then_some(0),
+        }
+    }
+}
+
+/// Server-wide paste buffers
+#[derive(Debug, Default)]
+pub struct PasteBuffers {
+    stack: Mutex<Stack>,
+}
+
+impl PasteBuffers {
+    /// Create an empty set of buffers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store text, returning the name of the buffer it went into
+    ///
+    /// Without a name, `append` adds to the most recent buffer and otherwise
+    /// a new automatically named buffer is created. A buffer that is set
+    /// moves to the top of the stack.
+    pub fn set(&self, name: Option<&str>, data: String, append: bool) -> Result<String, String> {
+        if name.is_some_and(|name| name.trim().is_empty()) {
+            return Err("Buffer name cannot be empty".to_string());
+        }
+
+        let mut stack = self.stack.lock();
+        let existing = match name {
+            Some(_) => stack.position(name),
+            None if append => stack.position(None),
+            None => None,
+        };
+
+        let size = match existing {
+            Some(index) if append => stack.buffers[index].data.len() + data.len(),
+            _ => data.len(),
+        };
+        if size > MAX_BUFFER_BYTES {
+            return Err(format!(
+                "Buffer would be {} bytes, over the limit of {}",
+                size, MAX_BUFFER_BYTES
+            ));
+        }
+
+        let named = match existing {
+            Some(index) => !stack.buffers[index].automatic,
+            None => name.is_some(),
+        };
+        if named {
+            let others: usize = stack
+                .buffers
+                .iter()
+                .enumerate()
+                .filter(|(index, buffer)| !buffer.automatic && Some(*index) != existing)
+                .map(|(_, buffer)| buffer.data.len())
+                .sum();
+            if others + size > MAX_NAMED_BYTES {
+                return Err(format!(
+                    "Named buffers would total {} bytes, over the limit of {}",
+                    others + size,
+                    MAX_NAMED_BYTES
+                ));
+            }
+        }
+
+        let buffer = match existing {
+            Some(index) => {
+                let mut buffer = stack.buffers.remove(index);
+                if append {
+                    buffer.data.push_str(&data);
+                } else {
+                    buffer.data = data;
+                }
+                buffer
+            }
+            None => {
+                let (name, automatic) = match name {
+                    Some(name) => (name.to_string(), false),
+                    None => (Self::automatic_name(&mut stack), true),
+                };
+                PasteBuffer {
+                    name,
+                    data,
+                    created_at: unix_now(),
+                    automatic,
+                }
+            }
+        };
+
+        let name = buffer.name.clone();
+        stack.buffers.insert(0, buffer);
+
+        let mut automatic = 0;
+        stack.buffers.retain(|buffer| {
+            automatic += buffer.automatic as usize;
+            !buffer.automatic || automatic <= MAX_AUTOMATIC_BUFFERS
+        });
+        Ok(name)
+    }
+
+    /// Next unused automatic name
+    fn automatic_name(stack: &mut Stack) -> String {
+        loop {
+            let name = format!("buffer{}", stack.next_automatic);
+            stack.next_automatic += 1;
+            if stack.position(Some(&name)).is_none() {
+                return name;
+            }
+        }
+    }
+
+    /// Name and text of a buffer, or of the most recent one
+    pub fn get(&self, name: Option<&str>) -> Option<(String, String)> {
+        let stack = self.stack.lock();
+        let buffer = &stack.buffers[stack.position(name)?];
+        Some((buffer.name.clone(), buffer.data.clone()))
+    }
+
+    /// Delete a buffer, or the most recent one, returning its name and text
+    pub fn delete(&self, name: Option<&str>) -> Option<(String, String)> {
+        let mut stack = self.stack.lock();
+        let index = stack.position(name)?;
+        let buffer = stack.buffers.remove(index);
+        Some((buffer.name, buffer.data))
+    }
+
+    /// All buffers, most recent first
+    pub fn list(&self) -> Vec<PasteBufferInfo> {
+        self.stack
+            .lock()
+            .buffers
+            .iter()
+            .map(|buffer| PasteBufferInfo {
+                name: buffer.name.clone(),
+                size: buffer.data.len(),
+                sample: sample(&buffer.data),
+                created_at: buffer.created_at,
+                automatic: buffer.automatic,
+            })
+            .collect()
+    }
+}
+
+/// Start of a buffer with newlines and other control characters escaped
+fn sample(data: &str) -> String {
+    let mut sample: String = data
+        .chars()
+        .take(SAMPLE_CHARS)
+        .flat_map(char::escape_default)
+        .collect();
+    if data.chars().nth(SAMPLE_CHARS).is_some() {
+        sample.push_str("...");
+    }
+    sample
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Text as typed into a pane: line endings become carriage returns, like tmux
+pub fn paste_bytes(data: &str) -> Vec<u8> {
+    data.replace("\r\n", "\r").replace('\n', "\r").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_automatic_and_named_buffers() {
+        let buffers = PasteBuffers::new();
+        assert_eq!(buffers.set(None, "first".into(), false).unwrap(), "buffer0");
+        assert_eq!(buffers.set(Some("plan"), "a".into(), false).unwrap(), "plan");
+        assert_eq!(buffers.set(None, "second".into(), false).unwrap(), "buffer1");
+
+        assert_eq!(buffers.get(None), Some(("buffer1".into(), "second".into())));
+        assert_eq!(buffers.get(Some("buffer0")).unwrap().1, "first");
+
+        // Appending without a name goes to the most recent buffer
+        buffers.set(None, " more".into(), true).unwrap();
+        assert_eq!(buffers.get(None).unwrap().1, "second more");
+
+        // Setting a named buffer moves it to the top
+        buffers.set(Some("plan"), "b".into(), true).unwrap();
+        let names: Vec<_> = buffers.list().into_iter().map(|info| info.name).collect();
+        assert_eq!(names, vec!["plan", "buffer1", "buffer0"]);
+        assert_eq!(buffers.get(None).unwrap().1, "ab");
+
+        assert_eq!(buffers.delete(None).unwrap().0, "plan");
+        assert!(buffers.delete(Some("plan")).is_none());
+        assert!(buffers.set(Some(" "), "x".into(), false).is_err());
+    }
+
+    #[test]
+    fn test_only_automatic_buffers_are_limited() {
+        let buffers = PasteBuffers::new();
+        buffers.set(Some("keep"), "named".into(), false).unwrap();
+        for i in 0..MAX_AUTOMATIC_BUFFERS + 5 {
+            buffers.set(None, format!("yank {}", i), false).unwrap();
+        }
+
+        let list = buffers.list();
+        assert_eq!(list.len(), MAX_AUTOMATIC_BUFFERS + 1);
+        assert!(list.iter().any(|info| info.name == "keep"));
+        assert!(buffers.get(Some("buffer4")).is_none());
+        assert!(buffers.get(Some("buffer5")).is_some());
+    }
+
+    #[test]
+    fn test_oversized_buffer_rejected() {
+        let buffers = PasteBuffers::new();
+        buffers.set(Some("big"), "x".repeat(MAX_BUFFER_BYTES), false).unwrap();
+        assert!(buffers.set(Some("big"), "y".into(), true).is_err());
+        assert_eq!(buffers.get(Some("big")).unwrap().1.len(), MAX_BUFFER_BYTES);
+        assert_eq!(buffers.list()[0].name, "big");
+    }
+
+    #[test]
+    fn test_named_buffers_total_is_limited() {
+        let buffers = PasteBuffers::new();
+        let count = MAX_NAMED_BYTES / MAX_BUFFER_BYTES;
+        for i in 0..count {
+            buffers.set(Some(&format!("big{}", i)), "x".repeat(MAX_BUFFER_BYTES), false).unwrap();
+        }
+        assert!(buffers.set(Some("one-more"), "y".into(), false).is_err());
+
+        // Replacing a named buffer only counts its new size, and automatic
+        // buffers don't count at all
+        buffers.set(Some("big0"), "small".into(), false).unwrap();
+        buffers.set(Some("one-more"), "y".into(), false).unwrap();
+        buffers.set(None, "x".repeat(MAX_BUFFER_BYTES), false).unwrap();
+    }
+
+    #[test]
+    fn test_sample_and_paste_bytes() {
+        assert_eq!(sample("a\tb\n"), "a\\tb\\n");
+        assert!(sample(&"z".repeat(60)).ends_with("..."));
+        assert_eq!(paste_bytes("ls\r\necho hi\n"), b"ls\recho hi\r");
+    }
+}
@@ -6,10 +6,15 @@
 
 use std::time::{Duration, Instant};
 use tracing::{debug, info, trace};
-use fugue_protocol::{ClaudeActivity, ClaudeState};
+use fugue_protocol::{ClaudeActivity, ClaudeState, PermissionRequest};
+use crate::agents::permission;
+use crate::agents::screen::{ScreenSnapshot, BRAILLE_SPINNER};
 use crate::observability::Metrics;
 use super::state::{DetectorConfig, ClaudeSessionInfo, ClaudeStateChange};
 
+/// Glyphs Claude Code cycles through on its spinner row
+const SPINNER_GLYPHS: &[char] = &['·', '✢', '✳', '✶', '✻', '✽', '*'];
+
 /// Strip ANSI escape sequences from text for cleaner pattern matching
 fn strip_ansi(text: &str) -> String {
     let mut result = String::with_capacity(text.len());
@@ -248,13 +253,199 @@ impl ClaudeDetector {
         }
     }
 
-    /// Analyze a vt100 screen buffer for Claude state
+    /// Analyze the rendered screen for Claude state
     ///
     /// This provides more reliable detection by examining the full screen
-    /// rather than incremental output.
-    pub fn analyze_screen(&mut self, screen: &vt100::Screen) -> Option<ClaudeStateChange> {
-        let content = screen.contents();
-        self.analyze(&content)
+    /// rather than incremental output: the spinner row is complete no matter
+    /// how it was redrawn. Screen states are already settled, so changes are
+    /// not debounced.
+    pub fn analyze_screen(&mut self, screen: &ScreenSnapshot) -> Option<ClaudeStateChange> {
+        let text = screen.text();
+
+        if !self.is_claude {
+            if self.detect_claude_presence(&text) {
+                self.is_claude = true;
+                if self.config.log_transitions {
+                    info!(confidence = self.confidence, "Claude Code detected on screen");
+                }
+            } else {
+                return None;
+            }
+        }
+
+        self.extract_session_id(&text);
+        self.extract_model(&text);
+
+        let new_activity = Self::classify_screen(screen)?;
+        if new_activity == self.activity {
+            return None;
+        }
+
+        let previous = std::mem::replace(&mut self.activity, new_activity.clone());
+        self.last_change = Instant::now();
+        let state = self.session_info.to_claude_state(new_activity.clone());
+        let change = ClaudeStateChange::new(previous, new_activity, state);
+        if self.config.log_transitions {
+            debug!(description = %change.description, "Claude state changed (screen)");
+        }
+        Metrics::global().record_claude_transition();
+        Some(change)
+    }
+
+    /// Classify Claude's activity from the rendered screen
+    ///
+    /// Looks, in priority order, for a permission dialog, the spinner row
+    /// (and whether the tool call above it is still running), and the
+    /// prompt box. Returns `None` if none of them is visible.
+    pub fn classify_screen(screen: &ScreenSnapshot) -> Option<ClaudeActivity> {
+        let tail: Vec<&str> = screen
+            .last_rows(16)
+            .into_iter()
+            .map(|row| row.trim().trim_matches('│').trim())
+            .collect();
+        if tail.iter().any(|row| row.starts_with("Do you want to"))
+            && tail.iter().any(|row| row.starts_with("❯ 1.") || row.starts_with("1. Yes"))
+        {
+            return Some(ClaudeActivity::AwaitingConfirmation);
+        }
+
+        let spinner = screen.find_free_row_rev(|row| {
+            row.starts_with(|c| SPINNER_GLYPHS.contains(&c) || BRAILLE_SPINNER.contains(&c))
+                && row.contains('…')
+                && row.contains("to interrupt")
+        });
+        if let Some((spinner_row, _)) = spinner {
+            // The most recent transcript entry above the spinner
+            let mut since_entry = Vec::new();
+            let entry = screen
+                .free_rows()
+                .rev()
+                .filter(|(idx, _)| *idx < spinner_row)
+                .find(|(_, row)| {
+                    let is_entry = row.starts_with('●') || row.starts_with('⏺') || row.starts_with('>');
+                    if !is_entry {
+                        since_entry.push(row.trim());
+                    }
+                    is_entry
+                })
+                .map(|(_, row)| row);
+
+            let running_tool = entry.is_some_and(Self::is_tool_call_header)
+                && (since_entry.iter().all(|row| !row.starts_with('⎿'))
+                    || since_entry.iter().any(|row| row.contains("Running…")));
+            return Some(if running_tool {
+                ClaudeActivity::ToolUse
+            } else {
+                ClaudeActivity::Thinking
+            });
+        }
+
+        // Prompt input: either boxed, or between horizontal rules in newer builds
+        let is_input = |line: &str| line.starts_with('>') || line.starts_with('❯');
+        let prompt_box = screen
+            .boxes()
+            .iter()
+            .any(|b| b.lines.first().is_some_and(|line| is_input(line)));
+        let free: Vec<&str> = screen.free_rows().map(|(_, row)| row.trim()).collect();
+        let ruled_prompt = free.windows(2).any(|pair| {
+            !pair[0].is_empty() && pair[0].chars().all(|c| c == '─') && is_input(pair[1])
+        });
+        if prompt_box || ruled_prompt {
+            return Some(ClaudeActivity::Idle);
+        }
+
+        None
+    }
+
+    /// Parse a tool-permission dialog from the rendered screen
+    ///
+    /// The dialog is either boxed or, in newer builds, opened by a
+    /// horizontal rule. Its first line names the tool ("Bash command",
+    /// "Edit file"), followed by the arguments, the question and the options.
+    pub fn parse_permission(screen: &ScreenSnapshot) -> Option<PermissionRequest> {
+        let is_question = |line: &str| line.starts_with("Do you want to");
+        let dialog: Vec<&str> = match screen
+            .boxes()
+            .iter()
+            .rev()
+            .find(|b| b.lines.iter().any(|line| is_question(line)))
+        {
+            Some(dialog_box) => dialog_box.lines.iter().map(String::as_str).collect(),
+            None => {
+                let rows = screen.rows();
+                let question = rows.iter().rposition(|row| is_question(row.trim()))?;
+                let start = rows[..question]
+                    .iter()
+                    .rposition(|row| {
+                        let row = row.trim();
+                        !row.is_empty() && row.chars().all(|c| c == '─')
+                    })
+                    .map_or(0, |idx| idx + 1);
+                rows[start..].iter().map(|row| row.trim()).collect()
+            }
+        };
+
+        let question = dialog.iter().rposition(|line| is_question(line))?;
+        let options = permission::parse_options(dialog[question + 1..].iter().copied());
+        if options.is_empty() {
+            return None;
+        }
+
+        let mut content = dialog[..question]
+            .iter()
+            .map(|line| permission::unframe(line))
+            .filter(|line| !line.is_empty() && !line.starts_with(['╭', '╰', '─']));
+        let header = content.next()?;
+        let first = content.next();
+        let second = content.next();
+
+        let question = dialog[question].trim_end_matches('?');
+        let (tool, arguments, description) = match header {
+            "Bash command" => ("Bash".to_string(), first, second),
+            "Edit file" | "Create file" => {
+                let tool = if header == "Edit file" { "Edit" } else { "Write" };
+                // "Do you want to make this edit to main.rs" / "Do you want to create main.rs"
+                let target = question
+                    .rsplit_once(" to ")
+                    .map(|(_, target)| target)
+                    .filter(|target| !target.contains(' '))
+                    .or_else(|| question.rsplit_once("create ").map(|(_, target)| target));
+                (tool.to_string(), target.or(first), None)
+            }
+            "Tool use" => {
+                // MCP tools: "server - tool_name(args) (MCP)"
+                let call = first.unwrap_or_default();
+                let (name, args) = call.split_once('(').unwrap_or((call, ""));
+                let args = args.trim_end_matches(" (MCP)").trim_end_matches(')');
+                (name.trim().to_string(), Some(args), second)
+            }
+            other => (
+                other.split_whitespace().next().unwrap_or(other).to_string(),
+                first,
+                second,
+            ),
+        };
+
+        Some(PermissionRequest {
+            tool,
+            arguments: arguments.unwrap_or_default().to_string(),
+            description: description.map(str::to_string),
+            options,
+        })
+    }
+
+    /// Check if a transcript line is a tool call header (`● Bash(cargo test)`)
+    fn is_tool_call_header(line: &str) -> bool {
+        let Some(rest) = line.strip_prefix('●').or_else(|| line.strip_prefix('⏺')) else {
+            return false;
+        };
+        let rest = rest.trim_start();
+        match rest.find('(') {
+            Some(paren) => {
+                paren > 0 && rest[..paren].chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '_')
+            }
+            None => false,
+        }
     }
 
     /// Check if the cursor appears to be at Claude's prompt
@@ -20,6 +20,11 @@
 //!
 //! 3. **Session Info**: Extracts session ID, model name when visible in output.
 //!
+//! With `detection_method = "stream_json"`, panes running
+//! `claude --output-format stream-json` are instead decoded event by event
+//! (see [`StreamJsonDecoder`]), which gives exact tool calls, token usage and
+//! session IDs rather than pattern matches.
+//!
 //! # Usage
 //!
 //! ```rust,ignore
@@ -41,9 +46,11 @@
 mod command;
 mod detector;
 mod state;
+mod stream_json;
 
-pub use command::{create_resume_command, inject_session_id, is_claude_command};
+pub use command::{create_resume_command, inject_session_id};
 pub use detector::ClaudeDetector;
+pub use stream_json::StreamJsonDecoder;
 // These types are part of the public API for external consumers
 #[allow(unused_imports)]
 pub use state::{ClaudeSessionInfo, ClaudeStateChange, DetectorConfig};
@@ -0,0 +1,575 @@
+//! Claude Code stream-json decoding
+//!
+//! With `claude --output-format stream-json` Claude emits one JSON event per
+//! line instead of a TUI. When the `stream_json` detection method is
+//! selected, panes that start producing these events are decoded
+//! structurally: tool calls, token usage, session ID and result events update
+//! `ClaudeState` directly, and each event is rendered as readable text so the
+//! pane still shows what Claude is doing.
+//!
+//! Anything that isn't a stream-json event (stderr, a shell prompt after
+//! Claude exits) is passed through unchanged.
+
+use serde_json::Value;
+
+use fugue_protocol::{AgentState, ClaudeActivity, ClaudeState};
+
+/// Longest partial JSON line held back while waiting for its newline
+const MAX_PENDING_LINE: usize = 4 * 1024 * 1024;
+
+/// Longest tool argument summary shown in the rendered view
+const MAX_SUMMARY_CHARS: usize = 80;
+
+const DIM: &str = "\x1b[2m";
+const BOLD: &str = "\x1b[1m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Output of feeding a chunk through the decoder
+#[derive(Debug, Default)]
+pub struct DecodedOutput {
+    /// Bytes to show in the pane instead of the raw chunk
+    pub display: Vec<u8>,
+    /// New agent state, if any event changed it
+    pub state: Option<AgentState>,
+}
+
+/// Decoder for one pane's stream-json output
+#[derive(Debug, Default)]
+pub struct StreamJsonDecoder {
+    /// Whether the pane is currently producing stream-json
+    active: bool,
+    /// Incomplete line carried over from the previous chunk
+    pending: Vec<u8>,
+    /// Structured Claude state built from events
+    state: ClaudeState,
+    /// Tool currently being run, if any
+    current_tool: Option<String>,
+    /// Cost reported by the most recent result event
+    cost_usd: Option<f64>,
+    /// Turns reported by the most recent result event
+    num_turns: Option<u64>,
+    /// Set after a result event; the next non-JSON line ends stream-json mode
+    finished: bool,
+    /// Whether state changed since the last `DecodedOutput`
+    dirty: bool,
+}
+
+impl StreamJsonDecoder {
+    /// Create an inactive decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the pane is currently decoded as stream-json
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Current state as an `AgentState`
+    pub fn agent_state(&self) -> AgentState {
+        let mut state: AgentState = self.state.clone().into();
+        if let Some(tool) = &self.current_tool {
+            state.set_metadata("tool", Value::String(tool.clone()));
+        }
+        if let Some(cost) = self.cost_usd {
+            state.set_metadata("cost_usd", Value::from(cost));
+        }
+        if let Some(turns) = self.num_turns {
+            state.set_metadata("num_turns", Value::from(turns));
+        }
+        state.set_metadata("output_format", Value::String("stream-json".into()));
+        state
+    }
+
+    /// Feed a chunk of pane output
+    ///
+    /// Returns `None` if the pane isn't producing stream-json (and `detect` is
+    /// false or no event was found), in which case the caller handles the
+    /// chunk as ordinary terminal output.
+    pub fn feed(&mut self, data: &[u8], detect: bool) -> Option<DecodedOutput> {
+        let mut out = DecodedOutput::default();
+
+        if !self.active {
+            if !detect {
+                return None;
+            }
+            // Activate at the first complete line that is a stream-json event
+            let start = Self::find_first_event(data)?;
+            out.display.extend_from_slice(&data[..start]);
+            self.activate();
+            self.decode(&data[start..], &mut out);
+        } else {
+            self.decode(data, &mut out);
+        }
+
+        if self.dirty {
+            self.dirty = false;
+            out.state = Some(self.agent_state());
+        }
+        Some(out)
+    }
+
+    /// Reset to the inactive state
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn activate(&mut self) {
+        *self = Self {
+            active: true,
+            dirty: true,
+            ..Self::default()
+        };
+    }
+
+    /// Offset of the first complete line in `data` that is a stream-json event
+    fn find_first_event(data: &[u8]) -> Option<usize> {
+        let mut offset = 0;
+        for line in data.split_inclusive(|&b| b == b'\n') {
+            if line.ends_with(b"\n") && Self::parse_event(line).is_some() {
+                return Some(offset);
+            }
+            offset += line.len();
+        }
+        None
+    }
+
+    /// Parse a line as a stream-json event (a JSON object with a string `type`)
+    fn parse_event(line: &[u8]) -> Option<Value> {
+        let trimmed = line.trim_ascii();
+        if !trimmed.starts_with(b"{") {
+            return None;
+        }
+        let value: Value = serde_json::from_slice(trimmed).ok()?;
+        value.get("type")?.as_str()?;
+        value.get("session_id")?.as_str()?;
+        Some(value)
+    }
+
+    fn decode(&mut self, data: &[u8], out: &mut DecodedOutput) {
+        self.pending.extend_from_slice(data);
+        let buffered = std::mem::take(&mut self.pending);
+
+        let mut rest: &[u8] = &buffered;
+        while let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+            let (line, tail) = rest.split_at(pos + 1);
+            rest = tail;
+
+            if !self.active {
+                out.display.extend_from_slice(line);
+                continue;
+            }
+            match Self::parse_event(line) {
+                Some(event) => self.handle_event(&event, &mut out.display),
+                None => {
+                    if self.finished && !line.trim_ascii().is_empty() {
+                        // Claude exited; back to ordinary terminal output
+                        self.active = false;
+                    }
+                    out.display.extend_from_slice(line);
+                }
+            }
+        }
+
+        // Hold back a partial line only while it could still be a JSON event
+        if self.active && rest.first() == Some(&b'{') && rest.len() <= MAX_PENDING_LINE {
+            self.pending = rest.to_vec();
+        } else {
+            if self.active && self.finished && !rest.trim_ascii().is_empty() {
+                self.active = false;
+            }
+            out.display.extend_from_slice(rest);
+        }
+    }
+
+    fn set_activity(&mut self, activity: ClaudeActivity) {
+        if self.state.activity != activity {
+            self.state.activity = activity;
+            self.dirty = true;
+        }
+    }
+
+    fn set_tool(&mut self, tool: Option<String>) {
+        if self.current_tool != tool {
+            self.current_tool = tool;
+            self.dirty = true;
+        }
+    }
+
+    fn handle_event(&mut self, event: &Value, display: &mut Vec<u8>) {
+        if let Some(session_id) = event["session_id"].as_str() {
+            if self.state.session_id.as_deref() != Some(session_id) {
+                self.state.session_id = Some(session_id.to_string());
+                self.dirty = true;
+            }
+        }
+        self.finished = false;
+
+        match event["type"].as_str().unwrap_or_default() {
+            "system" if event["subtype"] == "init" => {
+                if let Some(model) = event["model"].as_str() {
+                    self.state.model = Some(model.to_string());
+                    self.dirty = true;
+                }
+                self.set_activity(ClaudeActivity::Thinking);
+                let model = event["model"].as_str().unwrap_or("unknown model");
+                push_line(
+                    display,
+                    &format!(
+                        "{DIM}● Claude session {} · {}{RESET}",
+                        event["session_id"].as_str().unwrap_or_default(),
+                        model
+                    ),
+                );
+            }
+            "assistant" => self.handle_assistant(&event["message"], display),
+            "user" => self.handle_user(&event["message"], display),
+            "result" => self.handle_result(event, display),
+            "stream_event" => self.handle_stream_event(&event["event"]),
+            _ => {}
+        }
+    }
+
+    fn handle_assistant(&mut self, message: &Value, display: &mut Vec<u8>) {
+        if let Some(model) = message["model"].as_str() {
+            if self.state.model.as_deref() != Some(model) {
+                self.state.model = Some(model.to_string());
+                self.dirty = true;
+            }
+        }
+
+        let usage = &message["usage"];
+        if usage.is_object() {
+            // Input already includes the whole conversation, so this is the
+            // context size after this message
+            let tokens = [
+                "input_tokens",
+                "cache_creation_input_tokens",
+                "cache_read_input_tokens",
+                "output_tokens",
+            ]
+            .iter()
+            .filter_map(|key| usage[*key].as_u64())
+            .sum::<u64>();
+            if self.state.tokens_used != Some(tokens) {
+                self.state.tokens_used = Some(tokens);
+                self.dirty = true;
+            }
+        }
+
+        for block in message["content"].as_array().into_iter().flatten() {
+            match block["type"].as_str().unwrap_or_default() {
+                "text" => {
+                    self.set_tool(None);
+                    self.set_activity(ClaudeActivity::Coding);
+                    let text = block["text"].as_str().unwrap_or_default();
+                    push_line(display, &format!("{BOLD}●{RESET} {}", text.trim_end()));
+                }
+                "thinking" => {
+                    self.set_activity(ClaudeActivity::Thinking);
+                    push_line(display, &format!("{DIM}✻ Thinking…{RESET}"));
+                }
+                "tool_use" => {
+                    let name = block["name"].as_str().unwrap_or("tool").to_string();
+                    push_line(
+                        display,
+                        &format!("{CYAN}⏺ {}{RESET}({})", name, tool_summary(&block["input"])),
+                    );
+                    self.set_tool(Some(name));
+                    self.set_activity(ClaudeActivity::ToolUse);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn handle_user(&mut self, message: &Value, display: &mut Vec<u8>) {
+        for block in message["content"].as_array().into_iter().flatten() {
+            if block["type"] != "tool_result" {
+                continue;
+            }
+            let content = match &block["content"] {
+                Value::String(s) => s.clone(),
+                Value::Array(parts) => parts
+                    .iter()
+                    .filter_map(|p| p["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => String::new(),
+            };
+            let mut lines = content.lines();
+            let first = truncate(lines.next().unwrap_or_default(), MAX_SUMMARY_CHARS);
+            let more = lines.count();
+            let color = if block["is_error"] == true { RED } else { DIM };
+            let suffix = if more > 0 {
+                format!(" (+{} lines)", more)
+            } else {
+                String::new()
+            };
+            push_line(display, &format!("  {color}⎿ {}{}{RESET}", first, suffix));
+        }
+
+        // Claude continues after tool results come back
+        self.set_tool(None);
+        self.set_activity(ClaudeActivity::Thinking);
+    }
+
+    fn handle_result(&mut self, event: &Value, display: &mut Vec<u8>) {
+        self.cost_usd = event["total_cost_usd"].as_f64().or(self.cost_usd);
+        self.num_turns = event["num_turns"].as_u64().or(self.num_turns);
+        self.dirty = true;
+        self.set_tool(None);
+        self.set_activity(ClaudeActivity::Idle);
+        self.finished = true;
+
+        let mut summary = Vec::new();
+        if let Some(turns) = event["num_turns"].as_u64() {
+            summary.push(format!("{} turns", turns));
+        }
+        if let Some(ms) = event["duration_ms"].as_u64() {
+            summary.push(format!("{:.1}s", ms as f64 / 1000.0));
+        }
+        if let Some(cost) = event["total_cost_usd"].as_f64() {
+            summary.push(format!("${:.4}", cost));
+        }
+        let summary = summary.join(" · ");
+
+        if event["is_error"] == true || event["subtype"].as_str().is_some_and(|s| s != "success") {
+            let subtype = event["subtype"].as_str().unwrap_or("error");
+            push_line(display, &format!("{RED}✗ {}{RESET} {}", subtype, summary));
+        } else {
+            push_line(display, &format!("{GREEN}✓ Done{RESET} {}", summary));
+        }
+    }
+
+    /// Partial-message events (`--include-partial-messages`) only move the activity
+    fn handle_stream_event(&mut self, event: &Value) {
+        match event["type"].as_str().unwrap_or_default() {
+            "message_start" => self.set_activity(ClaudeActivity::Thinking),
+            "content_block_start" => match event["content_block"]["type"].as_str() {
+                Some("tool_use") => {
+                    let name = event["content_block"]["name"].as_str().map(String::from);
+                    self.set_tool(name);
+                    self.set_activity(ClaudeActivity::ToolUse);
+                }
+                Some("thinking") => self.set_activity(ClaudeActivity::Thinking),
+                Some("text") => self.set_activity(ClaudeActivity::Coding),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+/// Append a rendered line, translating newlines for the terminal
+fn push_line(display: &mut Vec<u8>, line: &str) {
+    display.extend_from_slice(line.replace('\n', "\r\n").as_bytes());
+    display.extend_from_slice(b"\r\n");
+}
+
+/// Short, single-line description of a tool call's input
+fn tool_summary(input: &Value) -> String {
+    let summary = ["command", "file_path", "path", "pattern", "url", "description", "prompt"]
+        .iter()
+        .find_map(|key| input[*key].as_str())
+        .map(String::from)
+        .unwrap_or_else(|| match input {
+            Value::Object(map) if map.is_empty() => String::new(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        });
+    truncate(summary.lines().next().unwrap_or_default(), MAX_SUMMARY_CHARS)
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut truncated: String = text.chars().take(max_chars - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::AgentActivity;
+
+    const SESSION: &str = "0b6a3a4e-4c1a-4a2b-9d1e-1f2e3d4c5b6a";
+
+    fn line(value: Value) -> Vec<u8> {
+        let mut bytes = serde_json::to_vec(&value).unwrap();
+        bytes.extend_from_slice(b"\r\n");
+        bytes
+    }
+
+    fn init() -> Vec<u8> {
+        line(serde_json::json!({
+            "type": "system",
+            "subtype": "init",
+            "session_id": SESSION,
+            "model": "claude-sonnet-4-5",
+            "tools": ["Bash", "Read"],
+        }))
+    }
+
+    fn text(display: &[u8]) -> String {
+        String::from_utf8_lossy(&strip_ansi_escapes::strip(display)).into_owned()
+    }
+
+    #[test]
+    fn test_ignores_plain_output() {
+        let mut decoder = StreamJsonDecoder::new();
+        assert!(decoder.feed(b"$ ls\r\nCargo.toml\r\n", true).is_none());
+        assert!(decoder.feed(b"{\"not\": \"an event\"}\r\n", true).is_none());
+        assert!(!decoder.is_active());
+
+        // Detection disabled
+        assert!(decoder.feed(&init(), false).is_none());
+    }
+
+    #[test]
+    fn test_init_activates_and_sets_session() {
+        let mut decoder = StreamJsonDecoder::new();
+        let mut data = b"$ claude -p hi --output-format stream-json\r\n".to_vec();
+        data.extend(init());
+
+        let out = decoder.feed(&data, true).unwrap();
+        assert!(decoder.is_active());
+
+        let shown = text(&out.display);
+        assert!(shown.starts_with("$ claude -p hi"));
+        assert!(shown.contains("Claude session"));
+        assert!(!shown.contains("\"type\""));
+
+        let state = out.state.unwrap();
+        assert_eq!(state.session_id.as_deref(), Some(SESSION));
+        assert_eq!(state.activity, AgentActivity::Processing);
+        assert_eq!(state.get_metadata("model"), Some(&serde_json::json!("claude-sonnet-4-5")));
+    }
+
+    #[test]
+    fn test_tool_use_usage_and_result() {
+        let mut decoder = StreamJsonDecoder::new();
+        decoder.feed(&init(), true).unwrap();
+
+        let out = decoder
+            .feed(
+                &line(serde_json::json!({
+                    "type": "assistant",
+                    "session_id": SESSION,
+                    "message": {
+                        "model": "claude-sonnet-4-5",
+                        "content": [
+                            {"type": "text", "text": "Let me check."},
+                            {"type": "tool_use", "id": "t1", "name": "Bash", "input": {"command": "cargo test"}}
+                        ],
+                        "usage": {"input_tokens": 100, "cache_read_input_tokens": 900, "output_tokens": 20}
+                    }
+                })),
+                true,
+            )
+            .unwrap();
+        let shown = text(&out.display);
+        assert!(shown.contains("Let me check."));
+        assert!(shown.contains("Bash(cargo test)"));
+        let state = out.state.unwrap();
+        assert_eq!(state.activity, AgentActivity::ToolUse);
+        assert_eq!(state.get_metadata("tokens_used"), Some(&serde_json::json!(1020)));
+        assert_eq!(state.get_metadata("tool"), Some(&serde_json::json!("Bash")));
+
+        let out = decoder
+            .feed(
+                &line(serde_json::json!({
+                    "type": "user",
+                    "session_id": SESSION,
+                    "message": {"content": [
+                        {"type": "tool_result", "tool_use_id": "t1", "content": "ok\nline2\nline3"}
+                    ]}
+                })),
+                true,
+            )
+            .unwrap();
+        assert!(text(&out.display).contains("⎿ ok (+2 lines)"));
+        assert_eq!(out.state.unwrap().activity, AgentActivity::Processing);
+
+        let out = decoder
+            .feed(
+                &line(serde_json::json!({
+                    "type": "result",
+                    "subtype": "success",
+                    "is_error": false,
+                    "session_id": SESSION,
+                    "duration_ms": 2500,
+                    "num_turns": 3,
+                    "total_cost_usd": 0.0123,
+                    "result": "done"
+                })),
+                true,
+            )
+            .unwrap();
+        assert!(text(&out.display).contains("✓ Done 3 turns · 2.5s · $0.0123"));
+        let state = out.state.unwrap();
+        assert_eq!(state.activity, AgentActivity::Idle);
+        assert_eq!(state.get_metadata("cost_usd"), Some(&serde_json::json!(0.0123)));
+        assert!(state.get_metadata("tool").is_none());
+    }
+
+    #[test]
+    fn test_event_split_across_chunks() {
+        let mut decoder = StreamJsonDecoder::new();
+        decoder.feed(&init(), true).unwrap();
+
+        let event = line(serde_json::json!({
+            "type": "assistant",
+            "session_id": SESSION,
+            "message": {"content": [{"type": "text", "text": "hello"}]}
+        }));
+        let (a, b) = event.split_at(event.len() / 2);
+
+        let out = decoder.feed(a, true).unwrap();
+        assert!(out.display.is_empty());
+        let out = decoder.feed(b, true).unwrap();
+        assert!(text(&out.display).contains("hello"));
+    }
+
+    #[test]
+    fn test_shell_prompt_after_result_deactivates() {
+        let mut decoder = StreamJsonDecoder::new();
+        decoder.feed(&init(), true).unwrap();
+        decoder
+            .feed(
+                &line(serde_json::json!({"type": "result", "subtype": "success", "session_id": SESSION})),
+                true,
+            )
+            .unwrap();
+
+        // A prompt without a trailing newline must be shown immediately
+        let out = decoder.feed(b"$ ", true).unwrap();
+        assert_eq!(out.display, b"$ ");
+        assert!(!decoder.is_active());
+        assert!(decoder.feed(b"ls\r\n", true).is_none());
+    }
+
+    #[test]
+    fn test_error_result() {
+        let mut decoder = StreamJsonDecoder::new();
+        decoder.feed(&init(), true).unwrap();
+        let out = decoder
+            .feed(
+                &line(serde_json::json!({
+                    "type": "result",
+                    "subtype": "error_max_turns",
+                    "is_error": true,
+                    "session_id": SESSION
+                })),
+                true,
+            )
+            .unwrap();
+        assert!(text(&out.display).contains("✗ error_max_turns"));
+    }
+}
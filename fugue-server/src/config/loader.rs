@@ -4,7 +4,7 @@ use std::path::Path;
 
 use fugue_utils::{config_file, CcmuxError, Result};
 
-use super::AppConfig;
+use super::{AppConfig, BudgetScopeKind, NotificationSinkConfig};
 
 /// Configuration loader
 pub struct ConfigLoader;
@@ -59,6 +59,56 @@ impl ConfigLoader {
             ));
         }
 
+        // Validate agent detector patterns
+        crate::agents::configured::compile_all(&config.agent_detectors)
+            .map_err(CcmuxError::config)?;
+
+        // Validate usage budgets
+        for (idx, budget) in config.usage.budgets.iter().enumerate() {
+            if budget.max_tokens.is_none() && budget.max_cost_usd.is_none() {
+                return Err(CcmuxError::config(format!(
+                    "usage.budgets[{}] needs max_tokens or max_cost_usd",
+                    idx
+                )));
+            }
+            if budget.scope == BudgetScopeKind::Tag && budget.tag.is_none() {
+                return Err(CcmuxError::config(format!(
+                    "usage.budgets[{}] has scope = \"tag\" but no tag",
+                    idx
+                )));
+            }
+        }
+
+        // Validate hooks
+        for (idx, hook) in config.hooks.iter().enumerate() {
+            if hook.run.is_none() && hook.fugue.is_none() {
+                return Err(CcmuxError::config(format!(
+                    "hooks[{}] needs run or fugue",
+                    idx
+                )));
+            }
+        }
+
+        // Validate notification sinks and routes
+        for (name, sink) in &config.notifications.sinks {
+            if let NotificationSinkConfig::Webhook { url, .. } = sink {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    return Err(CcmuxError::config(format!(
+                        "notifications.sinks.{} url must start with http:// or https://",
+                        name
+                    )));
+                }
+            }
+        }
+        for (idx, route) in config.notifications.routes.iter().enumerate() {
+            if let Some(sink) = route.sinks.iter().find(|sink| !config.notifications.sinks.contains_key(*sink)) {
+                return Err(CcmuxError::config(format!(
+                    "notifications.routes[{}] uses unknown sink '{}'",
+                    idx, sink
+                )));
+            }
+        }
+
         Ok(())
     }
 
@@ -301,4 +351,113 @@ mod tests {
         let result = ConfigLoader::parse(content, Path::new("test.toml"));
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_and_validate_agent_detectors() {
+        let content = r#"
+            [agent_detectors.aider]
+            presence = ['^Aider v\d']
+            session_id = 'session (\S+)'
+
+            [agent_detectors.aider.activity]
+            processing = ['Thinking']
+
+            [agent_detectors.aider.activity.custom]
+            committing = ['Commit [0-9a-f]{7}']
+        "#;
+
+        let mut config = ConfigLoader::parse(content, Path::new("test.toml")).unwrap();
+        let aider = &config.agent_detectors["aider"];
+        assert_eq!(aider.presence, vec![r"^Aider v\d".to_string()]);
+        assert_eq!(aider.activity.processing, vec!["Thinking".to_string()]);
+        assert!(aider.activity.custom.contains_key("committing"));
+        assert!(ConfigLoader::validate(&config).is_ok());
+
+        config
+            .agent_detectors
+            .get_mut("aider")
+            .unwrap()
+            .activity
+            .idle = vec!["(".to_string()];
+        assert!(ConfigLoader::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_parse_and_validate_usage_budgets() {
+        let content = r#"
+            [usage]
+            default_price_per_mtok = 2.5
+
+            [usage.prices]
+            "claude-opus" = 30.0
+
+            [[usage.budgets]]
+            scope = "session"
+            max_cost_usd = 20.0
+            action = "pause"
+
+            [[usage.budgets]]
+            scope = "tag"
+            tag = "frontend"
+            max_tokens = 5000000
+        "#;
+
+        let mut config = ConfigLoader::parse(content, Path::new("test.toml")).unwrap();
+        assert_eq!(config.usage.prices["claude-opus"], 30.0);
+        assert_eq!(config.usage.budgets[0].scope, BudgetScopeKind::Session);
+        assert_eq!(config.usage.budgets[0].action, crate::config::BudgetAction::Pause);
+        assert_eq!(config.usage.budgets[1].tag.as_deref(), Some("frontend"));
+        assert!(ConfigLoader::validate(&config).is_ok());
+
+        config.usage.budgets[1].tag = None;
+        assert!(ConfigLoader::validate(&config).is_err());
+
+        config.usage.budgets[1].tag = Some("frontend".into());
+        config.usage.budgets[1].max_tokens = None;
+        assert!(ConfigLoader::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_hooks() {
+        let content = r#"
+            [[hooks]]
+            event = "pane-exited"
+            run = "notify-send done"
+        "#;
+
+        let mut config = ConfigLoader::parse(content, Path::new("test.toml")).unwrap();
+        assert!(ConfigLoader::validate(&config).is_ok());
+
+        config.hooks[0].run = None;
+        assert!(ConfigLoader::validate(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_notification_routes() {
+        let content = r#"
+            [notifications.sinks.chat]
+            type = "webhook"
+            url = "http://127.0.0.1:9000/hook"
+
+            [[notifications.routes]]
+            sinks = ["chat"]
+        "#;
+
+        let mut config = ConfigLoader::parse(content, Path::new("test.toml")).unwrap();
+        assert!(ConfigLoader::validate(&config).is_ok());
+
+        config.notifications.routes[0].sinks.push("desktop".into());
+        assert!(ConfigLoader::validate(&config).is_err());
+
+        config.notifications.routes[0].sinks.pop();
+        config.notifications.sinks.insert(
+            "chat".into(),
+            NotificationSinkConfig::Webhook {
+                url: "ftp://example.com".into(),
+                template: None,
+                headers: Default::default(),
+            },
+        );
+        assert!(ConfigLoader::validate(&config).is_err());
+    }
 }
@@ -2,7 +2,7 @@
 
 use fugue_utils::{SessionLogConfig, SessionLogLevel};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// Root configuration
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -22,6 +22,295 @@ pub struct AppConfig {
     pub presets: HashMap<String, AgentPreset>,
     /// Prometheus metrics endpoint configuration (FEAT-074)
     pub metrics: MetricsConfig,
+    /// TLS and authentication for the TCP listener
+    pub tls: TlsConfig,
+    /// Streamable HTTP transport for MCP
+    pub mcp_http: McpHttpConfig,
+    /// Additional agent detectors, keyed by agent type (e.g. `[agent_detectors.aider]`)
+    pub agent_detectors: BTreeMap<String, AgentDetectorConfig>,
+    /// Token/cost accounting and budgets
+    pub usage: UsageConfig,
+    /// Commands run on server events (`[[hooks]]`)
+    pub hooks: Vec<HookConfig>,
+    /// Desktop, terminal and webhook notifications
+    pub notifications: NotificationsConfig,
+    /// Bumped on every reload, so consumers can tell cheaply that the
+    /// config changed
+    #[serde(skip)]
+    pub generation: u64,
+}
+
+/// Where notifications are delivered (`[notifications]`)
+///
+/// Notifications come from panes (`notify` sideband commands, mail) and from
+/// agents finishing or waiting for input. Each one goes to the sinks of every
+/// route that matches it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Sinks by name (e.g. `[notifications.sinks.desktop]`)
+    pub sinks: BTreeMap<String, NotificationSinkConfig>,
+    /// Routing rules (`[[notifications.routes]]`)
+    pub routes: Vec<NotificationRoute>,
+}
+
+/// A place notifications can be sent
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum NotificationSinkConfig {
+    /// freedesktop notification over the session D-Bus
+    Desktop {
+        /// Milliseconds before the notification closes (default: server's choice)
+        #[serde(default)]
+        timeout_ms: Option<i32>,
+    },
+    /// Bell or OSC notification written by attached clients
+    Terminal {
+        #[serde(default)]
+        alert: TerminalAlertKind,
+    },
+    /// HTTP POST of a JSON body
+    Webhook {
+        /// `http://` or `https://` URL
+        url: String,
+        /// Body with `{{title}}`, `{{message}}`, `{{level}}`, `{{event}}`,
+        /// `{{session}}`, `{{session_id}}`, `{{pane_id}}` and `{{timestamp}}`
+        /// replaced by JSON-escaped values (default: all fields as an object)
+        #[serde(default)]
+        template: Option<String>,
+        /// Extra request headers, e.g. `Authorization`
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+}
+
+/// Terminal alert used by a `terminal` sink
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalAlertKind {
+    /// Ring the bell
+    Bell,
+    /// OSC 9 notification (iTerm2, Windows Terminal, kitty)
+    #[default]
+    Osc9,
+    /// OSC 777 notification with a title (foot, Ghostty, rxvt)
+    Osc777,
+}
+
+/// A routing rule (`[[notifications.routes]]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NotificationRoute {
+    /// Names of the sinks to deliver to
+    pub sinks: Vec<String>,
+    /// Only notifications from this session (name or UUID)
+    pub session: Option<String>,
+    /// Lowest level delivered
+    pub min_level: NotificationLevel,
+    /// Only when no client is attached to the session
+    pub detached_only: bool,
+}
+
+/// Severity of a notification
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationLevel {
+    #[default]
+    Info,
+    Warning,
+    Error,
+}
+
+/// A command run when something happens on the server (`[[hooks]]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HookConfig {
+    /// Event that runs the hook
+    pub event: HookEvent,
+    /// Only run for this session (name or UUID)
+    pub session: Option<String>,
+    /// `agent-state-changed` only: activity the agent is leaving
+    pub from: Option<String>,
+    /// `agent-state-changed` only: activity the agent is entering
+    pub to: Option<String>,
+    /// Shell command, run with `sh -c`
+    pub run: Option<String>,
+    /// `fugue-compat` command line, e.g. `send-keys -t api "make test" Enter`
+    pub fugue: Option<String>,
+}
+
+/// Server events hooks can run on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    /// A pane closed, either because its process exited or it was killed
+    PaneExited,
+    /// A session was created
+    SessionCreated,
+    /// A client attached to a session
+    ClientAttached,
+    /// An agent's activity changed (e.g. processing -> idle)
+    AgentStateChanged,
+    /// A pane sent mail to the orchestrator
+    MailReceived,
+}
+
+impl HookEvent {
+    /// Name as written in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PaneExited => "pane-exited",
+            HookEvent::SessionCreated => "session-created",
+            HookEvent::ClientAttached => "client-attached",
+            HookEvent::AgentStateChanged => "agent-state-changed",
+            HookEvent::MailReceived => "mail-received",
+        }
+    }
+}
+
+/// Token and cost accounting for agent panes
+///
+/// Agents that report their cost (Claude stream-json) are charged what they
+/// report; for the rest cost is estimated from token counts using a blended
+/// per-model price.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Blended USD per million tokens by model name prefix, overriding the
+    /// built-in table (longest matching prefix wins)
+    pub prices: BTreeMap<String, f64>,
+    /// Price for models matching no prefix (default: none, cost not estimated)
+    pub default_price_per_mtok: Option<f64>,
+    /// Usage limits, checked whenever a pane's usage changes
+    pub budgets: Vec<BudgetConfig>,
+}
+
+/// A usage limit (`[[usage.budgets]]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BudgetConfig {
+    /// What the limit applies to
+    pub scope: BudgetScopeKind,
+    /// Tag the limit applies to (required for `scope = "tag"`)
+    pub tag: Option<String>,
+    /// Token limit
+    pub max_tokens: Option<u64>,
+    /// Cost limit in USD
+    pub max_cost_usd: Option<f64>,
+    /// What to do when the limit is exceeded
+    pub action: BudgetAction,
+}
+
+/// Scope of a `BudgetConfig`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetScopeKind {
+    /// Each agent pane separately
+    #[default]
+    Pane,
+    /// Each session, summed over its panes
+    Session,
+    /// All sessions carrying `tag`, summed
+    Tag,
+}
+
+/// Action taken when a budget is exceeded
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAction {
+    /// Broadcast `BudgetExceeded` only
+    #[default]
+    Notify,
+    /// Also stop (SIGSTOP) the agent processes in scope until resumed
+    Pause,
+}
+
+/// Streamable HTTP/SSE transport for MCP
+///
+/// Lets many agents share one daemon-hosted MCP endpoint and receive pushed
+/// events over SSE instead of each spawning a stdio `mcp-bridge`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpHttpConfig {
+    /// Enable the MCP HTTP endpoint (default: false)
+    pub enabled: bool,
+    /// Address to listen on (default: "127.0.0.1:9899")
+    pub listen_addr: String,
+    /// Bearer token required in the `Authorization` header, if set
+    pub auth_token: Option<String>,
+    /// Extra `Origin` values to accept besides localhost (DNS rebinding protection)
+    pub allowed_origins: Vec<String>,
+    /// Drop MCP sessions with no requests or open streams for this long (default: 3600)
+    pub session_idle_timeout_secs: u64,
+}
+
+impl Default for McpHttpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: "127.0.0.1:9899".to_string(),
+            auth_token: None,
+            allowed_origins: Vec::new(),
+            session_idle_timeout_secs: 3600,
+        }
+    }
+}
+
+/// A config-defined agent detector
+///
+/// Every pattern is a regex matched against pane output with escape
+/// sequences stripped. Detectors are tried after the built-in Claude, Gemini
+/// and Codex detectors and are reloaded when `config.toml` changes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AgentDetectorConfig {
+    /// Any match marks the agent as present
+    pub presence: Vec<String>,
+    /// Confidence reported once detected, 0-100 (default: 90)
+    pub confidence: Option<u8>,
+    /// Patterns that select the agent's current activity
+    pub activity: AgentActivityPatterns,
+    /// Regex whose first capture group is the agent's session ID
+    pub session_id: Option<String>,
+    /// Metadata key -> regex whose first capture group is the value
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Per-activity patterns for a config-defined agent detector
+///
+/// Checked in field order; the first activity with a matching pattern wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct AgentActivityPatterns {
+    pub awaiting_confirmation: Vec<String>,
+    pub tool_use: Vec<String>,
+    pub generating: Vec<String>,
+    pub processing: Vec<String>,
+    pub idle: Vec<String>,
+    /// Agent-specific states, reported as `AgentActivity::Custom(name)`
+    pub custom: BTreeMap<String, Vec<String>>,
+}
+
+/// TLS and authentication settings for `general.listen_tcp`
+///
+/// With `enabled = false` the TCP listener speaks plaintext, which is only
+/// safe behind an SSH tunnel. `client_ca_file` turns on mutual TLS and
+/// `auth_token`/`auth_token_file` require a pre-shared token before `Connect`;
+/// either or both may be used.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// Wrap TCP connections in TLS (default: false)
+    pub enabled: bool,
+    /// Server certificate chain (PEM)
+    pub cert_file: Option<String>,
+    /// Server private key (PEM)
+    pub key_file: Option<String>,
+    /// CA (PEM) that client certificates must chain to; enables mutual TLS
+    pub client_ca_file: Option<String>,
+    /// Pre-shared token clients must present before the handshake
+    pub auth_token: Option<String>,
+    /// File containing the pre-shared token (takes precedence over `auth_token`)
+    pub auth_token_file: Option<String>,
 }
 
 /// Prometheus metrics endpoint configuration (FEAT-074)
@@ -132,6 +421,29 @@ pub struct ClaudeHarnessConfig {
     pub context_limit: Option<usize>,
 }
 
+impl ClaudeHarnessConfig {
+    /// Settings for the pane's isolated `.claude.json`
+    pub fn to_settings(&self) -> serde_json::Map<String, serde_json::Value> {
+        let mut settings = serde_json::Map::new();
+        if let Some(m) = &self.model {
+            settings.insert("model".to_string(), serde_json::json!(m));
+        }
+        if let Some(c) = self.context_limit {
+            settings.insert("context_limit".to_string(), serde_json::json!(c));
+        }
+        if let Some(sp) = &self.system_prompt {
+            settings.insert("system_prompt".to_string(), serde_json::json!(sp));
+        }
+        if let Some(dsp) = &self.dangerously_skip_permissions {
+            settings.insert("dangerously_skip_permissions".to_string(), serde_json::json!(dsp));
+        }
+        if let Some(at) = &self.allowed_tools {
+            settings.insert("allowed_tools".to_string(), serde_json::json!(at));
+        }
+        settings
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct GeminiHarnessConfig {
     pub model: Option<String>,
@@ -483,6 +795,8 @@ pub struct ScrollbackConfig {
     pub orchestrator: usize,
     /// Scrollback for worker sessions (minimal buffer)
     pub worker: usize,
+    /// Keeping lines evicted from memory on disk
+    pub spill: SpillConfig,
     /// Custom session type overrides
     #[serde(flatten)]
     pub custom: HashMap<String, usize>,
@@ -494,11 +808,41 @@ impl Default for ScrollbackConfig {
             default: 1000,
             orchestrator: 50000,
             worker: 500,
+            spill: SpillConfig::default(),
             custom: HashMap::new(),
         }
     }
 }
 
+/// Scrollback spill-to-disk settings (`[terminal.scrollback.spill]`)
+///
+/// When enabled, lines that fall out of a pane's in-memory scrollback are
+/// appended to compressed segment files instead of being dropped, and stay
+/// readable through `capture-pane -S`, `fugue_read_pane` and copy mode.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct SpillConfig {
+    /// Spill evicted lines to disk (default: false)
+    pub enabled: bool,
+    /// Segment directory (default: `scrollback` in the cache directory)
+    pub dir: Option<String>,
+    /// Lines per compressed segment
+    pub segment_lines: usize,
+    /// Disk limit per pane in MB; the oldest segments are deleted beyond it (0 = unlimited)
+    pub max_disk_mb: u64,
+}
+
+impl Default for SpillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            segment_lines: 4096,
+            max_disk_mb: 1024,
+        }
+    }
+}
+
 impl ScrollbackConfig {
     /// Minimum allowed scrollback lines
     pub const MIN_LINES: usize = 100;
@@ -556,12 +900,20 @@ impl Default for ClaudeConfig {
     }
 }
 
+impl ClaudeConfig {
+    /// Detection method in effect, or `None` if detection is disabled
+    pub fn active_detection_method(&self) -> Option<DetectionMethod> {
+        self.detection_enabled.then_some(self.detection_method)
+    }
+}
+
 /// Claude detection method
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum DetectionMethod {
     #[default]
     Pty,
+    #[serde(alias = "stream_json")]
     StreamJson,
     Visual,
 }
@@ -586,6 +938,8 @@ pub struct PersistenceConfig {
     pub compression_method: String,
     /// Sync WAL on each write (true = safer, false = faster)
     pub sync_on_write: bool,
+    /// How pane processes are brought back after a restart
+    pub restore: RestoreConfig,
 }
 
 impl Default for PersistenceConfig {
@@ -599,10 +953,54 @@ impl Default for PersistenceConfig {
             max_checkpoints: 5,
             compression_method: "lz4".to_string(),
             sync_on_write: true,
+            restore: RestoreConfig::default(),
         }
     }
 }
 
+/// What to run in a pane when it is restored
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RestorePolicy {
+    /// Re-run the command the pane was originally launched with
+    Rerun,
+    /// Run the rule's configured `command` through `sh -c`
+    Command,
+    /// Start the default shell
+    #[default]
+    Shell,
+}
+
+/// Process resurrection settings (`[persistence.restore]`)
+///
+/// Rules are checked first, then the built-in strategies (agent resume and
+/// common long-running tools), then `default_policy`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RestoreConfig {
+    /// Policy for panes that no rule or built-in strategy matches
+    pub default_policy: RestorePolicy,
+    /// Disable the built-in strategies, leaving only `rules` and `default_policy`
+    pub disable_builtin: bool,
+    /// Per-program overrides (`[[persistence.restore.rules]]`)
+    pub rules: Vec<RestoreRule>,
+}
+
+/// A restore rule matching panes by the program they were running
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RestoreRule {
+    /// Program name to match, e.g. "npm" (compared against the basename)
+    pub program: String,
+    /// Only match when the command line contains this text
+    #[serde(default)]
+    pub contains: Option<String>,
+    /// Policy to apply
+    pub policy: RestorePolicy,
+    /// Command line for `policy = "command"`
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -696,6 +1094,55 @@ mod tests {
         assert_eq!(config.parser_timeout_secs, 5);
     }
 
+    #[test]
+    fn test_tls_config_defaults() {
+        let config = TlsConfig::default();
+        assert!(!config.enabled);
+        assert!(config.cert_file.is_none());
+        assert!(config.client_ca_file.is_none());
+        assert!(config.auth_token.is_none());
+    }
+
+    #[test]
+    fn test_tls_config_parse() {
+        let toml_str = r#"
+            [general]
+            listen_tcp = "0.0.0.0:9999"
+
+            [tls]
+            enabled = true
+            cert_file = "/etc/fugue/server.pem"
+            key_file = "/etc/fugue/server.key"
+            client_ca_file = "/etc/fugue/ca.pem"
+            auth_token_file = "/etc/fugue/token"
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.tls.enabled);
+        assert_eq!(config.tls.cert_file.as_deref(), Some("/etc/fugue/server.pem"));
+        assert_eq!(config.tls.client_ca_file.as_deref(), Some("/etc/fugue/ca.pem"));
+        assert_eq!(config.tls.auth_token_file.as_deref(), Some("/etc/fugue/token"));
+    }
+
+    #[test]
+    fn test_mcp_http_config_parse() {
+        let config = McpHttpConfig::default();
+        assert!(!config.enabled);
+        assert_eq!(config.listen_addr, "127.0.0.1:9899");
+
+        let toml_str = r#"
+            [mcp_http]
+            enabled = true
+            auth_token = "s3cret"
+            allowed_origins = ["https://agents.internal"]
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.mcp_http.enabled);
+        assert_eq!(config.mcp_http.listen_addr, "127.0.0.1:9899");
+        assert_eq!(config.mcp_http.auth_token.as_deref(), Some("s3cret"));
+        assert_eq!(config.mcp_http.allowed_origins, vec!["https://agents.internal"]);
+        assert_eq!(config.mcp_http.session_idle_timeout_secs, 3600);
+    }
+
     #[test]
     fn test_claude_config_defaults() {
         let config = ClaudeConfig::default();
@@ -760,6 +1207,10 @@ mod tests {
         let config: AppConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.claude.detection_method, DetectionMethod::StreamJson);
 
+        let toml_str = "[claude]\ndetection_method = \"stream_json\"";
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.claude.detection_method, DetectionMethod::StreamJson);
+
         let toml_str = "[claude]\ndetection_method = \"visual\"";
         let config: AppConfig = toml::from_str(toml_str).unwrap();
         assert_eq!(config.claude.detection_method, DetectionMethod::Visual);
@@ -1022,6 +1473,28 @@ screen_snapshot_lines = 1000
         assert_eq!(config.terminal.scrollback.worker, 500); // Default
     }
 
+    #[test]
+    fn test_scrollback_spill_config_parse() {
+        let toml_str = r#"
+            [terminal.scrollback]
+            default = 2000
+            reviewer = 3000
+
+            [terminal.scrollback.spill]
+            enabled = true
+            max_disk_mb = 0
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        let scrollback = &config.terminal.scrollback;
+        assert!(scrollback.spill.enabled);
+        assert_eq!(scrollback.spill.max_disk_mb, 0);
+        assert_eq!(scrollback.spill.segment_lines, 4096); // Default
+        assert_eq!(scrollback.lines_for_custom("reviewer"), 3000);
+        assert!(!scrollback.custom.contains_key("spill"));
+
+        assert!(!ScrollbackConfig::default().spill.enabled);
+    }
+
     // ==================== SessionLoggingConfig Tests ====================
 
     #[test]
@@ -1269,4 +1742,110 @@ screen_snapshot_lines = 1000
         assert_eq!(config.beads.query.refresh_interval, 30);
         assert_eq!(config.beads.query.socket_timeout, 1000);
     }
+
+    #[test]
+    fn test_restore_config_parse() {
+        let toml_str = r#"
+            [persistence.restore]
+            default_policy = "rerun"
+
+            [[persistence.restore.rules]]
+            program = "npm"
+            contains = "run dev"
+            policy = "command"
+            command = "npm run dev -- --port 3001"
+
+            [[persistence.restore.rules]]
+            program = "htop"
+            policy = "shell"
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        let restore = &config.persistence.restore;
+        assert_eq!(restore.default_policy, RestorePolicy::Rerun);
+        assert!(!restore.disable_builtin);
+        assert_eq!(restore.rules.len(), 2);
+        assert_eq!(restore.rules[0].policy, RestorePolicy::Command);
+        assert_eq!(restore.rules[0].contains.as_deref(), Some("run dev"));
+        assert_eq!(restore.rules[1].command, None);
+        // Other persistence settings keep their defaults
+        assert_eq!(config.persistence.checkpoint_interval_secs, 30);
+    }
+
+    #[test]
+    fn test_hooks_config_parse() {
+        let toml_str = r#"
+            [[hooks]]
+            event = "agent-state-changed"
+            from = "processing"
+            to = "idle"
+            run = "notify-send \"$FUGUE_SESSION_NAME finished\""
+
+            [[hooks]]
+            event = "pane-exited"
+            session = "api"
+            fugue = "new-session -d -s api"
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.hooks.len(), 2);
+        assert_eq!(config.hooks[0].event, HookEvent::AgentStateChanged);
+        assert_eq!(config.hooks[0].to.as_deref(), Some("idle"));
+        assert_eq!(config.hooks[1].event.name(), "pane-exited");
+        assert_eq!(config.hooks[1].session.as_deref(), Some("api"));
+        assert!(config.hooks[1].run.is_none());
+
+        assert!(toml::from_str::<AppConfig>("[[hooks]]\nevent = \"pane-died\"\nrun = \"true\"").is_err());
+        assert!(AppConfig::default().hooks.is_empty());
+    }
+
+    #[test]
+    fn test_notifications_config_parse() {
+        let toml_str = r#"
+            [notifications.sinks.desktop]
+            type = "desktop"
+
+            [notifications.sinks.term]
+            type = "terminal"
+            alert = "osc777"
+
+            [notifications.sinks.chat]
+            type = "webhook"
+            url = "https://chat.example.com/hook"
+            template = '{"text": "{{session}}: {{message}}"}'
+            headers = { Authorization = "Bearer abc" }
+
+            [[notifications.routes]]
+            sinks = ["desktop", "term"]
+
+            [[notifications.routes]]
+            sinks = ["chat"]
+            session = "api"
+            min_level = "warning"
+            detached_only = true
+        "#;
+        let config: AppConfig = toml::from_str(toml_str).unwrap();
+        let notifications = &config.notifications;
+        assert_eq!(notifications.sinks.len(), 3);
+        assert_eq!(
+            notifications.sinks["desktop"],
+            NotificationSinkConfig::Desktop { timeout_ms: None }
+        );
+        assert_eq!(
+            notifications.sinks["term"],
+            NotificationSinkConfig::Terminal { alert: TerminalAlertKind::Osc777 }
+        );
+        match &notifications.sinks["chat"] {
+            NotificationSinkConfig::Webhook { url, headers, .. } => {
+                assert_eq!(url, "https://chat.example.com/hook");
+                assert_eq!(headers["Authorization"], "Bearer abc");
+            }
+            other => panic!("Expected webhook sink, got {:?}", other),
+        }
+        assert_eq!(notifications.routes[0].min_level, NotificationLevel::Info);
+        assert!(!notifications.routes[0].detached_only);
+        assert_eq!(notifications.routes[1].min_level, NotificationLevel::Warning);
+        assert!(NotificationLevel::Error > NotificationLevel::Warning);
+
+        assert!(toml::from_str::<AppConfig>("[notifications.sinks.x]\ntype = \"pager\"").is_err());
+        assert!(AppConfig::default().notifications.routes.is_empty());
+    }
 }
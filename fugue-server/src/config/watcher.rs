@@ -101,8 +101,9 @@ impl ConfigWatcher {
         tracing::info!("Config file changed, reloading...");
 
         match ConfigLoader::load_and_validate() {
-            Ok(new_config) => {
+            Ok(mut new_config) => {
                 let old_config = config.load();
+                new_config.generation = old_config.generation + 1;
 
                 // Log significant changes
                 if old_config.general.prefix_key != new_config.general.prefix_key {
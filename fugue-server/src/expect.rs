@@ -0,0 +1,501 @@
+//! Server-side Expect
+//!
+//! Lets a client wait for a regex to appear in a pane's output, or for the
+//! pane's agent to reach a given activity, without polling `ReadPane`.
+//! Waiters are fed from the same session broadcasts that carry
+//! `PtyOutputPoller` output to attached clients, so every chunk of output is
+//! seen exactly once regardless of how fast it scrolls past.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use regex::Regex;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use fugue_protocol::{AgentActivity, ExpectCondition, ExpectOutcome, PaneState, ServerMessage};
+
+/// Earlier output rescanned along with each new chunk, so matches can span
+/// chunk boundaries; a match must start within this many bytes of new output
+const MATCH_OVERLAP_BYTES: usize = 4 * 1024;
+
+/// Escape-free text of a pane's output, fed one chunk at a time
+///
+/// The parser keeps its state between chunks, so escape sequences and UTF-8
+/// characters split across chunks are handled without rescanning.
+#[derive(Default)]
+pub struct OutputText {
+    parser: vte::Parser,
+    text: String,
+}
+
+impl OutputText {
+    /// Strip escapes from a chunk and append the printable text
+    fn feed(&mut self, data: &[u8]) {
+        self.parser.advance(&mut TextSink(&mut self.text), data);
+    }
+
+    /// Keep only the last `MATCH_OVERLAP_BYTES`, from a line start if possible
+    fn trim(&mut self) {
+        if self.text.len() <= MATCH_OVERLAP_BYTES {
+            return;
+        }
+        let mut cut = self.text.len() - MATCH_OVERLAP_BYTES;
+        while !self.text.is_char_boundary(cut) {
+            cut += 1;
+        }
+        if let Some(nl) = self.text[cut..].find('\n') {
+            cut += nl + 1;
+        }
+        self.text.drain(..cut);
+    }
+}
+
+impl fmt::Debug for OutputText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutputText").field("text", &self.text).finish_non_exhaustive()
+    }
+}
+
+/// Collects what `strip_ansi_escapes` would keep: printed characters and newlines
+struct TextSink<'a>(&'a mut String);
+
+impl vte::Perform for TextSink<'_> {
+    fn print(&mut self, c: char) {
+        self.0.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        if byte == b'\n' {
+            self.0.push('\n');
+        }
+    }
+}
+
+/// Compiled form of an `ExpectCondition`
+#[derive(Debug)]
+pub enum ExpectMatcher {
+    /// Regexes checked against accumulated output
+    Output {
+        patterns: Vec<Regex>,
+        /// Recent output seen since the waiter was registered
+        output: Box<OutputText>,
+    },
+    /// Agent activities that satisfy the wait
+    Agent(Vec<AgentActivity>),
+}
+
+impl ExpectMatcher {
+    /// Compile a condition, rejecting empty conditions and invalid regexes
+    pub fn new(condition: &ExpectCondition) -> Result<Self, String> {
+        match condition {
+            ExpectCondition::Output { patterns } => {
+                if patterns.is_empty() {
+                    return Err("Expect requires at least one pattern".to_string());
+                }
+                let patterns = patterns
+                    .iter()
+                    .map(|p| Regex::new(p).map_err(|e| format!("Invalid regex pattern '{}': {}", p, e)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Self::Output {
+                    patterns,
+                    output: Box::default(),
+                })
+            }
+            ExpectCondition::AgentActivity { activities } => {
+                if activities.is_empty() {
+                    return Err("Expect requires at least one agent activity".to_string());
+                }
+                Ok(Self::Agent(activities.clone()))
+            }
+        }
+    }
+
+    /// Match output text (already free of escape sequences)
+    ///
+    /// The earliest match in the text wins; on a tie the first pattern wins.
+    pub fn match_text(&self, text: &str) -> Option<ExpectOutcome> {
+        match self {
+            Self::Output { patterns, .. } => match_patterns(patterns, text),
+            Self::Agent(_) => None,
+        }
+    }
+
+    /// Match an agent activity
+    pub fn match_activity(&self, activity: &AgentActivity) -> Option<ExpectOutcome> {
+        match self {
+            Self::Agent(activities) if activities.contains(activity) => {
+                Some(ExpectOutcome::AgentActivity {
+                    activity: activity.clone(),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Append a chunk of raw pane output and match it with the recent output before it
+    fn push_output(&mut self, data: &[u8]) -> Option<ExpectOutcome> {
+        let Self::Output { patterns, output } = self else {
+            return None;
+        };
+
+        let seen = output.text.len();
+        output.feed(data);
+        if output.text.len() == seen {
+            return None;
+        }
+
+        // Everything before `seen` has already failed to match on its own, so
+        // only the new text plus a bounded overlap is ever scanned
+        let outcome = match_patterns(patterns, &output.text);
+        output.trim();
+        outcome
+    }
+}
+
+/// Match output text (already free of escape sequences) against `patterns`
+///
+/// The earliest match in the text wins; on a tie the first pattern wins.
+fn match_patterns(patterns: &[Regex], text: &str) -> Option<ExpectOutcome> {
+    let mut best: Option<(usize, regex::Captures<'_>)> = None;
+    for (index, regex) in patterns.iter().enumerate() {
+        if let Some(caps) = regex.captures(text) {
+            let start = caps.get(0).map_or(0, |m| m.start());
+            let earlier = best
+                .as_ref()
+                .is_none_or(|(_, b)| start < b.get(0).map_or(0, |m| m.start()));
+            if earlier {
+                best = Some((index, caps));
+            }
+        }
+    }
+
+    let (pattern_index, caps) = best?;
+    let whole = caps.get(0)?;
+    let line_start = text[..whole.start()].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[whole.start()..]
+        .find('\n')
+        .map_or(text.len(), |i| whole.start() + i);
+
+    let captures = (0..caps.len())
+        .map(|i| caps.get(i).map(|m| m.as_str().to_string()))
+        .collect();
+    let named_captures = patterns[pattern_index]
+        .capture_names()
+        .flatten()
+        .filter_map(|name| caps.name(name).map(|m| (name.to_string(), m.as_str().to_string())))
+        .collect();
+
+    Some(ExpectOutcome::Matched {
+        pattern_index,
+        text: whole.as_str().to_string(),
+        line: text[line_start..line_end].trim_end_matches('\r').to_string(),
+        captures,
+        named_captures,
+    })
+}
+
+/// A pending wait on one pane
+struct Waiter {
+    id: u64,
+    matcher: ExpectMatcher,
+    reply: oneshot::Sender<ExpectOutcome>,
+}
+
+/// Registry of pending server-side Expect requests, keyed by pane
+#[derive(Default)]
+pub struct ExpectRegistry {
+    waiters: Mutex<HashMap<Uuid, Vec<Waiter>>>,
+    /// Number of panes with waiters, so broadcasts skip the lock when idle
+    waiting_panes: AtomicUsize,
+    next_id: AtomicU64,
+}
+
+impl ExpectRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start waiting on a pane
+    ///
+    /// Returns an ID for `cancel` and a receiver that yields the outcome.
+    /// The receiver errors if the registry is dropped before an outcome.
+    pub fn register(
+        &self,
+        pane_id: Uuid,
+        matcher: ExpectMatcher,
+    ) -> (u64, oneshot::Receiver<ExpectOutcome>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (reply, rx) = oneshot::channel();
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters
+            .entry(pane_id)
+            .or_default()
+            .push(Waiter { id, matcher, reply });
+        self.waiting_panes.store(waiters.len(), Ordering::Relaxed);
+        (id, rx)
+    }
+
+    /// Stop waiting (e.g. after a timeout)
+    pub fn cancel(&self, pane_id: Uuid, id: u64) {
+        let mut waiters = self.waiters.lock().unwrap();
+        if let Some(list) = waiters.get_mut(&pane_id) {
+            list.retain(|w| w.id != id);
+            if list.is_empty() {
+                waiters.remove(&pane_id);
+            }
+        }
+        self.waiting_panes.store(waiters.len(), Ordering::Relaxed);
+    }
+
+    /// Number of pending waits across all panes
+    #[cfg(test)]
+    pub fn pending(&self) -> usize {
+        self.waiters.lock().unwrap().values().map(Vec::len).sum()
+    }
+
+    /// Feed a session broadcast to the waiters of the pane it concerns
+    pub fn observe(&self, message: &ServerMessage) {
+        let message = match message {
+            ServerMessage::Sequenced { inner, .. } => inner.as_ref(),
+            other => other,
+        };
+
+        if self.waiting_panes.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        let mut waiters = self.waiters.lock().unwrap();
+
+        match message {
+            ServerMessage::Output { pane_id, data } => {
+                Self::resolve(&mut waiters, *pane_id, |m| m.push_output(data));
+            }
+            ServerMessage::PaneStateChanged { pane_id, state } => match state {
+                PaneState::Agent(agent) => {
+                    Self::resolve(&mut waiters, *pane_id, |m| m.match_activity(&agent.activity));
+                }
+                PaneState::Exited { .. } => {
+                    Self::resolve(&mut waiters, *pane_id, |_| Some(ExpectOutcome::PaneClosed));
+                }
+                _ => {}
+            },
+            ServerMessage::ClaudeStateChanged { pane_id, state } => {
+                let activity = AgentActivity::from(state.activity.clone());
+                Self::resolve(&mut waiters, *pane_id, |m| m.match_activity(&activity));
+            }
+            ServerMessage::PaneClosed { pane_id, .. } => {
+                Self::resolve(&mut waiters, *pane_id, |_| Some(ExpectOutcome::PaneClosed));
+            }
+            _ => {}
+        }
+        self.waiting_panes.store(waiters.len(), Ordering::Relaxed);
+    }
+
+    /// Reply to and remove every waiter on `pane_id` for which `check` yields an outcome
+    fn resolve<F>(waiters: &mut HashMap<Uuid, Vec<Waiter>>, pane_id: Uuid, mut check: F)
+    where
+        F: FnMut(&mut ExpectMatcher) -> Option<ExpectOutcome>,
+    {
+        let Some(list) = waiters.get_mut(&pane_id) else {
+            return;
+        };
+
+        let mut pending = Vec::with_capacity(list.len());
+        for mut waiter in list.drain(..) {
+            match check(&mut waiter.matcher) {
+                // The requester may already have timed out; nothing to do then
+                Some(outcome) => {
+                    let _ = waiter.reply.send(outcome);
+                }
+                None => pending.push(waiter),
+            }
+        }
+
+        if pending.is_empty() {
+            waiters.remove(&pane_id);
+        } else {
+            *list = pending;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::{AgentState, ClaudeActivity, ClaudeState};
+
+    fn output(pane_id: Uuid, data: &str) -> ServerMessage {
+        ServerMessage::Output {
+            pane_id,
+            data: data.as_bytes().to_vec(),
+        }
+    }
+
+    fn output_matcher(patterns: &[&str]) -> ExpectMatcher {
+        ExpectMatcher::new(&ExpectCondition::Output {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_rejects_invalid_conditions() {
+        assert!(ExpectMatcher::new(&ExpectCondition::Output { patterns: vec![] }).is_err());
+        assert!(ExpectMatcher::new(&ExpectCondition::Output {
+            patterns: vec!["(".into()]
+        })
+        .is_err());
+        assert!(ExpectMatcher::new(&ExpectCondition::AgentActivity { activities: vec![] }).is_err());
+    }
+
+    #[test]
+    fn test_match_spans_chunks_and_strips_escapes() {
+        let registry = ExpectRegistry::new();
+        let pane_id = Uuid::new_v4();
+        let (_, mut rx) = registry.register(pane_id, output_matcher(&[r"BUILD (\w+)"]));
+
+        registry.observe(&output(pane_id, "compiling...\r\nBUI"));
+        assert!(rx.try_recv().is_err());
+        registry.observe(&output(pane_id, "LD \x1b[32mOK\x1b[0m\r\n"));
+
+        match rx.try_recv().unwrap() {
+            ExpectOutcome::Matched {
+                pattern_index,
+                text,
+                line,
+                captures,
+                ..
+            } => {
+                assert_eq!(pattern_index, 0);
+                assert_eq!(text, "BUILD OK");
+                assert_eq!(line, "BUILD OK");
+                assert_eq!(captures, vec![Some("BUILD OK".into()), Some("OK".into())]);
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+        assert_eq!(registry.pending(), 0);
+    }
+
+    #[test]
+    fn test_earliest_match_wins_and_named_captures() {
+        let matcher = output_matcher(&["error", r"(?P<passed>\d+) passed", "passed"]);
+        let outcome = matcher.match_text("test result: 12 passed\nerror: later");
+
+        match outcome.unwrap() {
+            ExpectOutcome::Matched {
+                pattern_index,
+                named_captures,
+                line,
+                ..
+            } => {
+                assert_eq!(pattern_index, 1);
+                assert_eq!(named_captures.get("passed").map(String::as_str), Some("12"));
+                assert_eq!(line, "test result: 12 passed");
+            }
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_output_for_other_pane_is_ignored() {
+        let registry = ExpectRegistry::new();
+        let pane_id = Uuid::new_v4();
+        let (_, mut rx) = registry.register(pane_id, output_matcher(&["done"]));
+
+        registry.observe(&output(Uuid::new_v4(), "done"));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(registry.pending(), 1);
+    }
+
+    #[test]
+    fn test_agent_activity() {
+        let registry = ExpectRegistry::new();
+        let pane_id = Uuid::new_v4();
+        let matcher = ExpectMatcher::new(&ExpectCondition::AgentActivity {
+            activities: vec![AgentActivity::Idle],
+        })
+        .unwrap();
+        let (_, mut rx) = registry.register(pane_id, matcher);
+
+        let mut state = AgentState::new("claude");
+        state.activity = AgentActivity::Processing;
+        registry.observe(&ServerMessage::PaneStateChanged {
+            pane_id,
+            state: PaneState::Agent(state.clone()),
+        });
+        assert!(rx.try_recv().is_err());
+
+        registry.observe(&ServerMessage::ClaudeStateChanged {
+            pane_id,
+            state: ClaudeState {
+                activity: ClaudeActivity::Idle,
+                ..Default::default()
+            },
+        });
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ExpectOutcome::AgentActivity {
+                activity: AgentActivity::Idle
+            }
+        );
+    }
+
+    #[test]
+    fn test_pane_closed_resolves_all_waiters() {
+        let registry = ExpectRegistry::new();
+        let pane_id = Uuid::new_v4();
+        let (_, mut rx1) = registry.register(pane_id, output_matcher(&["x"]));
+        let (_, mut rx2) = registry.register(pane_id, output_matcher(&["y"]));
+
+        registry.observe(&ServerMessage::Sequenced {
+            seq: 1,
+            inner: Box::new(ServerMessage::PaneClosed {
+                pane_id,
+                exit_code: None,
+            }),
+        });
+
+        assert_eq!(rx1.try_recv().unwrap(), ExpectOutcome::PaneClosed);
+        assert_eq!(rx2.try_recv().unwrap(), ExpectOutcome::PaneClosed);
+        assert_eq!(registry.pending(), 0);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let registry = ExpectRegistry::new();
+        let pane_id = Uuid::new_v4();
+        let (id, _rx) = registry.register(pane_id, output_matcher(&["x"]));
+        let (_, _rx2) = registry.register(pane_id, output_matcher(&["y"]));
+
+        registry.cancel(pane_id, id);
+        assert_eq!(registry.pending(), 1);
+
+        registry.observe(&output(pane_id, "x y"));
+        assert_eq!(registry.pending(), 0);
+    }
+
+    #[test]
+    fn test_retained_output_is_bounded() {
+        let mut matcher = output_matcher(&["never"]);
+        let chunk = "a".repeat(1000) + "\n";
+        for _ in 0..100 {
+            assert!(matcher.push_output(chunk.as_bytes()).is_none());
+        }
+        match &matcher {
+            ExpectMatcher::Output { output, .. } => {
+                assert!(output.text.len() <= MATCH_OVERLAP_BYTES);
+                assert!(output.text.starts_with('a'));
+            }
+            ExpectMatcher::Agent(_) => unreachable!(),
+        }
+
+        // Escape sequences and characters split across chunks still match
+        let mut matcher = output_matcher(&["caf\u{e9} OK"]);
+        assert!(matcher.push_output(b"caf\xc3").is_none());
+        assert!(matcher.push_output(b"\xa9 \x1b[3").is_none());
+        assert!(matcher.push_output(b"2mOK").is_some());
+    }
+}
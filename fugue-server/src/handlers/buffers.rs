@@ -0,0 +1,228 @@
+//! Paste buffer handlers
+//!
+//! Handles: SetBuffer, ListBuffers, GetBuffer, DeleteBuffer, PasteBuffer
+
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{ErrorCode, ServerMessage};
+
+use super::{HandlerContext, HandlerResult};
+use crate::arbitration::{Action, Resource};
+use crate::buffers::paste_bytes;
+
+/// Error for a buffer that doesn't exist
+fn no_buffer(name: Option<&str>) -> HandlerResult {
+    let message = match name {
+        Some(name) => format!("No buffer '{}'", name),
+        None => "No buffers".to_string(),
+    };
+    HandlerContext::error(ErrorCode::InvalidOperation, message)
+}
+
+impl HandlerContext {
+    /// Handle SetBuffer - store text in a paste buffer
+    pub fn handle_set_buffer(&self, name: Option<String>, data: String, append: bool) -> HandlerResult {
+        debug!(
+            "SetBuffer {:?} ({} bytes, append={}) from {}",
+            name,
+            data.len(),
+            append,
+            self.client_id
+        );
+        match self.registry.buffers().set(name.as_deref(), data, append) {
+            Ok(name) => HandlerResult::Response(ServerMessage::BufferSet { name }),
+            Err(e) => HandlerContext::error(ErrorCode::InvalidOperation, e),
+        }
+    }
+
+    /// Handle ListBuffers - list paste buffers, most recent first
+    pub fn handle_list_buffers(&self) -> HandlerResult {
+        HandlerResult::Response(ServerMessage::BufferList {
+            buffers: self.registry.buffers().list(),
+        })
+    }
+
+    /// Handle GetBuffer - read a paste buffer
+    pub fn handle_get_buffer(&self, name: Option<String>) -> HandlerResult {
+        match self.registry.buffers().get(name.as_deref()) {
+            Some((name, data)) => HandlerResult::Response(ServerMessage::BufferContent { name, data }),
+            None => no_buffer(name.as_deref()),
+        }
+    }
+
+    /// Handle DeleteBuffer - remove a paste buffer
+    pub fn handle_delete_buffer(&self, name: Option<String>) -> HandlerResult {
+        match self.registry.buffers().delete(name.as_deref()) {
+            Some((name, _)) => HandlerResult::Response(ServerMessage::BufferDeleted { name }),
+            None => no_buffer(name.as_deref()),
+        }
+    }
+
+    /// Handle PasteBuffer - type a paste buffer into a pane
+    pub async fn handle_paste_buffer(
+        &self,
+        name: Option<String>,
+        pane_id: Uuid,
+        delete: bool,
+        bracketed: bool,
+    ) -> HandlerResult {
+        if let Err(blocked) = self.check_arbitration(Resource::Pane(pane_id), Action::Input) {
+            return blocked;
+        }
+
+        let Some((name, data)) = self.registry.buffers().get(name.as_deref()) else {
+            return no_buffer(name.as_deref());
+        };
+
+        let use_bracketed = {
+            let session_manager = self.session_manager.read().await;
+            match session_manager.find_pane(pane_id) {
+                Some((_, _, pane)) => bracketed && pane.bracketed_paste_enabled(),
+                None => {
+                    return HandlerContext::error(
+                        ErrorCode::PaneNotFound,
+                        format!("Pane {} not found", pane_id),
+                    )
+                }
+            }
+        };
+
+        debug!(
+            "PasteBuffer '{}' into pane {} ({} bytes) from {}",
+            name,
+            pane_id,
+            data.len(),
+            self.client_id
+        );
+        self.record_human_activity(Resource::Pane(pane_id), Action::Input);
+
+        let mut to_write = Vec::with_capacity(data.len() + 12);
+        if use_bracketed {
+            to_write.extend_from_slice(b"\x1b[200~");
+        }
+        to_write.extend_from_slice(&paste_bytes(&data));
+        if use_bracketed {
+            to_write.extend_from_slice(b"\x1b[201~");
+        }
+
+        {
+            let pty_manager = self.pty_manager.read().await;
+            let Some(handle) = pty_manager.get(pane_id) else {
+                debug!("No PTY handle for pane {}", pane_id);
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("No PTY handle for pane {}", pane_id),
+                );
+            };
+            if let Err(e) = handle.write_all(&to_write) {
+                warn!("Failed to write buffer to PTY for pane {}: {}", pane_id, e);
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("Failed to write to PTY: {}", e),
+                );
+            }
+        }
+
+        if delete {
+            self.registry.buffers().delete(Some(&name));
+        }
+        HandlerResult::Response(ServerMessage::BufferPasted { name, pane_id })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitration::Arbitrator;
+    use crate::pty::PtyManager;
+    use crate::registry::ClientRegistry;
+    use crate::session::SessionManager;
+    use crate::sideband::AsyncCommandExecutor;
+    use crate::watchdog::WatchdogManager;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, RwLock};
+
+    fn create_test_context() -> HandlerContext {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+        let registry = Arc::new(ClientRegistry::new());
+        let config = crate::config::new_config_handle();
+
+        let (tx, _rx) = mpsc::channel(10);
+        let client_id = registry.register_client(tx);
+
+        let (pane_closed_tx, _) = mpsc::channel(10);
+        let command_executor = Arc::new(AsyncCommandExecutor::new(
+            Arc::clone(&session_manager),
+            Arc::clone(&pty_manager),
+            Arc::clone(&registry),
+        ));
+
+        HandlerContext::new(
+            session_manager,
+            pty_manager,
+            registry,
+            config,
+            client_id,
+            pane_closed_tx,
+            command_executor,
+            Arc::new(Arbitrator::new()),
+            None,
+            Arc::new(WatchdogManager::new()),
+        )
+    }
+
+    #[test]
+    fn test_buffers_shared_between_clients() {
+        let ctx = create_test_context();
+        match ctx.handle_set_buffer(None, "cargo test".into(), false) {
+            HandlerResult::Response(ServerMessage::BufferSet { name }) => assert_eq!(name, "buffer0"),
+            _ => panic!("Expected BufferSet"),
+        }
+
+        // A second client sees the same buffers
+        let (tx, _rx) = mpsc::channel(10);
+        let other = HandlerContext {
+            client_id: ctx.registry.register_client(tx),
+            registry: Arc::clone(&ctx.registry),
+            ..create_test_context()
+        };
+        match other.handle_get_buffer(None) {
+            HandlerResult::Response(ServerMessage::BufferContent { name, data }) => {
+                assert_eq!(name, "buffer0");
+                assert_eq!(data, "cargo test");
+            }
+            _ => panic!("Expected BufferContent"),
+        }
+        match other.handle_list_buffers() {
+            HandlerResult::Response(ServerMessage::BufferList { buffers }) => {
+                assert_eq!(buffers.len(), 1);
+                assert_eq!(buffers[0].size, 10);
+            }
+            _ => panic!("Expected BufferList"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_buffer_and_pane_errors() {
+        let ctx = create_test_context();
+        match ctx.handle_delete_buffer(Some("nope".into())) {
+            HandlerResult::Response(ServerMessage::Error { code, message, .. }) => {
+                assert_eq!(code, ErrorCode::InvalidOperation);
+                assert!(message.contains("nope"));
+            }
+            _ => panic!("Expected error"),
+        }
+
+        ctx.handle_set_buffer(Some("cmd".into()), "ls\n".into(), false);
+        match ctx.handle_paste_buffer(Some("cmd".into()), Uuid::new_v4(), true, false).await {
+            HandlerResult::Response(ServerMessage::Error { code, .. }) => {
+                assert_eq!(code, ErrorCode::PaneNotFound);
+            }
+            _ => panic!("Expected error"),
+        }
+        // A failed paste keeps the buffer even with delete
+        assert!(ctx.registry.buffers().get(Some("cmd")).is_some());
+    }
+}
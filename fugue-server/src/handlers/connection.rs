@@ -1,6 +1,6 @@
 //! Connection-related message handlers
 //!
-//! Handles: Connect, Ping, Sync, Detach
+//! Handles: Connect, Authenticate, Ping, Sync, Detach, SubscribeEvents, UnsubscribeEvents
 
 use tracing::{debug, info};
 use uuid::Uuid;
@@ -43,6 +43,41 @@ impl HandlerContext {
         })
     }
 
+    /// Handle Authenticate message outside the TCP auth gate
+    ///
+    /// Token checks happen in `tcp.rs` before a peer reaches the handler
+    /// layer, so by the time an `Authenticate` gets here the connection is
+    /// already trusted (Unix socket or an authenticated TCP peer).
+    pub fn handle_authenticate(&self) -> HandlerResult {
+        debug!("Authenticate from already-trusted client {}", self.client_id);
+        HandlerResult::Response(ServerMessage::Authenticated)
+    }
+
+    /// Handle SubscribeEvents - receive broadcasts from every session
+    pub fn handle_subscribe_events(&self, include_output: bool) -> HandlerResult {
+        if !self.registry.subscribe_events(self.client_id, include_output) {
+            return HandlerContext::error(ErrorCode::InvalidOperation, "Client is not registered");
+        }
+        info!(
+            "Client {} subscribed to events (include_output={})",
+            self.client_id, include_output
+        );
+        HandlerResult::Response(ServerMessage::EventSubscription {
+            subscribed: true,
+            include_output,
+        })
+    }
+
+    /// Handle UnsubscribeEvents - stop receiving broadcasts from other sessions
+    pub fn handle_unsubscribe_events(&self) -> HandlerResult {
+        self.registry.unsubscribe_events(self.client_id);
+        debug!("Client {} unsubscribed from events", self.client_id);
+        HandlerResult::Response(ServerMessage::EventSubscription {
+            subscribed: false,
+            include_output: false,
+        })
+    }
+
     /// Handle Ping message - simple heartbeat response
     pub fn handle_ping(&self) -> HandlerResult {
         debug!("Received Ping from {}, sending Pong", self.client_id);
@@ -71,7 +106,7 @@ impl HandlerContext {
                     let windows: Vec<_> = session.windows().map(|w| w.to_info()).collect();
 
                     let mut panes = Vec::new();
-                    let mut initial_output: Vec<ServerMessage> = Vec::new();
+                    let mut initial_output = Self::layout_messages(session);
 
                     for window in session.windows() {
                         for pane in window.panes() {
@@ -225,12 +260,15 @@ impl HandlerContext {
                     0
                 };
 
-                return HandlerResult::Response(ServerMessage::StateSnapshot {
-                    commit_seq: current_seq,
-                    session: session_info,
-                    windows,
-                    panes,
-                });
+                return HandlerResult::ResponseWithFollowUp {
+                    response: ServerMessage::StateSnapshot {
+                        commit_seq: current_seq,
+                        session: session_info,
+                        windows,
+                        panes,
+                    },
+                    follow_up: Self::layout_messages(session),
+                };
             }
         }
 
@@ -256,7 +294,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let arbitrator = Arc::new(Arbitrator::new());
         let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
@@ -317,6 +355,29 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handle_subscribe_events() {
+        let ctx = create_test_context();
+        let result = ctx.handle_subscribe_events(true);
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::EventSubscription {
+                subscribed: true,
+                include_output: true,
+            })
+        ));
+
+        let result = ctx.handle_unsubscribe_events();
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::EventSubscription {
+                subscribed: false,
+                ..
+            })
+        ));
+        assert!(!ctx.registry.unsubscribe_events(ctx.client_id));
+    }
+
     #[test]
     fn test_handle_ping() {
         let ctx = create_test_context();
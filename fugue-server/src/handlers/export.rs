@@ -0,0 +1,223 @@
+//! Portable session export/import handlers
+//!
+//! Handles: ExportSession, ImportSession
+
+use std::collections::HashMap;
+
+use tracing::info;
+use uuid::Uuid;
+
+use fugue_protocol::{ErrorCode, ExportFormat, ServerMessage};
+
+use crate::persistence::{capture_session, SessionExport};
+
+use super::{HandlerContext, HandlerResult};
+
+impl HandlerContext {
+    /// Handle ExportSession - serialize a session as a portable document
+    pub async fn handle_export_session(
+        &self,
+        session_filter: String,
+        format: ExportFormat,
+        include_scrollback: bool,
+    ) -> HandlerResult {
+        info!(
+            "ExportSession '{}' request from {} ({:?}, scrollback: {})",
+            session_filter, self.client_id, format, include_scrollback
+        );
+
+        let export = {
+            let session_manager = self.session_manager.read().await;
+            let session = match Uuid::parse_str(&session_filter) {
+                Ok(id) => session_manager.get_session(id),
+                Err(_) => session_manager.get_session_by_name(&session_filter),
+            };
+            let Some(session) = session else {
+                return HandlerContext::error(
+                    ErrorCode::SessionNotFound,
+                    format!("Session '{}' not found", session_filter),
+                );
+            };
+
+            let scrollback: HashMap<Uuid, Vec<String>> = if include_scrollback {
+                session
+                    .windows()
+                    .flat_map(|window| window.panes())
+                    .map(|pane| {
+                        let lines = pane.scrollback().get_lines().map(String::from).collect();
+                        (pane.id(), lines)
+                    })
+                    .collect()
+            } else {
+                HashMap::new()
+            };
+
+            SessionExport::new(capture_session(session), scrollback)
+        };
+
+        match export.to_document(format) {
+            Ok(document) => HandlerResult::Response(ServerMessage::SessionExported { document }),
+            Err(e) => HandlerContext::error(ErrorCode::InternalError, e.to_string()),
+        }
+    }
+
+    /// Handle ImportSession - recreate an exported session with fresh IDs
+    pub async fn handle_import_session(
+        &self,
+        document: String,
+        name: Option<String>,
+    ) -> HandlerResult {
+        let export = match SessionExport::from_document(&document) {
+            Ok(export) => export,
+            Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        };
+        let name = name.unwrap_or_else(|| export.session.name.clone());
+
+        info!(
+            "ImportSession '{}' (exported as '{}') request from {}",
+            name, export.session.name, self.client_id
+        );
+
+        if self
+            .session_manager
+            .read()
+            .await
+            .get_session_by_name(&name)
+            .is_some()
+        {
+            return HandlerContext::error(
+                ErrorCode::SessionNameExists,
+                format!("Session '{}' already exists", name),
+            );
+        }
+
+        let (session, scrollback) = export.instantiate(name);
+        let (mut sessions, skipped, broadcast) = self
+            .restore_sessions(vec![session], scrollback, self.config.load().persistence.restore.clone())
+            .await;
+
+        match sessions.pop() {
+            Some(session) => HandlerResult::ResponseWithGlobalBroadcast {
+                response: ServerMessage::SessionImported { session },
+                broadcast,
+            },
+            None => match skipped.first() {
+                Some(name) => HandlerContext::error(
+                    ErrorCode::SessionNameExists,
+                    format!("Session '{}' already exists", name),
+                ),
+                None => HandlerContext::error(
+                    ErrorCode::InternalError,
+                    "Imported session could not be restored",
+                ),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitration::Arbitrator;
+    use crate::pty::PtyManager;
+    use crate::registry::ClientRegistry;
+    use crate::session::SessionManager;
+    use crate::watchdog::WatchdogManager;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, RwLock};
+
+    fn create_test_context() -> HandlerContext {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+        let registry = Arc::new(ClientRegistry::new());
+        let config = crate::config::new_config_handle();
+        let arbitrator = Arc::new(Arbitrator::new());
+        let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
+            Arc::clone(&session_manager),
+            Arc::clone(&pty_manager),
+            Arc::clone(&registry),
+        ));
+        let watchdog = Arc::new(WatchdogManager::new());
+
+        let (tx, _rx) = mpsc::channel(10);
+        let client_id = registry.register_client(tx);
+
+        let (pane_closed_tx, _) = mpsc::channel(10);
+        HandlerContext::new(
+            session_manager,
+            pty_manager,
+            registry,
+            config,
+            client_id,
+            pane_closed_tx,
+            command_executor,
+            arbitrator,
+            None,
+            watchdog,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_export_then_import_session() {
+        let ctx = create_test_context();
+        {
+            let mut session_manager = ctx.session_manager.write().await;
+            let session_id = session_manager.create_session("dev").unwrap().id();
+            let session = session_manager.get_session_mut(session_id).unwrap();
+            session.create_window(Some("editor".to_string()));
+            session.set_metadata("project", "fugue");
+        }
+
+        let document = match ctx
+            .handle_export_session("dev".to_string(), ExportFormat::Toml, true)
+            .await
+        {
+            HandlerResult::Response(ServerMessage::SessionExported { document }) => document,
+            _ => panic!("Expected SessionExported response"),
+        };
+
+        // Importing under the existing name is refused
+        let result = ctx.handle_import_session(document.clone(), None).await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error {
+                code: ErrorCode::SessionNameExists,
+                ..
+            })
+        ));
+
+        match ctx
+            .handle_import_session(document, Some("dev-copy".to_string()))
+            .await
+        {
+            HandlerResult::ResponseWithGlobalBroadcast {
+                response: ServerMessage::SessionImported { session },
+                ..
+            } => {
+                assert_eq!(session.name, "dev-copy");
+                assert_eq!(session.window_count, 1);
+                assert_eq!(session.metadata.get("project").map(String::as_str), Some("fugue"));
+            }
+            _ => panic!("Expected SessionImported response with global broadcast"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_skips_taken_names() {
+        let ctx = create_test_context();
+        let snapshot = {
+            let mut session_manager = ctx.session_manager.write().await;
+            let session_id = session_manager.create_session("dev").unwrap().id();
+            capture_session(session_manager.get_session(session_id).unwrap())
+        };
+
+        // The name is checked again under the write lock, so a session that
+        // appeared after the handler's own check is left alone
+        let (restored, skipped, _) = ctx
+            .restore_sessions(vec![snapshot], HashMap::new(), Default::default())
+            .await;
+        assert!(restored.is_empty());
+        assert_eq!(skipped, vec!["dev".to_string()]);
+        assert_eq!(ctx.session_manager.read().await.list_sessions().len(), 1);
+    }
+}
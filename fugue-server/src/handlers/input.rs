@@ -1,11 +1,13 @@
 //! Input-related message handlers
 //!
-//! Handles: Input, Reply, SetViewportOffset, JumpToBottom
+//! Handles: Input, Reply, ResolvePermission, SetViewportOffset, JumpToBottom
 
 use tracing::{debug, warn};
 use uuid::Uuid;
 
-use fugue_protocol::{ErrorCode, ReplyMessage, ServerMessage};
+use fugue_protocol::{
+    AgentActivity, ErrorCode, PaneState, PermissionDecision, ReplyMessage, ServerMessage,
+};
 
 use super::{HandlerContext, HandlerResult};
 use crate::arbitration::{Action, Resource};
@@ -150,6 +152,91 @@ impl HandlerContext {
         }
     }
 
+    /// Handle ResolvePermission message - answer an agent's tool-permission prompt
+    ///
+    /// Sends the key for the prompt option matching the decision. Denying a
+    /// prompt without a "No" option presses Escape, which every supported
+    /// agent treats as a rejection.
+    pub async fn handle_resolve_permission(
+        &self,
+        pane_id: Uuid,
+        decision: PermissionDecision,
+    ) -> HandlerResult {
+        if let Err(blocked) = self.check_arbitration(Resource::Pane(pane_id), Action::Input) {
+            return blocked;
+        }
+
+        debug!("ResolvePermission {:?} for pane {} from {}", decision, pane_id, self.client_id);
+
+        let mut session_manager = self.session_manager.write().await;
+        let Some((session_id, request)) = session_manager
+            .find_pane(pane_id)
+            .map(|(session, _, pane)| (session.id(), pane.permission_request()))
+        else {
+            return HandlerContext::error(
+                ErrorCode::PaneNotFound,
+                format!("Pane {} not found", pane_id),
+            );
+        };
+        let Some(request) = request else {
+            return HandlerContext::error(
+                ErrorCode::NotAwaitingInput,
+                format!("Pane {} is not waiting on a permission prompt", pane_id),
+            );
+        };
+
+        let (key, option) = match request.option_for(decision) {
+            Some(option) => (option.key.clone(), option.label.clone()),
+            None if decision == PermissionDecision::Deny => ("\x1b".to_string(), "Cancel".to_string()),
+            None => {
+                return HandlerContext::error(
+                    ErrorCode::InvalidOperation,
+                    format!("Permission prompt in pane {} has no option for {:?}", pane_id, decision),
+                );
+            }
+        };
+
+        self.record_human_activity(Resource::Pane(pane_id), Action::Input);
+
+        {
+            let pty_manager = self.pty_manager.read().await;
+            let Some(handle) = pty_manager.get(pane_id) else {
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("No PTY handle for pane {}", pane_id),
+                );
+            };
+            if let Err(e) = handle.write_all(key.as_bytes()) {
+                warn!("Failed to write permission answer to pane {}: {}", pane_id, e);
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("Failed to write to PTY: {}", e),
+                );
+            }
+        }
+
+        // Clear the parsed prompt so a second answer can't be sent blindly;
+        // it is parsed again if the dialog is still on screen after the redraw
+        if let Some(pane) = session_manager.find_pane_mut(pane_id) {
+            if let PaneState::Agent(mut state) = pane.state().clone() {
+                state.activity = AgentActivity::AwaitingConfirmation;
+                state.set_permission_request(None);
+                pane.set_agent_state(state);
+            }
+        }
+
+        let resolved = ServerMessage::PermissionResolved {
+            pane_id,
+            decision,
+            option,
+        };
+        HandlerResult::ResponseWithBroadcast {
+            response: resolved.clone(),
+            session_id,
+            broadcast: resolved,
+        }
+    }
+
     /// Handle SetViewportOffset message - update pane viewport
     pub async fn handle_set_viewport_offset(
         &self,
@@ -216,6 +303,7 @@ mod tests {
     use crate::registry::ClientRegistry;
     use crate::session::SessionManager;
     use crate::arbitration::Arbitrator;
+    use fugue_protocol::{PermissionOption, PermissionRequest};
     use std::sync::Arc;
     use tokio::sync::{mpsc, RwLock};
 
@@ -223,7 +311,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let arbitrator = Arc::new(Arbitrator::new());
         let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
@@ -306,6 +394,56 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handle_resolve_permission_errors() {
+        let ctx = create_test_context();
+        let result = ctx
+            .handle_resolve_permission(Uuid::new_v4(), PermissionDecision::Approve)
+            .await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error { code: ErrorCode::PaneNotFound, .. })
+        ));
+
+        // Agent pane that isn't showing a prompt
+        let pane_id = create_pane(&ctx).await;
+        ctx.session_manager
+            .write()
+            .await
+            .find_pane_mut(pane_id)
+            .unwrap()
+            .mark_as_agent("claude");
+        let result = ctx.handle_resolve_permission(pane_id, PermissionDecision::Deny).await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error { code: ErrorCode::NotAwaitingInput, .. })
+        ));
+
+        // Prompt without an "always" option
+        {
+            let mut session_manager = ctx.session_manager.write().await;
+            let pane = session_manager.find_pane_mut(pane_id).unwrap();
+            let mut state = pane.agent_state().unwrap().clone();
+            state.set_permission_request(Some(&PermissionRequest {
+                tool: "Bash".into(),
+                arguments: "ls".into(),
+                description: None,
+                options: vec![
+                    PermissionOption { key: "1".into(), label: "Yes".into() },
+                    PermissionOption { key: "2".into(), label: "No".into() },
+                ],
+            }));
+            pane.set_agent_state(state);
+        }
+        let result = ctx
+            .handle_resolve_permission(pane_id, PermissionDecision::ApproveAlways)
+            .await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error { code: ErrorCode::InvalidOperation, .. })
+        ));
+    }
+
     #[tokio::test]
     async fn test_handle_set_viewport_offset_success() {
         let ctx = create_test_context();
@@ -1,7 +1,25 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::debug;
 use uuid::Uuid;
-use fugue_protocol::{ErrorCode, ServerMessage};
+use fugue_protocol::{ErrorCode, ExpectCondition, ExpectOutcome, ServerMessage};
+use crate::expect::ExpectMatcher;
 use crate::handlers::{HandlerContext, HandlerResult};
+use crate::pty::HistoryRange;
+
+/// Most lines returned by one `CapturePane`
+const MAX_CAPTURE_LINES: usize = 50_000;
+
+/// Most bytes of text returned by one `CapturePane`, well under the
+/// protocol's 16 MiB message limit
+const MAX_CAPTURE_BYTES: usize = 8 * 1024 * 1024;
+
+/// Read a range copied out of a scrollback buffer off the async runtime
+async fn read_range(range: HistoryRange) -> std::io::Result<Vec<String>> {
+    tokio::task::spawn_blocking(move || range.read())
+        .await
+        .map_err(std::io::Error::other)?
+}
 
 impl HandlerContext {
     /// Handle ReadPane - read scrollback from a pane
@@ -12,33 +30,198 @@ impl HandlerContext {
     ) -> HandlerResult {
         debug!("ReadPane {} request from {} (lines: {})", pane_id, self.client_id, lines);
 
-        let session_manager = self.session_manager.read().await;
+        let range = {
+            let session_manager = self.session_manager.read().await;
+            let Some((_, _, pane)) = session_manager.find_pane(pane_id) else {
+                debug!("Pane {} not found for ReadPane", pane_id);
+                return HandlerContext::error(
+                    ErrorCode::PaneNotFound,
+                    format!("Pane {} not found", pane_id),
+                );
+            };
 
-        match session_manager.find_pane(pane_id) {
-            Some((_, _, pane)) => {
-                // Limit to reasonable number of lines
-                let lines = lines.min(1000);
+            // Limit to reasonable number of lines
+            let lines = lines.min(1000);
 
-                // Get lines from scrollback
-                let scrollback = pane.scrollback();
-                let all_lines: Vec<&str> = scrollback.get_lines().collect();
-                let start = all_lines.len().saturating_sub(lines);
-                let content = all_lines[start..].join("\n");
+            // Get lines from scrollback, reaching into spilled history if needed
+            let scrollback = pane.scrollback();
+            let end = scrollback.total_pushed();
+            scrollback.history_range(end.saturating_sub(lines as u64), end)
+        };
 
-                debug!("Read {} lines from pane {}", all_lines.len().min(lines), pane_id);
-                HandlerResult::Response(ServerMessage::PaneContent {
-                    pane_id,
-                    content,
-                })
+        let lines = match read_range(range).await {
+            Ok(lines) => lines,
+            Err(e) => {
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("Failed to read spilled scrollback: {}", e),
+                )
             }
-            None => {
-                debug!("Pane {} not found for ReadPane", pane_id);
-                HandlerContext::error(
+        };
+        let content = lines.join("\n");
+
+        debug!("Read {} lines from pane {}", lines.len(), pane_id);
+        HandlerResult::Response(ServerMessage::PaneContent {
+            pane_id,
+            content,
+        })
+    }
+
+    /// Handle CapturePane - read a range of a pane's history
+    ///
+    /// Line 0 is the top of the pane's screen and negative lines are
+    /// history, as in tmux. At most `MAX_CAPTURE_LINES` lines and
+    /// `MAX_CAPTURE_BYTES` bytes are returned, from the start of the range;
+    /// callers page on from the returned `start`.
+    pub async fn handle_capture_pane(
+        &self,
+        pane_id: Uuid,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> HandlerResult {
+        debug!(
+            "CapturePane {} request from {} ({:?}..={:?})",
+            pane_id, self.client_id, start, end
+        );
+
+        let (first, screen_top, history_start, range) = {
+            let session_manager = self.session_manager.read().await;
+            let Some((_, _, pane)) = session_manager.find_pane(pane_id) else {
+                debug!("Pane {} not found for CapturePane", pane_id);
+                return HandlerContext::error(
                     ErrorCode::PaneNotFound,
                     format!("Pane {} not found", pane_id),
-                )
+                );
+            };
+
+            let scrollback = pane.scrollback();
+            let total = scrollback.total_pushed() as i64;
+            let screen_top = total.saturating_sub(pane.dimensions().1 as i64).max(0);
+            let history_start = scrollback.history_start() as i64;
+
+            let first = start.map_or(history_start, |line| screen_top.saturating_add(line));
+            let first = first.max(history_start);
+            let last = end.map_or(total - 1, |line| screen_top.saturating_add(line));
+            let last = last.min(total - 1).min(first.saturating_add(MAX_CAPTURE_LINES as i64 - 1));
+
+            let range = (first <= last).then(|| scrollback.history_range(first as u64, last as u64 + 1));
+            (first, screen_top, history_start, range)
+        };
+
+        let mut lines = match range {
+            Some(range) => match read_range(range).await {
+                Ok(lines) => lines,
+                Err(e) => {
+                    return HandlerContext::error(
+                        ErrorCode::InternalError,
+                        format!("Failed to read spilled scrollback: {}", e),
+                    )
+                }
+            },
+            None => Vec::new(),
+        };
+
+        let mut bytes = 0;
+        let fits = lines
+            .iter()
+            .take_while(|line| {
+                bytes += line.len();
+                bytes <= MAX_CAPTURE_BYTES
+            })
+            .count();
+        lines.truncate(fits);
+
+        debug!("Captured {} lines from pane {}", lines.len(), pane_id);
+        HandlerResult::Response(ServerMessage::PaneCaptured {
+            pane_id,
+            start: first - screen_top,
+            lines,
+            history_start: history_start - screen_top,
+        })
+    }
+
+    /// Handle Expect - wait for output or agent state on a pane
+    ///
+    /// Replies immediately if the condition already holds, otherwise sends
+    /// `ExpectResult` to this client once it is met, the pane closes, or
+    /// the timeout elapses.
+    pub async fn handle_expect(
+        &self,
+        pane_id: Uuid,
+        condition: ExpectCondition,
+        timeout_ms: u64,
+        lines: usize,
+    ) -> HandlerResult {
+        debug!("Expect on pane {} from {} ({:?})", pane_id, self.client_id, condition);
+
+        let matcher = match ExpectMatcher::new(&condition) {
+            Ok(matcher) => matcher,
+            Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e),
+        };
+        let started = Instant::now();
+
+        // Register while holding the session lock: output is processed under the
+        // write lock before it is broadcast, so anything not yet in scrollback
+        // will still reach the waiter.
+        let (waiter_id, rx) = {
+            let session_manager = self.session_manager.read().await;
+            let Some((_, _, pane)) = session_manager.find_pane(pane_id) else {
+                debug!("Pane {} not found for Expect", pane_id);
+                return HandlerContext::error(
+                    ErrorCode::PaneNotFound,
+                    format!("Pane {} not found", pane_id),
+                );
+            };
+
+            let immediate = match &condition {
+                ExpectCondition::Output { .. } if lines > 0 => {
+                    let all_lines: Vec<&str> = pane.scrollback().get_lines().collect();
+                    let start = all_lines.len().saturating_sub(lines.min(1000));
+                    let raw = all_lines[start..].join("\n");
+                    let text = String::from_utf8_lossy(&strip_ansi_escapes::strip(raw.as_bytes()))
+                        .into_owned();
+                    matcher.match_text(&text)
+                }
+                ExpectCondition::Output { .. } => None,
+                ExpectCondition::AgentActivity { .. } => pane
+                    .agent_state()
+                    .and_then(|state| matcher.match_activity(&state.activity)),
+            };
+            if let Some(outcome) = immediate {
+                return HandlerResult::Response(ServerMessage::ExpectResult {
+                    pane_id,
+                    outcome,
+                    elapsed_ms: 0,
+                });
             }
-        }
+
+            self.registry.expect().register(pane_id, matcher)
+        };
+
+        let registry = Arc::clone(&self.registry);
+        let client_id = self.client_id;
+        tokio::spawn(async move {
+            let outcome = match tokio::time::timeout(Duration::from_millis(timeout_ms), rx).await {
+                Ok(Ok(outcome)) => outcome,
+                Ok(Err(_)) => ExpectOutcome::PaneClosed,
+                Err(_) => {
+                    registry.expect().cancel(pane_id, waiter_id);
+                    ExpectOutcome::Timeout
+                }
+            };
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            registry
+                .send_to_client(
+                    client_id,
+                    ServerMessage::ExpectResult {
+                        pane_id,
+                        outcome,
+                        elapsed_ms,
+                    },
+                )
+                .await;
+        });
+
+        HandlerResult::NoResponse
     }
 }
-
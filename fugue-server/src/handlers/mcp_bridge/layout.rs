@@ -1,8 +1,8 @@
 use tracing::info;
 use uuid::Uuid;
-use fugue_protocol::{ErrorCode, ServerMessage, SplitDirection};
+use fugue_protocol::{ErrorCode, LayoutNode, ServerMessage, SplitDirection};
 use crate::pty::{PtyConfig, PtyOutputPoller};
-use crate::session::SessionManager;
+use crate::session::{PaneLaunch, SessionManager};
 use crate::arbitration::{Action, Resource};
 use crate::handlers::{HandlerContext, HandlerResult};
 
@@ -136,13 +136,26 @@ impl HandlerContext {
                 &mut pty_configs,
             );
 
-            if let Err(e) = result {
-                return HandlerContext::error(ErrorCode::InvalidOperation, e);
+            let tree = match result {
+                Ok(tree) => tree,
+                Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e),
+            };
+
+            // Arrange the new panes as specified, next to any existing ones
+            if let Some(window) = session_manager
+                .get_session_mut(session_id)
+                .and_then(|session| session.get_window_mut(window_id))
+            {
+                window.arrange(tree, SplitDirection::Vertical);
             }
 
             (session_id, session_name, window_id, pane_ids, pane_infos, pty_configs)
         }; // session_manager lock released here
 
+        for (pane_id, config) in &pty_configs {
+            self.record_launch(*pane_id, PaneLaunch::from(config)).await;
+        }
+
         // Phase 2: Spawn PTYs without holding session_manager lock (BUG-028 fix)
         // This prevents deadlock if PTY output poller tries to access session_manager
         {
@@ -179,6 +192,7 @@ impl HandlerContext {
                 }
             ).await;
         }
+        self.broadcast_layout(window_id).await;
 
         // Return response to MCP client
         HandlerResult::Response(ServerMessage::LayoutCreated {
@@ -194,6 +208,8 @@ impl HandlerContext {
     /// This is a synchronous function that creates panes and collects PTY configs.
     /// It does NOT spawn PTYs - that happens in Phase 2 after the lock is released.
     /// BUG-032: Also collects PaneInfo for TUI broadcast.
+    ///
+    /// Returns the split tree of the created panes.
     #[allow(clippy::too_many_arguments)]
     fn create_layout_panes(
         session_manager: &mut SessionManager,
@@ -204,7 +220,7 @@ impl HandlerContext {
         pane_ids: &mut Vec<Uuid>,
         pane_infos: &mut Vec<fugue_protocol::PaneInfo>,
         pty_configs: &mut Vec<(Uuid, PtyConfig)>,
-    ) -> Result<(), String> {
+    ) -> Result<LayoutNode, String> {
         // Check if this is a simple pane definition
         if layout.get("pane").is_some() {
             let pane_spec = &layout["pane"];
@@ -252,17 +268,24 @@ impl HandlerContext {
             // Store config for later PTY spawning (Phase 2)
             pty_configs.push((pane_id, config));
 
-            return Ok(())
+            return Ok(LayoutNode::pane(pane_id))
         }
 
         // Check if this is a split definition
         if let Some(splits) = layout.get("splits").and_then(|s| s.as_array()) {
+            let direction = match layout["direction"].as_str() {
+                Some("horizontal") => SplitDirection::Horizontal,
+                Some("vertical") | None => SplitDirection::Vertical,
+                Some(other) => return Err(format!("Invalid split direction '{}'", other)),
+            };
+
+            let mut children = Vec::with_capacity(splits.len());
             for split in splits {
                 let nested_layout = split.get("layout").ok_or_else(|| {
                     "Each split must have a 'layout' field".to_string()
                 })?;
                 // Recursively create panes for nested layouts
-                Self::create_layout_panes(
+                let child = Self::create_layout_panes(
                     session_manager,
                     session_id,
                     session_name,
@@ -272,8 +295,23 @@ impl HandlerContext {
                     pane_infos,
                     pty_configs,
                 )?;
+                let ratio = split["ratio"].as_f64().map(|r| r as f32).filter(|r| *r > 0.0);
+                children.push((child, ratio));
             }
-            return Ok(())
+
+            // Missing ratios share equally; all are normalized to sum to 1.0
+            let default_ratio = 1.0 / children.len().max(1) as f32;
+            let total: f32 = children.iter().map(|(_, r)| r.unwrap_or(default_ratio)).sum();
+            let mut children: Vec<(LayoutNode, f32)> = children
+                .into_iter()
+                .map(|(child, r)| (child, r.unwrap_or(default_ratio) / total))
+                .collect();
+
+            return match children.len() {
+                0 => Err("'splits' must not be empty".to_string()),
+                1 => Ok(children.remove(0).0),
+                _ => Ok(LayoutNode::Split { direction, children }),
+            };
         }
 
         Err("Invalid layout specification: must contain 'pane' or 'splits'".to_string())
@@ -202,6 +202,15 @@ impl HandlerContext {
         // Broadcast updated session list to all clients (BUG-032)
         let sessions: Vec<_> = session_manager.list_sessions().iter().map(|s| s.to_info()).collect();
 
+        // Release lock before persistence call
+        drop(session_manager);
+
+        // Log to persistence so tag-based routing survives restarts
+        if let Some(persistence_lock) = &self.persistence {
+            let persistence = persistence_lock.read().await;
+            let _ = persistence.log_session_tags_set(session_id, tags.clone());
+        }
+
         HandlerResult::ResponseWithGlobalBroadcast {
             response: ServerMessage::TagsSet {
                 session_id,
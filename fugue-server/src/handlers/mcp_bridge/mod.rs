@@ -14,4 +14,41 @@ pub mod window;
 #[cfg(test)]
 mod tests;
 
-// Note: Submodules extend HandlerContext
\ No newline at end of file
+// Note: Submodules extend HandlerContext
+
+use tracing::info;
+
+use crate::claude::inject_session_id;
+use crate::pty::PtyConfig;
+use crate::session::Pane;
+
+/// Give a pane launching Claude a known `--session-id`
+///
+/// The pane is marked as Claude with the generated ID, so recovery can resume
+/// the conversation. Returns the arguments to launch `command` with, or `None`
+/// if it isn't Claude or already names a session.
+fn inject_claude_session(pane: &mut Pane, command: &str, args: &[String]) -> Option<Vec<String>> {
+    let injection = inject_session_id(command, args);
+    if !injection.injected {
+        return None;
+    }
+    let session_id = injection.session_id?;
+    info!("Injected session ID {} for Claude pane {}", session_id, pane.id());
+    pane.mark_as_claude_with_session(session_id);
+    Some(injection.args)
+}
+
+/// Spawn config running `command` through `sh -c`, or the user's shell
+///
+/// A bare `claude` command runs directly instead, with an injected session ID.
+fn pane_command(pane: &mut Pane, command: Option<&str>) -> PtyConfig {
+    let Some(cmd) = command else {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into());
+        return PtyConfig::command(shell);
+    };
+    match inject_claude_session(pane, cmd, &[]) {
+        Some(args) => args.into_iter().fold(PtyConfig::command(cmd), |c, arg| c.with_arg(arg)),
+        // Wrap user command in shell to handle arguments and shell syntax
+        None => PtyConfig::command("sh").with_arg("-c").with_arg(cmd),
+    }
+}
\ No newline at end of file
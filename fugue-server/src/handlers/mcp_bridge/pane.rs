@@ -2,8 +2,10 @@ use tracing::{info, warn, debug};
 use uuid::Uuid;
 use fugue_protocol::{ErrorCode, PaneListEntry, ServerMessage, SplitDirection};
 use crate::pty::{PtyConfig, PtyOutputPoller};
+use crate::session::PaneLaunch;
 use crate::arbitration::{Action, Resource};
 use crate::handlers::{HandlerContext, HandlerResult};
+use super::{inject_claude_session, pane_command};
 
 impl HandlerContext {
     /// Handle ListAllPanes - list all panes across all sessions
@@ -18,8 +20,12 @@ impl HandlerContext {
 
         let session_manager = self.session_manager.read().await;
 
-        // FEAT-078: Get client's focus state
-        let client_focus = self.registry.get_client_focus(self.client_id);
+        // FEAT-078: Get client's focus state; clients that never focused
+        // anything (e.g. fugue-compat) see the global focus instead
+        let client_focus = self
+            .registry
+            .get_client_focus(self.client_id)
+            .filter(|focus| focus.active_session_id.is_some());
 
         let mut panes = Vec::new();
 
@@ -271,7 +277,8 @@ impl HandlerContext {
             }
         };
 
-        let pane = window.create_pane();
+        let target = self.resolve_active_pane(window);
+        let pane = window.create_pane_split(target, direction, 0.5);
         let pane_info = pane.to_info();
         let pane_id = pane_info.id;
 
@@ -297,7 +304,7 @@ impl HandlerContext {
 
         // FEAT-071: Per-pane Claude configuration / FEAT-105: Universal Agent Presets
         if claude_model.is_some() || claude_config.is_some() || preset.is_some() {
-            let config = &self.config;
+            let config = self.config.load_full();
             
             let mut final_config = serde_json::Map::new();
             
@@ -348,22 +355,7 @@ impl HandlerContext {
                     // Check if it's a Claude harness for config extraction
                     if preset_cfg.harness == "claude" {
                         if let crate::config::HarnessConfig::Claude(claude_cfg) = &preset_cfg.config {
-                            if let Some(m) = &claude_cfg.model {
-                                final_config.insert("model".to_string(), serde_json::json!(m));
-                            }
-                            if let Some(c) = claude_cfg.context_limit {
-                                final_config.insert("context_limit".to_string(), serde_json::json!(c));
-                            }
-                            // Map other fields
-                            if let Some(sp) = &claude_cfg.system_prompt {
-                                final_config.insert("system_prompt".to_string(), serde_json::json!(sp));
-                            }
-                            if let Some(dsp) = &claude_cfg.dangerously_skip_permissions {
-                                final_config.insert("dangerously_skip_permissions".to_string(), serde_json::json!(dsp));
-                            }
-                            if let Some(at) = &claude_cfg.allowed_tools {
-                                final_config.insert("allowed_tools".to_string(), serde_json::json!(at));
-                            }
+                            final_config.extend(claude_cfg.to_settings());
                         }
                     }
                     debug!("Applied preset '{}' to pane {}", preset_name, pane_id);
@@ -405,6 +397,12 @@ impl HandlerContext {
             }
         }
 
+        if let Some(cmd) = &final_command {
+            if let Some(args) = inject_claude_session(pane, cmd, &harness_args) {
+                harness_args = args;
+            }
+        }
+
         // If select is true, focus the new pane (set as active pane in window and window as active)
         if select {
             window.set_active_pane(pane_id);
@@ -479,6 +477,8 @@ impl HandlerContext {
         // Apply session environment variables
         config = config.with_env_map(&session_env);
 
+        self.record_launch(pane_id, PaneLaunch::from(&config)).await;
+
         {
             let mut pty_manager = self.pty_manager.write().await;
             match pty_manager.spawn(pane_id, config) {
@@ -609,7 +609,7 @@ impl HandlerContext {
             }
         };
 
-        let new_pane = window.create_pane();
+        let new_pane = window.create_pane_split(Some(pane_id), direction, ratio);
         let new_pane_id = new_pane.id();
         let new_pane_info = new_pane.to_info();
 
@@ -621,6 +621,7 @@ impl HandlerContext {
             }
         };
         pane.init_parser();
+        let pty_config = pane_command(pane, command.as_deref());
 
         // If select is true, focus the new pane
         if select {
@@ -639,12 +640,7 @@ impl HandlerContext {
         drop(session_manager);
 
         // Spawn PTY for the new pane
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into());
-        let mut config = if let Some(ref cmd) = command {
-            PtyConfig::command("sh").with_arg("-c").with_arg(cmd)
-        } else {
-            PtyConfig::command(&shell)
-        };
+        let mut config = pty_config;
         // BUG-050: Apply explicit cwd, or inherit from source pane
         if let Some(ref cwd) = cwd {
             config = config.with_cwd(cwd);
@@ -655,6 +651,8 @@ impl HandlerContext {
         // Apply session environment variables
         config = config.with_env_map(&session_env);
 
+        self.record_launch(new_pane_id, PaneLaunch::from(&config)).await;
+
         {
             let mut pty_manager = self.pty_manager.write().await;
             match pty_manager.spawn(new_pane_id, config) {
@@ -747,15 +745,28 @@ impl HandlerContext {
         // Drop read lock before taking write lock
         drop(session_manager);
 
-        // Calculate new dimensions based on delta
-        // Delta is a fraction: positive grows, negative shrinks
-        // We'll apply delta to both dimensions proportionally
-        let scale = 1.0 + delta;
-        let new_cols = ((current_cols as f32) * scale).clamp(10.0, 500.0) as u16;
-        let new_rows = ((current_rows as f32) * scale).clamp(5.0, 200.0) as u16;
-
-        // Update pane dimensions
+        // Grow or shrink the pane within its split in the window layout.
+        // Delta is a fraction of the split: positive grows, negative shrinks.
         let mut session_manager = self.session_manager.write().await;
+        let resized = session_manager
+            .get_session_mut(session_id)
+            .and_then(|session| session.get_window_mut(window_id))
+            .and_then(|window| window.resize_in_layout(pane_id, delta));
+
+        // Scale the pane along the split axis until clients re-fit it to the layout
+        let (new_cols, new_rows) = match resized {
+            Some((SplitDirection::Horizontal, factor)) => (
+                ((current_cols as f32) * factor).clamp(10.0, 500.0) as u16,
+                current_rows,
+            ),
+            Some((SplitDirection::Vertical, factor)) => (
+                current_cols,
+                ((current_rows as f32) * factor).clamp(5.0, 200.0) as u16,
+            ),
+            // A pane that fills its window has nothing to resize against
+            None => (current_cols, current_rows),
+        };
+
         if let Some(pane) = session_manager.find_pane_mut(pane_id) {
             pane.resize(new_cols, new_rows);
         }
@@ -776,6 +787,10 @@ impl HandlerContext {
             pane_id, current_cols, current_rows, new_cols, new_rows, delta
         );
 
+        if resized.is_some() {
+            self.broadcast_layout(window_id).await;
+        }
+
         // Return response to MCP client and broadcast to TUI clients (BUG-032)
         HandlerResult::ResponseWithBroadcast {
             response: ServerMessage::PaneResized {
@@ -1,7 +1,9 @@
 use tracing::{info, warn, debug};
 use fugue_protocol::{ErrorCode, ServerMessage};
-use crate::pty::{PtyConfig, PtyOutputPoller};
+use crate::pty::PtyOutputPoller;
+use crate::session::PaneLaunch;
 use crate::handlers::{HandlerContext, HandlerResult};
+use super::pane_command;
 
 impl HandlerContext {
     /// Handle CreateSessionWithOptions - create a session with full control
@@ -87,10 +89,11 @@ impl HandlerContext {
             }
         };
         pane.init_parser();
+        let pty_config = pane_command(pane, command.as_deref());
 
         // FEAT-071: Per-pane Claude configuration
         if claude_model.is_some() || claude_config.is_some() || preset.is_some() {
-            let config = &self.config;
+            let config = self.config.load_full();
             
             let mut final_config = serde_json::Map::new();
             
@@ -100,22 +103,7 @@ impl HandlerContext {
                     // Check if it's a Claude harness
                     if preset_cfg.harness == "claude" {
                         if let crate::config::HarnessConfig::Claude(claude_cfg) = &preset_cfg.config {
-                            if let Some(m) = &claude_cfg.model {
-                                final_config.insert("model".to_string(), serde_json::json!(m));
-                            }
-                            if let Some(c) = claude_cfg.context_limit {
-                                final_config.insert("context_limit".to_string(), serde_json::json!(c));
-                            }
-                            // Map other fields
-                            if let Some(sp) = &claude_cfg.system_prompt {
-                                final_config.insert("system_prompt".to_string(), serde_json::json!(sp));
-                            }
-                            if let Some(dsp) = &claude_cfg.dangerously_skip_permissions {
-                                final_config.insert("dangerously_skip_permissions".to_string(), serde_json::json!(dsp));
-                            }
-                            if let Some(at) = &claude_cfg.allowed_tools {
-                                final_config.insert("allowed_tools".to_string(), serde_json::json!(at));
-                            }
+                            final_config.extend(claude_cfg.to_settings());
                         }
                     }
                     debug!("Applied preset '{}' to pane {}", preset_name, pane_id);
@@ -164,18 +152,14 @@ impl HandlerContext {
         drop(session_manager);
 
         // Spawn PTY for the default pane
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into());
-        let mut config = if let Some(ref cmd) = command {
-            // Wrap user command in shell to handle arguments and shell syntax
-            PtyConfig::command("sh").with_arg("-c").with_arg(cmd)
-        } else {
-            PtyConfig::command(&shell)
-        };
+        let mut config = pty_config;
         if let Some(ref cwd) = cwd {
             config = config.with_cwd(cwd);
         }
         config = config.with_fugue_context(session_id, &session_name, window_id, pane_id);
 
+        self.record_launch(pane_id, PaneLaunch::from(&config)).await;
+
         {
             let mut pty_manager = self.pty_manager.write().await;
             match pty_manager.spawn(pane_id, config) {
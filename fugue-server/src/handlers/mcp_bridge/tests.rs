@@ -13,7 +13,7 @@ fn create_test_context() -> HandlerContext {
     let session_manager = Arc::new(RwLock::new(SessionManager::new()));
     let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
     let registry = Arc::new(ClientRegistry::new());
-    let config = Arc::new(crate::config::AppConfig::default());
+    let config = crate::config::new_config_handle();
     let arbitrator = Arc::new(Arbitrator::new());
     let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
         Arc::clone(&session_manager),
@@ -80,6 +80,8 @@ async fn test_list_all_panes_with_panes() {
             assert_eq!(panes.len(), 1);
             assert_eq!(panes[0].id, pane_id);
             assert_eq!(panes[0].session_name, "test");
+            // Falls back to the session's focus for a client without its own
+            assert!(panes[0].is_focused);
         }
         _ => panic!("Expected AllPanesList response"),
     }
@@ -172,6 +174,80 @@ async fn test_read_pane_success() {
     }
 }
 
+#[tokio::test]
+async fn test_capture_pane_reads_spilled_history() {
+    let ctx = create_test_context();
+    let (_session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut session_manager = ctx.session_manager.write().await;
+        let pane = session_manager.find_pane_mut(pane_id).unwrap();
+        let settings = Arc::new(crate::pty::SpillSettings {
+            dir: dir.path().to_path_buf(),
+            segment_lines: 100,
+            max_disk_bytes: 0,
+        });
+        *pane.scrollback_mut() = crate::pty::ScrollbackBuffer::with_spill(200, settings);
+        for i in 0..1000 {
+            pane.scrollback_mut().push_line(format!("line {}", i));
+        }
+    }
+
+    // 24-row screen: line 0 is "line 976", history goes back to "line 0"
+    let result = ctx.handle_capture_pane(pane_id, None, Some(-970)).await;
+    match result {
+        HandlerResult::Response(ServerMessage::PaneCaptured { start, lines, history_start, .. }) => {
+            assert_eq!(start, -976);
+            assert_eq!(history_start, -976);
+            assert_eq!(lines.len(), 7);
+            assert_eq!(lines[0], "line 0");
+        }
+        _ => panic!("Expected PaneCaptured response"),
+    }
+
+    let result = ctx.handle_capture_pane(pane_id, Some(-2), Some(1)).await;
+    match result {
+        HandlerResult::Response(ServerMessage::PaneCaptured { lines, .. }) => {
+            assert_eq!(lines, vec!["line 974", "line 975", "line 976", "line 977"]);
+        }
+        _ => panic!("Expected PaneCaptured response"),
+    }
+
+    // ReadPane reaches past the in-memory lines too
+    let result = ctx.handle_read_pane(pane_id, 500).await;
+    match result {
+        HandlerResult::Response(ServerMessage::PaneContent { content, .. }) => {
+            assert_eq!(content.lines().count(), 500);
+            assert!(content.starts_with("line 500\n"));
+        }
+        _ => panic!("Expected PaneContent response"),
+    }
+}
+
+#[tokio::test]
+async fn test_capture_pane_limits_bytes() {
+    let ctx = create_test_context();
+    let (_session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+    {
+        let mut session_manager = ctx.session_manager.write().await;
+        let pane = session_manager.find_pane_mut(pane_id).unwrap();
+        for _ in 0..20 {
+            pane.scrollback_mut().push_line("x".repeat(1024 * 1024));
+        }
+    }
+
+    // The rest of the range is left for the next page
+    let result = ctx.handle_capture_pane(pane_id, None, None).await;
+    match result {
+        HandlerResult::Response(ServerMessage::PaneCaptured { start, lines, history_start, .. }) => {
+            assert_eq!(start, history_start);
+            assert_eq!(lines.len(), 8);
+        }
+        _ => panic!("Expected PaneCaptured response"),
+    }
+}
+
 #[tokio::test]
 async fn test_get_pane_status_not_found() {
     let ctx = create_test_context();
@@ -315,6 +391,43 @@ async fn test_create_pane_with_options_creates_session() {
     }
 }
 
+#[tokio::test]
+async fn test_claude_panes_get_session_id() {
+    let ctx = create_test_context();
+    let result = ctx
+        .handle_create_session_with_options(Some("agents".to_string()), Some("/nonexistent/claude".to_string()), None, None, None, None, None)
+        .await;
+    let claude_pane = match result {
+        HandlerResult::ResponseWithGlobalBroadcast {
+            response: ServerMessage::SessionCreatedWithDetails { pane_id, .. },
+            ..
+        } => pane_id,
+        _ => panic!("Expected SessionCreatedWithDetails response with global broadcast"),
+    };
+
+    let result = ctx
+        .handle_create_pane_with_options(Some("agents".to_string()), None, SplitDirection::Vertical, Some("echo hi".to_string()), None, false, None, None, None, None)
+        .await;
+    let shell_pane = match result {
+        HandlerResult::ResponseWithBroadcast {
+            response: ServerMessage::PaneCreatedWithDetails { pane_id, .. },
+            ..
+        } => pane_id,
+        _ => panic!("Expected PaneCreatedWithDetails response with broadcast"),
+    };
+
+    let session_manager = ctx.session_manager.read().await;
+    let (_, _, pane) = session_manager.find_pane(claude_pane).unwrap();
+    let session_id = pane.agent_state().and_then(|state| state.session_id).unwrap();
+    let launch = pane.launch().unwrap();
+    assert_eq!(launch.command, "/nonexistent/claude");
+    assert_eq!(launch.args, vec!["--session-id".to_string(), session_id]);
+
+    let (_, _, pane) = session_manager.find_pane(shell_pane).unwrap();
+    assert!(pane.agent_state().is_none());
+    assert!(!pane.launch().unwrap().args.contains(&"--session-id".to_string()));
+}
+
 // ==================== MCP-to-TUI Broadcast Integration Tests (BUG-010) ====================
 
 /// Test that MCP pane creation broadcasts to TUI clients
@@ -327,7 +440,7 @@ async fn test_mcp_pane_creation_broadcasts_to_tui() {
     let session_manager = Arc::new(RwLock::new(SessionManager::new()));
     let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
     let registry = Arc::new(ClientRegistry::new());
-    let config = Arc::new(crate::config::AppConfig::default());
+    let config = crate::config::new_config_handle();
     let arbitrator = Arc::new(Arbitrator::new());
     let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
         Arc::clone(&session_manager),
@@ -431,7 +544,7 @@ async fn test_mcp_broadcast_fails_with_session_mismatch() {
     let session_manager = Arc::new(RwLock::new(SessionManager::new()));
     let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
     let registry = Arc::new(ClientRegistry::new());
-    let config = Arc::new(crate::config::AppConfig::default());
+    let config = crate::config::new_config_handle();
     let arbitrator = Arc::new(Arbitrator::new());
     let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
         Arc::clone(&session_manager),
@@ -544,7 +657,7 @@ async fn test_mcp_split_pane_broadcasts_to_tui() {
     let session_manager = Arc::new(RwLock::new(SessionManager::new()));
     let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
     let registry = Arc::new(ClientRegistry::new());
-    let config = Arc::new(crate::config::AppConfig::default());
+    let config = crate::config::new_config_handle();
     let arbitrator = Arc::new(Arbitrator::new());
     let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
         Arc::clone(&session_manager),
@@ -637,7 +750,7 @@ async fn test_mcp_resize_pane_broadcasts_to_tui() {
     let session_manager = Arc::new(RwLock::new(SessionManager::new()));
     let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
     let registry = Arc::new(ClientRegistry::new());
-    let config = Arc::new(crate::config::AppConfig::default());
+    let config = crate::config::new_config_handle();
     let arbitrator = Arc::new(Arbitrator::new());
     let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
         Arc::clone(&session_manager),
@@ -1086,3 +1199,135 @@ async fn test_create_pane_blocked_by_human_activity() {
         _ => panic!("Expected UserPriorityActive error, got {:?}", std::mem::discriminant(&result)),
     }
 }
+
+// ==================== Server-side Expect ====================
+
+#[tokio::test]
+async fn test_expect_matches_existing_scrollback() {
+    use fugue_protocol::{ExpectCondition, ExpectOutcome};
+
+    let ctx = create_test_context();
+    let (_session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+    ctx.session_manager
+        .write()
+        .await
+        .find_pane_mut(pane_id)
+        .unwrap()
+        .process(b"build \x1b[1mfinished\x1b[0m in 3s\r\n");
+
+    let condition = ExpectCondition::Output {
+        patterns: vec![r"finished in (\d+)s".into()],
+    };
+    let result = ctx.handle_expect(pane_id, condition, 1000, 10).await;
+
+    match result {
+        HandlerResult::Response(ServerMessage::ExpectResult {
+            outcome: ExpectOutcome::Matched { captures, .. },
+            ..
+        }) => {
+            assert_eq!(captures[1].as_deref(), Some("3"));
+        }
+        other => panic!("Expected immediate ExpectResult, got {:?}", std::mem::discriminant(&other)),
+    }
+    assert_eq!(ctx.registry.expect().pending(), 0);
+}
+
+#[tokio::test]
+async fn test_expect_waits_for_broadcast_output() {
+    use fugue_protocol::{ExpectCondition, ExpectOutcome};
+
+    let mut ctx = create_test_context();
+    let (tx, mut rx) = mpsc::channel(10);
+    ctx.client_id = ctx.registry.register_client(tx);
+    let (session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+
+    let condition = ExpectCondition::Output {
+        patterns: vec!["ready".into()],
+    };
+    let result = ctx.handle_expect(pane_id, condition, 5000, 0).await;
+    assert!(matches!(result, HandlerResult::NoResponse));
+    assert_eq!(ctx.registry.expect().pending(), 1);
+
+    ctx.registry
+        .broadcast_to_session(
+            session_id,
+            ServerMessage::Output {
+                pane_id,
+                data: b"server ready\n".to_vec(),
+            },
+        )
+        .await;
+
+    let reply = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    match reply {
+        ServerMessage::ExpectResult {
+            pane_id: id,
+            outcome: ExpectOutcome::Matched { line, .. },
+            ..
+        } => {
+            assert_eq!(id, pane_id);
+            assert_eq!(line, "server ready");
+        }
+        other => panic!("Expected ExpectResult, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_expect_times_out() {
+    use fugue_protocol::{AgentActivity, ExpectCondition, ExpectOutcome};
+
+    let mut ctx = create_test_context();
+    let (tx, mut rx) = mpsc::channel(10);
+    ctx.client_id = ctx.registry.register_client(tx);
+    let (_session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+
+    let condition = ExpectCondition::AgentActivity {
+        activities: vec![AgentActivity::Idle],
+    };
+    let result = ctx.handle_expect(pane_id, condition, 20, 0).await;
+    assert!(matches!(result, HandlerResult::NoResponse));
+
+    let reply = tokio::time::timeout(std::time::Duration::from_secs(5), rx.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        reply,
+        ServerMessage::ExpectResult {
+            outcome: ExpectOutcome::Timeout,
+            ..
+        }
+    ));
+    assert_eq!(ctx.registry.expect().pending(), 0);
+}
+
+#[tokio::test]
+async fn test_expect_invalid_pattern_and_missing_pane() {
+    use fugue_protocol::ExpectCondition;
+
+    let ctx = create_test_context();
+    let (_session_id, _window_id, pane_id) = create_session_with_pane(&ctx).await;
+
+    let bad = ExpectCondition::Output {
+        patterns: vec!["(".into()],
+    };
+    match ctx.handle_expect(pane_id, bad, 1000, 0).await {
+        HandlerResult::Response(ServerMessage::Error { code, .. }) => {
+            assert_eq!(code, ErrorCode::InvalidOperation);
+        }
+        _ => panic!("Expected Error response"),
+    }
+
+    let ok = ExpectCondition::Output {
+        patterns: vec!["x".into()],
+    };
+    match ctx.handle_expect(Uuid::new_v4(), ok, 1000, 0).await {
+        HandlerResult::Response(ServerMessage::Error { code, .. }) => {
+            assert_eq!(code, ErrorCode::PaneNotFound);
+        }
+        _ => panic!("Expected Error response"),
+    }
+}
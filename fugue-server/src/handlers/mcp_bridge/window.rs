@@ -1,8 +1,10 @@
 use tracing::{info, warn, debug};
 use uuid::Uuid;
 use fugue_protocol::{ErrorCode, ServerMessage, WindowInfo, SplitDirection};
-use crate::pty::{PtyConfig, PtyOutputPoller};
+use crate::pty::PtyOutputPoller;
+use crate::session::PaneLaunch;
 use crate::handlers::{HandlerContext, HandlerResult};
+use super::pane_command;
 
 impl HandlerContext {
     /// Handle ListWindows - list all windows in a session
@@ -169,6 +171,7 @@ impl HandlerContext {
             }
         };
         pane.init_parser();
+        let pty_config = pane_command(pane, command.as_deref());
 
         // Broadcast WindowCreated to all clients in session (BUG-032)
         let window_info = window.to_info();
@@ -180,13 +183,7 @@ impl HandlerContext {
         drop(session_manager);
 
         // Spawn PTY
-        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".into());
-        let mut config = if let Some(ref cmd) = command {
-            // Wrap user command in shell to handle arguments and shell syntax
-            PtyConfig::command("sh").with_arg("-c").with_arg(cmd)
-        } else {
-            PtyConfig::command(&shell)
-        };
+        let mut config = pty_config;
         // BUG-050: Apply cwd if provided
         if let Some(ref cwd) = cwd {
             config = config.with_cwd(cwd);
@@ -195,6 +192,8 @@ impl HandlerContext {
         // Apply session environment variables
         config = config.with_env_map(&session_env);
 
+        self.record_launch(pane_id, PaneLaunch::from(&config)).await;
+
         {
             let mut pty_manager = self.pty_manager.write().await;
             match pty_manager.spawn(pane_id, config) {
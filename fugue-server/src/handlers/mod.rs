@@ -3,12 +3,17 @@
 //! This module provides the complete message handling layer that routes incoming
 //! `ClientMessage` types to appropriate handlers and responds with `ServerMessage` types.
 
+mod buffers;
 mod connection;
+mod export;
 mod input;
 mod mcp_bridge;
 mod orchestration;
 mod pane;
+mod search;
 mod session;
+mod snapshot;
+mod workspace;
 
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
@@ -17,12 +22,12 @@ use uuid::Uuid;
 use fugue_protocol::{ClientMessage, ErrorCode, ServerMessage, messages::ErrorDetails};
 
 use crate::arbitration::{Action, Actor, Arbitrator, Resource};
-use crate::config::AppConfig;
+use crate::config::ConfigHandle;
 use crate::observability::Metrics;
 use crate::persistence::PersistenceManager;
 use crate::pty::{PaneClosedNotification, PtyManager};
 use crate::registry::{ClientId, ClientRegistry};
-use crate::session::{Session, SessionManager, Window};
+use crate::session::{PaneLaunch, Session, SessionManager, Window};
 use crate::sideband::AsyncCommandExecutor;
 use crate::watchdog::WatchdogManager;
 
@@ -36,8 +41,8 @@ pub struct HandlerContext {
     pub pty_manager: Arc<RwLock<PtyManager>>,
     /// Client connection registry for tracking and broadcasting
     pub registry: Arc<ClientRegistry>,
-    /// Application configuration
-    pub config: Arc<AppConfig>,
+    /// Application configuration, swapped on reload
+    pub config: ConfigHandle,
     /// The client making this request
     pub client_id: ClientId,
     /// Channel to notify when panes close (for cleanup)
@@ -94,7 +99,7 @@ impl HandlerContext {
         session_manager: Arc<RwLock<SessionManager>>,
         pty_manager: Arc<RwLock<PtyManager>>,
         registry: Arc<ClientRegistry>,
-        config: Arc<AppConfig>,
+        config: ConfigHandle,
         client_id: ClientId,
         pane_closed_tx: mpsc::Sender<PaneClosedNotification>,
         command_executor: Arc<AsyncCommandExecutor>,
@@ -164,14 +169,60 @@ impl HandlerContext {
     }
 
     /// Resolve the current pane for the client within a window (FEAT-078)
-    #[allow(dead_code)] // API surface consistent with resolve_active_session/window
     pub fn resolve_active_pane(&self, window: &Window) -> Option<Uuid> {
         self.registry
             .get_client_focus(self.client_id)
             .and_then(|f| f.active_pane_id)
+            .filter(|id| window.get_pane(*id).is_some())
             .or_else(|| window.active_pane_id())
     }
 
+    /// Log a window's layout and broadcast it to every client attached to its session
+    ///
+    /// Must be called without holding the session manager lock.
+    pub async fn broadcast_layout(&self, window_id: Uuid) {
+        let found = {
+            let session_manager = self.session_manager.read().await;
+            session_manager
+                .find_window(window_id)
+                .and_then(|(session, window)| window.to_layout().map(|layout| (session.id(), layout)))
+        };
+        if let Some((session_id, layout)) = found {
+            if let Some(persistence_lock) = &self.persistence {
+                let persistence = persistence_lock.read().await;
+                let _ = persistence.log_window_layout_changed(window_id, Some(layout.root.clone()));
+            }
+            self.registry
+                .broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout })
+                .await;
+        }
+    }
+
+    /// Remember the program a pane was launched with so it can be restored
+    ///
+    /// Must be called without holding the session manager lock.
+    pub async fn record_launch(&self, pane_id: Uuid, launch: PaneLaunch) {
+        {
+            let mut session_manager = self.session_manager.write().await;
+            if let Some(pane) = session_manager.find_pane_mut(pane_id) {
+                pane.set_launch(Some(launch.clone()));
+            }
+        }
+        if let Some(persistence_lock) = &self.persistence {
+            let persistence = persistence_lock.read().await;
+            let _ = persistence.log_pane_launched(pane_id, launch.command, launch.args, launch.env);
+        }
+    }
+
+    /// `LayoutChanged` for every window of a session, sent after attaching
+    pub fn layout_messages(session: &Session) -> Vec<ServerMessage> {
+        session
+            .windows()
+            .filter_map(|window| window.to_layout())
+            .map(|layout| ServerMessage::LayoutChanged { layout })
+            .collect()
+    }
+
     /// Route a message to the appropriate handler
     pub async fn route_message(&self, msg: ClientMessage) -> HandlerResult {
         // FEAT-074: Record request metric by message type
@@ -185,8 +236,16 @@ impl HandlerContext {
                 client_type,
             } => self.handle_connect(client_id, protocol_version, client_type).await,
 
+            ClientMessage::Authenticate { .. } => self.handle_authenticate(),
+
             ClientMessage::Ping => self.handle_ping(),
 
+            ClientMessage::SubscribeEvents { include_output } => {
+                self.handle_subscribe_events(include_output)
+            }
+
+            ClientMessage::UnsubscribeEvents => self.handle_unsubscribe_events(),
+
             ClientMessage::Sync => self.handle_sync().await,
 
             ClientMessage::GetServerStatus => self.handle_get_server_status().await,
@@ -231,6 +290,12 @@ impl HandlerContext {
             // Input handlers
             ClientMessage::Input { pane_id, data } => self.handle_input(pane_id, data).await,
 
+            ClientMessage::ResolvePermission { pane_id, decision } => {
+                self.handle_resolve_permission(pane_id, decision).await
+            }
+
+            ClientMessage::ResumePane { pane_id } => self.handle_resume_pane(pane_id).await,
+
             ClientMessage::Paste { pane_id, data } => self.handle_paste(pane_id, data).await,
 
             ClientMessage::Reply { reply } => self.handle_reply(reply).await,
@@ -266,6 +331,13 @@ impl HandlerContext {
                 self.handle_read_pane(pane_id, lines).await
             }
 
+            ClientMessage::Expect {
+                pane_id,
+                condition,
+                timeout_ms,
+                lines,
+            } => self.handle_expect(pane_id, condition, timeout_ms, lines).await,
+
             ClientMessage::GetPaneStatus { pane_id } => {
                 self.handle_get_pane_status(pane_id).await
             }
@@ -482,6 +554,78 @@ impl HandlerContext {
             ClientMessage::WatchdogStop { name } => self.handle_watchdog_stop(name).await,
 
             ClientMessage::WatchdogStatus { name } => self.handle_watchdog_status(name).await,
+
+            // Named snapshots and point-in-time restore
+            ClientMessage::SaveSnapshot {
+                name,
+                session_filter,
+            } => self.handle_save_snapshot(name, session_filter).await,
+
+            ClientMessage::ListSnapshots => self.handle_list_snapshots().await,
+
+            ClientMessage::DeleteSnapshot { name } => self.handle_delete_snapshot(name).await,
+
+            ClientMessage::RestoreSnapshot {
+                source,
+                session_filter,
+                clone_as,
+            } => {
+                self.handle_restore_snapshot(source, session_filter, clone_as)
+                    .await
+            }
+
+            // Portable session export/import
+            ClientMessage::ExportSession {
+                session_filter,
+                format,
+                include_scrollback,
+            } => {
+                self.handle_export_session(session_filter, format, include_scrollback)
+                    .await
+            }
+
+            ClientMessage::ImportSession { document, name } => {
+                self.handle_import_session(document, name).await
+            }
+
+            ClientMessage::WorkspaceUp { document, base_dir } => {
+                self.handle_workspace_up(document, base_dir).await
+            }
+
+            ClientMessage::WorkspaceDown { document } => {
+                self.handle_workspace_down(document).await
+            }
+
+            ClientMessage::SearchScrollback {
+                query,
+                session_filter,
+                context,
+                limit,
+            } => {
+                self.handle_search_scrollback(query, session_filter, context, limit)
+                    .await
+            }
+
+            ClientMessage::CapturePane { pane_id, start, end } => {
+                self.handle_capture_pane(pane_id, start, end).await
+            }
+
+            ClientMessage::SetBuffer { name, data, append } => {
+                self.handle_set_buffer(name, data, append)
+            }
+
+            ClientMessage::ListBuffers => self.handle_list_buffers(),
+
+            ClientMessage::GetBuffer { name } => self.handle_get_buffer(name),
+
+            ClientMessage::DeleteBuffer { name } => self.handle_delete_buffer(name),
+
+            ClientMessage::PasteBuffer {
+                name,
+                pane_id,
+                delete,
+                bracketed,
+            } => self.handle_paste_buffer(name, pane_id, delete, bracketed).await,
         }
     }
 
@@ -581,7 +725,7 @@ impl HandlerContext {
         };
 
         // Get config for timeout
-        let timeout_ms = self.config.beads.query.socket_timeout;
+        let timeout_ms = self.config.load().beads.query.socket_timeout;
 
         // Try to create a beads client and get status
         let status = if let Some(client) = BeadsClient::new(&cwd, timeout_ms) {
@@ -608,7 +752,7 @@ impl HandlerContext {
         };
 
         // Get config for timeout
-        let timeout_ms = self.config.beads.query.socket_timeout;
+        let timeout_ms = self.config.load().beads.query.socket_timeout;
 
         // Try to create a beads client and get tasks
         let tasks = if let Some(client) = BeadsClient::new(&cwd, timeout_ms) {
@@ -645,7 +789,7 @@ impl HandlerContext {
                         .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
                 };
 
-                let timeout_ms = self.config.beads.query.socket_timeout;
+                let timeout_ms = self.config.load().beads.query.socket_timeout;
 
                 let status = if let Some(client) = BeadsClient::new(&cwd, timeout_ms) {
                     client.get_status(Some(10)).await
@@ -673,7 +817,7 @@ impl HandlerContext {
                         .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
                 };
 
-                let timeout_ms = self.config.beads.query.socket_timeout;
+                let timeout_ms = self.config.load().beads.query.socket_timeout;
 
                 let tasks = if let Some(client) = BeadsClient::new(&cwd, timeout_ms) {
                     client.query_ready(Some(100)).await.unwrap_or_default()
@@ -777,7 +921,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let command_executor = Arc::new(AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
             Arc::clone(&pty_manager),
@@ -6,6 +6,9 @@ use tracing::{debug, info};
 
 use fugue_protocol::{ErrorCode, OrchestrationMessage, OrchestrationTarget, ServerMessage};
 
+use crate::session::Session;
+use crate::usage;
+
 use super::{HandlerContext, HandlerResult};
 
 impl HandlerContext {
@@ -198,7 +201,7 @@ impl HandlerContext {
             };
 
             if let Some(session) = session_manager.get_session(session_id) {
-                let status = session.get_status().cloned().unwrap_or(serde_json::Value::Null);
+                let status = worker_status_with_usage(session);
                 HandlerResult::Response(ServerMessage::WorkerStatus {
                     status: fugue_protocol::types::JsonValue::new(status),
                 })
@@ -213,8 +216,7 @@ impl HandlerContext {
             // This returns a map of {worker_id: status}
             let mut all_statuses = serde_json::Map::new();
             for session in session_manager.list_sessions() {
-                let status = session.get_status().cloned().unwrap_or(serde_json::Value::Null);
-                all_statuses.insert(session.id().to_string(), status);
+                all_statuses.insert(session.id().to_string(), worker_status_with_usage(session));
             }
             HandlerResult::Response(ServerMessage::WorkerStatus {
                 status: fugue_protocol::types::JsonValue::new(serde_json::Value::Object(all_statuses)),
@@ -277,6 +279,19 @@ impl HandlerContext {
     }
 }
 
+/// Worker status reported by the session, with its token usage attached
+fn worker_status_with_usage(session: &Session) -> serde_json::Value {
+    let usage = usage::session_usage_json(session);
+    match session.get_status().cloned() {
+        Some(serde_json::Value::Object(mut map)) => {
+            map.insert("usage".to_string(), usage);
+            serde_json::Value::Object(map)
+        }
+        None | Some(serde_json::Value::Null) => serde_json::json!({ "usage": usage }),
+        Some(status) => serde_json::json!({ "status": status, "usage": usage }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -294,7 +309,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let arbitrator = Arc::new(Arbitrator::new());
         let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
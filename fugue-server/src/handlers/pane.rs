@@ -12,14 +12,15 @@ use fugue_protocol::{ErrorCode, ServerMessage, SplitDirection, messages::ErrorDe
 use crate::arbitration::{Action, Resource};
 use crate::beads::{self, metadata_keys};
 use crate::pty::{PtyConfig, PtyOutputPoller};
+use crate::session::PaneLaunch;
 
 use super::{HandlerContext, HandlerResult};
 
 impl HandlerContext {
     /// Handle CreatePane message - create a new pane in a window
     ///
-    /// Note: The direction parameter is currently stored for future split implementation
-    /// but all panes are created at the default position for now.
+    /// The new pane splits the client's focused pane (or the window's active
+    /// pane) along `direction`.
     pub async fn handle_create_pane(
         &self,
         window_id: Uuid,
@@ -57,7 +58,8 @@ impl HandlerContext {
                         let env = session.environment().clone();
                         let session_name = session.name().to_string();
                         if let Some(window) = session.get_window_mut(window_id) {
-                            let pane = window.create_pane();
+                            let target = self.resolve_active_pane(window);
+                            let pane = window.create_pane_split(target, direction, 0.5);
                             let pane_info = pane.to_info();
                             let pane_id = pane_info.id;
 
@@ -93,11 +95,12 @@ impl HandlerContext {
         };
 
         // Spawn PTY for the new pane
-        let beads_detection: Option<PathBuf> = {
+        let (beads_detection, launch): (Option<PathBuf>, PaneLaunch) = {
             let mut pty_manager = self.pty_manager.write().await;
+            let config = self.config.load_full();
 
             // Use default_command from config if set, otherwise shell
-            let mut pty_config = if let Some(ref cmd) = self.config.general.default_command {
+            let mut pty_config = if let Some(ref cmd) = config.general.default_command {
                 PtyConfig::command(cmd).with_size(cols, rows)
             } else {
                 PtyConfig::shell().with_size(cols, rows)
@@ -110,7 +113,7 @@ impl HandlerContext {
                 pty_config = pty_config.with_cwd(&cwd);
 
                 // FEAT-057: Detect beads root and configure environment
-                if self.config.beads.auto_detect {
+                if config.beads.auto_detect {
                     if let Some(detection) = beads::detect_beads_root(&cwd) {
                         info!(
                             "Beads detected for pane {}: {:?}",
@@ -118,7 +121,7 @@ impl HandlerContext {
                         );
                         pty_config = pty_config.with_beads_config(
                             &detection.beads_dir,
-                            &self.config.beads,
+                            &config.beads,
                         );
                         detected_beads = Some(detection.beads_dir);
                     }
@@ -128,6 +131,7 @@ impl HandlerContext {
 
             // Apply session environment variables
             pty_config = pty_config.with_env_map(&session_env);
+            let launch = PaneLaunch::from(&pty_config);
 
             match pty_manager.spawn(pane_id, pty_config) {
                 Ok(handle) => {
@@ -150,8 +154,9 @@ impl HandlerContext {
                 }
             }
 
-            detected_beads
+            (detected_beads, launch)
         };
+        self.record_launch(pane_id, launch).await;
 
         // FEAT-057: Store beads state in pane and session metadata
         if let Some(beads_dir) = beads_detection {
@@ -306,12 +311,14 @@ impl HandlerContext {
         // FEAT-079: Record human activity if this is a human actor
         self.record_human_activity(Resource::Window(window_id), Action::Layout);
 
-        // Remove PTY if exists
+        // Remove PTY if exists, keeping the exit code if it already exited
+        let mut exit_code = None;
         {
             let mut pty_manager = self.pty_manager.write().await;
             if let Some(handle) = pty_manager.remove(pane_id) {
-                if let Err(e) = handle.kill() {
-                    warn!("Failed to kill PTY for pane {}: {}", pane_id, e);
+                match handle.exit_code_or_kill() {
+                    Ok(code) => exit_code = code,
+                    Err(e) => warn!("Failed to kill PTY for pane {}: {}", pane_id, e),
                 }
             }
         }
@@ -327,17 +334,19 @@ impl HandlerContext {
 
                     info!("Pane {} closed successfully", pane_id);
 
+                    let layout = window.to_layout();
+                    drop(session_manager);
+                    if let Some(layout) = layout {
+                        self.registry
+                            .broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout })
+                            .await;
+                    }
+
                     // Broadcast to all clients attached to this session
                     return HandlerResult::ResponseWithBroadcast {
-                        response: ServerMessage::PaneClosed {
-                            pane_id,
-                            exit_code: None,
-                        },
+                        response: ServerMessage::PaneClosed { pane_id, exit_code },
                         session_id,
-                        broadcast: ServerMessage::PaneClosed {
-                            pane_id,
-                            exit_code: None,
-                        },
+                        broadcast: ServerMessage::PaneClosed { pane_id, exit_code },
                     };
                 }
             }
@@ -444,6 +453,55 @@ impl HandlerContext {
         HandlerResult::NoResponse
     }
 
+    /// Handle ResumePane - continue a pane paused by a usage budget
+    pub async fn handle_resume_pane(&self, pane_id: Uuid) -> HandlerResult {
+        let mut session_manager = self.session_manager.write().await;
+        let Some(pane) = session_manager.find_pane_mut(pane_id) else {
+            return HandlerContext::error(
+                ErrorCode::PaneNotFound,
+                format!("Pane {} not found", pane_id),
+            );
+        };
+        if !pane.is_paused() {
+            return HandlerContext::error(
+                ErrorCode::InvalidOperation,
+                format!("Pane {} is not paused", pane_id),
+            );
+        }
+
+        let pty_manager = self.pty_manager.read().await;
+        if let Some(handle) = pty_manager.get(pane_id) {
+            if let Err(e) = handle.resume() {
+                warn!("Failed to resume pane {}: {}", pane_id, e);
+                return HandlerContext::error(
+                    ErrorCode::InternalError,
+                    format!("Failed to resume pane: {}", e),
+                );
+            }
+        }
+        pane.set_paused(false);
+        crate::usage::rearm_budgets(
+            &self.config.load().usage,
+            self.registry.fired_budgets(),
+            &session_manager,
+            pane_id,
+        );
+        info!("Resumed pane {} paused by usage budget", pane_id);
+
+        let session_id = session_manager
+            .find_pane(pane_id)
+            .map(|(session, _, _)| session.id());
+        let resumed = ServerMessage::PaneResumed { pane_id };
+        match session_id {
+            Some(session_id) => HandlerResult::ResponseWithBroadcast {
+                response: resumed.clone(),
+                session_id,
+                broadcast: resumed,
+            },
+            None => HandlerResult::Response(resumed),
+        }
+    }
+
     // ==================== Mirror Pane Handler (FEAT-062) ====================
 
     /// Handle CreateMirror message - create a read-only mirror of another pane
@@ -534,7 +592,8 @@ impl HandlerContext {
             let mirror_info = mirror_pane.to_info();
 
             // Add the pane to the window
-            window.add_pane(mirror_pane);
+            let target = self.resolve_active_pane(window);
+            window.add_pane_split(mirror_pane, target, direction, 0.5);
 
             (mirror_info, session_id, window_id, session_name)
         };
@@ -639,6 +698,10 @@ impl HandlerContext {
             Some("top") | Some("bottom") => SplitDirection::Vertical,
             _ => SplitDirection::Horizontal,
         };
+        // Share of the space the split pane keeps
+        let ratio = width_percent
+            .map(|percent| 1.0 - percent.clamp(10, 90) as f32 / 100.0)
+            .unwrap_or(0.5);
 
         // Create the status pane
         let pane_info = {
@@ -646,7 +709,8 @@ impl HandlerContext {
             
             if let Some(session) = session_manager.get_session_mut(session_id) {
                 if let Some(window) = session.get_window_mut(window_id) {
-                    let pane_id = window.create_pane().id();
+                    let target = self.resolve_active_pane(window);
+                    let pane_id = window.create_pane_split(target, direction, ratio).id();
                     
                     // Configure as status pane
                     if let Some(pane) = window.get_pane_mut(pane_id) {
@@ -705,7 +769,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let arbitrator = Arc::new(Arbitrator::new());
         let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
@@ -896,6 +960,44 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_handle_resume_pane() {
+        let ctx = create_test_context();
+        let result = ctx.handle_resume_pane(Uuid::new_v4()).await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error { code: ErrorCode::PaneNotFound, .. })
+        ));
+
+        let (session_id, window_id) = create_session_with_window(&ctx).await;
+        let pane_id = {
+            let mut session_manager = ctx.session_manager.write().await;
+            let session = session_manager.get_session_mut(session_id).unwrap();
+            session.get_window_mut(window_id).unwrap().create_pane().id()
+        };
+        let result = ctx.handle_resume_pane(pane_id).await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error { code: ErrorCode::InvalidOperation, .. })
+        ));
+
+        ctx.session_manager
+            .write()
+            .await
+            .find_pane_mut(pane_id)
+            .unwrap()
+            .set_paused(true);
+        match ctx.handle_resume_pane(pane_id).await {
+            HandlerResult::ResponseWithBroadcast { response, session_id: id, .. } => {
+                assert_eq!(response, ServerMessage::PaneResumed { pane_id });
+                assert_eq!(id, session_id);
+            }
+            _ => panic!("Expected PaneResumed"),
+        }
+        let session_manager = ctx.session_manager.read().await;
+        assert!(!session_manager.find_pane(pane_id).unwrap().2.is_paused());
+    }
+
     #[tokio::test]
     async fn test_handle_resize_success() {
         let ctx = create_test_context();
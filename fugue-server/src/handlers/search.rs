@@ -0,0 +1,261 @@
+//! Scrollback search handler
+//!
+//! Handles: SearchScrollback
+
+use tracing::debug;
+use uuid::Uuid;
+
+use fugue_protocol::{ErrorCode, ScrollbackHit, ServerMessage};
+
+use crate::pty::{plain_text, ScrollbackBuffer, SearchQuery};
+
+use super::{HandlerContext, HandlerResult};
+
+/// Most hits returned for one search
+const MAX_SEARCH_HITS: usize = 1000;
+
+/// Most context lines returned around a hit
+const MAX_SEARCH_CONTEXT: usize = 20;
+
+impl HandlerContext {
+    /// Handle SearchScrollback - find lines matching a query in every pane
+    ///
+    /// When there are more hits than `limit`, they are shared out evenly so
+    /// that one noisy pane doesn't hide the others.
+    pub async fn handle_search_scrollback(
+        &self,
+        query: String,
+        session_filter: Option<String>,
+        context: usize,
+        limit: usize,
+    ) -> HandlerResult {
+        let Some(parsed) = SearchQuery::parse(&query) else {
+            return HandlerContext::error(
+                ErrorCode::InvalidOperation,
+                "Search query has no words to search for",
+            );
+        };
+        let context = context.min(MAX_SEARCH_CONTEXT);
+        let limit = limit.clamp(1, MAX_SEARCH_HITS);
+
+        let session_manager = self.session_manager.read().await;
+        let sessions = match &session_filter {
+            Some(filter) => {
+                let session = match Uuid::parse_str(filter) {
+                    Ok(id) => session_manager.get_session(id),
+                    Err(_) => session_manager.get_session_by_name(filter),
+                };
+                match session {
+                    Some(session) => vec![session],
+                    None => {
+                        return HandlerContext::error(
+                            ErrorCode::SessionNotFound,
+                            format!("Session '{}' not found", filter),
+                        )
+                    }
+                }
+            }
+            None => session_manager.list_sessions(),
+        };
+
+        let panes: Vec<_> = sessions
+            .into_iter()
+            .flat_map(|session| session.windows().map(move |window| (session, window)))
+            .flat_map(|(session, window)| window.panes().map(move |pane| (session, window, pane)))
+            .map(|(session, window, pane)| {
+                let matches = pane.scrollback().find(&parsed);
+                (session, window, pane, matches)
+            })
+            .collect();
+
+        // Take hits round-robin, newest first in each pane
+        let total: usize = panes.iter().map(|(_, _, _, matches)| matches.len()).sum();
+        let mut taken = vec![0; panes.len()];
+        let mut remaining = limit.min(total);
+        while remaining > 0 {
+            for (count, (_, _, _, matches)) in taken.iter_mut().zip(&panes) {
+                if remaining > 0 && *count < matches.len() {
+                    *count += 1;
+                    remaining -= 1;
+                }
+            }
+        }
+
+        let mut hits = Vec::new();
+        for ((session, window, pane, matches), count) in panes.iter().zip(taken) {
+            let buffer = pane.scrollback();
+            for (seq, line) in &matches[..count] {
+                let seq = *seq;
+                hits.push(ScrollbackHit {
+                    session_id: session.id(),
+                    session_name: session.name().to_string(),
+                    window_id: window.id(),
+                    window_index: window.index(),
+                    pane_id: pane.id(),
+                    pane_index: pane.index(),
+                    lines_from_end: (buffer.total_pushed() - 1 - seq) as usize,
+                    text: plain_text(line),
+                    before: context_lines(buffer, seq.saturating_sub(context as u64), seq),
+                    after: context_lines(buffer, seq + 1, seq + 1 + context as u64),
+                });
+            }
+        }
+
+        debug!(
+            "SearchScrollback '{}' from {}: {} of {} hits",
+            query,
+            self.client_id,
+            hits.len(),
+            total
+        );
+
+        HandlerResult::Response(ServerMessage::ScrollbackSearchResults {
+            query,
+            hits,
+            truncated: total > limit,
+        })
+    }
+}
+
+/// Plain text of the lines in `start..end` that are still in the history
+fn context_lines(buffer: &ScrollbackBuffer, start: u64, end: u64) -> Vec<String> {
+    buffer
+        .read_history(start, end)
+        .unwrap_or_default()
+        .iter()
+        .map(|line| plain_text(line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitration::Arbitrator;
+    use crate::pty::PtyManager;
+    use crate::registry::ClientRegistry;
+    use crate::session::SessionManager;
+    use crate::sideband::AsyncCommandExecutor;
+    use crate::watchdog::WatchdogManager;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, RwLock};
+
+    fn create_test_context() -> HandlerContext {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+        let registry = Arc::new(ClientRegistry::new());
+        let config = crate::config::new_config_handle();
+
+        let (tx, _rx) = mpsc::channel(10);
+        let client_id = registry.register_client(tx);
+
+        let (pane_closed_tx, _) = mpsc::channel(10);
+        let command_executor = Arc::new(AsyncCommandExecutor::new(
+            Arc::clone(&session_manager),
+            Arc::clone(&pty_manager),
+            Arc::clone(&registry),
+        ));
+
+        HandlerContext::new(
+            session_manager,
+            pty_manager,
+            registry,
+            config,
+            client_id,
+            pane_closed_tx,
+            command_executor,
+            Arc::new(Arbitrator::new()),
+            None,
+            Arc::new(WatchdogManager::new()),
+        )
+    }
+
+    async fn add_pane(ctx: &HandlerContext, session: &str, lines: &[&str]) -> Uuid {
+        let mut session_manager = ctx.session_manager.write().await;
+        let session_id = session_manager.create_session(session).unwrap().id();
+
+        let session = session_manager.get_session_mut(session_id).unwrap();
+        let window_id = session.create_window(None).id();
+        let pane_id = session.get_window_mut(window_id).unwrap().create_pane().id();
+
+        let pane = session_manager.find_pane_mut(pane_id).unwrap();
+        for line in lines {
+            pane.scrollback_mut().push_line(line.to_string());
+        }
+        pane_id
+    }
+
+    #[tokio::test]
+    async fn test_search_returns_hits_with_context() {
+        let ctx = create_test_context();
+        let api = add_pane(
+            &ctx,
+            "api",
+            &["starting", "\x1b[31mERROR\x1b[0m redis: connection refused", "retrying", "ok"],
+        )
+        .await;
+        add_pane(&ctx, "web", &["compiled successfully"]).await;
+
+        let result = ctx
+            .handle_search_scrollback("redis refused".into(), None, 1, 10)
+            .await;
+        match result {
+            HandlerResult::Response(ServerMessage::ScrollbackSearchResults { hits, truncated, .. }) => {
+                assert!(!truncated);
+                assert_eq!(hits.len(), 1);
+                assert_eq!(hits[0].pane_id, api);
+                assert_eq!(hits[0].session_name, "api");
+                assert_eq!(hits[0].text, "ERROR redis: connection refused");
+                assert_eq!(hits[0].before, vec!["starting"]);
+                assert_eq!(hits[0].after, vec!["retrying"]);
+                assert_eq!(hits[0].lines_from_end, 2);
+            }
+            _ => panic!("Expected ScrollbackSearchResults"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_limit_is_shared_between_panes() {
+        let ctx = create_test_context();
+        let noisy: Vec<String> = (0..20).map(|i| format!("warning {}", i)).collect();
+        let noisy: Vec<&str> = noisy.iter().map(String::as_str).collect();
+        let noisy = add_pane(&ctx, "noisy", &noisy).await;
+        let quiet = add_pane(&ctx, "quiet", &["warning: unused variable"]).await;
+
+        let result = ctx
+            .handle_search_scrollback("warning".into(), None, 0, 5)
+            .await;
+        match result {
+            HandlerResult::Response(ServerMessage::ScrollbackSearchResults { hits, truncated, .. }) => {
+                assert!(truncated);
+                assert_eq!(hits.len(), 5);
+                // Newest first within a pane; the order of panes is unspecified
+                let first_noisy = hits.iter().find(|hit| hit.pane_id == noisy).unwrap();
+                assert_eq!(first_noisy.text, "warning 19");
+                assert!(hits.iter().any(|hit| hit.pane_id == quiet));
+            }
+            _ => panic!("Expected ScrollbackSearchResults"),
+        }
+
+        let result = ctx
+            .handle_search_scrollback("warning".into(), Some("quiet".into()), 0, 5)
+            .await;
+        match result {
+            HandlerResult::Response(ServerMessage::ScrollbackSearchResults { hits, .. }) => {
+                assert_eq!(hits.len(), 1);
+            }
+            _ => panic!("Expected ScrollbackSearchResults"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_rejects_empty_query() {
+        let ctx = create_test_context();
+        let result = ctx.handle_search_scrollback("  ".into(), None, 0, 5).await;
+        match result {
+            HandlerResult::Response(ServerMessage::Error { code, .. }) => {
+                assert_eq!(code, ErrorCode::InvalidOperation);
+            }
+            _ => panic!("Expected error"),
+        }
+    }
+}
@@ -8,6 +8,7 @@ use fugue_utils::CcmuxError;
 
 use crate::arbitration::{Action, Resource};
 use crate::pty::{PtyConfig, PtyOutputPoller};
+use crate::session::PaneLaunch;
 
 use fugue_protocol::{
     ErrorCode,
@@ -97,14 +98,15 @@ impl HandlerContext {
                 }
 
                 // Spawn PTY for the default pane
-                {
+                let launch = {
                     let mut pty_manager = self.pty_manager.write().await;
+                    let config = self.config.load_full();
 
                     // Priority: CLI command > config default_command > shell
                     let mut pty_config = if let Some(ref cmd) = command {
                         // CLI argument provided - parse command string with args
                         PtyConfig::from_command_string(cmd).with_size(cols, rows)
-                    } else if let Some(ref cmd) = self.config.general.default_command {
+                    } else if let Some(ref cmd) = config.general.default_command {
                         // Use default_command from config
                         PtyConfig::from_command_string(cmd).with_size(cols, rows)
                     } else {
@@ -117,6 +119,7 @@ impl HandlerContext {
                         pty_config = pty_config.with_cwd(cwd);
                     }
                     pty_config = pty_config.with_fugue_context(session_id, &name, window_id, pane_id);
+                    let launch = PaneLaunch::from(&pty_config);
 
                     match pty_manager.spawn(pane_id, pty_config) {
                         Ok(handle) => {
@@ -140,7 +143,10 @@ impl HandlerContext {
                             warn!("Failed to spawn PTY for default pane: {}", e);
                         }
                     }
-                }
+
+                    launch
+                };
+                self.record_launch(pane_id, launch).await;
 
                 let response = ServerMessage::SessionCreated {
                     session: session_info,
@@ -218,9 +224,9 @@ impl HandlerContext {
                 let session = session_manager.get_session(session_id).unwrap();
                 let windows: Vec<_> = session.windows().map(|w| w.to_info()).collect();
 
-                // Collect pane info and scrollback content
+                // Collect pane info and scrollback content, after the window layouts
                 let mut panes = Vec::new();
-                let mut initial_output: Vec<ServerMessage> = Vec::new();
+                let mut initial_output = Self::layout_messages(session);
 
                 for window in session.windows() {
                     for pane in window.panes() {
@@ -484,11 +490,12 @@ impl HandlerContext {
         let window_id = window_info.id;
 
         // Spawn PTY for the default pane
-        {
+        let launch = {
             let mut pty_manager = self.pty_manager.write().await;
+            let config = self.config.load_full();
 
             // Use default_command from config if set, otherwise shell
-            let mut pty_config = if let Some(ref cmd) = self.config.general.default_command {
+            let mut pty_config = if let Some(ref cmd) = config.general.default_command {
                 PtyConfig::command(cmd).with_size(cols, rows)
             } else {
                 PtyConfig::shell().with_size(cols, rows)
@@ -501,6 +508,7 @@ impl HandlerContext {
             pty_config = pty_config.with_fugue_context(session_id, &session_name, window_id, pane_id);
             // Apply session environment variables
             pty_config = pty_config.with_env_map(&session_env);
+            let launch = PaneLaunch::from(&pty_config);
 
             match pty_manager.spawn(pane_id, pty_config) {
                 Ok(handle) => {
@@ -522,7 +530,10 @@ impl HandlerContext {
                     warn!("Failed to spawn PTY for default pane: {}", e);
                 }
             }
-        }
+
+            launch
+        };
+        self.record_launch(pane_id, launch).await;
 
         // Log to persistence
         let mut commit_seq = 0;
@@ -883,7 +894,7 @@ mod tests {
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
         let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
         let registry = Arc::new(ClientRegistry::new());
-        let config = Arc::new(crate::config::AppConfig::default());
+        let config = crate::config::new_config_handle();
         let arbitrator = Arc::new(Arbitrator::new());
         let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
             Arc::clone(&session_manager),
@@ -1084,9 +1095,15 @@ mod tests {
                 assert_eq!(session.name, "test");
                 assert_eq!(panes.len(), 1);
 
-                // Should have follow-up Output messages with scrollback
-                assert_eq!(follow_up.len(), 1);
+                // The window layout comes first, then Output with scrollback
+                assert_eq!(follow_up.len(), 2);
                 match &follow_up[0] {
+                    ServerMessage::LayoutChanged { layout } => {
+                        assert_eq!(layout.root.pane_ids(), vec![panes[0].id]);
+                    }
+                    other => panic!("Expected LayoutChanged in follow_up, got {:?}", other),
+                }
+                match &follow_up[1] {
                     ServerMessage::Output { pane_id, data } => {
                         assert_eq!(*pane_id, panes[0].id);
                         let content = String::from_utf8_lossy(data);
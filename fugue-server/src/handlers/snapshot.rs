@@ -0,0 +1,302 @@
+//! Named snapshot and point-in-time restore handlers
+//!
+//! Handles: SaveSnapshot, ListSnapshots, DeleteSnapshot, RestoreSnapshot
+
+use std::collections::HashMap;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{ErrorCode, ServerMessage, SessionInfo, SnapshotSource};
+
+use crate::config::RestoreConfig;
+use crate::persistence::{
+    capture_session, clone_session, RecoveryState, SessionRestorer, SessionSnapshot,
+};
+use crate::pty::PtyOutputPoller;
+
+use super::{HandlerContext, HandlerResult};
+
+/// Whether a snapshot session is selected by a name-or-ID filter
+fn matches_filter(session: &SessionSnapshot, filter: Option<&str>) -> bool {
+    filter.is_none_or(|filter| session.name == filter || session.id.to_string() == filter)
+}
+
+impl HandlerContext {
+    /// Error response for snapshot requests when persistence is disabled
+    fn persistence_disabled() -> HandlerResult {
+        HandlerContext::error(
+            ErrorCode::InvalidOperation,
+            "Snapshots require persistence to be enabled",
+        )
+    }
+
+    /// Handle SaveSnapshot - save live sessions under a name
+    pub async fn handle_save_snapshot(
+        &self,
+        name: String,
+        session_filter: Option<String>,
+    ) -> HandlerResult {
+        info!("SaveSnapshot '{}' request from {}", name, self.client_id);
+
+        let Some(persistence) = &self.persistence else {
+            return Self::persistence_disabled();
+        };
+
+        let sessions: Vec<SessionSnapshot> = {
+            let session_manager = self.session_manager.read().await;
+            session_manager
+                .list_sessions()
+                .into_iter()
+                .map(capture_session)
+                .filter(|session| matches_filter(session, session_filter.as_deref()))
+                .collect()
+        };
+
+        if let Some(filter) = &session_filter {
+            if sessions.is_empty() {
+                return HandlerContext::error(
+                    ErrorCode::SessionNotFound,
+                    format!("Session '{}' not found", filter),
+                );
+            }
+        }
+
+        match persistence.read().await.snapshots().save(&name, sessions) {
+            Ok(snapshot) => HandlerResult::Response(ServerMessage::SnapshotSaved { snapshot }),
+            Err(e) => HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        }
+    }
+
+    /// Handle ListSnapshots - list saved snapshots
+    pub async fn handle_list_snapshots(&self) -> HandlerResult {
+        let Some(persistence) = &self.persistence else {
+            return Self::persistence_disabled();
+        };
+
+        match persistence.read().await.snapshots().list() {
+            Ok(snapshots) => HandlerResult::Response(ServerMessage::SnapshotList { snapshots }),
+            Err(e) => HandlerContext::error(ErrorCode::InternalError, e.to_string()),
+        }
+    }
+
+    /// Handle DeleteSnapshot - remove a saved snapshot
+    pub async fn handle_delete_snapshot(&self, name: String) -> HandlerResult {
+        info!("DeleteSnapshot '{}' request from {}", name, self.client_id);
+
+        let Some(persistence) = &self.persistence else {
+            return Self::persistence_disabled();
+        };
+
+        match persistence.read().await.snapshots().delete(&name) {
+            Ok(()) => HandlerResult::Response(ServerMessage::SnapshotDeleted { name }),
+            Err(e) => HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        }
+    }
+
+    /// Handle RestoreSnapshot - roll sessions back, or clone one
+    ///
+    /// Without `clone_as`, each restored session replaces the live session of
+    /// the same name. Restored sessions always get fresh IDs: the replaced
+    /// panes' output pollers are still winding down and would otherwise clean
+    /// up the new panes when they report the old ones closed.
+    pub async fn handle_restore_snapshot(
+        &self,
+        source: SnapshotSource,
+        session_filter: Option<String>,
+        clone_as: Option<String>,
+    ) -> HandlerResult {
+        info!(
+            "RestoreSnapshot {:?} request from {} (session: {:?}, clone as: {:?})",
+            source, self.client_id, session_filter, clone_as
+        );
+
+        let Some(persistence) = &self.persistence else {
+            return Self::persistence_disabled();
+        };
+
+        let loaded = {
+            let persistence = persistence.read().await;
+            match &source {
+                SnapshotSource::Named(name) => {
+                    persistence.snapshots().load(name).map(|checkpoint| checkpoint.sessions)
+                }
+                SnapshotSource::Sequence(sequence) => {
+                    persistence.state_at(*sequence).map(|state| state.sessions)
+                }
+            }
+        };
+        let sessions: Vec<SessionSnapshot> = match loaded {
+            Ok(sessions) => sessions
+                .into_iter()
+                .filter(|session| matches_filter(session, session_filter.as_deref()))
+                .collect(),
+            Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        };
+
+        if sessions.is_empty() {
+            return HandlerContext::error(
+                ErrorCode::SessionNotFound,
+                match &session_filter {
+                    Some(filter) => format!("Session '{}' not found in snapshot", filter),
+                    None => "Snapshot contains no sessions".to_string(),
+                },
+            );
+        }
+
+        let sessions = match &clone_as {
+            Some(new_name) => {
+                if sessions.len() != 1 {
+                    return HandlerContext::error(
+                        ErrorCode::InvalidOperation,
+                        "Cloning requires selecting exactly one session",
+                    );
+                }
+                if self
+                    .session_manager
+                    .read()
+                    .await
+                    .get_session_by_name(new_name)
+                    .is_some()
+                {
+                    return HandlerContext::error(
+                        ErrorCode::SessionNameExists,
+                        format!("Session '{}' already exists", new_name),
+                    );
+                }
+                vec![clone_session(&sessions[0], new_name.clone())]
+            }
+            None => {
+                // Tear down the live sessions being rolled back
+                let replaced: Vec<Uuid> = {
+                    let session_manager = self.session_manager.read().await;
+                    sessions
+                        .iter()
+                        .filter_map(|session| session_manager.get_session_by_name(&session.name))
+                        .map(|session| session.id())
+                        .collect()
+                };
+                for session_id in replaced {
+                    let result = self.handle_destroy_session(session_id).await;
+                    if matches!(result, HandlerResult::Response(ServerMessage::Error { .. })) {
+                        return result;
+                    }
+                }
+                sessions
+                    .iter()
+                    .map(|session| clone_session(session, session.name.clone()))
+                    .collect()
+            }
+        };
+
+        let (sessions, skipped, broadcast) = self
+            .restore_sessions(sessions, HashMap::new(), self.config.load().persistence.restore.clone())
+            .await;
+
+        // A session of the same name appeared while we restored. Report it,
+        // but still announce whatever did get restored.
+        if let Some(name) = skipped.first() {
+            let error = HandlerContext::error(
+                ErrorCode::SessionNameExists,
+                format!("Session '{}' already exists", name),
+            );
+            return match error {
+                HandlerResult::Response(response) if !sessions.is_empty() => {
+                    HandlerResult::ResponseWithGlobalBroadcast { response, broadcast }
+                }
+                error => error,
+            };
+        }
+
+        HandlerResult::ResponseWithGlobalBroadcast {
+            response: ServerMessage::SnapshotRestored { sessions },
+            broadcast,
+        }
+    }
+
+    /// Recreate snapshotted sessions and start their output pollers
+    ///
+    /// `scrollback` seeds the new panes' scrollback by pane ID and
+    /// `restore_config` decides what each pane runs. The result is
+    /// checkpointed when persistence is enabled. Sessions whose name is
+    /// already taken are skipped, since callers check names before taking the
+    /// write lock. Returns the restored sessions, the names skipped, and a
+    /// `SessionsChanged` broadcast.
+    pub(super) async fn restore_sessions(
+        &self,
+        sessions: Vec<SessionSnapshot>,
+        scrollback: HashMap<Uuid, Vec<String>>,
+        restore_config: RestoreConfig,
+    ) -> (Vec<SessionInfo>, Vec<String>, ServerMessage) {
+        let restorer = SessionRestorer::new().with_restore_config(restore_config);
+
+        let (restored, skipped, all_sessions) = {
+            let mut session_manager = self.session_manager.write().await;
+            let mut pty_manager = self.pty_manager.write().await;
+
+            let (taken, sessions): (Vec<_>, Vec<_>) = sessions
+                .into_iter()
+                .partition(|session| session_manager.get_session_by_name(&session.name).is_some());
+            let skipped: Vec<String> = taken.into_iter().map(|session| session.name).collect();
+            for name in &skipped {
+                warn!("Not restoring session '{}': name already exists", name);
+            }
+
+            let state = RecoveryState {
+                sessions,
+                clean_shutdown: true,
+                ..Default::default()
+            };
+            let result = restorer.restore(&state, &mut session_manager, &mut pty_manager);
+
+            for (pane_id, lines) in scrollback {
+                if let Some(pane) = session_manager.find_pane_mut(pane_id) {
+                    let buffer = pane.scrollback_mut();
+                    for line in lines {
+                        buffer.push_line(line);
+                    }
+                }
+            }
+
+            for session in &result.sessions {
+                for pane in session.pane_results.iter().filter(|p| p.pty_spawned) {
+                    if let Some(handle) = pty_manager.get(pane.pane_id) {
+                        let _poller_handle = PtyOutputPoller::spawn_with_sideband(
+                            pane.pane_id,
+                            session.session_id,
+                            handle.clone_reader(),
+                            self.registry.clone(),
+                            Some(self.pane_closed_tx.clone()),
+                            self.command_executor.clone(),
+                        );
+                    }
+                }
+            }
+
+            let restored: Vec<_> = result
+                .sessions
+                .iter()
+                .filter_map(|session| session_manager.get_session(session.session_id))
+                .map(|session| session.to_info())
+                .collect();
+            let all_sessions: Vec<_> = session_manager
+                .list_sessions()
+                .into_iter()
+                .map(|session| (session.to_info(), capture_session(session)))
+                .collect();
+            info!("{}", result.summary());
+            (restored, skipped, all_sessions)
+        };
+
+        let (infos, snapshots): (Vec<_>, Vec<_>) = all_sessions.into_iter().unzip();
+
+        // Restored sessions bypass the WAL, so checkpoint to make them durable
+        if let Some(persistence) = &self.persistence {
+            if let Err(e) = persistence.write().await.create_checkpoint(snapshots) {
+                warn!("Failed to checkpoint after session restore: {}", e);
+            }
+        }
+
+        (restored, skipped, ServerMessage::SessionsChanged { sessions: infos })
+    }
+}
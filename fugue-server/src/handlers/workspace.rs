@@ -0,0 +1,298 @@
+//! Declarative workspace handlers
+//!
+//! Handles: WorkspaceUp, WorkspaceDown
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use fugue_protocol::{ErrorCode, ServerMessage};
+
+use crate::config::{RestoreConfig, RestorePolicy};
+use crate::isolation;
+use crate::workspace::Workspace;
+
+use super::{HandlerContext, HandlerResult};
+
+impl HandlerContext {
+    /// Handle WorkspaceUp - create the workspace's sessions that don't exist yet
+    pub async fn handle_workspace_up(
+        &self,
+        document: String,
+        base_dir: Option<String>,
+    ) -> HandlerResult {
+        let workspace = match Workspace::parse(&document) {
+            Ok(workspace) => workspace,
+            Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        };
+
+        info!(
+            "WorkspaceUp request from {} ({} sessions)",
+            self.client_id,
+            workspace.sessions.len()
+        );
+
+        let mut existing = Vec::new();
+        let mut plans = Vec::new();
+        {
+            let session_manager = self.session_manager.read().await;
+            for spec in &workspace.sessions {
+                if session_manager.get_session_by_name(&spec.name).is_some() {
+                    existing.push(spec.name.clone());
+                    continue;
+                }
+                // Plan everything before creating anything, so a bad spec
+                // doesn't leave the workspace half up
+                match spec.plan(
+                    workspace.root.as_deref(),
+                    base_dir.as_deref().map(Path::new),
+                    &self.config.load().presets,
+                ) {
+                    Ok(plan) => plans.push(plan),
+                    Err(e) => {
+                        return HandlerContext::error(ErrorCode::InvalidOperation, e.to_string())
+                    }
+                }
+            }
+        }
+
+        if plans.is_empty() {
+            return HandlerResult::Response(ServerMessage::WorkspaceStarted {
+                created: Vec::new(),
+                existing,
+            });
+        }
+
+        for (pane_id, settings) in plans.iter().flat_map(|plan| &plan.claude_settings) {
+            let written = isolation::ensure_config_dir(*pane_id).and_then(|dir| {
+                let json = serde_json::to_vec_pretty(&serde_json::Value::Object(settings.clone()))?;
+                std::fs::write(dir.join(".claude.json"), json)
+            });
+            if let Err(e) = written {
+                warn!("Failed to write Claude config for pane {}: {}", pane_id, e);
+            }
+        }
+
+        // Panes run exactly what the workspace says, not restore heuristics
+        let restore_config = RestoreConfig {
+            default_policy: RestorePolicy::Rerun,
+            disable_builtin: true,
+            rules: Vec::new(),
+        };
+        let watchdogs: Vec<_> = plans
+            .iter()
+            .flat_map(|plan| plan.watchdogs.iter().map(|w| (plan.snapshot.name.clone(), w.clone())))
+            .collect();
+        let snapshots = plans.into_iter().map(|plan| plan.snapshot).collect();
+        let (created, skipped, broadcast) = self
+            .restore_sessions(snapshots, HashMap::new(), restore_config)
+            .await;
+
+        // Sessions created by someone else since the check above
+        let watchdogs: Vec<_> = watchdogs
+            .into_iter()
+            .filter(|(name, _)| !skipped.contains(name))
+            .map(|(_, watchdog)| watchdog)
+            .collect();
+        existing.extend(skipped);
+
+        for watchdog in watchdogs {
+            self.watchdog
+                .start(
+                    watchdog.pane_id,
+                    watchdog.interval_secs,
+                    watchdog.message,
+                    Some(watchdog.name),
+                    Arc::clone(&self.pty_manager),
+                )
+                .await;
+        }
+
+        HandlerResult::ResponseWithGlobalBroadcast {
+            response: ServerMessage::WorkspaceStarted { created, existing },
+            broadcast,
+        }
+    }
+
+    /// Handle WorkspaceDown - destroy the workspace's sessions that exist
+    pub async fn handle_workspace_down(&self, document: String) -> HandlerResult {
+        let workspace = match Workspace::parse(&document) {
+            Ok(workspace) => workspace,
+            Err(e) => return HandlerContext::error(ErrorCode::InvalidOperation, e.to_string()),
+        };
+
+        info!(
+            "WorkspaceDown request from {} ({} sessions)",
+            self.client_id,
+            workspace.sessions.len()
+        );
+
+        let mut destroyed = Vec::new();
+        let mut missing = Vec::new();
+        for spec in &workspace.sessions {
+            let session_id = self
+                .session_manager
+                .read()
+                .await
+                .get_session_by_name(&spec.name)
+                .map(|session| session.id());
+            let Some(session_id) = session_id else {
+                missing.push(spec.name.clone());
+                continue;
+            };
+
+            // handle_destroy_session notifies clients itself
+            if let HandlerResult::Response(error @ ServerMessage::Error { .. }) =
+                self.handle_destroy_session(session_id).await
+            {
+                return HandlerResult::Response(error);
+            }
+            for name in spec.watchdog_names() {
+                self.watchdog.stop(Some(name)).await;
+            }
+            destroyed.push(spec.name.clone());
+        }
+
+        HandlerResult::Response(ServerMessage::WorkspaceStopped { destroyed, missing })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitration::Arbitrator;
+    use crate::pty::PtyManager;
+    use crate::registry::ClientRegistry;
+    use crate::session::SessionManager;
+    use crate::watchdog::WatchdogManager;
+    use tokio::sync::{mpsc, RwLock};
+
+    fn create_test_context() -> HandlerContext {
+        let session_manager = Arc::new(RwLock::new(SessionManager::new()));
+        let pty_manager = Arc::new(RwLock::new(PtyManager::new()));
+        let registry = Arc::new(ClientRegistry::new());
+        let config = crate::config::new_config_handle();
+        let arbitrator = Arc::new(Arbitrator::new());
+        let command_executor = Arc::new(crate::sideband::AsyncCommandExecutor::new(
+            Arc::clone(&session_manager),
+            Arc::clone(&pty_manager),
+            Arc::clone(&registry),
+        ));
+        let watchdog = Arc::new(WatchdogManager::new());
+
+        let (tx, _rx) = mpsc::channel(10);
+        let client_id = registry.register_client(tx);
+
+        let (pane_closed_tx, _) = mpsc::channel(10);
+        HandlerContext::new(
+            session_manager,
+            pty_manager,
+            registry,
+            config,
+            client_id,
+            pane_closed_tx,
+            command_executor,
+            arbitrator,
+            None,
+            watchdog,
+        )
+    }
+
+    const WORKSPACE: &str = r#"
+[[session]]
+name = "app"
+tags = ["dev"]
+
+[[session.window]]
+name = "main"
+panes = [{ command = "sleep 30" }, { command = "sleep 30" }]
+
+[[session]]
+name = "notes"
+"#;
+
+    #[tokio::test]
+    async fn test_workspace_up_and_down_are_idempotent() {
+        let ctx = create_test_context();
+        ctx.session_manager.write().await.create_session("notes").unwrap();
+
+        match ctx.handle_workspace_up(WORKSPACE.to_string(), None).await {
+            HandlerResult::ResponseWithGlobalBroadcast {
+                response: ServerMessage::WorkspaceStarted { created, existing },
+                ..
+            } => {
+                assert_eq!(created.len(), 1);
+                assert_eq!(created[0].name, "app");
+                assert!(created[0].tags.contains("dev"));
+                assert_eq!(existing, vec!["notes"]);
+            }
+            _ => panic!("Expected WorkspaceStarted response with global broadcast"),
+        }
+        {
+            let session_manager = ctx.session_manager.read().await;
+            let session = session_manager.get_session_by_name("app").unwrap();
+            assert_eq!(session.windows().next().unwrap().pane_count(), 2);
+        }
+
+        match ctx.handle_workspace_up(WORKSPACE.to_string(), None).await {
+            HandlerResult::Response(ServerMessage::WorkspaceStarted { created, existing }) => {
+                assert!(created.is_empty());
+                assert_eq!(existing.len(), 2);
+            }
+            _ => panic!("Expected WorkspaceStarted response"),
+        }
+
+        match ctx.handle_workspace_down(WORKSPACE.to_string()).await {
+            HandlerResult::Response(ServerMessage::WorkspaceStopped { destroyed, missing }) => {
+                assert_eq!(destroyed, vec!["app", "notes"]);
+                assert!(missing.is_empty());
+            }
+            _ => panic!("Expected WorkspaceStopped response"),
+        }
+
+        match ctx.handle_workspace_down(WORKSPACE.to_string()).await {
+            HandlerResult::Response(ServerMessage::WorkspaceStopped { destroyed, missing }) => {
+                assert!(destroyed.is_empty());
+                assert_eq!(missing, vec!["app", "notes"]);
+            }
+            _ => panic!("Expected WorkspaceStopped response"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restore_skips_names_taken_after_planning() {
+        let ctx = create_test_context();
+        let workspace = Workspace::parse(WORKSPACE).unwrap();
+        let snapshots = workspace
+            .sessions
+            .iter()
+            .map(|spec| spec.plan(None, None, &HashMap::new()).unwrap().snapshot)
+            .collect();
+        ctx.session_manager.write().await.create_session("notes").unwrap();
+
+        let (created, skipped, _) = ctx
+            .restore_sessions(snapshots, HashMap::new(), RestoreConfig::default())
+            .await;
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0].name, "app");
+        assert_eq!(skipped, vec!["notes"]);
+        assert_eq!(ctx.session_manager.read().await.list_sessions().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_up_rejects_invalid_document() {
+        let ctx = create_test_context();
+        let result = ctx
+            .handle_workspace_up("[[session]]\nname = 3\n".to_string(), None)
+            .await;
+        assert!(matches!(
+            result,
+            HandlerResult::Response(ServerMessage::Error {
+                code: ErrorCode::InvalidOperation,
+                ..
+            })
+        ));
+    }
+}
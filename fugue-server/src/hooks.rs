@@ -0,0 +1,510 @@
+//! Server-side hooks
+//!
+//! `[[hooks]]` entries run a shell command or a `fugue-compat` command line
+//! when something happens on the server, like tmux's `set-hook`. Events are
+//! picked up from the same broadcasts that reach clients (plus client
+//! attaches), and their details are passed to the command in `FUGUE_*`
+//! environment variables. Commands run in the background with a time limit;
+//! failures are logged, never reported to clients.
+
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{AgentActivity, ClientType, PaneState, ServerMessage};
+
+use crate::config::{HookConfig, HookEvent};
+
+/// Longest a hook command may run before it is killed
+const HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Closed panes remembered so a second `PaneClosed` doesn't fire again
+const CLOSED_PANES_REMEMBERED: usize = 64;
+
+/// Something that happened, with the variables hooks see
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookContext {
+    pub event: HookEvent,
+    pub session_id: Option<Uuid>,
+    pub session_name: Option<String>,
+    pub pane_id: Option<Uuid>,
+    /// Event-specific variables, e.g. `FUGUE_EXIT_CODE`
+    pub vars: Vec<(&'static str, String)>,
+}
+
+impl HookContext {
+    fn new(event: HookEvent, session_id: Option<Uuid>, session_name: Option<String>) -> Self {
+        Self {
+            event,
+            session_id,
+            session_name,
+            pane_id: None,
+            vars: Vec::new(),
+        }
+    }
+
+    /// Value of an event-specific variable
+    pub fn var(&self, name: &str) -> Option<&str> {
+        self.vars
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Environment for the hook command
+    pub fn env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("FUGUE_HOOK", self.event.name().to_string())];
+        if let Some(id) = self.session_id {
+            env.push(("FUGUE_SESSION_ID", id.to_string()));
+        }
+        if let Some(name) = &self.session_name {
+            env.push(("FUGUE_SESSION_NAME", name.clone()));
+        }
+        if let Some(id) = self.pane_id {
+            env.push(("FUGUE_PANE_ID", id.to_string()));
+        }
+        env.extend(self.vars.iter().cloned());
+        env
+    }
+
+    /// Whether a hook applies to this event
+    pub fn matches(&self, hook: &HookConfig) -> bool {
+        if hook.event != self.event {
+            return false;
+        }
+        if let Some(session) = &hook.session {
+            let by_id = self.session_id.is_some_and(|id| id.to_string() == *session);
+            let by_name = self.session_name.as_deref() == Some(session.as_str());
+            if !by_id && !by_name {
+                return false;
+            }
+        }
+        let activity_matches = |filter: &Option<String>, var: &str| match filter {
+            Some(filter) => self.var(var) == Some(normalize_activity(filter).as_str()),
+            None => true,
+        };
+        activity_matches(&hook.from, "FUGUE_AGENT_FROM") && activity_matches(&hook.to, "FUGUE_AGENT_TO")
+    }
+}
+
+/// Lower-case a filter and accept Claude's names for the generic activities
+fn normalize_activity(filter: &str) -> String {
+    match filter.to_lowercase().replace('-', "_").as_str() {
+        "thinking" => "processing".to_string(),
+        "coding" => "generating".to_string(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Session names by ID; `None` until seeded or the first `SessionsChanged`
+    sessions: Option<HashMap<Uuid, String>>,
+    /// Last agent activity seen per pane
+    activities: HashMap<Uuid, String>,
+    /// Recently closed panes; closing a pane is broadcast both by the close
+    /// handler and by its output poller at EOF
+    closed: VecDeque<Uuid>,
+}
+
+/// Turns server broadcasts into hook events
+#[derive(Debug, Default)]
+pub struct HookRunner {
+    state: Mutex<State>,
+}
+
+impl HookRunner {
+    /// Create a runner that knows no sessions yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the sessions that already exist, so they don't count as created
+    pub fn seed_sessions(&self, sessions: impl IntoIterator<Item = (Uuid, String)>) {
+        self.state.lock().sessions = Some(sessions.into_iter().collect());
+    }
+
+    /// Run `hooks` for a broadcast, sent to `session_id` or to every client
+    ///
+    /// Returns the events found, which also drive notifications.
+    pub fn observe(
+        &self,
+        hooks: &[HookConfig],
+        session_id: Option<Uuid>,
+        message: &ServerMessage,
+    ) -> Vec<HookContext> {
+        let events = self.events(session_id, message);
+        for context in &events {
+            run_matching(hooks, context.clone());
+        }
+        events
+    }
+
+    /// Run `hooks` for a client attaching to a session
+    pub fn client_attached(
+        &self,
+        hooks: &[HookConfig],
+        client_id: impl ToString,
+        client_type: ClientType,
+        session_id: Uuid,
+    ) {
+        let mut context = HookContext::new(
+            HookEvent::ClientAttached,
+            Some(session_id),
+            self.session_name(session_id),
+        );
+        context.vars.push(("FUGUE_CLIENT_ID", client_id.to_string()));
+        context.vars.push(("FUGUE_CLIENT_TYPE", format!("{:?}", client_type).to_lowercase()));
+        run_matching(hooks, context);
+    }
+
+    fn session_name(&self, session_id: Uuid) -> Option<String> {
+        self.state
+            .lock()
+            .sessions
+            .as_ref()
+            .and_then(|sessions| sessions.get(&session_id).cloned())
+    }
+
+    /// Hook events in a broadcast, updating what is known about sessions and agents
+    pub fn events(&self, session_id: Option<Uuid>, message: &ServerMessage) -> Vec<HookContext> {
+        let message = match message {
+            ServerMessage::Sequenced { inner, .. } => inner.as_ref(),
+            other => other,
+        };
+
+        // Cheap exit for output, the bulk of all broadcasts
+        let agent = match message {
+            ServerMessage::PaneStateChanged { state: PaneState::Agent(agent), .. } => {
+                Some((agent.activity.name().to_string(), Some(agent.agent_type.as_str())))
+            }
+            ServerMessage::ClaudeStateChanged { state, .. } => {
+                Some((AgentActivity::from(state.activity.clone()).name().to_string(), Some("claude")))
+            }
+            ServerMessage::PaneStateChanged { .. }
+            | ServerMessage::PaneClosed { .. }
+            | ServerMessage::MailReceived { .. }
+            | ServerMessage::SessionsChanged { .. } => None,
+            _ => return Vec::new(),
+        };
+
+        let mut state = self.state.lock();
+        let session_name = session_id.and_then(|id| {
+            state
+                .sessions
+                .as_ref()
+                .and_then(|sessions| sessions.get(&id).cloned())
+        });
+        let mut context = HookContext::new(HookEvent::PaneExited, session_id, session_name);
+
+        match message {
+            ServerMessage::PaneStateChanged { pane_id, .. } => {
+                let Some((to, agent_type)) = agent else {
+                    // The agent is gone; a new one starts from "none"
+                    state.activities.remove(pane_id);
+                    return Vec::new();
+                };
+                Self::agent_event(&mut state, context, *pane_id, to, agent_type)
+            }
+            ServerMessage::ClaudeStateChanged { pane_id, .. } => {
+                let (to, agent_type) = agent.unwrap_or_default();
+                Self::agent_event(&mut state, context, *pane_id, to, agent_type)
+            }
+            ServerMessage::PaneClosed { pane_id, exit_code } => {
+                state.activities.remove(pane_id);
+                if state.closed.contains(pane_id) {
+                    return Vec::new();
+                }
+                if state.closed.len() == CLOSED_PANES_REMEMBERED {
+                    state.closed.pop_front();
+                }
+                state.closed.push_back(*pane_id);
+                context.pane_id = Some(*pane_id);
+                if let Some(code) = exit_code {
+                    context.vars.push(("FUGUE_EXIT_CODE", code.to_string()));
+                }
+                vec![context]
+            }
+            ServerMessage::MailReceived { pane_id, priority, summary } => {
+                context.event = HookEvent::MailReceived;
+                context.pane_id = Some(*pane_id);
+                context.vars.push(("FUGUE_MAIL_PRIORITY", format!("{:?}", priority).to_lowercase()));
+                context.vars.push(("FUGUE_MAIL_SUMMARY", summary.clone()));
+                vec![context]
+            }
+            ServerMessage::SessionsChanged { sessions } => {
+                let current: HashMap<Uuid, String> = sessions
+                    .iter()
+                    .map(|session| (session.id, session.name.clone()))
+                    .collect();
+                let created = match &state.sessions {
+                    Some(known) => sessions
+                        .iter()
+                        .filter(|session| !known.contains_key(&session.id))
+                        .map(|session| {
+                            HookContext::new(
+                                HookEvent::SessionCreated,
+                                Some(session.id),
+                                Some(session.name.clone()),
+                            )
+                        })
+                        .collect(),
+                    None => Vec::new(),
+                };
+                state.sessions = Some(current);
+                created
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn agent_event(
+        state: &mut State,
+        mut context: HookContext,
+        pane_id: Uuid,
+        to: String,
+        agent_type: Option<&str>,
+    ) -> Vec<HookContext> {
+        let from = state.activities.insert(pane_id, to.clone());
+        if from.as_deref() == Some(to.as_str()) {
+            return Vec::new();
+        }
+        context.event = HookEvent::AgentStateChanged;
+        context.pane_id = Some(pane_id);
+        context.vars.push(("FUGUE_AGENT_FROM", from.unwrap_or_else(|| "none".to_string())));
+        context.vars.push(("FUGUE_AGENT_TO", to));
+        if let Some(agent_type) = agent_type {
+            context.vars.push(("FUGUE_AGENT_TYPE", agent_type.to_string()));
+        }
+        vec![context]
+    }
+}
+
+/// Start every hook that matches an event
+fn run_matching(hooks: &[HookConfig], context: HookContext) {
+    for hook in hooks.iter().filter(|hook| context.matches(hook)) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No runtime to run {} hook on", context.event.name());
+            return;
+        };
+        let hook = hook.clone();
+        let context = context.clone();
+        runtime.spawn(async move {
+            run_hook(&hook, &context).await;
+        });
+    }
+}
+
+/// Run a hook's commands, `run` before `fugue`
+pub async fn run_hook(hook: &HookConfig, context: &HookContext) {
+    if let Some(run) = &hook.run {
+        run_command(context, run, &[]).await;
+    }
+    if let Some(fugue) = &hook.fugue {
+        // Word splitting and variables are left to the shell, as for `run`
+        let compat = compat_binary();
+        let addr = format!("unix://{}", fugue_utils::socket_path().display());
+        let extra = [("FUGUE_COMPAT", compat.display().to_string()), ("FUGUE_ADDR", addr)];
+        run_command(context, &format!("\"$FUGUE_COMPAT\" {}", fugue), &extra).await;
+    }
+}
+
+/// `fugue-compat` next to the server binary, or from `PATH`
+fn compat_binary() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name("fugue-compat"))
+        .filter(|path| path.exists())
+        .unwrap_or_else(|| PathBuf::from("fugue-compat"))
+}
+
+async fn run_command(context: &HookContext, command: &str, extra: &[(&str, String)]) {
+    debug!("Running {} hook: {}", context.event.name(), command);
+    let mut child = tokio::process::Command::new("sh");
+    child
+        .arg("-c")
+        .arg(command)
+        .envs(context.env())
+        .envs(extra.iter().map(|(key, value)| (*key, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let output = match child.spawn() {
+        Ok(child) => tokio::time::timeout(HOOK_TIMEOUT, child.wait_with_output()).await,
+        Err(e) => {
+            warn!("Failed to start {} hook '{}': {}", context.event.name(), command, e);
+            return;
+        }
+    };
+    match output {
+        Ok(Ok(output)) if output.status.success() => {}
+        Ok(Ok(output)) => warn!(
+            "{} hook '{}' failed ({}): {}",
+            context.event.name(),
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ),
+        Ok(Err(e)) => warn!("{} hook '{}' failed: {}", context.event.name(), command, e),
+        Err(_) => warn!(
+            "{} hook '{}' killed after {}s",
+            context.event.name(),
+            command,
+            HOOK_TIMEOUT.as_secs()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::{AgentState, MailPriority, SessionInfo};
+    use std::collections::HashSet;
+
+    fn hook(event: HookEvent) -> HookConfig {
+        HookConfig {
+            event,
+            session: None,
+            from: None,
+            to: None,
+            run: Some("true".into()),
+            fugue: None,
+        }
+    }
+
+    fn session_info(id: Uuid, name: &str) -> SessionInfo {
+        SessionInfo {
+            id,
+            name: name.into(),
+            created_at: 0,
+            window_count: 1,
+            attached_clients: 0,
+            worktree: None,
+            tags: HashSet::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn agent_changed(pane_id: Uuid, activity: AgentActivity) -> ServerMessage {
+        ServerMessage::PaneStateChanged {
+            pane_id,
+            state: PaneState::Agent(AgentState::new("claude").with_activity(activity)),
+        }
+    }
+
+    #[test]
+    fn test_agent_transitions_and_filters() {
+        let runner = HookRunner::new();
+        let session_id = Uuid::new_v4();
+        runner.seed_sessions([(session_id, "api".to_string())]);
+        let pane_id = Uuid::new_v4();
+
+        let first = runner.events(Some(session_id), &agent_changed(pane_id, AgentActivity::Processing));
+        assert_eq!(first[0].var("FUGUE_AGENT_FROM"), Some("none"));
+
+        // Repeats of the same activity are not changes
+        assert!(runner
+            .events(Some(session_id), &agent_changed(pane_id, AgentActivity::Processing))
+            .is_empty());
+
+        let events = runner.events(Some(session_id), &agent_changed(pane_id, AgentActivity::Idle));
+        assert_eq!(events.len(), 1);
+        let context = &events[0];
+        assert_eq!(context.event, HookEvent::AgentStateChanged);
+        assert_eq!(context.session_name.as_deref(), Some("api"));
+
+        let mut filtered = hook(HookEvent::AgentStateChanged);
+        filtered.from = Some("Thinking".into());
+        filtered.to = Some("idle".into());
+        filtered.session = Some("api".into());
+        assert!(context.matches(&filtered));
+        filtered.to = Some("tool-use".into());
+        assert!(!context.matches(&filtered));
+        filtered.to = None;
+        filtered.session = Some("web".into());
+        assert!(!context.matches(&filtered));
+
+        let env = context.env();
+        assert!(env.contains(&("FUGUE_HOOK", "agent-state-changed".to_string())));
+        assert!(env.contains(&("FUGUE_PANE_ID", pane_id.to_string())));
+        assert!(env.contains(&("FUGUE_AGENT_TYPE", "claude".to_string())));
+    }
+
+    #[test]
+    fn test_session_created_pane_exited_and_mail() {
+        let runner = HookRunner::new();
+        let old = Uuid::new_v4();
+        runner.seed_sessions([(old, "old".to_string())]);
+
+        let new = Uuid::new_v4();
+        let events = runner.events(
+            None,
+            &ServerMessage::SessionsChanged {
+                sessions: vec![session_info(old, "old"), session_info(new, "new")],
+            },
+        );
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, HookEvent::SessionCreated);
+        assert_eq!(events[0].session_id, Some(new));
+
+        let pane_id = Uuid::new_v4();
+        let events = runner.events(
+            Some(new),
+            &ServerMessage::PaneClosed { pane_id, exit_code: Some(3) },
+        );
+        assert_eq!(events[0].event, HookEvent::PaneExited);
+        assert_eq!(events[0].session_name.as_deref(), Some("new"));
+        assert_eq!(events[0].var("FUGUE_EXIT_CODE"), Some("3"));
+        assert!(runner
+            .events(Some(new), &ServerMessage::PaneClosed { pane_id, exit_code: None })
+            .is_empty());
+
+        let events = runner.events(
+            Some(new),
+            &ServerMessage::MailReceived {
+                pane_id,
+                priority: MailPriority::Warning,
+                summary: "tests failing".into(),
+            },
+        );
+        assert_eq!(events[0].event, HookEvent::MailReceived);
+        assert_eq!(events[0].var("FUGUE_MAIL_PRIORITY"), Some("warning"));
+
+        assert!(runner
+            .events(Some(new), &ServerMessage::Output { pane_id, data: b"x".to_vec() })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unseeded_runner_does_not_report_existing_sessions() {
+        let runner = HookRunner::new();
+        let sessions = vec![session_info(Uuid::new_v4(), "restored")];
+        assert!(runner
+            .events(None, &ServerMessage::SessionsChanged { sessions })
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hook_runs_with_event_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let mut config = hook(HookEvent::PaneExited);
+        config.run = Some(format!(
+            "echo \"$FUGUE_HOOK $FUGUE_SESSION_NAME $FUGUE_EXIT_CODE\" > '{}'",
+            out.display()
+        ));
+
+        let mut context = HookContext::new(HookEvent::PaneExited, Some(Uuid::new_v4()), Some("api".into()));
+        context.vars.push(("FUGUE_EXIT_CODE", "0".into()));
+        run_hook(&config, &context).await;
+
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "pane-exited api 0\n");
+    }
+}
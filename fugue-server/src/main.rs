@@ -1,9 +1,9 @@
 //! fugue server - Background daemon
 
-use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use futures::stream::StreamExt;
 use futures::sink::SinkExt;
 use tokio::io::{AsyncRead, AsyncWrite};
@@ -20,11 +20,15 @@ use fugue_utils::Result;
 mod agents;
 mod arbitration;
 mod beads;
+mod buffers;
 mod claude;
 mod config;
+mod expect;
 mod handlers;
+mod hooks;
 mod isolation;
 mod mcp;
+mod notifications;
 mod observability;
 mod orchestration;
 mod parser;
@@ -34,17 +38,20 @@ mod registry;
 mod reply;
 mod session;
 pub mod sideband;
+mod state_cli;
 mod tcp;
+mod usage;
 mod watchdog;
+mod workspace;
 
 pub use arbitration::Arbitrator;
 pub use registry::{ClientId, ClientRegistry};
 pub use reply::{ReplyError, ReplyHandler};
 
-use config::AppConfig;
+use config::{AppConfig, ConfigHandle};
 use persistence::{
     parse_compression_method, PersistenceConfig, PersistenceManager, RestorationResult,
-    ScrollbackCapture, ScrollbackConfig, SessionRestorer, SessionSnapshot, WindowSnapshot,
+    ScrollbackCapture, ScrollbackConfig, SessionRestorer, SessionSnapshot,
 };
 use pty::{PaneClosedNotification, PtyManager, PtyOutputPoller};
 use session::SessionManager;
@@ -62,8 +69,8 @@ pub struct SharedState {
     pub pty_manager: Arc<RwLock<PtyManager>>,
     /// Client connection registry for tracking and broadcasting
     pub registry: Arc<ClientRegistry>,
-    /// Application configuration
-    pub config: Arc<AppConfig>,
+    /// Application configuration, swapped on reload
+    pub config: ConfigHandle,
     /// Shutdown signal sender
     shutdown_tx: broadcast::Sender<()>,
     /// Channel for pane cleanup notifications (when PTY dies)
@@ -95,6 +102,8 @@ pub struct Server {
     persistence: Option<Arc<RwLock<PersistenceManager>>>,
     /// Scrollback capture config
     scrollback_config: ScrollbackConfig,
+    /// Process resurrection policy for restored panes
+    restore_config: config::RestoreConfig,
     /// Shutdown signal sender
     shutdown_tx: broadcast::Sender<()>,
     /// Active client count
@@ -122,24 +131,19 @@ impl Server {
                 compression: parse_compression_method(&persistence_config.compression_method),
                 ..Default::default()
             },
+            restore_config: persistence_config.restore.clone(),
             shutdown_tx,
             active_clients: AtomicUsize::new(0),
-            client_registry: ClientRegistry::new(),
+            client_registry: ClientRegistry::with_config(Arc::new(ArcSwap::from_pointee(
+                app_config.clone(),
+            ))),
             session_manager_ref: None,
             pty_manager_ref: None,
         };
 
         // Initialize persistence if enabled
         if persistence_config.enabled {
-            let state_dir = persistence_config
-                .state_dir
-                .as_ref()
-                .map(PathBuf::from)
-                .unwrap_or_else(|| {
-                    dirs::data_dir()
-                        .unwrap_or_else(|| PathBuf::from("."))
-                        .join(persistence::DEFAULT_STATE_DIR)
-                });
+            let state_dir = persistence::resolve_state_dir(persistence_config);
 
             let config = PersistenceConfig::from(persistence_config);
             let manager = PersistenceManager::new(&state_dir, config)?;
@@ -179,10 +183,20 @@ impl Server {
         }
 
         // Restore sessions
-        let restorer = SessionRestorer::new();
+        let restorer = SessionRestorer::new().with_restore_config(self.restore_config.clone());
         let result =
             restorer.restore(&state, &mut self.session_manager, &mut self.pty_manager);
 
+        // Pane IDs survive restoration, so scrollback goes back where it was
+        for (pane_id, lines) in persistence.load_scrollback(&state.scrollback) {
+            if let Some(pane) = self.session_manager.find_pane_mut(pane_id) {
+                let buffer = pane.scrollback_mut();
+                for line in lines {
+                    buffer.push_line(line);
+                }
+            }
+        }
+
         info!("{}", result.summary());
 
         // Log any warnings
@@ -220,6 +234,13 @@ impl Server {
         Ok(())
     }
 
+    /// Persist new scrollback from an external session manager reference
+    pub async fn capture_scrollback(&self, session_manager: &SessionManager) {
+        if let Some(ref persistence_lock) = self.persistence {
+            persistence_lock.read().await.capture_scrollback(session_manager);
+        }
+    }
+
     /// Collect session snapshots from an external session manager reference
     pub fn collect_session_snapshots_from(
         &self,
@@ -230,52 +251,7 @@ impl Server {
         session_manager
             .list_sessions()
             .iter()
-            .map(|session| {
-                let windows: Vec<WindowSnapshot> = session
-                    .windows()
-                    .map(|window| {
-                        let panes = window
-                            .panes()
-                            .map(|pane| {
-                                let (cols, rows) = pane.dimensions();
-                                persistence::PaneSnapshot {
-                                    id: pane.id(),
-                                    window_id: window.id(),
-                                    index: pane.index(),
-                                    cols,
-                                    rows,
-                                    state: pane.state().clone(),
-                                    name: pane.name().map(String::from),
-                                    title: pane.title().map(String::from),
-                                    cwd: pane.cwd().map(String::from),
-                                    created_at: pane.created_at_unix(),
-                                    scrollback: None,
-                                }
-                            })
-                            .collect();
-
-                        WindowSnapshot {
-                            id: window.id(),
-                            session_id: session.id(),
-                            name: window.name().to_string(),
-                            index: window.index(),
-                            panes,
-                            active_pane_id: window.active_pane_id(),
-                            created_at: window.created_at_unix(),
-                        }
-                    })
-                    .collect();
-
-                SessionSnapshot {
-                    id: session.id(),
-                    name: session.name().to_string(),
-                    windows,
-                    active_window_id: session.active_window_id(),
-                    created_at: session.created_at_unix(),
-                    metadata: session.all_metadata().clone(),
-                    environment: session.environment().clone(),
-                }
-            })
+            .map(|session| persistence::capture_session(session))
             .collect()
     }
 
@@ -307,6 +283,7 @@ impl Server {
         if let Some(persistence_lock) = self.persistence.take() {
             let sessions = self.collect_session_snapshots();
             let mut persistence = persistence_lock.write().await;
+            persistence.capture_scrollback(&self.session_manager);
             persistence.shutdown(sessions)?;
         }
 
@@ -330,52 +307,7 @@ impl Server {
         self.session_manager
             .list_sessions()
             .iter()
-            .map(|session| {
-                let windows: Vec<WindowSnapshot> = session
-                    .windows()
-                    .map(|window| {
-                        let panes = window
-                            .panes()
-                            .map(|pane| {
-                                let (cols, rows) = pane.dimensions();
-                                persistence::PaneSnapshot {
-                                    id: pane.id(),
-                                    window_id: window.id(),
-                                    index: pane.index(),
-                                    cols,
-                                    rows,
-                                    state: pane.state().clone(),
-                                    name: pane.name().map(String::from),
-                                    title: pane.title().map(String::from),
-                                    cwd: pane.cwd().map(String::from),
-                                    created_at: pane.created_at_unix(),
-                                    scrollback: None, // TODO: Get from PTY
-                                }
-                            })
-                            .collect();
-
-                        WindowSnapshot {
-                            id: window.id(),
-                            session_id: session.id(),
-                            name: window.name().to_string(),
-                            index: window.index(),
-                            panes,
-                            active_pane_id: window.active_pane_id(),
-                            created_at: window.created_at_unix(),
-                        }
-                    })
-                    .collect();
-
-                SessionSnapshot {
-                    id: session.id(),
-                    name: session.name().to_string(),
-                    windows,
-                    active_window_id: session.active_window_id(),
-                    created_at: session.created_at_unix(),
-                    metadata: session.all_metadata().clone(),
-                    environment: session.environment().clone(),
-                }
-            })
+            .map(|session| persistence::capture_session(session))
             .collect()
     }
 
@@ -643,6 +575,14 @@ where
                                     break;
                                 }
 
+                                // A new pane's layout is sent after the pane itself so
+                                // clients never see a layout naming an unknown pane
+                                let layout_window = match &broadcast {
+                                    ServerMessage::PaneCreated { pane, .. } => Some(pane.window_id),
+                                    ServerMessage::MirrorCreated { window_id, .. } => Some(*window_id),
+                                    _ => None,
+                                };
+
                                 // Broadcast to other clients in the session
                                 debug!(
                                     %session_id,
@@ -658,6 +598,10 @@ where
                                     clients_notified = broadcast_count,
                                     "Broadcast complete"
                                 );
+
+                                if let Some(window_id) = layout_window {
+                                    handler_ctx.broadcast_layout(window_id).await;
+                                }
                             }
                             HandlerResult::ResponseWithFollowUp {
                                 response,
@@ -759,18 +703,6 @@ where
     info!("Client {} disconnected", client_id);
 }
 
-/// Run the MCP server mode (standalone, legacy)
-fn run_mcp_server() -> Result<()> {
-    use mcp::McpServer;
-    use fugue_utils::logging::{init_logging_with_config, LogConfig};
-
-    // Initialize file-based logging for standalone MCP server
-    init_logging_with_config(LogConfig::mcp_server())?;
-
-    let mut mcp_server = McpServer::new();
-    mcp_server.run().map_err(|e| fugue_utils::CcmuxError::Internal(e.to_string()))
-}
-
 /// Run the MCP bridge mode (connects to daemon)
 async fn run_mcp_bridge() -> Result<()> {
     use mcp::McpBridge;
@@ -804,6 +736,12 @@ async fn run_daemon(tcp_override: Option<String>) -> Result<()> {
         info!("Default command for new sessions: {}", cmd);
     }
 
+    // Panes skip invalid agent detectors rather than stopping the server
+    if let Err(e) = agents::configured::compile_all(&app_config.agent_detectors) {
+        warn!("Skipping invalid agent detector: {}", e);
+    }
+    pty::remove_stale_spills(&app_config.terminal.scrollback.spill);
+
     // Create server
     let mut server = Server::new(&app_config)?;
 
@@ -823,6 +761,15 @@ async fn run_daemon(tcp_override: Option<String>) -> Result<()> {
     // Clean up orphaned isolation directories
     server.cleanup_isolation();
 
+    // Restored sessions were not created, so hooks start out knowing them
+    server.client_registry.hooks().seed_sessions(
+        server
+            .session_manager
+            .list_sessions()
+            .into_iter()
+            .map(|session| (session.id(), session.name().to_string())),
+    );
+
     // Set up Unix socket
     let listener = setup_socket().await?;
 
@@ -852,8 +799,8 @@ async fn run_daemon(tcp_override: Option<String>) -> Result<()> {
     let shared_state = SharedState {
         session_manager,
         pty_manager,
+        config: Arc::clone(registry.config()),
         registry,
-        config: Arc::new(app_config.clone()),
         shutdown_tx: shutdown_tx.clone(),
         pane_closed_tx,
         command_executor,
@@ -925,6 +872,23 @@ async fn run_daemon(tcp_override: Option<String>) -> Result<()> {
         });
     }
 
+    // Spawn MCP streamable HTTP server
+    if app_config.mcp_http.enabled {
+        let mcp_http_config = app_config.mcp_http.clone();
+        let state = Arc::new(shared_state.clone());
+        tokio::spawn(async move {
+            mcp::run_mcp_http_server(mcp_http_config, state).await;
+        });
+    }
+
+    // Spawn config watcher for hot-reload
+    match config::ConfigWatcher::new() {
+        Ok(watcher) => {
+            tokio::spawn(watcher.run(Arc::clone(&shared_state.config)));
+        }
+        Err(e) => warn!("Config hot-reload disabled: {}", e),
+    }
+
     // Spawn checkpoint task
     let server_for_checkpoint = Arc::clone(&server);
     let shared_state_for_checkpoint = shared_state.clone();
@@ -1006,6 +970,7 @@ async fn run_daemon(tcp_override: Option<String>) -> Result<()> {
         // Collect final state and shutdown persistence
         if server_guard.persistence.is_some() {
             let snapshots = server_guard.collect_session_snapshots_from(&session_manager);
+            server_guard.capture_scrollback(&session_manager).await;
             drop(session_manager);
 
             if let Some(persistence_lock) = server_guard.persistence.take() {
@@ -1059,9 +1024,10 @@ async fn wait_for_shutdown_signal() {
 ///
 /// This task handles cleanup when PTY processes die. When a pane's shell exits,
 /// the output poller sends a notification and this loop:
-/// 1. Removes the pane from session state
-/// 2. Removes the window if it becomes empty
-/// 3. Removes the session if it has no windows
+/// 1. Collects the exit code and broadcasts `PaneClosed`
+/// 2. Removes the pane from session state
+/// 3. Removes the window if it becomes empty
+/// 4. Removes the session if it has no windows
 async fn run_pane_cleanup_loop(
     mut rx: mpsc::Receiver<PaneClosedNotification>,
     shared_state: SharedState,
@@ -1079,21 +1045,28 @@ async fn run_pane_cleanup_loop(
                             "Processing pane cleanup notification"
                         );
 
-                        // Remove PTY if it exists
-                        {
-                            let mut pty_manager = shared_state.pty_manager.write().await;
-                            if let Some(handle) = pty_manager.remove(pane_id) {
-                                if let Err(e) = handle.kill() {
-                                    warn!("Failed to kill PTY for pane {}: {}", pane_id, e);
-                                }
+                        // Remove PTY if it exists, collecting the exit code
+                        let handle = shared_state.pty_manager.write().await.remove(pane_id);
+                        let mut exit_code = None;
+                        if let Some(handle) = handle {
+                            match handle.reap().await {
+                                Ok(code) => exit_code = code,
+                                Err(e) => warn!("Failed to reap PTY for pane {}: {}", pane_id, e),
                             }
                         }
 
+                        // Notify clients that the pane has closed
+                        shared_state
+                            .registry
+                            .broadcast_to_session(session_id, ServerMessage::PaneClosed { pane_id, exit_code })
+                            .await;
+
                         // Remove pane from session and clean up empty containers
                         let mut session_manager = shared_state.session_manager.write().await;
 
                         // Track whether session should be removed after we release the session borrow
                         let mut should_remove_session = false;
+                        let mut layout = None;
 
                         if let Some(session) = session_manager.get_session_mut(session_id) {
                             // Find which window contains this pane
@@ -1117,6 +1090,8 @@ async fn run_pane_cleanup_loop(
                                         );
                                     }
 
+                                    layout = window.to_layout();
+
                                     // Check if window is now empty
                                     if window.is_empty() {
                                         session.remove_window(window_id);
@@ -1142,6 +1117,13 @@ async fn run_pane_cleanup_loop(
 
                         drop(session_manager);
 
+                        if let Some(layout) = layout {
+                            shared_state
+                                .registry
+                                .broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout })
+                                .await;
+                        }
+
                         if let Some(session) = removed_session {
                             let session_name = session.name().to_string();
 
@@ -1228,6 +1210,7 @@ async fn run_checkpoint_loop(server: Arc<Mutex<Server>>, shared_state: SharedSta
                     // Collect snapshots from shared state
                     let session_manager = shared_state.session_manager.read().await;
                     let snapshots = server_guard.collect_session_snapshots_from(&session_manager);
+                    server_guard.capture_scrollback(&session_manager).await;
                     drop(session_manager);
 
                     if let Err(e) = server_guard.checkpoint_with_snapshots(snapshots).await {
@@ -1253,11 +1236,20 @@ async fn main() -> Result<()> {
         // Handle subcommands first (exact match on first arg)
         match args[1].as_str() {
             "mcp-server" => {
-                return run_mcp_server();
+                // The standalone server kept private session state; proxy through the daemon instead
+                eprintln!("fugue-server: 'mcp-server' is deprecated, running 'mcp-bridge'");
+                return run_mcp_bridge().await;
             }
             "mcp-bridge" => {
                 return run_mcp_bridge().await;
             }
+            "state" => {
+                if let Err(e) = state_cli::run(&args[2..]) {
+                    eprintln!("fugue-server state: {}", e);
+                    std::process::exit(1);
+                }
+                return Ok(());
+            }
             "--help" | "-h" => {
                 print_help();
                 return Ok(());
@@ -1312,8 +1304,9 @@ SUBCOMMANDS:
     (none)          Run as daemon (default)
     mcp-bridge      Run as MCP bridge for Claude Code (recommended)
                     Connects to the running daemon, sharing sessions with TUI
-    mcp-server      Run as standalone MCP server (legacy)
-                    Has its own session state, separate from daemon
+    mcp-server      Deprecated alias for mcp-bridge
+    state           Inspect and repair persisted state without a running daemon
+                    (run 'fugue-server state --help' for details)
 
 OPTIONS:
     -h, --help      Print this help information
@@ -1326,6 +1319,9 @@ EXAMPLES:
     # Run MCP bridge for Claude Code integration
     fugue-server mcp-bridge
 
+    # Check checkpoints and the WAL after a failed recovery
+    fugue-server state verify
+
     # Claude Code MCP configuration (~/.config/claude/claude_desktop_config.json):
     {{
       "mcpServers": {{
@@ -1370,7 +1366,7 @@ mod tests {
             session_manager: Arc::new(RwLock::new(SessionManager::new())),
             pty_manager: Arc::new(RwLock::new(PtyManager::new())),
             registry: Arc::new(ClientRegistry::new()),
-            config: Arc::new(AppConfig::default()),
+            config: crate::config::new_config_handle(),
             shutdown_tx,
             pane_closed_tx,
             command_executor,
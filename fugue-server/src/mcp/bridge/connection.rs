@@ -43,8 +43,9 @@ pub struct ConnectionManager {
     #[allow(dead_code)]
     state_rx: watch::Receiver<ConnectionState>,
     /// Handle to health monitor task (for cleanup)
-    #[allow(dead_code)]
     health_monitor_handle: Option<JoinHandle<()>>,
+    /// Handle to the socket I/O task (for cleanup)
+    io_handle: Option<JoinHandle<()>>,
     /// BUG-065 FIX: Mutex to serialize daemon requests.
     /// The internal daemon protocol lacks request IDs, so concurrent requests
     /// can cause responses to be delivered to wrong callers. This mutex ensures
@@ -66,6 +67,7 @@ impl ConnectionManager {
             state_tx,
             state_rx,
             health_monitor_handle: None,
+            io_handle: None,
             request_lock: Arc::new(Mutex::new(())),
         }
     }
@@ -113,7 +115,7 @@ impl ConnectionManager {
         let io_state_tx = self.state_tx.clone();
 
         // Spawn task to handle socket I/O
-        tokio::spawn(async move {
+        self.io_handle = Some(tokio::spawn(async move {
             loop {
                 tokio::select! {
                     // Send outgoing messages
@@ -158,7 +160,7 @@ impl ConnectionManager {
                     }
                 }
             }
-        });
+        }));
 
         // Send Connect message to establish session with daemon
         self.send_to_daemon(ClientMessage::Connect {
@@ -247,9 +249,7 @@ impl ConnectionManager {
             tokio::time::sleep(Duration::from_millis(*delay_ms)).await;
 
             // Clean up old resources
-            self.daemon_tx = None;
-            self.daemon_rx = None;
-            self.health_monitor_handle = None;
+            self.shutdown();
             debug!("Cleaned up old connection resources");
 
             // Try to reconnect
@@ -419,11 +419,25 @@ impl ConnectionManager {
     }
 
     /// Receive a response from the daemon, filtering based on a predicate
-    pub async fn recv_filtered<F>(&mut self, mut predicate: F) -> Result<ServerMessage, McpError>
+    pub async fn recv_filtered<F>(&mut self, predicate: F) -> Result<ServerMessage, McpError>
+    where
+        F: FnMut(&ServerMessage) -> bool,
+    {
+        self.recv_filtered_within(Duration::from_secs(DAEMON_RESPONSE_TIMEOUT_SECS), predicate)
+            .await
+    }
+
+    /// Like `recv_filtered`, but with a caller-chosen timeout
+    ///
+    /// Used for requests the daemon deliberately answers late, such as `Expect`.
+    pub async fn recv_filtered_within<F>(
+        &mut self,
+        timeout_duration: Duration,
+        mut predicate: F,
+    ) -> Result<ServerMessage, McpError>
     where
         F: FnMut(&ServerMessage) -> bool,
     {
-        let timeout_duration = Duration::from_secs(DAEMON_RESPONSE_TIMEOUT_SECS);
         let deadline = Instant::now() + timeout_duration;
 
         loop {
@@ -431,10 +445,10 @@ impl ConnectionManager {
             if remaining.is_zero() {
                 warn!(
                     "Timeout waiting for daemon response after {}s",
-                    DAEMON_RESPONSE_TIMEOUT_SECS
+                    timeout_duration.as_secs()
                 );
                 return Err(McpError::ResponseTimeout {
-                    seconds: DAEMON_RESPONSE_TIMEOUT_SECS,
+                    seconds: timeout_duration.as_secs(),
                 });
             }
 
@@ -508,6 +522,13 @@ impl ConnectionManager {
             // FEAT-058: Beads query integration broadcasts
             | ServerMessage::BeadsStatusUpdate { .. }
             | ServerMessage::BeadsReadyList { .. }
+            // Agent permission prompts (PermissionResolved is also a direct
+            // response to ResolvePermission, so like PaneClosed it is not listed)
+            | ServerMessage::PermissionRequested { .. }
+            // Usage budgets (PaneResumed is a direct response to ResumePane)
+            | ServerMessage::BudgetExceeded { .. }
+            // Server-owned window layouts
+            | ServerMessage::LayoutChanged { .. }
         )
     }
 
@@ -568,4 +589,114 @@ impl ConnectionManager {
         debug!("BUG-065: Releasing request lock after send_and_recv_filtered");
         result
     }
-}
\ No newline at end of file
+
+    /// Send a message and wait up to `timeout_duration` for a matching response.
+    ///
+    /// For requests the daemon deliberately answers late, such as `Expect`.
+    /// The request_lock (BUG-065) only covers the send: the response is told
+    /// apart by `predicate`, and holding the lock for the whole wait would
+    /// stall every other request for up to the timeout.
+    pub async fn send_and_recv_filtered_within<F>(
+        &mut self,
+        msg: ClientMessage,
+        timeout_duration: Duration,
+        predicate: F,
+    ) -> Result<ServerMessage, McpError>
+    where
+        F: FnMut(&ServerMessage) -> bool,
+    {
+        {
+            let lock = self.request_lock.clone();
+            let _guard = lock.lock().await;
+            self.send_to_daemon(msg).await?;
+        }
+        self.recv_filtered_within(timeout_duration, predicate).await
+    }
+
+    /// Tear down the daemon connection and its background tasks
+    ///
+    /// The health monitor holds a clone of `daemon_tx`, so dropping the
+    /// channels alone would leave the socket open; both tasks are aborted.
+    pub fn shutdown(&mut self) {
+        if let Some(handle) = self.health_monitor_handle.take() {
+            handle.abort();
+        }
+        if let Some(handle) = self.io_handle.take() {
+            handle.abort();
+        }
+        self.daemon_tx = None;
+        self.daemon_rx = None;
+    }
+}
+
+impl Drop for ConnectionManager {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+/// Dedicated daemon connection that receives broadcasts from every session
+///
+/// Kept separate from `ConnectionManager` so unsolicited events never mix
+/// with request/response traffic, which has no request IDs (BUG-065).
+pub struct EventStream {
+    rx: mpsc::UnboundedReceiver<ServerMessage>,
+    handle: JoinHandle<()>,
+}
+
+impl EventStream {
+    /// Connect to the daemon and subscribe to events
+    pub async fn connect(include_output: bool) -> Result<Self, McpError> {
+        let socket = socket_path();
+        let stream = UnixStream::connect(&socket)
+            .await
+            .map_err(|e| McpError::ConnectionFailed(e.to_string()))?;
+        let mut framed = Framed::new(stream, ClientCodec::new());
+
+        framed
+            .send(ClientMessage::Connect {
+                client_id: Uuid::new_v4(),
+                protocol_version: PROTOCOL_VERSION,
+                client_type: ClientType::Mcp,
+            })
+            .await
+            .map_err(|e| McpError::ConnectionFailed(e.to_string()))?;
+
+        match framed.next().await {
+            Some(Ok(ServerMessage::Connected { .. })) => {}
+            Some(Ok(ServerMessage::Error { code, message, .. })) => {
+                return Err(McpError::DaemonError(format!("{:?}: {}", code, message)));
+            }
+            Some(Ok(msg)) => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+            Some(Err(e)) => return Err(McpError::ConnectionFailed(e.to_string())),
+            None => return Err(McpError::DaemonDisconnected),
+        }
+
+        framed
+            .send(ClientMessage::SubscribeEvents { include_output })
+            .await
+            .map_err(|e| McpError::ConnectionFailed(e.to_string()))?;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let handle = tokio::spawn(async move {
+            while let Some(Ok(msg)) = framed.next().await {
+                if tx.send(msg).is_err() {
+                    break;
+                }
+            }
+            debug!("Event stream closed");
+        });
+
+        Ok(Self { rx, handle })
+    }
+
+    /// Receive the next event, or `None` once the connection has closed
+    pub async fn recv(&mut self) -> Option<ServerMessage> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for EventStream {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
 use uuid::Uuid;
 use fugue_protocol::{
     ClientMessage,
@@ -6,6 +7,9 @@ use fugue_protocol::{
     PaneListEntry,
     OrchestrationTarget,
     OrchestrationMessage,
+    ExpectCondition,
+    ExportFormat,
+    PermissionDecision,
 };
 use crate::mcp::error::McpError;
 use crate::mcp::protocol::ToolResult;
@@ -63,6 +67,9 @@ pub fn format_pane_list(panes: &[PaneListEntry]) -> Vec<serde_json::Value> {
     .collect()
 }
 
+/// Next index for `fugue_select_worker` with the round-robin strategy
+static ROUND_ROBIN_CURSOR: AtomicUsize = AtomicUsize::new(0);
+
 pub struct ToolHandlers<'a> {
     pub connection: &'a mut ConnectionManager,
 }
@@ -176,6 +183,31 @@ impl<'a> ToolHandlers<'a> {
     }
     }
 
+    pub async fn tool_capture_pane(
+    &mut self,
+    pane_id: Uuid,
+    start: Option<i64>,
+    end: Option<i64>,
+    strip_escapes: bool,
+    ) -> Result<ToolResult, McpError> {
+    match self.connection.send_and_recv(ClientMessage::CapturePane { pane_id, start, end }).await? {
+    ServerMessage::PaneCaptured { lines, .. } => {
+        let content = lines.join("\n");
+        let output = if strip_escapes {
+            let stripped = strip_ansi_escapes::strip(&content);
+            String::from_utf8_lossy(&stripped).into_owned()
+        } else {
+            content
+        };
+        Ok(ToolResult::text(output))
+    }
+    ServerMessage::Error { code, message, .. } => {
+    Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+    }
+    msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+    }
+    }
+
     // BUG-065 FIX: Use atomic send_and_recv to prevent response mismatches
     pub async fn tool_get_status(&mut self, pane_id: Uuid) -> Result<ToolResult, McpError> {
     match self.connection.send_and_recv(ClientMessage::GetPaneStatus { pane_id }).await? {
@@ -203,6 +235,7 @@ impl<'a> ToolHandlers<'a> {
         "activity": format!("{:?}", agent_state.activity),
         "model": agent_state.get_metadata("model"),
         "tokens_used": agent_state.get_metadata("tokens_used"),
+        "permission_request": agent_state.permission_request(),
     }),
     fugue_protocol::PaneState::Exited { code } => serde_json::json!({
         "type": "exited",
@@ -1121,6 +1154,175 @@ impl<'a> ToolHandlers<'a> {
     }
     }
 
+    /// Export a session as a portable document, optionally writing it to `path`
+    pub async fn tool_export_session(
+        &mut self,
+        session_filter: String,
+        path: Option<String>,
+        format: Option<&str>,
+        include_scrollback: bool,
+    ) -> Result<ToolResult, McpError> {
+        let format = match format {
+            Some("toml") => ExportFormat::Toml,
+            Some("json") => ExportFormat::Json,
+            Some(other) => {
+                return Err(McpError::InvalidParams(format!(
+                    "Invalid format '{}': expected 'json' or 'toml'",
+                    other
+                )))
+            }
+            None if path.as_deref().is_some_and(|p| p.ends_with(".toml")) => ExportFormat::Toml,
+            None => ExportFormat::Json,
+        };
+
+        match self
+            .connection
+            .send_and_recv(ClientMessage::ExportSession {
+                session_filter,
+                format,
+                include_scrollback,
+            })
+            .await?
+        {
+            ServerMessage::SessionExported { document } => match path {
+                Some(path) => {
+                    if let Err(e) = std::fs::write(&path, &document) {
+                        return Ok(ToolResult::error(format!("Failed to write {}: {}", path, e)));
+                    }
+                    let result = serde_json::json!({
+                        "success": true,
+                        "path": path,
+                        "bytes": document.len(),
+                    });
+                    let json = serde_json::to_string_pretty(&result)
+                        .map_err(|e| McpError::Internal(e.to_string()))?;
+                    Ok(ToolResult::text(json))
+                }
+                None => Ok(ToolResult::text(document)),
+            },
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
+    }
+
+    /// Recreate a session from an exported document
+    pub async fn tool_import_session(
+        &mut self,
+        document: String,
+        name: Option<String>,
+    ) -> Result<ToolResult, McpError> {
+        match self
+            .connection
+            .send_and_recv(ClientMessage::ImportSession { document, name })
+            .await?
+        {
+            ServerMessage::SessionImported { session } => {
+                let result = serde_json::json!({
+                    "success": true,
+                    "session_id": session.id.to_string(),
+                    "session_name": session.name,
+                    "window_count": session.window_count,
+                });
+                let json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::Internal(e.to_string()))?;
+                Ok(ToolResult::text(json))
+            }
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
+    }
+
+    pub async fn tool_search(
+        &mut self,
+        query: String,
+        session_filter: Option<String>,
+        context: usize,
+        limit: usize,
+    ) -> Result<ToolResult, McpError> {
+        match self
+            .connection
+            .send_and_recv(ClientMessage::SearchScrollback {
+                query,
+                session_filter,
+                context,
+                limit,
+            })
+            .await?
+        {
+            ServerMessage::ScrollbackSearchResults { query, hits, truncated } => {
+                let result = serde_json::json!({
+                    "query": query,
+                    "count": hits.len(),
+                    "truncated": truncated,
+                    "hits": hits,
+                });
+                let json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::Internal(e.to_string()))?;
+                Ok(ToolResult::text(json))
+            }
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
+    }
+
+    pub async fn tool_buffer(
+        &mut self,
+        action: &str,
+        name: Option<String>,
+        data: Option<String>,
+        append: bool,
+        pane_id: Option<Uuid>,
+        delete: bool,
+    ) -> Result<ToolResult, McpError> {
+        let msg = match (action, data, pane_id) {
+            ("set", Some(data), _) => ClientMessage::SetBuffer { name, data, append },
+            ("set", None, _) => {
+                return Err(McpError::InvalidParams("Missing 'data' parameter".into()));
+            }
+            ("get", _, _) => ClientMessage::GetBuffer { name },
+            ("list", _, _) => ClientMessage::ListBuffers,
+            ("delete", _, _) => ClientMessage::DeleteBuffer { name },
+            ("paste", _, Some(pane_id)) => ClientMessage::PasteBuffer {
+                name,
+                pane_id,
+                delete,
+                bracketed: true,
+            },
+            _ => {
+                return Err(McpError::InvalidParams(
+                    "'action' must be one of set, get, list, delete, paste".into(),
+                ));
+            }
+        };
+
+        let result = match self.connection.send_and_recv(msg).await? {
+            ServerMessage::BufferSet { name } => serde_json::json!({ "name": name, "set": true }),
+            ServerMessage::BufferContent { name, data } => {
+                serde_json::json!({ "name": name, "data": data })
+            }
+            ServerMessage::BufferList { buffers } => serde_json::json!({ "buffers": buffers }),
+            ServerMessage::BufferDeleted { name } => {
+                serde_json::json!({ "name": name, "deleted": true })
+            }
+            ServerMessage::BufferPasted { name, pane_id } => {
+                serde_json::json!({ "name": name, "pane_id": pane_id.to_string(), "pasted": true })
+            }
+            ServerMessage::Error { code, message, .. } => {
+                return Ok(ToolResult::error(format!("{:?}: {}", code, message)));
+            }
+            msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        };
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::Internal(e.to_string()))?;
+        Ok(ToolResult::text(json))
+    }
+
     // BUG-065 FIX: Use atomic send_and_recv to prevent response mismatches
     pub async fn tool_report_status(
     &mut self,
@@ -1248,6 +1450,98 @@ impl<'a> ToolHandlers<'a> {
     }
     }
 
+    /// Get server-wide operational status (FEAT-074)
+    pub async fn tool_server_status(&mut self) -> Result<ToolResult, McpError> {
+        match self.connection.send_and_recv(ClientMessage::GetServerStatus).await? {
+            ServerMessage::ServerStatus {
+                commit_seq,
+                client_count,
+                session_count,
+                replay_range,
+                wal_healthy,
+                checkpoint_healthy,
+                human_control_active,
+            } => {
+                let result = serde_json::json!({
+                    "commit_seq": commit_seq,
+                    "client_count": client_count,
+                    "session_count": session_count,
+                    "replay_range": [replay_range.0, replay_range.1],
+                    "persistence": {
+                        "wal_healthy": wal_healthy,
+                        "checkpoint_healthy": checkpoint_healthy,
+                    },
+                    "human_control_active": human_control_active,
+                });
+                let json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::Internal(e.to_string()))?;
+                Ok(ToolResult::text(json))
+            }
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
+    }
+
+    /// Select a worker session by strategy, optionally restricted to a pool and tags (FEAT-105)
+    pub async fn tool_select_worker(
+        &mut self,
+        strategy: Option<&str>,
+        pool: Option<Vec<String>>,
+        criteria: serde_json::Value,
+    ) -> Result<ToolResult, McpError> {
+        let strategy = strategy.unwrap_or("random");
+        if !matches!(strategy, "random" | "round-robin") {
+            return Err(McpError::InvalidParams(format!("Unknown strategy: {}", strategy)));
+        }
+
+        let required_tags: Vec<&str> = criteria
+            .get("tags")
+            .and_then(|t| t.as_array())
+            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let sessions = match self.connection.send_and_recv(ClientMessage::ListSessions).await? {
+            ServerMessage::SessionList { sessions } => sessions,
+            ServerMessage::Error { code, message, .. } => {
+                return Ok(ToolResult::error(format!("{:?}: {}", code, message)));
+            }
+            msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        };
+
+        let mut candidates: Vec<_> = sessions
+            .into_iter()
+            .filter(|s| {
+                pool.as_ref().is_none_or(|p| {
+                    p.iter().any(|entry| *entry == s.name || *entry == s.id.to_string())
+                })
+            })
+            .filter(|s| required_tags.iter().all(|tag| s.has_tag(tag)))
+            .collect();
+
+        if candidates.is_empty() {
+            return Ok(ToolResult::error("No workers found matching criteria"));
+        }
+
+        // Stable order so round-robin cycles predictably
+        candidates.sort_by(|a, b| a.name.cmp(&b.name));
+        let idx = match strategy {
+            "round-robin" => ROUND_ROBIN_CURSOR.fetch_add(1, Ordering::Relaxed) % candidates.len(),
+            _ => fastrand::usize(..candidates.len()),
+        };
+        let selected = &candidates[idx];
+
+        let result = serde_json::json!({
+            "worker_id": selected.id.to_string(),
+            "name": selected.name,
+            "strategy": strategy,
+        });
+        let json = serde_json::to_string_pretty(&result)
+            .map_err(|e| McpError::Internal(e.to_string()))?;
+        Ok(ToolResult::text(json))
+    }
+
     pub async fn tool_connection_status(&self) -> Result<ToolResult, McpError> {
     let state = *self.connection.connection_state.read().await;
 
@@ -1667,24 +1961,77 @@ impl<'a> ToolHandlers<'a> {
     pub async fn tool_expect(
         &mut self,
         pane_id: Uuid,
-        pattern: &str,
+        condition: ExpectCondition,
         timeout_ms: u64,
         action: &str,
-        poll_interval_ms: u64,
         lines: usize,
     ) -> Result<ToolResult, McpError> {
         let expect_action: ExpectAction = action.parse()?;
-        
-        run_expect(
-            self.connection,
-            pane_id,
-            pattern,
-            timeout_ms,
-            expect_action,
-            poll_interval_ms,
-            lines,
-        )
-        .await
+
+        run_expect(self.connection, pane_id, condition, timeout_ms, expect_action, lines).await
+    }
+
+    // ==================== Permission Prompts ====================
+
+    pub async fn tool_resolve_permission(
+        &mut self,
+        pane_id: Uuid,
+        decision: PermissionDecision,
+    ) -> Result<ToolResult, McpError> {
+        match self
+            .connection
+            .send_and_recv_filtered(
+                ClientMessage::ResolvePermission { pane_id, decision },
+                |msg| {
+                    matches!(msg, ServerMessage::PermissionResolved { pane_id: id, .. } if *id == pane_id)
+                        || matches!(msg, ServerMessage::Error { .. })
+                },
+            )
+            .await?
+        {
+            ServerMessage::PermissionResolved { decision, option, .. } => {
+                let result = serde_json::json!({
+                    "pane_id": pane_id.to_string(),
+                    "decision": format!("{:?}", decision),
+                    "option": option,
+                    "status": "resolved"
+                });
+
+                let json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::Internal(e.to_string()))?;
+                Ok(ToolResult::text(json))
+            }
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
+    }
+
+    pub async fn tool_resume_pane(&mut self, pane_id: Uuid) -> Result<ToolResult, McpError> {
+        match self
+            .connection
+            .send_and_recv_filtered(ClientMessage::ResumePane { pane_id }, |msg| {
+                matches!(msg, ServerMessage::PaneResumed { pane_id: id } if *id == pane_id)
+                    || matches!(msg, ServerMessage::Error { .. })
+            })
+            .await?
+        {
+            ServerMessage::PaneResumed { .. } => {
+                let result = serde_json::json!({
+                    "pane_id": pane_id.to_string(),
+                    "status": "resumed"
+                });
+
+                let json = serde_json::to_string_pretty(&result)
+                    .map_err(|e| McpError::Internal(e.to_string()))?;
+                Ok(ToolResult::text(json))
+            }
+            ServerMessage::Error { code, message, .. } => {
+                Ok(ToolResult::error(format!("{:?}: {}", code, message)))
+            }
+            msg => Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+        }
     }
 
     // ==================== FEAT-095: Pipeline Tool ====================
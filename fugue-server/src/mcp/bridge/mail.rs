@@ -590,6 +590,30 @@ pub fn mail_delete(
     }
 }
 
+/// Cheap change marker for a mailbox: unread message count and newest mtime
+///
+/// Used to raise resource update notifications for subscribed mailboxes,
+/// since mail is delivered by writing files rather than through the daemon.
+pub fn mailbox_stamp(mailbox: &str) -> Option<(usize, std::time::SystemTime)> {
+    let entries = fs::read_dir(get_mailbox_path(mailbox)).ok()?;
+
+    let mut count = 0;
+    let mut newest = std::time::SystemTime::UNIX_EPOCH;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_message = path.extension().is_some_and(|e| e == "md")
+            && !path.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with('.'));
+        if !is_message {
+            continue;
+        }
+        if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+            count += 1;
+            newest = newest.max(modified);
+        }
+    }
+    Some((count, newest))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,13 +1,16 @@
 //! MCP Bridge - Connects MCP protocol to the fugue daemon
 //!
 //! This module implements the MCP bridge that translates between MCP JSON-RPC
-//! (over stdio) and the fugue IPC protocol (over Unix socket).
+//! (over stdio) and the fugue IPC protocol (over Unix socket). It is the only
+//! MCP implementation: every tool in `tools.rs` is proxied through the daemon,
+//! so anything an agent creates is visible to attached TUI clients.
 
 pub mod connection;
 pub mod handlers;
 pub mod health;
 pub mod mail;
 pub mod orchestration;
+pub mod resources;
 
 #[cfg(test)]
 mod tests;
@@ -15,6 +18,7 @@ mod tests;
 use std::io::Write;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::io::{AsyncBufReadExt, BufReader};
+use fugue_protocol::{PermissionDecision, ServerMessage};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
@@ -23,13 +27,15 @@ static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(1);
 
 use crate::mcp::error::McpError;
 use crate::mcp::protocol::{
-    InitializeResult, JsonRpcError, JsonRpcRequest, JsonRpcResponse, ToolResult, ToolsListResult,
+    InitializeResult, JsonRpcError, JsonRpcRequest, JsonRpcResponse, ResourceTemplatesListResult,
+    ResourcesListResult, ToolResult, ToolsListResult,
 };
 use crate::mcp::tools::get_tool_definitions;
 
-use self::connection::{ConnectionManager, MAX_RECONNECT_ATTEMPTS};
+use self::connection::{ConnectionManager, EventStream, MAX_RECONNECT_ATTEMPTS};
 use self::handlers::{ToolHandlers, parse_uuid};
 use self::health::ConnectionState;
+use self::resources::{ResourceSubscriptions, ResourceUri};
 
 /// MCP Bridge
 ///
@@ -37,6 +43,7 @@ use self::health::ConnectionState;
 pub struct McpBridge {
     connection: ConnectionManager,
     initialized: bool,
+    subscriptions: ResourceSubscriptions,
 }
 
 impl McpBridge {
@@ -45,9 +52,25 @@ impl McpBridge {
         Self {
             connection: ConnectionManager::new(),
             initialized: false,
+            subscriptions: ResourceSubscriptions::default(),
         }
     }
 
+    /// Connect to the daemon without starting the stdio loop
+    ///
+    /// Used by transports other than stdio (see `mcp::http`).
+    pub async fn connect(&mut self) -> Result<(), McpError> {
+        self.connection.connect_to_daemon().await
+    }
+
+    /// Resource subscriptions made through this bridge
+    ///
+    /// Transports feed these daemon events and send the resulting
+    /// `notifications/resources/updated` to the client.
+    pub fn resource_subscriptions(&self) -> ResourceSubscriptions {
+        self.subscriptions.clone()
+    }
+
     /// Run the MCP bridge, reading from stdin and writing to stdout
     pub async fn run(&mut self) -> Result<(), McpError> {
         // Connect to daemon first
@@ -63,7 +86,34 @@ impl McpBridge {
 
         info!("MCP bridge starting");
 
-        while let Some(line) = lines.next_line().await? {
+        // Opened on first resource subscription; see `resources`
+        let mut events: Option<EventStream> = None;
+        let mut flush = tokio::time::interval(resources::UPDATE_FLUSH_INTERVAL);
+
+        loop {
+            let line = tokio::select! {
+                line = lines.next_line() => match line? {
+                    Some(line) => line,
+                    None => break,
+                },
+                event = next_event(&mut events) => {
+                    match event {
+                        Some(msg) => self.subscriptions.note_event(&msg),
+                        None => events = None,
+                    }
+                    continue;
+                }
+                _ = flush.tick() => {
+                    self.sync_event_stream(&mut events).await;
+                    for uri in self.subscriptions.take_updates() {
+                        let json = serde_json::to_string(&resources::updated_notification(&uri))?;
+                        writeln!(stdout, "{}", json)?;
+                    }
+                    stdout.flush()?;
+                    continue;
+                }
+            };
+
             if line.is_empty() {
                 continue;
             }
@@ -104,21 +154,13 @@ impl McpBridge {
             );
 
             // Validate JSON-RPC version
-            if request.jsonrpc != "2.0" {
+            if let Some(response) = invalid_version_response(&request) {
                 error!(
                     req_id = log_req_id,
                     method = %request.method,
                     got_version = %request.jsonrpc,
                     "Invalid JSON-RPC version"
                 );
-                let response = JsonRpcResponse::error(
-                    request.id,
-                    JsonRpcError::with_data(
-                        JsonRpcError::INVALID_REQUEST,
-                        "Invalid JSON-RPC version",
-                        serde_json::json!({"expected": "2.0", "got": request.jsonrpc}),
-                    ),
-                );
                 let json = serde_json::to_string(&response)?;
                 writeln!(stdout, "{}", json)?;
                 stdout.flush()?;
@@ -169,8 +211,20 @@ impl McpBridge {
         Ok(())
     }
 
+    /// Open or close the stdio event stream to match current subscriptions
+    async fn sync_event_stream(&self, events: &mut Option<EventStream>) {
+        if self.subscriptions.is_empty() {
+            *events = None;
+        } else if events.is_none() {
+            match EventStream::connect(true).await {
+                Ok(stream) => *events = Some(stream),
+                Err(e) => debug!(error = %e, "Failed to open event stream for resource updates"),
+            }
+        }
+    }
+
     /// Handle a JSON-RPC request
-    async fn handle_request(&mut self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
+    pub(crate) async fn handle_request(&mut self, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
         let is_notification = request.id.is_null();
         let result = match request.method.as_str() {
             "initialize" => self.handle_initialize(&request.params),
@@ -178,6 +232,11 @@ impl McpBridge {
             "ping" => Ok(serde_json::json!({})),
             "tools/list" => self.handle_tools_list(),
             "tools/call" => self.handle_tools_call(&request.params).await,
+            "resources/list" => self.handle_resources_list().await,
+            "resources/templates/list" => Self::handle_resource_templates_list(),
+            "resources/read" => self.handle_resources_read(&request.params).await,
+            "resources/subscribe" => self.handle_resources_subscribe(&request.params, true),
+            "resources/unsubscribe" => self.handle_resources_subscribe(&request.params, false),
             _ => Err(McpError::MethodNotFound(request.method.clone())),
         };
 
@@ -213,6 +272,48 @@ impl McpBridge {
         serde_json::to_value(result).map_err(|e| McpError::Internal(e.to_string()))
     }
 
+    /// Handle resources/list request
+    async fn handle_resources_list(&mut self) -> Result<serde_json::Value, McpError> {
+        let resources = resources::list_resources(&mut self.connection).await?;
+        serde_json::to_value(ResourcesListResult { resources })
+            .map_err(|e| McpError::Internal(e.to_string()))
+    }
+
+    /// Handle resources/templates/list request
+    fn handle_resource_templates_list() -> Result<serde_json::Value, McpError> {
+        let result = ResourceTemplatesListResult {
+            resource_templates: resources::resource_templates(),
+        };
+        serde_json::to_value(result).map_err(|e| McpError::Internal(e.to_string()))
+    }
+
+    /// Handle resources/read request
+    async fn handle_resources_read(
+        &mut self,
+        params: &serde_json::Value,
+    ) -> Result<serde_json::Value, McpError> {
+        let uri = parse_resource_uri(params)?;
+        let result = resources::read_resource(&mut self.connection, &uri).await?;
+        serde_json::to_value(result).map_err(|e| McpError::Internal(e.to_string()))
+    }
+
+    /// Handle resources/subscribe and resources/unsubscribe requests
+    fn handle_resources_subscribe(
+        &mut self,
+        params: &serde_json::Value,
+        subscribe: bool,
+    ) -> Result<serde_json::Value, McpError> {
+        let uri = parse_resource_uri(params)?;
+        if subscribe {
+            self.subscriptions.subscribe(&uri);
+            info!(uri = %uri, "Subscribed to resource");
+        } else {
+            self.subscriptions.unsubscribe(&uri);
+            info!(uri = %uri, "Unsubscribed from resource");
+        }
+        Ok(serde_json::json!({}))
+    }
+
     /// Handle tools/call request
     async fn handle_tools_call(
         &mut self,
@@ -326,8 +427,14 @@ impl McpBridge {
             "fugue_read_pane" => {
                 let pane_id = parse_uuid(arguments, "pane_id")?;
                 let lines = arguments["lines"].as_u64().unwrap_or(100) as usize;
+                let start = arguments["start"].as_i64();
+                let end = arguments["end"].as_i64();
                 let strip_escapes = arguments["strip_escapes"].as_bool().unwrap_or(false);
-                handlers.tool_read_pane(pane_id, lines, strip_escapes).await
+                if start.is_some() || end.is_some() {
+                    handlers.tool_capture_pane(pane_id, start, end, strip_escapes).await
+                } else {
+                    handlers.tool_read_pane(pane_id, lines, strip_escapes).await
+                }
             }
             "fugue_get_status" => {
                 let pane_id = parse_uuid(arguments, "pane_id")?;
@@ -551,7 +658,60 @@ impl McpBridge {
                 let session = arguments["session"].as_str().map(String::from);
                 handlers.tool_get_tags(session).await
             }
-            "fugue_report_status" => {
+            "fugue_export_session" => {
+                let session = arguments["session"]
+                    .as_str()
+                    .ok_or_else(|| McpError::InvalidParams("Missing 'session' parameter".into()))?;
+                let path = arguments["path"].as_str().map(String::from);
+                let format = arguments["format"].as_str();
+                let include_scrollback = arguments["include_scrollback"].as_bool().unwrap_or(false);
+                handlers
+                    .tool_export_session(session.to_string(), path, format, include_scrollback)
+                    .await
+            }
+            "fugue_import_session" => {
+                let document = match (arguments["document"].as_str(), arguments["path"].as_str()) {
+                    (Some(document), _) => document.to_string(),
+                    (None, Some(path)) => std::fs::read_to_string(path).map_err(|e| {
+                        McpError::InvalidParams(format!("Cannot read '{}': {}", path, e))
+                    })?,
+                    (None, None) => {
+                        return Err(McpError::InvalidParams(
+                            "Missing 'path' or 'document' parameter".into(),
+                        ))
+                    }
+                };
+                let name = arguments["name"].as_str().map(String::from);
+                handlers.tool_import_session(document, name).await
+            }
+            "fugue_search" => {
+                let query = arguments["query"]
+                    .as_str()
+                    .ok_or_else(|| McpError::InvalidParams("Missing 'query' parameter".into()))?;
+                let session = arguments["session"].as_str().map(String::from);
+                let context = arguments["context"].as_u64().unwrap_or(2) as usize;
+                let limit = arguments["limit"].as_u64().unwrap_or(50) as usize;
+                handlers
+                    .tool_search(query.to_string(), session, context, limit)
+                    .await
+            }
+            "fugue_buffer" => {
+                let action = arguments["action"]
+                    .as_str()
+                    .ok_or_else(|| McpError::InvalidParams("Missing 'action' parameter".into()))?;
+                let name = arguments["name"].as_str().map(String::from);
+                let data = arguments["data"].as_str().map(String::from);
+                let append = arguments["append"].as_bool().unwrap_or(false);
+                let pane_id = match action {
+                    "paste" => Some(parse_uuid(arguments, "pane_id")?),
+                    _ => None,
+                };
+                let delete = arguments["delete"].as_bool().unwrap_or(false);
+                handlers
+                    .tool_buffer(action, name, data, append, pane_id, delete)
+                    .await
+            }
+                        "fugue_report_status" => {
                 let status = arguments["status"]
                     .as_str()
                     .ok_or_else(|| McpError::InvalidParams("Missing 'status' parameter".into()))?;
@@ -619,15 +779,29 @@ impl McpBridge {
             }
             "fugue_expect" => {
                 let pane_id = parse_uuid(arguments, "pane_id")?;
-                let pattern = arguments["pattern"]
-                    .as_str()
-                    .ok_or_else(|| McpError::InvalidParams("Missing 'pattern' parameter".into()))?;
+                let condition = orchestration::expect_condition(arguments)?;
                 let timeout_ms = arguments["timeout_ms"].as_u64().unwrap_or(60000);
                 let action = arguments["action"].as_str().unwrap_or("notify");
-                let poll_interval_ms = arguments["poll_interval_ms"].as_u64().unwrap_or(200);
                 let lines = arguments["lines"].as_u64().unwrap_or(100) as usize;
 
-                handlers.tool_expect(pane_id, pattern, timeout_ms, action, poll_interval_ms, lines).await
+                handlers.tool_expect(pane_id, condition, timeout_ms, action, lines).await
+            }
+            "fugue_approve" => {
+                let pane_id = parse_uuid(arguments, "pane_id")?;
+                let decision = if arguments["always"].as_bool().unwrap_or(false) {
+                    PermissionDecision::ApproveAlways
+                } else {
+                    PermissionDecision::Approve
+                };
+                handlers.tool_resolve_permission(pane_id, decision).await
+            }
+            "fugue_deny" => {
+                let pane_id = parse_uuid(arguments, "pane_id")?;
+                handlers.tool_resolve_permission(pane_id, PermissionDecision::Deny).await
+            }
+            "fugue_resume_pane" => {
+                let pane_id = parse_uuid(arguments, "pane_id")?;
+                handlers.tool_resume_pane(pane_id).await
             }
             "fugue_run_parallel" => {
                 let request: orchestration::RunParallelRequest = serde_json::from_value(arguments.clone())
@@ -746,6 +920,14 @@ impl McpBridge {
                 let archive = arguments["archive"].as_bool().unwrap_or(true);
                 handlers.tool_mail_delete(mailbox, filename, archive).await
             }
+            "fugue_server_status" => handlers.tool_server_status().await,
+            "fugue_select_worker" => {
+                let strategy = arguments["strategy"].as_str();
+                let pool = arguments["pool"].as_array().map(|arr| {
+                    arr.iter().filter_map(|v| v.as_str().map(String::from)).collect()
+                });
+                handlers.tool_select_worker(strategy, pool, arguments["criteria"].clone()).await
+            }
             _ => Err(McpError::UnknownTool(name.into())),
         }
     }
@@ -755,4 +937,63 @@ impl Default for McpBridge {
     fn default() -> Self {
         Self::new()
     }
+}
+
+/// Parse the `uri` parameter of a resources/* request
+fn parse_resource_uri(params: &serde_json::Value) -> Result<ResourceUri, McpError> {
+    let uri = params["uri"]
+        .as_str()
+        .ok_or_else(|| McpError::InvalidParams("Missing 'uri' parameter".into()))?;
+    ResourceUri::parse(uri)
+}
+
+/// Wait for the next event, or forever if no event stream is open
+async fn next_event(events: &mut Option<EventStream>) -> Option<ServerMessage> {
+    match events {
+        Some(stream) => stream.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Build the error response for a request that is not JSON-RPC 2.0
+pub(crate) fn invalid_version_response(request: &JsonRpcRequest) -> Option<JsonRpcResponse> {
+    if request.jsonrpc == "2.0" {
+        return None;
+    }
+    Some(JsonRpcResponse::error(
+        request.id.clone(),
+        JsonRpcError::with_data(
+            JsonRpcError::INVALID_REQUEST,
+            "Invalid JSON-RPC version",
+            serde_json::json!({"expected": "2.0", "got": request.jsonrpc}),
+        ),
+    ))
+}
+
+#[cfg(test)]
+impl McpBridge {
+    /// Build a connected bridge whose daemon requests are answered by `respond`
+    pub(crate) async fn with_fake_daemon<F>(respond: F) -> Self
+    where
+        F: Fn(fugue_protocol::ClientMessage) -> fugue_protocol::ServerMessage + Send + 'static,
+    {
+        use fugue_protocol::{ClientMessage, ServerMessage};
+        use tokio::sync::mpsc;
+
+        let (daemon_tx, mut outgoing_rx) = mpsc::channel::<ClientMessage>(32);
+        let (incoming_tx, daemon_rx) = mpsc::unbounded_channel::<ServerMessage>();
+        tokio::spawn(async move {
+            while let Some(msg) = outgoing_rx.recv().await {
+                if incoming_tx.send(respond(msg)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut bridge = Self::new();
+        bridge.connection.daemon_tx = Some(daemon_tx);
+        bridge.connection.daemon_rx = Some(daemon_rx);
+        *bridge.connection.connection_state.write().await = ConnectionState::Connected;
+        bridge
+    }
 }pub mod orchestration_context;
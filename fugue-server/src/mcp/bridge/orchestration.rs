@@ -9,9 +9,9 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 use regex::Regex;
-use serde_json::json;
+use serde_json::{json, Value};
 
-use fugue_protocol::{ClientMessage, ServerMessage};
+use fugue_protocol::{AgentActivity, ClientMessage, ExpectCondition, ExpectOutcome, ServerMessage};
 
 use super::connection::ConnectionManager;
 use super::orchestration_context::{OrchestrationContext, OrchestrationConfig, CreatePaneOptions};
@@ -42,90 +42,186 @@ impl std::str::FromStr for ExpectAction {
     }
 }
 
-/// Wait for a regex pattern to appear in a pane's output
+/// Extra time allowed for the daemon's `ExpectResult` beyond the requested timeout
+const EXPECT_RESPONSE_GRACE: Duration = Duration::from_secs(5);
+
+/// Parse an agent activity name as accepted by `fugue_expect`
+///
+/// Accepts snake_case or the Debug names used in tool output (`tool_use`,
+/// `ToolUse`); anything else is treated as an agent-specific custom state.
+pub fn parse_agent_activity(name: &str) -> AgentActivity {
+    match name.to_ascii_lowercase().replace(['_', '-'], "").as_str() {
+        "idle" => AgentActivity::Idle,
+        "processing" | "thinking" => AgentActivity::Processing,
+        "generating" | "coding" => AgentActivity::Generating,
+        "tooluse" => AgentActivity::ToolUse,
+        "awaitingconfirmation" => AgentActivity::AwaitingConfirmation,
+        "awaitingpermission" | "permission" => AgentActivity::AwaitingPermission,
+        _ => AgentActivity::Custom(name.to_string()),
+    }
+}
+
+/// Build the daemon-side condition from `fugue_expect` arguments
+///
+/// Exactly one of `pattern`/`patterns` or `agent_state` must be given.
+pub fn expect_condition(arguments: &Value) -> Result<ExpectCondition, McpError> {
+    fn strings(value: &Value, name: &str) -> Result<Vec<String>, McpError> {
+        match value {
+            Value::Null => Ok(Vec::new()),
+            Value::String(s) => Ok(vec![s.clone()]),
+            Value::Array(items) => items
+                .iter()
+                .map(|v| {
+                    v.as_str().map(String::from).ok_or_else(|| {
+                        McpError::InvalidParams(format!("'{}' must contain only strings", name))
+                    })
+                })
+                .collect(),
+            _ => Err(McpError::InvalidParams(format!(
+                "'{}' must be a string or an array of strings",
+                name
+            ))),
+        }
+    }
+
+    let mut patterns = strings(&arguments["pattern"], "pattern")?;
+    patterns.extend(strings(&arguments["patterns"], "patterns")?);
+    let states = strings(&arguments["agent_state"], "agent_state")?;
+
+    match (patterns.is_empty(), states.is_empty()) {
+        (false, true) => Ok(ExpectCondition::Output { patterns }),
+        (true, false) => Ok(ExpectCondition::AgentActivity {
+            activities: states.iter().map(|s| parse_agent_activity(s)).collect(),
+        }),
+        (true, true) => Err(McpError::InvalidParams(
+            "Missing 'pattern', 'patterns' or 'agent_state' parameter".into(),
+        )),
+        (false, false) => Err(McpError::InvalidParams(
+            "'agent_state' cannot be combined with 'pattern'/'patterns'".into(),
+        )),
+    }
+}
+
+/// Wait for pane output or agent state via a daemon-side `Expect`
+///
+/// The daemon matches against the live output stream, so nothing that
+/// scrolls past between reads is missed and no polling is needed.
 pub async fn run_expect(
     connection: &mut ConnectionManager,
     pane_id: Uuid,
-    pattern: &str,
+    condition: ExpectCondition,
     timeout_ms: u64,
     action: ExpectAction,
-    poll_interval_ms: u64,
     lines: usize,
 ) -> Result<ToolResult, McpError> {
-    let regex = Regex::new(pattern)
-        .map_err(|e| McpError::InvalidParams(format!("Invalid regex pattern: {}", e)))?;
+    // Validate locally so bad patterns are reported as invalid params
+    let patterns = match &condition {
+        ExpectCondition::Output { patterns } => {
+            for pattern in patterns {
+                Regex::new(pattern).map_err(|e| {
+                    McpError::InvalidParams(format!("Invalid regex pattern: {}", e))
+                })?;
+            }
+            patterns.clone()
+        }
+        ExpectCondition::AgentActivity { .. } => Vec::new(),
+    };
 
-    let start_time = Instant::now();
-    let timeout = Duration::from_millis(timeout_ms);
-    let poll_interval = Duration::from_millis(poll_interval_ms);
+    let wait = Duration::from_millis(timeout_ms) + EXPECT_RESPONSE_GRACE;
+    let response = connection
+        .send_and_recv_filtered_within(
+            ClientMessage::Expect {
+                pane_id,
+                condition,
+                timeout_ms,
+                lines,
+            },
+            wait,
+            |msg| {
+                matches!(msg, ServerMessage::ExpectResult { pane_id: id, .. } if *id == pane_id)
+                    || matches!(msg, ServerMessage::Error { .. })
+            },
+        )
+        .await?;
+
+    let (outcome, elapsed_ms) = match response {
+        ServerMessage::ExpectResult {
+            outcome,
+            elapsed_ms,
+            ..
+        } => (outcome, elapsed_ms),
+        ServerMessage::Error { code, message, .. } => {
+            return Ok(ToolResult::error(format!("{:?}: {}", code, message)));
+        }
+        msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
+    };
 
-    loop {
-        // 1. Check for timeout
-        let elapsed = start_time.elapsed();
-        if elapsed > timeout {
+    let mut response = match outcome {
+        ExpectOutcome::Matched {
+            pattern_index,
+            text,
+            line,
+            captures,
+            named_captures,
+        } => json!({
+            "status": "matched",
+            "pattern": patterns.get(pattern_index),
+            "pattern_index": pattern_index,
+            "match": text,
+            "line": line,
+            "captures": captures,
+            "named_captures": named_captures,
+            "duration_ms": elapsed_ms,
+        }),
+        ExpectOutcome::AgentActivity { activity } => json!({
+            "status": "matched",
+            "agent_state": format!("{:?}", activity),
+            "duration_ms": elapsed_ms,
+        }),
+        ExpectOutcome::Timeout => {
             return Ok(ToolResult::text(json!({
                 "status": "timeout",
-                "pattern": pattern,
-                "duration_ms": elapsed.as_millis(),
+                "duration_ms": elapsed_ms,
             }).to_string()));
         }
+        ExpectOutcome::PaneClosed => {
+            return Ok(ToolResult::text(json!({
+                "status": "pane_closed",
+                "duration_ms": elapsed_ms,
+            }).to_string()));
+        }
+    };
 
-        // 2. Read pane content
-        connection.send_to_daemon(ClientMessage::ReadPane { pane_id, lines }).await?;
-
-        let content = match connection.recv_response_from_daemon().await? {
-            ServerMessage::PaneContent { content, .. } => content,
-            ServerMessage::Error { code, message, .. } => {
-                // If pane not found or other error, return immediately
-                return Ok(ToolResult::error(format!("{:?}: {}", code, message)));
+    match action {
+        ExpectAction::Notify => {}
+        ExpectAction::ClosePane => {
+            connection.send_to_daemon(ClientMessage::ClosePane { pane_id }).await?;
+            match connection.recv_response_from_daemon().await? {
+                ServerMessage::PaneClosed { .. } => {},
+                ServerMessage::Error { code, message, .. } => {
+                     return Ok(ToolResult::error(format!("Pattern found but failed to close pane: {:?}: {}", code, message)));
+                }
+                 msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
             }
-            msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
-        };
-
-        // 3. Check for match
-        if let Some(mat) = regex.find(&content) {
-            let match_text = mat.as_str().to_string();
-            // Extract the line containing the match for context
-            let start_index = mat.start();
-            let line_start = content[..start_index].rfind('\n').map(|i| i + 1).unwrap_or(0);
-            let line_end = content[start_index..].find('\n').map(|i| start_index + i).unwrap_or(content.len());
-            let line_content = content[line_start..line_end].to_string();
-
-            let mut response = json!({
-                "status": "matched",
-                "pattern": pattern,
-                "match": match_text,
-                "line": line_content,
-                "duration_ms": elapsed.as_millis(),
-            });
-
-            // 4. Perform action
-            match action {
-                ExpectAction::Notify => {
-                    // Just return success
-                },
-                ExpectAction::ClosePane => {
-                    connection.send_to_daemon(ClientMessage::ClosePane { pane_id }).await?;
-                    match connection.recv_response_from_daemon().await? {
-                        ServerMessage::PaneClosed { .. } => {},
-                        ServerMessage::Error { code, message, .. } => {
-                             return Ok(ToolResult::error(format!("Pattern found but failed to close pane: {:?}: {}", code, message)));
-                        }
-                         msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
-                    }
-                    response["pane_closed"] = json!(true);
-                },
-                ExpectAction::ReturnOutput => {
+            response["pane_closed"] = json!(true);
+        }
+        ExpectAction::ReturnOutput => {
+            match connection
+                .send_and_recv(ClientMessage::ReadPane { pane_id, lines: lines.max(1) })
+                .await?
+            {
+                ServerMessage::PaneContent { content, .. } => {
                     response["output"] = json!(content);
                 }
+                ServerMessage::Error { code, message, .. } => {
+                    return Ok(ToolResult::error(format!("Pattern found but failed to read pane: {:?}: {}", code, message)));
+                }
+                msg => return Err(McpError::UnexpectedResponse(format!("{:?}", msg))),
             }
-
-            return Ok(ToolResult::text(response.to_string()));
         }
-
-        // 5. Wait before next poll
-        tokio::time::sleep(poll_interval).await;
     }
+
+    Ok(ToolResult::text(response.to_string()))
 }
 
 // ============================================================================ 
@@ -0,0 +1,512 @@
+//! MCP resources for panes, sessions and mailboxes
+//!
+//! Exposes read-only views that agents can subscribe to instead of polling
+//! `fugue_read_pane` in a loop:
+//!
+//! - `fugue://sessions` - session tree (sessions, windows, panes, agent state)
+//! - `fugue://pane/{pane_id}/screen` - visible screen of a pane
+//! - `fugue://pane/{pane_id}/scrollback` - recent scrollback of a pane
+//! - `fugue://mail/{mailbox}` - unread messages in a mailbox
+//!
+//! Subscriptions are tracked in [`ResourceSubscriptions`]. The transport feeds
+//! it daemon events and periodically drains coalesced URIs to send
+//! `notifications/resources/updated`, so a chatty pane produces at most one
+//! notification per flush interval.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, SystemTime};
+
+use fugue_protocol::{ClientMessage, ErrorCode, ServerMessage};
+use uuid::Uuid;
+
+use crate::mcp::error::McpError;
+use crate::mcp::protocol::{
+    JsonRpcNotification, ReadResourceResult, Resource, ResourceContents, ResourceTemplate,
+    ToolContent,
+};
+
+use super::connection::ConnectionManager;
+use super::handlers::format_pane_list;
+use super::mail;
+
+/// URI of the session tree resource
+pub const SESSIONS_URI: &str = "fugue://sessions";
+
+/// Notification method for subscribed resource changes
+pub const UPDATED_METHOD: &str = "notifications/resources/updated";
+
+/// How often transports flush coalesced resource updates
+pub const UPDATE_FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Lines returned for the scrollback resource
+const SCROLLBACK_LINES: usize = 1000;
+
+const URI_SCHEME: &str = "fugue://";
+
+/// A parsed fugue resource URI
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResourceUri {
+    Sessions,
+    PaneScreen(Uuid),
+    PaneScrollback(Uuid),
+    Mailbox(String),
+}
+
+impl ResourceUri {
+    /// Parse a `fugue://` URI
+    pub fn parse(uri: &str) -> Result<Self, McpError> {
+        let not_found = || McpError::ResourceNotFound(uri.to_string());
+        let path = uri.strip_prefix(URI_SCHEME).ok_or_else(not_found)?;
+        let parts: Vec<&str> = path.split('/').collect();
+
+        match parts.as_slice() {
+            ["sessions"] => Ok(Self::Sessions),
+            ["pane", id, view] => {
+                let pane_id = Uuid::parse_str(id).map_err(|_| not_found())?;
+                match *view {
+                    "screen" => Ok(Self::PaneScreen(pane_id)),
+                    "scrollback" => Ok(Self::PaneScrollback(pane_id)),
+                    _ => Err(not_found()),
+                }
+            }
+            // Mailbox names map to directories under .mail/, so no traversal
+            ["mail", name] if !name.is_empty() && *name != "." && *name != ".." => {
+                Ok(Self::Mailbox(name.to_string()))
+            }
+            _ => Err(not_found()),
+        }
+    }
+
+    fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Sessions | Self::Mailbox(_) => "application/json",
+            Self::PaneScreen(_) | Self::PaneScrollback(_) => "text/plain",
+        }
+    }
+}
+
+impl fmt::Display for ResourceUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sessions => write!(f, "{}", SESSIONS_URI),
+            Self::PaneScreen(id) => write!(f, "{}pane/{}/screen", URI_SCHEME, id),
+            Self::PaneScrollback(id) => write!(f, "{}pane/{}/scrollback", URI_SCHEME, id),
+            Self::Mailbox(name) => write!(f, "{}mail/{}", URI_SCHEME, name),
+        }
+    }
+}
+
+/// Resource templates advertised by `resources/templates/list`
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+    vec![
+        ResourceTemplate {
+            uri_template: "fugue://pane/{pane_id}/screen".into(),
+            name: "Pane screen".into(),
+            description: Some("Visible screen contents of a pane, escape sequences stripped".into()),
+            mime_type: Some("text/plain".into()),
+        },
+        ResourceTemplate {
+            uri_template: "fugue://pane/{pane_id}/scrollback".into(),
+            name: "Pane scrollback".into(),
+            description: Some(format!(
+                "Last {} lines of pane output, escape sequences stripped",
+                SCROLLBACK_LINES
+            )),
+            mime_type: Some("text/plain".into()),
+        },
+        ResourceTemplate {
+            uri_template: "fugue://mail/{mailbox}".into(),
+            name: "Mailbox".into(),
+            description: Some("Unread messages in a mailbox (see fugue_mail_check)".into()),
+            mime_type: Some("application/json".into()),
+        },
+    ]
+}
+
+/// Concrete resources advertised by `resources/list`
+///
+/// Lists the session tree and the screen of every pane.
+pub async fn list_resources(connection: &mut ConnectionManager) -> Result<Vec<Resource>, McpError> {
+    let panes = match connection
+        .send_and_recv(ClientMessage::ListAllPanes { session_filter: None })
+        .await?
+    {
+        ServerMessage::AllPanesList { panes } => panes,
+        msg => return Err(unexpected(msg)),
+    };
+
+    let mut resources = vec![Resource {
+        uri: SESSIONS_URI.into(),
+        name: "Sessions".into(),
+        description: Some("Session tree with windows, panes and agent state".into()),
+        mime_type: Some("application/json".into()),
+    }];
+
+    resources.extend(panes.iter().map(|p| {
+        let label = p.name.clone().unwrap_or_else(|| format!("{}:{}.{}", p.session_name, p.window_index, p.pane_index));
+        Resource {
+            uri: ResourceUri::PaneScreen(p.id).to_string(),
+            name: format!("Pane {} screen", label),
+            description: p.title.clone(),
+            mime_type: Some("text/plain".into()),
+        }
+    }));
+
+    Ok(resources)
+}
+
+/// Read a resource
+pub async fn read_resource(
+    connection: &mut ConnectionManager,
+    uri: &ResourceUri,
+) -> Result<ReadResourceResult, McpError> {
+    let text = match uri {
+        ResourceUri::Sessions => read_session_tree(connection).await?,
+        ResourceUri::PaneScreen(pane_id) => {
+            let rows = match connection
+                .send_and_recv(ClientMessage::GetPaneStatus { pane_id: *pane_id })
+                .await?
+            {
+                ServerMessage::PaneStatus { rows, .. } => rows as usize,
+                msg => return Err(daemon_error(uri, msg)),
+            };
+            read_pane_text(connection, uri, *pane_id, rows).await?
+        }
+        ResourceUri::PaneScrollback(pane_id) => {
+            read_pane_text(connection, uri, *pane_id, SCROLLBACK_LINES).await?
+        }
+        ResourceUri::Mailbox(name) => {
+            let result = mail::mail_check(name, None, None, None)?;
+            result
+                .content
+                .into_iter()
+                .map(|ToolContent::Text { text }| text)
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    };
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(uri.mime_type().into()),
+            text,
+        }],
+    })
+}
+
+async fn read_pane_text(
+    connection: &mut ConnectionManager,
+    uri: &ResourceUri,
+    pane_id: Uuid,
+    lines: usize,
+) -> Result<String, McpError> {
+    match connection
+        .send_and_recv(ClientMessage::ReadPane { pane_id, lines })
+        .await?
+    {
+        ServerMessage::PaneContent { content, .. } => {
+            let stripped = strip_ansi_escapes::strip(&content);
+            Ok(String::from_utf8_lossy(&stripped).into_owned())
+        }
+        msg => Err(daemon_error(uri, msg)),
+    }
+}
+
+async fn read_session_tree(connection: &mut ConnectionManager) -> Result<String, McpError> {
+    let sessions = match connection.send_and_recv(ClientMessage::ListSessions).await? {
+        ServerMessage::SessionList { sessions } => sessions,
+        msg => return Err(unexpected(msg)),
+    };
+    let panes = match connection
+        .send_and_recv(ClientMessage::ListAllPanes { session_filter: None })
+        .await?
+    {
+        ServerMessage::AllPanesList { panes } => panes,
+        msg => return Err(unexpected(msg)),
+    };
+
+    // session name -> window index -> (window name, panes)
+    let mut windows: HashMap<&str, BTreeMap<usize, (&str, Vec<serde_json::Value>)>> = HashMap::new();
+    for (pane, json) in panes.iter().zip(format_pane_list(&panes)) {
+        windows
+            .entry(pane.session_name.as_str())
+            .or_default()
+            .entry(pane.window_index)
+            .or_insert_with(|| (pane.window_name.as_str(), Vec::new()))
+            .1
+            .push(json);
+    }
+
+    let tree: Vec<serde_json::Value> = sessions
+        .iter()
+        .map(|s| {
+            let session_windows: Vec<serde_json::Value> = windows
+                .remove(s.name.as_str())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(index, (name, panes))| {
+                    serde_json::json!({
+                        "index": index,
+                        "name": name,
+                        "panes": panes,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "id": s.id.to_string(),
+                "name": s.name,
+                "attached_clients": s.attached_clients,
+                "windows": session_windows,
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&tree).map_err(|e| McpError::Internal(e.to_string()))
+}
+
+fn daemon_error(uri: &ResourceUri, msg: ServerMessage) -> McpError {
+    match msg {
+        ServerMessage::Error {
+            code: ErrorCode::PaneNotFound | ErrorCode::SessionNotFound,
+            ..
+        } => McpError::ResourceNotFound(uri.to_string()),
+        msg => unexpected(msg),
+    }
+}
+
+fn unexpected(msg: ServerMessage) -> McpError {
+    match msg {
+        ServerMessage::Error { code, message, .. } => {
+            McpError::DaemonError(format!("{:?}: {}", code, message))
+        }
+        msg => McpError::UnexpectedResponse(format!("{:?}", msg)),
+    }
+}
+
+/// Resources whose contents may have changed because of a daemon event
+pub fn affected_resources(msg: &ServerMessage) -> Vec<ResourceUri> {
+    match msg {
+        ServerMessage::Sequenced { inner, .. } => affected_resources(inner),
+        ServerMessage::Output { pane_id, .. } => {
+            vec![ResourceUri::PaneScreen(*pane_id), ResourceUri::PaneScrollback(*pane_id)]
+        }
+        ServerMessage::ClaudeStateChanged { pane_id, .. } => {
+            vec![ResourceUri::PaneScreen(*pane_id), ResourceUri::Sessions]
+        }
+        ServerMessage::PaneClosed { pane_id, .. } => vec![
+            ResourceUri::PaneScreen(*pane_id),
+            ResourceUri::PaneScrollback(*pane_id),
+            ResourceUri::Sessions,
+        ],
+        ServerMessage::PaneResized { pane_id, .. } => {
+            vec![ResourceUri::PaneScreen(*pane_id), ResourceUri::Sessions]
+        }
+        ServerMessage::SessionCreated { .. }
+        | ServerMessage::WindowCreated { .. }
+        | ServerMessage::PaneCreated { .. }
+        | ServerMessage::PaneStateChanged { .. }
+        | ServerMessage::WindowClosed { .. }
+        | ServerMessage::SessionEnded { .. }
+        | ServerMessage::SessionsChanged { .. }
+        | ServerMessage::SessionRenamed { .. }
+        | ServerMessage::PaneRenamed { .. }
+        | ServerMessage::WindowRenamed { .. }
+        | ServerMessage::PaneSplit { .. }
+        | ServerMessage::LayoutCreated { .. }
+        | ServerMessage::SessionDestroyed { .. } => vec![ResourceUri::Sessions],
+        _ => Vec::new(),
+    }
+}
+
+/// Build a `notifications/resources/updated` notification
+pub fn updated_notification(uri: &str) -> JsonRpcNotification {
+    JsonRpcNotification::new(UPDATED_METHOD, serde_json::json!({ "uri": uri }))
+}
+
+/// Resource subscriptions for one MCP session
+///
+/// Cheap to clone; clones share state so a transport can collect updates
+/// while the bridge handles `resources/subscribe` requests.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceSubscriptions {
+    inner: Arc<Mutex<SubscriptionState>>,
+}
+
+type MailboxStamp = Option<(usize, SystemTime)>;
+
+#[derive(Debug, Default)]
+struct SubscriptionState {
+    uris: BTreeSet<String>,
+    mailboxes: HashMap<String, MailboxStamp>,
+    pending: BTreeSet<String>,
+}
+
+impl ResourceSubscriptions {
+    fn state(&self) -> MutexGuard<'_, SubscriptionState> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Subscribe to updates for a resource
+    pub fn subscribe(&self, uri: &ResourceUri) {
+        let mut state = self.state();
+        if let ResourceUri::Mailbox(name) = uri {
+            state.mailboxes.insert(name.clone(), mail::mailbox_stamp(name));
+        }
+        state.uris.insert(uri.to_string());
+    }
+
+    /// Unsubscribe from a resource, returning whether it was subscribed
+    pub fn unsubscribe(&self, uri: &ResourceUri) -> bool {
+        let mut state = self.state();
+        if let ResourceUri::Mailbox(name) = uri {
+            state.mailboxes.remove(name);
+        }
+        let key = uri.to_string();
+        state.pending.remove(&key);
+        state.uris.remove(&key)
+    }
+
+    /// Whether any resource is subscribed
+    pub fn is_empty(&self) -> bool {
+        self.state().uris.is_empty()
+    }
+
+    /// Record subscribed resources touched by a daemon event
+    pub fn note_event(&self, msg: &ServerMessage) {
+        let affected = affected_resources(msg);
+        if affected.is_empty() {
+            return;
+        }
+        let mut state = self.state();
+        for uri in affected {
+            let key = uri.to_string();
+            if state.uris.contains(&key) {
+                state.pending.insert(key);
+            }
+        }
+    }
+
+    /// Drain URIs that changed since the last call
+    ///
+    /// Mailboxes are checked here since mail is delivered through the
+    /// filesystem rather than as daemon events.
+    pub fn take_updates(&self) -> Vec<String> {
+        let mut state = self.state();
+        let SubscriptionState {
+            mailboxes, pending, ..
+        } = &mut *state;
+
+        for (name, stamp) in mailboxes.iter_mut() {
+            let current = mail::mailbox_stamp(name);
+            if current != *stamp {
+                *stamp = current;
+                pending.insert(ResourceUri::Mailbox(name.clone()).to_string());
+            }
+        }
+
+        std::mem::take(pending).into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::PaneState;
+
+    #[test]
+    fn test_parse_round_trip() {
+        let pane_id = Uuid::new_v4();
+        for uri in [
+            ResourceUri::Sessions,
+            ResourceUri::PaneScreen(pane_id),
+            ResourceUri::PaneScrollback(pane_id),
+            ResourceUri::Mailbox("orchestrator".into()),
+        ] {
+            assert_eq!(ResourceUri::parse(&uri.to_string()).unwrap(), uri);
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_uris() {
+        for uri in [
+            "fugue://nope",
+            "fugue://pane/not-a-uuid/screen",
+            "fugue://pane/00000000-0000-0000-0000-000000000000/other",
+            "fugue://mail/..",
+            "fugue://mail/a/b",
+            "file:///etc/passwd",
+        ] {
+            assert!(
+                matches!(ResourceUri::parse(uri), Err(McpError::ResourceNotFound(_))),
+                "{} should not parse",
+                uri
+            );
+        }
+    }
+
+    #[test]
+    fn test_affected_resources() {
+        let pane_id = Uuid::new_v4();
+        let output = ServerMessage::Output {
+            pane_id,
+            data: b"hi".to_vec(),
+        };
+        assert_eq!(
+            affected_resources(&output),
+            vec![ResourceUri::PaneScreen(pane_id), ResourceUri::PaneScrollback(pane_id)]
+        );
+
+        let sequenced = ServerMessage::Sequenced {
+            seq: 1,
+            inner: Box::new(ServerMessage::PaneStateChanged {
+                pane_id,
+                state: PaneState::Normal,
+            }),
+        };
+        assert_eq!(affected_resources(&sequenced), vec![ResourceUri::Sessions]);
+        assert!(affected_resources(&ServerMessage::Pong).is_empty());
+    }
+
+    #[test]
+    fn test_subscriptions_coalesce_updates() {
+        let pane_id = Uuid::new_v4();
+        let other_pane = Uuid::new_v4();
+        let subs = ResourceSubscriptions::default();
+        assert!(subs.is_empty());
+
+        let screen = ResourceUri::PaneScreen(pane_id);
+        subs.subscribe(&screen);
+        assert!(!subs.is_empty());
+
+        for _ in 0..5 {
+            subs.note_event(&ServerMessage::Output {
+                pane_id,
+                data: b"x".to_vec(),
+            });
+        }
+        subs.note_event(&ServerMessage::Output {
+            pane_id: other_pane,
+            data: b"x".to_vec(),
+        });
+
+        assert_eq!(subs.take_updates(), vec![screen.to_string()]);
+        assert!(subs.take_updates().is_empty());
+
+        assert!(subs.unsubscribe(&screen));
+        subs.note_event(&ServerMessage::Output {
+            pane_id,
+            data: b"x".to_vec(),
+        });
+        assert!(subs.take_updates().is_empty());
+    }
+
+    #[test]
+    fn test_updated_notification() {
+        let notification = updated_notification(SESSIONS_URI);
+        assert_eq!(notification.method, UPDATED_METHOD);
+        assert_eq!(notification.params["uri"], SESSIONS_URI);
+    }
+}
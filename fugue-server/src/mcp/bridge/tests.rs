@@ -363,4 +363,590 @@ mod tests {
         assert!(result.get("window_id").is_some());
         assert_eq!(result["status"], "created");
     }
-}
\ No newline at end of file
+
+    // ==================== Single MCP Code Path ====================
+    //
+    // The bridge is the only MCP implementation, so these tests drive the real
+    // JSON-RPC and tool dispatch against an in-process fake daemon.
+
+    use std::collections::{HashMap, HashSet};
+
+    use fugue_protocol::{ClientMessage, SessionInfo};
+
+    use crate::mcp::bridge::McpBridge;
+    use crate::mcp::protocol::JsonRpcRequest;
+    use crate::mcp::tools::get_tool_definitions;
+
+    fn session(name: &str, tags: &[&str]) -> SessionInfo {
+        SessionInfo {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: 0,
+            window_count: 1,
+            attached_clients: 0,
+            worktree: None,
+            tags: tags.iter().map(|t| t.to_string()).collect::<HashSet<_>>(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn request(id: u64, method: &str, params: serde_json::Value) -> JsonRpcRequest {
+        JsonRpcRequest {
+            jsonrpc: "2.0".into(),
+            id: serde_json::json!(id),
+            method: method.into(),
+            params,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_defined_tool_is_dispatched_by_bridge() {
+        // Any daemon round-trip fails fast with NotConnected; only unknown names
+        // should produce UnknownTool.
+        for tool in get_tool_definitions() {
+            let mut bridge = McpBridge::new();
+            let result = tokio::time::timeout(
+                std::time::Duration::from_secs(5),
+                bridge.dispatch_tool(&tool.name, &serde_json::json!({})),
+            )
+            .await
+            .unwrap_or_else(|_| panic!("{} did not return", tool.name));
+            assert!(
+                !matches!(result, Err(McpError::UnknownTool(_))),
+                "tool {} is advertised but not dispatched",
+                tool.name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_unknown_tool() {
+        let mut bridge = McpBridge::new();
+        let result = bridge.dispatch_tool("unknown_tool", &serde_json::json!({})).await;
+        assert!(matches!(result, Err(McpError::UnknownTool(_))));
+    }
+
+    #[tokio::test]
+    async fn test_handle_unknown_method() {
+        let mut bridge = McpBridge::new();
+        let response = bridge
+            .handle_request(request(1, "unknown/method", serde_json::json!({})))
+            .await;
+        assert!(response.unwrap().error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_full_request_cycle_through_daemon() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| match msg {
+            ClientMessage::ListAllPanes { .. } => ServerMessage::AllPanesList { panes: vec![] },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let init = bridge
+            .handle_request(request(1, "initialize", serde_json::json!({})))
+            .await
+            .unwrap();
+        assert!(init.error.is_none());
+        assert!(bridge.initialized);
+
+        let list = bridge
+            .handle_request(request(2, "tools/list", serde_json::json!({})))
+            .await
+            .unwrap();
+        let tools = list.result.unwrap()["tools"].as_array().unwrap().len();
+        assert_eq!(tools, get_tool_definitions().len());
+
+        let call = bridge
+            .handle_request(request(
+                3,
+                "tools/call",
+                serde_json::json!({"name": "fugue_list_panes", "arguments": {}}),
+            ))
+            .await
+            .unwrap();
+        assert!(call.error.is_none());
+        assert!(call.result.unwrap()["isError"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_server_status_proxied_to_daemon() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| match msg {
+            ClientMessage::GetServerStatus => ServerMessage::ServerStatus {
+                commit_seq: 42,
+                client_count: 2,
+                session_count: 3,
+                replay_range: (10, 42),
+                wal_healthy: true,
+                checkpoint_healthy: false,
+                human_control_active: false,
+            },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let result = bridge
+            .dispatch_tool("fugue_server_status", &serde_json::json!({}))
+            .await
+            .unwrap();
+        let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["commit_seq"], 42);
+        assert_eq!(json["session_count"], 3);
+        assert_eq!(json["persistence"]["checkpoint_healthy"], false);
+    }
+
+    #[tokio::test]
+    async fn test_select_worker_filters_by_tags_and_pool() {
+        let sessions = vec![
+            session("orchestrator", &["orchestrator"]),
+            session("worker-a", &["worker"]),
+            session("worker-b", &["worker", "gpu"]),
+        ];
+        let mut bridge = McpBridge::with_fake_daemon(move |_| ServerMessage::SessionList {
+            sessions: sessions.clone(),
+        })
+        .await;
+
+        let result = bridge
+            .dispatch_tool(
+                "fugue_select_worker",
+                &serde_json::json!({"criteria": {"tags": ["worker", "gpu"]}}),
+            )
+            .await
+            .unwrap();
+        let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["name"], "worker-b");
+
+        let result = bridge
+            .dispatch_tool(
+                "fugue_select_worker",
+                &serde_json::json!({"pool": ["orchestrator"], "criteria": {"tags": ["worker"]}}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_select_worker_rejects_unknown_strategy() {
+        let mut bridge = McpBridge::new();
+        let result = bridge
+            .dispatch_tool("fugue_select_worker", &serde_json::json!({"strategy": "fastest"}))
+            .await;
+        assert!(matches!(result, Err(McpError::InvalidParams(_))));
+    }
+
+    // ==================== MCP Resources ====================
+
+    use fugue_protocol::PaneListEntry;
+
+    use crate::mcp::bridge::resources::ResourceUri;
+    use crate::mcp::protocol::JsonRpcError;
+
+    fn pane_entry(id: Uuid, session_name: &str, window_index: usize) -> PaneListEntry {
+        PaneListEntry {
+            id,
+            session_name: session_name.to_string(),
+            window_index,
+            window_name: format!("win{}", window_index),
+            pane_index: 0,
+            cols: 80,
+            rows: 24,
+            name: None,
+            title: None,
+            cwd: None,
+            state: PaneState::Normal,
+            is_claude: false,
+            claude_state: None,
+            is_focused: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initialize_advertises_resource_subscriptions() {
+        let mut bridge = McpBridge::new();
+        let response = bridge
+            .handle_request(request(1, "initialize", serde_json::json!({})))
+            .await
+            .unwrap();
+        let result = response.result.unwrap();
+        assert_eq!(result["capabilities"]["resources"]["subscribe"], true);
+    }
+
+    #[tokio::test]
+    async fn test_resource_templates_list() {
+        let mut bridge = McpBridge::new();
+        let response = bridge
+            .handle_request(request(1, "resources/templates/list", serde_json::json!({})))
+            .await
+            .unwrap();
+        let templates = response.result.unwrap()["resourceTemplates"].clone();
+        let uris: Vec<&str> = templates
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["uriTemplate"].as_str().unwrap())
+            .collect();
+        assert!(uris.contains(&"fugue://pane/{pane_id}/screen"));
+        assert!(uris.contains(&"fugue://mail/{mailbox}"));
+    }
+
+    #[tokio::test]
+    async fn test_resources_list_includes_pane_screens() {
+        let pane_id = Uuid::new_v4();
+        let mut bridge = McpBridge::with_fake_daemon(move |msg| match msg {
+            ClientMessage::ListAllPanes { .. } => ServerMessage::AllPanesList {
+                panes: vec![pane_entry(pane_id, "dev", 0)],
+            },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let response = bridge
+            .handle_request(request(1, "resources/list", serde_json::json!({})))
+            .await
+            .unwrap();
+        let resources = response.result.unwrap()["resources"].clone();
+        let uris: Vec<&str> = resources
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            uris,
+            vec!["fugue://sessions".to_string(), format!("fugue://pane/{}/screen", pane_id)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_pane_screen_resource() {
+        let pane_id = Uuid::new_v4();
+        let mut bridge = McpBridge::with_fake_daemon(move |msg| match msg {
+            ClientMessage::GetPaneStatus { pane_id } => ServerMessage::PaneStatus {
+                pane_id,
+                session_name: "dev".into(),
+                window_name: "main".into(),
+                window_index: 0,
+                pane_index: 0,
+                cols: 80,
+                rows: 2,
+                title: None,
+                cwd: None,
+                state: PaneState::Normal,
+                has_pty: true,
+                is_awaiting_input: false,
+                is_awaiting_confirmation: false,
+            },
+            ClientMessage::ReadPane { pane_id, lines } => {
+                assert_eq!(lines, 2, "screen reads one page of rows");
+                ServerMessage::PaneContent {
+                    pane_id,
+                    content: "\x1b[31mred\x1b[0m\n$ ".into(),
+                }
+            }
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let uri = format!("fugue://pane/{}/screen", pane_id);
+        let response = bridge
+            .handle_request(request(1, "resources/read", serde_json::json!({"uri": uri})))
+            .await
+            .unwrap();
+        let contents = &response.result.unwrap()["contents"][0];
+        assert_eq!(contents["uri"], uri);
+        assert_eq!(contents["mimeType"], "text/plain");
+        assert_eq!(contents["text"], "red\n$ ");
+    }
+
+    #[tokio::test]
+    async fn test_read_missing_pane_is_resource_not_found() {
+        let mut bridge = McpBridge::with_fake_daemon(|_| ServerMessage::Error {
+            code: ErrorCode::PaneNotFound,
+            message: "no such pane".into(),
+            details: None,
+        })
+        .await;
+
+        let uri = format!("fugue://pane/{}/scrollback", Uuid::new_v4());
+        let response = bridge
+            .handle_request(request(1, "resources/read", serde_json::json!({"uri": uri})))
+            .await
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, JsonRpcError::RESOURCE_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_read_session_tree_resource() {
+        let dev = session("dev", &[]);
+        let idle = session("idle", &[]);
+        let (pane_a, pane_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let sessions = vec![dev.clone(), idle.clone()];
+        let mut bridge = McpBridge::with_fake_daemon(move |msg| match msg {
+            ClientMessage::ListSessions => ServerMessage::SessionList {
+                sessions: sessions.clone(),
+            },
+            ClientMessage::ListAllPanes { .. } => ServerMessage::AllPanesList {
+                panes: vec![pane_entry(pane_a, "dev", 0), pane_entry(pane_b, "dev", 1)],
+            },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let response = bridge
+            .handle_request(request(1, "resources/read", serde_json::json!({"uri": "fugue://sessions"})))
+            .await
+            .unwrap();
+        let text = response.result.unwrap()["contents"][0]["text"].as_str().unwrap().to_string();
+        let tree: serde_json::Value = serde_json::from_str(&text).unwrap();
+
+        assert_eq!(tree[0]["id"], dev.id.to_string());
+        assert_eq!(tree[0]["windows"].as_array().unwrap().len(), 2);
+        assert_eq!(tree[0]["windows"][1]["panes"][0]["id"], pane_b.to_string());
+        assert_eq!(tree[1]["name"], "idle");
+        assert!(tree[1]["windows"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_resource_subscribe_and_unsubscribe() {
+        let mut bridge = McpBridge::new();
+        let subscriptions = bridge.resource_subscriptions();
+        let pane_id = Uuid::new_v4();
+        let uri = ResourceUri::PaneScreen(pane_id).to_string();
+
+        let response = bridge
+            .handle_request(request(1, "resources/subscribe", serde_json::json!({"uri": uri})))
+            .await
+            .unwrap();
+        assert!(response.error.is_none());
+
+        subscriptions.note_event(&ServerMessage::ClaudeStateChanged {
+            pane_id,
+            state: ClaudeState::default(),
+        });
+        assert_eq!(subscriptions.take_updates(), vec![uri.clone()]);
+
+        bridge
+            .handle_request(request(2, "resources/unsubscribe", serde_json::json!({"uri": uri})))
+            .await
+            .unwrap();
+        assert!(subscriptions.is_empty());
+
+        let response = bridge
+            .handle_request(request(3, "resources/subscribe", serde_json::json!({"uri": "fugue://bogus"})))
+            .await
+            .unwrap();
+        assert_eq!(response.error.unwrap().code, JsonRpcError::RESOURCE_NOT_FOUND);
+    }
+
+    // ==================== Server-side Expect ====================
+
+    use fugue_protocol::{AgentActivity, ExpectCondition, ExpectOutcome};
+
+    use crate::mcp::bridge::orchestration::{expect_condition, parse_agent_activity};
+
+    #[test]
+    fn test_expect_condition_from_arguments() {
+        let condition = expect_condition(&serde_json::json!({
+            "pattern": "a",
+            "patterns": ["b", "c"],
+        }))
+        .unwrap();
+        assert_eq!(
+            condition,
+            ExpectCondition::Output {
+                patterns: vec!["a".into(), "b".into(), "c".into()]
+            }
+        );
+
+        let condition = expect_condition(&serde_json::json!({"agent_state": ["idle", "ToolUse"]})).unwrap();
+        assert_eq!(
+            condition,
+            ExpectCondition::AgentActivity {
+                activities: vec![AgentActivity::Idle, AgentActivity::ToolUse]
+            }
+        );
+
+        assert!(expect_condition(&serde_json::json!({})).is_err());
+        assert!(expect_condition(&serde_json::json!({"pattern": "a", "agent_state": "idle"})).is_err());
+        assert!(expect_condition(&serde_json::json!({"patterns": [1]})).is_err());
+    }
+
+    #[test]
+    fn test_parse_agent_activity() {
+        assert_eq!(parse_agent_activity("awaiting_confirmation"), AgentActivity::AwaitingConfirmation);
+        assert_eq!(parse_agent_activity("Processing"), AgentActivity::Processing);
+        assert_eq!(parse_agent_activity("awaiting_permission"), AgentActivity::AwaitingPermission);
+        assert_eq!(parse_agent_activity("reviewing"), AgentActivity::Custom("reviewing".into()));
+    }
+
+    #[tokio::test]
+    async fn test_expect_sends_single_daemon_request() {
+        let pane_id = Uuid::new_v4();
+        let mut bridge = McpBridge::with_fake_daemon(move |msg| match msg {
+            ClientMessage::Expect {
+                pane_id: id,
+                condition: ExpectCondition::Output { patterns },
+                timeout_ms: 5000,
+                lines: 0,
+            } => {
+                assert_eq!(patterns, vec!["error".to_string(), r"(?P<n>\d+) passed".to_string()]);
+                let mut named_captures = HashMap::new();
+                named_captures.insert("n".to_string(), "7".to_string());
+                ServerMessage::ExpectResult {
+                    pane_id: id,
+                    outcome: ExpectOutcome::Matched {
+                        pattern_index: 1,
+                        text: "7 passed".into(),
+                        line: "ok: 7 passed".into(),
+                        captures: vec![Some("7 passed".into()), Some("7".into())],
+                        named_captures,
+                    },
+                    elapsed_ms: 12,
+                }
+            }
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let result = bridge
+            .dispatch_tool(
+                "fugue_expect",
+                &serde_json::json!({
+                    "pane_id": pane_id.to_string(),
+                    "patterns": ["error", r"(?P<n>\d+) passed"],
+                    "timeout_ms": 5000,
+                    "lines": 0,
+                }),
+            )
+            .await
+            .unwrap();
+        let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["status"], "matched");
+        assert_eq!(json["pattern_index"], 1);
+        assert_eq!(json["pattern"], r"(?P<n>\d+) passed");
+        assert_eq!(json["captures"][1], "7");
+        assert_eq!(json["named_captures"]["n"], "7");
+        assert_eq!(json["duration_ms"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_expect_reports_timeout() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| match msg {
+            ClientMessage::Expect { pane_id, .. } => ServerMessage::ExpectResult {
+                pane_id,
+                outcome: ExpectOutcome::Timeout,
+                elapsed_ms: 100,
+            },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let result = bridge
+            .dispatch_tool(
+                "fugue_expect",
+                &serde_json::json!({
+                    "pane_id": Uuid::new_v4().to_string(),
+                    "agent_state": "idle",
+                    "timeout_ms": 100,
+                }),
+            )
+            .await
+            .unwrap();
+        let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["status"], "timeout");
+    }
+
+    #[tokio::test]
+    async fn test_expect_rejects_invalid_regex() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| panic!("unexpected daemon request: {:?}", msg)).await;
+
+        let result = bridge
+            .dispatch_tool(
+                "fugue_expect",
+                &serde_json::json!({"pane_id": Uuid::new_v4().to_string(), "pattern": "("}),
+            )
+            .await;
+        assert!(matches!(result, Err(McpError::InvalidParams(_))));
+    }
+
+    // ==================== Permission Prompts ====================
+
+    use fugue_protocol::PermissionDecision;
+
+    #[tokio::test]
+    async fn test_approve_and_deny_send_decision() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| match msg {
+            ClientMessage::ResolvePermission { pane_id, decision } => ServerMessage::PermissionResolved {
+                pane_id,
+                decision,
+                option: match decision {
+                    PermissionDecision::Approve => "Yes".into(),
+                    PermissionDecision::ApproveAlways => "Yes, and don't ask again".into(),
+                    PermissionDecision::Deny => "No".into(),
+                },
+            },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let pane_id = Uuid::new_v4().to_string();
+        for (tool, args, expected) in [
+            ("fugue_approve", serde_json::json!({"pane_id": pane_id}), ("Approve", "Yes")),
+            (
+                "fugue_approve",
+                serde_json::json!({"pane_id": pane_id, "always": true}),
+                ("ApproveAlways", "Yes, and don't ask again"),
+            ),
+            ("fugue_deny", serde_json::json!({"pane_id": pane_id}), ("Deny", "No")),
+        ] {
+            let result = bridge.dispatch_tool(tool, &args).await.unwrap();
+            let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+            let json: serde_json::Value = serde_json::from_str(text).unwrap();
+            assert_eq!(json["decision"], expected.0);
+            assert_eq!(json["option"], expected.1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_approve_reports_daemon_error() {
+        let mut bridge = McpBridge::with_fake_daemon(|_| ServerMessage::Error {
+            code: ErrorCode::NotAwaitingInput,
+            message: "Pane is not showing a permission prompt".into(),
+            details: None,
+        })
+        .await;
+
+        let result = bridge
+            .dispatch_tool("fugue_approve", &serde_json::json!({"pane_id": Uuid::new_v4().to_string()}))
+            .await
+            .unwrap();
+        assert_eq!(result.is_error, Some(true));
+    }
+
+    // ==================== Usage Budgets ====================
+
+    #[tokio::test]
+    async fn test_resume_pane() {
+        let mut bridge = McpBridge::with_fake_daemon(|msg| match msg {
+            ClientMessage::ResumePane { pane_id } => ServerMessage::PaneResumed { pane_id },
+            other => panic!("unexpected daemon request: {:?}", other),
+        })
+        .await;
+
+        let pane_id = Uuid::new_v4().to_string();
+        let result = bridge
+            .dispatch_tool("fugue_resume_pane", &serde_json::json!({"pane_id": pane_id}))
+            .await
+            .unwrap();
+        let crate::mcp::protocol::ToolContent::Text { text } = &result.content[0];
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["pane_id"], pane_id);
+        assert_eq!(json["status"], "resumed");
+    }
+}
@@ -4,7 +4,7 @@ use std::io;
 
 use super::protocol::JsonRpcError;
 
-/// MCP server errors
+/// MCP bridge errors
 #[derive(Debug, thiserror::Error)]
 pub enum McpError {
     /// IO error (stdin/stdout)
@@ -27,25 +27,13 @@ pub enum McpError {
     #[error("Unknown tool: {0}")]
     UnknownTool(String),
 
-    /// Pane not found
-    #[error("Pane not found: {0}")]
-    PaneNotFound(String),
-
-    /// Window not found
-    #[error("Window not found: {0}")]
-    WindowNotFound(String),
-
-    /// Session not found
-    #[error("Session not found: {0}")]
-    SessionNotFound(String),
-
     /// Internal error
     #[error("Internal error: {0}")]
     Internal(String),
 
-    /// PTY error
-    #[error("PTY error: {0}")]
-    Pty(String),
+    /// Unknown or unreadable resource URI
+    #[error("Resource not found: {0}")]
+    ResourceNotFound(String),
 
     // ==================== Bridge-specific errors ====================
 
@@ -102,15 +90,6 @@ impl From<McpError> for JsonRpcError {
             McpError::UnknownTool(name) => {
                 JsonRpcError::new(JsonRpcError::METHOD_NOT_FOUND, format!("Unknown tool: {}", name))
             }
-            McpError::PaneNotFound(id) => {
-                JsonRpcError::new(JsonRpcError::INVALID_PARAMS, format!("Pane not found: {}", id))
-            }
-            McpError::WindowNotFound(id) => {
-                JsonRpcError::new(JsonRpcError::INVALID_PARAMS, format!("Window not found: {}", id))
-            }
-            McpError::SessionNotFound(name) => {
-                JsonRpcError::new(JsonRpcError::INVALID_PARAMS, format!("Session not found: {}", name))
-            }
             McpError::Io(err) => {
                 JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, format!("IO error: {}", err))
             }
@@ -120,8 +99,8 @@ impl From<McpError> for JsonRpcError {
             McpError::Internal(msg) => {
                 JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, msg)
             }
-            McpError::Pty(msg) => {
-                JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, format!("PTY error: {}", msg))
+            McpError::ResourceNotFound(uri) => {
+                JsonRpcError::new(JsonRpcError::RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri))
             }
             McpError::DaemonNotRunning => {
                 JsonRpcError::new(JsonRpcError::INTERNAL_ERROR, "fugue daemon is not running".to_string())
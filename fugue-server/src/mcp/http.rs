@@ -0,0 +1,961 @@
+//! Streamable HTTP transport for MCP
+//!
+//! Serves the MCP streamable HTTP protocol on a single `/mcp` endpoint so
+//! many agents can share one daemon-hosted server instead of each spawning
+//! a stdio `mcp-bridge`:
+//!
+//! - `POST /mcp` carries one JSON-RPC message or a batch. An `initialize`
+//!   request without an `Mcp-Session-Id` header starts a new session and the
+//!   id is returned in that header; every later request must echo it.
+//! - `GET /mcp` opens a Server-Sent Events stream of
+//!   `notifications/fugue/event` notifications (pane state changes, Claude
+//!   activity, session/window/pane lifecycle) from every session, plus
+//!   `notifications/resources/updated` for the session's resource
+//!   subscriptions.
+//! - `DELETE /mcp` ends the session.
+//!
+//! Each HTTP session owns an [`McpBridge`] connected to the daemon socket,
+//! so tool calls take exactly the same path as the stdio bridge. Events come
+//! from a single registry client subscribed with `subscribe_events`.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use futures::future::BoxFuture;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited, StreamBody};
+use hyper::body::{Body, Bytes, Frame};
+use hyper::header::{HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE, ORIGIN, WWW_AUTHENTICATE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{HeaderMap, Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc, Mutex};
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{ClientType, ServerMessage};
+use fugue_utils::tls::tokens_match;
+
+use crate::config::McpHttpConfig;
+use crate::mcp::bridge::resources::{self, ResourceSubscriptions};
+use crate::mcp::bridge::{invalid_version_response, McpBridge};
+use crate::mcp::error::McpError;
+use crate::mcp::protocol::{JsonRpcError, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse};
+use crate::SharedState;
+
+/// Path of the MCP endpoint
+pub const ENDPOINT: &str = "/mcp";
+
+/// Header carrying the MCP session id
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Notification method used for daemon events on the SSE stream
+pub const EVENT_METHOD: &str = "notifications/fugue/event";
+
+/// Largest request body accepted on POST
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+/// Interval between SSE comment frames that keep idle streams open
+const SSE_KEEPALIVE: Duration = Duration::from_secs(15);
+
+/// Events buffered per SSE stream before a slow reader starts lagging
+const EVENT_BUFFER: usize = 256;
+
+/// How often idle sessions are reaped
+const REAP_INTERVAL: Duration = Duration::from_secs(60);
+
+type HttpBody = BoxBody<Bytes, Infallible>;
+
+/// Creates a connected bridge for a new HTTP session
+type BridgeFactory = Box<dyn Fn() -> BoxFuture<'static, Result<McpBridge, McpError>> + Send + Sync>;
+
+/// One MCP client session
+struct HttpSession {
+    bridge: Mutex<McpBridge>,
+    subscriptions: ResourceSubscriptions,
+    last_seen: std::sync::Mutex<Instant>,
+}
+
+impl HttpSession {
+    fn new(bridge: McpBridge) -> Self {
+        Self {
+            subscriptions: bridge.resource_subscriptions(),
+            bridge: Mutex::new(bridge),
+            last_seen: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    fn touch(&self) {
+        if let Ok(mut last_seen) = self.last_seen.lock() {
+            *last_seen = Instant::now();
+        }
+    }
+
+    fn idle_for(&self) -> Duration {
+        self.last_seen
+            .lock()
+            .map(|last_seen| last_seen.elapsed())
+            .unwrap_or_default()
+    }
+}
+
+/// State shared by all HTTP connections
+struct McpHttpState {
+    config: McpHttpConfig,
+    sessions: DashMap<String, Arc<HttpSession>>,
+    events: broadcast::Sender<ServerMessage>,
+    new_bridge: BridgeFactory,
+}
+
+impl McpHttpState {
+    fn new(config: McpHttpConfig, events: broadcast::Sender<ServerMessage>, new_bridge: BridgeFactory) -> Self {
+        Self {
+            config,
+            sessions: DashMap::new(),
+            events,
+            new_bridge,
+        }
+    }
+
+    /// Drop sessions that have been idle longer than the configured timeout
+    fn reap_idle(&self) {
+        let timeout = Duration::from_secs(self.config.session_idle_timeout_secs);
+        self.sessions.retain(|id, session| {
+            let keep = session.idle_for() < timeout;
+            if !keep {
+                info!(session = %id, "Reaping idle MCP HTTP session");
+            }
+            keep
+        });
+    }
+}
+
+/// Run the MCP HTTP server
+///
+/// Listens on `config.listen_addr` until the daemon shuts down.
+pub async fn run_mcp_http_server(config: McpHttpConfig, state: Arc<SharedState>) {
+    let socket_addr: SocketAddr = match config.listen_addr.parse() {
+        Ok(a) => a,
+        Err(e) => {
+            error!("Invalid MCP HTTP listen address '{}': {}", config.listen_addr, e);
+            return;
+        }
+    };
+
+    let listener = match TcpListener::bind(socket_addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            error!("Failed to bind MCP HTTP server to {}: {}", socket_addr, e);
+            return;
+        }
+    };
+
+    if !socket_addr.ip().is_loopback() && config.auth_token.is_none() {
+        warn!(
+            "MCP HTTP server on {} is reachable from the network without an auth_token",
+            socket_addr
+        );
+    }
+
+    info!("MCP HTTP server listening on http://{}{}", socket_addr, ENDPOINT);
+
+    // Register an in-process client that receives events from every session
+    let (watcher_tx, mut watcher_rx) = mpsc::channel(EVENT_BUFFER);
+    let watcher_id = state.registry.register_client(watcher_tx);
+    state.registry.set_client_type(watcher_id, ClientType::Mcp);
+    state.registry.subscribe_events(watcher_id, true);
+
+    let (events, _) = broadcast::channel(EVENT_BUFFER);
+    let events_for_forwarder = events.clone();
+    let forwarder = tokio::spawn(async move {
+        while let Some(msg) = watcher_rx.recv().await {
+            // No open SSE streams is not an error
+            let _ = events_for_forwarder.send(strip_output(msg));
+        }
+    });
+
+    let http_state = Arc::new(McpHttpState::new(
+        config,
+        events,
+        Box::new(|| {
+            Box::pin(async {
+                let mut bridge = McpBridge::new();
+                bridge.connect().await?;
+                Ok(bridge)
+            })
+        }),
+    ));
+
+    let mut shutdown_rx = state.subscribe_shutdown();
+    let mut reap_interval = tokio::time::interval(REAP_INTERVAL);
+
+    loop {
+        tokio::select! {
+            accept_result = listener.accept() => {
+                let (stream, remote_addr) = match accept_result {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        warn!("MCP HTTP accept error: {}", e);
+                        continue;
+                    }
+                };
+
+                let io = TokioIo::new(stream);
+                let state_clone = Arc::clone(&http_state);
+
+                tokio::spawn(async move {
+                    let service = service_fn(move |req| {
+                        let state = Arc::clone(&state_clone);
+                        async move { handle_request(req, state).await }
+                    });
+
+                    if let Err(e) = http1::Builder::new()
+                        .serve_connection(io, service)
+                        .await
+                    {
+                        // Connection errors are expected when clients disconnect
+                        if !e.is_incomplete_message() {
+                            warn!("MCP HTTP connection error from {}: {}", remote_addr, e);
+                        }
+                    }
+                });
+            }
+
+            _ = reap_interval.tick() => {
+                http_state.reap_idle();
+            }
+
+            _ = shutdown_rx.recv() => {
+                info!("MCP HTTP server shutting down");
+                break;
+            }
+        }
+    }
+
+    http_state.sessions.clear();
+    forwarder.abort();
+    state.registry.unregister_client(watcher_id);
+}
+
+/// Handle an HTTP request
+async fn handle_request<B>(req: Request<B>, state: Arc<McpHttpState>) -> Result<Response<HttpBody>, Infallible>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    if req.uri().path() != ENDPOINT {
+        return Ok(status_response(StatusCode::NOT_FOUND));
+    }
+
+    if !origin_allowed(req.headers(), &state.config.allowed_origins) {
+        warn!("Rejected MCP HTTP request with disallowed Origin");
+        return Ok(status_response(StatusCode::FORBIDDEN));
+    }
+
+    if let Some(ref expected) = state.config.auth_token {
+        if !bearer_matches(req.headers(), expected) {
+            let mut response = status_response(StatusCode::UNAUTHORIZED);
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+            return Ok(response);
+        }
+    }
+
+    let response = match *req.method() {
+        Method::POST => handle_post(req, &state).await,
+        Method::GET => handle_get(req.headers(), &state),
+        Method::DELETE => handle_delete(req.headers(), &state),
+        _ => status_response(StatusCode::METHOD_NOT_ALLOWED),
+    };
+    Ok(response)
+}
+
+/// Handle a POST carrying one JSON-RPC message or a batch
+async fn handle_post<B>(req: Request<B>, state: &McpHttpState) -> Response<HttpBody>
+where
+    B: Body<Data = Bytes>,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let session_id = header_str(req.headers(), SESSION_HEADER).map(str::to_string);
+
+    let body = match Limited::new(req.into_body(), MAX_BODY_BYTES).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<LengthLimitError>() => {
+            return status_response(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        Err(e) => {
+            debug!("Failed to read MCP HTTP request body: {}", e);
+            return status_response(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let (messages, is_batch) = match serde_json::from_slice::<serde_json::Value>(&body) {
+        Ok(serde_json::Value::Array(items)) if !items.is_empty() => (items, true),
+        Ok(serde_json::Value::Array(_)) => {
+            return rpc_error_response(
+                StatusCode::BAD_REQUEST,
+                JsonRpcError::new(JsonRpcError::INVALID_REQUEST, "Empty batch"),
+            );
+        }
+        Ok(value) => (vec![value], false),
+        Err(e) => {
+            return rpc_error_response(
+                StatusCode::BAD_REQUEST,
+                JsonRpcError::new(JsonRpcError::PARSE_ERROR, e.to_string()),
+            );
+        }
+    };
+
+    // Responses from the client (no `method`) answer server requests, which
+    // this transport never sends, so they are accepted and dropped
+    let requests: Vec<Result<JsonRpcRequest, (serde_json::Value, serde_json::Error)>> = messages
+        .into_iter()
+        .filter(|msg| msg.get("method").is_some())
+        .map(|msg| {
+            let id = msg.get("id").cloned().unwrap_or(serde_json::Value::Null);
+            serde_json::from_value(msg).map_err(|e| (id, e))
+        })
+        .collect();
+
+    let is_initialize = requests
+        .iter()
+        .any(|r| matches!(r, Ok(req) if req.method == "initialize"));
+
+    let (session_id, session, created) = match session_id {
+        Some(id) => match state.sessions.get(&id) {
+            Some(session) => {
+                let session = Arc::clone(session.value());
+                (id, session, false)
+            }
+            None => return status_response(StatusCode::NOT_FOUND),
+        },
+        None if is_initialize => match (state.new_bridge)().await {
+            Ok(bridge) => {
+                let id = Uuid::new_v4().to_string();
+                let session = Arc::new(HttpSession::new(bridge));
+                state.sessions.insert(id.clone(), Arc::clone(&session));
+                info!(session = %id, "MCP HTTP session started");
+                (id, session, true)
+            }
+            Err(e) => {
+                error!("Failed to start MCP HTTP session: {}", e);
+                return rpc_error_response(StatusCode::SERVICE_UNAVAILABLE, e.into());
+            }
+        },
+        None => return status_response(StatusCode::BAD_REQUEST),
+    };
+
+    session.touch();
+
+    let mut responses = Vec::new();
+    {
+        let mut bridge = session.bridge.lock().await;
+        for request in requests {
+            let request = match request {
+                Ok(request) => request,
+                Err((id, e)) => {
+                    responses.push(JsonRpcResponse::error(
+                        id,
+                        JsonRpcError::new(JsonRpcError::INVALID_REQUEST, e.to_string()),
+                    ));
+                    continue;
+                }
+            };
+            if let Some(response) = invalid_version_response(&request) {
+                responses.push(response);
+                continue;
+            }
+            if let Some(response) = bridge.handle_request(request).await {
+                responses.push(response);
+            }
+        }
+    }
+
+    let mut response = if responses.is_empty() {
+        status_response(StatusCode::ACCEPTED)
+    } else if is_batch {
+        json_response(StatusCode::OK, &responses)
+    } else {
+        json_response(StatusCode::OK, &responses[0])
+    };
+
+    if created {
+        if let Ok(value) = HeaderValue::from_str(&session_id) {
+            response.headers_mut().insert(SESSION_HEADER, value);
+        }
+    }
+    response
+}
+
+/// Handle a GET by opening an SSE event stream for the session
+fn handle_get(headers: &HeaderMap, state: &McpHttpState) -> Response<HttpBody> {
+    let accepts_sse = header_str(headers, ACCEPT.as_str())
+        .map(|accept| accept.contains("text/event-stream"))
+        .unwrap_or(false);
+    if !accepts_sse {
+        return status_response(StatusCode::NOT_ACCEPTABLE);
+    }
+
+    let session = match header_str(headers, SESSION_HEADER) {
+        Some(id) => match state.sessions.get(id) {
+            Some(session) => Arc::clone(session.value()),
+            None => return status_response(StatusCode::NOT_FOUND),
+        },
+        None => return status_response(StatusCode::BAD_REQUEST),
+    };
+    session.touch();
+
+    let sse = SseStream {
+        events: state.events.subscribe(),
+        keepalive: tokio::time::interval_at(
+            tokio::time::Instant::now() + SSE_KEEPALIVE,
+            SSE_KEEPALIVE,
+        ),
+        flush: tokio::time::interval(resources::UPDATE_FLUSH_INTERVAL),
+        session: Arc::downgrade(&session),
+    };
+    let stream = futures::stream::unfold(sse, |mut sse| async move {
+        let frame = sse.next_frame().await?;
+        Some((Ok(Frame::data(frame)), sse))
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "text/event-stream")
+        .header("Cache-Control", "no-cache")
+        .body(StreamBody::new(stream).boxed())
+        .unwrap()
+}
+
+/// State of one open SSE stream
+struct SseStream {
+    events: broadcast::Receiver<ServerMessage>,
+    keepalive: tokio::time::Interval,
+    flush: tokio::time::Interval,
+    session: Weak<HttpSession>,
+}
+
+impl SseStream {
+    /// Wait for the next SSE frame, or `None` once the session or event source is gone
+    async fn next_frame(&mut self) -> Option<Bytes> {
+        loop {
+            // An open stream keeps its session alive; deleting the session ends it
+            let session = self.session.upgrade()?;
+            session.touch();
+
+            tokio::select! {
+                event = self.events.recv() => match event {
+                    Ok(msg) => {
+                        session.subscriptions.note_event(&msg);
+                        if let Some(notification) = event_notification(&msg) {
+                            return sse_frame(&[notification]);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "MCP SSE stream lagging, events dropped");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                _ = self.flush.tick() => {
+                    let updates: Vec<_> = session
+                        .subscriptions
+                        .take_updates()
+                        .iter()
+                        .map(|uri| resources::updated_notification(uri))
+                        .collect();
+                    if !updates.is_empty() {
+                        return sse_frame(&updates);
+                    }
+                }
+                _ = self.keepalive.tick() => {
+                    return Some(Bytes::from_static(b": keep-alive\n\n"));
+                }
+            }
+        }
+    }
+}
+
+/// Encode notifications as SSE `message` events
+fn sse_frame(notifications: &[JsonRpcNotification]) -> Option<Bytes> {
+    let mut frame = String::new();
+    for notification in notifications {
+        let json = serde_json::to_string(notification).ok()?;
+        frame.push_str(&format!("event: message\ndata: {}\n\n", json));
+    }
+    Some(Bytes::from(frame))
+}
+
+/// Handle a DELETE by ending the session
+fn handle_delete(headers: &HeaderMap, state: &McpHttpState) -> Response<HttpBody> {
+    match header_str(headers, SESSION_HEADER) {
+        Some(id) => match state.sessions.remove(id) {
+            Some(_) => {
+                info!(session = %id, "MCP HTTP session ended");
+                status_response(StatusCode::NO_CONTENT)
+            }
+            None => status_response(StatusCode::NOT_FOUND),
+        },
+        None => status_response(StatusCode::BAD_REQUEST),
+    }
+}
+
+/// Drop the payload of `Output` events
+///
+/// Output is only needed to mark pane resources as changed, and every SSE
+/// stream holds its own copy of each buffered event.
+fn strip_output(msg: ServerMessage) -> ServerMessage {
+    match msg {
+        ServerMessage::Output { pane_id, .. } => ServerMessage::Output {
+            pane_id,
+            data: Vec::new(),
+        },
+        ServerMessage::Sequenced { seq, inner } => ServerMessage::Sequenced {
+            seq,
+            inner: Box::new(strip_output(*inner)),
+        },
+        other => other,
+    }
+}
+
+/// Convert a daemon broadcast into an MCP notification
+///
+/// `Output` is never forwarded; agents read pane contents with tools.
+fn event_notification(msg: &ServerMessage) -> Option<JsonRpcNotification> {
+    let msg = match msg {
+        ServerMessage::Sequenced { inner, .. } => inner.as_ref(),
+        other => other,
+    };
+    if matches!(msg, ServerMessage::Output { .. } | ServerMessage::Pong) {
+        return None;
+    }
+
+    let event_type = msg.type_name();
+    // Externally tagged: take the variant body so `data` holds just the fields
+    let data = match serde_json::to_value(msg).ok()? {
+        serde_json::Value::Object(mut map) => map.remove(event_type).unwrap_or(serde_json::Value::Null),
+        _ => serde_json::Value::Null,
+    };
+
+    Some(JsonRpcNotification::new(
+        EVENT_METHOD,
+        serde_json::json!({ "type": event_type, "data": data }),
+    ))
+}
+
+/// Check the `Origin` header against localhost and the configured allow list
+///
+/// Requests without an `Origin` (non-browser clients) are allowed; browsers
+/// always send one, which is what DNS rebinding protection needs.
+fn origin_allowed(headers: &HeaderMap, allowed: &[String]) -> bool {
+    let origin = match header_str(headers, ORIGIN.as_str()) {
+        Some(origin) => origin,
+        None => return true,
+    };
+    if allowed.iter().any(|a| a == origin) {
+        return true;
+    }
+
+    let authority = origin.split_once("://").map(|(_, rest)| rest).unwrap_or(origin);
+    let host = match authority.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(""),
+        None => authority.split(':').next().unwrap_or(""),
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Check for `Authorization: Bearer <expected>`
+fn bearer_matches(headers: &HeaderMap, expected: &str) -> bool {
+    header_str(headers, AUTHORIZATION.as_str())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| tokens_match(expected, token.trim()))
+        .unwrap_or(false)
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn status_response(status: StatusCode) -> Response<HttpBody> {
+    Response::builder()
+        .status(status)
+        .body(Full::new(Bytes::new()).boxed())
+        .unwrap()
+}
+
+fn json_response<T: serde::Serialize>(status: StatusCode, body: &T) -> Response<HttpBody> {
+    let json = serde_json::to_vec(body).unwrap_or_default();
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(json)).boxed())
+        .unwrap()
+}
+
+fn rpc_error_response(status: StatusCode, error: JsonRpcError) -> Response<HttpBody> {
+    json_response(status, &JsonRpcResponse::error(serde_json::Value::Null, error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::PaneState;
+
+    fn test_state(config: McpHttpConfig) -> Arc<McpHttpState> {
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        Arc::new(McpHttpState::new(
+            config,
+            events,
+            Box::new(|| {
+                Box::pin(async {
+                    Ok(McpBridge::with_fake_daemon(|_| ServerMessage::Pong).await)
+                })
+            }),
+        ))
+    }
+
+    fn post(session: Option<&str>, body: serde_json::Value) -> Request<Full<Bytes>> {
+        let mut builder = Request::builder().method(Method::POST).uri(ENDPOINT);
+        if let Some(id) = session {
+            builder = builder.header(SESSION_HEADER, id);
+        }
+        builder
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap()
+    }
+
+    fn empty(method: Method, session: Option<&str>) -> Request<Full<Bytes>> {
+        let mut builder = Request::builder()
+            .method(method)
+            .uri(ENDPOINT)
+            .header(ACCEPT, "text/event-stream");
+        if let Some(id) = session {
+            builder = builder.header(SESSION_HEADER, id);
+        }
+        builder.body(Full::new(Bytes::new())).unwrap()
+    }
+
+    async fn body_json(response: Response<HttpBody>) -> serde_json::Value {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    async fn initialize(state: &Arc<McpHttpState>) -> String {
+        let req = post(
+            None,
+            serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        );
+        let response = handle_request(req, Arc::clone(state)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response.headers()[SESSION_HEADER].to_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_initialize_creates_session() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+        assert!(state.sessions.contains_key(&id));
+    }
+
+    #[tokio::test]
+    async fn test_request_with_session() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+
+        let req = post(
+            Some(&id),
+            serde_json::json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        );
+        let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let json = body_json(response).await;
+        assert_eq!(json["id"], 2);
+        assert!(json["result"]["tools"].as_array().unwrap().len() > 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_without_or_with_unknown_session() {
+        let state = test_state(McpHttpConfig::default());
+        let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"});
+
+        let response = handle_request(post(None, body.clone()), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = handle_request(post(Some("nope"), body), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_notification_returns_accepted() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+
+        let req = post(
+            Some(&id),
+            serde_json::json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        );
+        let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_batch_returns_array() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+
+        let req = post(
+            Some(&id),
+            serde_json::json!([
+                {"jsonrpc": "2.0", "id": 1, "method": "ping"},
+                {"jsonrpc": "2.0", "method": "notifications/initialized"},
+                {"jsonrpc": "1.0", "id": 3, "method": "ping"}
+            ]),
+        );
+        let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+        let json = body_json(response).await;
+        let items = json.as_array().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0]["id"], 1);
+        assert_eq!(items[1]["error"]["code"], JsonRpcError::INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json_is_parse_error() {
+        let state = test_state(McpHttpConfig::default());
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri(ENDPOINT)
+            .body(Full::new(Bytes::from_static(b"{not json")))
+            .unwrap();
+        let response = handle_request(req, state).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body_json(response).await["error"]["code"], JsonRpcError::PARSE_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_delete_ends_session() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+
+        let response = handle_request(empty(Method::DELETE, Some(&id)), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert!(!state.sessions.contains_key(&id));
+
+        let response = handle_request(empty(Method::DELETE, Some(&id)), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_delivers_events() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+
+        let response = handle_request(empty(Method::GET, Some(&id)), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "text/event-stream");
+
+        let pane_id = Uuid::new_v4();
+        state
+            .events
+            .send(ServerMessage::PaneStateChanged {
+                pane_id,
+                state: PaneState::Normal,
+            })
+            .unwrap();
+
+        let mut body = response.into_body();
+        let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        let text = String::from_utf8(frame.to_vec()).unwrap();
+        let data = text
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(data).unwrap();
+        assert_eq!(json["method"], EVENT_METHOD);
+        assert_eq!(json["params"]["type"], "PaneStateChanged");
+        assert_eq!(json["params"]["data"]["pane_id"], pane_id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_sse_stream_delivers_resource_updates() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+        let pane_id = Uuid::new_v4();
+        let uri = format!("fugue://pane/{}/screen", pane_id);
+
+        let req = post(
+            Some(&id),
+            serde_json::json!({
+                "jsonrpc": "2.0", "id": 2, "method": "resources/subscribe",
+                "params": {"uri": uri}
+            }),
+        );
+        let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = handle_request(empty(Method::GET, Some(&id)), Arc::clone(&state))
+            .await
+            .unwrap();
+        let mut body = response.into_body();
+
+        // Output itself is not forwarded as an event, only as a resource update
+        for _ in 0..3 {
+            state
+                .events
+                .send(strip_output(ServerMessage::Output {
+                    pane_id,
+                    data: b"hello".to_vec(),
+                }))
+                .unwrap();
+        }
+
+        let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        let text = String::from_utf8(frame.to_vec()).unwrap();
+        let updates: Vec<serde_json::Value> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .map(|data| serde_json::from_str(data).unwrap())
+            .collect();
+        assert_eq!(updates.len(), 1, "updates are coalesced: {}", text);
+        assert_eq!(updates[0]["method"], "notifications/resources/updated");
+        assert_eq!(updates[0]["params"]["uri"], uri);
+    }
+
+    #[test]
+    fn test_strip_output_drops_payload() {
+        let pane_id = Uuid::new_v4();
+        let msg = ServerMessage::Sequenced {
+            seq: 3,
+            inner: Box::new(ServerMessage::Output {
+                pane_id,
+                data: vec![0; 4096],
+            }),
+        };
+        match strip_output(msg) {
+            ServerMessage::Sequenced { inner, .. } => {
+                assert!(matches!(*inner, ServerMessage::Output { ref data, .. } if data.is_empty()));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sse_requires_event_stream_accept() {
+        let state = test_state(McpHttpConfig::default());
+        let id = initialize(&state).await;
+        let req = Request::builder()
+            .method(Method::GET)
+            .uri(ENDPOINT)
+            .header(SESSION_HEADER, id)
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = handle_request(req, state).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+    }
+
+    #[tokio::test]
+    async fn test_origin_check() {
+        let config = McpHttpConfig {
+            allowed_origins: vec!["https://agents.example.com".to_string()],
+            ..Default::default()
+        };
+        let state = test_state(config);
+        let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+
+        for (origin, expected) in [
+            ("https://evil.example.com", StatusCode::FORBIDDEN),
+            ("http://localhost.evil.com", StatusCode::FORBIDDEN),
+            ("http://localhost:3000", StatusCode::OK),
+            ("http://[::1]:8080", StatusCode::OK),
+            ("https://agents.example.com", StatusCode::OK),
+        ] {
+            let mut req = post(None, body.clone());
+            req.headers_mut()
+                .insert(ORIGIN, HeaderValue::from_str(origin).unwrap());
+            let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+            assert_eq!(response.status(), expected, "origin {}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_token_required() {
+        let config = McpHttpConfig {
+            auth_token: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let state = test_state(config);
+        let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "initialize"});
+
+        let response = handle_request(post(None, body.clone()), Arc::clone(&state))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+
+        let mut req = post(None, body);
+        req.headers_mut()
+            .insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+        let response = handle_request(req, Arc::clone(&state)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_wrong_path_is_not_found() {
+        let state = test_state(McpHttpConfig::default());
+        let req = Request::builder()
+            .uri("/other")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        let response = handle_request(req, state).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_reap_idle_sessions() {
+        let config = McpHttpConfig {
+            session_idle_timeout_secs: 0,
+            ..Default::default()
+        };
+        let state = test_state(config);
+        let id = initialize(&state).await;
+        state.reap_idle();
+        assert!(!state.sessions.contains_key(&id));
+    }
+
+    #[test]
+    fn test_event_notification_mapping() {
+        let pane_id = Uuid::new_v4();
+        let msg = ServerMessage::Sequenced {
+            seq: 7,
+            inner: Box::new(ServerMessage::PaneStateChanged {
+                pane_id,
+                state: PaneState::Normal,
+            }),
+        };
+        let notification = event_notification(&msg).unwrap();
+        assert_eq!(notification.method, EVENT_METHOD);
+        assert_eq!(notification.params["type"], "PaneStateChanged");
+
+        let output = ServerMessage::Output {
+            pane_id,
+            data: b"hi".to_vec(),
+        };
+        assert!(event_notification(&output).is_none());
+        assert!(event_notification(&ServerMessage::Pong).is_none());
+    }
+}
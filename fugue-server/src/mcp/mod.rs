@@ -6,18 +6,17 @@
 //!
 //! MCP Protocol: <https://modelcontextprotocol.io/>
 //!
-//! ## Two Modes
-//!
-//! - **`mcp-server`**: Standalone mode with its own session state (legacy)
-//! - **`mcp-bridge`**: Connects to the fugue daemon, sharing sessions with TUI
+//! All tools are served by the **`mcp-bridge`**, which connects to the fugue
+//! daemon and shares sessions with TUI clients. The `mcp-server` subcommand
+//! is kept as a deprecated alias for it. The daemon can additionally serve
+//! the same tools over streamable HTTP with SSE events (see [`http`]).
 
 pub mod bridge;
 mod error;
-mod handlers;
+pub mod http;
 pub mod keys;
 mod protocol;
-mod server;
 mod tools;
 
 pub use bridge::McpBridge;
-pub use server::McpServer;
+pub use http::run_mcp_http_server;
@@ -56,6 +56,28 @@ impl JsonRpcResponse {
     }
 }
 
+/// JSON-RPC 2.0 notification sent from server to client (no id, no response)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    /// JSON-RPC version (always "2.0")
+    pub jsonrpc: String,
+    /// Notification method name
+    pub method: String,
+    /// Notification parameters
+    pub params: serde_json::Value,
+}
+
+impl JsonRpcNotification {
+    /// Create a notification
+    pub fn new(method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self {
+            jsonrpc: "2.0".into(),
+            method: method.into(),
+            params,
+        }
+    }
+}
+
 /// JSON-RPC 2.0 error object
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JsonRpcError {
@@ -98,6 +120,10 @@ impl JsonRpcError {
     pub const INVALID_PARAMS: i32 = -32602;
     /// Internal error: Internal JSON-RPC error
     pub const INTERNAL_ERROR: i32 = -32603;
+
+    // MCP-defined error codes
+    /// Resource not found: The requested resource URI does not exist
+    pub const RESOURCE_NOT_FOUND: i32 = -32002;
 }
 
 /// MCP Tool definition
@@ -160,12 +186,19 @@ pub struct ServerCapabilities {
     /// Tool capabilities
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<ToolsCapability>,
+    /// Resource capabilities
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
 }
 
 impl Default for ServerCapabilities {
     fn default() -> Self {
         Self {
             tools: Some(ToolsCapability {}),
+            resources: Some(ResourcesCapability {
+                subscribe: true,
+                list_changed: false,
+            }),
         }
     }
 }
@@ -174,6 +207,16 @@ impl Default for ServerCapabilities {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ToolsCapability {}
 
+/// Resource capability flags
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourcesCapability {
+    /// Whether `resources/subscribe` is supported
+    pub subscribe: bool,
+    /// Whether `notifications/resources/list_changed` is sent
+    #[serde(rename = "listChanged")]
+    pub list_changed: bool,
+}
+
 /// MCP Server information
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ServerInfo {
@@ -222,6 +265,71 @@ pub struct ToolsListResult {
     pub tools: Vec<Tool>,
 }
 
+/// MCP resource definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resource {
+    /// Resource URI
+    pub uri: String,
+    /// Human-readable name
+    pub name: String,
+    /// Resource description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of the contents
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// MCP resource template (RFC 6570 URI template)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceTemplate {
+    /// URI template
+    #[serde(rename = "uriTemplate")]
+    pub uri_template: String,
+    /// Human-readable name
+    pub name: String,
+    /// Template description
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of the contents
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Resources list response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourcesListResult {
+    /// Available resources
+    pub resources: Vec<Resource>,
+}
+
+/// Resource templates list response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceTemplatesListResult {
+    /// Available resource templates
+    #[serde(rename = "resourceTemplates")]
+    pub resource_templates: Vec<ResourceTemplate>,
+}
+
+/// Text contents of a resource
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResourceContents {
+    /// Resource URI
+    pub uri: String,
+    /// MIME type of the text
+    #[serde(rename = "mimeType", skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Resource text
+    pub text: String,
+}
+
+/// Resource read response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    /// Resource contents
+    pub contents: Vec<ResourceContents>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,4 +456,14 @@ mod tests {
         assert_eq!(JsonRpcError::INVALID_PARAMS, -32602);
         assert_eq!(JsonRpcError::INTERNAL_ERROR, -32603);
     }
+
+    #[test]
+    fn test_json_rpc_notification_serialize() {
+        let notification =
+            JsonRpcNotification::new("notifications/fugue/event", serde_json::json!({"type": "Pong"}));
+        let json = serde_json::to_value(&notification).unwrap();
+        assert_eq!(json["jsonrpc"], "2.0");
+        assert_eq!(json["method"], "notifications/fugue/event");
+        assert!(json.get("id").is_none());
+    }
 }
@@ -34,6 +34,14 @@ pub fn get_tool_definitions() -> Vec<Tool> {
                         "type": "integer",
                         "description": "Number of lines to read (default: 100, max: 1000)"
                     },
+                    "start": {
+                        "type": "integer",
+                        "description": "First line of a range to read instead of the last `lines`: 0 is the top of the visible screen, negative numbers go back into history (including history spilled to disk). Omit with `end` set to start at the oldest line"
+                    },
+                    "end": {
+                        "type": "integer",
+                        "description": "Last line of the range, same numbering (default: bottom of the screen)"
+                    },
                     "strip_escapes": {
                         "type": "boolean",
                         "default": false,
@@ -381,7 +389,7 @@ pub fn get_tool_definitions() -> Vec<Tool> {
                     },
                     "delta": {
                         "type": "number",
-                        "description": "Size change as a fraction (-0.5 to 0.5). Positive values grow the pane, negative values shrink it."
+                        "description": "Change in the pane's share of its split (-0.5 to 0.5). Positive values grow the pane at the expense of its neighbour, negative values shrink it."
                     }
                 },
                 "required": ["pane_id", "delta"]
@@ -647,6 +655,122 @@ pub fn get_tool_definitions() -> Vec<Tool> {
                 "required": ["session"]
             }),
         },
+        Tool {
+            name: "fugue_export_session".into(),
+            description: "Export a session (windows, layout, pane commands, cwd, env, metadata, tags) as a portable JSON or TOML document. Returns the document, or writes it to 'path'.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "session": {
+                        "type": "string",
+                        "description": "Session UUID or name"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "File to write the export to (returned inline if omitted)"
+                    },
+                    "format": {
+                        "type": "string",
+                        "enum": ["json", "toml"],
+                        "description": "Document format (default: from the path extension, else json)"
+                    },
+                    "include_scrollback": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Include each pane's scrollback as plain text"
+                    }
+                },
+                "required": ["session"]
+            }),
+        },
+        Tool {
+            name: "fugue_import_session".into(),
+            description: "Recreate a session from a document produced by fugue_export_session".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Exported file to import"
+                    },
+                    "document": {
+                        "type": "string",
+                        "description": "Export document contents (instead of 'path')"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Name for the new session (default: the exported name)"
+                    }
+                }
+            }),
+        },
+        Tool {
+            name: "fugue_search".into(),
+            description: "Search the scrollback of every pane for lines containing all of the given words (or \"quoted phrases\"). Case-insensitive; words match at word starts. Returns newest matches first with their session, window, pane and surrounding lines.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "query": {
+                        "type": "string",
+                        "description": "Words or \"quoted phrases\" that must all occur in a line"
+                    },
+                    "session": {
+                        "type": "string",
+                        "description": "Only search this session (UUID or name)"
+                    },
+                    "context": {
+                        "type": "integer",
+                        "default": 2,
+                        "maximum": 20,
+                        "description": "Lines of context before and after each match"
+                    },
+                    "limit": {
+                        "type": "integer",
+                        "default": 50,
+                        "maximum": 1000,
+                        "description": "Maximum matches to return, shared between panes"
+                    }
+                },
+                "required": ["query"]
+            }),
+        },
+        Tool {
+            name: "fugue_buffer".into(),
+            description: "Use the server's paste buffers, which are shared by every client and agent. Buffers hand text between agents and panes: 'set' stores text (a new buffer0, buffer1, ... unless a name is given), 'get' reads it, 'list' shows all buffers, 'delete' removes one, and 'paste' types a buffer into a pane. Without a name, get/delete/paste use the most recent buffer.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "action": {
+                        "type": "string",
+                        "enum": ["set", "get", "list", "delete", "paste"],
+                        "description": "What to do"
+                    },
+                    "name": {
+                        "type": "string",
+                        "description": "Buffer name"
+                    },
+                    "data": {
+                        "type": "string",
+                        "description": "Text to store (set)"
+                    },
+                    "append": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Append to the buffer instead of replacing it (set)"
+                    },
+                    "pane_id": {
+                        "type": "string",
+                        "description": "Pane to paste into (paste)"
+                    },
+                    "delete": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Delete the buffer after pasting (paste)"
+                    }
+                },
+                "required": ["action"]
+            }),
+        },
         Tool {
             name: "fugue_report_status".into(),
             description: "Report current session status to orchestrator (sends to sessions tagged 'orchestrator')".into(),
@@ -803,7 +927,7 @@ pub fn get_tool_definitions() -> Vec<Tool> {
         // ==================== FEAT-096: Expect Tool ====================
         Tool {
             name: "fugue_expect".into(),
-            description: "Wait for regex pattern in pane output".into(),
+            description: "Wait until pane output matches a regex, or until the pane's agent reaches a given state. Matching happens in the daemon against the live output stream, so output that scrolls past is never missed.".into(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -815,6 +939,18 @@ pub fn get_tool_definitions() -> Vec<Tool> {
                         "type": "string",
                         "description": "Regex pattern to match"
                     },
+                    "patterns": {
+                        "type": "array",
+                        "items": {"type": "string"},
+                        "description": "Several regex patterns; the earliest match wins and its index is reported"
+                    },
+                    "agent_state": {
+                        "oneOf": [
+                            {"type": "string"},
+                            {"type": "array", "items": {"type": "string"}}
+                        ],
+                        "description": "Wait for the agent to reach one of these states (idle, processing, generating, tool_use, awaiting_confirmation, awaiting_permission, or a custom state) instead of a pattern"
+                    },
                     "timeout_ms": {
                         "type": "integer",
                         "default": 60000,
@@ -824,20 +960,64 @@ pub fn get_tool_definitions() -> Vec<Tool> {
                         "type": "string",
                         "enum": ["notify", "close_pane", "return_output"],
                         "default": "notify",
-                        "description": "Action to take on pattern match"
-                    },
-                    "poll_interval_ms": {
-                        "type": "integer",
-                        "default": 200,
-                        "description": "Polling interval (default 200ms)"
+                        "description": "Action to take on match"
                     },
                     "lines": {
                         "type": "integer",
                         "default": 100,
-                        "description": "Number of lines to check from end of output"
+                        "description": "Number of existing scrollback lines to check before waiting for new output (0 = new output only); also the amount returned by return_output"
+                    }
+                },
+                "required": ["pane_id"]
+            }),
+        },
+        // ==================== Permission Prompts ====================
+        Tool {
+            name: "fugue_approve".into(),
+            description: "Approve the tool-permission prompt an agent is showing in a pane (activity awaiting_permission). Selects the agent's own \"Yes\" option, or its \"always allow\" option when always is true.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pane_id": {
+                        "type": "string",
+                        "description": "UUID of the pane showing the prompt"
+                    },
+                    "always": {
+                        "type": "boolean",
+                        "default": false,
+                        "description": "Choose the option that stops the agent asking again for this tool, if it offers one"
                     }
                 },
-                "required": ["pane_id", "pattern"]
+                "required": ["pane_id"]
+            }),
+        },
+        Tool {
+            name: "fugue_deny".into(),
+            description: "Deny the tool-permission prompt an agent is showing in a pane (activity awaiting_permission).".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pane_id": {
+                        "type": "string",
+                        "description": "UUID of the pane showing the prompt"
+                    }
+                },
+                "required": ["pane_id"]
+            }),
+        },
+        // ==================== Usage Budgets ====================
+        Tool {
+            name: "fugue_resume_pane".into(),
+            description: "Resume an agent pane that was paused because a usage budget with action \"pause\" was exceeded.".into(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pane_id": {
+                        "type": "string",
+                        "description": "UUID of the paused pane"
+                    }
+                },
+                "required": ["pane_id"]
             }),
         },
         // ==================== FEAT-094: Parallel Command Execution ====================
@@ -941,7 +1121,7 @@ pub fn get_tool_definitions() -> Vec<Tool> {
         // ==================== FEAT-097: Orchestration Message Receive ====================
         Tool {
             name: "fugue_get_worker_status".into(),
-            description: "Retrieves the current status of a specific worker (or all workers if no ID provided).".into(),
+            description: "Retrieves the current status of a specific worker (or all workers if no ID provided), including its agents' token usage and estimated cost.".into(),
             input_schema: serde_json::json!({
                 "type": "object",
                 "properties": {
@@ -1338,6 +1518,10 @@ mod tests {
         assert!(names.contains(&"fugue_connection_status"));
         // FEAT-096: Expect tool
         assert!(names.contains(&"fugue_expect"));
+        // Permission prompts
+        assert!(names.contains(&"fugue_approve"));
+        assert!(names.contains(&"fugue_deny"));
+        assert!(names.contains(&"fugue_resume_pane"));
         // FEAT-094: Parallel command execution
         assert!(names.contains(&"fugue_run_parallel"));
         // FEAT-095: Sequential pipeline execution
@@ -1357,5 +1541,12 @@ mod tests {
         assert!(names.contains(&"fugue_mail_read"));
         assert!(names.contains(&"fugue_mail_list"));
         assert!(names.contains(&"fugue_mail_delete"));
+        // Portable session export/import
+        assert!(names.contains(&"fugue_export_session"));
+        assert!(names.contains(&"fugue_import_session"));
+        // Scrollback search
+        assert!(names.contains(&"fugue_search"));
+        // Paste buffers
+        assert!(names.contains(&"fugue_buffer"));
     }
 }
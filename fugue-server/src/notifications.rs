@@ -0,0 +1,514 @@
+//! Notification sinks
+//!
+//! Sends notifications to places that work when no client is watching:
+//! freedesktop desktop notifications over the session D-Bus, bell/OSC alerts
+//! written by attached clients, and HTTP webhooks with a templated JSON body.
+//! `[[notifications.routes]]` pick the sinks for each notification by session
+//! and level. Notifications come from pane `notify` sideband commands, mail,
+//! and agents that finish or start waiting for input; the last two are found
+//! by the hook runner, see `crate::hooks`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{CONTENT_TYPE, HOST, USER_AGENT};
+use hyper::{Request, Uri};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsConnector;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{ServerMessage, TerminalAlert};
+
+use crate::config::{
+    HookEvent, NotificationLevel, NotificationSinkConfig, NotificationsConfig, TerminalAlertKind,
+};
+use crate::hooks::HookContext;
+use crate::sideband::NotifyLevel;
+
+/// Longest a desktop or webhook delivery may take
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Something worth telling the user about
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// What raised it: `notify`, `mail`, `agent-finished` or `agent-waiting`
+    pub event: &'static str,
+    pub level: NotificationLevel,
+    pub title: String,
+    pub message: String,
+    pub session_id: Option<Uuid>,
+    pub session_name: Option<String>,
+    pub pane_id: Option<Uuid>,
+}
+
+impl Notification {
+    /// Notification sent by a pane with the `notify` sideband command
+    pub fn from_sideband(
+        title: Option<String>,
+        message: String,
+        level: NotifyLevel,
+        session: Option<(Uuid, String)>,
+        pane_id: Uuid,
+    ) -> Self {
+        let level = match level {
+            NotifyLevel::Info => NotificationLevel::Info,
+            NotifyLevel::Warning => NotificationLevel::Warning,
+            NotifyLevel::Error => NotificationLevel::Error,
+        };
+        let (session_id, session_name) = session.unzip();
+        Self {
+            event: "notify",
+            level,
+            title: title.unwrap_or_else(|| "Notification".to_string()),
+            message,
+            session_id,
+            session_name,
+            pane_id: Some(pane_id),
+        }
+    }
+
+    /// Notification for a server event, if it is one worth notifying about
+    ///
+    /// Agents going back to idle after working are "finished"; agents asking
+    /// for confirmation or permission are "waiting". Mail is passed on at
+    /// its own priority.
+    pub fn from_event(event: &HookContext) -> Option<Self> {
+        let session = event
+            .session_name
+            .clone()
+            .or_else(|| event.session_id.map(|id| id.to_string()))
+            .unwrap_or_else(|| "unknown session".to_string());
+        let (event_name, level, title, message) = match event.event {
+            HookEvent::AgentStateChanged => {
+                let agent = event.var("FUGUE_AGENT_TYPE").unwrap_or("agent");
+                let from = event.var("FUGUE_AGENT_FROM")?;
+                match event.var("FUGUE_AGENT_TO")? {
+                    "idle" if matches!(from, "processing" | "generating" | "tool_use") => (
+                        "agent-finished",
+                        NotificationLevel::Info,
+                        "Agent finished".to_string(),
+                        format!("{} in {} is idle", agent, session),
+                    ),
+                    to @ ("awaiting_confirmation" | "awaiting_permission") => (
+                        "agent-waiting",
+                        NotificationLevel::Warning,
+                        "Agent needs input".to_string(),
+                        format!("{} in {} is {}", agent, session, to.replace('_', " ")),
+                    ),
+                    _ => return None,
+                }
+            }
+            HookEvent::MailReceived => {
+                let level = match event.var("FUGUE_MAIL_PRIORITY")? {
+                    "error" => NotificationLevel::Error,
+                    "warning" => NotificationLevel::Warning,
+                    _ => NotificationLevel::Info,
+                };
+                (
+                    "mail",
+                    level,
+                    format!("Mail from {}", session),
+                    event.var("FUGUE_MAIL_SUMMARY").unwrap_or_default().to_string(),
+                )
+            }
+            _ => return None,
+        };
+        Some(Self {
+            event: event_name,
+            level,
+            title,
+            message,
+            session_id: event.session_id,
+            session_name: event.session_name.clone(),
+            pane_id: event.pane_id,
+        })
+    }
+
+    /// Values available to webhook templates
+    fn fields(&self) -> Vec<(&'static str, String)> {
+        let level = match self.level {
+            NotificationLevel::Info => "info",
+            NotificationLevel::Warning => "warning",
+            NotificationLevel::Error => "error",
+        };
+        vec![
+            ("event", self.event.to_string()),
+            ("level", level.to_string()),
+            ("title", self.title.clone()),
+            ("message", self.message.clone()),
+            ("session", self.session_name.clone().unwrap_or_default()),
+            ("session_id", self.session_id.map(|id| id.to_string()).unwrap_or_default()),
+            ("pane_id", self.pane_id.map(|id| id.to_string()).unwrap_or_default()),
+            ("timestamp", chrono::Utc::now().to_rfc3339()),
+        ]
+    }
+}
+
+/// Sinks a notification goes to, each once, in configuration order
+///
+/// `attached` is whether a terminal client is attached to the notification's
+/// session, for routes with `detached_only`.
+pub fn route<'a>(
+    config: &'a NotificationsConfig,
+    notification: &Notification,
+    attached: bool,
+) -> Vec<(&'a str, &'a NotificationSinkConfig)> {
+    let mut sinks: Vec<(&str, &NotificationSinkConfig)> = Vec::new();
+    for route in &config.routes {
+        if notification.level < route.min_level || (route.detached_only && attached) {
+            continue;
+        }
+        if let Some(session) = &route.session {
+            let by_id = notification.session_id.is_some_and(|id| id.to_string() == *session);
+            let by_name = notification.session_name.as_deref() == Some(session.as_str());
+            if !by_id && !by_name {
+                continue;
+            }
+        }
+        for name in &route.sinks {
+            if let Some((name, sink)) = config.sinks.get_key_value(name) {
+                if !sinks.iter().any(|(seen, _)| *seen == name.as_str()) {
+                    sinks.push((name, sink));
+                }
+            }
+        }
+    }
+    sinks
+}
+
+/// Deliver a notification to the sinks `config` routes it to
+///
+/// Desktop and webhook deliveries run in the background. Terminal alerts are
+/// returned for the caller to send to the session's attached clients.
+pub fn dispatch(config: &NotificationsConfig, notification: &Notification, attached: bool) -> Vec<ServerMessage> {
+    let mut alerts = Vec::new();
+    for (name, sink) in route(config, notification, attached) {
+        debug!("Notification '{}' to sink {}", notification.title, name);
+        let alert = match sink {
+            NotificationSinkConfig::Terminal { alert } => *alert,
+            NotificationSinkConfig::Desktop { timeout_ms } => {
+                let timeout_ms = *timeout_ms;
+                spawn_delivery(name, notification, move |notification| async move {
+                    send_desktop(&notification, timeout_ms).await
+                });
+                continue;
+            }
+            NotificationSinkConfig::Webhook { url, template, headers } => {
+                let body = render_body(template.as_deref(), notification);
+                let url = url.clone();
+                let headers = headers.clone();
+                spawn_delivery(name, notification, move |_| async move {
+                    post_webhook(&url, &headers, body).await
+                });
+                continue;
+            }
+        };
+        alerts.push(ServerMessage::TerminalNotification {
+            title: notification.title.clone(),
+            message: notification.message.clone(),
+            alert: match alert {
+                TerminalAlertKind::Bell => TerminalAlert::Bell,
+                TerminalAlertKind::Osc9 => TerminalAlert::Osc9,
+                TerminalAlertKind::Osc777 => TerminalAlert::Osc777,
+            },
+        });
+    }
+    alerts
+}
+
+/// Run a delivery in the background with a time limit, logging failures
+fn spawn_delivery<F, Fut>(sink: &str, notification: &Notification, deliver: F)
+where
+    F: FnOnce(Notification) -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = Result<(), String>> + Send + 'static,
+{
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        warn!("No runtime to deliver notification to {}", sink);
+        return;
+    };
+    let sink = sink.to_string();
+    let notification = notification.clone();
+    runtime.spawn(async move {
+        match tokio::time::timeout(DELIVERY_TIMEOUT, deliver(notification)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => warn!("Notification sink {} failed: {}", sink, e),
+            Err(_) => warn!("Notification sink {} timed out", sink),
+        }
+    });
+}
+
+/// Show a freedesktop notification
+async fn send_desktop(notification: &Notification, timeout_ms: Option<i32>) -> Result<(), String> {
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| format!("no session bus: {}", e))?;
+
+    // Urgency hint: 1 = normal, 2 = critical
+    let urgency: u8 = if notification.level == NotificationLevel::Error { 2 } else { 1 };
+    let hints = HashMap::from([("urgency", zbus::zvariant::Value::U8(urgency))]);
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "fugue",
+                0u32,
+                "",
+                notification.title.as_str(),
+                notification.message.as_str(),
+                Vec::<&str>::new(),
+                hints,
+                timeout_ms.unwrap_or(-1),
+            ),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Webhook body: the template with fields substituted, or all fields as JSON
+pub fn render_body(template: Option<&str>, notification: &Notification) -> String {
+    let fields = notification.fields();
+    match template {
+        Some(template) => fields.iter().fold(template.to_string(), |body, (key, value)| {
+            // Escaped for use inside a JSON string, without the quotes
+            let escaped = serde_json::to_string(value).unwrap_or_default();
+            body.replace(&format!("{{{{{}}}}}", key), &escaped[1..escaped.len() - 1])
+        }),
+        None => {
+            let object: serde_json::Map<String, serde_json::Value> = fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), serde_json::Value::String(value)))
+                .collect();
+            serde_json::Value::Object(object).to_string()
+        }
+    }
+}
+
+/// POST a JSON body, failing on anything but a 2xx response
+async fn post_webhook(url: &str, headers: &BTreeMap<String, String>, body: String) -> Result<(), String> {
+    let uri: Uri = url.parse().map_err(|e| format!("invalid URL: {}", e))?;
+    let https = match uri.scheme_str() {
+        Some("https") => true,
+        Some("http") => false,
+        _ => return Err("URL must be http:// or https://".to_string()),
+    };
+    let host = uri.host().ok_or("URL has no host")?.to_string();
+    let port = uri.port_u16().unwrap_or(if https { 443 } else { 80 });
+    let authority = uri.authority().map(|a| a.as_str()).unwrap_or(&host).to_string();
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+
+    let mut request = Request::post(path)
+        .header(HOST, authority)
+        .header(CONTENT_TYPE, "application/json")
+        .header(USER_AGENT, concat!("fugue/", env!("CARGO_PKG_VERSION")));
+    for (name, value) in headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let request = request
+        .body(Full::new(Bytes::from(body)))
+        .map_err(|e| format!("invalid request: {}", e))?;
+
+    let stream = TcpStream::connect((host.as_str(), port))
+        .await
+        .map_err(|e| format!("connect to {}:{} failed: {}", host, port, e))?;
+    let status = if https {
+        let tls = fugue_utils::tls::web_client_config().map_err(|e| e.to_string())?;
+        let server_name = fugue_utils::tls::server_name(&host).map_err(|e| e.to_string())?;
+        let stream = TlsConnector::from(tls)
+            .connect(server_name, stream)
+            .await
+            .map_err(|e| format!("TLS handshake failed: {}", e))?;
+        send_request(TokioIo::new(stream), request).await?
+    } else {
+        send_request(TokioIo::new(stream), request).await?
+    };
+
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("server answered {}", status))
+    }
+}
+
+async fn send_request<S>(io: S, request: Request<Full<Bytes>>) -> Result<hyper::StatusCode, String>
+where
+    S: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(io)
+        .await
+        .map_err(|e| e.to_string())?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            debug!("Webhook connection closed: {}", e);
+        }
+    });
+    let response = sender.send_request(request).await.map_err(|e| e.to_string())?;
+    Ok(response.status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::NotificationRoute;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn notification(level: NotificationLevel, session: &str) -> Notification {
+        Notification {
+            event: "notify",
+            level,
+            title: "Build".into(),
+            message: "tests \"passed\"".into(),
+            session_id: Some(Uuid::new_v4()),
+            session_name: Some(session.into()),
+            pane_id: None,
+        }
+    }
+
+    fn config() -> NotificationsConfig {
+        let mut sinks = BTreeMap::new();
+        sinks.insert("desktop".to_string(), NotificationSinkConfig::Desktop { timeout_ms: None });
+        sinks.insert(
+            "term".to_string(),
+            NotificationSinkConfig::Terminal { alert: TerminalAlertKind::Bell },
+        );
+        NotificationsConfig {
+            sinks,
+            routes: vec![
+                NotificationRoute {
+                    sinks: vec!["term".into()],
+                    ..Default::default()
+                },
+                NotificationRoute {
+                    sinks: vec!["desktop".into(), "term".into()],
+                    session: Some("api".into()),
+                    min_level: NotificationLevel::Warning,
+                    detached_only: true,
+                },
+            ],
+        }
+    }
+
+    fn names<'a>(sinks: &[(&'a str, &NotificationSinkConfig)]) -> Vec<&'a str> {
+        sinks.iter().map(|(name, _)| *name).collect()
+    }
+
+    #[test]
+    fn test_route_by_session_level_and_attachment() {
+        let config = config();
+        let warning = notification(NotificationLevel::Warning, "api");
+        assert_eq!(names(&route(&config, &warning, false)), vec!["term", "desktop"]);
+        assert_eq!(names(&route(&config, &warning, true)), vec!["term"]);
+
+        let info = notification(NotificationLevel::Info, "api");
+        assert_eq!(names(&route(&config, &info, false)), vec!["term"]);
+
+        let other = notification(NotificationLevel::Error, "web");
+        assert_eq!(names(&route(&config, &other, false)), vec!["term"]);
+    }
+
+    #[test]
+    fn test_notifications_from_events() {
+        let mut event = HookContext {
+            event: HookEvent::AgentStateChanged,
+            session_id: Some(Uuid::new_v4()),
+            session_name: Some("api".into()),
+            pane_id: Some(Uuid::new_v4()),
+            vars: vec![
+                ("FUGUE_AGENT_FROM", "tool_use".into()),
+                ("FUGUE_AGENT_TO", "idle".into()),
+                ("FUGUE_AGENT_TYPE", "claude".into()),
+            ],
+        };
+        let finished = Notification::from_event(&event).unwrap();
+        assert_eq!(finished.event, "agent-finished");
+        assert_eq!(finished.message, "claude in api is idle");
+
+        event.vars[1].1 = "awaiting_permission".into();
+        let waiting = Notification::from_event(&event).unwrap();
+        assert_eq!(waiting.level, NotificationLevel::Warning);
+        assert_eq!(waiting.message, "claude in api is awaiting permission");
+
+        // Idle after being detected idle is not a finish
+        event.vars[0].1 = "none".into();
+        event.vars[1].1 = "idle".into();
+        assert!(Notification::from_event(&event).is_none());
+
+        event.event = HookEvent::MailReceived;
+        event.vars = vec![
+            ("FUGUE_MAIL_PRIORITY", "error".into()),
+            ("FUGUE_MAIL_SUMMARY", "build broke".into()),
+        ];
+        let mail = Notification::from_event(&event).unwrap();
+        assert_eq!(mail.level, NotificationLevel::Error);
+        assert_eq!(mail.title, "Mail from api");
+
+        event.event = HookEvent::PaneExited;
+        assert!(Notification::from_event(&event).is_none());
+    }
+
+    #[test]
+    fn test_render_body_escapes_values() {
+        let notification = notification(NotificationLevel::Warning, "api");
+        let body = render_body(Some(r#"{"text": "[{{level}}] {{session}}: {{message}}"}"#), &notification);
+        let value: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(value["text"], r#"[warning] api: tests "passed""#);
+
+        let value: serde_json::Value = serde_json::from_str(&render_body(None, &notification)).unwrap();
+        assert_eq!(value["title"], "Build");
+        assert_eq!(value["event"], "notify");
+        assert_eq!(value["pane_id"], "");
+    }
+
+    #[tokio::test]
+    async fn test_webhook_posts_to_local_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Headers, then a body of Content-Length bytes
+            loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request).to_string();
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let length = head
+                        .lines()
+                        .find_map(|line| line.to_lowercase().strip_prefix("content-length: ").map(str::to_string))
+                        .and_then(|len| len.parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if body.len() >= length {
+                        break;
+                    }
+                }
+            }
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let headers = BTreeMap::from([("Authorization".to_string(), "Bearer abc".to_string())]);
+        let url = format!("http://127.0.0.1:{}/hooks/fugue?x=1", port);
+        post_webhook(&url, &headers, r#"{"text":"hi"}"#.to_string()).await.unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /hooks/fugue?x=1 HTTP/1.1\r\n"));
+        assert!(request.to_lowercase().contains("authorization: bearer abc"));
+        assert!(request.to_lowercase().contains("content-type: application/json"));
+        assert!(request.ends_with(r#"{"text":"hi"}"#));
+
+        // Nothing listening any more
+        assert!(post_webhook(&url, &headers, String::new()).await.is_err());
+    }
+}
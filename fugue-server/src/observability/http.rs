@@ -16,8 +16,8 @@ use hyper_util::rt::TokioIo;
 use tokio::net::TcpListener;
 use tracing::{error, info, warn};
 
-use super::metrics::{GaugeSnapshot, Metrics};
-use crate::SharedState;
+use super::metrics::{GaugeSnapshot, Metrics, PaneUsageSample};
+use crate::{usage, SharedState};
 
 /// Run the metrics HTTP server
 ///
@@ -119,6 +119,30 @@ async fn serve_metrics(state: Arc<SharedState>) -> Response<Full<Bytes>> {
             }
         }
         gauges.active_panes = pane_count;
+
+        // Token/cost accounting, per pane and rolled up per session and tag
+        let mut tags = std::collections::BTreeSet::new();
+        for session in session_manager.list_sessions() {
+            for pane in session.windows().flat_map(|w| w.panes()) {
+                if pane.usage_tracker().is_empty() {
+                    continue;
+                }
+                gauges.pane_usage.push(PaneUsageSample {
+                    session: session.name().to_string(),
+                    pane_id: pane.id().to_string(),
+                    agent_type: pane.agent_state().map(|s| s.agent_type).unwrap_or_default(),
+                    usage: pane.usage(),
+                });
+            }
+            gauges
+                .session_usage
+                .push((session.name().to_string(), usage::session_usage(session)));
+            tags.extend(session.tags().iter().cloned());
+        }
+        for tag in tags {
+            let total = usage::tag_usage(&session_manager, &tag);
+            gauges.tag_usage.push((tag, total));
+        }
     }
 
     // Collect process metrics (Linux only)
@@ -175,6 +199,7 @@ fn not_found() -> Response<Full<Bytes>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fugue_protocol::AgentUsage;
 
     #[test]
     fn test_gauge_snapshot_default() {
@@ -195,6 +220,14 @@ mod tests {
             active_panes: 10,
             process_memory_bytes: Some(1024 * 1024),
             process_open_fds: Some(25),
+            pane_usage: vec![PaneUsageSample {
+                session: "main".into(),
+                pane_id: "p1".into(),
+                agent_type: "claude".into(),
+                usage: AgentUsage { tokens: 1500, cost_usd: 0.25, estimated: false },
+            }],
+            session_usage: vec![("main".into(), AgentUsage { tokens: 1500, cost_usd: 0.25, estimated: false })],
+            tag_usage: Vec::new(),
         };
 
         let output = metrics.to_prometheus(&gauges);
@@ -211,6 +244,9 @@ mod tests {
         assert!(output.contains("fugue_active_connections 5"));
         assert!(output.contains("fugue_active_sessions 2"));
         assert!(output.contains("fugue_active_panes 10"));
+        assert!(output.contains("fugue_pane_tokens{session=\"main\",pane_id=\"p1\",agent=\"claude\"} 1500"));
+        assert!(output.contains("fugue_session_cost_usd{session=\"main\"} 0.25"));
+        assert!(!output.contains("fugue_tag_tokens"));
     }
 
     #[test]
@@ -4,6 +4,7 @@
 //! and performance. Supports Prometheus text format export (FEAT-074).
 
 use dashmap::DashMap;
+use fugue_protocol::AgentUsage;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 
@@ -20,6 +21,21 @@ pub struct GaugeSnapshot {
     pub process_memory_bytes: Option<u64>,
     /// Number of open file descriptors (Linux only)
     pub process_open_fds: Option<u64>,
+    /// Usage of each agent pane that has recorded any
+    pub pane_usage: Vec<PaneUsageSample>,
+    /// Usage per session name
+    pub session_usage: Vec<(String, AgentUsage)>,
+    /// Usage per session tag
+    pub tag_usage: Vec<(String, AgentUsage)>,
+}
+
+/// Usage of one agent pane
+#[derive(Debug)]
+pub struct PaneUsageSample {
+    pub session: String,
+    pub pane_id: String,
+    pub agent_type: String,
+    pub usage: AgentUsage,
 }
 
 impl GaugeSnapshot {
@@ -88,6 +104,8 @@ pub struct Metrics {
 
     /// Total Claude state transitions
     pub claude_state_transitions_total: AtomicU64,
+    /// Total usage budgets exceeded
+    pub budget_exceeded_total: AtomicU64,
 
     // FEAT-074: Per-type counters for detailed telemetry
     /// Requests by message type
@@ -116,6 +134,7 @@ impl Metrics {
             wal_bytes_written_total: AtomicU64::new(0),
             checkpoint_bytes_written_total: AtomicU64::new(0),
             claude_state_transitions_total: AtomicU64::new(0),
+            budget_exceeded_total: AtomicU64::new(0),
             requests_by_type: DashMap::new(),
             errors_by_code: DashMap::new(),
         })
@@ -173,6 +192,11 @@ impl Metrics {
         self.claude_state_transitions_total.fetch_add(1, Ordering::Relaxed);
     }
 
+    /// Record a usage budget being exceeded
+    pub fn record_budget_exceeded(&self) {
+        self.budget_exceeded_total.fetch_add(1, Ordering::Relaxed);
+    }
+
     // FEAT-074: New methods for per-type tracking
 
     /// Record a request by message type name
@@ -292,6 +316,11 @@ impl Metrics {
             "Total Claude state transitions",
             self.claude_state_transitions_total
         );
+        counter!(
+            "fugue_budget_exceeded_total",
+            "Total usage budgets exceeded",
+            self.budget_exceeded_total
+        );
 
         // === Per-type counters ===
 
@@ -360,6 +389,57 @@ impl Metrics {
             );
         }
 
+        // Token/cost accounting
+        if !gauges.pane_usage.is_empty() {
+            let _ = writeln!(output, "# HELP fugue_pane_tokens Tokens used by the agent in a pane");
+            let _ = writeln!(output, "# TYPE fugue_pane_tokens gauge");
+            for sample in &gauges.pane_usage {
+                let _ = writeln!(
+                    output,
+                    "fugue_pane_tokens{{session=\"{}\",pane_id=\"{}\",agent=\"{}\"}} {}",
+                    escape_label(&sample.session),
+                    sample.pane_id,
+                    escape_label(&sample.agent_type),
+                    sample.usage.tokens
+                );
+            }
+            let _ = writeln!(output, "# HELP fugue_pane_cost_usd Cost of the agent in a pane (reported or estimated)");
+            let _ = writeln!(output, "# TYPE fugue_pane_cost_usd gauge");
+            for sample in &gauges.pane_usage {
+                let _ = writeln!(
+                    output,
+                    "fugue_pane_cost_usd{{session=\"{}\",pane_id=\"{}\",agent=\"{}\"}} {}",
+                    escape_label(&sample.session),
+                    sample.pane_id,
+                    escape_label(&sample.agent_type),
+                    sample.usage.cost_usd
+                );
+            }
+        }
+        for (metric, label, totals) in [
+            ("fugue_session", "session", &gauges.session_usage),
+            ("fugue_tag", "tag", &gauges.tag_usage),
+        ] {
+            if totals.is_empty() {
+                continue;
+            }
+            let _ = writeln!(output, "# HELP {}_tokens Tokens used, summed per {}", metric, label);
+            let _ = writeln!(output, "# TYPE {}_tokens gauge", metric);
+            for (name, usage) in totals {
+                let _ = writeln!(output, "{}_tokens{{{}=\"{}\"}} {}", metric, label, escape_label(name), usage.tokens);
+            }
+            let _ = writeln!(output, "# HELP {}_cost_usd Cost in USD, summed per {}", metric, label);
+            let _ = writeln!(output, "# TYPE {}_cost_usd gauge", metric);
+            for (name, usage) in totals {
+                let _ = writeln!(output, "{}_cost_usd{{{}=\"{}\"}} {}", metric, label, escape_label(name), usage.cost_usd);
+            }
+        }
+
         output
     }
 }
+
+/// Escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,794 @@
+//! Content-addressed scrollback blocks
+//!
+//! Scrollback is persisted as append-only blocks of up to `BLOCK_LINES`
+//! lines. Each block is zstd-compressed and stored under the BLAKE3 hash of
+//! its content, so identical blocks are stored once. Checkpoints only list
+//! each pane's blocks, which means a checkpoint compresses just the output
+//! produced since the previous one instead of every pane's whole scrollback.
+//!
+//! Once enough blocks have been written, a zstd dictionary is trained from
+//! them and used for every later block. Terminal output from the same tools
+//! repeats a lot, and the dictionary lets small blocks benefit from that.
+//!
+//! ```text
+//! scrollback/
+//! ├── dictionary
+//! └── 3f/
+//!     └── 3fa9...e1.zst
+//! ```
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use fugue_utils::{CcmuxError, Result};
+
+use super::types::BlockRef;
+use crate::pty::ScrollbackBuffer;
+use crate::session::SessionManager;
+
+/// Lines per sealed block
+pub const BLOCK_LINES: usize = 256;
+
+/// zstd level for blocks
+const ZSTD_LEVEL: i32 = 3;
+
+/// Dictionary file name inside the block directory
+const DICTIONARY_FILE: &str = "dictionary";
+
+/// Maximum trained dictionary size
+const DICTIONARY_SIZE: usize = 16 * 1024;
+
+/// Blocks to sample before training the dictionary
+const DICTIONARY_SAMPLES: usize = 64;
+
+/// Header byte of a block compressed without a dictionary
+const BLOCK_PLAIN: u8 = 0;
+
+/// Header byte of a block compressed with the store's dictionary
+const BLOCK_WITH_DICTIONARY: u8 = 1;
+
+/// Directory of compressed, content-addressed blocks of lines
+pub struct BlockStore {
+    dir: PathBuf,
+    /// Trained dictionary, once there is one (never replaced afterwards)
+    dictionary: Option<Dictionary>,
+    /// Raw blocks kept for training until the dictionary exists
+    samples: Vec<Vec<u8>>,
+}
+
+impl BlockStore {
+    /// Open (creating if needed) a block store
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| {
+            CcmuxError::persistence(format!("Failed to create block directory: {}", e))
+        })?;
+
+        let dictionary = match fs::read(dir.join(DICTIONARY_FILE)) {
+            Ok(dictionary) => Some(Dictionary::new(dictionary)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                return Err(CcmuxError::persistence(format!(
+                    "Failed to read scrollback dictionary: {}",
+                    e
+                )))
+            }
+        };
+
+        Ok(Self {
+            dir,
+            dictionary,
+            samples: Vec::new(),
+        })
+    }
+
+    /// Whether blocks are being compressed with a trained dictionary
+    pub fn has_dictionary(&self) -> bool {
+        self.dictionary.is_some()
+    }
+
+    /// Path of a block, rejecting IDs that aren't a BLAKE3 hex digest
+    fn block_path(&self, id: &str) -> Result<PathBuf> {
+        if id.len() != 64 || !id.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(CcmuxError::persistence(format!(
+                "Invalid scrollback block ID '{}'",
+                id
+            )));
+        }
+        Ok(self.dir.join(&id[..2]).join(format!("{}.zst", id)))
+    }
+
+    /// Store lines as a block, returning its reference
+    ///
+    /// Nothing is written if an identical block is already stored.
+    pub fn put(&mut self, lines: &[&str]) -> Result<BlockRef> {
+        let raw = encode_lines(lines);
+        let block = BlockRef {
+            id: blake3::hash(&raw).to_hex().to_string(),
+            line_count: lines.len() as u32,
+        };
+
+        let path = self.block_path(&block.id)?;
+        if path.exists() {
+            return Ok(block);
+        }
+
+        let data = self.compress(&raw)?;
+        write_atomic(&path, &data)?;
+        debug!(
+            "Stored scrollback block {} ({} lines, {} -> {} bytes)",
+            block.id,
+            block.line_count,
+            raw.len(),
+            data.len()
+        );
+
+        if self.dictionary.is_none() {
+            self.samples.push(raw);
+            if self.samples.len() >= DICTIONARY_SAMPLES {
+                self.train_dictionary();
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Read a block's lines
+    pub fn get(&self, block: &BlockRef) -> Result<Vec<String>> {
+        let path = self.block_path(&block.id)?;
+        let data = fs::read(&path).map_err(|e| CcmuxError::persistence(format!(
+            "Failed to read scrollback block {}: {}",
+            block.id, e
+        )))?;
+
+        let raw = self.decompress(&data)?;
+        if blake3::hash(&raw).to_hex().as_str() != block.id {
+            return Err(CcmuxError::persistence(format!(
+                "Scrollback block {} is corrupt",
+                block.id
+            )));
+        }
+
+        let content = String::from_utf8(raw).map_err(|e| {
+            CcmuxError::persistence(format!("Invalid UTF-8 in scrollback block: {}", e))
+        })?;
+        Ok(content.split_terminator('\n').map(String::from).collect())
+    }
+
+    /// Delete every block not in `live`, returning how many were removed
+    pub fn retain(&self, live: &HashSet<&str>) -> Result<usize> {
+        let mut removed = 0;
+        for path in self.block_files()? {
+            let id = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or_default();
+            if !live.contains(id) && fs::remove_file(&path).is_ok() {
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Total size of the stored blocks and dictionary in bytes
+    pub fn disk_usage(&self) -> Result<u64> {
+        let dictionary = self.dictionary.as_ref().map_or(0, |d| d.raw.len() as u64);
+        let blocks = self
+            .block_files()?
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum::<u64>();
+        Ok(dictionary + blocks)
+    }
+
+    fn block_files(&self) -> Result<Vec<PathBuf>> {
+        let read_dir = |dir: &Path| {
+            fs::read_dir(dir).map_err(|e| {
+                CcmuxError::persistence(format!("Failed to read block directory: {}", e))
+            })
+        };
+
+        let mut files = Vec::new();
+        for shard in read_dir(&self.dir)?.flatten() {
+            if !shard.path().is_dir() {
+                continue;
+            }
+            for entry in read_dir(&shard.path())?.flatten() {
+                let path = entry.path();
+                if path.extension().is_some_and(|ext| ext == "zst") {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
+    fn compress(&self, raw: &[u8]) -> Result<Vec<u8>> {
+        let compress_error =
+            |e: std::io::Error| CcmuxError::persistence(format!("Zstd compression failed: {}", e));
+
+        let mut data = Vec::with_capacity(raw.len() / 4);
+        let encoder = match &self.dictionary {
+            Some(dictionary) => {
+                data.push(BLOCK_WITH_DICTIONARY);
+                zstd::Encoder::with_prepared_dictionary(data, &dictionary.encoder)
+            }
+            None => {
+                data.push(BLOCK_PLAIN);
+                zstd::Encoder::new(data, ZSTD_LEVEL)
+            }
+        };
+        let mut encoder = encoder.map_err(compress_error)?;
+        encoder.write_all(raw).map_err(compress_error)?;
+        encoder.finish().map_err(compress_error)
+    }
+
+    fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let decompress_error = |e: std::io::Error| {
+            CcmuxError::persistence(format!("Zstd decompression failed: {}", e))
+        };
+
+        let mut raw = Vec::new();
+        let read = match data.first() {
+            Some(&BLOCK_PLAIN) => zstd::Decoder::new(&data[1..])
+                .and_then(|mut decoder| decoder.read_to_end(&mut raw)),
+            Some(&BLOCK_WITH_DICTIONARY) => {
+                let dictionary = self.dictionary.as_ref().ok_or_else(|| {
+                    CcmuxError::persistence("Scrollback block needs the missing dictionary")
+                })?;
+                zstd::Decoder::with_prepared_dictionary(&data[1..], &dictionary.decoder)
+                    .and_then(|mut decoder| decoder.read_to_end(&mut raw))
+            }
+            _ => return Err(CcmuxError::persistence("Unknown scrollback block format")),
+        };
+        read.map_err(decompress_error)?;
+        Ok(raw)
+    }
+
+    fn train_dictionary(&mut self) {
+        let samples = std::mem::take(&mut self.samples);
+        let dictionary = match zstd::dict::from_samples(&samples, DICTIONARY_SIZE) {
+            Ok(dictionary) => dictionary,
+            Err(e) => {
+                // Typically too little distinct output; retry with more samples
+                debug!("Scrollback dictionary training failed: {}", e);
+                self.samples = samples;
+                self.samples.drain(..DICTIONARY_SAMPLES / 2);
+                return;
+            }
+        };
+
+        match write_atomic(&self.dir.join(DICTIONARY_FILE), &dictionary) {
+            Ok(()) => {
+                info!(
+                    "Trained {} byte scrollback dictionary from {} blocks",
+                    dictionary.len(),
+                    samples.len()
+                );
+                self.dictionary = Some(Dictionary::new(dictionary));
+            }
+            Err(e) => warn!("Failed to save scrollback dictionary: {}", e),
+        }
+    }
+}
+
+/// A trained dictionary, digested once for compression and decompression
+struct Dictionary {
+    raw: Vec<u8>,
+    encoder: zstd::dict::EncoderDictionary<'static>,
+    decoder: zstd::dict::DecoderDictionary<'static>,
+}
+
+impl Dictionary {
+    fn new(raw: Vec<u8>) -> Self {
+        Self {
+            encoder: zstd::dict::EncoderDictionary::copy(&raw, ZSTD_LEVEL),
+            decoder: zstd::dict::DecoderDictionary::copy(&raw),
+            raw,
+        }
+    }
+}
+
+/// Block content: every line followed by a newline
+fn encode_lines(lines: &[&str]) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(lines.iter().map(|line| line.len() + 1).sum());
+    for line in lines {
+        raw.extend_from_slice(line.as_bytes());
+        raw.push(b'\n');
+    }
+    raw
+}
+
+/// Write via a temporary file and rename, syncing before the rename
+fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            CcmuxError::persistence(format!("Failed to create block directory: {}", e))
+        })?;
+    }
+
+    let temp_path = path.with_extension("tmp");
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(&temp_path)?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    };
+    write().map_err(|e| {
+        CcmuxError::persistence(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+/// A pane's blocks, oldest first
+#[derive(Debug, Default)]
+struct PaneBlocks {
+    /// Full blocks with the sequence number of their first line
+    sealed: VecDeque<(u64, BlockRef)>,
+    /// Sequence number of the first line not in a sealed block
+    next_seq: u64,
+    /// Lines after the sealed blocks; replaced on every update
+    tail: Option<BlockRef>,
+    /// Buffer's `total_pushed` at the last update
+    seen: Option<u64>,
+}
+
+impl PaneBlocks {
+    fn refs(&self) -> Vec<BlockRef> {
+        self.sealed
+            .iter()
+            .map(|(_, block)| block.clone())
+            .chain(self.tail.clone())
+            .collect()
+    }
+}
+
+/// Keeps every pane's scrollback stored as blocks between checkpoints
+pub struct ScrollbackBlocks {
+    store: BlockStore,
+    /// Most recent lines to keep per pane (0 disables scrollback persistence)
+    max_lines: usize,
+    panes: HashMap<Uuid, PaneBlocks>,
+}
+
+impl ScrollbackBlocks {
+    /// Open the block store in `dir`
+    pub fn open(dir: impl AsRef<Path>, max_lines: usize) -> Result<Self> {
+        Ok(Self {
+            store: BlockStore::open(dir)?,
+            max_lines,
+            panes: HashMap::new(),
+        })
+    }
+
+    /// The underlying block store
+    pub fn store(&self) -> &BlockStore {
+        &self.store
+    }
+
+    /// Store a pane's new output and return its current blocks
+    ///
+    /// Only lines added since the last update are compressed: full blocks
+    /// are sealed and never rewritten, and the remainder goes into a small
+    /// tail block that the next update replaces.
+    pub fn update(&mut self, pane_id: Uuid, buffer: &ScrollbackBuffer) -> Result<Vec<BlockRef>> {
+        if self.max_lines == 0 {
+            return Ok(Vec::new());
+        }
+
+        let pushed = buffer.total_pushed();
+        let pane = self.panes.entry(pane_id).or_default();
+        if pane.seen == Some(pushed) {
+            return Ok(pane.refs());
+        }
+        if pushed < pane.next_seq {
+            // Not the buffer these blocks were made from
+            *pane = PaneBlocks::default();
+        }
+
+        // Forget blocks whose lines are all older than what we keep
+        let start = buffer
+            .first_sequence()
+            .max(pushed.saturating_sub(self.max_lines as u64));
+        while let Some((first, block)) = pane.sealed.front() {
+            if first + block.line_count as u64 > start {
+                break;
+            }
+            pane.sealed.pop_front();
+        }
+        pane.next_seq = pane.next_seq.max(start);
+        pane.tail = None;
+
+        let pending: Vec<&str> = buffer.lines_since(pane.next_seq).collect();
+        for chunk in pending.chunks(BLOCK_LINES) {
+            let block = self.store.put(chunk)?;
+            if chunk.len() == BLOCK_LINES {
+                pane.sealed.push_back((pane.next_seq, block));
+                pane.next_seq += BLOCK_LINES as u64;
+            } else {
+                pane.tail = Some(block);
+            }
+        }
+        pane.seen = Some(pushed);
+
+        Ok(pane.refs())
+    }
+
+    /// Update every pane's blocks, forgetting panes that no longer exist
+    ///
+    /// Panes whose blocks cannot be written are left out and logged.
+    pub fn capture(&mut self, session_manager: &SessionManager) -> HashMap<Uuid, Vec<BlockRef>> {
+        let mut refs = HashMap::new();
+        for session in session_manager.list_sessions() {
+            for pane in session.windows().flat_map(|window| window.panes()) {
+                match self.update(pane.id(), pane.scrollback()) {
+                    Ok(blocks) if blocks.is_empty() => {}
+                    Ok(blocks) => {
+                        refs.insert(pane.id(), blocks);
+                    }
+                    Err(e) => warn!("Failed to persist scrollback for pane {}: {}", pane.id(), e),
+                }
+            }
+        }
+
+        self.panes.retain(|pane_id, _| refs.contains_key(pane_id));
+        refs
+    }
+
+    /// Current blocks of every pane, as of the last capture
+    pub fn current(&self) -> HashMap<Uuid, Vec<BlockRef>> {
+        self.panes
+            .iter()
+            .map(|(pane_id, pane)| (*pane_id, pane.refs()))
+            .filter(|(_, refs)| !refs.is_empty())
+            .collect()
+    }
+
+    /// Read a pane's lines back
+    ///
+    /// This can be up to a block more than the configured number of lines.
+    pub fn load(&self, blocks: &[BlockRef]) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+        for block in blocks {
+            lines.extend(self.store.get(block)?);
+        }
+        Ok(lines)
+    }
+
+    /// Take over blocks loaded into a pane's empty scrollback
+    ///
+    /// The full blocks become the pane's sealed blocks, so they are not
+    /// written again by the next update.
+    pub fn adopt(&mut self, pane_id: Uuid, blocks: &[BlockRef]) {
+        let mut pane = PaneBlocks::default();
+        for block in blocks {
+            if block.line_count as usize != BLOCK_LINES {
+                break;
+            }
+            pane.sealed.push_back((pane.next_seq, block.clone()));
+            pane.next_seq += BLOCK_LINES as u64;
+        }
+        self.panes.insert(pane_id, pane);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn output_line(pane: usize, n: u64) -> String {
+        format!(
+            "\x1b[32m[{:05}]\x1b[0m pane-{} compiling crate_{} v0.{}.{} (/src/crate_{}) in {}ms",
+            n,
+            pane,
+            n % 37,
+            n % 7,
+            n % 13,
+            n % 37,
+            (n * 7919) % 1000
+        )
+    }
+
+    fn push_lines(buffer: &mut ScrollbackBuffer, pane: usize, count: u64) {
+        for _ in 0..count {
+            let n = buffer.total_pushed();
+            buffer.push_line(output_line(pane, n));
+        }
+    }
+
+    #[test]
+    fn test_block_roundtrip_and_dedup() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = BlockStore::open(temp_dir.path()).unwrap();
+
+        let lines = ["first", "", "\x1b[1mbold\x1b[0m \u{1F600}"];
+        let block = store.put(&lines).unwrap();
+        assert_eq!(block.line_count, 3);
+        assert_eq!(store.get(&block).unwrap(), lines);
+
+        // Same content, same block
+        assert_eq!(store.put(&lines).unwrap(), block);
+        assert_eq!(store.block_files().unwrap().len(), 1);
+
+        let other = store.put(&["other"]).unwrap();
+        store.retain(&HashSet::from([block.id.as_str()])).unwrap();
+        assert!(store.get(&block).is_ok());
+        assert!(store.get(&other).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_block_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = BlockStore::open(temp_dir.path()).unwrap();
+        let block = store.put(&["original"]).unwrap();
+
+        let tampered = store.compress(b"tampered\n").unwrap();
+        fs::write(store.block_path(&block.id).unwrap(), tampered).unwrap();
+        assert!(store.get(&block).is_err());
+    }
+
+    #[test]
+    fn test_invalid_block_id_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let store = BlockStore::open(temp_dir.path()).unwrap();
+        for id in ["", "a", "../etc/passwd", &"z".repeat(64)] {
+            let block = BlockRef {
+                id: id.to_string(),
+                line_count: 1,
+            };
+            assert!(store.get(&block).is_err());
+        }
+    }
+
+    #[test]
+    fn test_update_only_writes_new_output() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut blocks = ScrollbackBlocks::open(temp_dir.path(), 10_000).unwrap();
+        let pane_id = Uuid::new_v4();
+        let mut buffer = ScrollbackBuffer::new(10_000);
+
+        push_lines(&mut buffer, 0, BLOCK_LINES as u64 * 2 + 10);
+        let first = blocks.update(pane_id, &buffer).unwrap();
+        assert_eq!(first.len(), 3);
+        assert_eq!(first[2].line_count, 10);
+
+        // Idle pane: nothing changes
+        assert_eq!(blocks.update(pane_id, &buffer).unwrap(), first);
+
+        push_lines(&mut buffer, 0, BLOCK_LINES as u64);
+        let second = blocks.update(pane_id, &buffer).unwrap();
+        assert_eq!(second.len(), 4);
+        assert_eq!(second[..2], first[..2]);
+        assert_eq!(second[3].line_count, 10);
+
+        let restored = blocks.load(&second).unwrap();
+        let expected: Vec<String> = buffer.get_lines().map(String::from).collect();
+        assert_eq!(restored, expected);
+    }
+
+    #[test]
+    fn test_update_drops_blocks_beyond_max_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let max_lines = BLOCK_LINES + 100;
+        let mut blocks = ScrollbackBlocks::open(temp_dir.path(), max_lines).unwrap();
+        let pane_id = Uuid::new_v4();
+        let mut buffer = ScrollbackBuffer::new(10_000);
+
+        push_lines(&mut buffer, 0, BLOCK_LINES as u64 * 4);
+        let refs = blocks.update(pane_id, &buffer).unwrap();
+        assert_eq!(refs.len(), 2);
+
+        let restored = blocks.load(&refs).unwrap();
+        assert!(restored.len() >= max_lines);
+        assert_eq!(restored.last().map(String::as_str), buffer.get_lines().last());
+    }
+
+    #[test]
+    fn test_adopted_blocks_are_not_rewritten() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut blocks = ScrollbackBlocks::open(temp_dir.path(), 10_000).unwrap();
+        let mut buffer = ScrollbackBuffer::new(10_000);
+        push_lines(&mut buffer, 0, BLOCK_LINES as u64 + 10);
+        let refs = blocks.update(Uuid::new_v4(), &buffer).unwrap();
+
+        // Restore into a fresh pane after a restart
+        let mut blocks = ScrollbackBlocks::open(temp_dir.path(), 10_000).unwrap();
+        let pane_id = Uuid::new_v4();
+        let mut restored = ScrollbackBuffer::new(10_000);
+        for line in blocks.load(&refs).unwrap() {
+            restored.push_line(line);
+        }
+        blocks.adopt(pane_id, &refs);
+        fs::remove_file(blocks.store.block_path(&refs[1].id).unwrap()).unwrap();
+
+        push_lines(&mut restored, 0, 5);
+        let updated = blocks.update(pane_id, &restored).unwrap();
+        assert_eq!(updated[0], refs[0]);
+        assert_eq!(updated[1].line_count, 15);
+        assert_eq!(blocks.store.block_files().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_dictionary_is_trained_and_used() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut store = BlockStore::open(temp_dir.path()).unwrap();
+
+        let mut written = Vec::new();
+        for i in 0..DICTIONARY_SAMPLES as u64 * 2 {
+            let lines: Vec<String> = (0..32).map(|n| output_line(1, i * 32 + n)).collect();
+            let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+            written.push((store.put(&lines).unwrap(), lines.join("\n")));
+        }
+        assert!(store.has_dictionary());
+
+        // Blocks from before and after training both read back, also after reopening
+        let store = BlockStore::open(temp_dir.path()).unwrap();
+        assert!(store.has_dictionary());
+        for (block, content) in written {
+            assert_eq!(store.get(&block).unwrap().join("\n"), content);
+        }
+    }
+
+    /// Checkpoint cost with 24 chatty panes: re-compressing every pane's
+    /// whole scrollback into the checkpoint vs. incremental blocks.
+    ///
+    /// Run with:
+    /// `cargo test --release -p fugue-server bench_checkpoint_scrollback -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_checkpoint_scrollback() {
+        use crate::persistence::scrollback::{ScrollbackCapture, ScrollbackConfig};
+        use crate::persistence::types::{
+            CompressionMethod, PaneSnapshot, ScrollbackSnapshot, SessionSnapshot, WindowSnapshot,
+        };
+        use crate::persistence::{CheckpointConfig, CheckpointManager};
+        use fugue_protocol::PaneState;
+        use std::time::{Duration, Instant};
+
+        const PANES: usize = 24;
+        const MAX_LINES: usize = 10_000;
+        const LINES_PER_ROUND: u64 = 500;
+        const ROUNDS: usize = 30;
+
+        fn dir_size(dir: &Path) -> u64 {
+            fs::read_dir(dir)
+                .unwrap()
+                .flatten()
+                .map(|entry| match entry.metadata().unwrap() {
+                    m if m.is_dir() => dir_size(&entry.path()),
+                    m => m.len(),
+                })
+                .sum()
+        }
+
+        let session_id = Uuid::new_v4();
+        let window_id = Uuid::new_v4();
+        let pane_ids: Vec<Uuid> = (0..PANES).map(|_| Uuid::new_v4()).collect();
+        let mut buffers: Vec<ScrollbackBuffer> =
+            (0..PANES).map(|_| ScrollbackBuffer::new(MAX_LINES)).collect();
+
+        let sessions = |scrollback: &mut dyn FnMut(usize) -> Option<ScrollbackSnapshot>| {
+            let panes = pane_ids
+                .iter()
+                .enumerate()
+                .map(|(index, id)| PaneSnapshot {
+                    id: *id,
+                    window_id,
+                    index,
+                    cols: 120,
+                    rows: 40,
+                    state: PaneState::Normal,
+                    name: None,
+                    title: None,
+                    cwd: None,
+                    created_at: 0,
+                    scrollback: scrollback(index),
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
+                })
+                .collect();
+            vec![SessionSnapshot {
+                id: session_id,
+                name: "bench".to_string(),
+                windows: vec![WindowSnapshot {
+                    id: window_id,
+                    session_id,
+                    name: "agents".to_string(),
+                    index: 0,
+                    panes,
+                    active_pane_id: None,
+                    created_at: 0,
+                    layout: None,
+                }],
+                active_window_id: None,
+                created_at: 0,
+                metadata: HashMap::new(),
+                environment: HashMap::new(),
+                tags: HashSet::new(),
+            }]
+        };
+
+        let config = CheckpointConfig::default();
+        let retained = config.max_checkpoints;
+
+        let before_dir = TempDir::new().unwrap();
+        let mut before = CheckpointManager::new(before_dir.path(), config.clone()).unwrap();
+        let capture = ScrollbackCapture::new(ScrollbackConfig {
+            max_lines: MAX_LINES,
+            compression: CompressionMethod::Zstd,
+            zstd_level: ZSTD_LEVEL,
+        });
+
+        let after_dir = TempDir::new().unwrap();
+        let mut after =
+            CheckpointManager::new(after_dir.path().join("checkpoints"), config).unwrap();
+        let mut blocks =
+            ScrollbackBlocks::open(after_dir.path().join("scrollback"), MAX_LINES).unwrap();
+        let mut history: VecDeque<HashMap<Uuid, Vec<BlockRef>>> = VecDeque::new();
+
+        let (mut before_total, mut after_total) = (Duration::ZERO, Duration::ZERO);
+        println!("round   full re-compress (time, size)   blocks (time, size)");
+        for round in 1..=ROUNDS {
+            for (pane, buffer) in buffers.iter_mut().enumerate() {
+                push_lines(buffer, pane, LINES_PER_ROUND);
+            }
+
+            let start = Instant::now();
+            let path = before
+                .create(sessions(&mut |index| {
+                    let lines: Vec<String> =
+                        buffers[index].get_lines().map(String::from).collect();
+                    Some(capture.capture(&lines).unwrap())
+                }))
+                .unwrap();
+            let before_time = start.elapsed();
+            let before_size = fs::metadata(&path).unwrap().len();
+            before_total += before_time;
+
+            let start = Instant::now();
+            let refs: HashMap<Uuid, Vec<BlockRef>> = pane_ids
+                .iter()
+                .zip(&buffers)
+                .map(|(id, buffer)| (*id, blocks.update(*id, buffer).unwrap()))
+                .collect();
+            let path = after
+                .create_with_scrollback(sessions(&mut |_| None), refs.clone())
+                .unwrap();
+            history.push_back(refs);
+            if history.len() > retained {
+                history.pop_front();
+            }
+            let live: HashSet<&str> = history
+                .iter()
+                .flat_map(|refs| refs.values().flatten())
+                .map(|block| block.id.as_str())
+                .collect();
+            blocks.store().retain(&live).unwrap();
+            let after_time = start.elapsed();
+            let after_size = fs::metadata(&path).unwrap().len();
+            after_total += after_time;
+
+            if round % 5 == 0 {
+                println!(
+                    "{:>5}   {:>10.1?} {:>12} B   {:>10.1?} {:>9} B",
+                    round, before_time, before_size, after_time, after_size
+                );
+            }
+        }
+
+        println!(
+            "total checkpoint time: full re-compress {:.1?}, blocks {:.1?}",
+            before_total, after_total
+        );
+        println!(
+            "state on disk: full re-compress {} B, blocks {} B (dictionary: {})",
+            dir_size(before_dir.path()),
+            dir_size(after_dir.path()),
+            blocks.store().has_dictionary()
+        );
+    }
+}
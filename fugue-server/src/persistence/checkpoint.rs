@@ -6,14 +6,19 @@
 // Scaffolding for crash recovery feature - not all methods are wired up yet
 #![allow(dead_code)]
 
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use tracing::{debug, info, warn};
+use uuid::Uuid;
 
-use super::types::{Checkpoint, SessionSnapshot, CHECKPOINT_MAGIC, CHECKPOINT_VERSION};
+use super::types::{
+    BlockRef, Checkpoint, CheckpointV2, CheckpointV3, SessionSnapshot, CHECKPOINT_MAGIC,
+    CHECKPOINT_VERSION,
+};
 use crate::observability::Metrics;
 use fugue_utils::{CcmuxError, Result};
 
@@ -121,6 +126,15 @@ impl CheckpointManager {
 
     /// Create a new checkpoint
     pub fn create(&mut self, sessions: Vec<SessionSnapshot>) -> Result<PathBuf> {
+        self.create_with_scrollback(sessions, HashMap::new())
+    }
+
+    /// Create a new checkpoint referencing each pane's scrollback blocks
+    pub fn create_with_scrollback(
+        &mut self,
+        sessions: Vec<SessionSnapshot>,
+        scrollback: HashMap<Uuid, Vec<BlockRef>>,
+    ) -> Result<PathBuf> {
         self.sequence += 1;
         let sequence = self.sequence;
         let path = self.checkpoint_path(sequence);
@@ -133,6 +147,7 @@ impl CheckpointManager {
             sequence,
             timestamp: Self::unix_timestamp(),
             sessions,
+            scrollback,
         };
 
         self.write_checkpoint(&path, &checkpoint)?;
@@ -152,6 +167,20 @@ impl CheckpointManager {
     /// Write a checkpoint to disk
     fn write_checkpoint(&self, path: &Path, checkpoint: &Checkpoint) -> Result<()> {
         let start = std::time::Instant::now();
+        let size = Self::write_file(path, checkpoint)?;
+
+        // Record metrics
+        Metrics::global().record_checkpoint(start.elapsed().as_millis() as u64, size);
+
+        debug!("Wrote checkpoint to {}", path.display());
+
+        Ok(())
+    }
+
+    /// Atomically write a checkpoint file at any path, returning its data size
+    ///
+    /// Also used for named snapshots, which share the checkpoint format.
+    pub fn write_file(path: &Path, checkpoint: &Checkpoint) -> Result<u64> {
         // Write to a temporary file first, then rename for atomicity
         let temp_path = path.with_extension("tmp");
 
@@ -191,12 +220,7 @@ impl CheckpointManager {
             io_error(format!("Failed to rename checkpoint file: {}", e))
         })?;
 
-        // Record metrics
-        Metrics::global().record_checkpoint(start.elapsed().as_millis() as u64, data.len() as u64);
-
-        debug!("Wrote checkpoint to {}", path.display());
-
-        Ok(())
+        Ok(data.len() as u64)
     }
 
     /// Load the most recent valid checkpoint
@@ -226,6 +250,11 @@ impl CheckpointManager {
 
     /// Load a specific checkpoint file
     pub fn load_checkpoint(&self, path: &Path) -> Result<Checkpoint> {
+        Self::read_file(path)
+    }
+
+    /// Read a checkpoint file at any path
+    pub fn read_file(path: &Path) -> Result<Checkpoint> {
         let file = File::open(path).map_err(|e| {
             io_error(format!("Failed to open checkpoint: {}", e))
         })?;
@@ -251,9 +280,7 @@ impl CheckpointManager {
         })?;
 
         // Deserialize
-        let checkpoint: Checkpoint = bincode::deserialize(&data).map_err(|e| {
-            serialization_error(format!("Failed to deserialize checkpoint: {}", e))
-        })?;
+        let checkpoint = Self::decode_checkpoint(&data)?;
 
         // Validate version
         if checkpoint.version > CHECKPOINT_VERSION {
@@ -273,6 +300,43 @@ impl CheckpointManager {
         Ok(checkpoint)
     }
 
+    /// Decode checkpoint data, migrating older formats to the current one
+    ///
+    /// The version is the first serialized field, so it can be read before
+    /// choosing which layout to decode the rest with.
+    fn decode_checkpoint(data: &[u8]) -> Result<Checkpoint> {
+        let version = data
+            .get(..4)
+            .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .ok_or_else(|| serialization_error("Checkpoint data is truncated"))?;
+
+        if version < 3 {
+            let old: CheckpointV2 = bincode::deserialize(data).map_err(|e| {
+                serialization_error(format!(
+                    "Failed to deserialize version {} checkpoint: {}",
+                    version, e
+                ))
+            })?;
+            info!(
+                "Migrating checkpoint from version {} to {}",
+                version, CHECKPOINT_VERSION
+            );
+            return Ok(old.into());
+        }
+
+        if version == 3 {
+            let old: CheckpointV3 = bincode::deserialize(data).map_err(|e| {
+                serialization_error(format!("Failed to deserialize version 3 checkpoint: {}", e))
+            })?;
+            info!("Migrating checkpoint from version 3 to {}", CHECKPOINT_VERSION);
+            return Ok(old.into());
+        }
+
+        bincode::deserialize(data).map_err(|e| {
+            serialization_error(format!("Failed to deserialize checkpoint: {}", e))
+        })
+    }
+
     /// List all valid checkpoint files in the directory, sorted by sequence
     pub fn list_checkpoints(&self) -> Result<Vec<PathBuf>> {
         let mut checkpoints = Vec::new();
@@ -424,7 +488,7 @@ impl CheckpointManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use fugue_protocol::PaneState;
     use tempfile::TempDir;
     use uuid::Uuid;
@@ -466,14 +530,19 @@ mod tests {
                     cwd: Some("/home/user".to_string()),
                     created_at: 12345,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 12345,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 12345,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         }
     }
 
@@ -561,6 +630,7 @@ mod tests {
             timestamp: 12345,
             sequence: 1,
             sessions: vec![session],
+            scrollback: HashMap::new(),
         };
 
         assert!(manager.validate(&checkpoint).is_ok());
@@ -581,6 +651,7 @@ mod tests {
             timestamp: 12345,
             sequence: 1,
             sessions: vec![session],
+            scrollback: HashMap::new(),
         };
 
         assert!(manager.validate(&checkpoint).is_err());
@@ -598,6 +669,7 @@ mod tests {
             timestamp: 12345,
             sequence: 1,
             sessions: vec![session],
+            scrollback: HashMap::new(),
         };
 
         assert!(manager.validate(&checkpoint).is_err());
@@ -0,0 +1,599 @@
+//! Offline inspection and repair of persisted state
+//!
+//! Used by `fugue-server state` while no daemon is running. Opening the WAL
+//! through okaywal would recover and checkpoint its segments, so segment files
+//! are parsed here directly, verifying each chunk's CRC along the way.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use super::checkpoint::{CheckpointConfig, CheckpointManager};
+use super::recovery::RecoveryManager;
+use super::types::{Checkpoint, RecoveryState, WalEntry};
+use super::wal::{replay_start, Wal, WalConfig};
+use fugue_utils::{CcmuxError, Result};
+
+/// Magic bytes at the start of every okaywal segment
+const SEGMENT_MAGIC: &[u8; 3] = b"okw";
+/// Marker byte starting an entry
+const NEW_ENTRY: u8 = 1;
+/// Marker byte starting a chunk of an entry
+const CHUNK: u8 = 2;
+/// Marker byte ending an entry
+const END_OF_ENTRY: u8 = 3;
+
+/// A WAL segment file read from disk
+#[derive(Debug)]
+pub struct WalSegment {
+    /// Segment file path
+    pub path: PathBuf,
+    /// Whether okaywal already checkpointed the segment (skipped on recovery)
+    pub checkpointed: bool,
+    /// Entries in the segment, in order
+    pub entries: Vec<WalRecord>,
+    /// Whether the segment ends in an entry that was never finished
+    pub torn_tail: bool,
+}
+
+impl WalSegment {
+    /// File name of the segment
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+}
+
+/// A single entry of a WAL segment
+#[derive(Debug)]
+pub struct WalRecord {
+    /// okaywal entry ID
+    pub entry_id: u64,
+    /// The decoded entry, or why it could not be decoded
+    pub entry: std::result::Result<WalEntry, String>,
+}
+
+/// A checkpoint file and the result of loading it
+#[derive(Debug)]
+pub struct CheckpointInfo {
+    /// Checkpoint file path
+    pub path: PathBuf,
+    /// File size in bytes
+    pub size: u64,
+    /// The loaded and validated checkpoint, or why it is unusable
+    pub checkpoint: std::result::Result<Checkpoint, String>,
+}
+
+/// Files removed by a prune
+#[derive(Debug, Default)]
+pub struct PruneReport {
+    /// Checkpoints deleted
+    pub checkpoints: Vec<PathBuf>,
+    /// Checkpointed WAL segments deleted
+    pub segments: Vec<PathBuf>,
+}
+
+/// WAL directory inside a state directory
+pub fn wal_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join("wal")
+}
+
+/// Checkpoint directory inside a state directory
+pub fn checkpoint_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join("checkpoints")
+}
+
+/// Load every checkpoint in the state directory, oldest first
+pub fn read_checkpoints(state_dir: &Path) -> Result<Vec<CheckpointInfo>> {
+    let dir = checkpoint_dir(state_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let manager = CheckpointManager::new(&dir, CheckpointConfig::default())?;
+    let mut checkpoints = Vec::new();
+    for path in manager.list_checkpoints()? {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let checkpoint = manager
+            .load_checkpoint(&path)
+            .and_then(|checkpoint| manager.validate(&checkpoint).map(|_| checkpoint))
+            .map_err(|e| e.to_string());
+        checkpoints.push(CheckpointInfo {
+            path,
+            size,
+            checkpoint,
+        });
+    }
+
+    Ok(checkpoints)
+}
+
+/// Find a loadable checkpoint by sequence, or the newest one
+pub fn find_checkpoint(checkpoints: Vec<CheckpointInfo>, sequence: Option<u64>) -> Result<Option<Checkpoint>> {
+    let mut loaded = checkpoints.into_iter().rev().filter_map(|info| info.checkpoint.ok());
+    match sequence {
+        None => Ok(loaded.next()),
+        Some(sequence) => loaded
+            .find(|checkpoint| checkpoint.sequence == sequence)
+            .map(Some)
+            .ok_or_else(|| {
+                CcmuxError::persistence(format!("No valid checkpoint with sequence {}", sequence))
+            }),
+    }
+}
+
+/// List WAL segment files as (segment ID, path, checkpointed), oldest first
+pub fn list_segments(state_dir: &Path) -> Result<Vec<(u64, PathBuf, bool)>> {
+    let dir = wal_dir(state_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| CcmuxError::persistence(format!("Failed to read WAL directory: {}", e)))?;
+
+    // Segments are named "wal-<id>", with a "-cp" suffix once checkpointed
+    let mut segments: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let mut parts = name.split('-');
+            match (parts.next(), parts.next().and_then(|id| id.parse().ok()), parts.next()) {
+                (Some("wal"), Some(id), suffix) => Some((id, entry.path(), suffix == Some("cp"))),
+                _ => None,
+            }
+        })
+        .collect();
+    segments.sort_by_key(|(id, _, _)| *id);
+
+    Ok(segments)
+}
+
+/// Parse a WAL segment file
+///
+/// Fails if the file cannot be read or is not an okaywal segment. Entries
+/// with a bad CRC or that don't decode are returned as errors.
+pub fn read_segment(path: &Path, id: u64, checkpointed: bool) -> Result<WalSegment> {
+    let data = fs::read(path).map_err(|e| {
+        CcmuxError::persistence(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+
+    if data.len() < 5 || &data[..3] != SEGMENT_MAGIC {
+        return Err(CcmuxError::persistence(format!(
+            "{}: not a WAL segment (bad magic bytes)",
+            path.display()
+        )));
+    }
+    if data[3] != 0 {
+        return Err(CcmuxError::persistence(format!(
+            "{}: unsupported segment format version {}",
+            path.display(),
+            data[3]
+        )));
+    }
+
+    let mut segment = WalSegment {
+        path: path.to_path_buf(),
+        checkpointed,
+        entries: Vec::new(),
+        torn_tail: false,
+    };
+    let mut pos = 5 + usize::from(data[4]);
+
+    // A checkpointed segment may be reused, leaving stale entries with
+    // lower IDs after the live ones; okaywal stops at the first of those
+    while let Some(header) = data.get(pos..pos + 9) {
+        let entry_id = u64::from_le_bytes(header[1..9].try_into().expect("8 bytes"));
+        if header[0] != NEW_ENTRY || entry_id < id {
+            break;
+        }
+        pos += 9;
+
+        let mut payload = Vec::new();
+        let mut crc_ok = true;
+        let finished = loop {
+            match data.get(pos) {
+                Some(&CHUNK) => {
+                    let Some(len) = data.get(pos + 1..pos + 5) else {
+                        break false;
+                    };
+                    let len = u32::from_le_bytes(len.try_into().expect("4 bytes")) as usize;
+                    let start = pos + 5;
+                    let (Some(chunk), Some(crc)) =
+                        (data.get(start..start + len), data.get(start + len..start + len + 4))
+                    else {
+                        break false;
+                    };
+                    crc_ok &= crc32c::crc32c(chunk) == u32::from_le_bytes(crc.try_into().expect("4 bytes"));
+                    payload.extend_from_slice(chunk);
+                    pos = start + len + 4;
+                }
+                Some(&END_OF_ENTRY) => {
+                    pos += 1;
+                    break true;
+                }
+                _ => break false,
+            }
+        };
+
+        if !finished {
+            segment.torn_tail = true;
+            break;
+        }
+
+        let entry = if crc_ok {
+            bincode::deserialize::<WalEntry>(&payload)
+                .map_err(|e| format!("failed to decode entry: {}", e))
+        } else {
+            Err("CRC mismatch".to_string())
+        };
+        segment.entries.push(WalRecord { entry_id, entry });
+    }
+
+    Ok(segment)
+}
+
+/// Read every WAL segment in the state directory, oldest first
+pub fn read_wal(state_dir: &Path) -> Result<Vec<WalSegment>> {
+    list_segments(state_dir)?
+        .into_iter()
+        .map(|(id, path, checkpointed)| read_segment(&path, id, checkpointed))
+        .collect()
+}
+
+/// Entries the server would replay on startup, indexed by WAL sequence
+///
+/// Mirrors recovery: checkpointed segments are skipped, as are entries that
+/// fail their CRC or don't decode.
+pub fn live_entries(segments: &[WalSegment]) -> Vec<WalEntry> {
+    segments
+        .iter()
+        .filter(|segment| !segment.checkpointed)
+        .flat_map(|segment| &segment.entries)
+        .filter_map(|record| record.entry.as_ref().ok().cloned())
+        .collect()
+}
+
+/// Replay WAL entries on top of a checkpoint, stopping after sequence `up_to`
+pub fn replay(checkpoint: Option<&Checkpoint>, entries: &[WalEntry], up_to: Option<u64>) -> RecoveryState {
+    let checkpoint_sequence = checkpoint.map_or(0, |checkpoint| checkpoint.sequence);
+    let mut state = RecoveryState {
+        sessions: checkpoint.map(|checkpoint| checkpoint.sessions.clone()).unwrap_or_default(),
+        last_checkpoint_sequence: checkpoint_sequence,
+        scrollback: checkpoint.map(|checkpoint| checkpoint.scrollback.clone()).unwrap_or_default(),
+        ..Default::default()
+    };
+    let mut session_map: HashMap<Uuid, usize> = state
+        .sessions
+        .iter()
+        .enumerate()
+        .map(|(i, session)| (session.id, i))
+        .collect();
+
+    let Some(start) = replay_start(entries, checkpoint_sequence) else {
+        state.clean_shutdown = true;
+        return state;
+    };
+
+    for (sequence, entry) in entries.iter().enumerate().skip(start) {
+        if up_to.is_some_and(|up_to| sequence as u64 > up_to) {
+            break;
+        }
+        if entry.checkpoint_sequence() == Some(checkpoint_sequence) {
+            continue;
+        }
+        match RecoveryManager::apply_wal_entry(&mut state.sessions, &mut session_map, entry.clone()) {
+            Ok(()) => state.wal_entries_replayed += 1,
+            Err(e) => state.add_warning(format!("WAL entry {}: {}", sequence, e)),
+        }
+    }
+    state.clean_shutdown = state.wal_entries_replayed == 0;
+
+    state
+}
+
+/// Rebuild state as of WAL sequence `sequence`
+///
+/// Starts from the newest checkpoint whose marker is at or before `sequence`
+/// and replays the WAL up to it. Without such a marker, replay starts from
+/// empty state, which is only possible while the WAL still holds its first
+/// entry.
+pub fn state_at(state_dir: &Path, sequence: u64) -> Result<RecoveryState> {
+    let checkpoints: Vec<Checkpoint> = read_checkpoints(state_dir)?
+        .into_iter()
+        .filter_map(|info| info.checkpoint.ok())
+        .collect();
+    let segments = read_wal(state_dir)?;
+    let entries = live_entries(&segments);
+
+    if sequence as usize >= entries.len() {
+        return Err(CcmuxError::persistence(format!(
+            "WAL sequence {} is past the end of the log ({} entries)",
+            sequence,
+            entries.len()
+        )));
+    }
+
+    let base = entries[..=sequence as usize]
+        .iter()
+        .rev()
+        .filter_map(WalEntry::checkpoint_sequence)
+        .find_map(|marker| checkpoints.iter().find(|c| c.sequence == marker));
+
+    match base {
+        Some(checkpoint) => Ok(replay(Some(checkpoint), &entries, Some(sequence))),
+        // Entries before the first marker build on empty state, as long as
+        // the WAL still starts at its first entry
+        None if starts_at_first_entry(&segments) => Ok(replay(None, &entries, Some(sequence))),
+        None => Err(CcmuxError::persistence(format!(
+            "No checkpoint available before WAL sequence {}",
+            sequence
+        ))),
+    }
+}
+
+/// Whether no entries have been checkpointed out of the live WAL
+fn starts_at_first_entry(segments: &[WalSegment]) -> bool {
+    segments
+        .iter()
+        .filter(|segment| !segment.checkpointed)
+        .flat_map(|segment| &segment.entries)
+        .next()
+        .is_some_and(|record| record.entry_id == 1)
+}
+
+/// Delete all but the newest `keep` checkpoints, plus checkpointed WAL segments
+///
+/// `keep` must be at least 1: the segments removed are only redundant while a
+/// checkpoint covering them remains.
+pub fn prune(state_dir: &Path, keep: usize) -> Result<PruneReport> {
+    if keep == 0 {
+        return Err(CcmuxError::persistence(
+            "Pruning must keep at least one checkpoint",
+        ));
+    }
+    let mut report = PruneReport::default();
+
+    let checkpoints = read_checkpoints(state_dir)?;
+    let excess = checkpoints.len().saturating_sub(keep);
+    for info in checkpoints.into_iter().take(excess) {
+        remove_file(&info.path)?;
+        report.checkpoints.push(info.path);
+    }
+
+    for (_, path, checkpointed) in list_segments(state_dir)? {
+        if checkpointed {
+            remove_file(&path)?;
+            report.segments.push(path);
+        }
+    }
+
+    Ok(report)
+}
+
+/// Fold the WAL into a new checkpoint and delete its segments
+///
+/// Returns the new checkpoint's path and the number of segments removed.
+pub fn compact(state_dir: &Path, max_checkpoints: usize) -> Result<(PathBuf, usize)> {
+    let checkpoint = find_checkpoint(read_checkpoints(state_dir)?, None)?;
+    let segments = read_wal(state_dir)?;
+    let state = replay(checkpoint.as_ref(), &live_entries(&segments), None);
+
+    let mut manager = CheckpointManager::new(
+        checkpoint_dir(state_dir),
+        CheckpointConfig {
+            max_checkpoints,
+            ..Default::default()
+        },
+    )?;
+    let path = manager.create_with_scrollback(state.sessions, state.scrollback)?;
+
+    for segment in &segments {
+        remove_file(&segment.path)?;
+    }
+
+    // Start the new WAL with the checkpoint's marker so entries the server
+    // appends later are replayed on top of it
+    let wal = Wal::open(wal_dir(state_dir), WalConfig::default())?;
+    wal.append(&WalEntry::CheckpointMarker {
+        sequence: manager.sequence(),
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    })?;
+    wal.shutdown()?;
+
+    Ok((path, segments.len()))
+}
+
+fn remove_file(path: &Path) -> Result<()> {
+    fs::remove_file(path).map_err(|e| {
+        CcmuxError::persistence(format!("Failed to remove {}: {}", path.display(), e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::types::{BlockRef, SessionSnapshot};
+    use std::collections::HashSet;
+    use tempfile::TempDir;
+
+    fn write_wal(state_dir: &Path, entries: &[WalEntry]) {
+        let wal = Wal::open(wal_dir(state_dir), WalConfig::default()).unwrap();
+        for entry in entries {
+            wal.append(entry).unwrap();
+        }
+        wal.shutdown().unwrap();
+    }
+
+    fn session_created(name: &str) -> WalEntry {
+        WalEntry::SessionCreated {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_read_wal_and_replay() {
+        let dir = TempDir::new().unwrap();
+        write_wal(
+            dir.path(),
+            &[session_created("one"), session_created("two"), session_created("three")],
+        );
+
+        let segments = read_wal(dir.path()).unwrap();
+        let entries = live_entries(&segments);
+        assert_eq!(entries.len(), 3);
+        assert!(segments.iter().all(|s| !s.torn_tail));
+
+        let state = replay(None, &entries, Some(1));
+        assert_eq!(state.wal_entries_replayed, 2);
+        let names: Vec<_> = state.sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_read_segment_detects_corruption() {
+        let dir = TempDir::new().unwrap();
+        write_wal(dir.path(), &[session_created("corrupt-me")]);
+
+        let (id, path, checkpointed) = list_segments(dir.path()).unwrap().remove(0);
+        let mut data = fs::read(&path).unwrap();
+        let offset = data
+            .windows(10)
+            .position(|w| w == b"corrupt-me")
+            .unwrap();
+        data[offset] = b'C';
+        fs::write(&path, &data).unwrap();
+
+        let segment = read_segment(&path, id, checkpointed).unwrap();
+        assert_eq!(segment.entries.len(), 1);
+        assert_eq!(segment.entries[0].entry.as_ref().unwrap_err(), "CRC mismatch");
+
+        fs::write(&path, b"nope").unwrap();
+        assert!(read_segment(&path, id, checkpointed).is_err());
+    }
+
+    #[test]
+    fn test_compact_and_prune() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = CheckpointManager::new(checkpoint_dir(dir.path()), CheckpointConfig::default()).unwrap();
+        let session = SessionSnapshot {
+            id: Uuid::new_v4(),
+            name: "base".to_string(),
+            windows: Vec::new(),
+            active_window_id: None,
+            created_at: 0,
+            metadata: HashMap::new(),
+            environment: HashMap::new(),
+            tags: HashSet::new(),
+        };
+        manager.create(vec![session]).unwrap();
+        write_wal(
+            dir.path(),
+            &[
+                WalEntry::CheckpointMarker { sequence: 1, timestamp: 0 },
+                session_created("after"),
+            ],
+        );
+
+        let (_, removed) = compact(dir.path(), 5).unwrap();
+        assert!(removed > 0);
+
+        // Only the new checkpoint's marker is left in the WAL
+        let entries = live_entries(&read_wal(dir.path()).unwrap());
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].checkpoint_sequence(), Some(2));
+
+        let checkpoints = read_checkpoints(dir.path()).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        let latest = find_checkpoint(checkpoints, None).unwrap().unwrap();
+        assert_eq!(latest.sessions.len(), 2);
+
+        assert!(prune(dir.path(), 0).is_err());
+        assert_eq!(read_checkpoints(dir.path()).unwrap().len(), 2);
+
+        let report = prune(dir.path(), 1).unwrap();
+        assert_eq!(report.checkpoints.len(), 1);
+        let remaining = read_checkpoints(dir.path()).unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].checkpoint.as_ref().unwrap().sequence, 2);
+    }
+
+    #[test]
+    fn test_compact_keeps_scrollback_for_recovery() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = CheckpointManager::new(checkpoint_dir(dir.path()), CheckpointConfig::default()).unwrap();
+        let pane_id = Uuid::new_v4();
+        let blocks = vec![BlockRef {
+            id: "ab".repeat(32),
+            line_count: 10,
+        }];
+        manager
+            .create_with_scrollback(Vec::new(), HashMap::from([(pane_id, blocks.clone())]))
+            .unwrap();
+        write_wal(
+            dir.path(),
+            &[
+                WalEntry::CheckpointMarker { sequence: 1, timestamp: 0 },
+                session_created("after"),
+            ],
+        );
+
+        compact(dir.path(), 5).unwrap();
+
+        let recovered = RecoveryManager::new(dir.path(), CheckpointConfig::default(), WalConfig::default())
+            .unwrap()
+            .recover()
+            .unwrap();
+        assert_eq!(recovered.last_checkpoint_sequence, 2);
+        assert_eq!(recovered.sessions.len(), 1);
+        assert_eq!(recovered.scrollback.get(&pane_id), Some(&blocks));
+    }
+
+    #[test]
+    fn test_state_at_uses_preceding_checkpoint() {
+        let dir = TempDir::new().unwrap();
+        let mut manager = CheckpointManager::new(checkpoint_dir(dir.path()), CheckpointConfig::default()).unwrap();
+        let session = |name: &str| SessionSnapshot {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            windows: Vec::new(),
+            active_window_id: None,
+            created_at: 0,
+            metadata: HashMap::new(),
+            environment: HashMap::new(),
+            tags: HashSet::new(),
+        };
+        manager.create(vec![session("first")]).unwrap();
+        manager.create(vec![session("first"), session("second")]).unwrap();
+        write_wal(
+            dir.path(),
+            &[
+                WalEntry::CheckpointMarker { sequence: 1, timestamp: 0 },
+                session_created("a"),
+                WalEntry::CheckpointMarker { sequence: 2, timestamp: 0 },
+                session_created("b"),
+            ],
+        );
+
+        let names = |state: RecoveryState| -> Vec<String> {
+            state.sessions.into_iter().map(|s| s.name).collect()
+        };
+        assert_eq!(names(state_at(dir.path(), 1).unwrap()), vec!["first", "a"]);
+        assert_eq!(names(state_at(dir.path(), 2).unwrap()), vec!["first", "second"]);
+        assert_eq!(names(state_at(dir.path(), 3).unwrap()), vec!["first", "second", "b"]);
+        assert!(state_at(dir.path(), 4).is_err());
+
+        let fresh = TempDir::new().unwrap();
+        write_wal(fresh.path(), &[session_created("one"), session_created("two")]);
+        assert_eq!(names(state_at(fresh.path(), 0).unwrap()), vec!["one"]);
+    }
+}
@@ -39,41 +39,58 @@
 //! manager.create_checkpoint(sessions)?;
 //! ```
 
+pub mod blocks;
 pub mod checkpoint;
+pub mod inspect;
+pub mod portable;
 pub mod recovery;
 pub mod replay;
 pub mod restoration;
+pub mod resurrect;
 pub mod scrollback;
+pub mod snapshots;
 pub mod types;
 pub mod wal;
 
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use parking_lot::Mutex;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use fugue_protocol::{PaneState, ServerMessage};
+use fugue_protocol::{LayoutNode, PaneState, ServerMessage};
 use fugue_utils::{CcmuxError, Result};
 use replay::ReplayBuffer;
 
+use crate::session::SessionManager;
+
 // Re-exports for public API - allow unused during development
 #[allow(unused_imports)]
+pub use blocks::{BlockStore, ScrollbackBlocks};
+#[allow(unused_imports)]
 pub use checkpoint::{CheckpointConfig, CheckpointManager};
 #[allow(unused_imports)]
+pub use portable::SessionExport;
+#[allow(unused_imports)]
 pub use recovery::{
-    detect_unclean_shutdown, mark_clean_shutdown, mark_server_running, RecoveryManager,
+    detect_unclean_shutdown, mark_clean_shutdown, mark_server_running, running_server_pid,
+    RecoveryManager,
 };
 #[allow(unused_imports)]
 pub use restoration::{
     PaneRestorationResult, RestorationResult, SessionRestorationResult, SessionRestorer,
 };
 #[allow(unused_imports)]
+pub use resurrect::{plan_restore, RestorePlan};
+#[allow(unused_imports)]
 pub use scrollback::{ScrollbackCapture, ScrollbackConfig, ScrollbackRestore};
 #[allow(unused_imports)]
+pub use snapshots::{capture_session, clone_session, SnapshotStore};
+#[allow(unused_imports)]
 pub use types::{
-    Checkpoint, CompressionMethod, PaneSnapshot, RecoveryState, ScrollbackSnapshot,
+    BlockRef, Checkpoint, CompressionMethod, PaneSnapshot, RecoveryState, ScrollbackSnapshot,
     SessionSnapshot, WalEntry, WindowSnapshot, CHECKPOINT_MAGIC, CHECKPOINT_VERSION,
 };
 #[allow(unused_imports)]
@@ -125,6 +142,19 @@ impl From<&crate::config::PersistenceConfig> for PersistenceConfig {
     }
 }
 
+/// Resolve the state directory from configuration
+pub fn resolve_state_dir(config: &crate::config::PersistenceConfig) -> PathBuf {
+    config
+        .state_dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            dirs::data_dir()
+                .unwrap_or_else(|| PathBuf::from("."))
+                .join(DEFAULT_STATE_DIR)
+        })
+}
+
 /// Parse compression method from string
 pub fn parse_compression_method(method: &str) -> CompressionMethod {
     match method.to_lowercase().as_str() {
@@ -155,6 +185,8 @@ pub struct PersistenceManager {
     last_checkpoint_sequence: Mutex<u64>,
     /// Replay buffer for client resync
     replay_buffer: Mutex<ReplayBuffer>,
+    /// Scrollback stored as blocks, referenced from checkpoints
+    scrollback: Mutex<ScrollbackBlocks>,
 }
 
 impl PersistenceManager {
@@ -185,6 +217,11 @@ impl PersistenceManager {
 
         let replay_buffer = Mutex::new(ReplayBuffer::new(config.max_replay_events));
 
+        let scrollback = Mutex::new(ScrollbackBlocks::open(
+            state_dir.join("scrollback"),
+            config.screen_snapshot_lines,
+        )?);
+
         Ok(Self {
             state_dir,
             config,
@@ -192,6 +229,7 @@ impl PersistenceManager {
             last_checkpoint: Mutex::new(SystemTime::now()),
             last_checkpoint_sequence: Mutex::new(0),
             replay_buffer,
+            scrollback,
         })
     }
 
@@ -298,6 +336,12 @@ impl PersistenceManager {
         self.recovery_manager.wal().append(&entry)
     }
 
+    /// Log a session's tags being replaced
+    pub fn log_session_tags_set(&self, session_id: Uuid, tags: HashSet<String>) -> Result<u64> {
+        let entry = WalEntry::SessionTagsSet { session_id, tags };
+        self.recovery_manager.wal().append(&entry)
+    }
+
     /// Log a window creation
     pub fn log_window_created(
         &self,
@@ -331,6 +375,16 @@ impl PersistenceManager {
         self.recovery_manager.wal().append(&entry)
     }
 
+    /// Log a window layout change
+    pub fn log_window_layout_changed(
+        &self,
+        window_id: Uuid,
+        layout: Option<LayoutNode>,
+    ) -> Result<u64> {
+        let entry = WalEntry::WindowLayoutChanged { window_id, layout };
+        self.recovery_manager.wal().append(&entry)
+    }
+
     /// Log active window change
     pub fn log_active_window_changed(
         &self,
@@ -364,6 +418,23 @@ impl PersistenceManager {
         self.recovery_manager.wal().append(&entry)
     }
 
+    /// Log the command a pane's process was launched with
+    pub fn log_pane_launched(
+        &self,
+        id: Uuid,
+        command: impl Into<String>,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    ) -> Result<u64> {
+        let entry = WalEntry::PaneLaunched {
+            id,
+            command: command.into(),
+            args,
+            env,
+        };
+        self.recovery_manager.wal().append(&entry)
+    }
+
     /// Log a pane destruction
     pub fn log_pane_destroyed(&self, id: Uuid, window_id: Uuid) -> Result<u64> {
         let entry = WalEntry::PaneDestroyed { id, window_id };
@@ -412,12 +483,56 @@ impl PersistenceManager {
 
     // ==================== Checkpoint Operations ====================
 
+    /// Store new pane output as scrollback blocks for the next checkpoint
+    ///
+    /// Takes the persistence lock while the caller holds the session
+    /// manager lock, never the other way around.
+    pub fn capture_scrollback(&self, session_manager: &SessionManager) {
+        self.scrollback.lock().capture(session_manager);
+    }
+
+    /// Read back the scrollback of recovered panes
+    ///
+    /// The lines must be pushed into the panes' (empty) scrollback buffers;
+    /// their blocks are then reused instead of being written again.
+    pub fn load_scrollback(
+        &self,
+        refs: &HashMap<Uuid, Vec<BlockRef>>,
+    ) -> HashMap<Uuid, Vec<String>> {
+        let mut scrollback = self.scrollback.lock();
+        let mut loaded = HashMap::new();
+        for (pane_id, blocks) in refs {
+            match scrollback.load(blocks) {
+                Ok(lines) => {
+                    scrollback.adopt(*pane_id, blocks);
+                    loaded.insert(*pane_id, lines);
+                }
+                Err(e) => warn!("Failed to load scrollback for pane {}: {}", pane_id, e),
+            }
+        }
+        loaded
+    }
+
     /// Create a checkpoint with the given sessions
+    ///
+    /// Scrollback is referenced as of the last `capture_scrollback`.
     pub fn create_checkpoint(&mut self, sessions: Vec<SessionSnapshot>) -> Result<PathBuf> {
         let sequence = self.recovery_manager.checkpoint_manager_mut().sequence() + 1;
 
+        let pane_ids: HashSet<Uuid> = sessions
+            .iter()
+            .flat_map(|session| &session.windows)
+            .flat_map(|window| &window.panes)
+            .map(|pane| pane.id)
+            .collect();
+        let mut scrollback = self.scrollback.lock().current();
+        scrollback.retain(|pane_id, _| pane_ids.contains(pane_id));
+
         // Write checkpoint
-        let path = self.recovery_manager.checkpoint_manager_mut().create(sessions)?;
+        let path = self
+            .recovery_manager
+            .checkpoint_manager_mut()
+            .create_with_scrollback(sessions, scrollback)?;
 
         // Write checkpoint marker to WAL
         let marker = WalEntry::CheckpointMarker {
@@ -432,9 +547,55 @@ impl PersistenceManager {
 
         info!("Created checkpoint {} at {}", sequence, path.display());
 
+        self.remove_unreferenced_blocks();
+
         Ok(path)
     }
 
+    /// Delete scrollback blocks that no retained checkpoint references
+    fn remove_unreferenced_blocks(&self) {
+        let checkpoints = self.recovery_manager.checkpoint_manager();
+        let paths = match checkpoints.list_checkpoints() {
+            Ok(paths) => paths,
+            Err(e) => {
+                warn!("Not collecting scrollback blocks: {}", e);
+                return;
+            }
+        };
+
+        let mut referenced = Vec::new();
+        for path in paths {
+            match checkpoints.load_checkpoint(&path) {
+                Ok(checkpoint) => referenced.extend(checkpoint.scrollback.into_values().flatten()),
+                Err(e) => {
+                    // Its blocks may still be needed to recover from it
+                    warn!("Not collecting scrollback blocks, {} unreadable: {}", path.display(), e);
+                    return;
+                }
+            }
+        }
+
+        let live: HashSet<&str> = referenced.iter().map(|block| block.id.as_str()).collect();
+        match self.scrollback.lock().store().retain(&live) {
+            Ok(0) => {}
+            Ok(removed) => debug!("Removed {} unreferenced scrollback blocks", removed),
+            Err(e) => warn!("Failed to collect scrollback blocks: {}", e),
+        }
+    }
+
+    /// Named snapshots stored alongside the checkpoints
+    pub fn snapshots(&self) -> SnapshotStore {
+        SnapshotStore::new(&self.state_dir)
+    }
+
+    /// Rebuild persisted state as of a WAL sequence
+    ///
+    /// Sequences are positions in the live WAL, as shown by
+    /// `fugue-server state dump-wal`.
+    pub fn state_at(&self, sequence: u64) -> Result<RecoveryState> {
+        inspect::state_at(&self.state_dir, sequence)
+    }
+
     /// Check if a checkpoint is due based on interval
     pub fn is_checkpoint_due(&self) -> bool {
         let last = *self.last_checkpoint.lock();
@@ -536,7 +697,6 @@ impl PersistenceManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
     use tempfile::TempDir;
 
     fn create_test_manager() -> (TempDir, PersistenceManager) {
@@ -610,6 +770,7 @@ mod tests {
             created_at: 12345,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let path = manager.create_checkpoint(vec![session]).unwrap();
@@ -723,14 +884,19 @@ mod tests {
                     cwd: None,
                     created_at: 0,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 0,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 0,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         }];
 
         manager.create_checkpoint(sessions.clone()).unwrap();
@@ -793,14 +959,19 @@ mod tests {
                     cwd: None,
                     created_at: 0,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 0,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 0,
             metadata,
             environment: HashMap::new(),
+            tags: HashSet::new(),
         }];
 
         manager.create_checkpoint(sessions).unwrap();
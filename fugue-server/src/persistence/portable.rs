@@ -0,0 +1,211 @@
+//! Portable session export/import
+//!
+//! An export is a versioned JSON or TOML document wrapping a
+//! `SessionSnapshot`, so a session can be recreated on another daemon.
+//! Scrollback is optional and stored as plain text rather than the
+//! compressed form used by checkpoints.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use fugue_protocol::ExportFormat;
+use fugue_utils::{CcmuxError, Result};
+
+use super::snapshots::clone_session;
+use super::types::SessionSnapshot;
+
+/// Value of the `format` field identifying an export document
+pub const EXPORT_FORMAT: &str = "fugue-session";
+
+/// Current export document version
+pub const EXPORT_VERSION: u32 = 1;
+
+/// A session export document
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionExport {
+    /// Always `EXPORT_FORMAT`
+    pub format: String,
+    /// Document version (`EXPORT_VERSION` when written)
+    pub version: u32,
+    /// Export timestamp (Unix seconds)
+    pub exported_at: u64,
+    /// The exported session (compressed scrollback is never included)
+    pub session: SessionSnapshot,
+    /// Scrollback lines by pane ID, when exported with scrollback
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub scrollback: HashMap<Uuid, Vec<String>>,
+}
+
+impl SessionExport {
+    /// Wrap a session snapshot for export
+    pub fn new(mut session: SessionSnapshot, scrollback: HashMap<Uuid, Vec<String>>) -> Self {
+        for pane in session.windows.iter_mut().flat_map(|w| w.panes.iter_mut()) {
+            pane.scrollback = None;
+        }
+
+        Self {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            session,
+            scrollback,
+        }
+    }
+
+    /// Serialize the document
+    pub fn to_document(&self, format: ExportFormat) -> Result<String> {
+        match format {
+            ExportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| CcmuxError::persistence(format!("Failed to encode export: {}", e))),
+            ExportFormat::Toml => toml::to_string_pretty(self)
+                .map_err(|e| CcmuxError::persistence(format!("Failed to encode export: {}", e))),
+        }
+    }
+
+    /// Parse a JSON or TOML document and check its format and version
+    pub fn from_document(document: &str) -> Result<Self> {
+        let export: Self = if document.trim_start().starts_with('{') {
+            serde_json::from_str(document)
+                .map_err(|e| CcmuxError::persistence(format!("Invalid JSON export: {}", e)))?
+        } else {
+            toml::from_str(document)
+                .map_err(|e| CcmuxError::persistence(format!("Invalid TOML export: {}", e)))?
+        };
+
+        if export.format != EXPORT_FORMAT {
+            return Err(CcmuxError::persistence(format!(
+                "Not a session export (format '{}')",
+                export.format
+            )));
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(CcmuxError::persistence(format!(
+                "Export version {} is newer than supported version {}",
+                export.version, EXPORT_VERSION
+            )));
+        }
+
+        Ok(export)
+    }
+
+    /// Copy the session under `name` with fresh IDs
+    ///
+    /// Returns the copy and its scrollback, keyed by the new pane IDs.
+    pub fn instantiate(&self, name: impl Into<String>) -> (SessionSnapshot, HashMap<Uuid, Vec<String>>) {
+        let session = clone_session(&self.session, name);
+
+        // clone_session keeps window and pane order, so pair them up
+        let scrollback = self
+            .session
+            .windows
+            .iter()
+            .flat_map(|w| w.panes.iter())
+            .zip(session.windows.iter().flat_map(|w| w.panes.iter()))
+            .filter_map(|(old, new)| Some((new.id, self.scrollback.get(&old.id)?.clone())))
+            .collect();
+
+        (session, scrollback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::types::{PaneSnapshot, WindowSnapshot};
+    use fugue_protocol::{LayoutNode, PaneState, SplitDirection};
+    use std::collections::HashSet;
+
+    fn exported_session() -> SessionExport {
+        let session_id = Uuid::new_v4();
+        let window_id = Uuid::new_v4();
+        let (left, right) = (Uuid::new_v4(), Uuid::new_v4());
+        let pane = |id, index, command: Option<&str>| PaneSnapshot {
+            id,
+            window_id,
+            index,
+            cols: 80,
+            rows: 24,
+            state: PaneState::Normal,
+            name: None,
+            title: None,
+            cwd: Some("/srv/app".to_string()),
+            created_at: 0,
+            scrollback: None,
+            command: command.map(String::from),
+            args: vec!["-l".to_string()],
+            env: HashMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+        };
+        let session = SessionSnapshot {
+            id: session_id,
+            name: "dev".to_string(),
+            windows: vec![WindowSnapshot {
+                id: window_id,
+                session_id,
+                name: "editor".to_string(),
+                index: 0,
+                panes: vec![pane(left, 0, Some("vim")), pane(right, 1, None)],
+                active_pane_id: Some(left),
+                created_at: 0,
+                layout: Some(LayoutNode::Split {
+                    direction: SplitDirection::Horizontal,
+                    children: vec![
+                        (LayoutNode::pane(left), 0.75),
+                        (LayoutNode::pane(right), 0.25),
+                    ],
+                }),
+            }],
+            active_window_id: Some(window_id),
+            created_at: 0,
+            metadata: HashMap::from([("project".to_string(), "fugue".to_string())]),
+            environment: HashMap::from([("EDITOR".to_string(), "vim".to_string())]),
+            tags: HashSet::from(["worker".to_string()]),
+        };
+        SessionExport::new(
+            session,
+            HashMap::from([(right, vec!["$ cargo test".to_string(), "ok".to_string()])]),
+        )
+    }
+
+    #[test]
+    fn test_export_roundtrip_json_and_toml() {
+        let export = exported_session();
+        for format in [ExportFormat::Json, ExportFormat::Toml] {
+            let document = export.to_document(format).unwrap();
+            let parsed = SessionExport::from_document(&document).unwrap();
+            assert_eq!(parsed, export, "{:?} roundtrip", format);
+        }
+    }
+
+    #[test]
+    fn test_import_rejects_foreign_or_newer_documents() {
+        let mut export = exported_session();
+        export.version = EXPORT_VERSION + 1;
+        let document = export.to_document(ExportFormat::Json).unwrap();
+        assert!(SessionExport::from_document(&document).is_err());
+
+        export.version = EXPORT_VERSION;
+        export.format = "something-else".to_string();
+        let document = export.to_document(ExportFormat::Toml).unwrap();
+        assert!(SessionExport::from_document(&document).is_err());
+
+        assert!(SessionExport::from_document("not a document").is_err());
+    }
+
+    #[test]
+    fn test_instantiate_remaps_scrollback() {
+        let export = exported_session();
+        let (session, scrollback) = export.instantiate("dev-copy");
+
+        assert_eq!(session.name, "dev-copy");
+        assert_ne!(session.id, export.session.id);
+        let right = session.windows[0].panes[1].id;
+        assert_eq!(scrollback.len(), 1);
+        assert_eq!(scrollback[&right], vec!["$ cargo test", "ok"]);
+    }
+}
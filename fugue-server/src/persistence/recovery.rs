@@ -3,7 +3,7 @@
 //! This module handles detecting unclean shutdowns and recovering session state
 //! from checkpoints and WAL entries.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use tracing::{debug, info};
@@ -70,6 +70,7 @@ impl RecoveryManager {
                     cp.sessions.len()
                 );
                 state.last_checkpoint_sequence = cp.sequence;
+                state.scrollback = cp.scrollback;
                 (cp.sessions, cp.sequence)
             }
             None => {
@@ -91,7 +92,7 @@ impl RecoveryManager {
             .collect();
 
         for entry in wal_entries {
-            match self.apply_wal_entry(&mut sessions, &mut session_map, entry) {
+            match Self::apply_wal_entry(&mut sessions, &mut session_map, entry) {
                 Ok(_) => state.wal_entries_replayed += 1,
                 Err(e) => {
                     state.add_warning(format!("Failed to apply WAL entry: {}", e));
@@ -116,8 +117,7 @@ impl RecoveryManager {
     }
 
     /// Apply a WAL entry to the session state
-    fn apply_wal_entry(
-        &self,
+    pub(crate) fn apply_wal_entry(
         sessions: &mut Vec<SessionSnapshot>,
         session_map: &mut HashMap<Uuid, usize>,
         entry: WalEntry,
@@ -143,6 +143,7 @@ impl RecoveryManager {
                     created_at,
                     metadata: HashMap::new(),
                     environment: HashMap::new(),
+                    tags: HashSet::new(),
                 };
 
                 session_map.insert(id, sessions.len());
@@ -189,6 +190,7 @@ impl RecoveryManager {
                         panes: Vec::new(),
                         active_pane_id: None,
                         created_at,
+                        layout: None,
                     };
 
                     sessions[idx].windows.push(window);
@@ -245,6 +247,9 @@ impl RecoveryManager {
                             cwd: None,
                             created_at,
                             scrollback: None,
+                            command: None,
+                            args: Vec::new(),
+                            env: HashMap::new(),
                         };
 
                         window.panes.push(pane);
@@ -347,6 +352,37 @@ impl RecoveryManager {
                     debug!("Applied: SessionEnvironmentSet {} key={}", session_id, key);
                 }
             }
+
+            WalEntry::SessionTagsSet { session_id, tags } => {
+                if let Some(&idx) = session_map.get(&session_id) {
+                    sessions[idx].tags = tags;
+                    debug!("Applied: SessionTagsSet {}", session_id);
+                }
+            }
+
+            WalEntry::WindowLayoutChanged { window_id, layout } => {
+                for session in sessions.iter_mut() {
+                    if let Some(window) = session.windows.iter_mut().find(|w| w.id == window_id) {
+                        window.layout = layout;
+                        debug!("Applied: WindowLayoutChanged {}", window_id);
+                        break;
+                    }
+                }
+            }
+
+            WalEntry::PaneLaunched { id, command, args, env } => {
+                for session in sessions.iter_mut() {
+                    for window in session.windows.iter_mut() {
+                        if let Some(pane) = window.panes.iter_mut().find(|p| p.id == id) {
+                            pane.command = Some(command);
+                            pane.args = args;
+                            pane.env = env;
+                            debug!("Applied: PaneLaunched {}", id);
+                            return Ok(());
+                        }
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -416,6 +452,18 @@ pub fn mark_clean_shutdown(state_dir: impl AsRef<Path>) -> Result<()> {
     Ok(())
 }
 
+/// PID of a live server holding the state directory's lock file, if any
+///
+/// A lock file left behind by a crashed server names a dead process and is
+/// not reported.
+pub fn running_server_pid(state_dir: impl AsRef<Path>) -> Option<u32> {
+    let contents = std::fs::read_to_string(state_dir.as_ref().join(".lock")).ok()?;
+    let pid: u32 = contents.trim().parse().ok()?;
+    // SAFETY: kill(2) with signal 0 only checks that the process exists
+    let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+    alive.then_some(pid)
+}
+
 /// Create lock file to indicate server is running
 pub fn mark_server_running(state_dir: impl AsRef<Path>) -> Result<()> {
     let state_dir = state_dir.as_ref();
@@ -753,6 +801,73 @@ mod tests {
         assert_eq!(state.sessions[0].windows[0].active_pane_id, Some(pane_id));
     }
 
+    #[test]
+    fn test_recovery_tags_layout_and_launch() {
+        let temp_dir = create_test_dir();
+        let state_dir = temp_dir.path().join("state");
+
+        let session_id = Uuid::new_v4();
+        let window_id = Uuid::new_v4();
+        let pane_id = Uuid::new_v4();
+        let layout = fugue_protocol::LayoutNode::pane(pane_id);
+
+        {
+            let manager = create_manager_at(&state_dir);
+
+            manager.wal().append(&WalEntry::SessionCreated {
+                id: session_id,
+                name: "test".to_string(),
+                created_at: 0,
+            }).unwrap();
+
+            manager.wal().append(&WalEntry::WindowCreated {
+                id: window_id,
+                session_id,
+                name: "main".to_string(),
+                index: 0,
+                created_at: 0,
+            }).unwrap();
+
+            manager.wal().append(&WalEntry::PaneCreated {
+                id: pane_id,
+                window_id,
+                index: 0,
+                cols: 80,
+                rows: 24,
+                created_at: 0,
+            }).unwrap();
+
+            manager.wal().append(&WalEntry::SessionTagsSet {
+                session_id,
+                tags: HashSet::from(["worker".to_string()]),
+            }).unwrap();
+
+            manager.wal().append(&WalEntry::WindowLayoutChanged {
+                window_id,
+                layout: Some(layout.clone()),
+            }).unwrap();
+
+            manager.wal().append(&WalEntry::PaneLaunched {
+                id: pane_id,
+                command: "htop".to_string(),
+                args: vec!["-d".to_string(), "10".to_string()],
+                env: HashMap::from([("TERM".to_string(), "xterm".to_string())]),
+            }).unwrap();
+
+            manager.shutdown().unwrap();
+        }
+
+        let manager = create_manager_at(&state_dir);
+        let state = manager.recover().unwrap();
+        let session = &state.sessions[0];
+        assert!(session.tags.contains("worker"));
+        assert_eq!(session.windows[0].layout, Some(layout));
+        let pane = &session.windows[0].panes[0];
+        assert_eq!(pane.command.as_deref(), Some("htop"));
+        assert_eq!(pane.args, vec!["-d", "10"]);
+        assert_eq!(pane.env.get("TERM").map(String::as_str), Some("xterm"));
+    }
+
     #[test]
     fn test_recovery_session_metadata() {
         let temp_dir = create_test_dir();
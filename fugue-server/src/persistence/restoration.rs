@@ -13,13 +13,14 @@ use std::path::Path;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use fugue_protocol::PaneState;
+use fugue_protocol::{PaneState, SplitDirection};
 
-use crate::claude::create_resume_command;
+use crate::config::RestoreConfig;
 use crate::isolation;
 use crate::pty::{PtyConfig, PtyManager};
-use crate::session::{Pane, Session, SessionManager, Window};
+use crate::session::{Pane, PaneLaunch, Session, SessionManager, Window};
 
+use super::resurrect::{plan_restore, RestorePlan};
 use super::types::{PaneSnapshot, RecoveryState, SessionSnapshot, WindowSnapshot};
 
 /// Result of restoring a single pane
@@ -39,6 +40,8 @@ pub struct PaneRestorationResult {
     pub claude_resumed: bool,
     /// The session ID used for Claude resume (if any)
     pub claude_session_id: Option<String>,
+    /// Command line the pane was restored with (`None` when a shell was started)
+    pub restored_command: Option<String>,
 }
 
 /// Result of restoring a session
@@ -110,6 +113,8 @@ impl RestorationResult {
 pub struct SessionRestorer {
     /// Whether to spawn PTYs (can be disabled for testing)
     spawn_ptys: bool,
+    /// Process resurrection policy
+    restore_config: RestoreConfig,
 }
 
 impl Default for SessionRestorer {
@@ -121,12 +126,24 @@ impl Default for SessionRestorer {
 impl SessionRestorer {
     /// Create a new session restorer
     pub fn new() -> Self {
-        Self { spawn_ptys: true }
+        Self {
+            spawn_ptys: true,
+            restore_config: RestoreConfig::default(),
+        }
     }
 
     /// Create a restorer that doesn't spawn PTYs (for testing)
     pub fn without_pty_spawn() -> Self {
-        Self { spawn_ptys: false }
+        Self {
+            spawn_ptys: false,
+            restore_config: RestoreConfig::default(),
+        }
+    }
+
+    /// Use the given process resurrection policy
+    pub fn with_restore_config(mut self, config: RestoreConfig) -> Self {
+        self.restore_config = config;
+        self
     }
 
     /// Restore sessions from recovery state
@@ -197,6 +214,12 @@ impl SessionRestorer {
             snapshot.created_at,
             snapshot.metadata.clone(),
         );
+        for tag in &snapshot.tags {
+            session.add_tag(tag);
+        }
+        for (key, value) in &snapshot.environment {
+            session.set_env(key, value);
+        }
 
         let mut pane_results = Vec::new();
 
@@ -264,6 +287,21 @@ impl SessionRestorer {
         // Set active pane
         window.set_active_pane_id(snapshot.active_pane_id);
 
+        // Restore the split tree, dropping any panes that didn't come back
+        if let Some(mut layout) = snapshot.layout.clone() {
+            for pane_id in layout.pane_ids() {
+                if window.get_pane(pane_id).is_none() {
+                    layout.remove(pane_id);
+                }
+            }
+            if !window.arrange(layout, SplitDirection::Vertical) {
+                warn!(
+                    "Could not restore layout for window {}, using default arrangement",
+                    snapshot.id
+                );
+            }
+        }
+
         (window, pane_results)
     }
 
@@ -281,7 +319,7 @@ impl SessionRestorer {
         );
 
         // Create pane with restored state
-        let pane = Pane::restore(
+        let mut pane = Pane::restore(
             snapshot.id,
             snapshot.window_id,
             snapshot.index,
@@ -293,6 +331,12 @@ impl SessionRestorer {
             snapshot.cwd.clone(),
             snapshot.created_at,
         );
+        let launch = snapshot.command.as_ref().map(|command| PaneLaunch {
+            command: command.clone(),
+            args: snapshot.args.clone(),
+            env: snapshot.env.clone(),
+        });
+        pane.set_launch(launch.clone());
 
         // Determine if we should spawn a PTY
         let should_spawn_pty = self.spawn_ptys && Self::should_spawn_pty(&snapshot.state);
@@ -305,44 +349,41 @@ impl SessionRestorer {
             cwd_restored: false,
             claude_resumed: false,
             claude_session_id: None,
+            restored_command: None,
         };
 
-        // Check if this is a Claude pane with a session ID to resume
-        let claude_session_id = if let PaneState::Agent(ref agent_state) = snapshot.state {
-            if agent_state.is_claude() {
-                agent_state.session_id.clone()
-            } else {
-                None
-            }
-        } else {
-            None
-        };
+        // Decide what the pane runs (agent resume, re-run, configured command or shell)
+        let plan = plan_restore(snapshot, &self.restore_config);
+        result.restored_command = plan.command_line();
 
         // Track Claude resume intent (even if PTY spawning is disabled)
-        if let Some(ref session_id) = claude_session_id {
-            result.claude_resumed = true;
-            result.claude_session_id = Some(session_id.clone());
+        if let (RestorePlan::Resume { agent_type, .. }, PaneState::Agent(agent_state)) =
+            (&plan, &snapshot.state)
+        {
+            if agent_type == "claude" {
+                result.claude_resumed = true;
+                result.claude_session_id = agent_state.session_id.clone();
+            }
         }
 
         if should_spawn_pty {
-            // Build PTY config based on whether we're resuming a Claude session
-            let mut pty_config = if let Some(ref session_id) = claude_session_id {
-                // Resume Claude session
-                let (cmd, args) = create_resume_command(session_id);
-                info!(
-                    "Resuming Claude session {} for pane {}",
-                    session_id, snapshot.id
-                );
-
-                let mut config = PtyConfig::command(&cmd).with_size(snapshot.cols, snapshot.rows);
-                for arg in args {
-                    config = config.with_arg(arg);
+            let mut pty_config = match &plan {
+                RestorePlan::Resume { command, args, .. } | RestorePlan::Run { command, args } => {
+                    if let RestorePlan::Resume { agent_type, .. } = &plan {
+                        info!("Resuming {} session for pane {}", agent_type, snapshot.id);
+                    }
+                    let mut config =
+                        PtyConfig::command(command).with_size(snapshot.cols, snapshot.rows);
+                    for arg in args {
+                        config = config.with_arg(arg);
+                    }
+                    config
                 }
-                config
-            } else {
-                // Normal shell or Claude without session ID
-                PtyConfig::shell().with_size(snapshot.cols, snapshot.rows)
+                RestorePlan::Shell => PtyConfig::shell().with_size(snapshot.cols, snapshot.rows),
             };
+            if let Some(ref launch) = launch {
+                pty_config = pty_config.with_env_map(&launch.env);
+            }
 
             // Try to restore CWD
             if let Some(ref cwd) = snapshot.cwd {
@@ -427,7 +468,7 @@ impl SessionRestorer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
     use fugue_protocol::{AgentState, AgentActivity};
 
     fn create_test_session_snapshot() -> SessionSnapshot {
@@ -455,14 +496,19 @@ mod tests {
                     cwd: Some("/tmp".to_string()),
                     created_at: 12345,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 12345,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 12345,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         }
     }
 
@@ -600,6 +646,54 @@ mod tests {
         assert_eq!(pane.cwd(), Some("/tmp"));
     }
 
+    #[test]
+    fn test_restore_tags_layout_and_launch() {
+        let restorer = SessionRestorer::without_pty_spawn();
+
+        let mut snapshot = create_test_session_snapshot();
+        snapshot.tags.insert("worker".to_string());
+        snapshot.environment.insert("PROJECT".to_string(), "fugue".to_string());
+
+        let window = &mut snapshot.windows[0];
+        let first = window.panes[0].clone();
+        let mut second = first.clone();
+        second.id = Uuid::new_v4();
+        second.index = 1;
+        second.command = Some("htop".to_string());
+        second.args = vec!["-d".to_string(), "10".to_string()];
+        window.panes.push(second.clone());
+        let layout = fugue_protocol::LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            children: vec![
+                (fugue_protocol::LayoutNode::pane(first.id), 0.3),
+                (fugue_protocol::LayoutNode::pane(second.id), 0.7),
+            ],
+        };
+        window.layout = Some(layout.clone());
+
+        let state = RecoveryState {
+            sessions: vec![snapshot.clone()],
+            clean_shutdown: true,
+            ..Default::default()
+        };
+
+        let mut session_manager = SessionManager::new();
+        let mut pty_manager = PtyManager::new();
+        restorer.restore(&state, &mut session_manager, &mut pty_manager);
+
+        let session = session_manager.get_session(snapshot.id).unwrap();
+        assert!(session.has_tag("worker"));
+        assert_eq!(session.get_env("PROJECT").map(String::as_str), Some("fugue"));
+
+        let window = session.get_window(snapshot.windows[0].id).unwrap();
+        assert_eq!(window.layout(), Some(&layout));
+
+        let launch = window.get_pane(second.id).unwrap().launch().unwrap();
+        assert_eq!(launch.command, "htop");
+        assert_eq!(launch.args, vec!["-d", "10"]);
+        assert!(window.get_pane(first.id).unwrap().launch().is_none());
+    }
+
     #[test]
     fn test_should_spawn_pty_normal() {
         assert!(SessionRestorer::should_spawn_pty(&PaneState::Normal));
@@ -665,6 +759,7 @@ mod tests {
                     cwd_restored: true,
                     claude_resumed: false,
                     claude_session_id: None,
+                    restored_command: None,
                 }],
             }],
             total_panes: 1,
@@ -710,6 +805,7 @@ mod tests {
                     cwd_restored: false,
                     claude_resumed: false,
                     claude_session_id: None,
+                    restored_command: None,
                 }],
             }],
             total_panes: 1,
@@ -750,14 +846,19 @@ mod tests {
                     cwd: None,
                     created_at: 0,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 0,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 0,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let state = RecoveryState {
@@ -814,14 +915,19 @@ mod tests {
                     cwd: Some("/tmp".to_string()),
                     created_at: 12345,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 12345,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 12345,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let state = RecoveryState {
@@ -843,6 +949,10 @@ mod tests {
         let pane_result = &result.sessions[0].pane_results[0];
         assert!(pane_result.claude_resumed);
         assert_eq!(pane_result.claude_session_id, Some(claude_session_id));
+        assert_eq!(
+            pane_result.restored_command.as_deref(),
+            Some("claude --resume test-claude-session-123")
+        );
 
         // Check pane was restored with Claude state
         let (_, _, pane) = session_manager.find_pane(pane_id).unwrap();
@@ -881,14 +991,19 @@ mod tests {
                     cwd: None,
                     created_at: 0,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 0,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 0,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let state = RecoveryState {
@@ -941,14 +1056,19 @@ mod tests {
                     cwd: None,
                     created_at: 12345,
                     scrollback: None,
+                    command: None,
+                    args: Vec::new(),
+                    env: HashMap::new(),
                 }],
                 active_pane_id: Some(pane_id),
                 created_at: 12345,
+                layout: None,
             }],
             active_window_id: Some(window_id),
             created_at: 12345,
             metadata,
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let state = RecoveryState {
@@ -0,0 +1,367 @@
+//! Process resurrection for restored panes
+//!
+//! Decides what a pane should run when it is brought back after a restart:
+//! - Agent panes resume their conversation (Claude, Gemini, Codex)
+//! - Long-running tools (monitors, editors, dev servers, log tails) are re-run
+//! - Plain shells come back as the same shell
+//! - Everything else falls back to the configured default policy
+//!
+//! User rules from `[persistence.restore]` are checked before any of the
+//! built-in strategies.
+
+use std::path::Path;
+
+use fugue_protocol::PaneState;
+
+use crate::claude::create_resume_command;
+use crate::config::{RestoreConfig, RestorePolicy, RestoreRule};
+
+use super::types::PaneSnapshot;
+
+/// Shells that are restored by re-running them when launched without `-c`
+const SHELLS: &[&str] = &["sh", "bash", "zsh", "fish", "dash", "ksh", "nu"];
+
+/// Built-in long-running tools, as (program, required command line text)
+const LONG_RUNNING: &[(&str, Option<&str>)] = &[
+    ("htop", None),
+    ("top", None),
+    ("btop", None),
+    ("glances", None),
+    ("watch", None),
+    ("less", None),
+    ("man", None),
+    ("vi", None),
+    ("vim", None),
+    ("nvim", None),
+    ("nano", None),
+    ("emacs", None),
+    ("hx", None),
+    ("tig", None),
+    ("lazygit", None),
+    ("k9s", None),
+    ("bacon", None),
+    ("tail", Some(" -f")),
+    ("tail", Some(" -F")),
+    ("journalctl", Some(" -f")),
+    ("journalctl", Some("--follow")),
+    ("kubectl", Some(" logs -f")),
+    ("npm", Some(" run dev")),
+    ("npm", Some(" start")),
+    ("yarn", Some(" dev")),
+    ("pnpm", Some(" dev")),
+    ("cargo", Some(" watch")),
+    ("docker", Some(" compose up")),
+    ("docker-compose", Some(" up")),
+];
+
+/// What a restored pane should run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RestorePlan {
+    /// Start the default shell
+    Shell,
+    /// Run a program
+    Run { command: String, args: Vec<String> },
+    /// Resume an agent conversation
+    Resume {
+        agent_type: String,
+        command: String,
+        args: Vec<String>,
+    },
+}
+
+impl RestorePlan {
+    /// Command line this plan runs, or `None` for a shell
+    pub fn command_line(&self) -> Option<String> {
+        match self {
+            RestorePlan::Shell => None,
+            RestorePlan::Run { command, args } | RestorePlan::Resume { command, args, .. } => {
+                Some(std::iter::once(command).chain(args).cloned().collect::<Vec<_>>().join(" "))
+            }
+        }
+    }
+}
+
+/// Work out what a pane should run when restored
+pub fn plan_restore(snapshot: &PaneSnapshot, config: &RestoreConfig) -> RestorePlan {
+    let target = ProcessInfo::from_snapshot(snapshot);
+
+    if let Some(rule) = config.rules.iter().find(|rule| target.matches_rule(rule)) {
+        return apply_policy(rule.policy, rule.command.as_deref(), snapshot);
+    }
+
+    if !config.disable_builtin {
+        if let Some(plan) = agent_resume(&snapshot.state) {
+            return plan;
+        }
+        if target.is_plain_shell() || target.is_long_running() {
+            return apply_policy(RestorePolicy::Rerun, None, snapshot);
+        }
+    }
+
+    apply_policy(config.default_policy, None, snapshot)
+}
+
+/// Turn a policy into a plan for this pane
+fn apply_policy(policy: RestorePolicy, command: Option<&str>, snapshot: &PaneSnapshot) -> RestorePlan {
+    match (policy, command, &snapshot.command) {
+        (RestorePolicy::Rerun, _, Some(launch)) => RestorePlan::Run {
+            command: launch.clone(),
+            args: snapshot.args.clone(),
+        },
+        (RestorePolicy::Command, Some(command), _) => RestorePlan::Run {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), command.to_string()],
+        },
+        _ => RestorePlan::Shell,
+    }
+}
+
+/// Built-in resume commands for agents that persist their conversations
+fn agent_resume(state: &PaneState) -> Option<RestorePlan> {
+    let PaneState::Agent(agent) = state else {
+        return None;
+    };
+    let session_id = agent.session_id.as_deref();
+
+    let (command, args) = match (agent.agent_type.as_str(), session_id) {
+        ("claude", Some(id)) => create_resume_command(id),
+        // Gemini and Codex scope sessions to the working directory, so
+        // "latest" picks up the right conversation once the CWD is restored
+        ("gemini", id) => (
+            "gemini".to_string(),
+            vec!["--resume".to_string(), id.unwrap_or("latest").to_string()],
+        ),
+        ("codex", Some(id)) => (
+            "codex".to_string(),
+            vec!["resume".to_string(), id.to_string()],
+        ),
+        ("codex", None) => (
+            "codex".to_string(),
+            vec!["resume".to_string(), "--last".to_string()],
+        ),
+        _ => return None,
+    };
+
+    Some(RestorePlan::Resume {
+        agent_type: agent.agent_type.clone(),
+        command,
+        args,
+    })
+}
+
+/// The program a pane was running, seen through any `sh -c` wrapper
+struct ProcessInfo {
+    /// Basename of the program
+    program: Option<String>,
+    /// Full command line (after unwrapping)
+    line: String,
+    /// Whether the launch was a shell running a `-c` script
+    wrapped: bool,
+    /// Agent type, for agent panes
+    agent_type: Option<String>,
+}
+
+impl ProcessInfo {
+    fn from_snapshot(snapshot: &PaneSnapshot) -> Self {
+        let agent_type = match &snapshot.state {
+            PaneState::Agent(agent) => Some(agent.agent_type.clone()),
+            _ => None,
+        };
+
+        let Some(command) = &snapshot.command else {
+            return Self {
+                program: None,
+                line: String::new(),
+                wrapped: false,
+                agent_type,
+            };
+        };
+
+        let is_shell = SHELLS.contains(&basename(command));
+        let script = match snapshot.args.as_slice() {
+            [flag, script, ..] if is_shell && flag == "-c" => Some(script.clone()),
+            _ => None,
+        };
+
+        let line = match &script {
+            Some(script) => script.trim().to_string(),
+            None => std::iter::once(command)
+                .chain(&snapshot.args)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        // Skip `VAR=value` assignments and `exec` to find the real program
+        let program = line
+            .split_whitespace()
+            .find(|word| *word != "exec" && !word.contains('='))
+            .map(|word| basename(word).to_string());
+
+        Self {
+            program,
+            line,
+            wrapped: script.is_some(),
+            agent_type,
+        }
+    }
+
+    fn matches(&self, program: &str, contains: Option<&str>) -> bool {
+        let program_matches = self.program.as_deref() == Some(program)
+            || self.agent_type.as_deref() == Some(program);
+        program_matches && contains.is_none_or(|text| self.line.contains(text))
+    }
+
+    fn matches_rule(&self, rule: &RestoreRule) -> bool {
+        self.matches(&rule.program, rule.contains.as_deref())
+    }
+
+    fn is_plain_shell(&self) -> bool {
+        !self.wrapped
+            && self
+                .program
+                .as_deref()
+                .is_some_and(|program| SHELLS.contains(&program))
+    }
+
+    fn is_long_running(&self) -> bool {
+        LONG_RUNNING
+            .iter()
+            .any(|(program, contains)| self.matches(program, *contains))
+    }
+}
+
+fn basename(path: &str) -> &str {
+    Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::AgentState;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    fn snapshot(command: Option<&str>, args: &[&str], state: PaneState) -> PaneSnapshot {
+        PaneSnapshot {
+            id: Uuid::new_v4(),
+            window_id: Uuid::new_v4(),
+            index: 0,
+            cols: 80,
+            rows: 24,
+            state,
+            name: None,
+            title: None,
+            cwd: None,
+            created_at: 0,
+            scrollback: None,
+            command: command.map(String::from),
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: HashMap::new(),
+        }
+    }
+
+    fn run(command: &str, args: &[&str]) -> RestorePlan {
+        RestorePlan::Run {
+            command: command.to_string(),
+            args: args.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_long_running_rerun() {
+        let config = RestoreConfig::default();
+
+        let htop = snapshot(Some("/usr/bin/htop"), &[], PaneState::Normal);
+        assert_eq!(plan_restore(&htop, &config), run("/usr/bin/htop", &[]));
+
+        let dev = snapshot(Some("sh"), &["-c", "npm run dev"], PaneState::Normal);
+        assert_eq!(plan_restore(&dev, &config), run("sh", &["-c", "npm run dev"]));
+
+        let shell = snapshot(Some("/bin/zsh"), &["-l"], PaneState::Normal);
+        assert_eq!(plan_restore(&shell, &config), run("/bin/zsh", &["-l"]));
+
+        // One-shot commands fall back to the default policy
+        let build = snapshot(Some("sh"), &["-c", "make test"], PaneState::Normal);
+        assert_eq!(plan_restore(&build, &config), RestorePlan::Shell);
+        let tail = snapshot(Some("tail"), &["-n", "10", "log"], PaneState::Normal);
+        assert_eq!(plan_restore(&tail, &config), RestorePlan::Shell);
+    }
+
+    #[test]
+    fn test_agent_resume() {
+        let config = RestoreConfig::default();
+
+        let claude = AgentState::new("claude").with_session_id("abc".to_string());
+        let pane = snapshot(None, &[], PaneState::Agent(claude));
+        assert_eq!(
+            plan_restore(&pane, &config).command_line().as_deref(),
+            Some("claude --resume abc")
+        );
+
+        let pane = snapshot(Some("bash"), &[], PaneState::Agent(AgentState::new("gemini")));
+        assert_eq!(
+            plan_restore(&pane, &config).command_line().as_deref(),
+            Some("gemini --resume latest")
+        );
+
+        let codex = AgentState::new("codex").with_session_id("s-1".to_string());
+        let pane = snapshot(None, &[], PaneState::Agent(codex));
+        assert_eq!(
+            plan_restore(&pane, &config).command_line().as_deref(),
+            Some("codex resume s-1")
+        );
+
+        // Claude without a session ID has nothing to resume
+        let pane = snapshot(None, &[], PaneState::Agent(AgentState::new("claude")));
+        assert_eq!(plan_restore(&pane, &config), RestorePlan::Shell);
+    }
+
+    #[test]
+    fn test_rules_override_builtin() {
+        let config = RestoreConfig {
+            default_policy: RestorePolicy::Rerun,
+            disable_builtin: false,
+            rules: vec![
+                RestoreRule {
+                    program: "htop".to_string(),
+                    contains: None,
+                    policy: RestorePolicy::Shell,
+                    command: None,
+                },
+                RestoreRule {
+                    program: "make".to_string(),
+                    contains: Some("serve".to_string()),
+                    policy: RestorePolicy::Command,
+                    command: Some("make serve-restore".to_string()),
+                },
+                RestoreRule {
+                    program: "gemini".to_string(),
+                    contains: None,
+                    policy: RestorePolicy::Shell,
+                    command: None,
+                },
+            ],
+        };
+
+        let htop = snapshot(Some("htop"), &[], PaneState::Normal);
+        assert_eq!(plan_restore(&htop, &config), RestorePlan::Shell);
+
+        let serve = snapshot(Some("sh"), &["-c", "FOO=1 make serve"], PaneState::Normal);
+        assert_eq!(
+            plan_restore(&serve, &config),
+            run("sh", &["-c", "make serve-restore"])
+        );
+
+        // Rules match agent panes by agent type
+        let pane = snapshot(Some("bash"), &[], PaneState::Agent(AgentState::new("gemini")));
+        assert_eq!(plan_restore(&pane, &config), RestorePlan::Shell);
+
+        // Unmatched panes use the default policy
+        let build = snapshot(Some("make"), &["test"], PaneState::Normal);
+        assert_eq!(plan_restore(&build, &config), run("make", &["test"]));
+    }
+}
@@ -0,0 +1,358 @@
+//! Named snapshots of session state
+//!
+//! Unlike checkpoints, which are rotated automatically, snapshots are only
+//! created and removed on request. Each one is stored in the checkpoint file
+//! format under `<state_dir>/snapshots/<name>.snapshot`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use tracing::info;
+use uuid::Uuid;
+
+use fugue_protocol::{LayoutNode, SnapshotInfo};
+use fugue_utils::{CcmuxError, Result};
+
+use crate::session::Session;
+
+use super::checkpoint::CheckpointManager;
+use super::types::{Checkpoint, PaneSnapshot, SessionSnapshot, WindowSnapshot, CHECKPOINT_VERSION};
+
+/// File extension for snapshot files
+const SNAPSHOT_EXTENSION: &str = "snapshot";
+
+/// Capture a live session for persistence
+pub fn capture_session(session: &Session) -> SessionSnapshot {
+    let windows = session
+        .windows()
+        .map(|window| {
+            let panes = window
+                .panes()
+                .map(|pane| {
+                    let (cols, rows) = pane.dimensions();
+                    PaneSnapshot {
+                        id: pane.id(),
+                        window_id: window.id(),
+                        index: pane.index(),
+                        cols,
+                        rows,
+                        state: pane.state().clone(),
+                        name: pane.name().map(String::from),
+                        title: pane.title().map(String::from),
+                        cwd: pane.cwd().map(String::from),
+                        created_at: pane.created_at_unix(),
+                        scrollback: None, // TODO: Get from PTY
+                        command: pane.launch().map(|l| l.command.clone()),
+                        args: pane.launch().map(|l| l.args.clone()).unwrap_or_default(),
+                        env: pane.launch().map(|l| l.env.clone()).unwrap_or_default(),
+                    }
+                })
+                .collect();
+
+            WindowSnapshot {
+                id: window.id(),
+                session_id: session.id(),
+                name: window.name().to_string(),
+                index: window.index(),
+                panes,
+                active_pane_id: window.active_pane_id(),
+                created_at: window.created_at_unix(),
+                layout: window.layout().cloned(),
+            }
+        })
+        .collect();
+
+    SessionSnapshot {
+        id: session.id(),
+        name: session.name().to_string(),
+        windows,
+        active_window_id: session.active_window_id(),
+        created_at: session.created_at_unix(),
+        metadata: session.all_metadata().clone(),
+        environment: session.environment().clone(),
+        tags: session.tags().clone(),
+    }
+}
+
+/// Copy a session snapshot under a new name with fresh IDs
+///
+/// The copy can be restored alongside the original without colliding with it.
+pub fn clone_session(snapshot: &SessionSnapshot, name: impl Into<String>) -> SessionSnapshot {
+    let session_id = Uuid::new_v4();
+    let mut window_ids = HashMap::new();
+    let mut pane_ids = HashMap::new();
+
+    let windows = snapshot
+        .windows
+        .iter()
+        .map(|window| {
+            let window_id = Uuid::new_v4();
+            window_ids.insert(window.id, window_id);
+
+            let panes = window
+                .panes
+                .iter()
+                .map(|pane| {
+                    let pane_id = Uuid::new_v4();
+                    pane_ids.insert(pane.id, pane_id);
+                    PaneSnapshot {
+                        id: pane_id,
+                        window_id,
+                        ..pane.clone()
+                    }
+                })
+                .collect();
+
+            WindowSnapshot {
+                id: window_id,
+                session_id,
+                panes,
+                active_pane_id: window.active_pane_id.and_then(|id| pane_ids.get(&id).copied()),
+                layout: window.layout.as_ref().map(|layout| remap_layout(layout, &pane_ids)),
+                ..window.clone()
+            }
+        })
+        .collect();
+
+    SessionSnapshot {
+        id: session_id,
+        name: name.into(),
+        windows,
+        active_window_id: snapshot
+            .active_window_id
+            .and_then(|id| window_ids.get(&id).copied()),
+        ..snapshot.clone()
+    }
+}
+
+fn remap_layout(node: &LayoutNode, pane_ids: &HashMap<Uuid, Uuid>) -> LayoutNode {
+    match node {
+        LayoutNode::Pane { id } => LayoutNode::Pane {
+            id: pane_ids.get(id).copied().unwrap_or(*id),
+        },
+        LayoutNode::Split {
+            direction,
+            children,
+        } => LayoutNode::Split {
+            direction: *direction,
+            children: children
+                .iter()
+                .map(|(child, share)| (remap_layout(child, pane_ids), *share))
+                .collect(),
+        },
+    }
+}
+
+/// Store of named snapshots in a state directory
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Open the snapshot store for a state directory
+    pub fn new(state_dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: state_dir.as_ref().join("snapshots"),
+        }
+    }
+
+    /// Save sessions under `name`, replacing any snapshot with that name
+    pub fn save(&self, name: &str, sessions: Vec<SessionSnapshot>) -> Result<SnapshotInfo> {
+        let path = self.path(name)?;
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            CcmuxError::persistence(format!("Failed to create snapshot directory: {}", e))
+        })?;
+
+        let checkpoint = Checkpoint {
+            version: CHECKPOINT_VERSION,
+            sequence: 0,
+            timestamp: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            sessions,
+            // Snapshots capture layout and processes, not output
+            scrollback: HashMap::new(),
+        };
+        CheckpointManager::write_file(&path, &checkpoint)?;
+
+        info!("Saved snapshot '{}' to {}", name, path.display());
+        Ok(Self::info(name, &checkpoint))
+    }
+
+    /// Load the snapshot called `name`
+    pub fn load(&self, name: &str) -> Result<Checkpoint> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(CcmuxError::persistence(format!("Snapshot '{}' not found", name)));
+        }
+        CheckpointManager::read_file(&path)
+    }
+
+    /// Delete the snapshot called `name`
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let path = self.path(name)?;
+        fs::remove_file(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                CcmuxError::persistence(format!("Snapshot '{}' not found", name))
+            }
+            _ => CcmuxError::persistence(format!("Failed to delete snapshot '{}': {}", name, e)),
+        })
+    }
+
+    /// List snapshots, oldest first; unreadable files are skipped
+    pub fn list(&self) -> Result<Vec<SnapshotInfo>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&self.dir).map_err(|e| {
+            CcmuxError::persistence(format!("Failed to read snapshot directory: {}", e))
+        })?;
+
+        let mut snapshots: Vec<SnapshotInfo> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == SNAPSHOT_EXTENSION))
+            .filter_map(|path| {
+                let name = path.file_stem()?.to_str()?.to_string();
+                let checkpoint = CheckpointManager::read_file(&path).ok()?;
+                Some(Self::info(&name, &checkpoint))
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.name.cmp(&b.name)));
+
+        Ok(snapshots)
+    }
+
+    fn info(name: &str, checkpoint: &Checkpoint) -> SnapshotInfo {
+        SnapshotInfo {
+            name: name.to_string(),
+            created_at: checkpoint.timestamp,
+            sessions: checkpoint.sessions.iter().map(|s| s.name.clone()).collect(),
+        }
+    }
+
+    /// Path of a snapshot file; names are restricted so they stay in the directory
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(CcmuxError::persistence(format!(
+                "Invalid snapshot name '{}': use letters, digits, '-', '_' and '.'",
+                name
+            )));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, SNAPSHOT_EXTENSION)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::{PaneState, SplitDirection};
+    use std::collections::HashSet;
+    use tempfile::TempDir;
+
+    fn session_with_split() -> SessionSnapshot {
+        let session_id = Uuid::new_v4();
+        let window_id = Uuid::new_v4();
+        let (left, right) = (Uuid::new_v4(), Uuid::new_v4());
+        let pane = |id, index| PaneSnapshot {
+            id,
+            window_id,
+            index,
+            cols: 80,
+            rows: 24,
+            state: PaneState::Normal,
+            name: None,
+            title: None,
+            cwd: Some("/tmp".to_string()),
+            created_at: 0,
+            scrollback: None,
+            command: None,
+            args: Vec::new(),
+            env: HashMap::new(),
+        };
+        SessionSnapshot {
+            id: session_id,
+            name: "workers".to_string(),
+            windows: vec![WindowSnapshot {
+                id: window_id,
+                session_id,
+                name: "main".to_string(),
+                index: 0,
+                panes: vec![pane(left, 0), pane(right, 1)],
+                active_pane_id: Some(right),
+                created_at: 0,
+                layout: Some(LayoutNode::Split {
+                    direction: SplitDirection::Vertical,
+                    children: vec![
+                        (LayoutNode::pane(left), 0.5),
+                        (LayoutNode::pane(right), 0.5),
+                    ],
+                }),
+            }],
+            active_window_id: Some(window_id),
+            created_at: 0,
+            metadata: HashMap::new(),
+            environment: HashMap::new(),
+            tags: HashSet::from(["worker".to_string()]),
+        }
+    }
+
+    #[test]
+    fn test_snapshot_store_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        assert!(store.list().unwrap().is_empty());
+
+        let session = session_with_split();
+        let info = store.save("known-good", vec![session.clone()]).unwrap();
+        assert_eq!(info.sessions, vec!["workers"]);
+
+        let loaded = store.load("known-good").unwrap();
+        assert_eq!(loaded.sessions, vec![session]);
+        assert_eq!(store.list().unwrap().len(), 1);
+
+        store.delete("known-good").unwrap();
+        assert!(store.list().unwrap().is_empty());
+        assert!(store.load("known-good").is_err());
+        assert!(store.delete("known-good").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_names_validated() {
+        let dir = TempDir::new().unwrap();
+        let store = SnapshotStore::new(dir.path());
+        for name in ["", "../escape", ".hidden", "a/b", "has space"] {
+            assert!(store.save(name, Vec::new()).is_err(), "{:?} accepted", name);
+        }
+        assert!(store.save("v1.2_pre-merge", Vec::new()).is_ok());
+    }
+
+    #[test]
+    fn test_clone_session_remaps_ids() {
+        let original = session_with_split();
+        let copy = clone_session(&original, "workers-retry");
+
+        assert_eq!(copy.name, "workers-retry");
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.tags, original.tags);
+
+        let window = &copy.windows[0];
+        assert_ne!(window.id, original.windows[0].id);
+        assert_eq!(window.session_id, copy.id);
+        assert_eq!(copy.active_window_id, Some(window.id));
+        assert!(window.panes.iter().all(|p| p.window_id == window.id));
+
+        let pane_ids: Vec<Uuid> = window.panes.iter().map(|p| p.id).collect();
+        assert_eq!(window.layout.as_ref().unwrap().pane_ids(), pane_ids);
+        assert_eq!(window.active_pane_id, Some(pane_ids[1]));
+        assert!(original.windows[0].panes.iter().all(|p| !pane_ids.contains(&p.id)));
+    }
+}
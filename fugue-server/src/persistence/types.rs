@@ -8,9 +8,9 @@
 // Scaffolding for crash recovery feature - not all types are used yet
 #![allow(dead_code)]
 
-use fugue_protocol::PaneState;
+use fugue_protocol::{LayoutNode, PaneState};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
 /// Current checkpoint format version
@@ -18,7 +18,9 @@ use uuid::Uuid;
 /// Version history:
 /// - 1: Initial format with ClaudeState
 /// - 2: Added AgentState variant to PaneState (FEAT-084)
-pub const CHECKPOINT_VERSION: u32 = 2;
+/// - 3: Added session tags, window layouts and pane launch commands
+/// - 4: Added scrollback block references
+pub const CHECKPOINT_VERSION: u32 = 4;
 
 /// Magic bytes for checkpoint file identification
 pub const CHECKPOINT_MAGIC: [u8; 4] = *b"CCCP"; // CcmuX Checkpoint
@@ -34,6 +36,8 @@ pub struct Checkpoint {
     pub sequence: u64,
     /// All sessions in the checkpoint
     pub sessions: Vec<SessionSnapshot>,
+    /// Scrollback blocks of each pane, oldest first
+    pub scrollback: HashMap<Uuid, Vec<BlockRef>>,
 }
 
 impl Checkpoint {
@@ -47,6 +51,7 @@ impl Checkpoint {
                 .unwrap_or(0),
             sequence,
             sessions: Vec::new(),
+            scrollback: HashMap::new(),
         }
     }
 }
@@ -70,6 +75,9 @@ pub struct SessionSnapshot {
     /// Session environment variables (backward compatible with empty default)
     #[serde(default)]
     pub environment: HashMap<String, String>,
+    /// Session tags used for orchestration routing
+    #[serde(default)]
+    pub tags: HashSet<String>,
 }
 
 /// Snapshot of a window for persistence
@@ -89,6 +97,9 @@ pub struct WindowSnapshot {
     pub active_pane_id: Option<Uuid>,
     /// Creation timestamp (Unix seconds)
     pub created_at: u64,
+    /// Split tree of the window's panes
+    #[serde(default)]
+    pub layout: Option<LayoutNode>,
 }
 
 /// Snapshot of a pane for persistence
@@ -117,6 +128,15 @@ pub struct PaneSnapshot {
     pub created_at: u64,
     /// Scrollback content (compressed)
     pub scrollback: Option<ScrollbackSnapshot>,
+    /// Program the pane was launched with (None for panes from older checkpoints)
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Arguments the program was launched with
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Environment variables the pane was launched with
+    #[serde(default)]
+    pub env: HashMap<String, String>,
 }
 
 /// Scrollback buffer snapshot
@@ -130,6 +150,15 @@ pub struct ScrollbackSnapshot {
     pub compression: CompressionMethod,
 }
 
+/// Reference to a compressed block in the scrollback block store
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BlockRef {
+    /// BLAKE3 hash of the block's uncompressed content (hex)
+    pub id: String,
+    /// Number of lines in the block
+    pub line_count: u32,
+}
+
 /// Compression method for scrollback data
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub enum CompressionMethod {
@@ -236,6 +265,26 @@ pub enum WalEntry {
         key: String,
         value: String,
     },
+
+    /// Session tags replaced
+    SessionTagsSet {
+        session_id: Uuid,
+        tags: HashSet<String>,
+    },
+
+    /// Window split tree changed
+    WindowLayoutChanged {
+        window_id: Uuid,
+        layout: Option<LayoutNode>,
+    },
+
+    /// Pane process was launched
+    PaneLaunched {
+        id: Uuid,
+        command: String,
+        args: Vec<String>,
+        env: HashMap<String, String>,
+    },
 }
 
 impl WalEntry {
@@ -248,6 +297,139 @@ impl WalEntry {
     }
 }
 
+/// Checkpoint layout used by version 3 (no scrollback blocks), kept for migration
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckpointV3 {
+    pub version: u32,
+    pub timestamp: u64,
+    pub sequence: u64,
+    pub sessions: Vec<SessionSnapshot>,
+}
+
+impl From<CheckpointV3> for Checkpoint {
+    fn from(old: CheckpointV3) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            timestamp: old.timestamp,
+            sequence: old.sequence,
+            sessions: old.sessions,
+            scrollback: HashMap::new(),
+        }
+    }
+}
+
+/// Checkpoint layout used by versions 1 and 2, kept for migration
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckpointV2 {
+    pub version: u32,
+    pub timestamp: u64,
+    pub sequence: u64,
+    pub sessions: Vec<SessionSnapshotV2>,
+}
+
+/// Version 2 session snapshot (no tags)
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionSnapshotV2 {
+    pub id: Uuid,
+    pub name: String,
+    pub windows: Vec<WindowSnapshotV2>,
+    pub active_window_id: Option<Uuid>,
+    pub created_at: u64,
+    pub metadata: HashMap<String, String>,
+    pub environment: HashMap<String, String>,
+}
+
+/// Version 2 window snapshot (no layout)
+#[derive(Debug, Clone, Deserialize)]
+pub struct WindowSnapshotV2 {
+    pub id: Uuid,
+    pub session_id: Uuid,
+    pub name: String,
+    pub index: usize,
+    pub panes: Vec<PaneSnapshotV2>,
+    pub active_pane_id: Option<Uuid>,
+    pub created_at: u64,
+}
+
+/// Version 2 pane snapshot (no launch command)
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaneSnapshotV2 {
+    pub id: Uuid,
+    pub window_id: Uuid,
+    pub index: usize,
+    pub cols: u16,
+    pub rows: u16,
+    pub state: PaneState,
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub cwd: Option<String>,
+    pub created_at: u64,
+    pub scrollback: Option<ScrollbackSnapshot>,
+}
+
+impl From<CheckpointV2> for Checkpoint {
+    fn from(old: CheckpointV2) -> Self {
+        Self {
+            version: CHECKPOINT_VERSION,
+            timestamp: old.timestamp,
+            sequence: old.sequence,
+            sessions: old.sessions.into_iter().map(Into::into).collect(),
+            scrollback: HashMap::new(),
+        }
+    }
+}
+
+impl From<SessionSnapshotV2> for SessionSnapshot {
+    fn from(old: SessionSnapshotV2) -> Self {
+        Self {
+            id: old.id,
+            name: old.name,
+            windows: old.windows.into_iter().map(Into::into).collect(),
+            active_window_id: old.active_window_id,
+            created_at: old.created_at,
+            metadata: old.metadata,
+            environment: old.environment,
+            tags: HashSet::new(),
+        }
+    }
+}
+
+impl From<WindowSnapshotV2> for WindowSnapshot {
+    fn from(old: WindowSnapshotV2) -> Self {
+        Self {
+            id: old.id,
+            session_id: old.session_id,
+            name: old.name,
+            index: old.index,
+            panes: old.panes.into_iter().map(Into::into).collect(),
+            active_pane_id: old.active_pane_id,
+            created_at: old.created_at,
+            layout: None,
+        }
+    }
+}
+
+impl From<PaneSnapshotV2> for PaneSnapshot {
+    fn from(old: PaneSnapshotV2) -> Self {
+        Self {
+            id: old.id,
+            window_id: old.window_id,
+            index: old.index,
+            cols: old.cols,
+            rows: old.rows,
+            state: old.state,
+            name: old.name,
+            title: old.title,
+            cwd: old.cwd,
+            created_at: old.created_at,
+            scrollback: old.scrollback,
+            command: None,
+            args: Vec::new(),
+            env: HashMap::new(),
+        }
+    }
+}
+
 /// WAL segment metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalSegmentHeader {
@@ -274,6 +456,8 @@ pub struct RecoveryState {
     pub clean_shutdown: bool,
     /// Any warnings during recovery
     pub warnings: Vec<String>,
+    /// Scrollback blocks of each pane, from the checkpoint
+    pub scrollback: HashMap<Uuid, Vec<BlockRef>>,
 }
 
 impl RecoveryState {
@@ -319,6 +503,7 @@ mod tests {
             created_at: 12345,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         });
 
         let serialized = bincode::serialize(&checkpoint).unwrap();
@@ -343,11 +528,13 @@ mod tests {
                 panes: Vec::new(),
                 active_pane_id: None,
                 created_at: 1000,
+                layout: None,
             }],
             active_window_id: None,
             created_at: 1000,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         };
 
         let serialized = bincode::serialize(&snapshot).unwrap();
@@ -374,6 +561,9 @@ mod tests {
                 compressed_data: vec![1, 2, 3],
                 compression: CompressionMethod::None,
             }),
+            command: Some("vim".to_string()),
+            args: vec!["notes.md".to_string()],
+            env: HashMap::from([("EDITOR".to_string(), "vim".to_string())]),
         };
 
         let serialized = bincode::serialize(&snapshot).unwrap();
@@ -455,6 +645,7 @@ mod tests {
             created_at: 0,
             metadata: HashMap::new(),
             environment: HashMap::new(),
+            tags: HashSet::new(),
         });
 
         assert!(state.has_sessions());
@@ -550,6 +741,20 @@ mod tests {
                 sequence: 1,
                 timestamp: 12345,
             },
+            WalEntry::SessionTagsSet {
+                session_id: Uuid::new_v4(),
+                tags: HashSet::from(["worker".to_string()]),
+            },
+            WalEntry::WindowLayoutChanged {
+                window_id: Uuid::new_v4(),
+                layout: Some(LayoutNode::pane(Uuid::new_v4())),
+            },
+            WalEntry::PaneLaunched {
+                id: Uuid::new_v4(),
+                command: "htop".to_string(),
+                args: vec!["-d".to_string(), "10".to_string()],
+                env: HashMap::new(),
+            },
         ];
 
         for entry in entries {
@@ -557,4 +762,122 @@ mod tests {
             let _deserialized: WalEntry = bincode::deserialize(&serialized).unwrap();
         }
     }
+
+    #[test]
+    fn test_v2_checkpoint_migrates() {
+        #[derive(Serialize)]
+        struct OldPane {
+            id: Uuid,
+            window_id: Uuid,
+            index: usize,
+            cols: u16,
+            rows: u16,
+            state: PaneState,
+            name: Option<String>,
+            title: Option<String>,
+            cwd: Option<String>,
+            created_at: u64,
+            scrollback: Option<ScrollbackSnapshot>,
+        }
+        #[derive(Serialize)]
+        struct OldWindow {
+            id: Uuid,
+            session_id: Uuid,
+            name: String,
+            index: usize,
+            panes: Vec<OldPane>,
+            active_pane_id: Option<Uuid>,
+            created_at: u64,
+        }
+        #[derive(Serialize)]
+        struct OldSession {
+            id: Uuid,
+            name: String,
+            windows: Vec<OldWindow>,
+            active_window_id: Option<Uuid>,
+            created_at: u64,
+            metadata: HashMap<String, String>,
+            environment: HashMap<String, String>,
+        }
+        #[derive(Serialize)]
+        struct OldCheckpoint {
+            version: u32,
+            timestamp: u64,
+            sequence: u64,
+            sessions: Vec<OldSession>,
+        }
+
+        let session_id = Uuid::new_v4();
+        let window_id = Uuid::new_v4();
+        let old = OldCheckpoint {
+            version: 2,
+            timestamp: 1,
+            sequence: 7,
+            sessions: vec![OldSession {
+                id: session_id,
+                name: "old".to_string(),
+                windows: vec![OldWindow {
+                    id: window_id,
+                    session_id,
+                    name: "main".to_string(),
+                    index: 0,
+                    panes: vec![OldPane {
+                        id: Uuid::new_v4(),
+                        window_id,
+                        index: 0,
+                        cols: 80,
+                        rows: 24,
+                        state: PaneState::Normal,
+                        name: None,
+                        title: None,
+                        cwd: Some("/tmp".to_string()),
+                        created_at: 1,
+                        scrollback: None,
+                    }],
+                    active_pane_id: None,
+                    created_at: 1,
+                }],
+                active_window_id: Some(window_id),
+                created_at: 1,
+                metadata: HashMap::new(),
+                environment: HashMap::new(),
+            }],
+        };
+
+        let bytes = bincode::serialize(&old).unwrap();
+        let checkpoint: Checkpoint = bincode::deserialize::<CheckpointV2>(&bytes).unwrap().into();
+
+        assert_eq!(checkpoint.version, CHECKPOINT_VERSION);
+        assert_eq!(checkpoint.sequence, 7);
+        let session = &checkpoint.sessions[0];
+        assert!(session.tags.is_empty());
+        assert!(session.windows[0].layout.is_none());
+        let pane = &session.windows[0].panes[0];
+        assert_eq!(pane.cwd.as_deref(), Some("/tmp"));
+        assert!(pane.command.is_none());
+    }
+
+    #[test]
+    fn test_v3_checkpoint_migrates() {
+        #[derive(Serialize)]
+        struct OldCheckpoint {
+            version: u32,
+            timestamp: u64,
+            sequence: u64,
+            sessions: Vec<SessionSnapshot>,
+        }
+
+        let old = OldCheckpoint {
+            version: 3,
+            timestamp: 1,
+            sequence: 9,
+            sessions: Vec::new(),
+        };
+        let bytes = bincode::serialize(&old).unwrap();
+        let checkpoint: Checkpoint = bincode::deserialize::<CheckpointV3>(&bytes).unwrap().into();
+
+        assert_eq!(checkpoint.version, CHECKPOINT_VERSION);
+        assert_eq!(checkpoint.sequence, 9);
+        assert!(checkpoint.scrollback.is_empty());
+    }
 }
@@ -227,31 +227,15 @@ impl Wal {
 
     /// Read entries after a checkpoint sequence
     pub fn read_after_checkpoint(&self, checkpoint_sequence: u64) -> Result<Vec<WalEntry>> {
-        let entries = &self.recovered_entries;
-
-        // Find the checkpoint marker and return entries after it
-        let mut found_checkpoint = false;
-        let mut result = Vec::new();
-
-        for entry in entries {
-            if let Some(seq) = entry.checkpoint_sequence() {
-                if seq == checkpoint_sequence {
-                    found_checkpoint = true;
-                    continue;
-                }
-            }
-
-            if found_checkpoint {
-                result.push(entry.clone());
-            }
-        }
-
-        // If no checkpoint found, return all entries
-        if !found_checkpoint && checkpoint_sequence == 0 {
-            return Ok(self.recovered_entries.clone());
-        }
+        let Some(start) = replay_start(&self.recovered_entries, checkpoint_sequence) else {
+            return Ok(Vec::new());
+        };
 
-        Ok(result)
+        Ok(self.recovered_entries[start..]
+            .iter()
+            .filter(|entry| entry.checkpoint_sequence() != Some(checkpoint_sequence))
+            .cloned()
+            .collect())
     }
 
     /// Get current sequence number
@@ -295,6 +279,21 @@ impl Wal {
     }
 }
 
+/// Index of the first entry to replay on top of the given checkpoint
+///
+/// Replay starts after the checkpoint's marker. Without a marker, everything
+/// is replayed when there is no checkpoint (sequence 0) and nothing otherwise.
+pub fn replay_start(entries: &[WalEntry], checkpoint_sequence: u64) -> Option<usize> {
+    match entries
+        .iter()
+        .position(|entry| entry.checkpoint_sequence() == Some(checkpoint_sequence))
+    {
+        Some(index) => Some(index + 1),
+        None if checkpoint_sequence == 0 => Some(0),
+        None => None,
+    }
+}
+
 /// WAL entry reader for iterating through entries
 pub struct WalReader<'a> {
     entries: &'a [WalEntry],
@@ -1,10 +1,19 @@
 //! Scrollback buffer implementation
 //!
 //! Provides a circular buffer for storing terminal output history.
-//! VT100 escape sequences are preserved for faithful replay.
+//! VT100 escape sequences are preserved for faithful replay. When spilling
+//! is configured, evicted lines move to disk instead of being dropped.
 
 use std::collections::VecDeque;
+use std::io;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tracing::warn;
+
+use super::search::{plain_text, ScrollbackIndex, SearchQuery};
+use super::spill::{ScrollbackSpill, SpilledRange, SpillSettings};
 
 /// Global counter for tracking total scrollback memory across all buffers
 static GLOBAL_SCROLLBACK_BYTES: AtomicUsize = AtomicUsize::new(0);
@@ -71,6 +80,12 @@ pub struct ScrollbackBuffer {
     total_bytes: usize,
     /// Current viewport offset from the bottom (0 = at bottom, following output)
     viewport_offset: usize,
+    /// Lines ever pushed, i.e. the sequence number of the next line
+    pushed: u64,
+    /// Full-text index, brought up to date by `find`
+    index: Mutex<ScrollbackIndex>,
+    /// Evicted lines kept on disk, when spilling is configured
+    spill: Option<ScrollbackSpill>,
 }
 
 impl ScrollbackBuffer {
@@ -81,9 +96,38 @@ impl ScrollbackBuffer {
             max_lines,
             total_bytes: 0,
             viewport_offset: 0,
+            pushed: 0,
+            index: Mutex::new(ScrollbackIndex::default()),
+            spill: None,
+        }
+    }
+
+    /// Create a buffer that spills with the given settings
+    pub fn with_spill(max_lines: usize, settings: Arc<SpillSettings>) -> Self {
+        let mut buffer = Self::new(max_lines);
+        buffer.enable_spill(settings);
+        buffer
+    }
+
+    /// Spill lines evicted from now on to disk
+    ///
+    /// Does nothing if the buffer already spills.
+    pub fn enable_spill(&mut self, settings: Arc<SpillSettings>) {
+        if self.spill.is_none() {
+            self.spill = Some(ScrollbackSpill::new(settings, self.first_sequence()));
         }
     }
 
+    /// Stop spilling, dropping the spilled history from disk
+    pub fn disable_spill(&mut self) {
+        self.spill = None;
+    }
+
+    /// Whether evicted lines are spilled to disk
+    pub fn is_spilling(&self) -> bool {
+        self.spill.is_some()
+    }
+
     /// Get the current viewport offset from the bottom
     ///
     /// 0 means at bottom (following output), larger values mean scrolled up.
@@ -120,7 +164,7 @@ impl ScrollbackBuffer {
 
     /// Push a line into the buffer
     ///
-    /// If the buffer is at capacity, the oldest line is removed.
+    /// If the buffer is at capacity, the oldest line is removed (or spilled).
     /// VT100 escape sequences in the line are preserved.
     pub fn push_line(&mut self, line: String) {
         let line_bytes = line.len();
@@ -131,6 +175,9 @@ impl ScrollbackBuffer {
                 let removed_bytes = removed.len();
                 self.total_bytes = self.total_bytes.saturating_sub(removed_bytes);
                 GLOBAL_SCROLLBACK_BYTES.fetch_sub(removed_bytes, Ordering::Relaxed);
+                if let Some(spill) = &mut self.spill {
+                    spill.push(removed);
+                }
             }
         }
 
@@ -138,6 +185,67 @@ impl ScrollbackBuffer {
         self.total_bytes += line_bytes;
         GLOBAL_SCROLLBACK_BYTES.fetch_add(line_bytes, Ordering::Relaxed);
         self.lines.push_back(line);
+        self.pushed += 1;
+    }
+
+    /// Number of lines ever pushed, including ones since evicted
+    ///
+    /// Lines are numbered from 0 in push order, so this is also the
+    /// sequence number the next line will get.
+    pub fn total_pushed(&self) -> u64 {
+        self.pushed
+    }
+
+    /// Sequence number of the oldest line still in the buffer
+    pub fn first_sequence(&self) -> u64 {
+        self.pushed - self.lines.len() as u64
+    }
+
+    /// Lines with sequence numbers from `seq` on that are still in the buffer
+    pub fn lines_since(&self, seq: u64) -> impl Iterator<Item = &str> {
+        let skip = seq.saturating_sub(self.first_sequence()) as usize;
+        self.lines.iter().skip(skip).map(|s| s.as_str())
+    }
+
+    /// Sequence number of the oldest line available, in memory or spilled
+    pub fn history_start(&self) -> u64 {
+        self.spill
+            .as_ref()
+            .map_or(self.first_sequence(), |spill| spill.first_sequence())
+            .min(self.first_sequence())
+    }
+
+    /// Bytes of spilled history on disk
+    pub fn spilled_bytes(&self) -> u64 {
+        self.spill.as_ref().map_or(0, ScrollbackSpill::disk_bytes)
+    }
+
+    /// Lines with sequence numbers in `start..end`, including spilled ones
+    ///
+    /// Lines no longer available are skipped, so the result starts at
+    /// `max(start, history_start())`.
+    pub fn read_history(&self, start: u64, end: u64) -> io::Result<Vec<String>> {
+        self.history_range(start, end).read()
+    }
+
+    /// Copy out `start..end` to read later with [`HistoryRange::read`]
+    ///
+    /// In-memory lines are copied; spilled ones are only decompressed when
+    /// the range is read, so this is cheap enough to call under a lock.
+    pub fn history_range(&self, start: u64, end: u64) -> HistoryRange {
+        let start = start.max(self.history_start());
+        let first = self.first_sequence();
+        let spilled = match &self.spill {
+            Some(spill) if start < first => Some(spill.range(start, end.min(first))),
+            _ => None,
+        };
+        let from = start.max(first);
+        let lines = self
+            .lines_since(from)
+            .take(end.saturating_sub(from) as usize)
+            .map(String::from)
+            .collect();
+        HistoryRange { spilled, lines }
     }
 
     /// Push raw bytes, splitting into lines
@@ -171,11 +279,14 @@ impl ScrollbackBuffer {
             .map(|s| s.as_str())
     }
 
-    /// Clear all lines from the buffer
+    /// Clear all lines from the buffer, including spilled history
     pub fn clear(&mut self) {
         GLOBAL_SCROLLBACK_BYTES.fetch_sub(self.total_bytes, Ordering::Relaxed);
         self.lines.clear();
         self.total_bytes = 0;
+        if let Some(spill) = &mut self.spill {
+            spill.clear(self.pushed);
+        }
     }
 
     /// Get a line by index (0 is oldest)
@@ -192,6 +303,50 @@ impl ScrollbackBuffer {
             .map(|(i, line)| (i, line.as_str()))
     }
 
+    /// Line with the given sequence number, if it is still in the buffer
+    pub fn line(&self, seq: u64) -> Option<&str> {
+        let index = seq.checked_sub(self.first_sequence())?;
+        self.get(index as usize)
+    }
+
+    /// Lines matching a query with their sequence numbers, newest first
+    ///
+    /// Lines pushed since the last call are indexed first. Spilled history
+    /// is searched too: lines evicted before they were indexed are read back
+    /// from disk once to index them.
+    pub fn find(&self, query: &SearchQuery) -> Vec<(u64, String)> {
+        let history_start = self.history_start();
+        let first = self.first_sequence();
+        let candidates = {
+            let mut index = self.index.lock();
+            let start = index.next_seq().max(history_start);
+            if start < first {
+                match self.read_history(start, first) {
+                    Ok(lines) if lines.len() as u64 == first - start => {
+                        index.update(history_start, start, lines.iter().map(String::as_str));
+                    }
+                    Ok(_) => warn!("Spilled scrollback is incomplete, not indexing it"),
+                    Err(e) => warn!("Failed to read spilled scrollback to index it: {}", e),
+                }
+            }
+            let start = index.next_seq().max(first);
+            index.update(history_start, start, self.lines_since(start));
+            index.candidates(query, history_start)
+        };
+
+        candidates
+            .into_iter()
+            .rev()
+            .filter_map(|seq| {
+                let line = match self.line(seq) {
+                    Some(line) => line.to_string(),
+                    None => self.read_history(seq, seq + 1).ok()?.pop()?,
+                };
+                query.matches(&plain_text(&line)).then_some((seq, line))
+            })
+            .collect()
+    }
+
     /// Estimate memory usage of this buffer
     pub fn estimate_memory(&self) -> usize {
         // VecDeque overhead + string contents + string object overhead
@@ -208,6 +363,26 @@ impl Drop for ScrollbackBuffer {
     }
 }
 
+/// A range of history copied out of a [`ScrollbackBuffer`]
+#[derive(Debug)]
+pub struct HistoryRange {
+    spilled: Option<SpilledRange>,
+    lines: Vec<String>,
+}
+
+impl HistoryRange {
+    /// The lines in the range, decompressing any spilled ones
+    pub fn read(self) -> io::Result<Vec<String>> {
+        let mut lines = match self.spilled {
+            Some(spilled) => spilled.read()?,
+            None => Vec::new(),
+        };
+        lines.extend(self.lines);
+        Ok(lines)
+    }
+}
+
+/// Clones hold the in-memory lines only; spilled history stays with the original
 impl Clone for ScrollbackBuffer {
     fn clone(&self) -> Self {
         let cloned = Self {
@@ -215,6 +390,9 @@ impl Clone for ScrollbackBuffer {
             max_lines: self.max_lines,
             total_bytes: self.total_bytes,
             viewport_offset: self.viewport_offset,
+            pushed: self.pushed,
+            index: Mutex::new(ScrollbackIndex::default()),
+            spill: None,
         };
         // Update global counter for cloned bytes
         GLOBAL_SCROLLBACK_BYTES.fetch_add(cloned.total_bytes, Ordering::Relaxed);
@@ -272,6 +450,142 @@ mod tests {
         assert_eq!(buffer.get(2), Some("Line 4"));
     }
 
+    #[test]
+    fn test_find_indexes_new_lines() {
+        let mut buffer = ScrollbackBuffer::new(3);
+        buffer.push_line("cargo build".to_string());
+        buffer.push_line("error[E0308]: mismatched types".to_string());
+
+        let query = SearchQuery::parse("error").unwrap();
+        let find = |buffer: &ScrollbackBuffer| -> Vec<u64> {
+            buffer.find(&query).into_iter().map(|(seq, _)| seq).collect()
+        };
+        assert_eq!(find(&buffer), vec![1]);
+
+        buffer.push_line("\x1b[31merror\x1b[0m: aborting".to_string());
+        buffer.push_line("done".to_string());
+        assert_eq!(find(&buffer), vec![2, 1]);
+        assert_eq!(buffer.find(&query)[0].1, "\x1b[31merror\x1b[0m: aborting");
+
+        // Evicted lines no longer match
+        buffer.push_line("done".to_string());
+        assert_eq!(find(&buffer), vec![2]);
+        assert_eq!(buffer.line(2), Some("\x1b[31merror\x1b[0m: aborting"));
+        assert_eq!(buffer.line(1), None);
+    }
+
+    #[test]
+    fn test_line_sequence_numbers() {
+        let mut buffer = ScrollbackBuffer::new(3);
+        for i in 0..5 {
+            buffer.push_line(format!("Line {}", i));
+        }
+
+        assert_eq!(buffer.total_pushed(), 5);
+        assert_eq!(buffer.first_sequence(), 2);
+        assert_eq!(buffer.lines_since(3).collect::<Vec<_>>(), vec!["Line 3", "Line 4"]);
+        // Evicted lines are skipped
+        assert_eq!(buffer.lines_since(0).count(), 3);
+        assert_eq!(buffer.lines_since(5).count(), 0);
+
+        buffer.clear();
+        assert_eq!(buffer.first_sequence(), 5);
+    }
+
+    #[test]
+    fn test_read_history_includes_spilled_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Arc::new(SpillSettings {
+            dir: dir.path().to_path_buf(),
+            segment_lines: 4,
+            max_disk_bytes: 0,
+        });
+        let mut buffer = ScrollbackBuffer::with_spill(5, settings);
+        for i in 0..20 {
+            buffer.push_line(format!("Line {}", i));
+        }
+
+        assert_eq!(buffer.len(), 5);
+        assert_eq!(buffer.first_sequence(), 15);
+        assert_eq!(buffer.history_start(), 0);
+        assert!(buffer.spilled_bytes() > 0);
+
+        let lines = buffer.read_history(6, 18).unwrap();
+        let expected: Vec<String> = (6..18).map(|i| format!("Line {}", i)).collect();
+        assert_eq!(lines, expected);
+        assert_eq!(buffer.read_history(0, u64::MAX).unwrap().len(), 20);
+
+        buffer.clear();
+        assert_eq!(buffer.history_start(), 20);
+        assert!(buffer.read_history(0, u64::MAX).unwrap().is_empty());
+
+        // Turning spilling off drops what was spilled
+        for i in 20..30 {
+            buffer.push_line(format!("Line {}", i));
+        }
+        assert_eq!(buffer.history_start(), 20);
+        buffer.disable_spill();
+        assert!(!buffer.is_spilling());
+        assert_eq!(buffer.history_start(), 25);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        // Without spilling, history is just what is in memory
+        let mut plain = ScrollbackBuffer::new(5);
+        for i in 0..20 {
+            plain.push_line(format!("Line {}", i));
+        }
+        assert_eq!(plain.history_start(), 15);
+        assert_eq!(plain.read_history(0, 17).unwrap(), vec!["Line 15", "Line 16"]);
+    }
+
+    #[test]
+    fn test_find_searches_spilled_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Arc::new(SpillSettings {
+            dir: dir.path().to_path_buf(),
+            segment_lines: 4,
+            max_disk_bytes: 0,
+        });
+        let mut buffer = ScrollbackBuffer::with_spill(5, settings);
+        let query = SearchQuery::parse("panic").unwrap();
+
+        // Indexed while in memory, then spilled
+        buffer.push_line("thread 'main' panicked".to_string());
+        assert_eq!(buffer.find(&query).len(), 1);
+
+        // Spilled before ever being indexed
+        for i in 1..20 {
+            let line = if i == 7 { "another panic".to_string() } else { format!("Line {}", i) };
+            buffer.push_line(line);
+        }
+        assert_eq!(buffer.first_sequence(), 15);
+
+        let hits = buffer.find(&query);
+        assert_eq!(
+            hits,
+            vec![(7, "another panic".to_string()), (0, "thread 'main' panicked".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_history_range_reads_after_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let settings = Arc::new(SpillSettings {
+            dir: dir.path().to_path_buf(),
+            segment_lines: 4,
+            max_disk_bytes: 0,
+        });
+        let mut buffer = ScrollbackBuffer::with_spill(5, settings);
+        for i in 0..20 {
+            buffer.push_line(format!("Line {}", i));
+        }
+
+        let range = buffer.history_range(2, 17);
+        buffer.push_line("Line 20".to_string());
+        let expected: Vec<String> = (2..17).map(|i| format!("Line {}", i)).collect();
+        assert_eq!(range.read().unwrap(), expected);
+    }
+
     #[test]
     fn test_get_lines() {
         let mut buffer = ScrollbackBuffer::new(100);
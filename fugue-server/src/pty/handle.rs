@@ -2,11 +2,17 @@
 
 use std::io::{Read, Write};
 use std::sync::Arc;
+use std::time::Duration;
 
 use fugue_utils::{CcmuxError, Result};
 use parking_lot::Mutex;
 use portable_pty::{Child, MasterPty, PtySize};
 
+/// Times `reap` checks for the child's exit before killing it
+const REAP_ATTEMPTS: u32 = 10;
+/// Pause between `reap` checks
+const REAP_INTERVAL: Duration = Duration::from_millis(20);
+
 /// Handle to a running PTY
 pub struct PtyHandle {
     /// The master side of the PTY
@@ -107,6 +113,60 @@ impl PtyHandle {
             .map_err(|e| CcmuxError::pty(format!("Kill failed: {}", e)))
     }
 
+    /// Exit code of a child that has already exited; otherwise kill it
+    ///
+    /// Returns `None` for a child that had to be killed, which is how
+    /// `PaneClosed` reports killed panes.
+    pub fn exit_code_or_kill(&self) -> Result<Option<i32>> {
+        if let Some(code) = self.try_wait()? {
+            return Ok(Some(code));
+        }
+        self.kill()?;
+        Ok(None)
+    }
+
+    /// Collect the exit code once the PTY has reached EOF
+    ///
+    /// The child may close the PTY just before it exits, so it gets a moment
+    /// to finish before `exit_code_or_kill`.
+    pub async fn reap(&self) -> Result<Option<i32>> {
+        for _ in 0..REAP_ATTEMPTS {
+            if let Some(code) = self.try_wait()? {
+                return Ok(Some(code));
+            }
+            tokio::time::sleep(REAP_INTERVAL).await;
+        }
+        self.exit_code_or_kill()
+    }
+
+    /// Stop the child's process group (SIGSTOP) until `resume` is called
+    pub fn pause(&self) -> Result<()> {
+        self.signal_group(libc::SIGSTOP)
+    }
+
+    /// Continue a process group stopped by `pause` (SIGCONT)
+    pub fn resume(&self) -> Result<()> {
+        self.signal_group(libc::SIGCONT)
+    }
+
+    /// Send a signal to the child's process group
+    fn signal_group(&self, signal: libc::c_int) -> Result<()> {
+        let pid = self
+            .child
+            .lock()
+            .process_id()
+            .ok_or_else(|| CcmuxError::pty("Child has no process ID"))?;
+        // The child leads its own session, so its PID is also its process group ID
+        // SAFETY: kill(2) has no memory-safety preconditions
+        if unsafe { libc::kill(-(pid as libc::pid_t), signal) } != 0 {
+            return Err(CcmuxError::pty(format!(
+                "Signal failed: {}",
+                std::io::Error::last_os_error()
+            )));
+        }
+        Ok(())
+    }
+
     /// Get a clone of the reader (for async reading)
     pub fn clone_reader(&self) -> Arc<Mutex<Box<dyn Read + Send>>> {
         self.reader.clone()
@@ -189,6 +189,25 @@ mod tests {
         handle.kill().unwrap();
     }
 
+    #[tokio::test]
+    async fn test_reap_reports_exit_code() {
+        let mut manager = PtyManager::new();
+        let exited = Uuid::new_v4();
+        manager
+            .spawn(exited, PtyConfig::command("sh").with_arg("-c").with_arg("exit 3"))
+            .unwrap();
+        let handle = manager.remove(exited).unwrap();
+        let mut buf = [0u8; 1024];
+        while matches!(handle.read(&mut buf), Ok(n) if n > 0) {}
+        assert_eq!(handle.reap().await.unwrap(), Some(3));
+
+        // A child still running is killed and reported without a code
+        let running = Uuid::new_v4();
+        manager.spawn(running, PtyConfig::command("sleep").with_arg("30")).unwrap();
+        let handle = manager.remove(running).unwrap();
+        assert_eq!(handle.exit_code_or_kill().unwrap(), None);
+    }
+
     #[test]
     fn test_pty_resize() {
         let mut manager = PtyManager::new();
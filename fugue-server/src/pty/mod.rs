@@ -11,13 +11,17 @@ mod config;
 mod handle;
 mod manager;
 mod output;
+mod search;
+mod spill;
 
 pub use buffer::{
     check_memory_status, check_memory_status_with_thresholds, format_memory_usage,
-    global_scrollback_bytes, MemoryStatus, ScrollbackBuffer, DEFAULT_MEMORY_CRITICAL_BYTES,
+    global_scrollback_bytes, HistoryRange, MemoryStatus, ScrollbackBuffer, DEFAULT_MEMORY_CRITICAL_BYTES,
     DEFAULT_MEMORY_WARNING_BYTES,
 };
 pub use config::PtyConfig;
 pub use handle::PtyHandle;
 pub use manager::PtyManager;
 pub use output::{OutputPollerConfig, PaneClosedNotification, PollerHandle, PollerManager, PtyOutputPoller};
+pub use search::{plain_text, SearchQuery};
+pub use spill::{remove_stale_spills, ScrollbackSpill, SpillSettings};
@@ -22,7 +22,7 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 use uuid::Uuid;
 
-use fugue_protocol::ServerMessage;
+use fugue_protocol::{AgentActivity, PaneState, ServerMessage};
 
 use crate::registry::ClientRegistry;
 use crate::sideband::{AsyncCommandExecutor, SidebandCommand, SidebandParser, SplitDirection};
@@ -289,29 +289,32 @@ impl PtyOutputPoller {
         // Final flush before exiting
         self.flush().await;
 
-        // Notify clients that the pane has closed
-        let close_msg = ServerMessage::PaneClosed {
-            pane_id: self.pane_id,
-            exit_code: None, // We don't have access to the exit code here
-        };
-        self.registry.broadcast_to_session(self.session_id, close_msg).await;
-
-        // Notify server to clean up the pane from session state
-        // (only if we have a cleanup channel - this allows the server to
-        // remove zombie panes and empty sessions)
+        // Notify server to clean up the pane from session state (only if we
+        // have a cleanup channel - this allows the server to remove zombie
+        // panes and empty sessions). The cleanup collects the exit code and
+        // tells clients the pane closed; without it, they are told here.
+        let mut notified = false;
         if let Some(tx) = &self.pane_closed_tx {
             let notification = PaneClosedNotification {
                 session_id: self.session_id,
                 pane_id: self.pane_id,
             };
-            if let Err(e) = tx.send(notification).await {
-                warn!(
+            match tx.send(notification).await {
+                Ok(()) => notified = true,
+                Err(e) => warn!(
                     pane_id = %self.pane_id,
                     error = %e,
                     "Failed to send pane cleanup notification"
-                );
+                ),
             }
         }
+        if !notified {
+            let close_msg = ServerMessage::PaneClosed {
+                pane_id: self.pane_id,
+                exit_code: None,
+            };
+            self.registry.broadcast_to_session(self.session_id, close_msg).await;
+        }
 
         info!(
             pane_id = %self.pane_id,
@@ -547,7 +550,7 @@ impl PtyOutputPoller {
     /// Flush the buffer by broadcasting to session clients and routing to pane state
     ///
     /// This method:
-    /// 1. Routes output to pane.process() for scrollback and agent detection (FEAT-084)
+    /// 1. Routes output to pane.process_output() for scrollback and agent detection (FEAT-084)
     /// 2. Handles DSR [6n] cursor position requests (BUG-053)
     /// 3. Broadcasts PaneStateChanged if agent state changed
     /// 4. Broadcasts Output to all session clients
@@ -556,7 +559,7 @@ impl PtyOutputPoller {
             return;
         }
 
-        let data = std::mem::take(&mut self.buffer);
+        let mut data = std::mem::take(&mut self.buffer);
         self.buffer = Vec::with_capacity(self.config.max_buffer_size);
 
         trace!(
@@ -569,16 +572,19 @@ impl PtyOutputPoller {
         // Route output to pane state for scrollback and agent detection (FEAT-084)
         // Also handle DSR [6n] cursor position requests (BUG-053)
         let state_change_msg = if let Some(executor) = &self.command_executor {
+            let config = self.registry.config().load_full();
             let session_manager = executor.session_manager();
             let mut manager = session_manager.write().await;
             if let Some(pane) = manager.find_pane_mut(self.pane_id) {
-                // Process returns Some(AgentState) if state changed (FEAT-084)
-                let state_changed = pane.process(&data);
+                // Process returns Some(AgentState) if state changed (FEAT-084).
+                // Claude stream-json output is replaced by its rendered view.
+                let (display, state_changed) = pane.process_output(&data, &config);
+                let raw = std::mem::replace(&mut data, display);
 
                 // BUG-053: Handle DSR [6n] cursor position request
                 // Check if the output contains ESC[6n (cursor position query)
                 // The terminal should respond with ESC[row;colR
-                if Self::contains_dsr_cpr(&data) {
+                if Self::contains_dsr_cpr(&raw) {
                     if let Some(screen) = pane.screen() {
                         let (row, col) = screen.cursor_position();
                         // vt100 uses 0-based indexing, DSR response uses 1-based
@@ -669,10 +675,51 @@ impl PtyOutputPoller {
             None
         };
 
+        // An agent stopping at a tool-permission prompt gets its own event
+        let permission_msg = match &state_change_msg {
+            Some(ServerMessage::PaneStateChanged {
+                state: PaneState::Agent(agent),
+                ..
+            }) if agent.activity == AgentActivity::AwaitingPermission => agent
+                .permission_request()
+                .map(|request| ServerMessage::PermissionRequested {
+                    pane_id: self.pane_id,
+                    agent_type: agent.agent_type.clone(),
+                    request,
+                }),
+            _ => None,
+        };
+
+        // Usage only changes with agent state, so budgets are checked then
+        let check_budgets = matches!(
+            &state_change_msg,
+            Some(ServerMessage::PaneStateChanged { state: PaneState::Agent(agent), .. })
+                if agent.usage().is_some()
+        );
+
         // Broadcast state change if agent state changed
         if let Some(state_msg) = state_change_msg {
             self.registry.broadcast_to_session(self.session_id, state_msg).await;
         }
+        if let Some(permission_msg) = permission_msg {
+            self.registry.broadcast_to_session(self.session_id, permission_msg).await;
+        }
+        if check_budgets {
+            if let Some(executor) = &self.command_executor {
+                crate::usage::enforce_budgets(
+                    executor.session_manager(),
+                    executor.pty_manager(),
+                    &self.registry,
+                    self.pane_id,
+                )
+                .await;
+            }
+        }
+
+        // Nothing to display yet, e.g. a stream-json event still waiting for its newline
+        if data.is_empty() {
+            return;
+        }
 
         // Broadcast output to session clients
         let msg = ServerMessage::Output {
@@ -0,0 +1,216 @@
+//! Full-text index over scrollback
+//!
+//! Each scrollback buffer keeps an inverted index from lowercased words to
+//! the sequence numbers of the lines containing them. Lines are indexed
+//! lazily by the first search after they were pushed, so panes that are
+//! never searched pay nothing on the output path.
+
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+/// Words longer than this are indexed (and searched) by their prefix
+const MAX_TOKEN_CHARS: usize = 64;
+
+/// Evicted lines to accumulate before dropping them from the index
+const PRUNE_LINES: u64 = 1024;
+
+/// Parsed scrollback search query
+///
+/// Every whitespace-separated term, or double-quoted phrase, must occur in
+/// a line. Matching ignores case and escape sequences, and each word of a
+/// term must start at a word boundary: `conn` matches "connection" but
+/// `nection` does not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+    tokens: Vec<String>,
+}
+
+impl SearchQuery {
+    /// Parse a query, or `None` if it has no words to search for
+    pub fn parse(query: &str) -> Option<Self> {
+        let mut terms = Vec::new();
+        for (i, part) in query.split('"').enumerate() {
+            if i % 2 == 1 {
+                // Inside quotes: the whole phrase is one term
+                let phrase = part.trim();
+                if !phrase.is_empty() {
+                    terms.push(phrase.to_lowercase());
+                }
+            } else {
+                terms.extend(part.split_whitespace().map(str::to_lowercase));
+            }
+        }
+
+        let mut tokens: Vec<String> = terms.iter().flat_map(|term| tokenize(term)).collect();
+        tokens.sort();
+        tokens.dedup();
+        if tokens.is_empty() {
+            return None;
+        }
+        Some(Self { terms, tokens })
+    }
+
+    /// Whether a line (already stripped of escape sequences) matches
+    pub fn matches(&self, text: &str) -> bool {
+        let text = text.to_lowercase();
+        self.terms.iter().all(|term| text.contains(term.as_str()))
+    }
+}
+
+/// A line as shown to the user: escape sequences and control characters removed
+pub fn plain_text(line: &str) -> String {
+    let stripped = strip_ansi_escapes::strip(line.as_bytes());
+    String::from_utf8_lossy(&stripped)
+        .chars()
+        .filter(|c| !c.is_control() || *c == '\t')
+        .collect()
+}
+
+/// Lowercased words of a line
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(|word| word.chars().take(MAX_TOKEN_CHARS).flat_map(char::to_lowercase).collect())
+}
+
+/// Inverted index of the lines in one scrollback buffer
+#[derive(Debug, Default)]
+pub struct ScrollbackIndex {
+    /// Word -> ascending sequence numbers of lines containing it
+    postings: BTreeMap<String, Vec<u64>>,
+    /// Sequence number of the next line to index
+    next_seq: u64,
+    /// Lines before this have been dropped from `postings`
+    pruned_before: u64,
+}
+
+impl ScrollbackIndex {
+    /// Sequence number of the first line not yet indexed
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Index new lines, forgetting lines before `first_seq`
+    ///
+    /// `lines` are the buffer's lines starting at `start`, which must be
+    /// `max(next_seq, first_seq)`.
+    pub fn update<'a>(&mut self, first_seq: u64, start: u64, lines: impl Iterator<Item = &'a str>) {
+        let mut seq = start;
+        for line in lines {
+            let mut words: Vec<String> = tokenize(&plain_text(line)).collect();
+            words.sort();
+            words.dedup();
+            for word in words {
+                self.postings.entry(word).or_default().push(seq);
+            }
+            seq += 1;
+        }
+        self.next_seq = seq;
+
+        if first_seq >= self.pruned_before + PRUNE_LINES {
+            self.postings.retain(|_, seqs| {
+                let evicted = seqs.partition_point(|s| *s < first_seq);
+                seqs.drain(..evicted);
+                !seqs.is_empty()
+            });
+            self.pruned_before = first_seq;
+        }
+    }
+
+    /// Lines from `first_seq` on containing every word of the query, ascending
+    ///
+    /// Candidates still need checking with [`SearchQuery::matches`].
+    pub fn candidates(&self, query: &SearchQuery, first_seq: u64) -> Vec<u64> {
+        let mut result: Option<Vec<u64>> = None;
+        for token in &query.tokens {
+            let range = (Bound::Included(token.as_str()), Bound::Unbounded);
+            let mut seqs: Vec<u64> = self
+                .postings
+                .range::<str, _>(range)
+                .take_while(|(word, _)| word.starts_with(token.as_str()))
+                .flat_map(|(_, seqs)| {
+                    let start = seqs.partition_point(|s| *s < first_seq);
+                    seqs[start..].iter().copied()
+                })
+                .collect();
+            seqs.sort_unstable();
+            seqs.dedup();
+
+            let seqs = match result {
+                None => seqs,
+                Some(previous) => previous
+                    .into_iter()
+                    .filter(|seq| seqs.binary_search(seq).is_ok())
+                    .collect(),
+            };
+            if seqs.is_empty() {
+                return seqs;
+            }
+            result = Some(seqs);
+        }
+        result.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_of(lines: &[&str]) -> ScrollbackIndex {
+        let mut index = ScrollbackIndex::default();
+        index.update(0, 0, lines.iter().copied());
+        index
+    }
+
+    #[test]
+    fn test_query_parsing() {
+        let query = SearchQuery::parse("Redis \"connection  refused\" :6379").unwrap();
+        assert_eq!(query.terms, vec!["redis", "connection  refused", ":6379"]);
+        assert_eq!(query.tokens, vec!["6379", "connection", "redis", "refused"]);
+
+        assert!(SearchQuery::parse("  ").is_none());
+        assert!(SearchQuery::parse("-> \"\"").is_none());
+    }
+
+    #[test]
+    fn test_candidates_match_word_prefixes() {
+        let index = index_of(&[
+            "\x1b[31merror\x1b[0m: Connection refused (os error 111)",
+            "connected to redis",
+            "stderr closed",
+        ]);
+
+        let query = SearchQuery::parse("conn").unwrap();
+        assert_eq!(index.candidates(&query, 0), vec![0, 1]);
+
+        let query = SearchQuery::parse("error connection").unwrap();
+        assert_eq!(index.candidates(&query, 0), vec![0]);
+
+        // Not at the start of a word
+        let query = SearchQuery::parse("rror").unwrap();
+        assert!(index.candidates(&query, 0).is_empty());
+    }
+
+    #[test]
+    fn test_matches_phrases_case_insensitively() {
+        let query = SearchQuery::parse("\"connection refused\"").unwrap();
+        assert!(query.matches(&plain_text("\x1b[1mERROR\x1b[0m: Connection refused")));
+        assert!(!query.matches("refused connection"));
+    }
+
+    #[test]
+    fn test_evicted_lines_are_pruned() {
+        let mut index = ScrollbackIndex::default();
+        let lines: Vec<String> = (0..PRUNE_LINES * 2).map(|i| format!("line {}", i)).collect();
+        index.update(0, 0, lines.iter().map(String::as_str));
+        assert_eq!(index.postings["line"].len(), lines.len());
+
+        index.update(PRUNE_LINES + 10, index.next_seq(), std::iter::empty());
+        assert_eq!(index.postings["line"].len(), lines.len() - PRUNE_LINES as usize - 10);
+        assert!(!index.postings.contains_key("5"));
+
+        let query = SearchQuery::parse("line").unwrap();
+        assert_eq!(index.candidates(&query, PRUNE_LINES * 2 - 1), vec![PRUNE_LINES * 2 - 1]);
+    }
+}
@@ -0,0 +1,397 @@
+//! Spilling old scrollback to disk
+//!
+//! With `[terminal.scrollback.spill]` enabled, lines evicted from a pane's
+//! in-memory scrollback are collected into segments of `segment_lines`
+//! lines, compressed with zstd and written under
+//! `<dir>/<server pid>/<buffer id>/<first sequence number>.zst`.
+//!
+//! Segments are a cache rather than state: they are deleted with their
+//! buffer, and directories left behind by servers that are no longer
+//! running are removed at startup.
+
+use std::collections::VecDeque;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::config::SpillConfig;
+
+/// zstd level for segments; spilling happens on the PTY output path
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Resolved `[terminal.scrollback.spill]` settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpillSettings {
+    /// Directory for this server's segments
+    pub dir: PathBuf,
+    /// Lines per segment
+    pub segment_lines: usize,
+    /// Disk limit per buffer in bytes (0 = unlimited)
+    pub max_disk_bytes: u64,
+}
+
+impl SpillSettings {
+    /// Resolve `[terminal.scrollback.spill]`, or `None` when spilling is off
+    pub fn from_config(config: &SpillConfig) -> Option<Self> {
+        config.enabled.then(|| Self {
+            dir: spill_root(config).join(std::process::id().to_string()),
+            segment_lines: config.segment_lines.max(1),
+            max_disk_bytes: config.max_disk_mb * 1024 * 1024,
+        })
+    }
+}
+
+/// Directory holding every server's segments
+fn spill_root(config: &SpillConfig) -> PathBuf {
+    config
+        .dir
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| fugue_utils::cache_dir().join("scrollback"))
+}
+
+/// Remove segments left behind by servers that are no longer running
+///
+/// Called at startup when spilling is enabled.
+pub fn remove_stale_spills(config: &SpillConfig) {
+    if config.enabled {
+        remove_stale_dirs(&spill_root(config));
+    }
+}
+
+/// Remove the segment directories of servers that are no longer running
+fn remove_stale_dirs(root: &Path) {
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        // SAFETY: kill(2) with signal 0 only checks that the process exists
+        let alive = unsafe { libc::kill(pid as libc::pid_t, 0) } == 0
+            || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+        if !alive {
+            debug!("Removing scrollback spill of dead server {}", pid);
+            let _ = std::fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// One compressed segment file
+#[derive(Debug, Clone)]
+struct Segment {
+    first_seq: u64,
+    line_count: usize,
+    /// Compressed size on disk
+    bytes: u64,
+}
+
+impl Segment {
+    fn end_seq(&self) -> u64 {
+        self.first_seq + self.line_count as u64
+    }
+}
+
+/// Lines spilled from one scrollback buffer, oldest first
+///
+/// Spilled lines keep the sequence numbers they had in the buffer, so
+/// together with the buffer they form one contiguous history.
+#[derive(Debug)]
+pub struct ScrollbackSpill {
+    settings: Arc<SpillSettings>,
+    /// Segment directory, created with the first segment
+    dir: PathBuf,
+    segments: VecDeque<Segment>,
+    /// Total compressed size of `segments`
+    disk_bytes: u64,
+    /// Spilled lines not yet written to a segment
+    pending: Vec<String>,
+    /// Sequence number of the first pending line
+    pending_seq: u64,
+    /// Most recently read segment, so paging through history decodes each once
+    cache: SegmentCache,
+}
+
+type SegmentCache = Arc<Mutex<Option<(u64, Arc<Vec<String>>)>>>;
+
+impl ScrollbackSpill {
+    /// Create an empty spill whose first line will have sequence number `first_seq`
+    pub fn new(settings: Arc<SpillSettings>, first_seq: u64) -> Self {
+        let dir = settings.dir.join(Uuid::new_v4().to_string());
+        Self {
+            settings,
+            dir,
+            segments: VecDeque::new(),
+            disk_bytes: 0,
+            pending: Vec::new(),
+            pending_seq: first_seq,
+            cache: SegmentCache::default(),
+        }
+    }
+
+    /// Sequence number of the oldest spilled line
+    pub fn first_sequence(&self) -> u64 {
+        self.segments
+            .front()
+            .map_or(self.pending_seq, |segment| segment.first_seq)
+    }
+
+    /// Sequence number the next spilled line will get
+    pub fn end_sequence(&self) -> u64 {
+        self.pending_seq + self.pending.len() as u64
+    }
+
+    /// Compressed size of the segments on disk
+    pub fn disk_bytes(&self) -> u64 {
+        self.disk_bytes
+    }
+
+    /// Append the line evicted from the buffer after the last one
+    pub fn push(&mut self, line: String) {
+        self.pending.push(line);
+        if self.pending.len() < self.settings.segment_lines {
+            return;
+        }
+        if let Err(e) = self.write_segment() {
+            // A gap would shift every older line, so start over instead
+            warn!("Failed to spill scrollback to {}: {}", self.dir.display(), e);
+            let next_seq = self.end_sequence();
+            self.clear(next_seq);
+        }
+    }
+
+    /// Compress the pending lines into a new segment
+    fn write_segment(&mut self) -> io::Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let mut raw = Vec::new();
+        for line in &self.pending {
+            raw.extend_from_slice(&(line.len() as u32).to_le_bytes());
+            raw.extend_from_slice(line.as_bytes());
+        }
+        let data = zstd::encode_all(raw.as_slice(), COMPRESSION_LEVEL)?;
+        std::fs::write(self.segment_path(self.pending_seq), &data)?;
+
+        self.segments.push_back(Segment {
+            first_seq: self.pending_seq,
+            line_count: self.pending.len(),
+            bytes: data.len() as u64,
+        });
+        self.disk_bytes += data.len() as u64;
+        self.pending_seq += self.pending.len() as u64;
+        self.pending.clear();
+
+        let max = self.settings.max_disk_bytes;
+        while max > 0 && self.disk_bytes > max {
+            let Some(oldest) = self.segments.pop_front() else {
+                break;
+            };
+            self.disk_bytes -= oldest.bytes;
+            let _ = std::fs::remove_file(self.segment_path(oldest.first_seq));
+        }
+        Ok(())
+    }
+
+    /// Spilled lines with sequence numbers in `start..end`
+    pub fn read(&self, start: u64, end: u64) -> io::Result<Vec<String>> {
+        self.range(start, end).read()
+    }
+
+    /// Copy what is needed to read `start..end`, without decoding anything
+    ///
+    /// The copy can be read without holding the spill; segments deleted in
+    /// the meantime fail to read.
+    pub fn range(&self, start: u64, end: u64) -> SpilledRange {
+        let first = self.segments.partition_point(|segment| segment.end_seq() <= start);
+        let segments = self
+            .segments
+            .iter()
+            .skip(first)
+            .take_while(|segment| segment.first_seq < end)
+            .cloned()
+            .collect();
+
+        let pending = if end > self.pending_seq {
+            let from = (start.saturating_sub(self.pending_seq) as usize).min(self.pending.len());
+            let to = ((end - self.pending_seq) as usize).min(self.pending.len());
+            self.pending[from..to.max(from)].to_vec()
+        } else {
+            Vec::new()
+        };
+
+        SpilledRange {
+            dir: self.dir.clone(),
+            segments,
+            pending,
+            start,
+            end,
+            cache: Arc::clone(&self.cache),
+        }
+    }
+
+    /// Drop everything spilled so far; the next line gets `next_seq`
+    pub fn clear(&mut self, next_seq: u64) {
+        for segment in &self.segments {
+            let _ = std::fs::remove_file(self.segment_path(segment.first_seq));
+        }
+        self.segments.clear();
+        self.disk_bytes = 0;
+        self.pending.clear();
+        self.pending_seq = next_seq;
+        *self.cache.lock() = None;
+    }
+
+    fn segment_path(&self, first_seq: u64) -> PathBuf {
+        segment_path(&self.dir, first_seq)
+    }
+}
+
+impl Drop for ScrollbackSpill {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// A range of spilled lines copied out of a [`ScrollbackSpill`]
+#[derive(Debug)]
+pub struct SpilledRange {
+    dir: PathBuf,
+    segments: Vec<Segment>,
+    /// Pending lines in the range
+    pending: Vec<String>,
+    start: u64,
+    end: u64,
+    cache: SegmentCache,
+}
+
+impl SpilledRange {
+    /// Decompress the segments and return the lines in the range
+    pub fn read(self) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        for segment in &self.segments {
+            let decoded = self.load(segment)?;
+            let from = self.start.saturating_sub(segment.first_seq) as usize;
+            let to = (self.end.min(segment.end_seq()) - segment.first_seq) as usize;
+            lines.extend_from_slice(&decoded[from..to]);
+        }
+        lines.extend(self.pending);
+        Ok(lines)
+    }
+
+    /// Decompress a segment, or take it from the cache
+    fn load(&self, segment: &Segment) -> io::Result<Arc<Vec<String>>> {
+        let mut cache = self.cache.lock();
+        if let Some((first_seq, lines)) = cache.as_ref() {
+            if *first_seq == segment.first_seq {
+                return Ok(Arc::clone(lines));
+            }
+        }
+
+        let data = std::fs::read(segment_path(&self.dir, segment.first_seq))?;
+        let raw = zstd::decode_all(data.as_slice())?;
+        let lines = Arc::new(decode_lines(&raw, segment.line_count)?);
+        *cache = Some((segment.first_seq, Arc::clone(&lines)));
+        Ok(lines)
+    }
+}
+
+fn segment_path(dir: &Path, first_seq: u64) -> PathBuf {
+    dir.join(format!("{}.zst", first_seq))
+}
+
+/// Split a decompressed segment back into lines
+fn decode_lines(mut raw: &[u8], line_count: usize) -> io::Result<Vec<String>> {
+    let corrupt = || io::Error::new(io::ErrorKind::InvalidData, "corrupt scrollback segment");
+    let mut lines = Vec::with_capacity(line_count);
+    while !raw.is_empty() {
+        let (len, rest) = raw.split_first_chunk::<4>().ok_or_else(corrupt)?;
+        let len = u32::from_le_bytes(*len) as usize;
+        let line = rest.get(..len).ok_or_else(corrupt)?;
+        lines.push(String::from_utf8_lossy(line).into_owned());
+        raw = &rest[len..];
+    }
+    if lines.len() != line_count {
+        return Err(corrupt());
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(dir: &Path, segment_lines: usize, max_disk_bytes: u64) -> Arc<SpillSettings> {
+        Arc::new(SpillSettings {
+            dir: dir.to_path_buf(),
+            segment_lines,
+            max_disk_bytes,
+        })
+    }
+
+    #[test]
+    fn test_spilled_lines_read_back_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spill = ScrollbackSpill::new(settings(dir.path(), 4, 0), 100);
+        for i in 0..10 {
+            spill.push(format!("\x1b[32mline\x1b[0m {}", i));
+        }
+
+        assert_eq!(spill.segments.len(), 2);
+        assert_eq!(spill.pending.len(), 2);
+        assert_eq!(spill.first_sequence(), 100);
+        assert_eq!(spill.end_sequence(), 110);
+        assert!(spill.disk_bytes() > 0);
+
+        let lines = spill.read(103, 109).unwrap();
+        let expected: Vec<String> = (3..9).map(|i| format!("\x1b[32mline\x1b[0m {}", i)).collect();
+        assert_eq!(lines, expected);
+        assert_eq!(spill.read(0, 101).unwrap().len(), 1);
+        assert!(spill.read(110, 200).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_disk_limit_drops_oldest_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spill = ScrollbackSpill::new(settings(dir.path(), 100, 1), 0);
+        for i in 0..250 {
+            spill.push(format!("line {}", i));
+        }
+
+        // Each segment alone is over the limit, so none are kept
+        assert_eq!(spill.first_sequence(), 200);
+        assert_eq!(spill.read(0, 250).unwrap().len(), 50);
+    }
+
+    #[test]
+    fn test_segments_removed_on_clear_and_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut spill = ScrollbackSpill::new(settings(dir.path(), 2, 0), 0);
+        for i in 0..5 {
+            spill.push(format!("line {}", i));
+        }
+        let spill_dir = spill.dir.clone();
+        assert_eq!(std::fs::read_dir(&spill_dir).unwrap().count(), 2);
+
+        spill.clear(42);
+        assert_eq!(std::fs::read_dir(&spill_dir).unwrap().count(), 0);
+        assert_eq!(spill.first_sequence(), 42);
+
+        spill.push("again".into());
+        spill.push("again".into());
+        drop(spill);
+        assert!(!spill_dir.exists());
+    }
+
+    #[test]
+    fn test_decode_rejects_truncated_segment() {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&5u32.to_le_bytes());
+        raw.extend_from_slice(b"abc");
+        assert!(decode_lines(&raw, 1).is_err());
+    }
+}
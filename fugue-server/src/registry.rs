@@ -13,7 +13,13 @@ use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+use crate::buffers::PasteBuffers;
+use crate::config::{new_config_handle, ConfigHandle};
+use crate::expect::ExpectRegistry;
+use crate::hooks::HookRunner;
+use crate::notifications::{self, Notification};
 use crate::observability::Metrics;
+use crate::usage::FiredBudgets;
 use fugue_protocol::{ClientType, ServerMessage};
 
 /// Type alias for session IDs (matches the Uuid type used in session module)
@@ -89,6 +95,18 @@ pub struct ClientRegistry {
     session_clients: DashMap<SessionId, HashSet<ClientId>>,
     /// Counter for generating unique client IDs
     next_client_id: AtomicU64,
+    /// Clients receiving broadcasts from every session -> whether they want `Output`
+    event_watchers: DashMap<ClientId, bool>,
+    /// Pending server-side Expect requests, fed from session broadcasts
+    expect: ExpectRegistry,
+    /// Paste buffers shared by all clients
+    buffers: PasteBuffers,
+    /// `[[hooks]]` runner, fed from broadcasts and attaches
+    hooks: HookRunner,
+    /// Usage budgets already reported
+    fired_budgets: FiredBudgets,
+    /// Server configuration, swapped on reload
+    config: ConfigHandle,
 }
 
 impl Default for ClientRegistry {
@@ -98,12 +116,23 @@ impl Default for ClientRegistry {
 }
 
 impl ClientRegistry {
-    /// Create a new empty client registry
+    /// Create a new empty client registry with the default configuration
     pub fn new() -> Self {
+        Self::with_config(new_config_handle())
+    }
+
+    /// Create a new empty client registry reading settings from `config`
+    pub fn with_config(config: ConfigHandle) -> Self {
         Self {
             clients: DashMap::new(),
             session_clients: DashMap::new(),
             next_client_id: AtomicU64::new(1),
+            event_watchers: DashMap::new(),
+            expect: ExpectRegistry::new(),
+            buffers: PasteBuffers::new(),
+            hooks: HookRunner::new(),
+            fired_budgets: FiredBudgets::default(),
+            config,
         }
     }
 
@@ -148,6 +177,7 @@ impl ClientRegistry {
             if let Some(session_id) = entry.attached_session {
                 self.remove_client_from_session_index(client_id, session_id);
             }
+            self.event_watchers.remove(&client_id);
             debug!("Unregistered client {}", client_id);
         }
     }
@@ -192,6 +222,111 @@ impl ClientRegistry {
         })
     }
 
+    // ==================== Event Subscriptions ====================
+
+    /// Deliver broadcasts from every session to a client, not just its attached one
+    ///
+    /// `Output` is only forwarded when `include_output` is set, since it is
+    /// by far the highest-volume broadcast.
+    pub fn subscribe_events(&self, client_id: ClientId, include_output: bool) -> bool {
+        if !self.clients.contains_key(&client_id) {
+            return false;
+        }
+        self.event_watchers.insert(client_id, include_output);
+        debug!(client = %client_id, include_output, "Client subscribed to events");
+        true
+    }
+
+    /// Stop delivering broadcasts from unattached sessions to a client
+    pub fn unsubscribe_events(&self, client_id: ClientId) -> bool {
+        self.event_watchers.remove(&client_id).is_some()
+    }
+
+    /// Pending server-side Expect requests
+    pub fn expect(&self) -> &ExpectRegistry {
+        &self.expect
+    }
+
+    /// Paste buffers shared by all clients
+    pub fn buffers(&self) -> &PasteBuffers {
+        &self.buffers
+    }
+
+    /// Runner for `[[hooks]]`
+    pub fn hooks(&self) -> &HookRunner {
+        &self.hooks
+    }
+
+    /// Usage budgets already reported
+    pub fn fired_budgets(&self) -> &FiredBudgets {
+        &self.fired_budgets
+    }
+
+    /// Server configuration, swapped on reload
+    pub fn config(&self) -> &ConfigHandle {
+        &self.config
+    }
+
+    /// Deliver a notification to its configured sinks
+    ///
+    /// Terminal alerts go to the TUI clients attached to the notification's
+    /// session.
+    pub fn notify(&self, notification: Notification) {
+        let tui_clients = notification
+            .session_id
+            .map(|id| self.tui_clients_in_session(id))
+            .unwrap_or_default();
+        let config = self.config.load();
+        for alert in notifications::dispatch(&config.notifications, &notification, !tui_clients.is_empty()) {
+            for &client_id in &tui_clients {
+                self.try_send_to_client(client_id, alert.clone());
+            }
+        }
+    }
+
+    /// Run hooks and raise notifications for a broadcast
+    fn observe_events(&self, session_id: Option<SessionId>, message: &ServerMessage) {
+        let config = self.config.load();
+        for event in self.hooks.observe(&config.hooks, session_id, message) {
+            if let Some(notification) = Notification::from_event(&event) {
+                self.notify(notification);
+            }
+        }
+    }
+
+    /// Forward a session broadcast to event watchers not attached to that session
+    ///
+    /// Uses `try_send` so a slow watcher never stalls PTY output.
+    fn notify_event_watchers(
+        &self,
+        session_id: SessionId,
+        except_client: Option<ClientId>,
+        message: &ServerMessage,
+    ) {
+        if self.event_watchers.is_empty() {
+            return;
+        }
+
+        let is_output = matches!(message, ServerMessage::Output { .. });
+        let watchers: Vec<ClientId> = self
+            .event_watchers
+            .iter()
+            .filter(|w| *w.value() || !is_output)
+            .map(|w| *w.key())
+            .filter(|&id| Some(id) != except_client)
+            .collect();
+
+        for client_id in watchers {
+            let attached = self
+                .clients
+                .get(&client_id)
+                .and_then(|entry| entry.attached_session);
+            if attached != Some(session_id) {
+                self.try_send_to_client(client_id, message.clone());
+            }
+        }
+    }
+
     // ==================== Session Association ====================
 
     /// Attach a client to a session
@@ -216,7 +351,10 @@ impl ClientRegistry {
         }
 
         // Update client's attached session
+        let changed = entry.attached_session != Some(session_id);
         entry.attached_session = Some(session_id);
+        let client_type = entry.client_type;
+        drop(entry);
 
         // Add to session's client set
         self.session_clients
@@ -225,6 +363,10 @@ impl ClientRegistry {
             .insert(client_id);
 
         debug!("Client {} attached to session {}", client_id, session_id);
+        if changed {
+            self.hooks
+                .client_attached(&self.config.load().hooks, client_id, client_type, session_id);
+        }
         true
     }
 
@@ -345,6 +487,10 @@ impl ClientRegistry {
     /// Clients with closed channels (disconnected) will be automatically
     /// unregistered.
     pub async fn broadcast_to_session(&self, session_id: SessionId, message: ServerMessage) -> usize {
+        self.expect.observe(&message);
+        self.observe_events(Some(session_id), &message);
+        self.notify_event_watchers(session_id, None, &message);
+
         // Get the list of client IDs for this session
         let client_ids: Vec<ClientId> = match self.session_clients.get(&session_id) {
             Some(clients) => clients.iter().copied().collect(),
@@ -380,6 +526,10 @@ impl ClientRegistry {
     /// Clients with closed channels (disconnected) will be automatically
     /// unregistered.
     pub fn try_broadcast_to_session(&self, session_id: SessionId, message: ServerMessage) -> usize {
+        self.expect.observe(&message);
+        self.observe_events(Some(session_id), &message);
+        self.notify_event_watchers(session_id, None, &message);
+
         // Get the list of client IDs for this session
         let client_ids: Vec<ClientId> = match self.session_clients.get(&session_id) {
             Some(clients) => clients.iter().copied().collect(),
@@ -419,6 +569,10 @@ impl ClientRegistry {
         except_client: ClientId,
         message: ServerMessage,
     ) -> usize {
+        self.expect.observe(&message);
+        self.observe_events(Some(session_id), &message);
+        self.notify_event_watchers(session_id, Some(except_client), &message);
+
         // Log all clients attached to this session for debugging
         let all_session_clients: Vec<ClientId> = self.session_clients
             .get(&session_id)
@@ -533,6 +687,7 @@ impl ClientRegistry {
     ///
     /// Returns the number of clients that successfully received the message.
     pub fn broadcast_to_all(&self, message: ServerMessage) -> usize {
+        self.observe_events(None, &message);
         let client_ids = self.get_all_clients();
 
         if client_ids.is_empty() {
@@ -555,6 +710,7 @@ impl ClientRegistry {
     ///
     /// Returns the number of clients that successfully received the message.
     pub fn broadcast_to_all_except(&self, except_client: ClientId, message: ServerMessage) -> usize {
+        self.observe_events(None, &message);
         let client_ids = self.get_all_clients();
 
         if client_ids.is_empty() {
@@ -1420,4 +1576,150 @@ mod tests {
         let focus = registry.get_client_focus(client_id).unwrap();
         assert_eq!(focus.active_pane_id, Some(new_pane_id));
     }
-}
\ No newline at end of file
+
+    // ==================== Event Subscription Tests ====================
+
+    #[tokio::test]
+    async fn test_event_watcher_receives_unattached_session_broadcasts() {
+        let (registry, watcher, mut rx) = setup_client();
+        let session_id = Uuid::new_v4();
+        assert!(registry.subscribe_events(watcher, false));
+
+        registry
+            .broadcast_to_session(session_id, ServerMessage::SessionEnded { session_id })
+            .await;
+        assert!(matches!(rx.try_recv(), Ok(ServerMessage::SessionEnded { .. })));
+
+        registry
+            .broadcast_to_session(
+                session_id,
+                ServerMessage::Output {
+                    pane_id: Uuid::new_v4(),
+                    data: b"hi".to_vec(),
+                },
+            )
+            .await;
+        assert!(rx.try_recv().is_err(), "Output is filtered unless requested");
+    }
+
+    #[tokio::test]
+    async fn test_event_watcher_with_output() {
+        let (registry, watcher, mut rx) = setup_client();
+        registry.subscribe_events(watcher, true);
+
+        registry.try_broadcast_to_session(
+            Uuid::new_v4(),
+            ServerMessage::Output {
+                pane_id: Uuid::new_v4(),
+                data: b"hi".to_vec(),
+            },
+        );
+        assert!(matches!(rx.try_recv(), Ok(ServerMessage::Output { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_event_watcher_attached_gets_single_copy() {
+        let (registry, watcher, mut rx) = setup_client();
+        let session_id = Uuid::new_v4();
+        registry.attach_to_session(watcher, session_id);
+        registry.subscribe_events(watcher, false);
+
+        registry.broadcast_to_session(session_id, ServerMessage::Pong).await;
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err(), "attached watcher must not get duplicates");
+    }
+
+    #[tokio::test]
+    async fn test_event_watcher_respects_except_and_unsubscribe() {
+        let (registry, watcher, mut rx) = setup_client();
+        let session_id = Uuid::new_v4();
+        registry.subscribe_events(watcher, false);
+
+        registry
+            .broadcast_to_session_except(session_id, watcher, ServerMessage::Pong)
+            .await;
+        assert!(rx.try_recv().is_err());
+
+        assert!(registry.unsubscribe_events(watcher));
+        registry.broadcast_to_session(session_id, ServerMessage::Pong).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_event_watcher_removed_on_unregister() {
+        let (registry, watcher, _rx) = setup_client();
+        registry.subscribe_events(watcher, true);
+        registry.unregister_client(watcher);
+        assert!(!registry.unsubscribe_events(watcher));
+        assert!(!registry.subscribe_events(watcher, true));
+    }
+
+    // ==================== Config Reload Tests ====================
+
+    #[tokio::test]
+    async fn test_notify_follows_config_reload() {
+        use crate::config::{AppConfig, NotificationRoute, NotificationSinkConfig, TerminalAlertKind};
+
+        let (registry, client_id, mut rx) = setup_client();
+        registry.set_client_type(client_id, ClientType::Tui);
+        let session_id = Uuid::new_v4();
+        registry.attach_to_session(client_id, session_id);
+        let notification = Notification::from_sideband(
+            None,
+            "build done".to_string(),
+            crate::sideband::NotifyLevel::Info,
+            Some((session_id, "dev".to_string())),
+            Uuid::new_v4(),
+        );
+
+        // No sinks configured yet
+        registry.notify(notification.clone());
+        assert!(rx.try_recv().is_err());
+
+        let mut config = AppConfig::default();
+        config.notifications.sinks.insert(
+            "term".to_string(),
+            NotificationSinkConfig::Terminal { alert: TerminalAlertKind::Bell },
+        );
+        config.notifications.routes.push(NotificationRoute {
+            sinks: vec!["term".to_string()],
+            ..Default::default()
+        });
+        registry.config().store(Arc::new(config));
+
+        registry.notify(notification);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(ServerMessage::TerminalNotification { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hooks_follow_config_reload() {
+        use crate::config::{AppConfig, HookConfig, HookEvent};
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let (registry, client_id, _rx) = setup_client();
+
+        let mut config = AppConfig::default();
+        config.hooks.push(HookConfig {
+            event: HookEvent::ClientAttached,
+            session: None,
+            from: None,
+            to: None,
+            run: Some(format!("echo \"$FUGUE_HOOK\" > '{}'", out.display())),
+            fugue: None,
+        });
+        registry.config().store(Arc::new(config));
+        registry.attach_to_session(client_id, Uuid::new_v4());
+
+        for _ in 0..100 {
+            if std::fs::read_to_string(&out).is_ok_and(|text| text.ends_with('\n')) {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "client-attached\n");
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use fugue_utils::{CcmuxError, Result};
+use fugue_protocol::SplitDirection;
 
 use super::{MirrorRegistry, Pane, Session, Window};
 use crate::orchestration::WorktreeDetector;
@@ -245,13 +246,15 @@ impl SessionManager {
 
     /// Split a pane by creating a new pane in the same window
     ///
-    /// Creates a new pane in the window containing `source_pane_id`.
+    /// Creates a new pane in the window containing `source_pane_id`, placed
+    /// next to it in the layout along `direction`.
     /// The new pane can optionally have a specified cwd.
     ///
     /// Returns `(session_id, window_id, new_pane)` on success.
     ///
     /// # Arguments
     /// * `source_pane_id` - The pane to split from (determines which window)
+    /// * `direction` - Split direction
     /// * `cwd` - Optional working directory for the new pane
     ///
     /// # Errors
@@ -259,6 +262,7 @@ impl SessionManager {
     pub fn split_pane(
         &mut self,
         source_pane_id: Uuid,
+        direction: SplitDirection,
         cwd: Option<String>,
     ) -> Result<(Uuid, Uuid, &Pane)> {
         // Find the session and window containing the source pane
@@ -275,7 +279,7 @@ impl SessionManager {
             .ok_or_else(|| CcmuxError::WindowNotFound(window_id.to_string()))?;
 
         // Create the new pane
-        let new_pane = window.create_pane();
+        let new_pane = window.create_pane_split(Some(source_pane_id), direction, 0.5);
         let new_pane_id = new_pane.id();
 
         // Set the cwd - use provided cwd, or inherit from source pane
@@ -1009,7 +1013,7 @@ mod tests {
 
         // Split the pane
         let (result_session_id, result_window_id, new_pane) =
-            manager.split_pane(source_pane_id, None).unwrap();
+            manager.split_pane(source_pane_id, SplitDirection::Vertical, None).unwrap();
 
         // Verify the new pane is in the same session/window
         assert_eq!(result_session_id, session_id);
@@ -1039,7 +1043,7 @@ mod tests {
 
         // Split with specific cwd
         let (_, _, new_pane) =
-            manager.split_pane(source_pane_id, Some("/custom/path".to_string())).unwrap();
+            manager.split_pane(source_pane_id, SplitDirection::Vertical, Some("/custom/path".to_string())).unwrap();
 
         assert_eq!(new_pane.cwd(), Some("/custom/path"));
     }
@@ -1065,7 +1069,7 @@ mod tests {
         source_pane_mut.set_cwd(Some("/source/cwd".to_string()));
 
         // Split without cwd - should inherit from source
-        let (_, _, new_pane) = manager.split_pane(source_pane_id, None).unwrap();
+        let (_, _, new_pane) = manager.split_pane(source_pane_id, SplitDirection::Vertical, None).unwrap();
 
         assert_eq!(new_pane.cwd(), Some("/source/cwd"));
     }
@@ -1092,7 +1096,7 @@ mod tests {
 
         // Split with explicit cwd - should override inherited
         let (_, _, new_pane) =
-            manager.split_pane(source_pane_id, Some("/explicit/cwd".to_string())).unwrap();
+            manager.split_pane(source_pane_id, SplitDirection::Vertical, Some("/explicit/cwd".to_string())).unwrap();
 
         assert_eq!(new_pane.cwd(), Some("/explicit/cwd"));
     }
@@ -1102,7 +1106,7 @@ mod tests {
         let mut manager = SessionManager::new();
 
         let nonexistent_id = Uuid::new_v4();
-        let result = manager.split_pane(nonexistent_id, None);
+        let result = manager.split_pane(nonexistent_id, SplitDirection::Vertical, None);
 
         assert!(matches!(result, Err(CcmuxError::PaneNotFound(_))));
     }
@@ -1124,10 +1128,10 @@ mod tests {
         let source_pane_id = source_pane.id();
 
         // Split multiple times
-        let (_, _, pane2) = manager.split_pane(source_pane_id, None).unwrap();
+        let (_, _, pane2) = manager.split_pane(source_pane_id, SplitDirection::Vertical, None).unwrap();
         let pane2_id = pane2.id();
-        let (_, _, _pane3) = manager.split_pane(pane2_id, None).unwrap();
-        let (_, _, _pane4) = manager.split_pane(source_pane_id, None).unwrap();
+        let (_, _, _pane3) = manager.split_pane(pane2_id, SplitDirection::Vertical, None).unwrap();
+        let (_, _, _pane4) = manager.split_pane(source_pane_id, SplitDirection::Vertical, None).unwrap();
 
         // Verify window now has 4 panes
         let (_, window) = manager.find_window(window_id).unwrap();
@@ -17,6 +17,6 @@ mod window;
 
 pub use manager::SessionManager;
 pub use mirror::MirrorRegistry;
-pub use pane::Pane;
+pub use pane::{Pane, PaneLaunch};
 pub use session::Session;
 pub use window::Window;
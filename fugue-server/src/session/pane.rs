@@ -6,12 +6,48 @@ use std::path::PathBuf;
 use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 use vt100::Parser;
-use fugue_protocol::{AgentActivity, AgentState, ClaudeActivity, ClaudeState, PaneInfo, PaneState, PaneStuckStatus};
-use crate::agents::DetectorRegistry;
-use crate::claude::ClaudeDetector;
-use crate::config::SessionType;
+use fugue_protocol::{AgentActivity, AgentState, AgentUsage, ClaudeActivity, ClaudeState, PaneInfo, PaneState, PaneStuckStatus, PermissionRequest};
+use crate::agents::{DetectorRegistry, ScreenSnapshot};
+use crate::claude::{ClaudeDetector, StreamJsonDecoder};
+use crate::config::{AppConfig, DetectionMethod, SessionType, UsageConfig};
 use crate::isolation;
-use crate::pty::ScrollbackBuffer;
+use crate::pty::{PtyConfig, ScrollbackBuffer, SpillSettings};
+use crate::usage::PaneUsage;
+
+/// Environment variables injected by fugue itself, re-derived on every spawn
+const FUGUE_INJECTED_ENV: &[&str] = &[
+    "FUGUE_SESSION_ID",
+    "FUGUE_SESSION_NAME",
+    "FUGUE_WINDOW_ID",
+    "FUGUE_PANE_ID",
+    isolation::CLAUDE_CONFIG_DIR_ENV,
+];
+
+/// The program a pane's process was started with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaneLaunch {
+    /// Program to execute
+    pub command: String,
+    /// Arguments to the program
+    pub args: Vec<String>,
+    /// Environment variables set for the process (excluding fugue's own)
+    pub env: std::collections::HashMap<String, String>,
+}
+
+impl From<&PtyConfig> for PaneLaunch {
+    fn from(config: &PtyConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            args: config.args.clone(),
+            env: config
+                .env
+                .iter()
+                .filter(|(key, _)| !FUGUE_INJECTED_ENV.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        }
+    }
+}
 
 /// A terminal pane within a window
 pub struct Pane {
@@ -44,9 +80,17 @@ pub struct Pane {
     parser: Option<Parser>,
     /// Agent detector registry for state tracking (FEAT-084)
     agent_detector: DetectorRegistry,
+    /// Generation of the config the detectors and scrollback were last synced to
+    config_generation: Option<u64>,
     /// Claude detector for state tracking (deprecated, use agent_detector)
     #[deprecated(since = "0.2.0", note = "Use agent_detector instead")]
     claude_detector: ClaudeDetector,
+    /// Decoder for `claude --output-format stream-json` output
+    stream_json: StreamJsonDecoder,
+    /// Token/cost accounting for the pane's agent
+    usage: PaneUsage,
+    /// Whether the pane's process was stopped by a usage budget
+    paused: bool,
     /// Beads root directory if detected (FEAT-057)
     beads_root: Option<PathBuf>,
     /// Whether bracketed paste mode is enabled (ESC [ ? 2004 h)
@@ -57,6 +101,8 @@ pub struct Pane {
     is_mirror: bool,
     /// Source pane ID if this is a mirror pane (FEAT-062)
     mirror_source: Option<Uuid>,
+    /// Program the pane's process was launched with
+    launch: Option<PaneLaunch>,
 }
 
 impl fmt::Debug for Pane {
@@ -117,13 +163,18 @@ impl Pane {
             scrollback: ScrollbackBuffer::new(scrollback_lines),
             parser: None,
             agent_detector: DetectorRegistry::with_defaults(),
+            config_generation: None,
             #[allow(deprecated)]
             claude_detector: ClaudeDetector::new(),
+            stream_json: StreamJsonDecoder::new(),
             beads_root: None,
             bracketed_paste_enabled: false,
             metadata: std::collections::HashMap::new(),
             is_mirror: false,
             mirror_source: None,
+            usage: PaneUsage::default(),
+            paused: false,
+            launch: None,
         }
     }
 
@@ -172,13 +223,18 @@ impl Pane {
             scrollback: ScrollbackBuffer::new(DEFAULT_SCROLLBACK_LINES),
             parser: None,
             agent_detector,
+            config_generation: None,
             #[allow(deprecated)]
             claude_detector,
+            stream_json: StreamJsonDecoder::new(),
             beads_root: None,
             bracketed_paste_enabled: false,
             metadata: std::collections::HashMap::new(),
             is_mirror: false,
             mirror_source: None,
+            usage: PaneUsage::default(),
+            paused: false,
+            launch: None,
         }
     }
 
@@ -337,6 +393,7 @@ impl Pane {
         self.agent_detector.reset();
         #[allow(deprecated)]
         self.claude_detector.reset();
+        self.stream_json.reset();
         self.state = PaneState::Normal;
         self.state_changed_at = SystemTime::now();
     }
@@ -369,13 +426,15 @@ impl Pane {
     /// Check if pane is awaiting user input (AwaitingConfirmation or Idle state)
     ///
     /// Returns true if:
-    /// - This is an agent pane in AwaitingConfirmation state
+    /// - This is an agent pane in AwaitingConfirmation or AwaitingPermission state
     /// - This is an agent pane in Idle state (also waiting for input)
     pub fn is_awaiting_input(&self) -> bool {
         match &self.state {
             PaneState::Agent(state) => matches!(
                 state.activity,
-                AgentActivity::AwaitingConfirmation | AgentActivity::Idle
+                AgentActivity::AwaitingConfirmation
+                    | AgentActivity::AwaitingPermission
+                    | AgentActivity::Idle
             ),
             _ => false,
         }
@@ -384,9 +443,10 @@ impl Pane {
     /// Check specifically if pane is awaiting confirmation (tool use approval, etc.)
     pub fn is_awaiting_confirmation(&self) -> bool {
         match &self.state {
-            PaneState::Agent(state) => {
-                matches!(state.activity, AgentActivity::AwaitingConfirmation)
-            }
+            PaneState::Agent(state) => matches!(
+                state.activity,
+                AgentActivity::AwaitingConfirmation | AgentActivity::AwaitingPermission
+            ),
             _ => false,
         }
     }
@@ -517,6 +577,16 @@ impl Pane {
         self.cwd = cwd;
     }
 
+    /// Get the program the pane was launched with
+    pub fn launch(&self) -> Option<&PaneLaunch> {
+        self.launch.as_ref()
+    }
+
+    /// Record the program the pane was launched with
+    pub fn set_launch(&mut self, launch: Option<PaneLaunch>) {
+        self.launch = launch;
+    }
+
     /// Get beads root directory (FEAT-057)
     pub fn beads_root(&self) -> Option<&PathBuf> {
         self.beads_root.as_ref()
@@ -572,11 +642,151 @@ impl Pane {
         self.bracketed_paste_enabled
     }
 
+    /// Process PTY output, decoding Claude stream-json when enabled
+    ///
+    /// `config` is the server's current configuration, which picks the
+    /// detection method, config-defined detectors, usage prices and whether
+    /// evicted scrollback spills to disk. Returns the bytes to display (the
+    /// rendered view for stream-json output, otherwise `data` unchanged) and
+    /// the new agent state, if any.
+    pub fn process_output(&mut self, data: &[u8], config: &AppConfig) -> (Vec<u8>, Option<AgentState>) {
+        if self.config_generation != Some(config.generation) {
+            self.config_generation = Some(config.generation);
+            self.agent_detector.sync_configured(&config.agent_detectors);
+            match SpillSettings::from_config(&config.terminal.scrollback.spill) {
+                Some(settings) => self.scrollback.enable_spill(std::sync::Arc::new(settings)),
+                None => self.scrollback.disable_spill(),
+            }
+        }
+
+        let method = config.claude.active_detection_method();
+        let Some(decoded) = self.stream_json.feed(data, method == Some(DetectionMethod::StreamJson)) else {
+            let state = if method == Some(DetectionMethod::Visual) && self.parser.is_some() {
+                self.process_visual(data)
+            } else {
+                self.process(data)
+            };
+            let state = self.refresh_permission().or(state);
+            return (data.to_vec(), state.map(|state| self.record_usage(state, &config.usage)));
+        };
+
+        self.feed_terminal(&decoded.display);
+        let state = decoded.state.filter(|state| self.agent_state().as_ref() != Some(state));
+        if let Some(state) = &state {
+            self.set_agent_state(state.clone());
+        }
+        (decoded.display, state.map(|state| self.record_usage(state, &config.usage)))
+    }
+
+    /// Fold a new agent state into the pane's usage and make it current
+    ///
+    /// Once the pane has recorded usage, the state carries its total under
+    /// the `usage` metadata key.
+    pub(crate) fn record_usage(&mut self, mut state: AgentState, config: &UsageConfig) -> AgentState {
+        self.usage.observe(&state, config);
+        if !self.usage.is_empty() {
+            state.set_usage(&self.usage.total());
+        }
+        self.state = PaneState::Agent(state.clone());
+        state
+    }
+
+    /// Tokens and cost accounted to this pane
+    pub fn usage(&self) -> AgentUsage {
+        self.usage.total()
+    }
+
+    /// Usage tracker for this pane
+    pub fn usage_tracker(&self) -> &PaneUsage {
+        &self.usage
+    }
+
+    /// Whether the pane's process is stopped by a usage budget
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Record whether the pane's process is stopped
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Whether this pane's output is currently decoded as Claude stream-json
+    pub fn is_stream_json(&self) -> bool {
+        self.stream_json.is_active()
+    }
+
     /// Process terminal output through the parser
     ///
     /// Returns `Some(AgentState)` if agent state changed, `None` otherwise.
-    #[allow(deprecated)]
     pub fn process(&mut self, data: &[u8]) -> Option<AgentState> {
+        let text = self.feed_terminal(data);
+
+        // Analyze output for agent state changes (FEAT-084)
+        if let Some(agent_state) = self.agent_detector.analyze(&text) {
+            // State changed - update pane state and return new state
+            self.state = PaneState::Agent(agent_state.clone());
+            self.state_changed_at = SystemTime::now();
+            return Some(agent_state);
+        }
+        None
+    }
+
+    /// Process terminal output, classifying agent state from the rendered screen
+    ///
+    /// Used by `Visual` detection. Returns `Some(AgentState)` if agent state
+    /// changed, `None` otherwise.
+    pub fn process_visual(&mut self, data: &[u8]) -> Option<AgentState> {
+        self.feed_terminal(data);
+        let snapshot = ScreenSnapshot::from_screen(self.screen()?);
+
+        let agent_state = self.agent_detector.analyze_screen(&snapshot)?;
+        self.state = PaneState::Agent(agent_state.clone());
+        self.state_changed_at = SystemTime::now();
+        Some(agent_state)
+    }
+
+    /// Upgrade a confirmation prompt to `AwaitingPermission` if the screen
+    /// shows a tool-permission dialog, or downgrade once it is gone
+    ///
+    /// Returns the new agent state if it changed.
+    fn refresh_permission(&mut self) -> Option<AgentState> {
+        let PaneState::Agent(current) = &self.state else {
+            return None;
+        };
+        if !matches!(
+            current.activity,
+            AgentActivity::AwaitingConfirmation | AgentActivity::AwaitingPermission
+        ) {
+            return None;
+        }
+
+        let snapshot = ScreenSnapshot::from_screen(self.screen()?);
+        let request = self.agent_detector.parse_permission(&snapshot);
+
+        let mut next = current.clone();
+        if request.is_none() && next.activity == AgentActivity::AwaitingPermission {
+            next.activity = AgentActivity::AwaitingConfirmation;
+        }
+        next.set_permission_request(request.as_ref());
+        if next == *current {
+            return None;
+        }
+
+        self.state = PaneState::Agent(next.clone());
+        self.state_changed_at = SystemTime::now();
+        Some(next)
+    }
+
+    /// Pending tool-permission prompt, if the agent is blocked on one
+    pub fn permission_request(&self) -> Option<PermissionRequest> {
+        self.agent_state()
+            .filter(|state| state.activity == AgentActivity::AwaitingPermission)
+            .and_then(|state| state.permission_request())
+    }
+
+    /// Feed output to the terminal emulator and scrollback, returning it as text
+    fn feed_terminal(&mut self, data: &[u8]) -> String {
         if let Some(parser) = &mut self.parser {
             parser.process(data);
         }
@@ -595,14 +805,7 @@ impl Pane {
         // Also push to scrollback
         self.scrollback.push_bytes(data);
 
-        // Analyze output for agent state changes (FEAT-084)
-        if let Some(agent_state) = self.agent_detector.analyze(&text) {
-            // State changed - update pane state and return new state
-            self.state = PaneState::Agent(agent_state.clone());
-            self.state_changed_at = SystemTime::now();
-            return Some(agent_state);
-        }
-        None
+        text.into_owned()
     }
 
     /// Get current screen contents
@@ -701,6 +904,12 @@ mod tests {
     use super::*;
     use fugue_protocol::ClaudeActivity;
 
+    fn detecting(method: DetectionMethod) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.claude.detection_method = method;
+        config
+    }
+
     #[test]
     fn test_pane_creation() {
         let window_id = Uuid::new_v4();
@@ -1153,6 +1362,130 @@ mod tests {
         assert!(screen.contents().contains("Hello, World!"));
     }
 
+    #[test]
+    fn test_pane_follows_config_generation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut pane = Pane::new(Uuid::new_v4(), 0);
+
+        let mut config = AppConfig::default();
+        config.terminal.scrollback.spill.enabled = true;
+        config.terminal.scrollback.spill.dir = Some(dir.path().display().to_string());
+        pane.process_output(b"first\r\n", &config);
+        assert!(pane.scrollback().is_spilling());
+
+        // Settings are only re-read when the generation moves on
+        config.terminal.scrollback.spill.enabled = false;
+        pane.process_output(b"second\r\n", &config);
+        assert!(pane.scrollback().is_spilling());
+
+        config.generation += 1;
+        pane.process_output(b"third\r\n", &config);
+        assert!(!pane.scrollback().is_spilling());
+    }
+
+    #[test]
+    fn test_pane_process_output_stream_json() {
+        let window_id = Uuid::new_v4();
+        let mut pane = Pane::new(window_id, 0);
+        pane.init_parser();
+
+        let stream_json = detecting(DetectionMethod::StreamJson);
+        let (display, state) = pane.process_output(
+            b"{\"type\":\"system\",\"subtype\":\"init\",\"session_id\":\"abc-123\",\"model\":\"claude-opus-4\"}\r\n",
+            &stream_json,
+        );
+        assert!(pane.is_stream_json());
+        assert!(!String::from_utf8_lossy(&display).contains("\"type\""));
+
+        let state = state.unwrap();
+        assert_eq!(state.session_id.as_deref(), Some("abc-123"));
+        let claude = pane.claude_state().unwrap();
+        assert_eq!(claude.model.as_deref(), Some("claude-opus-4"));
+
+        // The rendered view, not the JSON, is what the terminal shows
+        let screen = pane.screen().unwrap();
+        assert!(screen.contents().contains("abc-123"));
+        assert!(!screen.contents().contains("subtype"));
+
+        // Ordinary output is unaffected
+        let (display, _) = Pane::new(window_id, 1).process_output(b"plain text\r\n", &stream_json);
+        assert_eq!(display, b"plain text\r\n");
+    }
+
+    #[test]
+    fn test_pane_stream_json_follows_reload() {
+        let mut pane = Pane::new(Uuid::new_v4(), 0);
+        pane.init_parser();
+        let init = b"{\"type\":\"system\",\"subtype\":\"init\",\"session_id\":\"abc-123\"}\r\n";
+
+        // Detection off: the JSON is just output
+        let mut config = AppConfig::default();
+        config.claude.detection_enabled = false;
+        let (display, state) = pane.process_output(init, &config);
+        assert_eq!(display, init);
+        assert!(state.is_none());
+
+        // The method in force when the output arrives is the one used
+        let mut reloaded = detecting(DetectionMethod::StreamJson);
+        reloaded.generation = config.generation + 1;
+        let (_, state) = pane.process_output(init, &reloaded);
+        assert_eq!(state.unwrap().session_id.as_deref(), Some("abc-123"));
+        assert!(pane.is_stream_json());
+    }
+
+    #[test]
+    fn test_pane_process_output_visual() {
+        let mut pane = Pane::new(Uuid::new_v4(), 0);
+        pane.init_parser();
+        pane.mark_as_agent("claude");
+
+        let visual = &detecting(DetectionMethod::Visual);
+        pane.process_output(b"> fix the tests\r\n\r\n", visual);
+
+        // The spinner row arrives in pieces and is redrawn in place
+        pane.process_output(b"\xe2\x9c\xbb Pondering", visual);
+        let (_, state) = pane.process_output("… (3s · esc to interrupt)".as_bytes(), visual);
+        assert_eq!(state.unwrap().activity, AgentActivity::Processing);
+        let (_, state) =
+            pane.process_output("\r\x1b[2K✶ Pondering… (4s · esc to interrupt)".as_bytes(), visual);
+        assert!(state.is_none());
+
+        // Spinner cleared, input prompt drawn
+        let (_, state) = pane.process_output(
+            "\r\x1b[2K\r\n╭────────╮\r\n│ >      │\r\n╰────────╯\r\n".as_bytes(),
+            visual,
+        );
+        assert_eq!(state.unwrap().activity, AgentActivity::Idle);
+        assert_eq!(pane.agent_state().unwrap().activity, AgentActivity::Idle);
+    }
+
+    #[test]
+    fn test_pane_permission_prompt() {
+        let mut pane = Pane::new(Uuid::new_v4(), 0);
+        pane.init_parser();
+        pane.mark_as_agent("claude");
+
+        let visual = &detecting(DetectionMethod::Visual);
+        let prompt = include_str!("../agents/screen/fixtures/claude_permission.screen").replace('\n', "\r\n");
+        let (_, state) = pane.process_output(prompt.as_bytes(), visual);
+        let state = state.unwrap();
+        assert_eq!(state.activity, AgentActivity::AwaitingPermission);
+        assert!(pane.is_awaiting_confirmation());
+
+        let request = pane.permission_request().unwrap();
+        assert_eq!(request.tool, "Bash");
+        assert_eq!(request.arguments, "rm -rf target");
+        assert_eq!(state.permission_request(), Some(request));
+
+        // Dialog dismissed: back at the prompt, request cleared
+        let (_, state) = pane.process_output(
+            "\x1b[2J\x1b[H╭────────╮\r\n│ >      │\r\n╰────────╯\r\n".as_bytes(),
+            visual,
+        );
+        assert_eq!(state.unwrap().activity, AgentActivity::Idle);
+        assert!(pane.permission_request().is_none());
+    }
+
     #[test]
     fn test_pane_parser_resize() {
         let window_id = Uuid::new_v4();
@@ -1221,4 +1554,24 @@ mod tests {
         pane.process(b"some data \x1b[?2004h more data");
         assert!(pane.bracketed_paste_enabled());
     }
+
+    #[test]
+    fn test_pane_launch_drops_injected_env() {
+        let pane_id = Uuid::new_v4();
+        let config = PtyConfig::command("htop")
+            .with_arg("-d")
+            .with_env("TERM", "xterm")
+            .with_fugue_context(Uuid::new_v4(), "work", Uuid::new_v4(), pane_id);
+
+        let launch = PaneLaunch::from(&config);
+        assert_eq!(launch.command, "htop");
+        assert_eq!(launch.args, vec!["-d"]);
+        assert_eq!(launch.env.len(), 1);
+        assert_eq!(launch.env.get("TERM").map(String::as_str), Some("xterm"));
+
+        let mut pane = Pane::new(Uuid::new_v4(), 0);
+        assert!(pane.launch().is_none());
+        pane.set_launch(Some(launch.clone()));
+        assert_eq!(pane.launch(), Some(&launch));
+    }
 }
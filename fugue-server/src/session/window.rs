@@ -4,7 +4,7 @@
 use std::collections::HashMap;
 use std::time::SystemTime;
 use uuid::Uuid;
-use fugue_protocol::WindowInfo;
+use fugue_protocol::{LayoutNode, SplitDirection, WindowInfo, WindowLayout};
 
 use super::Pane;
 
@@ -25,6 +25,8 @@ pub struct Window {
     pane_order: Vec<Uuid>,
     /// Currently active pane
     active_pane_id: Option<Uuid>,
+    /// Split tree of the panes (None while the window is empty)
+    layout: Option<LayoutNode>,
     /// When created
     created_at: SystemTime,
 }
@@ -40,6 +42,7 @@ impl Window {
             panes: HashMap::new(),
             pane_order: Vec::new(),
             active_pane_id: None,
+            layout: None,
             created_at: SystemTime::now(),
         }
     }
@@ -62,6 +65,7 @@ impl Window {
             panes: HashMap::new(),
             pane_order: Vec::new(),
             active_pane_id: None,
+            layout: None,
             created_at: SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(created_at),
         }
     }
@@ -73,6 +77,7 @@ impl Window {
         let pane_id = pane.id();
         self.panes.insert(pane_id, pane);
         self.pane_order.push(pane_id);
+        self.place_in_layout(pane_id, None, SplitDirection::Vertical, 0.5);
     }
 
     /// Set active pane ID directly (for restoration)
@@ -131,13 +136,28 @@ impl Window {
     }
 
     /// Create a new pane in this window
+    ///
+    /// The pane is placed below the active pane in the layout.
     pub fn create_pane(&mut self) -> &Pane {
+        self.create_pane_split(None, SplitDirection::Vertical, 0.5)
+    }
+
+    /// Create a new pane by splitting `target` (the active pane if None)
+    ///
+    /// `ratio` is the share of the space `target` keeps.
+    pub fn create_pane_split(
+        &mut self,
+        target: Option<Uuid>,
+        direction: SplitDirection,
+        ratio: f32,
+    ) -> &Pane {
         let index = self.panes.len();
         let pane = Pane::new(self.id, index);
         let pane_id = pane.id();
 
         self.panes.insert(pane_id, pane);
         self.pane_order.push(pane_id);
+        self.place_in_layout(pane_id, target, direction, ratio);
 
         // Set as active if first pane
         if self.active_pane_id.is_none() {
@@ -152,9 +172,102 @@ impl Window {
     /// Used for mirror panes and other special pane types that are created
     /// externally rather than through create_pane.
     pub fn add_pane(&mut self, pane: Pane) {
+        self.add_pane_split(pane, None, SplitDirection::Vertical, 0.5);
+    }
+
+    /// Add an existing pane by splitting `target` (the active pane if None)
+    pub fn add_pane_split(
+        &mut self,
+        pane: Pane,
+        target: Option<Uuid>,
+        direction: SplitDirection,
+        ratio: f32,
+    ) {
         let pane_id = pane.id();
         self.panes.insert(pane_id, pane);
         self.pane_order.push(pane_id);
+        self.place_in_layout(pane_id, target, direction, ratio);
+    }
+
+    /// Insert a pane into the layout next to `target`, falling back to the
+    /// active pane and then the most recently added one
+    fn place_in_layout(
+        &mut self,
+        pane_id: Uuid,
+        target: Option<Uuid>,
+        direction: SplitDirection,
+        ratio: f32,
+    ) {
+        let Some(layout) = self.layout.as_mut() else {
+            self.layout = Some(LayoutNode::pane(pane_id));
+            return;
+        };
+
+        let target = target
+            .into_iter()
+            .chain(self.active_pane_id)
+            .chain(self.pane_order.iter().rev().copied())
+            .find(|id| *id != pane_id && layout.contains(*id));
+        if let Some(target) = target {
+            layout.split(target, LayoutNode::pane(pane_id), direction, ratio);
+        }
+    }
+
+    /// Get the window's split tree
+    pub fn layout(&self) -> Option<&LayoutNode> {
+        self.layout.as_ref()
+    }
+
+    /// Rearrange the panes in `subtree` to match it
+    ///
+    /// The panes are taken out of the current layout and the subtree is put
+    /// next to the active pane, or becomes the whole layout if nothing else
+    /// is left. Returns false (leaving the layout untouched) if the subtree
+    /// names a pane this window doesn't have.
+    pub fn arrange(&mut self, subtree: LayoutNode, direction: SplitDirection) -> bool {
+        let ids = subtree.pane_ids();
+        if ids.is_empty() || ids.iter().any(|id| !self.panes.contains_key(id)) {
+            return false;
+        }
+
+        for id in &ids {
+            self.remove_from_layout(*id);
+        }
+        let target = self
+            .active_pane_id
+            .into_iter()
+            .chain(self.pane_order.iter().copied())
+            .find(|id| self.layout.as_ref().is_some_and(|l| l.contains(*id)));
+        match (self.layout.as_mut(), target) {
+            (Some(layout), Some(target)) => {
+                layout.split(target, subtree, direction, 0.5);
+            }
+            _ => self.layout = Some(subtree),
+        }
+        true
+    }
+
+    /// Grow or shrink a pane within its split by `delta` (a fraction of the split)
+    ///
+    /// Returns the split direction and the factor the pane's share changed by.
+    pub fn resize_in_layout(&mut self, pane_id: Uuid, delta: f32) -> Option<(SplitDirection, f32)> {
+        self.layout.as_mut()?.resize(pane_id, delta)
+    }
+
+    fn remove_from_layout(&mut self, pane_id: Uuid) {
+        if matches!(self.layout, Some(LayoutNode::Pane { id }) if id == pane_id) {
+            self.layout = None;
+        } else if let Some(layout) = self.layout.as_mut() {
+            layout.remove(pane_id);
+        }
+    }
+
+    /// Convert the split tree to its protocol form
+    pub fn to_layout(&self) -> Option<WindowLayout> {
+        self.layout.as_ref().map(|root| WindowLayout {
+            window_id: self.id,
+            root: root.clone(),
+        })
     }
 
     /// Get a pane by ID
@@ -186,6 +299,7 @@ impl Window {
     pub fn remove_pane(&mut self, pane_id: Uuid) -> Option<Pane> {
         if let Some(pane) = self.panes.remove(&pane_id) {
             self.pane_order.retain(|&id| id != pane_id);
+            self.remove_from_layout(pane_id);
 
             // Update active pane if needed
             if self.active_pane_id == Some(pane_id) {
@@ -594,4 +708,59 @@ mod tests {
         let pane = window.get_pane_by_index(1).unwrap();
         assert_eq!(pane.dimensions(), (100, 50));
     }
+
+    #[test]
+    fn test_window_layout_follows_panes() {
+        let mut window = Window::new(Uuid::new_v4(), 0, "main");
+        assert!(window.to_layout().is_none());
+
+        let pane1_id = window.create_pane().id();
+        let pane2_id = window
+            .create_pane_split(Some(pane1_id), SplitDirection::Horizontal, 0.7)
+            .id();
+        let pane3_id = window.create_pane().id();
+
+        let layout = window.to_layout().unwrap();
+        assert_eq!(layout.window_id, window.id());
+        assert_eq!(layout.root.pane_ids(), vec![pane1_id, pane3_id, pane2_id]);
+        match &layout.root {
+            LayoutNode::Split { direction, children } => {
+                assert_eq!(*direction, SplitDirection::Horizontal);
+                assert_eq!(children[0].1, 0.7);
+                // The default split goes under the active pane (pane1)
+                assert!(matches!(
+                    &children[0].0,
+                    LayoutNode::Split { direction: SplitDirection::Vertical, .. }
+                ));
+            }
+            other => panic!("expected a split, got {:?}", other),
+        }
+
+        window.remove_pane(pane1_id);
+        window.remove_pane(pane3_id);
+        assert_eq!(window.layout(), Some(&LayoutNode::pane(pane2_id)));
+        window.remove_pane(pane2_id);
+        assert!(window.layout().is_none());
+    }
+
+    #[test]
+    fn test_window_arrange_and_resize() {
+        let mut window = Window::new(Uuid::new_v4(), 0, "main");
+        let a = window.create_pane().id();
+        let b = window.create_pane().id();
+        let c = window.create_pane().id();
+
+        let subtree = LayoutNode::Split {
+            direction: SplitDirection::Horizontal,
+            children: vec![(LayoutNode::pane(b), 0.5), (LayoutNode::pane(c), 0.5)],
+        };
+        assert!(window.arrange(subtree.clone(), SplitDirection::Vertical));
+        assert_eq!(window.layout().unwrap().pane_ids(), vec![a, b, c]);
+        assert!(!window.arrange(LayoutNode::pane(Uuid::new_v4()), SplitDirection::Vertical));
+
+        let (direction, factor) = window.resize_in_layout(b, 0.2).unwrap();
+        assert_eq!(direction, SplitDirection::Horizontal);
+        assert!((factor - 1.4).abs() < 1e-6);
+        assert!(window.resize_in_layout(Uuid::new_v4(), 0.2).is_none());
+    }
 }
@@ -16,9 +16,10 @@ use fugue_protocol::{MailPriority, ServerMessage};
 
 use super::commands::{ControlAction, NotifyLevel, PaneRef, SidebandCommand, SplitDirection};
 use super::executor::{ExecuteError, ExecuteResult, SpawnResult};
+use crate::notifications::Notification;
 use crate::pty::{PtyConfig, PtyManager};
 use crate::registry::ClientRegistry;
-use crate::session::SessionManager;
+use crate::session::{PaneLaunch, SessionManager};
 
 /// Configuration for spawn limits to prevent runaway pane creation
 #[derive(Debug, Clone)]
@@ -161,7 +162,7 @@ impl AsyncCommandExecutor {
                 title,
                 message,
                 level,
-            } => self.execute_notify(title, message, level),
+            } => self.execute_notify(source_pane, title, message, level).await,
 
             SidebandCommand::Mail { summary, priority } => {
                 self.execute_mail(source_pane, summary, priority).await
@@ -271,11 +272,11 @@ impl AsyncCommandExecutor {
         };
 
         // Step 1: Create the new pane in SessionManager
-        let (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name) = {
+        let (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name, layout) = {
             let mut manager = self.session_manager.write().await;
 
             let (session_id, window_id, new_pane) = manager
-                .split_pane(source_pane, cwd.clone())
+                .split_pane(source_pane, direction.into(), cwd.clone())
                 .map_err(|e| ExecuteError::ExecutionFailed(e.to_string()))?;
 
             // Extract pane info before borrowing manager again
@@ -284,13 +285,14 @@ impl AsyncCommandExecutor {
             let pane_cwd = new_pane.cwd().map(String::from);
             let pane_size = new_pane.dimensions();
 
-            // Now we can safely get session name
-            let session_name = manager
-                .get_session(session_id)
-                .map(|s| s.name().to_string())
-                .unwrap_or_default();
+            // Now we can safely get session name and the updated layout
+            let session = manager.get_session(session_id);
+            let session_name = session.map(|s| s.name().to_string()).unwrap_or_default();
+            let layout = session
+                .and_then(|s| s.get_window(window_id))
+                .and_then(|w| w.to_layout());
 
-            (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name)
+            (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name, layout)
         };
 
         info!(
@@ -350,11 +352,13 @@ impl AsyncCommandExecutor {
                     
                     if let Some((session_id, window_id)) = location {
                         // 2. Remove PTY
+                        let mut exit_code = None;
                         {
                             let mut pty_mgr = pty_manager.write().await;
                             if let Some(handle) = pty_mgr.remove(pane_id) {
-                                if let Err(e) = handle.kill() {
-                                    warn!("Failed to kill PTY for pane {}: {}", pane_id, e);
+                                match handle.exit_code_or_kill() {
+                                    Ok(code) => exit_code = code,
+                                    Err(e) => warn!("Failed to kill PTY for pane {}: {}", pane_id, e),
                                 }
                             }
                         }
@@ -370,11 +374,13 @@ impl AsyncCommandExecutor {
                                         info!("Pane {} closed successfully due to timeout", pane_id);
                                         
                                         // 5. Broadcast PaneClosed
-                                        let msg = ServerMessage::PaneClosed {
-                                            pane_id,
-                                            exit_code: None,
-                                        };
+                                        let msg = ServerMessage::PaneClosed { pane_id, exit_code };
                                         registry.broadcast_to_session(session_id, msg).await;
+                                        if let Some(layout) = window.to_layout() {
+                                            registry
+                                                .broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout })
+                                                .await;
+                                        }
                                     }
                                 }
                             }
@@ -416,6 +422,11 @@ impl AsyncCommandExecutor {
             .with_size(pane_size.0, pane_size.1)
             .with_fugue_context(session_id, &session_name, window_id, pane_id);
 
+        // Remember the launch command so the pane can be restored
+        if let Some(pane) = self.session_manager.write().await.find_pane_mut(pane_id) {
+            pane.set_launch(Some(PaneLaunch::from(&pty_config)));
+        }
+
         // Step 3: Spawn PTY for the new pane
         let pty_reader = {
             let mut pty_manager = self.pty_manager.write().await;
@@ -442,6 +453,10 @@ impl AsyncCommandExecutor {
             "Broadcast PaneCreated to {} clients in session {}",
             delivered, session_id
         );
+        if let Some(layout) = layout {
+            self.registry
+                .try_broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout });
+        }
 
         // Increment spawn count for rate limiting
         let new_count = self.sideband_spawn_count.fetch_add(1, Ordering::SeqCst) + 1;
@@ -514,14 +529,14 @@ impl AsyncCommandExecutor {
         Ok(())
     }
 
-    /// Execute notify command - show a notification
-    fn execute_notify(
+    /// Execute notify command - log and route to notification sinks
+    async fn execute_notify(
         &self,
+        source_pane: Uuid,
         title: Option<String>,
         message: String,
         level: NotifyLevel,
     ) -> ExecuteResult<()> {
-        // Notifications are logged and will be broadcast to clients
         let level_str = match level {
             NotifyLevel::Info => "INFO",
             NotifyLevel::Warning => "WARN",
@@ -555,6 +570,20 @@ impl AsyncCommandExecutor {
             }
         }
 
+        let session = {
+            let manager = self.session_manager.read().await;
+            manager
+                .find_pane(source_pane)
+                .map(|(s, _, _)| (s.id(), s.name().to_string()))
+        };
+        self.registry.notify(Notification::from_sideband(
+            title,
+            message,
+            level,
+            session,
+            source_pane,
+        ));
+
         Ok(())
     }
 
@@ -16,7 +16,7 @@ use fugue_protocol::{MailPriority, PaneInfo, ServerMessage};
 use super::commands::{ControlAction, NotifyLevel, PaneRef, SidebandCommand, SplitDirection};
 use crate::pty::{PtyConfig, PtyManager};
 use crate::registry::ClientRegistry;
-use crate::session::SessionManager;
+use crate::session::{PaneLaunch, SessionManager};
 
 /// Errors that can occur during command execution
 #[derive(Debug, Error)]
@@ -242,11 +242,11 @@ impl CommandExecutor {
         );
 
         // Step 1: Create the new pane in SessionManager
-        let (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name) = {
+        let (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name, layout) = {
             let mut manager = self.session_manager.lock();
 
             let (session_id, window_id, new_pane) = manager
-                .split_pane(source_pane, cwd.clone())
+                .split_pane(source_pane, direction.into(), cwd.clone())
                 .map_err(|e| ExecuteError::ExecutionFailed(e.to_string()))?;
 
             // Extract pane info before borrowing manager again
@@ -255,13 +255,14 @@ impl CommandExecutor {
             let pane_cwd = new_pane.cwd().map(String::from);
             let pane_size = new_pane.dimensions();
 
-            // Now we can safely get session name
-            let session_name = manager
-                .get_session(session_id)
-                .map(|s| s.name().to_string())
-                .unwrap_or_default();
+            // Now we can safely get session name and the updated layout
+            let session = manager.get_session(session_id);
+            let session_name = session.map(|s| s.name().to_string()).unwrap_or_default();
+            let layout = session
+                .and_then(|s| s.get_window(window_id))
+                .and_then(|w| w.to_layout());
 
-            (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name)
+            (session_id, window_id, pane_id, pane_info, pane_cwd, pane_size, session_name, layout)
         };
 
         info!(
@@ -298,6 +299,11 @@ impl CommandExecutor {
             .with_size(pane_size.0, pane_size.1)
             .with_fugue_context(session_id, &session_name, window_id, pane_id);
 
+        // Remember the launch command so the pane can be restored
+        if let Some(pane) = self.session_manager.lock().find_pane_mut(pane_id) {
+            pane.set_launch(Some(PaneLaunch::from(&pty_config)));
+        }
+
         // Step 3: Spawn PTY for the new pane
         let pty_reader = {
             let mut pty_manager = self.pty_manager.lock();
@@ -324,6 +330,10 @@ impl CommandExecutor {
             "Broadcast PaneCreated to {} clients in session {}",
             delivered, session_id
         );
+        if let Some(layout) = layout {
+            self.registry
+                .try_broadcast_to_session(session_id, ServerMessage::LayoutChanged { layout });
+        }
 
         Ok(SpawnResult {
             session_id,
@@ -0,0 +1,437 @@
+//! `fugue-server state` - offline inspection and repair of persisted state
+//!
+//! Works directly on the state directory, so it can be used when the daemon
+//! won't start. Commands that modify the directory refuse to run while a
+//! daemon holds it.
+
+use std::path::{Path, PathBuf};
+
+use fugue_utils::{CcmuxError, Result};
+
+use crate::config::{AppConfig, ConfigLoader};
+use crate::persistence::{self, inspect, BlockStore, RecoveryState};
+
+const USAGE: &str = r#"fugue-server state - inspect and repair persisted state
+
+USAGE:
+    fugue-server state [--state-dir DIR] <COMMAND>
+
+COMMANDS:
+    list                        List checkpoints and WAL segments
+    dump-wal [--all]            Print WAL entries as JSON lines
+                                (--all includes segments already checkpointed)
+    verify                      Check checkpoint magic/version and WAL checksums
+    replay [--to SEQ] [--checkpoint SEQ] [--json]
+                                Rebuild state from a checkpoint plus the WAL,
+                                stopping after WAL sequence SEQ
+    prune [--keep N] [--force]  Delete all but the newest N >= 1 checkpoints (default 1)
+                                and checkpointed WAL segments
+    compact [--force]           Fold the WAL into a new checkpoint
+    export <SESSION> [--checkpoint SEQ] [--output FILE]
+                                Write a session (name or ID) from a checkpoint as JSON
+
+OPTIONS:
+    --state-dir DIR             State directory (default: from config)
+    --force                     Modify state even if a daemon appears to be running
+"#;
+
+/// Run a `state` subcommand with the arguments following `state`
+pub fn run(args: &[String]) -> Result<()> {
+    let mut args = Args::new(args);
+    let state_dir_override = args.value("--state-dir")?;
+
+    let Some(command) = args.positional() else {
+        println!("{}", USAGE);
+        return Ok(());
+    };
+
+    let config = ConfigLoader::load().unwrap_or_else(|_| AppConfig::default());
+    let state_dir = state_dir_override
+        .map(PathBuf::from)
+        .unwrap_or_else(|| persistence::resolve_state_dir(&config.persistence));
+
+    match command.as_str() {
+        "list" => list(&state_dir),
+        "dump-wal" => dump_wal(&state_dir, args.flag("--all")),
+        "verify" => verify(&state_dir),
+        "replay" => {
+            let to = args.number("--to")?;
+            let checkpoint = args.number("--checkpoint")?;
+            replay(&state_dir, checkpoint, to, args.flag("--json"))
+        }
+        "prune" => {
+            let keep = args.number("--keep")?.unwrap_or(1) as usize;
+            if keep == 0 {
+                return Err(usage_error("--keep must be at least 1"));
+            }
+            ensure_stopped(&state_dir, args.flag("--force"))?;
+            let report = inspect::prune(&state_dir, keep)?;
+            println!(
+                "Removed {} checkpoint(s) and {} WAL segment(s)",
+                report.checkpoints.len(),
+                report.segments.len()
+            );
+            Ok(())
+        }
+        "compact" => {
+            ensure_stopped(&state_dir, args.flag("--force"))?;
+            let (path, segments) =
+                inspect::compact(&state_dir, config.persistence.max_checkpoints)?;
+            println!(
+                "Wrote {} and removed {} WAL segment(s)",
+                path.display(),
+                segments
+            );
+            Ok(())
+        }
+        "export" => {
+            let checkpoint = args.number("--checkpoint")?;
+            let output = args.value("--output")?;
+            let session = args
+                .positional()
+                .ok_or_else(|| usage_error("export requires a session name or ID"))?;
+            export(&state_dir, &session, checkpoint, output.as_deref())
+        }
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(usage_error(format!("unknown state command '{}'", other))),
+    }
+}
+
+fn list(state_dir: &Path) -> Result<()> {
+    println!("State directory: {}", state_dir.display());
+
+    let checkpoints = inspect::read_checkpoints(state_dir)?;
+    println!("\nCheckpoints ({}):", checkpoints.len());
+    for info in &checkpoints {
+        let name = file_name(&info.path);
+        match &info.checkpoint {
+            Ok(checkpoint) => {
+                let panes: usize = checkpoint
+                    .sessions
+                    .iter()
+                    .flat_map(|s| &s.windows)
+                    .map(|w| w.panes.len())
+                    .sum();
+                println!(
+                    "  {}  seq={}  v{}  {}  {} session(s), {} pane(s)  {} bytes",
+                    name,
+                    checkpoint.sequence,
+                    checkpoint.version,
+                    format_timestamp(checkpoint.timestamp),
+                    checkpoint.sessions.len(),
+                    panes,
+                    info.size
+                );
+            }
+            Err(e) => println!("  {}  INVALID: {}", name, e),
+        }
+    }
+
+    let segments = inspect::list_segments(state_dir)?;
+    println!("\nWAL segments ({}):", segments.len());
+    for (id, path, checkpointed) in &segments {
+        match inspect::read_segment(path, *id, *checkpointed) {
+            Ok(segment) => println!(
+                "  {}  {} entr{}{}",
+                segment.name(),
+                segment.entries.len(),
+                if segment.entries.len() == 1 { "y" } else { "ies" },
+                if segment.checkpointed { "  (checkpointed)" } else { "" }
+            ),
+            Err(e) => println!("  {}  INVALID: {}", file_name(path), e),
+        }
+    }
+
+    let blocks_dir = state_dir.join("scrollback");
+    if blocks_dir.is_dir() {
+        let store = BlockStore::open(&blocks_dir)?;
+        println!(
+            "\nScrollback blocks: {} bytes{}",
+            store.disk_usage()?,
+            if store.has_dictionary() { " (with dictionary)" } else { "" }
+        );
+    }
+
+    Ok(())
+}
+
+fn dump_wal(state_dir: &Path, all: bool) -> Result<()> {
+    let mut sequence = 0u64;
+    for segment in inspect::read_wal(state_dir)? {
+        if segment.checkpointed && !all {
+            continue;
+        }
+        let name = segment.name();
+        for record in &segment.entries {
+            let mut line = serde_json::json!({
+                "segment": name,
+                "entry_id": record.entry_id,
+            });
+            match &record.entry {
+                // Sequence numbers match what the server replays on startup
+                Ok(entry) => {
+                    if !segment.checkpointed {
+                        line["seq"] = sequence.into();
+                        sequence += 1;
+                    }
+                    line["entry"] = serde_json::to_value(entry)
+                        .map_err(|e| CcmuxError::persistence(e.to_string()))?;
+                }
+                Err(e) => line["error"] = e.clone().into(),
+            }
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+fn verify(state_dir: &Path) -> Result<()> {
+    let mut problems = 0;
+    let mut checked = 0;
+
+    for info in inspect::read_checkpoints(state_dir)? {
+        checked += 1;
+        if let Err(e) = &info.checkpoint {
+            problems += 1;
+            println!("FAIL {}: {}", info.path.display(), e);
+        }
+    }
+
+    for (id, path, checkpointed) in inspect::list_segments(state_dir)? {
+        checked += 1;
+        let segment = match inspect::read_segment(&path, id, checkpointed) {
+            Ok(segment) => segment,
+            Err(e) => {
+                problems += 1;
+                println!("FAIL {}", e);
+                continue;
+            }
+        };
+        for record in &segment.entries {
+            if let Err(e) = &record.entry {
+                problems += 1;
+                println!("FAIL {} entry {}: {}", path.display(), record.entry_id, e);
+            }
+        }
+        if segment.torn_tail {
+            // Expected after a crash mid-write; recovery ignores it
+            println!("WARN {}: incomplete final entry", path.display());
+        }
+    }
+
+    if problems > 0 {
+        return Err(CcmuxError::persistence(format!(
+            "{} problem(s) found in {} file(s)",
+            problems, checked
+        )));
+    }
+    println!("OK: {} file(s) verified", checked);
+    Ok(())
+}
+
+fn replay(state_dir: &Path, checkpoint: Option<u64>, to: Option<u64>, json: bool) -> Result<()> {
+    let state = match (checkpoint, to) {
+        // Without an explicit checkpoint, start from whichever one preceded SEQ
+        (None, Some(to)) => inspect::state_at(state_dir, to)?,
+        _ => {
+            let checkpoint =
+                inspect::find_checkpoint(inspect::read_checkpoints(state_dir)?, checkpoint)?;
+            let entries = inspect::live_entries(&inspect::read_wal(state_dir)?);
+            inspect::replay(checkpoint.as_ref(), &entries, to)
+        }
+    };
+
+    if json {
+        let value = serde_json::to_string_pretty(&state.sessions)
+            .map_err(|e| CcmuxError::persistence(e.to_string()))?;
+        println!("{}", value);
+    } else {
+        print_state(&state);
+    }
+    Ok(())
+}
+
+fn print_state(state: &RecoveryState) {
+    println!(
+        "Checkpoint {} + {} WAL entr{} replayed",
+        state.last_checkpoint_sequence,
+        state.wal_entries_replayed,
+        if state.wal_entries_replayed == 1 { "y" } else { "ies" }
+    );
+    for session in &state.sessions {
+        println!("session {} ({})", session.name, session.id);
+        for window in &session.windows {
+            println!("  window {}: {} ({})", window.index, window.name, window.id);
+            for pane in &window.panes {
+                let command = pane
+                    .command
+                    .as_ref()
+                    .map(|c| std::iter::once(c).chain(&pane.args).cloned().collect::<Vec<_>>().join(" "))
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "    pane {}: {}x{} {:?} {} ({})",
+                    pane.index, pane.cols, pane.rows, pane.state, command, pane.id
+                );
+            }
+        }
+    }
+    for warning in &state.warnings {
+        println!("warning: {}", warning);
+    }
+}
+
+fn export(state_dir: &Path, session: &str, checkpoint: Option<u64>, output: Option<&str>) -> Result<()> {
+    let checkpoint = inspect::find_checkpoint(inspect::read_checkpoints(state_dir)?, checkpoint)?
+        .ok_or_else(|| CcmuxError::persistence("No valid checkpoint found"))?;
+    let snapshot = checkpoint
+        .sessions
+        .iter()
+        .find(|s| s.name == session || s.id.to_string() == session)
+        .ok_or_else(|| {
+            CcmuxError::persistence(format!(
+                "Session '{}' not found in checkpoint {}",
+                session, checkpoint.sequence
+            ))
+        })?;
+
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| CcmuxError::persistence(e.to_string()))?;
+    match output {
+        Some(path) => std::fs::write(path, json).map_err(|e| {
+            CcmuxError::persistence(format!("Failed to write {}: {}", path, e))
+        })?,
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Refuse to modify state owned by a running daemon
+///
+/// The lock file is only written once a daemon has recovered state, so a
+/// live server socket also counts.
+fn ensure_stopped(state_dir: &Path, force: bool) -> Result<()> {
+    if force {
+        return Ok(());
+    }
+    let owner = match persistence::running_server_pid(state_dir) {
+        Some(pid) => Some(format!("fugue-server (pid {})", pid)),
+        None => std::os::unix::net::UnixStream::connect(fugue_utils::socket_path())
+            .is_ok()
+            .then(|| "a running fugue-server".to_string()),
+    };
+    match owner {
+        Some(owner) => Err(CcmuxError::persistence(format!(
+            "{} may be using {}; stop it first or pass --force",
+            owner,
+            state_dir.display()
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn format_timestamp(secs: u64) -> String {
+    chrono::DateTime::from_timestamp(secs as i64, 0)
+        .map(|dt| dt.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| secs.to_string())
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn usage_error(msg: impl Into<String>) -> CcmuxError {
+    CcmuxError::Internal(format!("{} (see 'fugue-server state --help')", msg.into()))
+}
+
+/// Options that take a value, which must not be mistaken for positionals
+const VALUE_OPTIONS: &[&str] = &["--state-dir", "--to", "--checkpoint", "--keep", "--output"];
+
+/// Minimal argument cursor; options may appear anywhere after `state`
+struct Args {
+    args: Vec<String>,
+}
+
+impl Args {
+    fn new(args: &[String]) -> Self {
+        Self {
+            args: args.to_vec(),
+        }
+    }
+
+    /// Remove a boolean flag
+    fn flag(&mut self, name: &str) -> bool {
+        let before = self.args.len();
+        self.args.retain(|arg| arg != name);
+        self.args.len() != before
+    }
+
+    /// Remove an option and its value
+    fn value(&mut self, name: &str) -> Result<Option<String>> {
+        let Some(index) = self.args.iter().position(|arg| arg == name) else {
+            return Ok(None);
+        };
+        if index + 1 >= self.args.len() {
+            return Err(usage_error(format!("{} requires an argument", name)));
+        }
+        let value = self.args.remove(index + 1);
+        self.args.remove(index);
+        Ok(Some(value))
+    }
+
+    /// Remove an option with a numeric value
+    fn number(&mut self, name: &str) -> Result<Option<u64>> {
+        self.value(name)?
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| usage_error(format!("{} expects a number, got '{}'", name, value)))
+            })
+            .transpose()
+    }
+
+    /// Take the next positional argument, skipping options and their values
+    fn positional(&mut self) -> Option<String> {
+        let mut index = 0;
+        while let Some(arg) = self.args.get(index) {
+            if VALUE_OPTIONS.contains(&arg.as_str()) {
+                index += 2;
+            } else if arg.starts_with("--") {
+                index += 1;
+            } else {
+                return Some(self.args.remove(index));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Args {
+        Args::new(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_positional_skips_option_values() {
+        let mut args = args(&["--checkpoint", "3", "replay", "--json"]);
+        assert_eq!(args.positional().as_deref(), Some("replay"));
+        assert_eq!(args.number("--checkpoint").unwrap(), Some(3));
+        assert!(args.flag("--json"));
+        assert_eq!(args.positional(), None);
+    }
+
+    #[test]
+    fn test_positional_after_flags() {
+        let mut args = args(&["--force", "export", "--output", "out.json", "work"]);
+        assert_eq!(args.positional().as_deref(), Some("export"));
+        assert_eq!(args.positional().as_deref(), Some("work"));
+        assert_eq!(args.value("--output").unwrap().as_deref(), Some("out.json"));
+    }
+}
@@ -1,12 +1,117 @@
 //! TCP listener implementation for remote peering (FEAT-066)
+//!
+//! Connections may optionally be wrapped in TLS (with or without client
+//! certificates) and gated on a pre-shared token that must arrive in a
+//! `ClientMessage::Authenticate` before the regular `Connect` handshake.
+//! Only peers that pass both checks are handed to [`handle_client`].
 
-use tokio::net::TcpListener;
-use tracing::{debug, error, info};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::{SinkExt, StreamExt};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tokio_util::codec::Framed;
+use tracing::{debug, error, info, warn};
+
+use fugue_protocol::{ClientMessage, ErrorCode, ServerCodec, ServerMessage};
+use fugue_utils::{tls, CcmuxError, Result};
+
+use crate::config::TlsConfig;
+use crate::observability::Metrics;
 use crate::SharedState;
 use crate::handle_client;
 
+/// How long a peer has to finish the TLS handshake or send `Authenticate`
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Transport security applied to every accepted TCP connection
+#[derive(Clone, Default)]
+pub struct TcpSecurity {
+    /// TLS acceptor (None = plaintext)
+    acceptor: Option<TlsAcceptor>,
+    /// Pre-shared token required before `Connect` (None = no token check)
+    auth_token: Option<Arc<str>>,
+}
+
+impl TcpSecurity {
+    /// Build the security layer from the `[tls]` config section
+    pub fn from_config(config: &TlsConfig) -> Result<Self> {
+        let acceptor = if config.enabled {
+            let cert = config
+                .cert_file
+                .as_deref()
+                .ok_or_else(|| CcmuxError::config("tls.enabled requires tls.cert_file"))?;
+            let key = config
+                .key_file
+                .as_deref()
+                .ok_or_else(|| CcmuxError::config("tls.enabled requires tls.key_file"))?;
+            let server_config = tls::server_config(
+                Path::new(cert),
+                Path::new(key),
+                config.client_ca_file.as_deref().map(Path::new),
+            )?;
+            Some(TlsAcceptor::from(server_config))
+        } else {
+            None
+        };
+
+        let auth_token = match (&config.auth_token_file, &config.auth_token) {
+            (Some(path), _) => {
+                let token = std::fs::read_to_string(path).map_err(|e| CcmuxError::FileRead {
+                    path: path.into(),
+                    source: e,
+                })?;
+                Some(token.trim().to_string())
+            }
+            (None, Some(token)) => Some(token.clone()),
+            (None, None) => None,
+        };
+        if auth_token.as_deref() == Some("") {
+            return Err(CcmuxError::config("tls auth token must not be empty"));
+        }
+
+        Ok(Self {
+            acceptor,
+            auth_token: auth_token.map(Arc::from),
+        })
+    }
+
+    /// Whether TLS is enabled
+    pub fn is_tls(&self) -> bool {
+        self.acceptor.is_some()
+    }
+
+    /// Whether any form of peer authentication is configured
+    pub fn is_authenticated(&self, config: &TlsConfig) -> bool {
+        self.auth_token.is_some() || (self.is_tls() && config.client_ca_file.is_some())
+    }
+}
+
 /// Run the TCP accept loop
 pub async fn run_tcp_accept_loop(addr: String, shared_state: SharedState) {
+    let config = shared_state.config.load_full();
+    let tls_config = &config.tls;
+    let security = match TcpSecurity::from_config(tls_config) {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Refusing to start TCP listener on {}: {}", addr, e);
+            return;
+        }
+    };
+
+    if !security.is_authenticated(tls_config) {
+        warn!(
+            "TCP listener on {} has no authentication; only expose it through an SSH tunnel",
+            addr
+        );
+    } else if !security.is_tls() {
+        warn!("TCP listener on {} sends its auth token in cleartext; enable [tls]", addr);
+    }
+
     let listener = match TcpListener::bind(&addr).await {
         Ok(l) => l,
         Err(e) => {
@@ -15,8 +120,17 @@ pub async fn run_tcp_accept_loop(addr: String, shared_state: SharedState) {
         }
     };
 
-    info!("TCP listener bound to {}", addr);
+    info!(
+        "TCP listener bound to {} (tls: {})",
+        addr,
+        if security.is_tls() { "on" } else { "off" }
+    );
 
+    serve_tcp_listener(listener, security, shared_state).await;
+}
+
+/// Accept connections on an already-bound listener until shutdown
+async fn serve_tcp_listener(listener: TcpListener, security: TcpSecurity, shared_state: SharedState) {
     let mut shutdown_rx = shared_state.subscribe_shutdown();
 
     loop {
@@ -26,9 +140,9 @@ pub async fn run_tcp_accept_loop(addr: String, shared_state: SharedState) {
                     Ok((stream, peer_addr)) => {
                         debug!("New TCP connection from {}", peer_addr);
                         let state_clone = shared_state.clone();
+                        let security = security.clone();
                         tokio::spawn(async move {
-                            let (reader, writer) = stream.into_split();
-                            handle_client(reader, writer, state_clone).await;
+                            serve_tcp_peer(stream, peer_addr, security, state_clone).await;
                         });
                     }
                     Err(e) => {
@@ -44,21 +158,125 @@ pub async fn run_tcp_accept_loop(addr: String, shared_state: SharedState) {
     }
 }
 
+/// Secure a single TCP peer and hand it to the regular client handler
+async fn serve_tcp_peer(
+    stream: TcpStream,
+    peer_addr: SocketAddr,
+    security: TcpSecurity,
+    shared_state: SharedState,
+) {
+    match security.acceptor {
+        Some(ref acceptor) => {
+            let tls_stream = match tokio::time::timeout(AUTH_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(s)) => s,
+                Ok(Err(e)) => {
+                    warn!("TLS handshake with {} failed: {}", peer_addr, e);
+                    Metrics::global().record_error("TlsHandshake");
+                    return;
+                }
+                Err(_) => {
+                    warn!("TLS handshake with {} timed out", peer_addr);
+                    return;
+                }
+            };
+            serve_authenticated(tls_stream, peer_addr, security.auth_token, shared_state).await;
+        }
+        None => {
+            serve_authenticated(stream, peer_addr, security.auth_token, shared_state).await;
+        }
+    }
+}
+
+async fn serve_authenticated<S>(
+    stream: S,
+    peer_addr: SocketAddr,
+    auth_token: Option<Arc<str>>,
+    shared_state: SharedState,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let stream = match auth_token {
+        Some(token) => match authenticate_peer(stream, &token).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Rejected TCP peer {}: {}", peer_addr, e);
+                Metrics::global().record_error(&format!("{:?}", ErrorCode::Unauthorized));
+                return;
+            }
+        },
+        None => stream,
+    };
+
+    info!("TCP peer {} accepted", peer_addr);
+    let (reader, writer) = tokio::io::split(stream);
+    handle_client(reader, writer, shared_state).await;
+}
+
+/// Require `ClientMessage::Authenticate` with the expected token as the first frame
+///
+/// Returns the underlying stream on success so the normal message pump can
+/// take over. The client must wait for `Authenticated` before sending
+/// `Connect`; any bytes pipelined after the token are treated as a protocol
+/// violation since they would otherwise be lost with the codec buffer.
+async fn authenticate_peer<S>(stream: S, expected: &str) -> std::result::Result<S, String>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut framed = Framed::new(stream, ServerCodec::new());
+
+    let first = match tokio::time::timeout(AUTH_TIMEOUT, framed.next()).await {
+        Ok(Some(Ok(msg))) => msg,
+        Ok(Some(Err(e))) => return Err(format!("read error before authentication: {}", e)),
+        Ok(None) => return Err("closed before authentication".to_string()),
+        Err(_) => return Err("timed out waiting for Authenticate".to_string()),
+    };
+
+    let reason = match first {
+        ClientMessage::Authenticate { token } if tls::tokens_match(expected, &token) => {
+            framed
+                .send(ServerMessage::Authenticated)
+                .await
+                .map_err(|e| format!("failed to acknowledge authentication: {}", e))?;
+            let parts = framed.into_parts();
+            if !parts.read_buf.is_empty() {
+                return Err("sent data before authentication was acknowledged".to_string());
+            }
+            return Ok(parts.io);
+        }
+        ClientMessage::Authenticate { .. } => "invalid auth token",
+        _ => "authentication required before Connect",
+    };
+
+    let _ = framed
+        .send(ServerMessage::Error {
+            code: ErrorCode::Unauthorized,
+            message: reason.to_string(),
+            details: None,
+        })
+        .await;
+    Err(reason.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::Arc;
+    use std::path::PathBuf;
     use tokio::sync::{broadcast, mpsc, RwLock};
+    use tokio_rustls::TlsConnector;
     use crate::session::SessionManager;
     use crate::pty::PtyManager;
     use crate::registry::ClientRegistry;
     use crate::config::AppConfig;
     use crate::sideband::AsyncCommandExecutor;
     use crate::arbitration::Arbitrator;
+    use fugue_protocol::{ClientCodec, ClientType, PROTOCOL_VERSION};
+    use fugue_utils::tls::TlsClientOptions;
+    use rcgen::{
+        BasicConstraints, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    };
+    use tempfile::TempDir;
 
-    #[tokio::test]
-    async fn test_tcp_listener_binds() {
-        // Create minimal shared state
+    fn create_shared_state(config: AppConfig) -> (SharedState, broadcast::Sender<()>) {
         let (shutdown_tx, _) = broadcast::channel(1);
         let (pane_closed_tx, _) = mpsc::channel(100);
         let session_manager = Arc::new(RwLock::new(SessionManager::new()));
@@ -69,12 +287,12 @@ mod tests {
             Arc::clone(&pty_manager),
             Arc::clone(&registry),
         ));
-        
+
         let shared_state = SharedState {
             session_manager,
             pty_manager,
             registry,
-            config: Arc::new(AppConfig::default()),
+            config: Arc::new(arc_swap::ArcSwap::from_pointee(config)),
             shutdown_tx: shutdown_tx.clone(),
             pane_closed_tx,
             command_executor,
@@ -82,10 +300,127 @@ mod tests {
             persistence: None,
             watchdog: Arc::new(crate::watchdog::WatchdogManager::new()),
         };
+        (shared_state, shutdown_tx)
+    }
+
+    /// Self-signed CA plus server and client leaf certificates written as PEM
+    struct TestPki {
+        dir: TempDir,
+    }
+
+    impl TestPki {
+        fn generate() -> Self {
+            let dir = TempDir::new().unwrap();
+
+            let ca_key = KeyPair::generate().unwrap();
+            let mut ca_params = CertificateParams::new(Vec::<String>::new()).unwrap();
+            ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+            let server_key = KeyPair::generate().unwrap();
+            let mut server_params =
+                CertificateParams::new(vec!["localhost".to_string(), "127.0.0.1".to_string()])
+                    .unwrap();
+            server_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+            let server_cert = server_params.signed_by(&server_key, &ca_cert, &ca_key).unwrap();
+
+            let client_key = KeyPair::generate().unwrap();
+            let mut client_params = CertificateParams::new(vec!["client".to_string()]).unwrap();
+            client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+            let client_cert = client_params.signed_by(&client_key, &ca_cert, &ca_key).unwrap();
+
+            let write = |name: &str, contents: String| {
+                std::fs::write(dir.path().join(name), contents).unwrap();
+            };
+            write("ca.pem", ca_cert.pem());
+            write("server.pem", server_cert.pem());
+            write("server.key", server_key.serialize_pem());
+            write("client.pem", client_cert.pem());
+            write("client.key", client_key.serialize_pem());
+
+            Self { dir }
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.dir.path().join(name)
+        }
+
+        fn path_str(&self, name: &str) -> Option<String> {
+            Some(self.path(name).to_string_lossy().into_owned())
+        }
+
+        fn server_config(&self, mutual: bool, token: Option<&str>) -> TlsConfig {
+            TlsConfig {
+                enabled: true,
+                cert_file: self.path_str("server.pem"),
+                key_file: self.path_str("server.key"),
+                client_ca_file: if mutual { self.path_str("ca.pem") } else { None },
+                auth_token: token.map(String::from),
+                auth_token_file: None,
+            }
+        }
+
+        fn client_options(&self, with_cert: bool, token: Option<&str>) -> TlsClientOptions {
+            TlsClientOptions {
+                ca_file: Some(self.path("ca.pem")),
+                cert_file: with_cert.then(|| self.path("client.pem")),
+                key_file: with_cert.then(|| self.path("client.key")),
+                auth_token: token.map(String::from),
+            }
+        }
+    }
+
+    /// Start a TCP listener on an ephemeral port with the given `[tls]` config
+    async fn start_listener(tls: TlsConfig) -> (SocketAddr, broadcast::Sender<()>) {
+        let config = AppConfig {
+            tls,
+            ..Default::default()
+        };
+        let security = TcpSecurity::from_config(&config.tls).unwrap();
+        let (shared_state, shutdown_tx) = create_shared_state(config);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_tcp_listener(listener, security, shared_state));
+        (addr, shutdown_tx)
+    }
+
+    fn connect_msg() -> ClientMessage {
+        ClientMessage::Connect {
+            client_id: uuid::Uuid::new_v4(),
+            protocol_version: PROTOCOL_VERSION,
+            client_type: ClientType::Compat,
+        }
+    }
+
+    async fn tls_connect(
+        addr: SocketAddr,
+        opts: &TlsClientOptions,
+    ) -> std::io::Result<Framed<tokio_rustls::client::TlsStream<TcpStream>, ClientCodec>> {
+        let connector = TlsConnector::from(opts.client_config().unwrap());
+        let tcp = TcpStream::connect(addr).await?;
+        let stream = connector
+            .connect(tls::server_name("localhost").unwrap(), tcp)
+            .await?;
+        Ok(Framed::new(stream, ClientCodec::new()))
+    }
+
+    async fn recv<S: AsyncRead + AsyncWrite + Unpin>(
+        framed: &mut Framed<S, ClientCodec>,
+    ) -> Option<ServerMessage> {
+        tokio::time::timeout(Duration::from_secs(2), framed.next())
+            .await
+            .ok()
+            .flatten()
+            .and_then(|r| r.ok())
+    }
+
+    #[tokio::test]
+    async fn test_tcp_listener_binds() {
+        let (shared_state, shutdown_tx) = create_shared_state(AppConfig::default());
 
         // Pick a random high port
         let addr = "127.0.0.1:0".to_string();
-        
+
         // Run in background
         let handle = tokio::spawn(async move {
             run_tcp_accept_loop(addr, shared_state).await;
@@ -93,16 +428,137 @@ mod tests {
 
         // Give it a moment to bind (or fail)
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-        
+
         // Signal shutdown
         let _ = shutdown_tx.send(());
-        
+
         // Should exit cleanly
         let result = tokio::time::timeout(
             tokio::time::Duration::from_secs(1),
             handle
         ).await;
-        
+
         assert!(result.is_ok(), "TCP listener did not shut down");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_security_from_config_requires_cert() {
+        let config = TlsConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let err = TcpSecurity::from_config(&config).err().unwrap();
+        assert!(err.to_string().contains("cert_file"));
+    }
+
+    #[test]
+    fn test_security_token_file_trimmed() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "abc123\n").unwrap();
+        let config = TlsConfig {
+            auth_token: Some("ignored".to_string()),
+            auth_token_file: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let security = TcpSecurity::from_config(&config).unwrap();
+        assert_eq!(security.auth_token.as_deref(), Some("abc123"));
+        assert!(!security.is_tls());
+        assert!(security.is_authenticated(&config));
+    }
+
+    #[test]
+    fn test_security_rejects_empty_token() {
+        let config = TlsConfig {
+            auth_token: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(TcpSecurity::from_config(&config).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tls_token_auth_then_connect() {
+        let pki = TestPki::generate();
+        let (addr, shutdown_tx) = start_listener(pki.server_config(false, Some("hunter2"))).await;
+
+        let mut framed = tls_connect(addr, &pki.client_options(false, None)).await.unwrap();
+        framed
+            .send(ClientMessage::Authenticate { token: "hunter2".to_string() })
+            .await
+            .unwrap();
+        assert_eq!(recv(&mut framed).await, Some(ServerMessage::Authenticated));
+
+        framed.send(connect_msg()).await.unwrap();
+        assert!(matches!(recv(&mut framed).await, Some(ServerMessage::Connected { .. })));
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_tls_wrong_token_rejected() {
+        let pki = TestPki::generate();
+        let (addr, shutdown_tx) = start_listener(pki.server_config(false, Some("hunter2"))).await;
+
+        let mut framed = tls_connect(addr, &pki.client_options(false, None)).await.unwrap();
+        framed
+            .send(ClientMessage::Authenticate { token: "wrong".to_string() })
+            .await
+            .unwrap();
+        match recv(&mut framed).await {
+            Some(ServerMessage::Error { code, .. }) => assert_eq!(code, ErrorCode::Unauthorized),
+            other => panic!("Expected Unauthorized, got {:?}", other),
+        }
+
+        // Connection is closed after rejection
+        assert_eq!(recv(&mut framed).await, None);
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_connect_without_token_rejected() {
+        let pki = TestPki::generate();
+        let (addr, shutdown_tx) = start_listener(pki.server_config(false, Some("hunter2"))).await;
+
+        let mut framed = tls_connect(addr, &pki.client_options(false, None)).await.unwrap();
+        framed.send(connect_msg()).await.unwrap();
+        match recv(&mut framed).await {
+            Some(ServerMessage::Error { code, .. }) => assert_eq!(code, ErrorCode::Unauthorized),
+            other => panic!("Expected Unauthorized, got {:?}", other),
+        }
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_mutual_tls_requires_client_cert() {
+        let pki = TestPki::generate();
+        let (addr, shutdown_tx) = start_listener(pki.server_config(true, None)).await;
+
+        // Without a client certificate the server aborts the handshake; with
+        // TLS 1.3 the client may only notice on first read.
+        let result = tls_connect(addr, &pki.client_options(false, None)).await;
+        if let Ok(mut framed) = result {
+            let _ = framed.send(connect_msg()).await;
+            assert_eq!(recv(&mut framed).await, None);
+        }
+
+        // With a certificate signed by the configured CA, Connect succeeds
+        let mut framed = tls_connect(addr, &pki.client_options(true, None)).await.unwrap();
+        framed.send(connect_msg()).await.unwrap();
+        assert!(matches!(recv(&mut framed).await, Some(ServerMessage::Connected { .. })));
+
+        let _ = shutdown_tx.send(());
+    }
+
+    #[tokio::test]
+    async fn test_plaintext_client_rejected_by_tls_listener() {
+        let pki = TestPki::generate();
+        let (addr, shutdown_tx) = start_listener(pki.server_config(false, Some("hunter2"))).await;
+
+        let tcp = TcpStream::connect(addr).await.unwrap();
+        let mut framed = Framed::new(tcp, ClientCodec::new());
+        framed.send(connect_msg()).await.unwrap();
+        assert_eq!(recv(&mut framed).await, None);
+
+        let _ = shutdown_tx.send(());
+    }
+}
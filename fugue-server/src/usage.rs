@@ -0,0 +1,578 @@
+//! Token and cost accounting for agent panes
+//!
+//! Agents report running token counters (and Claude stream-json its cost) in
+//! their `AgentState` metadata. `PaneUsage` turns those counters into a
+//! per-pane total that survives agent restarts, estimating cost from a
+//! blended per-model price when the agent doesn't report one. Totals roll up
+//! per session and per tag, and `[[usage.budgets]]` limits are checked
+//! against them whenever a pane's agent state changes.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use fugue_protocol::{AgentState, AgentUsage, BudgetScope, ServerMessage};
+
+use crate::config::{BudgetAction, BudgetConfig, BudgetScopeKind, UsageConfig};
+use crate::observability::Metrics;
+use crate::pty::PtyManager;
+use crate::registry::ClientRegistry;
+use crate::session::{Session, SessionManager};
+
+/// Built-in blended prices in USD per million tokens, by model or agent prefix
+///
+/// Blended assuming roughly three input tokens per output token.
+const BUILTIN_PRICES: &[(&str, f64)] = &[
+    ("claude", 6.0),
+    ("claude-opus", 30.0),
+    ("claude-sonnet", 6.0),
+    ("claude-haiku", 1.6),
+    ("gemini", 3.5),
+    ("gemini-2.5-pro", 3.5),
+    ("gemini-2.5-flash", 0.85),
+    ("codex", 3.5),
+    ("gpt-5", 3.5),
+    ("o3", 3.5),
+    ("o4-mini", 1.9),
+];
+
+/// Blended price for a model (or, failing that, agent type)
+///
+/// `prices` from config are layered over the built-in table, and the
+/// longest matching prefix wins.
+pub fn price_per_mtok(config: &UsageConfig, agent_type: &str, model: Option<&str>) -> Option<f64> {
+    let lookup = |name: &str| {
+        config
+            .prices
+            .iter()
+            .map(|(prefix, price)| (prefix.as_str(), *price))
+            .chain(BUILTIN_PRICES.iter().copied())
+            .filter(|(prefix, _)| name.starts_with(prefix))
+            .fold(None, |best: Option<(&str, f64)>, (prefix, price)| match best {
+                Some((longest, _)) if longest.len() >= prefix.len() => best,
+                _ => Some((prefix, price)),
+            })
+            .map(|(_, price)| price)
+    };
+    model
+        .and_then(lookup)
+        .or_else(|| lookup(agent_type))
+        .or(config.default_price_per_mtok)
+}
+
+/// Budgets already reported
+///
+/// Cleared whenever `[[usage.budgets]]` changes, which re-arms them.
+/// Resuming a paused pane re-arms the budgets covering it from the usage
+/// at that point.
+#[derive(Debug, Default)]
+pub struct FiredBudgets {
+    state: Mutex<Fired>,
+}
+
+#[derive(Debug, Default)]
+struct Fired {
+    /// Budgets `scopes` refers to
+    budgets: Vec<BudgetConfig>,
+    /// Budget index and scope of each reported breach
+    scopes: HashSet<(usize, BudgetScope)>,
+    /// Usage at resume of scopes re-armed by resuming a pane
+    baselines: HashMap<(usize, BudgetScope), AgentUsage>,
+}
+
+impl Fired {
+    /// Forget what was reported if the budgets changed
+    fn sync(&mut self, budgets: &[BudgetConfig]) {
+        if self.budgets != budgets {
+            self.budgets = budgets.to_vec();
+            self.scopes.clear();
+            self.baselines.clear();
+        }
+    }
+}
+
+/// Usage accounted to one pane
+#[derive(Debug, Clone, Default)]
+pub struct PaneUsage {
+    /// Usage of earlier agent runs in the pane
+    carried: AgentUsage,
+    /// Agent type of the current run
+    agent_type: Option<String>,
+    /// Token counter of the current run
+    tokens: u64,
+    /// Cost reported by the current run, if the agent reports one
+    reported_cost: Option<f64>,
+    /// Price used to estimate the current run's cost
+    price_per_mtok: Option<f64>,
+}
+
+impl PaneUsage {
+    /// Fold the counters of a new agent state in
+    ///
+    /// Returns whether the total changed.
+    pub fn observe(&mut self, state: &AgentState, config: &UsageConfig) -> bool {
+        let tokens = state.get_metadata("tokens_used").and_then(|v| v.as_u64());
+        let cost = state.get_metadata("cost_usd").and_then(|v| v.as_f64());
+        if tokens.is_none() && cost.is_none() {
+            return false;
+        }
+
+        // A counter going backwards (or a different agent) is a new run
+        let restarted = tokens.is_some_and(|t| t < self.tokens)
+            || cost.is_some_and(|c| c < self.reported_cost.unwrap_or(0.0))
+            || self.agent_type.as_deref().is_some_and(|t| t != state.agent_type);
+        if restarted {
+            self.carried.add(&self.current());
+            *self = Self {
+                carried: self.carried,
+                ..Self::default()
+            };
+        }
+
+        let before = self.total();
+        self.agent_type = Some(state.agent_type.clone());
+        self.tokens = tokens.unwrap_or(self.tokens);
+        self.reported_cost = cost.or(self.reported_cost);
+        let model = state.get_metadata("model").and_then(|v| v.as_str());
+        self.price_per_mtok = price_per_mtok(config, &state.agent_type, model);
+        self.total() != before
+    }
+
+    /// Total over all agent runs in the pane
+    pub fn total(&self) -> AgentUsage {
+        let mut total = self.carried;
+        total.add(&self.current());
+        total
+    }
+
+    /// Whether any usage has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.total() == AgentUsage::default()
+    }
+
+    fn current(&self) -> AgentUsage {
+        let (cost_usd, estimated) = match (self.reported_cost, self.price_per_mtok) {
+            (Some(cost), _) => (cost, false),
+            (None, Some(price)) if self.tokens > 0 => (self.tokens as f64 * price / 1_000_000.0, true),
+            (None, _) => (0.0, false),
+        };
+        AgentUsage {
+            tokens: self.tokens,
+            cost_usd,
+            estimated,
+        }
+    }
+}
+
+/// Usage summed over a session's panes
+pub fn session_usage(session: &Session) -> AgentUsage {
+    let mut total = AgentUsage::default();
+    for pane in session.windows().flat_map(|w| w.panes()) {
+        total.add(&pane.usage());
+    }
+    total
+}
+
+/// Usage summed over all sessions carrying `tag`
+pub fn tag_usage(manager: &SessionManager, tag: &str) -> AgentUsage {
+    let mut total = AgentUsage::default();
+    for session in manager.list_sessions().into_iter().filter(|s| s.has_tag(tag)) {
+        total.add(&session_usage(session));
+    }
+    total
+}
+
+/// A budget that was just exceeded
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetBreach {
+    pub scope: BudgetScope,
+    pub usage: AgentUsage,
+    pub max_tokens: Option<u64>,
+    pub max_cost_usd: Option<f64>,
+    pub action: BudgetAction,
+    /// Sessions in scope
+    pub sessions: Vec<Uuid>,
+    /// Panes in scope
+    pub panes: Vec<Uuid>,
+}
+
+impl BudgetConfig {
+    fn is_exceeded(&self, usage: &AgentUsage) -> bool {
+        self.max_tokens.is_some_and(|max| usage.tokens >= max)
+            || self.max_cost_usd.is_some_and(|max| usage.cost_usd >= max)
+    }
+}
+
+/// Scope, usage and sessions of a budget covering a pane
+fn budget_scope<'a>(
+    budget: &BudgetConfig,
+    manager: &'a SessionManager,
+    session: &'a Session,
+    pane_id: Uuid,
+    pane_usage: AgentUsage,
+) -> Option<(BudgetScope, AgentUsage, Vec<&'a Session>)> {
+    match budget.scope {
+        BudgetScopeKind::Pane => Some((BudgetScope::Pane(pane_id), pane_usage, vec![session])),
+        BudgetScopeKind::Session => Some((BudgetScope::Session(session.id()), session_usage(session), vec![session])),
+        BudgetScopeKind::Tag => match budget.tag.as_deref() {
+            Some(tag) if session.has_tag(tag) => Some((
+                BudgetScope::Tag(tag.to_string()),
+                tag_usage(manager, tag),
+                manager.list_sessions().into_iter().filter(|s| s.has_tag(tag)).collect(),
+            )),
+            _ => None,
+        },
+    }
+}
+
+/// Check the budgets covering a pane after its usage changed
+///
+/// Each budget fires once per scope; it is re-armed when usage falls back
+/// under the limit (e.g. after raising it) or the budgets are reloaded.
+/// Scopes re-armed by [`rearm_budgets`] count usage from their baseline.
+pub fn check_budgets(
+    config: &UsageConfig,
+    fired: &FiredBudgets,
+    manager: &SessionManager,
+    pane_id: Uuid,
+) -> Vec<BudgetBreach> {
+    let Some((session, _, pane)) = manager.find_pane(pane_id) else {
+        return Vec::new();
+    };
+    let mut fired = fired.state.lock().unwrap_or_else(|e| e.into_inner());
+    fired.sync(&config.budgets);
+    let mut breaches = Vec::new();
+
+    for (idx, budget) in config.budgets.iter().enumerate() {
+        let Some((scope, usage, sessions)) = budget_scope(budget, manager, session, pane_id, pane.usage()) else {
+            continue;
+        };
+
+        let key = (idx, scope.clone());
+        let counted = match fired.baselines.get(&key) {
+            Some(baseline) => AgentUsage {
+                tokens: usage.tokens.saturating_sub(baseline.tokens),
+                cost_usd: (usage.cost_usd - baseline.cost_usd).max(0.0),
+                estimated: usage.estimated,
+            },
+            None => usage,
+        };
+        if !budget.is_exceeded(&counted) {
+            fired.scopes.remove(&key);
+            continue;
+        }
+        if !fired.scopes.insert(key) {
+            continue;
+        }
+
+        let panes = match &scope {
+            BudgetScope::Pane(id) => vec![*id],
+            _ => sessions
+                .iter()
+                .flat_map(|s| s.windows().flat_map(|w| w.panes()))
+                .filter(|p| p.is_agent())
+                .map(|p| p.id())
+                .collect(),
+        };
+        breaches.push(BudgetBreach {
+            scope,
+            usage,
+            max_tokens: budget.max_tokens,
+            max_cost_usd: budget.max_cost_usd,
+            action: budget.action,
+            sessions: sessions.iter().map(|s| s.id()).collect(),
+            panes,
+        });
+    }
+
+    breaches
+}
+
+/// Re-arm the reported budgets covering a pane that was resumed
+///
+/// Their usage is counted again from now, so they fire once more after
+/// another full allowance instead of pausing the pane right away.
+pub fn rearm_budgets(config: &UsageConfig, fired: &FiredBudgets, manager: &SessionManager, pane_id: Uuid) {
+    let Some((session, _, pane)) = manager.find_pane(pane_id) else {
+        return;
+    };
+    let mut fired = fired.state.lock().unwrap_or_else(|e| e.into_inner());
+    fired.sync(&config.budgets);
+
+    for (idx, budget) in config.budgets.iter().enumerate() {
+        let Some((scope, usage, _)) = budget_scope(budget, manager, session, pane_id, pane.usage()) else {
+            continue;
+        };
+        let key = (idx, scope);
+        if fired.scopes.remove(&key) {
+            fired.baselines.insert(key, usage);
+        }
+    }
+}
+
+/// Check budgets for a pane and carry out any that were exceeded
+///
+/// Pausing stops the agent processes in scope; every breach is broadcast
+/// as `BudgetExceeded` to the sessions in scope.
+pub async fn enforce_budgets(
+    session_manager: &tokio::sync::RwLock<SessionManager>,
+    pty_manager: &tokio::sync::RwLock<PtyManager>,
+    registry: &ClientRegistry,
+    pane_id: Uuid,
+) {
+    let config = registry.config().load_full();
+    let breaches = check_budgets(
+        &config.usage,
+        registry.fired_budgets(),
+        &*session_manager.read().await,
+        pane_id,
+    );
+
+    for breach in breaches {
+        Metrics::global().record_budget_exceeded();
+        info!(
+            scope = ?breach.scope,
+            tokens = breach.usage.tokens,
+            cost_usd = breach.usage.cost_usd,
+            action = ?breach.action,
+            "Usage budget exceeded"
+        );
+
+        let mut paused = Vec::new();
+        if breach.action == BudgetAction::Pause {
+            let mut manager = session_manager.write().await;
+            let ptys = pty_manager.read().await;
+            for id in &breach.panes {
+                let Some(handle) = ptys.get(*id) else { continue };
+                match handle.pause() {
+                    Ok(()) => {
+                        if let Some(pane) = manager.find_pane_mut(*id) {
+                            pane.set_paused(true);
+                        }
+                        paused.push(*id);
+                    }
+                    Err(e) => warn!(pane_id = %id, error = %e, "Failed to pause pane over budget"),
+                }
+            }
+        }
+
+        let msg = ServerMessage::BudgetExceeded {
+            scope: breach.scope,
+            pane_id,
+            usage: breach.usage,
+            max_tokens: breach.max_tokens,
+            max_cost_usd: breach.max_cost_usd,
+            paused,
+        };
+        for session_id in breach.sessions {
+            registry.broadcast_to_session(session_id, msg.clone()).await;
+        }
+    }
+}
+
+/// Usage of a session as JSON, with a per-pane breakdown
+pub fn session_usage_json(session: &Session) -> serde_json::Value {
+    let panes: Vec<serde_json::Value> = session
+        .windows()
+        .flat_map(|w| w.panes())
+        .filter(|p| !p.usage_tracker().is_empty())
+        .map(|p| {
+            serde_json::json!({
+                "pane_id": p.id().to_string(),
+                "agent_type": p.agent_state().map(|s| s.agent_type),
+                "usage": p.usage(),
+            })
+        })
+        .collect();
+    serde_json::json!({
+        "total": session_usage(session),
+        "panes": panes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fugue_protocol::AgentState;
+    use serde_json::json;
+
+    fn state(agent_type: &str, tokens: u64) -> AgentState {
+        AgentState::new(agent_type).with_metadata("tokens_used", json!(tokens))
+    }
+
+    #[test]
+    fn test_price_lookup() {
+        let mut config = UsageConfig::default();
+        config.prices.insert("claude-opus".into(), 20.0);
+        config.prices.insert("claude".into(), 5.0);
+
+        assert_eq!(price_per_mtok(&config, "claude", Some("claude-opus-4-1")), Some(20.0));
+        assert_eq!(price_per_mtok(&config, "claude", Some("claude-sonnet-4-5")), Some(6.0));
+        assert_eq!(price_per_mtok(&config, "claude", None), Some(5.0));
+        assert_eq!(price_per_mtok(&config, "aider", Some("deepseek")), None);
+    }
+
+    #[test]
+    fn test_pane_usage_estimates_and_survives_restart() {
+        let config = UsageConfig::default();
+        let mut usage = PaneUsage::default();
+        assert!(!usage.observe(&AgentState::new("claude"), &config));
+        assert!(usage.is_empty());
+
+        assert!(usage.observe(&state("claude", 1_000_000), &config));
+        assert_eq!(usage.total().tokens, 1_000_000);
+        assert_eq!(usage.total().cost_usd, 6.0);
+        assert!(usage.total().estimated);
+        assert!(!usage.observe(&state("claude", 1_000_000), &config));
+
+        // Counter reset: the earlier run is carried over
+        assert!(usage.observe(&state("claude", 500_000), &config));
+        assert_eq!(usage.total().tokens, 1_500_000);
+        assert_eq!(usage.total().cost_usd, 9.0);
+    }
+
+    #[test]
+    fn test_pane_usage_prefers_reported_cost() {
+        let config = UsageConfig::default();
+        let mut usage = PaneUsage::default();
+        let reported = state("claude", 2_000).with_metadata("cost_usd", json!(0.25));
+        usage.observe(&reported, &config);
+        assert_eq!(
+            usage.total(),
+            AgentUsage {
+                tokens: 2_000,
+                cost_usd: 0.25,
+                estimated: false,
+            }
+        );
+    }
+
+    fn manager_with_agent(tag: Option<&str>, tokens: u64) -> (SessionManager, Uuid) {
+        let mut manager = SessionManager::new();
+        let session = manager.create_session("work").unwrap();
+        let session_id = session.id();
+        let session = manager.get_session_mut(session_id).unwrap();
+        if let Some(tag) = tag {
+            session.add_tag(tag);
+        }
+        let window_id = session.create_window(None).id();
+        let pane_id = session.get_window_mut(window_id).unwrap().create_pane().id();
+
+        let pane = manager.find_pane_mut(pane_id).unwrap();
+        pane.mark_as_agent("claude");
+        pane.record_usage(state("claude", tokens), &UsageConfig::default());
+        (manager, pane_id)
+    }
+
+    #[test]
+    fn test_budgets_fire_once_per_scope() {
+        let mut config = UsageConfig {
+            budgets: vec![
+                BudgetConfig {
+                    scope: BudgetScopeKind::Pane,
+                    max_tokens: Some(1_000),
+                    ..Default::default()
+                },
+                BudgetConfig {
+                    scope: BudgetScopeKind::Tag,
+                    tag: Some("frontend".into()),
+                    max_cost_usd: Some(1.0),
+                    action: BudgetAction::Pause,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let fired = FiredBudgets::default();
+
+        let (manager, pane_id) = manager_with_agent(Some("frontend"), 500);
+        assert!(check_budgets(&config, &fired, &manager, pane_id).is_empty());
+
+        let (manager, pane_id) = manager_with_agent(Some("frontend"), 200_000);
+        let breaches = check_budgets(&config, &fired, &manager, pane_id);
+        assert_eq!(breaches.len(), 2);
+        assert_eq!(breaches[0].scope, BudgetScope::Pane(pane_id));
+        assert_eq!(breaches[1].scope, BudgetScope::Tag("frontend".into()));
+        assert_eq!(breaches[1].action, BudgetAction::Pause);
+        assert_eq!(breaches[1].panes, vec![pane_id]);
+
+        // Already reported
+        assert!(check_budgets(&config, &fired, &manager, pane_id).is_empty());
+
+        // Changing the budgets re-arms them
+        config.budgets[0].max_tokens = Some(2_000);
+        assert_eq!(check_budgets(&config, &fired, &manager, pane_id).len(), 2);
+
+        // Untagged sessions aren't covered by the tag budget
+        let (manager, pane_id) = manager_with_agent(None, 200_000);
+        let breaches = check_budgets(&config, &fired, &manager, pane_id);
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].scope, BudgetScope::Pane(pane_id));
+    }
+
+    #[test]
+    fn test_resume_rearms_budgets() {
+        let config = UsageConfig {
+            budgets: vec![BudgetConfig {
+                scope: BudgetScopeKind::Pane,
+                max_tokens: Some(1_000),
+                action: BudgetAction::Pause,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let fired = FiredBudgets::default();
+
+        let (mut manager, pane_id) = manager_with_agent(None, 1_500);
+        assert_eq!(check_budgets(&config, &fired, &manager, pane_id).len(), 1);
+
+        // Resuming counts from 1_500, so the budget waits for another 1_000
+        rearm_budgets(&config, &fired, &manager, pane_id);
+        assert!(check_budgets(&config, &fired, &manager, pane_id).is_empty());
+
+        let pane = manager.find_pane_mut(pane_id).unwrap();
+        pane.record_usage(state("claude", 2_000), &UsageConfig::default());
+        assert!(check_budgets(&config, &fired, &manager, pane_id).is_empty());
+
+        let pane = manager.find_pane_mut(pane_id).unwrap();
+        pane.record_usage(state("claude", 2_500), &UsageConfig::default());
+        let breaches = check_budgets(&config, &fired, &manager, pane_id);
+        assert_eq!(breaches.len(), 1);
+        assert_eq!(breaches[0].usage.tokens, 2_500);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_uses_reloaded_budgets() {
+        let (manager, pane_id) = manager_with_agent(None, 5_000);
+        let session_id = manager.find_pane(pane_id).unwrap().0.id();
+        let session_manager = tokio::sync::RwLock::new(manager);
+        let pty_manager = tokio::sync::RwLock::new(PtyManager::new());
+        let registry = ClientRegistry::new();
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        let client_id = registry.register_client(tx);
+        registry.attach_to_session(client_id, session_id);
+
+        // No budgets yet
+        enforce_budgets(&session_manager, &pty_manager, &registry, pane_id).await;
+        assert!(rx.try_recv().is_err());
+
+        let mut config = crate::config::AppConfig::default();
+        config.usage.budgets.push(BudgetConfig {
+            scope: BudgetScopeKind::Session,
+            max_tokens: Some(1_000),
+            ..Default::default()
+        });
+        registry.config().store(std::sync::Arc::new(config));
+
+        enforce_budgets(&session_manager, &pty_manager, &registry, pane_id).await;
+        match rx.try_recv() {
+            Ok(ServerMessage::BudgetExceeded { scope, paused, .. }) => {
+                assert_eq!(scope, BudgetScope::Session(session_id));
+                assert!(paused.is_empty());
+            }
+            other => panic!("Expected BudgetExceeded, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,669 @@
+//! Declarative workspace files (`.fugue.toml`)
+//!
+//! A workspace describes sessions, their windows and split layouts, and
+//! what runs in each pane. `fugue up` creates any sessions that do not exist
+//! yet and `fugue down` destroys them, so both can be run repeatedly.
+//!
+//! ```toml
+//! root = "~/src/app"
+//!
+//! [[session]]
+//! name = "app"
+//! tags = ["dev"]
+//! env = { RUST_LOG = "debug" }
+//!
+//! [[session.window]]
+//! name = "editor"
+//! command = "nvim"
+//!
+//! [[session.window]]
+//! name = "servers"
+//! panes = [{ command = "cargo watch -x run" }, { command = "npm run dev", cwd = "web" }]
+//!
+//! [[session.window]]
+//! name = "agents"
+//! [session.window.layout]
+//! direction = "horizontal"
+//! splits = [
+//!   { ratio = 0.7, layout = { pane = { preset = "reviewer", watchdog = { interval_secs = 300 } } } },
+//!   { ratio = 0.3, layout = { pane = { command = "htop" } } },
+//! ]
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+use uuid::Uuid;
+
+use fugue_protocol::{LayoutNode, PaneState, SplitDirection};
+use fugue_utils::{CcmuxError, Result};
+
+use crate::config::{AgentPreset, HarnessConfig};
+use crate::isolation;
+use crate::persistence::{PaneSnapshot, SessionSnapshot, WindowSnapshot};
+
+/// A parsed workspace file
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Workspace {
+    /// Directory that relative session paths are resolved against
+    #[serde(default)]
+    pub root: Option<String>,
+    /// Sessions to create (`[[session]]`)
+    #[serde(default, rename = "session")]
+    pub sessions: Vec<SessionSpec>,
+}
+
+/// A session in a workspace
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SessionSpec {
+    /// Session name, used to tell whether it already exists
+    pub name: String,
+    /// Working directory for the session's panes
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Environment for every pane in the session
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub metadata: HashMap<String, String>,
+    /// Windows in order (`[[session.window]]`); one shell window if empty
+    #[serde(default, rename = "window")]
+    pub windows: Vec<WindowSpec>,
+}
+
+/// A window in a workspace session
+///
+/// Panes are given by at most one of `command` (a single pane), `panes`
+/// (evenly split along `direction`) or `layout` (a split tree).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WindowSpec {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub panes: Vec<PaneSpec>,
+    #[serde(default)]
+    pub direction: Direction,
+    #[serde(default)]
+    pub layout: Option<LayoutSpec>,
+}
+
+/// A split layout, in the same shape `fugue_create_layout` accepts
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LayoutSpec {
+    Pane {
+        pane: PaneSpec,
+    },
+    Split {
+        #[serde(default)]
+        direction: Direction,
+        splits: Vec<SplitSpec>,
+    },
+}
+
+/// One child of a split
+#[derive(Debug, Clone, Deserialize)]
+pub struct SplitSpec {
+    /// Share of the space; missing ratios share equally
+    #[serde(default)]
+    pub ratio: Option<f32>,
+    pub layout: LayoutSpec,
+}
+
+/// Split direction, spelled as in `fugue_create_layout`
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Horizontal,
+    #[default]
+    Vertical,
+}
+
+impl From<Direction> for SplitDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Horizontal => SplitDirection::Horizontal,
+            Direction::Vertical => SplitDirection::Vertical,
+        }
+    }
+}
+
+/// A pane in a workspace window
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PaneSpec {
+    /// Command line, run through `sh -c`
+    #[serde(default)]
+    pub command: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Agent preset from the server config's `[presets]`
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    #[serde(default)]
+    pub watchdog: Option<WatchdogSpec>,
+}
+
+/// A watchdog timer attached to a pane
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchdogSpec {
+    pub interval_secs: u64,
+    #[serde(default)]
+    pub message: Option<String>,
+    /// Defaults to `<session>:<window>.<pane>`
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// A watchdog to start once its pane exists
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchdogPlan {
+    pub pane_id: Uuid,
+    pub name: String,
+    pub interval_secs: u64,
+    pub message: Option<String>,
+}
+
+/// A workspace session ready to be restored
+#[derive(Debug, Clone)]
+pub struct SessionPlan {
+    pub snapshot: SessionSnapshot,
+    pub watchdogs: Vec<WatchdogPlan>,
+    /// `.claude.json` contents for panes started from Claude presets
+    pub claude_settings: Vec<(Uuid, serde_json::Map<String, serde_json::Value>)>,
+}
+
+impl Workspace {
+    /// Parse and validate a workspace document
+    pub fn parse(document: &str) -> Result<Self> {
+        let workspace: Self = toml::from_str(document)
+            .map_err(|e| CcmuxError::config(format!("Invalid workspace file: {}", e)))?;
+
+        let mut names = HashSet::new();
+        for session in &workspace.sessions {
+            if session.name.trim().is_empty() {
+                return Err(CcmuxError::config("Workspace session names must not be empty"));
+            }
+            if !names.insert(session.name.as_str()) {
+                return Err(CcmuxError::config(format!(
+                    "Session '{}' is defined more than once",
+                    session.name
+                )));
+            }
+            for window in &session.windows {
+                window.layout_spec()?;
+            }
+        }
+
+        Ok(workspace)
+    }
+}
+
+impl SessionSpec {
+    /// Names of the watchdogs this session starts
+    pub fn watchdog_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        for (window_index, window) in self.windows.iter().enumerate() {
+            let Ok(layout) = window.layout_spec() else {
+                continue;
+            };
+            for (pane_index, pane) in layout.panes().into_iter().enumerate() {
+                if let Some(watchdog) = &pane.watchdog {
+                    names.push(self.watchdog_name(watchdog, window_index, pane_index));
+                }
+            }
+        }
+        names
+    }
+
+    fn watchdog_name(&self, watchdog: &WatchdogSpec, window_index: usize, pane_index: usize) -> String {
+        watchdog
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("{}:{}.{}", self.name, window_index, pane_index))
+    }
+
+    /// Build the session with fresh IDs
+    ///
+    /// Relative directories are resolved against `root`, which is itself
+    /// resolved against `base_dir` (the workspace file's directory).
+    pub fn plan(
+        &self,
+        root: Option<&str>,
+        base_dir: Option<&Path>,
+        presets: &HashMap<String, AgentPreset>,
+    ) -> Result<SessionPlan> {
+        let base_dir = base_dir.map(Path::to_path_buf);
+        let root = resolve_dir(base_dir.as_deref(), root);
+        let session_cwd = resolve_dir(root.as_deref(), self.cwd.as_deref());
+
+        let session_id = Uuid::new_v4();
+        let now = unix_now();
+        let mut plan = SessionPlan {
+            snapshot: SessionSnapshot {
+                id: session_id,
+                name: self.name.clone(),
+                windows: Vec::new(),
+                active_window_id: None,
+                created_at: now,
+                metadata: self.metadata.clone(),
+                environment: self.env.clone(),
+                tags: self.tags.iter().cloned().collect(),
+            },
+            watchdogs: Vec::new(),
+            claude_settings: Vec::new(),
+        };
+
+        let default_window = [WindowSpec::default()];
+        let windows = if self.windows.is_empty() {
+            &default_window[..]
+        } else {
+            &self.windows[..]
+        };
+
+        for (window_index, window) in windows.iter().enumerate() {
+            let window_id = Uuid::new_v4();
+            let window_cwd = resolve_dir(session_cwd.as_deref(), window.cwd.as_deref());
+            let mut builder = PaneBuilder {
+                session: self,
+                window_id,
+                window_index,
+                window_cwd,
+                presets,
+                now,
+                panes: Vec::new(),
+                plan: &mut plan,
+            };
+            let layout = builder.build(&window.layout_spec()?)?;
+            let panes = builder.panes;
+
+            plan.snapshot.windows.push(WindowSnapshot {
+                id: window_id,
+                session_id,
+                name: window.name.clone().unwrap_or_else(|| window_index.to_string()),
+                index: window_index,
+                active_pane_id: panes.first().map(|p| p.id),
+                panes,
+                created_at: now,
+                layout: Some(layout),
+            });
+        }
+        plan.snapshot.active_window_id = plan.snapshot.windows.first().map(|w| w.id);
+
+        Ok(plan)
+    }
+}
+
+impl WindowSpec {
+    /// The window's panes as a layout tree
+    fn layout_spec(&self) -> Result<LayoutSpec> {
+        let forms = [self.command.is_some(), !self.panes.is_empty(), self.layout.is_some()];
+        if forms.iter().filter(|set| **set).count() > 1 {
+            return Err(CcmuxError::config(
+                "A window may set only one of 'command', 'panes' or 'layout'",
+            ));
+        }
+
+        let layout = if let Some(layout) = &self.layout {
+            layout.clone()
+        } else if !self.panes.is_empty() {
+            LayoutSpec::Split {
+                direction: self.direction,
+                splits: self
+                    .panes
+                    .iter()
+                    .map(|pane| SplitSpec {
+                        ratio: None,
+                        layout: LayoutSpec::Pane { pane: pane.clone() },
+                    })
+                    .collect(),
+            }
+        } else {
+            LayoutSpec::Pane {
+                pane: PaneSpec {
+                    command: self.command.clone(),
+                    ..Default::default()
+                },
+            }
+        };
+        layout.validate()?;
+        Ok(layout)
+    }
+}
+
+impl LayoutSpec {
+    fn validate(&self) -> Result<()> {
+        match self {
+            LayoutSpec::Pane { .. } => Ok(()),
+            LayoutSpec::Split { splits, .. } if splits.is_empty() => {
+                Err(CcmuxError::config("'splits' must not be empty"))
+            }
+            LayoutSpec::Split { splits, .. } => {
+                splits.iter().try_for_each(|split| split.layout.validate())
+            }
+        }
+    }
+
+    /// Leaf panes in layout order
+    fn panes(&self) -> Vec<&PaneSpec> {
+        match self {
+            LayoutSpec::Pane { pane } => vec![pane],
+            LayoutSpec::Split { splits, .. } => {
+                splits.iter().flat_map(|split| split.layout.panes()).collect()
+            }
+        }
+    }
+}
+
+/// Turns one window's layout spec into pane snapshots and a layout tree
+struct PaneBuilder<'a> {
+    session: &'a SessionSpec,
+    window_id: Uuid,
+    window_index: usize,
+    window_cwd: Option<PathBuf>,
+    presets: &'a HashMap<String, AgentPreset>,
+    now: u64,
+    panes: Vec<PaneSnapshot>,
+    plan: &'a mut SessionPlan,
+}
+
+impl PaneBuilder<'_> {
+    fn build(&mut self, layout: &LayoutSpec) -> Result<LayoutNode> {
+        match layout {
+            LayoutSpec::Pane { pane } => self.add_pane(pane).map(LayoutNode::pane),
+            LayoutSpec::Split { direction, splits } => {
+                let mut children = Vec::with_capacity(splits.len());
+                for split in splits {
+                    let ratio = split.ratio.filter(|r| *r > 0.0);
+                    children.push((self.build(&split.layout)?, ratio));
+                }
+
+                // Missing ratios share equally; all are normalized to sum to 1.0
+                let default_ratio = 1.0 / children.len() as f32;
+                let total: f32 = children.iter().map(|(_, r)| r.unwrap_or(default_ratio)).sum();
+                let mut children: Vec<(LayoutNode, f32)> = children
+                    .into_iter()
+                    .map(|(child, r)| (child, r.unwrap_or(default_ratio) / total))
+                    .collect();
+
+                Ok(if children.len() == 1 {
+                    children.remove(0).0
+                } else {
+                    LayoutNode::Split {
+                        direction: (*direction).into(),
+                        children,
+                    }
+                })
+            }
+        }
+    }
+
+    fn add_pane(&mut self, spec: &PaneSpec) -> Result<Uuid> {
+        let pane_id = Uuid::new_v4();
+        let pane_index = self.panes.len();
+
+        let mut env = self.session.env.clone();
+        let mut launch: Option<(String, Vec<String>)> = spec
+            .command
+            .as_ref()
+            .map(|cmd| ("sh".to_string(), vec!["-c".to_string(), cmd.clone()]));
+
+        if let Some(preset_name) = &spec.preset {
+            let preset = self.presets.get(preset_name).ok_or_else(|| {
+                CcmuxError::config(format!("Unknown preset '{}'", preset_name))
+            })?;
+            let (program, args, preset_env) = match &preset.config {
+                HarnessConfig::Shell(cfg) => (
+                    cfg.command.clone(),
+                    cfg.args.clone().unwrap_or_default(),
+                    cfg.env.clone().unwrap_or_default(),
+                ),
+                HarnessConfig::Custom(cfg) => (
+                    Some(cfg.command.clone()),
+                    cfg.args.clone().unwrap_or_default(),
+                    cfg.env.clone().unwrap_or_default(),
+                ),
+                HarnessConfig::Claude(cfg) => {
+                    self.plan.claude_settings.push((pane_id, cfg.to_settings()));
+                    env.insert(
+                        isolation::CLAUDE_CONFIG_DIR_ENV.to_string(),
+                        isolation::pane_config_dir(pane_id).to_string_lossy().into_owned(),
+                    );
+                    (Some("claude".to_string()), Vec::new(), HashMap::new())
+                }
+                HarnessConfig::Gemini(_) => (Some("gemini".to_string()), Vec::new(), HashMap::new()),
+                HarnessConfig::Codex(_) => (Some("codex".to_string()), Vec::new(), HashMap::new()),
+            };
+            env.extend(preset_env);
+            if launch.is_none() {
+                launch = program.map(|program| (program, args));
+            }
+        }
+        env.extend(spec.env.clone());
+
+        if let Some(watchdog) = &spec.watchdog {
+            self.plan.watchdogs.push(WatchdogPlan {
+                pane_id,
+                name: self.session.watchdog_name(watchdog, self.window_index, pane_index),
+                interval_secs: watchdog.interval_secs,
+                message: watchdog.message.clone(),
+            });
+        }
+
+        let (command, args) = match launch {
+            Some((command, args)) => (Some(command), args),
+            None => (None, Vec::new()),
+        };
+        self.panes.push(PaneSnapshot {
+            id: pane_id,
+            window_id: self.window_id,
+            index: pane_index,
+            cols: 80,
+            rows: 24,
+            state: PaneState::Normal,
+            name: spec.name.clone(),
+            title: None,
+            cwd: resolve_dir(self.window_cwd.as_deref(), spec.cwd.as_deref())
+                .map(|dir| dir.to_string_lossy().into_owned()),
+            created_at: self.now,
+            scrollback: None,
+            command,
+            args,
+            env,
+        });
+
+        Ok(pane_id)
+    }
+}
+
+/// Resolve `dir` against `parent`, expanding a leading `~`
+///
+/// Without `dir` the parent itself is used.
+fn resolve_dir(parent: Option<&Path>, dir: Option<&str>) -> Option<PathBuf> {
+    let Some(dir) = dir else {
+        return parent.map(Path::to_path_buf);
+    };
+
+    let path = match dir.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match dirs::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(dir),
+        },
+        _ => PathBuf::from(dir),
+    };
+
+    match parent {
+        Some(parent) if path.is_relative() => Some(parent.join(path)),
+        _ => Some(path),
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{ClaudeHarnessConfig, CustomHarnessConfig};
+
+    const WORKSPACE: &str = r#"
+root = "/srv/app"
+
+[[session]]
+name = "app"
+tags = ["dev"]
+env = { RUST_LOG = "debug" }
+
+[[session.window]]
+name = "editor"
+command = "nvim"
+
+[[session.window]]
+name = "servers"
+cwd = "web"
+panes = [{ command = "npm run dev" }, { preset = "runner", cwd = "/tmp" }]
+
+[[session.window]]
+[session.window.layout]
+direction = "horizontal"
+splits = [
+  { ratio = 3.0, layout = { pane = { preset = "reviewer", watchdog = { interval_secs = 300 } } } },
+  { ratio = 1.0, layout = { pane = { name = "shell" } } },
+]
+
+[[session]]
+name = "notes"
+"#;
+
+    fn presets() -> HashMap<String, AgentPreset> {
+        HashMap::from([
+            (
+                "reviewer".to_string(),
+                AgentPreset {
+                    harness: "claude".to_string(),
+                    description: None,
+                    config: HarnessConfig::Claude(ClaudeHarnessConfig {
+                        model: Some("claude-sonnet".to_string()),
+                        ..Default::default()
+                    }),
+                },
+            ),
+            (
+                "runner".to_string(),
+                AgentPreset {
+                    harness: "custom".to_string(),
+                    description: None,
+                    config: HarnessConfig::Custom(CustomHarnessConfig {
+                        command: "make".to_string(),
+                        args: Some(vec!["watch".to_string()]),
+                        env: None,
+                    }),
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_plan_builds_windows_layouts_and_commands() {
+        let workspace = Workspace::parse(WORKSPACE).unwrap();
+        assert_eq!(workspace.sessions.len(), 2);
+
+        let plan = workspace.sessions[0]
+            .plan(workspace.root.as_deref(), None, &presets())
+            .unwrap();
+        let session = &plan.snapshot;
+        assert_eq!(session.tags, HashSet::from(["dev".to_string()]));
+        assert_eq!(session.windows.len(), 3);
+
+        let editor = &session.windows[0];
+        assert_eq!(editor.name, "editor");
+        assert_eq!(editor.panes[0].command.as_deref(), Some("sh"));
+        assert_eq!(editor.panes[0].args, vec!["-c", "nvim"]);
+        assert_eq!(editor.panes[0].cwd.as_deref(), Some("/srv/app"));
+        assert_eq!(editor.panes[0].env["RUST_LOG"], "debug");
+
+        let servers = &session.windows[1];
+        assert_eq!(servers.panes.len(), 2);
+        assert_eq!(servers.panes[0].cwd.as_deref(), Some("/srv/app/web"));
+        assert_eq!(servers.panes[1].command.as_deref(), Some("make"));
+        assert_eq!(servers.panes[1].args, vec!["watch"]);
+        assert_eq!(servers.panes[1].cwd.as_deref(), Some("/tmp"));
+
+        let agents = &session.windows[2];
+        assert_eq!(agents.name, "2");
+        let reviewer = agents.panes[0].id;
+        match agents.layout.as_ref().unwrap() {
+            LayoutNode::Split { direction, children } => {
+                assert_eq!(*direction, SplitDirection::Horizontal);
+                assert!((children[0].1 - 0.75).abs() < f32::EPSILON);
+                assert_eq!(children[0].0.pane_ids(), vec![reviewer]);
+            }
+            other => panic!("Expected split layout, got {:?}", other),
+        }
+        assert_eq!(agents.panes[0].command.as_deref(), Some("claude"));
+        assert!(agents.panes[0].env.contains_key(isolation::CLAUDE_CONFIG_DIR_ENV));
+        assert_eq!(agents.panes[1].command, None);
+        assert_eq!(agents.panes[1].name.as_deref(), Some("shell"));
+
+        assert_eq!(plan.claude_settings.len(), 1);
+        assert_eq!(plan.claude_settings[0].0, reviewer);
+        assert_eq!(plan.claude_settings[0].1["model"], "claude-sonnet");
+        assert_eq!(plan.watchdogs.len(), 1);
+        assert_eq!(plan.watchdogs[0].pane_id, reviewer);
+        assert_eq!(plan.watchdogs[0].name, "app:2.0");
+        assert_eq!(workspace.sessions[0].watchdog_names(), vec!["app:2.0"]);
+
+        // A session without windows gets a single shell window
+        let notes = workspace.sessions[1]
+            .plan(workspace.root.as_deref(), None, &presets())
+            .unwrap();
+        assert_eq!(notes.snapshot.windows.len(), 1);
+        assert_eq!(notes.snapshot.windows[0].panes[0].command, None);
+    }
+
+    #[test]
+    fn test_relative_root_uses_base_dir() {
+        let workspace = Workspace::parse("root = \"app\"\n[[session]]\nname = \"a\"\ncwd = \"api\"\n").unwrap();
+        let plan = workspace.sessions[0]
+            .plan(workspace.root.as_deref(), Some(Path::new("/home/dev")), &HashMap::new())
+            .unwrap();
+        assert_eq!(
+            plan.snapshot.windows[0].panes[0].cwd.as_deref(),
+            Some("/home/dev/app/api")
+        );
+    }
+
+    #[test]
+    fn test_invalid_workspaces_are_rejected() {
+        assert!(Workspace::parse("[[session]]\nname = \"a\"\n[[session]]\nname = \"a\"\n").is_err());
+        assert!(Workspace::parse("[[session]]\nname = \"\"\n").is_err());
+        assert!(Workspace::parse(
+            "[[session]]\nname = \"a\"\n[[session.window]]\ncommand = \"top\"\npanes = [{}]\n"
+        )
+        .is_err());
+        assert!(Workspace::parse(
+            "[[session]]\nname = \"a\"\n[[session.window]]\nlayout = { splits = [] }\n"
+        )
+        .is_err());
+
+        let workspace =
+            Workspace::parse("[[session]]\nname = \"a\"\n[[session.window]]\npanes = [{ preset = \"nope\" }]\n")
+                .unwrap();
+        assert!(workspace.sessions[0].plan(None, None, &HashMap::new()).is_err());
+    }
+}
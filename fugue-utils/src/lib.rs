@@ -5,11 +5,13 @@
 //! - Logging infrastructure ([`init_logging`], [`LogConfig`])
 //! - Per-session logging ([`SessionLogger`], [`SessionLogLevel`])
 //! - XDG-compliant path utilities ([`paths`] module)
+//! - TLS configuration for remote peering ([`tls`] module)
 
 pub mod error;
 pub mod logging;
 pub mod paths;
 pub mod session_logging;
+pub mod tls;
 
 // Re-export main types at crate root for convenience
 pub use error::{CcmuxError, Result};
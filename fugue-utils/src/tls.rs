@@ -0,0 +1,215 @@
+//! TLS configuration helpers for remote peering
+//!
+//! Shared between the server's TCP listener and the client/compat
+//! `tls://` connectors so certificate loading and verification policy
+//! live in exactly one place. Only the `ring` crypto provider is used.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use rustls::crypto::{ring, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{ClientConfig, RootCertStore, ServerConfig};
+
+use crate::error::{CcmuxError, Result};
+
+/// URL scheme for TLS-encrypted TCP connections
+pub const TLS_SCHEME: &str = "tls://";
+
+/// Client-side TLS and authentication options
+///
+/// Populated from CLI flags or `FUGUE_TLS_*` environment variables by the
+/// client and compat binaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TlsClientOptions {
+    /// CA certificate (PEM) used to verify the server certificate
+    pub ca_file: Option<PathBuf>,
+    /// Client certificate chain (PEM) for mutual TLS
+    pub cert_file: Option<PathBuf>,
+    /// Client private key (PEM) for mutual TLS
+    pub key_file: Option<PathBuf>,
+    /// Pre-shared token sent in the `Authenticate` message before `Connect`
+    pub auth_token: Option<String>,
+}
+
+impl TlsClientOptions {
+    /// Build a rustls client configuration from these options
+    ///
+    /// Without a `ca_file` the server is verified against no roots and the
+    /// handshake will fail, since fugue never trusts the system store for
+    /// peering.
+    pub fn client_config(&self) -> Result<Arc<ClientConfig>> {
+        let mut roots = RootCertStore::empty();
+        if let Some(ref ca) = self.ca_file {
+            for cert in load_certs(ca)? {
+                roots.add(cert).map_err(|e| {
+                    CcmuxError::config(format!("Invalid CA certificate {}: {}", ca.display(), e))
+                })?;
+            }
+        }
+
+        let builder = ClientConfig::builder_with_provider(provider())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| CcmuxError::config(format!("TLS setup failed: {}", e)))?
+            .with_root_certificates(roots);
+
+        let config = match (&self.cert_file, &self.key_file) {
+            (Some(cert), Some(key)) => builder
+                .with_client_auth_cert(load_certs(cert)?, load_private_key(key)?)
+                .map_err(|e| CcmuxError::config(format!("Invalid client certificate: {}", e)))?,
+            (None, None) => builder.with_no_client_auth(),
+            _ => {
+                return Err(CcmuxError::config(
+                    "Client certificate and key must be provided together",
+                ))
+            }
+        };
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Build a rustls client configuration for public HTTPS endpoints
+///
+/// Unlike peering, outgoing webhooks go to ordinary web servers, so the
+/// Mozilla root set (webpki-roots) is trusted.
+pub fn web_client_config() -> Result<Arc<ClientConfig>> {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = ClientConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| CcmuxError::config(format!("TLS setup failed: {}", e)))?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Arc::new(config))
+}
+
+/// Build a rustls server configuration
+///
+/// When `client_ca` is provided, clients must present a certificate signed
+/// by it (mutual TLS). Otherwise any client may complete the handshake and
+/// authentication is left to the pre-shared token.
+pub fn server_config(cert: &Path, key: &Path, client_ca: Option<&Path>) -> Result<Arc<ServerConfig>> {
+    let builder = ServerConfig::builder_with_provider(provider())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| CcmuxError::config(format!("TLS setup failed: {}", e)))?;
+
+    let builder = match client_ca {
+        Some(ca) => {
+            let mut roots = RootCertStore::empty();
+            for cert in load_certs(ca)? {
+                roots.add(cert).map_err(|e| {
+                    CcmuxError::config(format!("Invalid client CA {}: {}", ca.display(), e))
+                })?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider())
+                .build()
+                .map_err(|e| CcmuxError::config(format!("Invalid client CA: {}", e)))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+
+    let config = builder
+        .with_single_cert(load_certs(cert)?, load_private_key(key)?)
+        .map_err(|e| CcmuxError::config(format!("Invalid server certificate: {}", e)))?;
+
+    Ok(Arc::new(config))
+}
+
+/// Resolve the TLS server name for a host string (DNS name or IP address)
+pub fn server_name(host: &str) -> Result<ServerName<'static>> {
+    // Url::host_str() keeps the brackets around IPv6 literals
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    ServerName::try_from(host.to_string())
+        .map_err(|e| CcmuxError::connection(format!("Invalid TLS server name '{}': {}", host, e)))
+}
+
+/// Compare two tokens without short-circuiting on the first mismatch
+pub fn tokens_match(expected: &str, provided: &str) -> bool {
+    let (a, b) = (expected.as_bytes(), provided.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Load all PEM certificates from a file
+pub fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|iter| iter.collect::<std::result::Result<Vec<_>, _>>())
+        .map_err(|e| CcmuxError::config(format!("Failed to read certificates from {}: {}", path.display(), e)))?;
+
+    if certs.is_empty() {
+        return Err(CcmuxError::config(format!(
+            "No certificates found in {}",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// Load the first PEM private key (PKCS#8, PKCS#1 or SEC1) from a file
+pub fn load_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    PrivateKeyDer::from_pem_file(path).map_err(|e| {
+        CcmuxError::config(format!("Failed to read private key from {}: {}", path.display(), e))
+    })
+}
+
+fn provider() -> Arc<CryptoProvider> {
+    Arc::new(ring::default_provider())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_tokens_match() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "secreT"));
+        assert!(!tokens_match("secret", "secret2"));
+        assert!(!tokens_match("secret", ""));
+    }
+
+    #[test]
+    fn test_server_name_dns_and_ip() {
+        assert!(matches!(server_name("localhost").unwrap(), ServerName::DnsName(_)));
+        assert!(matches!(server_name("127.0.0.1").unwrap(), ServerName::IpAddress(_)));
+        assert!(matches!(server_name("[::1]").unwrap(), ServerName::IpAddress(_)));
+    }
+
+    #[test]
+    fn test_load_certs_missing_file() {
+        let err = load_certs(Path::new("/nonexistent/cert.pem")).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/cert.pem"));
+    }
+
+    #[test]
+    fn test_load_certs_empty_file() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("empty.pem");
+        std::fs::write(&path, "").unwrap();
+        let err = load_certs(&path).unwrap_err();
+        assert!(err.to_string().contains("No certificates"));
+    }
+
+    #[test]
+    fn test_client_config_requires_cert_and_key_together() {
+        let opts = TlsClientOptions {
+            cert_file: Some(PathBuf::from("/tmp/client.pem")),
+            ..Default::default()
+        };
+        let err = opts.client_config().unwrap_err();
+        assert!(err.to_string().contains("provided together"));
+    }
+
+    #[test]
+    fn test_client_config_without_ca() {
+        assert!(TlsClientOptions::default().client_config().is_ok());
+    }
+}